@@ -0,0 +1,32 @@
+//! UCM document to DOCX translator.
+//!
+//! This crate renders UCM documents as Word (`.docx`) files. The package is
+//! written directly as OOXML parts in a stored ZIP archive, so there are no
+//! native dependencies.
+//!
+//! # Example
+//!
+//! ```
+//! use ucm_core::{Block, Content, Document};
+//!
+//! let mut doc = Document::create();
+//! let root = doc.root;
+//! doc.add_block(Block::new(Content::text("Hello, Word!"), Some("paragraph")), &root)
+//!     .unwrap();
+//!
+//! let bytes = ucp_translator_docx::render_docx(&doc);
+//! assert!(bytes.starts_with(b"PK"));
+//! ```
+
+mod render;
+mod zip;
+
+pub use render::DocxRenderer;
+
+/// Render a UCM Document to `.docx` bytes.
+///
+/// Relative image paths resolve against the working directory; use
+/// [`DocxRenderer::with_base_dir`] to resolve them elsewhere.
+pub fn render_docx(doc: &ucm_core::Document) -> Vec<u8> {
+    DocxRenderer::new().render(doc)
+}
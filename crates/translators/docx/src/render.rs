@@ -0,0 +1,720 @@
+//! Render UCM documents to a WordprocessingML (`.docx`) package.
+//!
+//! Headings take their Word style (`Heading1`..`Heading6`) from section depth,
+//! as in the HTML renderer. Text becomes paragraphs with the parser's inline
+//! markdown turned into bold/italic/code runs, tables become Word tables with
+//! the first row repeated as a header, and code uses a monospace style. Images
+//! with a local path are embedded under `word/media/`.
+
+use std::path::{Path, PathBuf};
+
+use ucm_core::metadata::RoleCategory;
+use ucm_core::{
+    Block, BlockId, Cell, Content, Document, InlineSpan, Media, MediaSource, MediaType, Table,
+};
+
+use crate::zip::ZipWriter;
+
+/// Block metadata key holding a figure or table caption (shared with the HTML translator)
+const CAPTION_KEY: &str = "caption";
+
+/// EMUs (English Metric Units) per pixel at 96 DPI
+const EMU_PER_PIXEL: u64 = 9525;
+/// Widest image that fits between default page margins (6.5in at 96 DPI)
+const MAX_IMAGE_WIDTH_PX: u64 = 624;
+/// Size used when an image's dimensions are unknown
+const DEFAULT_IMAGE_SIZE_PX: (u64, u64) = (480, 360);
+
+const NAMESPACES: &str = concat!(
+    r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" "#,
+    r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" "#,
+    r#"xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing" "#,
+    r#"xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" "#,
+    r#"xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture""#,
+);
+
+/// UCM document to DOCX renderer
+#[derive(Debug, Clone, Default)]
+pub struct DocxRenderer {
+    base_dir: Option<PathBuf>,
+}
+
+/// An image copied into the package
+struct EmbeddedImage {
+    source: PathBuf,
+    part_name: String,
+    data: Vec<u8>,
+}
+
+/// Parts collected while walking the document
+#[derive(Default)]
+struct Package {
+    body: String,
+    images: Vec<EmbeddedImage>,
+}
+
+impl DocxRenderer {
+    /// Create a renderer that resolves relative image paths against the working directory
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve relative image paths against `dir`
+    pub fn with_base_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(dir.into());
+        self
+    }
+
+    /// Render a document as `.docx` bytes
+    pub fn render(&self, doc: &Document) -> Vec<u8> {
+        let mut package = Package::default();
+        self.render_children(doc, &doc.root, 0, &mut package);
+
+        let mut zip = ZipWriter::new();
+        zip.add("[Content_Types].xml", content_types(&package).as_bytes());
+        zip.add("_rels/.rels", ROOT_RELS.as_bytes());
+        zip.add("docProps/core.xml", core_properties(doc).as_bytes());
+        zip.add(
+            "word/document.xml",
+            format!(
+                "{}<w:document {}><w:body>{}{}</w:body></w:document>",
+                XML_DECLARATION, NAMESPACES, package.body, SECTION_PROPERTIES
+            )
+            .as_bytes(),
+        );
+        zip.add("word/styles.xml", STYLES.as_bytes());
+        zip.add(
+            "word/_rels/document.xml.rels",
+            document_rels(&package).as_bytes(),
+        );
+        for image in &package.images {
+            zip.add(&format!("word/{}", image.part_name), &image.data);
+        }
+        zip.finish()
+    }
+
+    fn render_children(
+        &self,
+        doc: &Document,
+        parent_id: &BlockId,
+        section_depth: usize,
+        package: &mut Package,
+    ) {
+        for child_id in doc.children(parent_id) {
+            let Some(block) = doc.get_block(child_id) else {
+                continue;
+            };
+
+            if let (true, Content::Text(text)) = (is_heading(block), &block.content) {
+                let style = format!("Heading{}", (section_depth + 1).min(6));
+                package
+                    .body
+                    .push_str(&paragraph(Some(&style), &inline_runs(&text.text)));
+                self.render_children(doc, child_id, section_depth + 1, package);
+            } else {
+                self.render_block(block, package);
+                self.render_children(doc, child_id, section_depth, package);
+            }
+        }
+    }
+
+    fn render_block(&self, block: &Block, package: &mut Package) {
+        let role = block.metadata.semantic_role.as_ref().map(|r| r.category);
+        let caption = block
+            .metadata
+            .custom
+            .get(CAPTION_KEY)
+            .and_then(|v| v.as_str());
+
+        let xml = match &block.content {
+            Content::Text(text) if text.text.is_empty() => String::new(),
+            Content::Text(text) => match role {
+                Some(RoleCategory::Quote) => paragraph(Some("Quote"), &inline_runs(&text.text)),
+                Some(RoleCategory::List) => {
+                    let (ordered, items) = list_items(&text.text);
+                    let style = if ordered { "ListNumber" } else { "ListBullet" };
+                    items
+                        .iter()
+                        .enumerate()
+                        .map(|(i, item)| {
+                            let marker = if ordered {
+                                format!("{}.\t", i + 1)
+                            } else {
+                                "\u{2022}\t".to_string()
+                            };
+                            paragraph(Some(style), &(run(&marker, None) + &inline_runs(item)))
+                        })
+                        .collect()
+                }
+                _ => paragraph(None, &inline_runs(&text.text)),
+            },
+            Content::RichText(rich) => {
+                let style = matches!(role, Some(RoleCategory::Quote)).then_some("Quote");
+                paragraph(style, &span_runs(&rich.spans, None))
+            }
+            Content::Code(code) => paragraph(Some("Code"), &run(&code.source, None)),
+            Content::CompressedCode { .. } => paragraph(
+                Some("Code"),
+                &run(&block.content.decompress().unwrap_or_default(), None),
+            ),
+            Content::Diagram(diagram) => paragraph(Some("Code"), &run(&diagram.source, None)),
+            Content::Diff(diff) => paragraph(Some("Code"), &run(&diff.patch, None)),
+            Content::Json { value, .. } | Content::GeoJson(value) => {
+                let json = serde_json_pretty(value);
+                paragraph(Some("Code"), &run(&json, None))
+            }
+            Content::Math(math) => paragraph(Some("Code"), &run(&math.expression, None)),
+            Content::Spreadsheet(sheet) => self.render_table(&sheet.to_table(), caption),
+            Content::Form(form) => {
+                let mut xml = paragraph(None, &run(&form.title, Some(Format::Bold)));
+                for field in &form.fields {
+                    let marker = if field.required { " *" } else { "" };
+                    let label = format!("{}{}: ", field.label, marker);
+                    xml.push_str(&paragraph(
+                        None,
+                        &(run(&label, Some(Format::Bold)) + &run(field.field_type.name(), None)),
+                    ));
+                }
+                xml
+            }
+            Content::Table(table) => self.render_table(table, caption),
+            Content::Media(media) => {
+                let mut xml = self.render_media(media, package);
+                if let Some(caption) = caption {
+                    xml.push_str(&paragraph(Some("Caption"), &inline_runs(caption)));
+                }
+                xml
+            }
+            Content::Composite { .. } | Content::Binary { .. } => String::new(),
+        };
+        package.body.push_str(&xml);
+    }
+
+    fn render_table(&self, table: &Table, caption: Option<&str>) -> String {
+        let mut xml = String::new();
+        if let Some(caption) = caption {
+            xml.push_str(&paragraph(Some("Caption"), &inline_runs(caption)));
+        }
+        let columns = table.rows.iter().map(|r| r.cells.len()).max().unwrap_or(0);
+        xml.push_str(
+            r#"<w:tbl><w:tblPr><w:tblStyle w:val="TableGrid"/><w:tblW w:w="0" w:type="auto"/></w:tblPr><w:tblGrid>"#,
+        );
+        xml.push_str(&"<w:gridCol/>".repeat(columns));
+        xml.push_str("</w:tblGrid>");
+        for (i, row) in table.rows.iter().enumerate() {
+            let header = i == 0 && table.rows.len() > 1;
+            xml.push_str("<w:tr>");
+            if header {
+                xml.push_str("<w:trPr><w:tblHeader/></w:trPr>");
+            }
+            for cell in &row.cells {
+                let format = header.then_some(Format::Bold);
+                xml.push_str(&format!(
+                    "<w:tc>{}</w:tc>",
+                    paragraph(None, &run(&cell_to_string(cell), format))
+                ));
+            }
+            xml.push_str("</w:tr>");
+        }
+        xml.push_str("</w:tbl>");
+        xml
+    }
+
+    fn render_media(&self, media: &Media, package: &mut Package) -> String {
+        let alt = media.alt_text.as_deref().unwrap_or("");
+        if media.media_type == MediaType::Image {
+            if let Some(drawing) = self.embed_image(media, alt, package) {
+                return paragraph(None, &drawing);
+            }
+        }
+
+        let source = match &media.source {
+            MediaSource::Url(url) => url.clone(),
+            MediaSource::Base64(_) => "embedded data".to_string(),
+            MediaSource::Reference(id) => id.to_string(),
+            MediaSource::External(ext) => format!("{}:{}", ext.provider, ext.key),
+        };
+        let label = if alt.is_empty() {
+            source
+        } else {
+            format!("{} ({})", alt, source)
+        };
+        paragraph(None, &run(&label, Some(Format::Italic)))
+    }
+
+    /// Copy a local image into the package and return its drawing run
+    fn embed_image(&self, media: &Media, alt: &str, package: &mut Package) -> Option<String> {
+        let path = self.local_path(&media.source)?;
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        image_mime(&extension)?;
+
+        let index = match package.images.iter().position(|i| i.source == path) {
+            Some(index) => index,
+            None => {
+                let data = std::fs::read(&path).ok()?;
+                package.images.push(EmbeddedImage {
+                    part_name: format!("media/image{}.{}", package.images.len() + 1, extension),
+                    source: path,
+                    data,
+                });
+                package.images.len() - 1
+            }
+        };
+        let image = &package.images[index];
+
+        let (width, height) = media
+            .dimensions
+            .map(|d| (d.width as u64, d.height as u64))
+            .or_else(|| image_size(&image.data))
+            .filter(|(w, h)| *w > 0 && *h > 0)
+            .unwrap_or(DEFAULT_IMAGE_SIZE_PX);
+        let (width, height) = if width > MAX_IMAGE_WIDTH_PX {
+            (MAX_IMAGE_WIDTH_PX, height * MAX_IMAGE_WIDTH_PX / width)
+        } else {
+            (width, height)
+        };
+        let (cx, cy) = (width * EMU_PER_PIXEL, height * EMU_PER_PIXEL);
+        // docPr ids must be unique per drawing, not per image part
+        let drawing_id = package.body.matches("<wp:docPr ").count() + 1;
+
+        Some(format!(
+            concat!(
+                r#"<w:r><w:drawing><wp:inline distT="0" distB="0" distL="0" distR="0">"#,
+                r#"<wp:extent cx="{cx}" cy="{cy}"/><wp:docPr id="{id}" name="Picture {id}" descr="{alt}"/>"#,
+                r#"<a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/picture">"#,
+                r#"<pic:pic><pic:nvPicPr><pic:cNvPr id="{id}" name="{name}"/><pic:cNvPicPr/></pic:nvPicPr>"#,
+                r#"<pic:blipFill><a:blip r:embed="{rel}"/><a:stretch><a:fillRect/></a:stretch></pic:blipFill>"#,
+                r#"<pic:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="{cx}" cy="{cy}"/></a:xfrm>"#,
+                r#"<a:prstGeom prst="rect"><a:avLst/></a:prstGeom></pic:spPr></pic:pic>"#,
+                r#"</a:graphicData></a:graphic></wp:inline></w:drawing></w:r>"#
+            ),
+            cx = cx,
+            cy = cy,
+            id = drawing_id,
+            alt = escape(alt),
+            name = escape(&image.part_name),
+            rel = image_rel_id(index),
+        ))
+    }
+
+    /// Filesystem path for a `file://` URL or a bare path; `None` for remote sources
+    fn local_path(&self, source: &MediaSource) -> Option<PathBuf> {
+        let MediaSource::Url(url) = source else {
+            return None;
+        };
+        let path = match url.strip_prefix("file://") {
+            Some(path) => path,
+            None if url.contains("://") || url.starts_with("data:") => return None,
+            None => url.as_str(),
+        };
+        let path = Path::new(path);
+        let path = match &self.base_dir {
+            Some(base) if path.is_relative() => base.join(path),
+            _ => path.to_path_buf(),
+        };
+        // Collecting components drops `.` segments, so `./a.png` and `a.png` match
+        Some(path.components().collect())
+    }
+}
+
+fn is_heading(block: &Block) -> bool {
+    matches!(
+        block.metadata.semantic_role.as_ref().map(|r| r.category),
+        Some(
+            RoleCategory::Heading1
+                | RoleCategory::Heading2
+                | RoleCategory::Heading3
+                | RoleCategory::Heading4
+                | RoleCategory::Heading5
+                | RoleCategory::Heading6
+                | RoleCategory::Title
+                | RoleCategory::Subtitle
+        )
+    )
+}
+
+/// Run formatting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Bold,
+    Italic,
+    Code,
+    Link,
+}
+
+fn paragraph(style: Option<&str>, runs: &str) -> String {
+    match style {
+        Some(style) => format!(
+            r#"<w:p><w:pPr><w:pStyle w:val="{}"/></w:pPr>{}</w:p>"#,
+            style, runs
+        ),
+        None => format!("<w:p>{}</w:p>", runs),
+    }
+}
+
+/// A run of text; newlines become line breaks and tabs become tab stops
+fn run(text: &str, format: Option<Format>) -> String {
+    let properties = match format {
+        None => "",
+        Some(Format::Bold) => "<w:rPr><w:b/></w:rPr>",
+        Some(Format::Italic) => "<w:rPr><w:i/></w:rPr>",
+        Some(Format::Code) => r#"<w:rPr><w:rStyle w:val="CodeChar"/></w:rPr>"#,
+        Some(Format::Link) => r#"<w:rPr><w:rStyle w:val="Hyperlink"/></w:rPr>"#,
+    };
+
+    let mut content = String::new();
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            content.push_str("<w:br/>");
+        }
+        for (j, part) in line.split('\t').enumerate() {
+            if j > 0 {
+                content.push_str("<w:tab/>");
+            }
+            if !part.is_empty() {
+                content.push_str(&format!(
+                    r#"<w:t xml:space="preserve">{}</w:t>"#,
+                    escape(part)
+                ));
+            }
+        }
+    }
+    format!("<w:r>{}{}</w:r>", properties, content)
+}
+
+/// Convert the parsers' inline markdown into formatted runs
+fn inline_runs(text: &str) -> String {
+    let mut output = String::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if let Some((inner, len, format)) = inline_span(rest) {
+            if !plain.is_empty() {
+                output.push_str(&run(&std::mem::take(&mut plain), None));
+            }
+            output.push_str(&run(inner, Some(format)));
+            rest = &rest[len..];
+            continue;
+        }
+        plain.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    if !plain.is_empty() {
+        output.push_str(&run(&plain, None));
+    }
+    output
+}
+
+/// Runs for rich text spans; runs carry a single format, so the innermost
+/// formatting wins when spans nest
+fn span_runs(spans: &[InlineSpan], format: Option<Format>) -> String {
+    spans
+        .iter()
+        .map(|span| match span {
+            InlineSpan::Plain(text) => run(text, format),
+            InlineSpan::Bold(inner) => span_runs(inner, Some(Format::Bold)),
+            InlineSpan::Italic(inner) => span_runs(inner, Some(Format::Italic)),
+            InlineSpan::Code(code) => run(code, Some(Format::Code)),
+            InlineSpan::Link { text, .. } => span_runs(text, Some(Format::Link)),
+            InlineSpan::BlockRef { display, .. } => run(display, Some(Format::Link)),
+        })
+        .collect()
+}
+
+/// Match an inline span at the start of `text`, returning its text, byte length and format
+fn inline_span(text: &str) -> Option<(&str, usize, Format)> {
+    if let Some(body) = text.strip_prefix('`') {
+        let end = body.find('`').filter(|&end| end > 0)?;
+        return Some((&body[..end], end + 2, Format::Code));
+    }
+
+    for (delim, format) in [("**", Format::Bold), ("*", Format::Italic)] {
+        if let Some(body) = text.strip_prefix(delim) {
+            let end = body.find(delim)?;
+            let inner = &body[..end];
+            if inner.is_empty()
+                || inner.starts_with(char::is_whitespace)
+                || inner.ends_with(char::is_whitespace)
+            {
+                return None;
+            }
+            return Some((inner, end + 2 * delim.len(), format));
+        }
+    }
+
+    if let Some(body) = text.strip_prefix('[') {
+        let label_end = body.find("](")?;
+        let url_end = body[label_end + 2..].find(')')?;
+        let label = &body[..label_end];
+        if label.is_empty() || label.contains('\n') {
+            return None;
+        }
+        return Some((label, 1 + label_end + 2 + url_end + 1, Format::Link));
+    }
+    None
+}
+
+/// Split list text into items, stripping markdown markers when every line has one
+fn list_items(text: &str) -> (bool, Vec<&str>) {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    let ordered: Option<Vec<&str>> = lines
+        .iter()
+        .map(|line| {
+            let digits = line.find(|c: char| !c.is_ascii_digit())?;
+            if digits == 0 {
+                return None;
+            }
+            let rest = &line[digits..];
+            rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") "))
+        })
+        .collect();
+    if let Some(items) = ordered {
+        return (true, items);
+    }
+
+    let bulleted: Option<Vec<&str>> = lines
+        .iter()
+        .map(|line| ["- ", "* ", "+ "].iter().find_map(|m| line.strip_prefix(m)))
+        .collect();
+    (false, bulleted.unwrap_or(lines))
+}
+
+fn cell_to_string(cell: &Cell) -> String {
+    match cell {
+        Cell::Null => String::new(),
+        Cell::Text(s) | Cell::Date(s) | Cell::DateTime(s) => s.clone(),
+        Cell::Number(n) => n.to_string(),
+        Cell::Boolean(b) => b.to_string(),
+        Cell::Json(v) => v.to_string(),
+    }
+}
+
+fn serde_json_pretty(value: &serde_json::Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+fn image_mime(extension: &str) -> Option<&'static str> {
+    match extension {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "bmp" => Some("image/bmp"),
+        _ => None,
+    }
+}
+
+/// Pixel size read from a PNG or GIF header
+fn image_size(data: &[u8]) -> Option<(u64, u64)> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") && data.len() >= 24 {
+        let width = u32::from_be_bytes(data[16..20].try_into().ok()?);
+        let height = u32::from_be_bytes(data[20..24].try_into().ok()?);
+        return Some((width as u64, height as u64));
+    }
+    if data.starts_with(b"GIF8") && data.len() >= 10 {
+        let width = u16::from_le_bytes([data[6], data[7]]);
+        let height = u16::from_le_bytes([data[8], data[9]]);
+        return Some((width as u64, height as u64));
+    }
+    None
+}
+
+fn image_rel_id(index: usize) -> String {
+    format!("rIdImage{}", index + 1)
+}
+
+/// Escape text for XML, dropping characters XML 1.0 cannot represent
+fn escape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\t' | '\n' | '\r' => output.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#;
+
+const SECTION_PROPERTIES: &str = concat!(
+    r#"<w:sectPr><w:pgSz w:w="12240" w:h="15840"/>"#,
+    r#"<w:pgMar w:top="1440" w:right="1440" w:bottom="1440" w:left="1440" w:header="720" w:footer="720" w:gutter="0"/>"#,
+    r#"</w:sectPr>"#
+);
+
+const ROOT_RELS: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>"#,
+    r#"<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/>"#,
+    r#"</Relationships>"#
+);
+
+fn content_types(package: &Package) -> String {
+    let mut xml = String::from(XML_DECLARATION);
+    xml.push_str(r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#);
+    xml.push_str(r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#);
+    xml.push_str(r#"<Default Extension="xml" ContentType="application/xml"/>"#);
+
+    let mut extensions: Vec<&str> = package
+        .images
+        .iter()
+        .filter_map(|i| i.part_name.rsplit('.').next())
+        .collect();
+    extensions.sort_unstable();
+    extensions.dedup();
+    for extension in extensions {
+        if let Some(mime) = image_mime(extension) {
+            xml.push_str(&format!(
+                r#"<Default Extension="{}" ContentType="{}"/>"#,
+                extension, mime
+            ));
+        }
+    }
+
+    xml.push_str(r#"<Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>"#);
+    xml.push_str(r#"<Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/>"#);
+    xml.push_str(r#"<Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>"#);
+    xml.push_str("</Types>");
+    xml
+}
+
+fn document_rels(package: &Package) -> String {
+    let mut xml = String::from(XML_DECLARATION);
+    xml.push_str(
+        r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    );
+    xml.push_str(r#"<Relationship Id="rIdStyles" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>"#);
+    for (i, image) in package.images.iter().enumerate() {
+        xml.push_str(&format!(
+            r#"<Relationship Id="{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="{}"/>"#,
+            image_rel_id(i),
+            escape(&image.part_name)
+        ));
+    }
+    xml.push_str("</Relationships>");
+    xml
+}
+
+fn core_properties(doc: &Document) -> String {
+    let mut xml = String::from(XML_DECLARATION);
+    xml.push_str(concat!(
+        r#"<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" "#,
+        r#"xmlns:dc="http://purl.org/dc/elements/1.1/">"#
+    ));
+    if let Some(title) = &doc.metadata.title {
+        xml.push_str(&format!("<dc:title>{}</dc:title>", escape(title)));
+    }
+    if let Some(description) = &doc.metadata.description {
+        xml.push_str(&format!(
+            "<dc:description>{}</dc:description>",
+            escape(description)
+        ));
+    }
+    if !doc.metadata.authors.is_empty() {
+        xml.push_str(&format!(
+            "<dc:creator>{}</dc:creator>",
+            escape(&doc.metadata.authors.join("; "))
+        ));
+    }
+    if let Some(language) = &doc.metadata.language {
+        xml.push_str(&format!("<dc:language>{}</dc:language>", escape(language)));
+    }
+    xml.push_str("</cp:coreProperties>");
+    xml
+}
+
+const STYLES: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">"#,
+    r#"<w:docDefaults><w:rPrDefault><w:rPr><w:rFonts w:ascii="Calibri" w:hAnsi="Calibri" w:cs="Calibri"/><w:sz w:val="22"/></w:rPr></w:rPrDefault>"#,
+    r#"<w:pPrDefault><w:pPr><w:spacing w:after="160" w:line="259" w:lineRule="auto"/></w:pPr></w:pPrDefault></w:docDefaults>"#,
+    r#"<w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/></w:style>"#,
+    r#"<w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="360" w:after="120"/><w:outlineLvl w:val="0"/></w:pPr><w:rPr><w:b/><w:sz w:val="36"/></w:rPr></w:style>"#,
+    r#"<w:style w:type="paragraph" w:styleId="Heading2"><w:name w:val="heading 2"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="240" w:after="80"/><w:outlineLvl w:val="1"/></w:pPr><w:rPr><w:b/><w:sz w:val="30"/></w:rPr></w:style>"#,
+    r#"<w:style w:type="paragraph" w:styleId="Heading3"><w:name w:val="heading 3"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="200" w:after="60"/><w:outlineLvl w:val="2"/></w:pPr><w:rPr><w:b/><w:sz w:val="26"/></w:rPr></w:style>"#,
+    r#"<w:style w:type="paragraph" w:styleId="Heading4"><w:name w:val="heading 4"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:outlineLvl w:val="3"/></w:pPr><w:rPr><w:b/><w:sz w:val="24"/></w:rPr></w:style>"#,
+    r#"<w:style w:type="paragraph" w:styleId="Heading5"><w:name w:val="heading 5"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:outlineLvl w:val="4"/></w:pPr><w:rPr><w:b/><w:i/></w:rPr></w:style>"#,
+    r#"<w:style w:type="paragraph" w:styleId="Heading6"><w:name w:val="heading 6"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:outlineLvl w:val="5"/></w:pPr><w:rPr><w:i/></w:rPr></w:style>"#,
+    r#"<w:style w:type="paragraph" w:styleId="Quote"><w:name w:val="Quote"/><w:basedOn w:val="Normal"/><w:pPr><w:ind w:left="720" w:right="720"/></w:pPr><w:rPr><w:i/></w:rPr></w:style>"#,
+    r#"<w:style w:type="paragraph" w:styleId="Code"><w:name w:val="Code"/><w:basedOn w:val="Normal"/><w:pPr><w:spacing w:after="0" w:line="240" w:lineRule="auto"/><w:shd w:val="clear" w:color="auto" w:fill="F2F2F2"/></w:pPr><w:rPr><w:rFonts w:ascii="Consolas" w:hAnsi="Consolas" w:cs="Courier New"/><w:sz w:val="20"/></w:rPr></w:style>"#,
+    r#"<w:style w:type="paragraph" w:styleId="Caption"><w:name w:val="caption"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:rPr><w:i/><w:sz w:val="18"/></w:rPr></w:style>"#,
+    r#"<w:style w:type="paragraph" w:styleId="ListBullet"><w:name w:val="List Bullet"/><w:basedOn w:val="Normal"/><w:pPr><w:spacing w:after="40"/><w:ind w:left="720" w:hanging="360"/></w:pPr></w:style>"#,
+    r#"<w:style w:type="paragraph" w:styleId="ListNumber"><w:name w:val="List Number"/><w:basedOn w:val="Normal"/><w:pPr><w:spacing w:after="40"/><w:ind w:left="720" w:hanging="360"/></w:pPr></w:style>"#,
+    r#"<w:style w:type="character" w:styleId="CodeChar"><w:name w:val="Code Char"/><w:rPr><w:rFonts w:ascii="Consolas" w:hAnsi="Consolas" w:cs="Courier New"/></w:rPr></w:style>"#,
+    r#"<w:style w:type="character" w:styleId="Hyperlink"><w:name w:val="Hyperlink"/><w:rPr><w:color w:val="0563C1"/><w:u w:val="single"/></w:rPr></w:style>"#,
+    r#"<w:style w:type="table" w:styleId="TableGrid"><w:name w:val="Table Grid"/><w:tblPr><w:tblBorders>"#,
+    r#"<w:top w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:left w:val="single" w:sz="4" w:space="0" w:color="auto"/>"#,
+    r#"<w:bottom w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:right w:val="single" w:sz="4" w:space="0" w:color="auto"/>"#,
+    r#"<w:insideH w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:insideV w:val="single" w:sz="4" w:space="0" w:color="auto"/>"#,
+    r#"</w:tblBorders><w:tblCellMar><w:left w:w="108" w:type="dxa"/><w:right w:w="108" w:type="dxa"/></w:tblCellMar></w:tblPr></w:style>"#,
+    r#"</w:styles>"#
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_runs() {
+        let xml = inline_runs("Use **bold**, *em* and `code` with [docs](https://x.y).");
+        assert!(
+            xml.contains(r#"<w:r><w:rPr><w:b/></w:rPr><w:t xml:space="preserve">bold</w:t></w:r>"#)
+        );
+        assert!(xml.contains(r#"<w:rPr><w:i/></w:rPr><w:t xml:space="preserve">em</w:t>"#));
+        assert!(xml.contains(
+            r#"<w:rStyle w:val="CodeChar"/></w:rPr><w:t xml:space="preserve">code</w:t>"#
+        ));
+        assert!(xml.contains(
+            r#"<w:rStyle w:val="Hyperlink"/></w:rPr><w:t xml:space="preserve">docs</w:t>"#
+        ));
+        assert!(!xml.contains("https://x.y"));
+    }
+
+    #[test]
+    fn test_run_line_breaks_and_escaping() {
+        assert_eq!(
+            run("a < b\nc\u{1}", None),
+            r#"<w:r><w:t xml:space="preserve">a &lt; b</w:t><w:br/><w:t xml:space="preserve">c</w:t></w:r>"#
+        );
+    }
+
+    #[test]
+    fn test_list_items() {
+        assert_eq!(list_items("1. one\n2. two"), (true, vec!["one", "two"]));
+        assert_eq!(list_items("- a\n- b"), (false, vec!["a", "b"]));
+    }
+
+    #[test]
+    fn test_image_size_from_header() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend_from_slice(&640u32.to_be_bytes());
+        png.extend_from_slice(&480u32.to_be_bytes());
+        assert_eq!(image_size(&png), Some((640, 480)));
+        assert_eq!(image_size(b"GIF89a\x10\0\x08\0"), Some((16, 8)));
+        assert_eq!(image_size(b"nope"), None);
+    }
+
+    #[test]
+    fn test_remote_images_are_not_local() {
+        let renderer = DocxRenderer::new().with_base_dir("/docs");
+        let local = |url: &str| renderer.local_path(&MediaSource::Url(url.into()));
+        assert_eq!(local("img/a.png"), Some(PathBuf::from("/docs/img/a.png")));
+        assert_eq!(
+            local("file:///tmp/a.png"),
+            Some(PathBuf::from("/tmp/a.png"))
+        );
+        assert_eq!(local("https://example.com/a.png"), None);
+        assert_eq!(local("data:image/png;base64,AAAA"), None);
+    }
+}
@@ -0,0 +1,159 @@
+//! Minimal ZIP archive writer.
+//!
+//! Entries are stored uncompressed with a fixed timestamp, which is all an
+//! OOXML package needs and keeps the output byte-for-byte reproducible.
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIR: u32 = 0x0605_4b50;
+const VERSION: u16 = 20;
+/// 1980-01-01, the earliest date ZIP can represent
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+struct Entry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// Writes stored (uncompressed) ZIP entries into memory
+pub(crate) struct ZipWriter {
+    buf: Vec<u8>,
+    entries: Vec<Entry>,
+}
+
+impl ZipWriter {
+    pub(crate) fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Append a file to the archive
+    pub(crate) fn add(&mut self, name: &str, data: &[u8]) {
+        let entry = Entry {
+            name: name.to_string(),
+            crc: crc32(data),
+            size: data.len() as u32,
+            offset: self.buf.len() as u32,
+        };
+
+        self.u32(LOCAL_HEADER);
+        self.u16(VERSION);
+        self.u16(0); // flags
+        self.u16(0); // method: stored
+        self.u16(0); // time
+        self.u16(DOS_DATE);
+        self.u32(entry.crc);
+        self.u32(entry.size);
+        self.u32(entry.size);
+        self.u16(name.len() as u16);
+        self.u16(0); // extra length
+        self.buf.extend_from_slice(name.as_bytes());
+        self.buf.extend_from_slice(data);
+
+        self.entries.push(entry);
+    }
+
+    /// Write the central directory and return the archive bytes
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        let central_offset = self.buf.len() as u32;
+        let entries = std::mem::take(&mut self.entries);
+        for entry in &entries {
+            self.u32(CENTRAL_HEADER);
+            self.u16(VERSION); // made by
+            self.u16(VERSION); // needed
+            self.u16(0); // flags
+            self.u16(0); // method
+            self.u16(0); // time
+            self.u16(DOS_DATE);
+            self.u32(entry.crc);
+            self.u32(entry.size);
+            self.u32(entry.size);
+            self.u16(entry.name.len() as u16);
+            self.u16(0); // extra length
+            self.u16(0); // comment length
+            self.u16(0); // disk
+            self.u16(0); // internal attributes
+            self.u32(0); // external attributes
+            self.u32(entry.offset);
+            self.buf.extend_from_slice(entry.name.as_bytes());
+        }
+        let central_size = self.buf.len() as u32 - central_offset;
+
+        self.u32(END_OF_CENTRAL_DIR);
+        self.u16(0); // this disk
+        self.u16(0); // central directory disk
+        self.u16(entries.len() as u16);
+        self.u16(entries.len() as u16);
+        self.u32(central_size);
+        self.u32(central_offset);
+        self.u16(0); // comment length
+        self.buf
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// CRC-32 (IEEE) as used by ZIP
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc = CRC_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_known_values() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_archive_layout() {
+        let mut zip = ZipWriter::new();
+        zip.add("a.txt", b"hello");
+        zip.add("dir/b.txt", b"");
+        let bytes = zip.finish();
+
+        assert_eq!(&bytes[..4], &LOCAL_HEADER.to_le_bytes());
+        let eocd = &bytes[bytes.len() - 22..];
+        assert_eq!(&eocd[..4], &END_OF_CENTRAL_DIR.to_le_bytes());
+        assert_eq!(u16::from_le_bytes([eocd[10], eocd[11]]), 2);
+    }
+}
@@ -0,0 +1,244 @@
+//! Golden-file tests: render a document, unzip the package and check its XML.
+//!
+//! Set `UPDATE_GOLDEN=1` to rewrite the files under `tests/golden/`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use ucm_core::{Block, Content, Document, Media, MediaSource};
+use ucp_translator_docx::{render_docx, DocxRenderer};
+
+/// Parts of a stored ZIP archive by name
+fn unzip(bytes: &[u8]) -> BTreeMap<String, Vec<u8>> {
+    let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]) as usize;
+    let u32_at = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap()) as usize;
+
+    let mut parts = BTreeMap::new();
+    let mut offset = 0;
+    while u32_at(offset) == 0x0403_4b50 {
+        assert_eq!(u16_at(offset + 8), 0, "entries are stored");
+        let size = u32_at(offset + 18);
+        let name_len = u16_at(offset + 26);
+        let extra_len = u16_at(offset + 28);
+        let name_start = offset + 30;
+        let data_start = name_start + name_len + extra_len;
+        let name = String::from_utf8(bytes[name_start..name_start + name_len].to_vec()).unwrap();
+        parts.insert(name, bytes[data_start..data_start + size].to_vec());
+        offset = data_start + size;
+    }
+
+    // The end-of-central-directory record counts every entry
+    let eocd = bytes.len() - 22;
+    assert_eq!(u32_at(eocd), 0x0605_4b50);
+    assert_eq!(u16_at(eocd + 10), parts.len());
+    parts
+}
+
+fn part(parts: &BTreeMap<String, Vec<u8>>, name: &str) -> String {
+    String::from_utf8(
+        parts
+            .get(name)
+            .unwrap_or_else(|| panic!("missing {}", name))
+            .clone(),
+    )
+    .unwrap()
+}
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn assert_golden(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(actual, expected, "{} differs from golden file", name);
+}
+
+/// Pretty-print XML one element per line so golden diffs stay readable
+fn indent_xml(xml: &str) -> String {
+    xml.replace("><", ">\n<")
+}
+
+fn report() -> Document {
+    let markdown = std::fs::read_to_string(fixture("report.md")).unwrap();
+    let mut doc = ucp_translator_markdown::parse_markdown(&markdown).unwrap();
+    doc.metadata.title = Some("Quarterly Report".into());
+    doc.metadata.authors = vec!["Finance".into()];
+    doc
+}
+
+#[test]
+fn test_package_parts() {
+    let parts = unzip(&render_docx(&report()));
+    let names: Vec<&str> = parts.keys().map(String::as_str).collect();
+    assert_eq!(
+        names,
+        vec![
+            "[Content_Types].xml",
+            "_rels/.rels",
+            "docProps/core.xml",
+            "word/_rels/document.xml.rels",
+            "word/document.xml",
+            "word/styles.xml",
+        ]
+    );
+
+    let content_types = part(&parts, "[Content_Types].xml");
+    assert!(content_types.contains(r#"<Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>"#));
+    assert!(part(&parts, "_rels/.rels").contains(r#"Target="word/document.xml""#));
+    assert!(part(&parts, "docProps/core.xml")
+        .contains("<dc:title>Quarterly Report</dc:title><dc:creator>Finance</dc:creator>"));
+
+    let styles = part(&parts, "word/styles.xml");
+    for style in [
+        "Heading1",
+        "Heading3",
+        "Code",
+        "Quote",
+        "ListBullet",
+        "TableGrid",
+    ] {
+        assert!(
+            styles.contains(&format!(r#"w:styleId="{}""#, style)),
+            "missing style {}",
+            style
+        );
+    }
+}
+
+#[test]
+fn test_report_document_xml_matches_golden() {
+    let parts = unzip(&render_docx(&report()));
+    assert_golden(
+        "report.document.xml",
+        &indent_xml(&part(&parts, "word/document.xml")),
+    );
+}
+
+#[test]
+fn test_report_structure() {
+    let parts = unzip(&render_docx(&report()));
+    let xml = part(&parts, "word/document.xml");
+
+    // Sections map to heading styles by depth
+    let headings: Vec<&str> = xml
+        .split(r#"<w:pStyle w:val=""#)
+        .skip(1)
+        .filter_map(|rest| rest.split('"').next())
+        .filter(|style| style.starts_with("Heading"))
+        .collect();
+    assert_eq!(
+        headings,
+        vec!["Heading1", "Heading2", "Heading2", "Heading3"]
+    );
+
+    // One table: a repeated header row plus two data rows
+    assert_eq!(xml.matches("<w:tbl>").count(), 1);
+    assert_eq!(xml.matches("<w:tr>").count(), 3);
+    assert_eq!(xml.matches("<w:tblHeader/>").count(), 1);
+
+    // Code keeps its line breaks in the monospace style
+    assert!(xml.contains(concat!(
+        r#"<w:pStyle w:val="Code"/></w:pPr><w:r><w:t xml:space="preserve">def total(rows):</w:t><w:br/>"#,
+        r#"<w:t xml:space="preserve">    return sum(r.amount for r in rows if r.amount &gt; 0)</w:t>"#
+    )));
+    assert_eq!(xml.matches(r#"<w:pStyle w:val="ListBullet"/>"#).count(), 3);
+    assert_eq!(xml.matches(r#"<w:pStyle w:val="ListNumber"/>"#).count(), 2);
+}
+
+#[test]
+fn test_local_images_are_embedded() {
+    let mut doc = Document::create();
+    let root = doc.root;
+    let mut figure = Block::new(
+        Content::Media(Media::image(MediaSource::Url("pixel.png".into())).with_alt("A pixel")),
+        None,
+    );
+    figure
+        .metadata
+        .custom
+        .insert("caption".into(), "Figure 1".into());
+    doc.add_block(figure, &root).unwrap();
+    // The same file again reuses the embedded part
+    doc.add_block(
+        Block::new(
+            Content::Media(Media::image(MediaSource::Url("./pixel.png".into()))),
+            Some("figure"),
+        ),
+        &root,
+    )
+    .unwrap();
+    doc.add_block(
+        Block::new(
+            Content::Media(
+                Media::image(MediaSource::Url("https://example.com/remote.png".into()))
+                    .with_alt("Remote"),
+            ),
+            None,
+        ),
+        &root,
+    )
+    .unwrap();
+
+    let bytes = DocxRenderer::new().with_base_dir(fixture("")).render(&doc);
+    let parts = unzip(&bytes);
+
+    assert_eq!(
+        parts.get("word/media/image1.png"),
+        Some(&std::fs::read(fixture("pixel.png")).unwrap())
+    );
+    assert_eq!(
+        parts
+            .keys()
+            .filter(|n| n.starts_with("word/media/"))
+            .count(),
+        1
+    );
+
+    let rels = part(&parts, "word/_rels/document.xml.rels");
+    assert!(rels.contains(r#"Id="rIdImage1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="media/image1.png""#));
+    assert!(part(&parts, "[Content_Types].xml")
+        .contains(r#"<Default Extension="png" ContentType="image/png"/>"#));
+
+    let xml = part(&parts, "word/document.xml");
+    // 2x1 pixels from the PNG header, in EMUs
+    assert!(xml.contains(r#"<wp:extent cx="19050" cy="9525"/>"#));
+    assert!(xml.contains(r#"<wp:docPr id="1" name="Picture 1" descr="A pixel"/>"#));
+    assert!(xml.contains(r#"<a:blip r:embed="rIdImage1"/>"#));
+    assert!(xml.contains(
+        r#"<w:pStyle w:val="Caption"/></w:pPr><w:r><w:t xml:space="preserve">Figure 1</w:t>"#
+    ));
+    // Remote images stay as text
+    assert!(xml.contains("Remote (https://example.com/remote.png)"));
+    assert_eq!(xml.matches("<w:drawing>").count(), 2);
+}
+
+#[test]
+fn test_missing_image_falls_back_to_text() {
+    let mut doc = Document::create();
+    let root = doc.root;
+    doc.add_block(
+        Block::new(
+            Content::Media(Media::image(MediaSource::Url("missing.png".into())).with_alt("Gone")),
+            None,
+        ),
+        &root,
+    )
+    .unwrap();
+
+    let parts = unzip(&render_docx(&doc));
+    assert!(!parts.keys().any(|n| n.starts_with("word/media/")));
+    assert!(part(&parts, "word/document.xml").contains("Gone (missing.png)"));
+}
+
+#[test]
+fn test_output_is_deterministic() {
+    assert_eq!(render_docx(&report()), render_docx(&report()));
+}
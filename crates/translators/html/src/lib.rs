@@ -2,7 +2,8 @@
 //!
 //! This crate provides translation from HTML documents to UCM's block-based
 //! document model. It extracts semantic structure from HTML elements and
-//! creates appropriate blocks with proper hierarchy.
+//! creates appropriate blocks with proper hierarchy, and renders documents
+//! back to semantic HTML.
 //!
 //! # Example
 //!
@@ -16,13 +17,19 @@
 //!
 //! let parser = HtmlParser::new();
 //! let doc = parser.parse(html).unwrap();
+//! let rendered = ucp_translator_html::render_html(&doc).unwrap();
 //! ```
 
 mod error;
 mod parser;
+mod render;
 
 pub use error::{HtmlError, Result};
-pub use parser::{HeadingStrategy, HtmlParser, HtmlParserConfig};
+pub use parser::{HeadingStrategy, HtmlParser, HtmlParserConfig, ParseOutput};
+pub use render::{HtmlRenderOptions, HtmlRenderer};
+
+/// Block metadata key holding a `<figcaption>` or table `<caption>`
+pub const CAPTION_KEY: &str = "caption";
 
 /// Parse HTML string into a UCM Document.
 ///
@@ -31,6 +38,26 @@ pub fn parse_html(html: &str) -> Result<ucm_core::Document> {
     HtmlParser::new().parse(html)
 }
 
+/// Parse HTML, also reporting anything that was not converted faithfully.
+pub fn parse_html_with_report(html: &str) -> Result<ParseOutput> {
+    HtmlParser::new().parse_with_report(html)
+}
+
+/// Render a UCM Document to an HTML fragment.
+///
+/// This is a convenience function that uses default options.
+pub fn render_html(doc: &ucm_core::Document) -> Result<String> {
+    HtmlRenderer::new().render(doc)
+}
+
+/// Render a UCM Document to HTML with explicit options.
+pub fn render_html_with_options(
+    doc: &ucm_core::Document,
+    options: HtmlRenderOptions,
+) -> Result<String> {
+    HtmlRenderer::with_options(options).render(doc)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
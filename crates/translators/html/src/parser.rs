@@ -1,8 +1,12 @@
 //! HTML parser implementation.
 
 use crate::error::{HtmlError, Result};
+use crate::CAPTION_KEY;
 use scraper::{ElementRef, Html, Selector};
-use ucm_core::{Block, BlockId, Content, Document, MediaSource};
+use ucm_core::{
+    Block, BlockId, Content, Document, MediaSource, TranslationReport, TranslationWarning,
+    TranslationWarningCode,
+};
 
 /// Strategy for handling heading levels
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -49,6 +53,13 @@ impl Default for HtmlParserConfig {
     }
 }
 
+/// A parsed document together with what could not be carried over faithfully
+#[derive(Debug, Clone)]
+pub struct ParseOutput {
+    pub document: Document,
+    pub report: TranslationReport,
+}
+
 /// HTML to UCM document parser
 pub struct HtmlParser {
     config: HtmlParserConfig,
@@ -69,8 +80,25 @@ impl HtmlParser {
 
     /// Parse HTML string into a UCM Document
     pub fn parse(&self, html: &str) -> Result<Document> {
+        Ok(self.parse_with_report(html)?.document)
+    }
+
+    /// Parse, also reporting dropped elements, nested tables flattened to
+    /// text and other lossy conversions
+    pub fn parse_with_report(&self, html: &str) -> Result<ParseOutput> {
         let mut doc = Document::create();
         let root = doc.root;
+        let mut report = TranslationReport::new();
+        let html = match html.strip_prefix('\u{feff}') {
+            Some(rest) => {
+                report.push(TranslationWarning::new(
+                    TranslationWarningCode::EncodingFixed,
+                    "byte order mark removed",
+                ));
+                rest
+            }
+            None => html,
+        };
 
         // Parse HTML
         let fragment = Html::parse_document(html);
@@ -80,17 +108,20 @@ impl HtmlParser {
         let body = fragment.select(&body_selector).next();
 
         if let Some(body_element) = body {
-            self.process_children(&mut doc, &root, body_element, 0)?;
+            self.process_children(&mut doc, &root, body_element, 0, &mut report)?;
         } else {
             // No body tag, process entire document
             if let Some(root_element) = fragment.root_element().first_child() {
                 if let Some(element) = ElementRef::wrap(root_element) {
-                    self.process_children(&mut doc, &root, element, 0)?;
+                    self.process_children(&mut doc, &root, element, 0, &mut report)?;
                 }
             }
         }
 
-        Ok(doc)
+        Ok(ParseOutput {
+            document: doc,
+            report,
+        })
     }
 
     /// Process all children of an element
@@ -100,6 +131,7 @@ impl HtmlParser {
         parent_id: &BlockId,
         element: ElementRef,
         depth: usize,
+        report: &mut TranslationReport,
     ) -> Result<()> {
         if depth > self.config.max_depth {
             return Err(HtmlError::ResourceLimit(format!(
@@ -148,7 +180,13 @@ impl HtmlParser {
                     }
                 } else {
                     // Non-heading elements go under current heading
-                    self.process_element(doc, &current_heading_parent, child_element, depth + 1)?;
+                    self.process_element(
+                        doc,
+                        &current_heading_parent,
+                        child_element,
+                        depth + 1,
+                        report,
+                    )?;
                 }
             } else if let Some(text_node) = child.value().as_text() {
                 let text = if self.config.preserve_whitespace {
@@ -174,13 +212,21 @@ impl HtmlParser {
         parent_id: &BlockId,
         element: ElementRef,
         depth: usize,
+        report: &mut TranslationReport,
     ) -> Result<Option<BlockId>> {
+        let tag_name = element.value().name();
+
         if depth > self.config.max_depth {
+            report.push(TranslationWarning::new(
+                TranslationWarningCode::DroppedElement,
+                format!(
+                    "<{}> nested deeper than {} levels dropped",
+                    tag_name, self.config.max_depth
+                ),
+            ));
             return Ok(None);
         }
 
-        let tag_name = element.value().name();
-
         match tag_name {
             // Skip script, style, meta, etc.
             "script" | "style" | "meta" | "link" | "head" | "noscript" => Ok(None),
@@ -215,18 +261,32 @@ impl HtmlParser {
             "blockquote" => self.process_blockquote(doc, parent_id, element),
 
             // Images
-            "img" => self.process_image(doc, parent_id, element),
+            "img" => self.process_image(doc, parent_id, element, report),
+
+            // Embedded content with no block representation
+            "iframe" | "video" | "audio" | "canvas" | "svg" | "object" | "embed" => {
+                report.push(TranslationWarning::new(
+                    TranslationWarningCode::DroppedElement,
+                    format!("<{}> has no block representation and was dropped", tag_name),
+                ));
+                Ok(None)
+            }
 
             // Links
             "a" => self.process_link(doc, parent_id, element),
 
             // Tables
-            "table" => self.process_table(doc, parent_id, element),
+            "table" => self.process_table(doc, parent_id, element, report),
+
+            // Figures with an image become a captioned media block
+            "figure" if self.config.extract_images && self.first_image(element).is_some() => {
+                self.process_figure(doc, parent_id, element)
+            }
 
             // Container elements - process children
             "div" | "section" | "article" | "main" | "aside" | "nav" | "header" | "footer"
             | "span" | "figure" | "figcaption" => {
-                self.process_children(doc, parent_id, element, depth)?;
+                self.process_children(doc, parent_id, element, depth, report)?;
                 Ok(None)
             }
 
@@ -241,7 +301,7 @@ impl HtmlParser {
                     Ok(Some(doc.add_block(block, parent_id)?))
                 } else {
                     // Process children for unknown container elements
-                    self.process_children(doc, parent_id, element, depth)?;
+                    self.process_children(doc, parent_id, element, depth, report)?;
                     Ok(None)
                 }
             }
@@ -282,12 +342,16 @@ impl HtmlParser {
         element: ElementRef,
     ) -> Result<Option<BlockId>> {
         let text = self.extract_formatted_text(element);
-        if text.is_empty() || text.len() < self.config.min_text_length {
-            return Ok(None);
+        let mut last = None;
+        if !text.is_empty() && text.len() >= self.config.min_text_length {
+            let block = Block::new(Content::text(&text), Some("paragraph"));
+            last = Some(doc.add_block(block, parent_id)?);
         }
 
-        let block = Block::new(Content::text(&text), Some("paragraph"));
-        Ok(Some(doc.add_block(block, parent_id)?))
+        // Inline images would otherwise be lost with the text extraction
+        Ok(self
+            .process_nested_images(doc, parent_id, element)?
+            .or(last))
     }
 
     /// Process a list (ul/ol)
@@ -328,19 +392,21 @@ impl HtmlParser {
             return Ok(None);
         }
 
-        // Try to extract language from class
-        let language = code_element
-            .value()
-            .attr("class")
-            .and_then(|class| {
-                class
-                    .split_whitespace()
-                    .find(|c| c.starts_with("language-") || c.starts_with("lang-"))
-                    .map(|c| {
-                        c.trim_start_matches("language-")
-                            .trim_start_matches("lang-")
-                    })
+        // Try to extract language from class, on <code> first and then <pre>
+        let language = [code_element, element]
+            .iter()
+            .find_map(|e| {
+                e.value().attr("class").and_then(|class| {
+                    class
+                        .split_whitespace()
+                        .find(|c| c.starts_with("language-") || c.starts_with("lang-"))
+                        .map(|c| {
+                            c.trim_start_matches("language-")
+                                .trim_start_matches("lang-")
+                        })
+                })
             })
+            .filter(|language| !language.is_empty())
             .unwrap_or("text");
 
         let block = Block::new(Content::code(language, &code_text), Some("code"));
@@ -369,16 +435,82 @@ impl HtmlParser {
         doc: &mut Document,
         parent_id: &BlockId,
         element: ElementRef,
+        report: &mut TranslationReport,
     ) -> Result<Option<BlockId>> {
         if !self.config.extract_images {
             return Ok(None);
         }
+        match self.image_block(element) {
+            Some(block) => Ok(Some(doc.add_block(block, parent_id)?)),
+            None => {
+                report.push(TranslationWarning::new(
+                    TranslationWarningCode::DroppedElement,
+                    "<img> without a src dropped",
+                ));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Process a figure, attaching its caption to the first image
+    fn process_figure(
+        &self,
+        doc: &mut Document,
+        parent_id: &BlockId,
+        element: ElementRef,
+    ) -> Result<Option<BlockId>> {
+        let Some(mut block) = self
+            .first_image(element)
+            .and_then(|img| self.image_block(img))
+        else {
+            return Ok(None);
+        };
+
+        let caption_selector = Selector::parse("figcaption").unwrap();
+        if let Some(caption) = element.select(&caption_selector).next() {
+            let caption = self.extract_formatted_text(caption);
+            if !caption.is_empty() {
+                block
+                    .metadata
+                    .custom
+                    .insert(CAPTION_KEY.to_string(), caption.into());
+            }
+        }
+        Ok(Some(doc.add_block(block, parent_id)?))
+    }
 
+    /// Add blocks for images nested inside an inline element
+    fn process_nested_images(
+        &self,
+        doc: &mut Document,
+        parent_id: &BlockId,
+        element: ElementRef,
+    ) -> Result<Option<BlockId>> {
+        if !self.config.extract_images {
+            return Ok(None);
+        }
+        let img_selector = Selector::parse("img").unwrap();
+        let mut last = None;
+        for img in element.select(&img_selector) {
+            if let Some(block) = self.image_block(img) {
+                last = Some(doc.add_block(block, parent_id)?);
+            }
+        }
+        Ok(last)
+    }
+
+    fn first_image<'a>(&self, element: ElementRef<'a>) -> Option<ElementRef<'a>> {
+        let img_selector = Selector::parse("img").unwrap();
+        element.select(&img_selector).next()
+    }
+
+    /// Build a media block from an `<img>`, or `None` if it has no source
+    fn image_block(&self, element: ElementRef) -> Option<Block> {
         let src = element.value().attr("src").unwrap_or("");
         let alt = element.value().attr("alt").unwrap_or("");
 
         if src.is_empty() {
-            return Ok(None);
+            return None;
         }
 
         // Create media content
@@ -391,8 +523,7 @@ impl HtmlParser {
         };
 
         let media = ucm_core::Media::image(media_source).with_alt(alt);
-        let block = Block::new(Content::Media(media), Some("image"));
-        Ok(Some(doc.add_block(block, parent_id)?))
+        Some(Block::new(Content::Media(media), Some("image")))
     }
 
     /// Process a link element
@@ -406,7 +537,7 @@ impl HtmlParser {
         let href = element.value().attr("href").unwrap_or("");
 
         if text.is_empty() {
-            return Ok(None);
+            return self.process_nested_images(doc, parent_id, element);
         }
 
         if self.config.extract_links && !href.is_empty() {
@@ -427,25 +558,84 @@ impl HtmlParser {
         doc: &mut Document,
         parent_id: &BlockId,
         element: ElementRef,
+        report: &mut TranslationReport,
     ) -> Result<Option<BlockId>> {
+        let table_selector = Selector::parse("table").unwrap();
+        if element
+            .select(&table_selector)
+            .any(|t| t.id() != element.id())
+        {
+            // Join text nodes with spaces so adjacent cells stay apart
+            let text = element
+                .text()
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let mut warning = TranslationWarning::new(
+                TranslationWarningCode::FlattenedStructure,
+                "nested <table> flattened to text",
+            );
+            let id = if text.is_empty() {
+                None
+            } else {
+                let block = Block::new(Content::text(&text), Some("text"));
+                let id = doc.add_block(block, parent_id)?;
+                warning = warning.with_block(id);
+                Some(id)
+            };
+            report.push(warning);
+            return Ok(id);
+        }
+
         let row_selector = Selector::parse("tr").unwrap();
         let cell_selector = Selector::parse("td, th").unwrap();
 
-        let rows: Vec<Vec<String>> = element
-            .select(&row_selector)
-            .map(|row| {
-                row.select(&cell_selector)
+        let mut header = None;
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        for row in element.select(&row_selector) {
+            let cells: Vec<ElementRef> = row.select(&cell_selector).collect();
+            if cells.is_empty() {
+                continue;
+            }
+            if rows.is_empty() && cells.iter().all(|c| c.value().name() == "th") {
+                header = Some(cells.len());
+            }
+            rows.push(
+                cells
+                    .into_iter()
                     .map(|cell| self.extract_text_content(cell))
-                    .collect()
-            })
-            .filter(|row: &Vec<String>| !row.is_empty())
-            .collect();
+                    .collect(),
+            );
+        }
 
         if rows.is_empty() {
             return Ok(None);
         }
 
-        let block = Block::new(Content::table(rows), Some("table"));
+        let mut content = Content::table(rows);
+        if let (Some(_), Content::Table(table)) = (header, &mut content) {
+            // Header cells name the columns
+            for (column, name) in table.columns.iter_mut().zip(&table.rows[0].cells) {
+                if let ucm_core::Cell::Text(name) = name {
+                    if !name.is_empty() {
+                        column.name = name.clone();
+                    }
+                }
+            }
+        }
+
+        let mut block = Block::new(content, Some("table"));
+        let caption_selector = Selector::parse("caption").unwrap();
+        if let Some(caption) = element.select(&caption_selector).next() {
+            let caption = self.extract_text_content(caption);
+            if !caption.is_empty() {
+                block
+                    .metadata
+                    .custom
+                    .insert(CAPTION_KEY.to_string(), caption.into());
+            }
+        }
         Ok(Some(doc.add_block(block, parent_id)?))
     }
 
@@ -517,7 +707,15 @@ impl HtmlParser {
                 let text = if self.config.preserve_whitespace {
                     text_node.to_string()
                 } else {
-                    text_node.split_whitespace().collect::<Vec<_>>().join(" ")
+                    // Keep word boundaries next to inline elements
+                    let mut text = text_node.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if text_node.starts_with(char::is_whitespace) {
+                        text.insert(0, ' ');
+                    }
+                    if text_node.ends_with(char::is_whitespace) && !text.ends_with(' ') {
+                        text.push(' ');
+                    }
+                    text
                 };
                 result.push_str(&text);
             }
@@ -581,6 +779,17 @@ mod tests {
         assert!(result.is_ok() || matches!(result, Err(HtmlError::ResourceLimit(_))));
     }
 
+    #[test]
+    fn test_inline_elements_keep_word_boundaries() {
+        let html = "<p>Call <code>run()</code> once, <b>then</b>\n  <i>stop</i>.</p>";
+        let doc = HtmlParser::new().parse(html).unwrap();
+        let block = doc.get_block(&doc.children(&doc.root)[0]).unwrap();
+        assert_eq!(
+            block.content,
+            Content::text("Call `run()` once, **then** *stop*.")
+        );
+    }
+
     #[test]
     fn test_heading_strategy_flatten() {
         let config = HtmlParserConfig {
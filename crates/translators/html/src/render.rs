@@ -0,0 +1,611 @@
+//! Render UCM documents to semantic HTML.
+//!
+//! Headings take their level from section depth and wrap their children in a
+//! `<section>`, so the output parses back into the same tree. Inline markdown
+//! produced by the parser (`**bold**`, `*em*`, `` `code` ``, `[text](href)`)
+//! is turned back into the matching elements.
+
+use crate::error::{HtmlError, Result};
+use crate::CAPTION_KEY;
+use ucm_core::metadata::RoleCategory;
+use ucm_core::{
+    Block, BlockId, Cell, Content, DiagramDialect, Document, FieldType, InlineSpan, Media,
+    MediaSource, MediaType, Table,
+};
+
+/// Options controlling HTML rendering
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HtmlRenderOptions {
+    /// Emit `data-ucp-block` attributes so elements can be mapped back to blocks
+    pub emit_block_ids: bool,
+}
+
+/// UCM document to HTML renderer
+pub struct HtmlRenderer {
+    options: HtmlRenderOptions,
+}
+
+impl HtmlRenderer {
+    /// Create a renderer with default options
+    pub fn new() -> Self {
+        Self {
+            options: HtmlRenderOptions::default(),
+        }
+    }
+
+    /// Create a renderer with custom options
+    pub fn with_options(options: HtmlRenderOptions) -> Self {
+        Self { options }
+    }
+
+    /// Render a document as an HTML fragment
+    pub fn render(&self, doc: &Document) -> Result<String> {
+        let mut output = String::new();
+        self.render_children(doc, &doc.root, &mut output, 0)?;
+        Ok(output)
+    }
+
+    fn render_children(
+        &self,
+        doc: &Document,
+        parent_id: &BlockId,
+        output: &mut String,
+        section_depth: usize,
+    ) -> Result<()> {
+        for child_id in doc.children(parent_id) {
+            let block = doc.get_block(child_id).ok_or_else(|| {
+                HtmlError::InvalidStructure(format!("Block not found: {}", child_id))
+            })?;
+
+            if let (true, Content::Text(text)) = (is_heading(block), &block.content) {
+                let level = (section_depth + 1).min(6);
+                output.push_str("<section>\n");
+                output.push_str(&format!(
+                    "<h{}{}>{}</h{}>\n",
+                    level,
+                    self.block_attr(block),
+                    render_inline(&text.text),
+                    level
+                ));
+                self.render_children(doc, child_id, output, section_depth + 1)?;
+                output.push_str("</section>\n");
+            } else {
+                self.render_content(block, output);
+                // Children of non-heading blocks cannot nest in HTML, keep them in order
+                self.render_children(doc, child_id, output, section_depth)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn render_content(&self, block: &Block, output: &mut String) {
+        let attr = self.block_attr(block);
+        let role = block.metadata.semantic_role.as_ref().map(|r| r.category);
+        let caption = block
+            .metadata
+            .custom
+            .get(CAPTION_KEY)
+            .and_then(|v| v.as_str());
+
+        match &block.content {
+            Content::Text(text) if text.text.is_empty() => {}
+            Content::Text(text) => match role {
+                Some(RoleCategory::Quote) => output.push_str(&format!(
+                    "<blockquote{}>{}</blockquote>\n",
+                    attr,
+                    render_inline(&text.text)
+                )),
+                Some(RoleCategory::List) => {
+                    let (tag, items) = list_items(&text.text);
+                    output.push_str(&format!("<{}{}>\n", tag, attr));
+                    for item in items {
+                        output.push_str(&format!("<li>{}</li>\n", render_inline(item)));
+                    }
+                    output.push_str(&format!("</{}>\n", tag));
+                }
+                _ => output.push_str(&format!("<p{}>{}</p>\n", attr, render_inline(&text.text))),
+            },
+            Content::RichText(rich) => {
+                let tag = match role {
+                    Some(RoleCategory::Quote) => "blockquote",
+                    _ => "p",
+                };
+                output.push_str(&format!(
+                    "<{}{}>{}</{}>\n",
+                    tag,
+                    attr,
+                    render_spans(&rich.spans),
+                    tag
+                ));
+            }
+            Content::Code(_) | Content::CompressedCode { .. } => {
+                let language = block.content.code_language().unwrap_or_default();
+                let source = block.content.decompress().unwrap_or_default();
+                let class = if language.is_empty() {
+                    String::new()
+                } else {
+                    format!(" class=\"language-{}\"", escape(language))
+                };
+                output.push_str(&format!(
+                    "<pre{}><code{}>{}</code></pre>\n",
+                    attr,
+                    class,
+                    escape(&source)
+                ));
+            }
+            // Mermaid JS renders `div.mermaid` in place; other dialects have
+            // no standard client renderer and stay source listings
+            Content::Diagram(diagram) if diagram.dialect == DiagramDialect::Mermaid => {
+                output.push_str(&format!(
+                    "<div class=\"mermaid\"{}>{}</div>\n",
+                    attr,
+                    escape(&diagram.source)
+                ));
+            }
+            Content::Diagram(diagram) => {
+                output.push_str(&format!(
+                    "<pre{}><code class=\"language-{}\">{}</code></pre>\n",
+                    attr,
+                    diagram.dialect.fence_tag(),
+                    escape(&diagram.source)
+                ));
+            }
+            Content::Diff(diff) => {
+                output.push_str(&format!(
+                    "<pre{}><code class=\"language-diff\">{}</code></pre>\n",
+                    attr,
+                    escape(&diff.patch)
+                ));
+            }
+            // Map libraries read the feature data from the attribute; the
+            // single-quoted form keeps JSON's double quotes readable
+            Content::GeoJson(value) => {
+                output.push_str(&format!(
+                    "<div{} data-geojson='{}'></div>\n",
+                    attr,
+                    escape_single_quoted(&value.to_string())
+                ));
+            }
+            Content::Form(form) => {
+                output.push_str(&format!("<fieldset{}>\n", attr));
+                if !form.title.is_empty() {
+                    output.push_str(&format!("<legend>{}</legend>\n", escape(&form.title)));
+                }
+                output.push_str("<dl>\n");
+                for field in &form.fields {
+                    let required = if field.required { " (required)" } else { "" };
+                    output.push_str(&format!(
+                        "<dt>{}{}</dt><dd>{}</dd>\n",
+                        escape(&field.label),
+                        required,
+                        escape(&field_description(&field.field_type))
+                    ));
+                }
+                output.push_str("</dl>\n</fieldset>\n");
+            }
+            Content::Table(table) => self.render_table(table, caption, &attr, output),
+            Content::Spreadsheet(sheet) => {
+                self.render_table(&sheet.to_table(), caption, &attr, output)
+            }
+            Content::Math(math) => {
+                let tag = if math.display_mode { "div" } else { "span" };
+                output.push_str(&format!(
+                    "<{} class=\"math\"{}>{}</{}>\n",
+                    tag,
+                    attr,
+                    escape(&math.expression),
+                    tag
+                ));
+            }
+            Content::Media(media) => self.render_media(media, caption, &attr, output),
+            Content::Json { value, .. } => {
+                output.push_str(&format!(
+                    "<pre{}><code class=\"language-json\">{}</code></pre>\n",
+                    attr,
+                    escape(&value.to_string())
+                ));
+            }
+            Content::Composite { children, .. } => {
+                output.push_str(&format!(
+                    "<!-- composite: {} children -->\n",
+                    children.len()
+                ));
+            }
+            Content::Binary { mime_type, .. } => {
+                output.push_str(&format!("<!-- binary: {} -->\n", escape(mime_type)));
+            }
+        }
+    }
+
+    fn render_table(&self, table: &Table, caption: Option<&str>, attr: &str, output: &mut String) {
+        output.push_str(&format!("<table{}>\n", attr));
+        if let Some(caption) = caption {
+            output.push_str(&format!("<caption>{}</caption>\n", escape(caption)));
+        }
+        for (i, row) in table.rows.iter().enumerate() {
+            let cell_tag = if i == 0 { "th" } else { "td" };
+            if i == 0 {
+                output.push_str("<thead>\n");
+            } else if i == 1 {
+                output.push_str("<tbody>\n");
+            }
+            output.push_str("<tr>");
+            for cell in &row.cells {
+                output.push_str(&format!(
+                    "<{}>{}</{}>",
+                    cell_tag,
+                    escape(&cell_to_string(cell)),
+                    cell_tag
+                ));
+            }
+            output.push_str("</tr>\n");
+            if i == 0 {
+                output.push_str("</thead>\n");
+            }
+        }
+        if table.rows.len() > 1 {
+            output.push_str("</tbody>\n");
+        }
+        output.push_str("</table>\n");
+    }
+
+    fn render_media(&self, media: &Media, caption: Option<&str>, attr: &str, output: &mut String) {
+        let (src_attr, src) = match &media.source {
+            MediaSource::Url(url) => ("src", url.clone()),
+            MediaSource::Base64(data) => ("src", format!("data:image;base64,{}", data)),
+            MediaSource::Reference(id) => ("data-ucp-ref", id.to_string()),
+            MediaSource::External(ext) => {
+                ("data-ucp-external", format!("{}:{}", ext.provider, ext.key))
+            }
+        };
+        let alt = media.alt_text.as_deref().unwrap_or("");
+        let size = media
+            .dimensions
+            .as_ref()
+            .map(|d| format!(" width=\"{}\" height=\"{}\"", d.width, d.height))
+            .unwrap_or_default();
+
+        let element = match media.media_type {
+            MediaType::Image => format!(
+                "<img {}=\"{}\" alt=\"{}\"{}>",
+                src_attr,
+                escape(&src),
+                escape(alt),
+                size
+            ),
+            MediaType::Audio => {
+                format!("<audio controls {}=\"{}\"></audio>", src_attr, escape(&src))
+            }
+            MediaType::Video => format!(
+                "<video controls {}=\"{}\"{}></video>",
+                src_attr,
+                escape(&src),
+                size
+            ),
+            MediaType::Document => {
+                let href_attr = if src_attr == "src" { "href" } else { src_attr };
+                let label = if alt.is_empty() { src.as_str() } else { alt };
+                format!(
+                    "<p{}><a {}=\"{}\">{}</a></p>\n",
+                    attr,
+                    href_attr,
+                    escape(&src),
+                    escape(label)
+                )
+            }
+        };
+        if media.media_type == MediaType::Document {
+            output.push_str(&element);
+        } else {
+            let caption = caption
+                .map(|c| format!("<figcaption>{}</figcaption>", render_inline(c)))
+                .unwrap_or_default();
+            output.push_str(&format!(
+                "<figure{}>{}{}</figure>\n",
+                attr, element, caption
+            ));
+        }
+    }
+
+    fn block_attr(&self, block: &Block) -> String {
+        if self.options.emit_block_ids {
+            format!(" data-ucp-block=\"{}\"", block.id)
+        } else {
+            String::new()
+        }
+    }
+}
+
+impl Default for HtmlRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_heading(block: &Block) -> bool {
+    matches!(
+        block.metadata.semantic_role.as_ref().map(|r| r.category),
+        Some(
+            RoleCategory::Heading1
+                | RoleCategory::Heading2
+                | RoleCategory::Heading3
+                | RoleCategory::Heading4
+                | RoleCategory::Heading5
+                | RoleCategory::Heading6
+                | RoleCategory::Title
+                | RoleCategory::Subtitle
+        )
+    )
+}
+
+/// Split list text into items, stripping markdown markers when every line has one
+fn list_items(text: &str) -> (&'static str, Vec<&str>) {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    let ordered: Option<Vec<&str>> = lines
+        .iter()
+        .map(|line| {
+            let digits = line.find(|c: char| !c.is_ascii_digit())?;
+            let rest = &line[digits..];
+            if digits == 0 {
+                return None;
+            }
+            rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") "))
+        })
+        .collect();
+    if let Some(items) = ordered {
+        return ("ol", items);
+    }
+
+    let bulleted: Option<Vec<&str>> = lines
+        .iter()
+        .map(|line| ["- ", "* ", "+ "].iter().find_map(|m| line.strip_prefix(m)))
+        .collect();
+    ("ul", bulleted.unwrap_or(lines))
+}
+
+/// Convert the parser's inline markdown back to HTML, escaping everything else
+fn render_inline(text: &str) -> String {
+    let mut output = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if let Some((html, len)) = inline_span(rest) {
+            output.push_str(&html);
+            rest = &rest[len..];
+            continue;
+        }
+        if c == '\n' {
+            output.push_str("<br>");
+        } else {
+            output.push_str(&escape(c.encode_utf8(&mut [0; 4])));
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    output
+}
+
+/// Render rich text spans as inline HTML
+fn render_spans(spans: &[InlineSpan]) -> String {
+    let mut output = String::new();
+    for span in spans {
+        match span {
+            InlineSpan::Plain(text) => {
+                output.push_str(&escape(text).replace('\n', "<br>"));
+            }
+            InlineSpan::Bold(inner) => {
+                output.push_str(&format!("<strong>{}</strong>", render_spans(inner)))
+            }
+            InlineSpan::Italic(inner) => {
+                output.push_str(&format!("<em>{}</em>", render_spans(inner)))
+            }
+            InlineSpan::Code(code) => output.push_str(&format!("<code>{}</code>", escape(code))),
+            InlineSpan::Link { text, href } => output.push_str(&format!(
+                "<a href=\"{}\">{}</a>",
+                escape(href),
+                render_spans(text)
+            )),
+            InlineSpan::BlockRef { display, block_id } => output.push_str(&format!(
+                "<a href=\"#{}\">{}</a>",
+                block_id,
+                escape(display)
+            )),
+        }
+    }
+    output
+}
+
+/// Match an inline span at the start of `text`, returning its HTML and byte length
+fn inline_span(text: &str) -> Option<(String, usize)> {
+    if let Some(body) = text.strip_prefix('`') {
+        let end = body.find('`').filter(|&end| end > 0)?;
+        return Some((format!("<code>{}</code>", escape(&body[..end])), end + 2));
+    }
+
+    for (delim, tag) in [("**", "strong"), ("*", "em")] {
+        if let Some(body) = text.strip_prefix(delim) {
+            let end = body.find(delim)?;
+            let inner = &body[..end];
+            if inner.is_empty()
+                || inner.starts_with(char::is_whitespace)
+                || inner.ends_with(char::is_whitespace)
+            {
+                return None;
+            }
+            let len = end + 2 * delim.len();
+            return Some((format!("<{}>{}</{}>", tag, render_inline(inner), tag), len));
+        }
+    }
+
+    let body = text.strip_prefix('[')?;
+    let close = body.find("](")?;
+    let (label, after) = (&body[..close], &body[close + 2..]);
+    let end = after.find(')')?;
+    let href = &after[..end];
+    if label.contains('\n') || href.is_empty() || href.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((
+        format!("<a href=\"{}\">{}</a>", escape(href), render_inline(label)),
+        close + end + 4,
+    ))
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn field_description(field_type: &FieldType) -> String {
+    let options = field_type.options();
+    if options.is_empty() {
+        field_type.name().to_string()
+    } else {
+        format!("{}: {}", field_type.name(), options.join(", "))
+    }
+}
+
+/// Escape text for a single-quoted attribute, leaving double quotes as-is
+fn escape_single_quoted(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\'', "&#39;")
+}
+
+fn cell_to_string(cell: &Cell) -> String {
+    match cell {
+        Cell::Null => String::new(),
+        Cell::Text(s) | Cell::Date(s) | Cell::DateTime(s) => s.clone(),
+        Cell::Number(n) => n.to_string(),
+        Cell::Boolean(b) => b.to_string(),
+        Cell::Json(v) => v.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(doc: &Document) -> String {
+        HtmlRenderer::new().render(doc).unwrap()
+    }
+
+    #[test]
+    fn test_heading_level_from_section_depth() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        // Explicit role says h3, but the block sits at the top level
+        let heading = doc
+            .add_block(Block::new(Content::text("Intro"), Some("heading3")), &root)
+            .unwrap();
+        doc.add_block(
+            Block::new(Content::text("Body"), Some("heading2")),
+            &heading,
+        )
+        .unwrap();
+
+        assert_eq!(
+            render(&doc),
+            "<section>\n<h1>Intro</h1>\n<section>\n<h2>Body</h2>\n</section>\n</section>\n"
+        );
+    }
+
+    #[test]
+    fn test_code_block_language_class() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        doc.add_block(
+            Block::new(Content::code("rust", "if a < b {}"), Some("code")),
+            &root,
+        )
+        .unwrap();
+
+        assert_eq!(
+            render(&doc),
+            "<pre><code class=\"language-rust\">if a &lt; b {}</code></pre>\n"
+        );
+    }
+
+    #[test]
+    fn test_mermaid_diagram_renders_as_mermaid_div() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        doc.add_block(
+            Block::new(Content::mermaid("graph TD\n  A --> B"), None),
+            &root,
+        )
+        .unwrap();
+
+        assert_eq!(
+            render(&doc),
+            "<div class=\"mermaid\">graph TD\n  A --&gt; B</div>\n"
+        );
+    }
+
+    #[test]
+    fn test_geojson_renders_data_attribute() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let value = serde_json::json!({
+            "type": "Feature",
+            "geometry": null,
+            "properties": {"name": "O'Hare & co"}
+        });
+        doc.add_block(
+            Block::new(Content::new_geojson(value).unwrap(), None),
+            &root,
+        )
+        .unwrap();
+
+        assert_eq!(
+            render(&doc),
+            "<div data-geojson='{\"geometry\":null,\"properties\":{\"name\":\"O&#39;Hare &amp; co\"},\"type\":\"Feature\"}'></div>\n"
+        );
+    }
+
+    #[test]
+    fn test_inline_markdown_becomes_elements() {
+        assert_eq!(
+            render_inline("a **b** *c* `d<e` [f](https://x.test/?a&b)"),
+            "a <strong>b</strong> <em>c</em> <code>d&lt;e</code> <a href=\"https://x.test/?a&amp;b\">f</a>"
+        );
+        assert_eq!(render_inline("2 * 3 * 4"), "2 * 3 * 4");
+        assert_eq!(render_inline("line\nbreak"), "line<br>break");
+    }
+
+    #[test]
+    fn test_list_markers_are_stripped() {
+        assert_eq!(list_items("- a\n- b"), ("ul", vec!["a", "b"]));
+        assert_eq!(list_items("1. a\n2) b"), ("ol", vec!["a", "b"]));
+        assert_eq!(list_items("a\n- b"), ("ul", vec!["a", "- b"]));
+    }
+
+    #[test]
+    fn test_block_ids_emitted_when_enabled() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let id = doc
+            .add_block(Block::new(Content::text("Hi"), Some("paragraph")), &root)
+            .unwrap();
+
+        let options = HtmlRenderOptions {
+            emit_block_ids: true,
+        };
+        let html = HtmlRenderer::with_options(options).render(&doc).unwrap();
+        assert_eq!(html, format!("<p data-ucp-block=\"{}\">Hi</p>\n", id));
+        assert_eq!(render(&doc), "<p>Hi</p>\n");
+    }
+}
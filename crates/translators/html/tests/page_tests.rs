@@ -0,0 +1,156 @@
+//! Parsing tests against real-world page layouts.
+
+use ucm_core::{Block, Content, Document, MediaSource, TranslationWarningCode};
+use ucp_translator_html::{HtmlParser, ParseOutput, CAPTION_KEY};
+
+fn parse(html: &str) -> ParseOutput {
+    HtmlParser::new().parse_with_report(html).unwrap()
+}
+
+fn blocks_of<'a>(doc: &'a Document, content_type: &str) -> Vec<&'a Block> {
+    let mut blocks = Vec::new();
+    collect(doc, &doc.root, content_type, &mut blocks);
+    blocks
+}
+
+/// Blocks in document order
+fn collect<'a>(
+    doc: &'a Document,
+    id: &ucm_core::BlockId,
+    content_type: &str,
+    out: &mut Vec<&'a Block>,
+) {
+    for child in doc.children(id) {
+        let block = doc.get_block(child).unwrap();
+        if block.content_type() == content_type {
+            out.push(block);
+        }
+        collect(doc, child, content_type, out);
+    }
+}
+
+fn caption(block: &Block) -> Option<&str> {
+    block
+        .metadata
+        .custom
+        .get(CAPTION_KEY)
+        .and_then(|v| v.as_str())
+}
+
+#[test]
+fn test_docs_page_code_blocks() {
+    let output = parse(include_str!("fixtures/pages/docs_page.html"));
+    assert!(output.report.is_clean());
+
+    let languages: Vec<&str> = blocks_of(&output.document, "code")
+        .iter()
+        .map(|b| match &b.content {
+            Content::Code(code) => code.language.as_str(),
+            _ => unreachable!(),
+        })
+        .collect();
+    // Language comes from the <code> class, then the <pre> class
+    assert_eq!(languages, vec!["bash", "python", "rust", "text"]);
+
+    let python = &blocks_of(&output.document, "code")[1];
+    assert_eq!(
+        python.content,
+        Content::code(
+            "python",
+            "from widget import Client\n\nclient = Client(token=\"...\")\nclient.render(\"hello\")"
+        )
+    );
+}
+
+#[test]
+fn test_docs_page_images() {
+    let output = parse(include_str!("fixtures/pages/docs_page.html"));
+    let media = blocks_of(&output.document, "media");
+    assert_eq!(media.len(), 2);
+
+    let Content::Media(screenshot) = &media[0].content else {
+        panic!("expected media");
+    };
+    assert_eq!(
+        screenshot.source,
+        MediaSource::Url("/images/dashboard.png".into())
+    );
+    assert_eq!(screenshot.alt_text.as_deref(), Some("Widget dashboard"));
+    assert_eq!(
+        caption(media[0]),
+        Some("The dashboard after your first **deploy**.")
+    );
+
+    // Images inside a paragraph are kept alongside its text
+    assert_eq!(caption(media[1]), None);
+    assert!(blocks_of(&output.document, "text")
+        .iter()
+        .any(|b| b.content == Content::text("Builds run on every commit.")));
+}
+
+#[test]
+fn test_wiki_page_tables() {
+    let output = parse(include_str!("fixtures/pages/wiki_page.html"));
+    let tables = blocks_of(&output.document, "table");
+    assert_eq!(tables.len(), 2);
+
+    let Content::Table(history) = &tables[0].content else {
+        panic!("expected table");
+    };
+    let columns: Vec<&str> = history.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(columns, vec!["Version", "Date", "Notes"]);
+    assert_eq!(history.rows.len(), 4);
+    assert_eq!(caption(tables[0]), Some("Release history"));
+
+    // Without a <th> row the columns keep generated names
+    let Content::Table(syntax) = &tables[1].content else {
+        panic!("expected table");
+    };
+    assert_eq!(syntax.columns[0].name, "col0");
+    assert_eq!(syntax.rows.len(), 2);
+}
+
+#[test]
+fn test_wiki_page_nested_table_degrades_to_text() {
+    let output = parse(include_str!("fixtures/pages/wiki_page.html"));
+    let warnings = &output.report.warnings;
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].code, TranslationWarningCode::FlattenedStructure);
+    assert!(warnings[0].block_id.is_some());
+
+    assert!(blocks_of(&output.document, "text")
+        .iter()
+        .any(|b| b.content
+            == Content::text("Rust Paradigms Concurrent Functional First appeared 2015")));
+}
+
+#[test]
+fn test_wiki_page_figures_and_linked_images() {
+    let output = parse(include_str!("fixtures/pages/wiki_page.html"));
+    let media = blocks_of(&output.document, "media");
+    assert_eq!(media.len(), 2);
+    assert_eq!(caption(media[0]), Some("Ferris, the unofficial mascot"));
+
+    let Content::Media(logo) = &media[1].content else {
+        panic!("expected media");
+    };
+    assert_eq!(logo.alt_text.as_deref(), Some("Rust logo"));
+}
+
+#[test]
+fn test_dropped_elements_are_reported() {
+    let html = "\u{feff}<body><p>Watch this:</p><video src=\"a.mp4\"></video><img alt=\"no source\"><iframe src=\"https://example.com\"></iframe></body>";
+    let output = parse(html);
+    let codes: Vec<_> = output.report.warnings.iter().map(|w| w.code).collect();
+    assert_eq!(
+        codes,
+        vec![
+            TranslationWarningCode::EncodingFixed,
+            TranslationWarningCode::DroppedElement,
+            TranslationWarningCode::DroppedElement,
+            TranslationWarningCode::DroppedElement,
+        ]
+    );
+    assert!(output.report.warnings[1].message.contains("<video>"));
+    assert_eq!(output.document.block_count(), 2);
+}
@@ -0,0 +1,110 @@
+//! Round-trip tests: parse_html -> render_html -> parse_html.
+
+use ucm_core::{BlockId, Content, Document};
+use ucp_translator_html::{
+    parse_html, render_html, render_html_with_options, HtmlRenderOptions, CAPTION_KEY,
+};
+
+const ROUNDTRIP_CORPUS: &[(&str, &str)] = &[
+    ("article", include_str!("fixtures/roundtrip/article.html")),
+    (
+        "code_and_tables",
+        include_str!("fixtures/roundtrip/code_and_tables.html"),
+    ),
+    ("media", include_str!("fixtures/roundtrip/media.html")),
+    (
+        "nested_sections",
+        include_str!("fixtures/roundtrip/nested_sections.html"),
+    ),
+];
+
+/// A block's role and content, with its children in order
+#[derive(Debug, PartialEq)]
+struct Shape {
+    role: Option<String>,
+    content: Content,
+    caption: Option<serde_json::Value>,
+    children: Vec<Shape>,
+}
+
+fn shape(doc: &Document, id: &BlockId) -> Vec<Shape> {
+    doc.children(id)
+        .iter()
+        .map(|child| {
+            let block = doc.get_block(child).unwrap();
+            Shape {
+                role: block
+                    .metadata
+                    .semantic_role
+                    .as_ref()
+                    .map(|r| r.category.as_str().to_string()),
+                content: block.content.clone(),
+                caption: block.metadata.custom.get(CAPTION_KEY).cloned(),
+                children: shape(doc, child),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_roundtrip_corpus_is_structurally_equal() {
+    for (name, html) in ROUNDTRIP_CORPUS {
+        let doc = parse_html(html).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        assert!(doc.block_count() > 1, "{} should parse into blocks", name);
+
+        let rendered = render_html(&doc).expect("Failed to render");
+        let reparsed = parse_html(&rendered).expect("Failed to reparse");
+        assert_eq!(
+            shape(&doc, &doc.root),
+            shape(&reparsed, &reparsed.root),
+            "{} should roundtrip, rendered:\n{}",
+            name,
+            rendered
+        );
+    }
+}
+
+#[test]
+fn test_render_is_stable_after_first_roundtrip() {
+    for (name, html) in ROUNDTRIP_CORPUS {
+        let first = render_html(&parse_html(html).unwrap()).unwrap();
+        let second = render_html(&parse_html(&first).unwrap()).unwrap();
+        assert_eq!(first, second, "{} should render identically", name);
+    }
+}
+
+#[test]
+fn test_block_ids_map_elements_to_blocks() {
+    let doc = parse_html(include_str!("fixtures/roundtrip/article.html")).unwrap();
+    let options = HtmlRenderOptions {
+        emit_block_ids: true,
+    };
+    let html = render_html_with_options(&doc, options).unwrap();
+
+    for id in doc.blocks.keys().filter(|id| **id != doc.root) {
+        let attr = format!("data-ucp-block=\"{}\"", id);
+        assert_eq!(html.matches(&attr).count(), 1, "missing {}", attr);
+    }
+    // Attributes do not change what the HTML parses into
+    let reparsed = parse_html(&html).unwrap();
+    assert_eq!(shape(&doc, &doc.root), shape(&reparsed, &reparsed.root));
+}
+
+#[test]
+fn test_semantic_elements() {
+    let doc = parse_html(include_str!("fixtures/roundtrip/code_and_tables.html")).unwrap();
+    let html = render_html(&doc).unwrap();
+
+    assert!(html.contains("<section>\n<h1>Reference</h1>\n"));
+    assert!(html.contains(
+        "<pre><code class=\"language-rust\">fn main() {\n    if a &lt; b &amp;&amp; c &gt; d {"
+    ));
+    assert!(html.contains("<thead>\n<tr><th>Name</th><th>Type</th></tr>\n</thead>"));
+    assert!(html.contains("<tr><td>count</td><td>number</td></tr>"));
+
+    let doc = parse_html(include_str!("fixtures/roundtrip/media.html")).unwrap();
+    let html = render_html(&doc).unwrap();
+    assert!(
+        html.contains("<figure><img src=\"https://example.com/cat.jpg\" alt=\"A cat\"><figcaption>A sleepy cat</figcaption></figure>")
+    );
+}
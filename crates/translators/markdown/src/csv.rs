@@ -0,0 +1,399 @@
+//! CSV/TSV import into table content.
+
+use crate::{Result, TranslatorError};
+use ucm_core::{Content, Table};
+
+/// Delimiters tried when none is given
+const CANDIDATE_DELIMITERS: [char; 3] = [',', '\t', ';'];
+
+/// Records inspected when sniffing the delimiter and header
+const SNIFF_RECORDS: usize = 20;
+
+/// Options controlling CSV parsing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Field delimiter, sniffed from comma, tab and semicolon when unset
+    pub delimiter: Option<char>,
+    /// Whether the first row is a header, detected when unset
+    pub header: Option<bool>,
+    /// Maximum number of rows (including the header) accepted
+    pub max_rows: usize,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            header: None,
+            max_rows: 10_000,
+        }
+    }
+}
+
+impl CsvOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = Some(delimiter);
+        self
+    }
+
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = max_rows;
+        self
+    }
+}
+
+/// Parse CSV (or TSV) data into table content.
+///
+/// The first row is always kept as `rows[0]`; when it is a header its cells
+/// also name the columns.
+pub fn parse_csv(data: &str, options: CsvOptions) -> Result<Content> {
+    let data = data.strip_prefix('\u{feff}').unwrap_or(data);
+    let delimiter = options.delimiter.unwrap_or_else(|| sniff_delimiter(data));
+    let records = read_records(data, delimiter, options.max_rows)?;
+
+    let Some((first, _)) = records.first() else {
+        return Ok(Content::table(Vec::new()));
+    };
+    let width = first.len();
+    for (row, (fields, line)) in records.iter().enumerate() {
+        if fields.len() != width {
+            return Err(TranslatorError::ParseError {
+                line: *line,
+                message: format!(
+                    "row {} has {} fields, expected {}",
+                    row + 1,
+                    fields.len(),
+                    width
+                ),
+            });
+        }
+    }
+
+    let rows: Vec<Vec<String>> = records.into_iter().map(|(fields, _)| fields).collect();
+    let header = options.header.unwrap_or_else(|| looks_like_header(&rows));
+    let mut content = Content::table(rows);
+    if header {
+        if let Content::Table(Table { columns, rows, .. }) = &mut content {
+            for (column, name) in columns.iter_mut().zip(&rows[0].cells) {
+                if let ucm_core::Cell::Text(name) = name {
+                    if !name.is_empty() {
+                        column.name = name.clone();
+                    }
+                }
+            }
+        }
+    }
+    Ok(content)
+}
+
+/// Reads records of fields, each with the line it starts on
+struct Records<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    delimiter: char,
+    line: usize,
+}
+
+impl<'a> Records<'a> {
+    fn new(data: &'a str, delimiter: char) -> Self {
+        Self {
+            chars: data.chars().peekable(),
+            delimiter,
+            line: 1,
+        }
+    }
+
+    fn read_record(&mut self) -> Result<Vec<String>> {
+        let start_line = self.line;
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut at_field_start = true;
+
+        loop {
+            match self.chars.next() {
+                None => {
+                    if quoted {
+                        return Err(TranslatorError::ParseError {
+                            line: start_line,
+                            message: "unterminated quoted field".to_string(),
+                        });
+                    }
+                    break;
+                }
+                Some('"') if quoted => {
+                    if self.chars.peek() == Some(&'"') {
+                        self.chars.next();
+                        field.push('"');
+                    } else {
+                        quoted = false;
+                    }
+                }
+                Some('"') if at_field_start => {
+                    quoted = true;
+                    at_field_start = false;
+                }
+                Some(c) if quoted => {
+                    if c == '\n' {
+                        self.line += 1;
+                    }
+                    field.push(c);
+                }
+                Some(c) if c == self.delimiter => {
+                    fields.push(std::mem::take(&mut field));
+                    at_field_start = true;
+                }
+                Some('\r') if self.chars.peek() == Some(&'\n') => {}
+                Some('\n') => {
+                    self.line += 1;
+                    break;
+                }
+                Some(c) => {
+                    field.push(c);
+                    at_field_start = false;
+                }
+            }
+        }
+        fields.push(field);
+        Ok(fields)
+    }
+}
+
+impl Iterator for Records<'_> {
+    type Item = Result<(Vec<String>, usize)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.chars.peek().is_some() {
+            let line = self.line;
+            match self.read_record() {
+                // Blank lines separate nothing
+                Ok(fields) if fields.len() == 1 && fields[0].is_empty() => continue,
+                Ok(fields) => return Some(Ok((fields, line))),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        None
+    }
+}
+
+fn read_records(data: &str, delimiter: char, max_rows: usize) -> Result<Vec<(Vec<String>, usize)>> {
+    let mut records = Vec::new();
+    for record in Records::new(data, delimiter) {
+        let (fields, line) = record?;
+        if records.len() == max_rows {
+            return Err(TranslatorError::ParseError {
+                line,
+                message: format!("row limit of {} exceeded", max_rows),
+            });
+        }
+        records.push((fields, line));
+    }
+    Ok(records)
+}
+
+/// Pick the candidate delimiter that splits the leading records most evenly,
+/// falling back to the one that splits the first record the most
+fn sniff_delimiter(data: &str) -> char {
+    let mut consistent = (',', 1);
+    let mut widest = (',', 1);
+    for delimiter in CANDIDATE_DELIMITERS {
+        let widths: Vec<usize> = Records::new(data, delimiter)
+            .take(SNIFF_RECORDS)
+            .map_while(|record| record.ok())
+            .map(|(fields, _)| fields.len())
+            .collect();
+        let Some(&width) = widths.first() else {
+            continue;
+        };
+        if width > consistent.1 && widths.iter().all(|w| *w == width) {
+            consistent = (delimiter, width);
+        }
+        if width > widest.1 {
+            widest = (delimiter, width);
+        }
+    }
+    if consistent.1 > 1 {
+        consistent.0
+    } else {
+        widest.0
+    }
+}
+
+/// A first row of distinct, non-empty, non-numeric cells is taken as a header,
+/// unless it looks just like the rows below it
+fn looks_like_header(rows: &[Vec<String>]) -> bool {
+    let Some(first) = rows.first() else {
+        return false;
+    };
+    let mut seen = std::collections::HashSet::new();
+    if first
+        .iter()
+        .any(|cell| cell.trim().is_empty() || is_numeric(cell) || !seen.insert(cell.trim()))
+    {
+        return false;
+    }
+
+    let body: Vec<&Vec<String>> = rows.iter().skip(1).take(SNIFF_RECORDS).collect();
+    if body.is_empty() {
+        return true;
+    }
+    // A numeric column under a text cell is the strongest signal
+    let numeric_column = (0..first.len()).any(|col| {
+        body.iter()
+            .all(|row| is_numeric(&row[col]) || row[col].is_empty())
+    });
+    // Otherwise the header should not repeat values found in its column
+    let repeats = (0..first.len()).any(|col| body.iter().any(|row| row[col] == first[col]));
+    numeric_column || !repeats
+}
+
+fn is_numeric(cell: &str) -> bool {
+    let cell = cell.trim();
+    !cell.is_empty() && cell.parse::<f64>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ucm_core::Cell;
+
+    fn table(content: Content) -> Table {
+        match content {
+            Content::Table(table) => table,
+            other => panic!("expected table, got {:?}", other),
+        }
+    }
+
+    fn texts(table: &Table) -> Vec<Vec<String>> {
+        table
+            .rows
+            .iter()
+            .map(|row| {
+                row.cells
+                    .iter()
+                    .map(|cell| match cell {
+                        Cell::Text(s) => s.clone(),
+                        other => panic!("expected text cell, got {:?}", other),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_quoted_commas_and_quotes() {
+        let csv = "name,motto\n\"Smith, J.\",\"Say \"\"hi\"\"\"\n";
+        let table = table(parse_csv(csv, CsvOptions::default()).unwrap());
+        assert_eq!(
+            texts(&table),
+            vec![
+                vec!["name".to_string(), "motto".to_string()],
+                vec!["Smith, J.".to_string(), "Say \"hi\"".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_embedded_newlines() {
+        let csv = "id,note\r\n1,\"first\r\nsecond\"\r\n2,plain\r\n";
+        let table = table(parse_csv(csv, CsvOptions::default()).unwrap());
+        assert_eq!(table.row_count(), 3);
+        assert_eq!(texts(&table)[1][1], "first\r\nsecond");
+        assert_eq!(texts(&table)[2], vec!["2", "plain"]);
+    }
+
+    #[test]
+    fn test_ragged_rows_report_row_and_line() {
+        let csv = "a,b\n1,\"x\ny\"\n3,4,5\n";
+        let err = parse_csv(csv, CsvOptions::default()).unwrap_err();
+        match err {
+            TranslatorError::ParseError { line, message } => {
+                assert_eq!(line, 4);
+                assert_eq!(message, "row 3 has 3 fields, expected 2");
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_delimiter_sniffing() {
+        let tsv = "city\tcountry\nParis, TX\tUS\n";
+        let table = table(parse_csv(tsv, CsvOptions::default()).unwrap());
+        assert_eq!(texts(&table)[1], vec!["Paris, TX", "US"]);
+
+        let semi = "a;b;c\n1;2;3\n";
+        assert_eq!(table_width(semi), 3);
+        assert_eq!(table_width("a,b\n1,2\n"), 2);
+    }
+
+    fn table_width(csv: &str) -> usize {
+        table(parse_csv(csv, CsvOptions::default()).unwrap()).column_count()
+    }
+
+    #[test]
+    fn test_header_detection() {
+        let with_header =
+            table(parse_csv("item,qty\napple,3\npear,5\n", CsvOptions::default()).unwrap());
+        let names: Vec<&str> = with_header
+            .columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["item", "qty"]);
+
+        let numbers = table(parse_csv("1,2\n3,4\n", CsvOptions::default()).unwrap());
+        assert_eq!(numbers.columns[0].name, "col0");
+
+        let forced = table(parse_csv("1,2\n3,4\n", CsvOptions::new().with_header(true)).unwrap());
+        assert_eq!(forced.columns[1].name, "2");
+    }
+
+    #[test]
+    fn test_row_limit() {
+        let err = parse_csv("a\n1\n2\n3\n", CsvOptions::new().with_max_rows(3)).unwrap_err();
+        assert!(err.to_string().contains("row limit of 3 exceeded"));
+        assert!(parse_csv("a\n1\n2\n", CsvOptions::new().with_max_rows(3)).is_ok());
+    }
+
+    #[test]
+    fn test_unterminated_quote() {
+        let err = parse_csv("a,b\n1,\"open\n", CsvOptions::default()).unwrap_err();
+        assert!(matches!(err, TranslatorError::ParseError { line: 2, .. }));
+    }
+
+    #[test]
+    fn test_blank_lines_and_bom_are_skipped() {
+        let table = table(parse_csv("\u{feff}a,b\n\n1,2\n\n", CsvOptions::default()).unwrap());
+        assert_eq!(texts(&table), vec![vec!["a", "b"], vec!["1", "2"]]);
+    }
+
+    #[test]
+    fn test_renders_as_gfm_table() {
+        let csv = "expr,meaning\n\"a|b\",\"either\nor\"\n,empty\n";
+        let mut doc = ucm_core::Document::create();
+        let root = doc.root;
+        let content = parse_csv(csv, CsvOptions::default()).unwrap();
+        doc.add_block(ucm_core::Block::new(content.clone(), Some("table")), &root)
+            .unwrap();
+
+        let markdown = crate::render_markdown(&doc).unwrap();
+        assert_eq!(
+            markdown,
+            "| expr | meaning |\n| --- | --- |\n| a\\|b | either<br>or |\n|  | empty |\n"
+        );
+
+        let reparsed = crate::parse_markdown(&markdown).unwrap();
+        let block = reparsed
+            .get_block(&reparsed.children(&reparsed.root)[0])
+            .unwrap();
+        assert_eq!(texts(&table(block.content.clone())), texts(&table(content)));
+    }
+}
@@ -1,18 +1,42 @@
 //! Parse Markdown into UCM documents.
 
-use crate::{Result, TranslatorError};
-use ucm_core::{Block, Content, Document};
+use crate::front_matter;
+use crate::inline::{parse_inline, render_inline};
+use crate::references::{self, FootnoteDefinition, ReferenceLink};
+use crate::{
+    Result, TranslatorError, CODE_LANGUAGE_KEY, LIST_MARKER_KEY, RAW_MARKDOWN_KEY, TASK_KEY,
+};
+use ucm_core::{
+    Block, Content, DiagramContent, DiagramDialect, DiffFormat, Document, InlineSpan, Math,
+    SourceSpan, TranslationReport, TranslationWarning, TranslationWarningCode,
+};
+
+/// Options controlling how unsupported syntax is handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ParseOptions {
+    /// Error on unsupported constructs instead of preserving them as raw blocks
+    pub strict: bool,
+}
+
+/// A parsed document together with what could not be carried over faithfully
+#[derive(Debug, Clone)]
+pub struct ParseOutput {
+    pub document: Document,
+    pub report: TranslationReport,
+}
 
 /// Markdown parser that converts to UCM
 #[derive(Debug, Clone)]
 pub struct MarkdownParser {
     preserve_raw: bool,
+    options: ParseOptions,
 }
 
 impl MarkdownParser {
     pub fn new() -> Self {
         Self {
             preserve_raw: false,
+            options: ParseOptions::default(),
         }
     }
 
@@ -21,17 +45,56 @@ impl MarkdownParser {
         self
     }
 
+    /// Set the parse options
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
     pub fn parse(&self, markdown: &str) -> Result<Document> {
+        self.parse_with_report(markdown)
+            .map(|output| output.document)
+    }
+
+    /// Parse, also reporting malformed front matter, raw blocks and other
+    /// lossy conversions
+    pub fn parse_with_report(&self, markdown: &str) -> Result<ParseOutput> {
         use ucm_core::BlockId;
 
         let mut doc = Document::create();
         let root = doc.root;
+        let mut report = TranslationReport::new();
+        let markdown = match markdown.strip_prefix('\u{feff}') {
+            Some(rest) => {
+                report.push(
+                    TranslationWarning::new(
+                        TranslationWarningCode::EncodingFixed,
+                        "byte order mark removed",
+                    )
+                    .with_span(SourceSpan::line(1)),
+                );
+                rest
+            }
+            None => markdown,
+        };
         let lines: Vec<&str> = markdown.lines().collect();
         let mut i = 0;
 
+        if let Some(front_matter) = front_matter::extract(&lines) {
+            if let Some(message) = front_matter::apply(&front_matter, &mut doc.metadata) {
+                report.push(
+                    TranslationWarning::new(TranslationWarningCode::InvalidMetadata, message)
+                        .with_span(SourceSpan::lines(1, front_matter.lines)),
+                );
+            }
+            i = front_matter.lines;
+        }
+
         // Track heading hierarchy: heading_stack[level-1] = BlockId of that level's heading
         // This allows H2 to be child of H1, H3 to be child of H2, etc.
         let mut heading_stack: Vec<Option<BlockId>> = vec![None; 6];
+        let mut footnotes: Vec<FootnoteDefinition> = Vec::new();
+        let mut reference_links: Vec<ReferenceLink> = Vec::new();
 
         while i < lines.len() {
             let line = lines[i];
@@ -42,6 +105,52 @@ impl MarkdownParser {
                 continue;
             }
 
+            // Footnote definitions are gathered into the footnotes section
+            if references::footnote_label(line).is_some() {
+                let consumed = definition_span(&lines[i..], false);
+                footnotes.extend(references::parse_footnote(&lines[i..i + consumed]));
+                i += consumed;
+                continue;
+            }
+
+            // Reference-link definitions live in document metadata
+            if let Some(link) = references::parse_reference_link(line) {
+                reference_links.push(link);
+                i += 1;
+                continue;
+            }
+
+            // Unsupported syntax - kept verbatim so rendering is lossless
+            if let Some(kind) = self.unsupported_kind(&lines[i..]) {
+                if self.options.strict {
+                    return Err(TranslatorError::ParseError {
+                        line: i + 1,
+                        message: format!("unsupported construct: {}", kind.name()),
+                    });
+                }
+                let current_parent = heading_stack.iter().rev().find_map(|h| *h).unwrap_or(root);
+                let consumed = self.raw_span(&lines[i..], kind);
+                let mut block =
+                    Block::new(Content::markdown(lines[i..i + consumed].join("\n")), None);
+                block
+                    .metadata
+                    .custom
+                    .insert(RAW_MARKDOWN_KEY.to_string(), true.into());
+                let id = doc
+                    .add_block(block, &current_parent)
+                    .map_err(|e| TranslatorError::InvalidStructure(e.to_string()))?;
+                report.push(
+                    TranslationWarning::new(
+                        TranslationWarningCode::PreservedRaw,
+                        format!("{} kept as raw markdown", kind.name()),
+                    )
+                    .with_span(SourceSpan::lines(i + 1, i + consumed))
+                    .with_block(id),
+                );
+                i += consumed;
+                continue;
+            }
+
             // Heading - creates hierarchical structure
             if let Some(heading) = self.parse_heading(line) {
                 let block = Block::new(Content::text(&heading.text), Some(&heading.role));
@@ -82,10 +191,45 @@ impl MarkdownParser {
             // Find current parent (most recent heading or root)
             let current_parent = heading_stack.iter().rev().find_map(|h| *h).unwrap_or(root);
 
-            // Code block
+            // Code block (a `math` fence is display math, diagram and diff fences
+            // are diagrams and diffs)
             if line.starts_with("```") {
                 let (code_block, consumed) = self.parse_code_block(&lines[i..])?;
-                let block = Block::new(code_block, Some("code"));
+                let block = match code_block {
+                    Content::Code(code) if code.language == "math" => {
+                        Block::new(Content::Math(Math::latex(code.source).display()), None)
+                    }
+                    Content::Code(code) => {
+                        let fenced = !line.trim_start_matches('`').trim().is_empty();
+                        let language = fenced.then(|| {
+                            serde_json::json!({
+                                "raw": code.language,
+                                "canonical": code.canonical_language(),
+                            })
+                        });
+                        let mut block = Block::new(Content::Code(code), Some("code"));
+                        if let Some(language) = language {
+                            block
+                                .metadata
+                                .custom
+                                .insert(CODE_LANGUAGE_KEY.to_string(), language);
+                        }
+                        block
+                    }
+                    diagram @ (Content::Diagram(_) | Content::Diff(_) | Content::GeoJson(_)) => {
+                        Block::new(diagram, None)
+                    }
+                    code_block => Block::new(code_block, Some("code")),
+                };
+                doc.add_block(block, &current_parent)
+                    .map_err(|e| TranslatorError::InvalidStructure(e.to_string()))?;
+                i += consumed;
+                continue;
+            }
+
+            // Display math
+            if let Some((expression, consumed)) = parse_display_math(&lines[i..]) {
+                let block = Block::new(Content::Math(Math::latex(expression).display()), None);
                 doc.add_block(block, &current_parent)
                     .map_err(|e| TranslatorError::InvalidStructure(e.to_string()))?;
                 i += consumed;
@@ -95,9 +239,14 @@ impl MarkdownParser {
             // List item
             if self.is_list_item(line) {
                 let (list_content, consumed) = self.parse_list(&lines[i..]);
-                let block = Block::new(Content::text(&list_content), Some("list"));
-                doc.add_block(block, &current_parent)
-                    .map_err(|e| TranslatorError::InvalidStructure(e.to_string()))?;
+                let items: Vec<ListItem> = list_content.lines().map(ListItem::parse).collect();
+                if items.iter().any(|item| item.checked.is_some()) {
+                    self.add_task_list(&mut doc, &current_parent, items)?;
+                } else {
+                    let block = Block::new(Content::text(&list_content), Some("list"));
+                    doc.add_block(block, &current_parent)
+                        .map_err(|e| TranslatorError::InvalidStructure(e.to_string()))?;
+                }
                 i += consumed;
                 continue;
             }
@@ -116,21 +265,71 @@ impl MarkdownParser {
             if self.is_table_start(line, lines.get(i + 1).copied()) {
                 let (table, consumed) = self.parse_table(&lines[i..])?;
                 let block = Block::new(table, Some("table"));
-                doc.add_block(block, &current_parent)
+                let id = doc
+                    .add_block(block, &current_parent)
                     .map_err(|e| TranslatorError::InvalidStructure(e.to_string()))?;
+                if lines[i..i + consumed]
+                    .iter()
+                    .any(|l| is_aligned_separator(l))
+                {
+                    report.push(
+                        TranslationWarning::new(
+                            TranslationWarningCode::DroppedElement,
+                            "table column alignment dropped",
+                        )
+                        .with_span(SourceSpan::lines(i + 1, i + consumed))
+                        .with_block(id),
+                    );
+                }
                 i += consumed;
                 continue;
             }
 
-            // Regular paragraph
+            // Regular paragraph, or a lone inline math span
             let (para, consumed) = self.parse_paragraph(&lines[i..]);
-            let block = Block::new(Content::text(&para), Some("paragraph"));
+            let block = match inline_math(&para) {
+                Some(expression) => Block::new(Content::Math(Math::latex(expression)), None),
+                None => Block::new(paragraph_content(&para), Some("paragraph")),
+            };
             doc.add_block(block, &current_parent)
                 .map_err(|e| TranslatorError::InvalidStructure(e.to_string()))?;
             i += consumed;
         }
 
-        Ok(doc)
+        references::attach_footnotes(&mut doc, footnotes)?;
+        references::store_reference_links(&mut doc, &reference_links)?;
+
+        Ok(ParseOutput {
+            document: doc,
+            report,
+        })
+    }
+
+    fn unsupported_kind(&self, lines: &[&str]) -> Option<Unsupported> {
+        let line = lines[0];
+        if line.trim_start().starts_with("<!--") {
+            return Some(Unsupported::HtmlComment);
+        }
+        let starts_block = line.starts_with('#')
+            || line.starts_with("```")
+            || line.starts_with('>')
+            || line.starts_with('|')
+            || self.is_list_item(line);
+        if !starts_block && lines.get(1).is_some_and(|next| next.starts_with(": ")) {
+            return Some(Unsupported::DefinitionList);
+        }
+        None
+    }
+
+    /// Number of lines covered by an unsupported construct
+    fn raw_span(&self, lines: &[&str], kind: Unsupported) -> usize {
+        match kind {
+            Unsupported::HtmlComment => lines
+                .iter()
+                .position(|l| l.contains("-->"))
+                .map_or(lines.len(), |end| end + 1),
+            Unsupported::DefinitionList => definition_span(lines, true),
+        }
     }
 
     fn parse_heading(&self, line: &str) -> Option<Heading> {
@@ -171,6 +370,27 @@ impl MarkdownParser {
         }
 
         let code = code_lines.join("\n");
+        if let Some(dialect) = DiagramDialect::from_fence_tag(&lang) {
+            return Ok((Content::Diagram(DiagramContent::new(dialect, code)), i));
+        }
+        if lang == "diff" {
+            // `git diff` output keeps its headers, so it can be told apart
+            let format = if code.starts_with("diff --git ") || code.starts_with("From ") {
+                DiffFormat::GitPatch
+            } else {
+                DiffFormat::Unified
+            };
+            return Ok((Content::diff(format, code), i));
+        }
+        if lang == "geojson" {
+            // Fences that do not hold valid GeoJSON stay plain code
+            let geojson = serde_json::from_str(&code)
+                .ok()
+                .and_then(|value| Content::new_geojson(value).ok());
+            if let Some(content) = geojson {
+                return Ok((content, i));
+            }
+        }
         Ok((Content::code(&lang, &code), i))
     }
 
@@ -202,6 +422,46 @@ impl MarkdownParser {
         (items.join("\n"), i.max(1))
     }
 
+    /// Add a list containing task items as one block per item.
+    ///
+    /// Nested items become children of the item they are indented under.
+    fn add_task_list(
+        &self,
+        doc: &mut Document,
+        parent: &ucm_core::BlockId,
+        items: Vec<ListItem>,
+    ) -> Result<()> {
+        let list_id = doc
+            .add_block(Block::new(Content::text(""), Some("list")), parent)
+            .map_err(|e| TranslatorError::InvalidStructure(e.to_string()))?;
+
+        let mut open: Vec<(usize, ucm_core::BlockId)> = Vec::new();
+        for item in items {
+            while open
+                .last()
+                .is_some_and(|(indent, _)| *indent >= item.indent)
+            {
+                open.pop();
+            }
+            let item_parent = open.last().map_or(list_id, |(_, id)| *id);
+
+            let mut block = Block::new(Content::text(&item.text), None);
+            let custom = &mut block.metadata.custom;
+            custom.insert(LIST_MARKER_KEY.to_string(), item.marker.into());
+            if let Some(checked) = item.checked {
+                custom.insert(
+                    TASK_KEY.to_string(),
+                    serde_json::json!({ "checked": checked }),
+                );
+            }
+            let id = doc
+                .add_block(block, &item_parent)
+                .map_err(|e| TranslatorError::InvalidStructure(e.to_string()))?;
+            open.push((item.indent, id));
+        }
+        Ok(())
+    }
+
     fn parse_blockquote(&self, lines: &[&str]) -> (String, usize) {
         let mut quote_lines = Vec::new();
         let mut i = 0;
@@ -232,21 +492,12 @@ impl MarkdownParser {
 
         while i < lines.len() && lines[i].contains('|') {
             // Skip separator row
-            if lines[i]
-                .chars()
-                .all(|c| c == '|' || c == '-' || c == ':' || c == ' ')
-            {
+            if is_separator(lines[i]) {
                 i += 1;
                 continue;
             }
 
-            let cells: Vec<String> = lines[i]
-                .split('|')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect();
-
-            rows.push(cells);
+            rows.push(split_table_row(lines[i]));
             i += 1;
         }
 
@@ -264,6 +515,8 @@ impl MarkdownParser {
                 || line.starts_with("```")
                 || line.starts_with('>')
                 || self.is_list_item(line)
+                || (i > 0 && references::footnote_label(line).is_some())
+                || (i > 0 && self.unsupported_kind(&lines[i..]).is_some())
             {
                 break;
             }
@@ -282,12 +535,204 @@ impl Default for MarkdownParser {
     }
 }
 
+/// A single line of a list
+struct ListItem {
+    indent: usize,
+    marker: String,
+    checked: Option<bool>,
+    text: String,
+}
+
+impl ListItem {
+    fn parse(line: &str) -> Self {
+        let trimmed = line.trim_start();
+        let indent = line[..line.len() - trimmed.len()]
+            .chars()
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum();
+        let (marker, rest) = trimmed.split_once(' ').unwrap_or((trimmed, ""));
+
+        let checkbox = |mark: &str| {
+            rest.strip_prefix(mark)
+                .filter(|text| text.is_empty() || text.starts_with(' '))
+                .map(|text| text.strip_prefix(' ').unwrap_or(text))
+        };
+        let (checked, text) = if let Some(text) = checkbox("[ ]") {
+            (Some(false), text)
+        } else if let Some(text) = checkbox("[x]").or_else(|| checkbox("[X]")) {
+            (Some(true), text)
+        } else {
+            (None, rest)
+        };
+
+        Self {
+            indent,
+            marker: marker.to_string(),
+            checked,
+            text: text.to_string(),
+        }
+    }
+}
+
+/// Markdown constructs the parser does not model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unsupported {
+    HtmlComment,
+    DefinitionList,
+}
+
+impl Unsupported {
+    fn name(self) -> &'static str {
+        match self {
+            Unsupported::HtmlComment => "HTML comment",
+            Unsupported::DefinitionList => "definition list",
+        }
+    }
+}
+
+/// A table's `|---|:---:|` row
+fn is_separator(line: &str) -> bool {
+    line.chars()
+        .all(|c| c == '|' || c == '-' || c == ':' || c == ' ')
+}
+
+/// A separator row that sets column alignment, which tables do not keep
+fn is_aligned_separator(line: &str) -> bool {
+    is_separator(line) && line.contains(':')
+}
+
+/// Number of lines in a footnote or definition list starting at `lines[0]`:
+/// lazy continuation lines, then indented blocks after blank lines
+fn definition_span(lines: &[&str], definition_list: bool) -> usize {
+    let mut end = 1;
+    let mut i = 1;
+    while i < lines.len() {
+        let line = lines[i];
+        if line.trim().is_empty() {
+            i += 1;
+            continue;
+        }
+        let continues = if i == end {
+            !line.starts_with('#')
+                && !line.starts_with("```")
+                && references::footnote_label(line).is_none()
+        } else {
+            // After a blank line, only indented text or another
+            // definition belongs to the construct
+            line.starts_with("    ")
+                || line.starts_with('\t')
+                || (definition_list && line.starts_with(": "))
+        };
+        if !continues {
+            break;
+        }
+        i += 1;
+        end = i;
+    }
+    end
+}
+
 struct Heading {
     level: usize,
     text: String,
     role: String,
 }
 
+/// A `$$ ... $$` block starting at `lines[0]`: the expression and lines consumed.
+///
+/// The closing `$$` must end its line and come before a blank line, so prose
+/// that merely starts with `$$` stays a paragraph.
+fn parse_display_math(lines: &[&str]) -> Option<(String, usize)> {
+    let rest = lines[0].trim().strip_prefix("$$")?;
+    if let Some(end) = rest.find("$$") {
+        let expression = rest[..end].trim();
+        return (end + 2 == rest.len() && !expression.is_empty())
+            .then(|| (expression.to_string(), 1));
+    }
+
+    let mut body = Vec::new();
+    if !rest.trim().is_empty() {
+        body.push(rest.trim_start());
+    }
+    for (i, line) in lines.iter().enumerate().skip(1) {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            return None;
+        }
+        if let Some(end) = line.find("$$") {
+            if end + 2 != line.len() {
+                return None;
+            }
+            if !line[..end].trim().is_empty() {
+                body.push(&line[..end]);
+            }
+            return Some((body.join("\n"), i + 1));
+        }
+        body.push(line);
+    }
+    None
+}
+
+/// Rich text for a paragraph with inline formatting, as long as it renders
+/// back to the same Markdown; anything else stays plain text
+fn paragraph_content(paragraph: &str) -> Content {
+    let spans = parse_inline(paragraph);
+    let formatted = spans.iter().any(|s| !matches!(s, InlineSpan::Plain(_)));
+    if formatted && render_inline(&spans) == paragraph {
+        Content::rich_text(spans)
+    } else {
+        Content::text(paragraph)
+    }
+}
+
+/// The expression of a paragraph that is exactly one `$...$` span.
+///
+/// Like pandoc, the span must not start or end with whitespace, and any other
+/// `$` inside must be escaped, so `$5 and $10` or `` `$x$` `` stay text.
+fn inline_math(paragraph: &str) -> Option<&str> {
+    let expression = paragraph.strip_prefix('$')?.strip_suffix('$')?;
+    let bytes = expression.as_bytes();
+    let unescaped_dollar = bytes
+        .iter()
+        .enumerate()
+        .any(|(i, &b)| b == b'$' && (i == 0 || bytes[i - 1] != b'\\'));
+    let padded = expression.starts_with(char::is_whitespace)
+        || expression.ends_with(char::is_whitespace)
+        || expression.ends_with('\\');
+    (!expression.is_empty() && !expression.contains('\n') && !unescaped_dollar && !padded)
+        .then_some(expression)
+}
+
+/// Split a GFM table row into cells, honouring `\|` escapes and `<br>` breaks
+fn split_table_row(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = match line.strip_suffix('|') {
+        Some(rest) if !rest.ends_with('\\') => rest,
+        _ => line,
+    };
+
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cell.push('|');
+                chars.next();
+            }
+            '|' => cells.push(std::mem::take(&mut cell)),
+            _ => cell.push(c),
+        }
+    }
+    cells.push(cell);
+
+    cells
+        .into_iter()
+        .map(|cell| cell.trim().replace("<br>", "\n"))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -437,6 +882,26 @@ This is content under section.
         );
     }
 
+    #[test]
+    fn test_code_block_language_is_canonicalized() {
+        let md = "```JS\nlet a = 1;\n```\n\n```\nplain\n```\n";
+        let doc = MarkdownParser::new().parse(md).unwrap();
+
+        let ids = doc.find_blocks_by_language("javascript");
+        assert_eq!(ids.len(), 1);
+        let block = doc.get_block(&ids[0]).unwrap();
+        assert_eq!(
+            block.metadata.custom[CODE_LANGUAGE_KEY],
+            serde_json::json!({"raw": "JS", "canonical": "javascript"})
+        );
+
+        // An unlabelled fence records no language
+        let plain = doc
+            .get_block(&doc.find_blocks_by_language("text")[0])
+            .unwrap();
+        assert!(!plain.metadata.custom.contains_key(CODE_LANGUAGE_KEY));
+    }
+
     #[test]
     fn test_code_block_under_heading() {
         let md = r#"# Code Example
@@ -506,6 +971,41 @@ def hello():
         );
     }
 
+    #[test]
+    fn test_task_list_items() {
+        let md = "- [ ] todo\n  - [x] nested done\n- plain item\n";
+        let doc = MarkdownParser::new().parse(md).unwrap();
+
+        let list = doc.children(&doc.root)[0];
+        let items = doc.children(&list);
+        assert_eq!(items.len(), 2, "Nested item belongs to its parent item");
+
+        let todo = doc.get_block(&items[0]).unwrap();
+        assert_eq!(todo.content, Content::text("todo"));
+        assert_eq!(
+            todo.metadata.custom[TASK_KEY],
+            serde_json::json!({"checked": false})
+        );
+
+        let nested = doc.get_block(&doc.children(&items[0])[0]).unwrap();
+        assert_eq!(nested.content, Content::text("nested done"));
+        assert_eq!(
+            nested.metadata.custom[TASK_KEY],
+            serde_json::json!({"checked": true})
+        );
+
+        let plain = doc.get_block(&items[1]).unwrap();
+        assert_eq!(plain.metadata.custom[LIST_MARKER_KEY], "-");
+        assert!(!plain.metadata.custom.contains_key(TASK_KEY));
+    }
+
+    #[test]
+    fn test_plain_list_stays_single_block() {
+        let md = "- one\n- [link](x)\n";
+        let doc = MarkdownParser::new().parse(md).unwrap();
+        assert_eq!(doc.block_count(), 2);
+    }
+
     #[test]
     fn test_paragraph_semantic_role() {
         let md = r#"# Title
@@ -529,6 +1029,149 @@ This is a paragraph.
         );
     }
 
+    #[test]
+    fn test_formatted_paragraphs_become_rich_text() {
+        let md =
+            "Run `cargo test` **before** pushing.\n\nPlain words.\n\nKeep __this__ as written.\n";
+        let doc = MarkdownParser::new().parse(md).unwrap();
+        let contents: Vec<_> = doc
+            .children(&doc.root)
+            .iter()
+            .map(|id| doc.get_block(id).unwrap().content.clone())
+            .collect();
+
+        assert_eq!(
+            contents[0],
+            Content::rich_text(vec![
+                InlineSpan::plain("Run "),
+                InlineSpan::Code("cargo test".into()),
+                InlineSpan::plain(" "),
+                InlineSpan::Bold(vec![InlineSpan::plain("before")]),
+                InlineSpan::plain(" pushing."),
+            ])
+        );
+        assert_eq!(contents[1], Content::text("Plain words."));
+        // `__` would render back as `**`, so it stays text to keep the source intact
+        assert_eq!(contents[2], Content::text("Keep __this__ as written."));
+        assert_eq!(crate::render_markdown(&doc).unwrap(), md);
+    }
+
+    #[test]
+    fn test_display_math_blocks() {
+        let md = "$$\n\\sum_{i=1}^n x_i\n$$\n\n$$ E = mc^2 $$\n\n```math\na^2 + b^2\n```\n";
+        let doc = MarkdownParser::new().parse(md).unwrap();
+        let math: Vec<_> = doc
+            .children(&doc.root)
+            .iter()
+            .map(|id| match &doc.get_block(id).unwrap().content {
+                Content::Math(m) => (m.expression.as_str(), m.display_mode),
+                other => panic!("expected math, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            math,
+            vec![
+                ("\\sum_{i=1}^n x_i", true),
+                ("E = mc^2", true),
+                ("a^2 + b^2", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_geojson_fence_round_trips() {
+        let value = serde_json::json!({"type": "Point", "coordinates": [1.5, 2.0]});
+        let mut doc = Document::create();
+        let root = doc.root;
+        doc.add_block(
+            Block::new(Content::new_geojson(value.clone()).unwrap(), None),
+            &root,
+        )
+        .unwrap();
+
+        let md = crate::render_markdown(&doc).unwrap();
+        assert!(md.starts_with("```geojson\n"));
+        let parsed = MarkdownParser::new().parse(&md).unwrap();
+        let block = parsed.get_block(&parsed.children(&parsed.root)[0]).unwrap();
+        assert_eq!(block.content, Content::GeoJson(value));
+
+        let invalid = MarkdownParser::new()
+            .parse("```geojson\n{\"type\": \"Circle\"}\n```\n")
+            .unwrap();
+        let block = invalid
+            .get_block(&invalid.children(&invalid.root)[0])
+            .unwrap();
+        assert_eq!(block.content.type_tag(), "code");
+    }
+
+    #[test]
+    fn test_diagram_fences_become_diagrams() {
+        let md = "```mermaid\ngraph TD\n  A --> B\n```\n\n```dot\ndigraph { a -> b }\n```\n";
+        let doc = MarkdownParser::new().parse(md).unwrap();
+        let diagrams: Vec<_> = doc
+            .children(&doc.root)
+            .iter()
+            .map(|id| match &doc.get_block(id).unwrap().content {
+                Content::Diagram(d) => (d.dialect, d.source.as_str()),
+                other => panic!("expected diagram, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            diagrams,
+            vec![
+                (DiagramDialect::Mermaid, "graph TD\n  A --> B"),
+                (DiagramDialect::Graphviz, "digraph { a -> b }"),
+            ]
+        );
+        assert_eq!(crate::render_markdown(&doc).unwrap(), md);
+    }
+
+    #[test]
+    fn test_diff_fences_become_diffs() {
+        let md = "```diff\n--- a.txt\n+++ b.txt\n@@ -1 +1 @@\n-old\n+new\n```\n\n```diff\ndiff --git a/x b/x\n```\n";
+        let doc = MarkdownParser::new().parse(md).unwrap();
+        let formats: Vec<_> = doc
+            .children(&doc.root)
+            .iter()
+            .map(|id| match &doc.get_block(id).unwrap().content {
+                Content::Diff(d) => d.format,
+                other => panic!("expected diff, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(formats, vec![DiffFormat::Unified, DiffFormat::GitPatch]);
+        assert_eq!(crate::render_markdown(&doc).unwrap(), md);
+    }
+
+    #[test]
+    fn test_dollars_outside_math_stay_text() {
+        let cases = [
+            "Use `$$` to open display math.",
+            "`$x$`",
+            "$5 and $10",
+            "$a$ and $b$",
+            "$ padded $",
+            "$$ unterminated\n\nnext",
+            "$$x$$ starts a sentence.",
+            "```sh\n$$\necho $HOME\n$$\n```",
+        ];
+        for md in cases {
+            let doc = MarkdownParser::new().parse(md).unwrap();
+            assert!(
+                !doc.blocks
+                    .values()
+                    .any(|b| matches!(b.content, Content::Math(_))),
+                "false positive in {:?}",
+                md
+            );
+        }
+
+        let doc = MarkdownParser::new().parse("$\\frac{1}{2}$").unwrap();
+        let block = doc.get_block(&doc.children(&doc.root)[0]).unwrap();
+        assert!(
+            matches!(&block.content, Content::Math(m) if m.expression == "\\frac{1}{2}" && !m.display_mode)
+        );
+    }
+
     #[test]
     fn test_quote_semantic_role() {
         let md = r#"# Quote Example
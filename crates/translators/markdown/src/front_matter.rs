@@ -0,0 +1,266 @@
+//! YAML (`---`) and TOML (`+++`) front matter.
+//!
+//! Known keys map onto [`DocumentMetadata`] fields; everything else is kept
+//! in `custom["front_matter"]` so it can be re-emitted on render.
+
+use crate::{Result, TranslatorError, FRONT_MATTER_FORMAT_KEY, FRONT_MATTER_KEY};
+use serde_json::{Map, Value};
+use ucm_core::DocumentMetadata;
+
+/// Front matter syntax
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FrontMatterFormat {
+    Yaml,
+    Toml,
+}
+
+impl FrontMatterFormat {
+    fn fence(self) -> &'static str {
+        match self {
+            FrontMatterFormat::Yaml => "---",
+            FrontMatterFormat::Toml => "+++",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            FrontMatterFormat::Yaml => "yaml",
+            FrontMatterFormat::Toml => "toml",
+        }
+    }
+}
+
+/// Front matter found at the top of a document
+pub(crate) struct FrontMatter {
+    pub format: FrontMatterFormat,
+    pub body: String,
+    /// Lines covered, including both fences
+    pub lines: usize,
+}
+
+/// Find front matter starting on the first line
+pub(crate) fn extract(lines: &[&str]) -> Option<FrontMatter> {
+    let format = match lines.first()?.trim_end() {
+        "---" => FrontMatterFormat::Yaml,
+        "+++" => FrontMatterFormat::Toml,
+        _ => return None,
+    };
+    let close = lines[1..].iter().position(|l| {
+        let l = l.trim_end();
+        l == format.fence() || (format == FrontMatterFormat::Yaml && l == "...")
+    })? + 1;
+
+    Some(FrontMatter {
+        format,
+        body: lines[1..close].join("\n"),
+        lines: close + 1,
+    })
+}
+
+/// Store front matter in document metadata.
+///
+/// Returns a warning message if the front matter could not be parsed; the
+/// raw text is then kept as a string so rendering stays lossless.
+pub(crate) fn apply(front_matter: &FrontMatter, metadata: &mut DocumentMetadata) -> Option<String> {
+    metadata.custom.insert(
+        FRONT_MATTER_FORMAT_KEY.to_string(),
+        front_matter.format.name().into(),
+    );
+
+    let parsed = match front_matter.format {
+        FrontMatterFormat::Yaml if front_matter.body.trim().is_empty() => Ok(Value::Null),
+        FrontMatterFormat::Yaml => {
+            serde_yaml::from_str::<Value>(&front_matter.body).map_err(|e| e.to_string())
+        }
+        FrontMatterFormat::Toml => toml::from_str::<toml::Value>(&front_matter.body)
+            .map(toml_to_json)
+            .map_err(|e| e.to_string()),
+    };
+
+    let fields = match parsed {
+        Ok(Value::Object(fields)) => fields,
+        Ok(Value::Null) => Map::new(),
+        Ok(_) => return Some(keep_raw(front_matter, metadata, "expected a mapping")),
+        Err(e) => return Some(keep_raw(front_matter, metadata, &e)),
+    };
+
+    let mut rest = Map::new();
+    for (key, value) in fields {
+        match (key.as_str(), value) {
+            ("title", Value::String(s)) => metadata.title = Some(s),
+            ("description", Value::String(s)) => metadata.description = Some(s),
+            ("language", Value::String(s)) => metadata.language = Some(s),
+            ("authors", Value::String(s)) => metadata.authors = vec![s],
+            ("authors", Value::Array(items)) if items.iter().all(Value::is_string) => {
+                metadata.authors = items
+                    .into_iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect();
+            }
+            (_, value) => {
+                rest.insert(key, value);
+            }
+        }
+    }
+    metadata
+        .custom
+        .insert(FRONT_MATTER_KEY.to_string(), Value::Object(rest));
+    None
+}
+
+fn keep_raw(front_matter: &FrontMatter, metadata: &mut DocumentMetadata, error: &str) -> String {
+    metadata.custom.insert(
+        FRONT_MATTER_KEY.to_string(),
+        front_matter.body.clone().into(),
+    );
+    format!(
+        "malformed {} front matter kept as raw text: {}",
+        front_matter.format.name(),
+        error
+    )
+}
+
+/// Render front matter from document metadata, fences included.
+///
+/// Returns `None` if the document has nothing to emit.
+pub(crate) fn render(metadata: &DocumentMetadata) -> Result<Option<String>> {
+    let format = match metadata
+        .custom
+        .get(FRONT_MATTER_FORMAT_KEY)
+        .and_then(|v| v.as_str())
+    {
+        Some("toml") => FrontMatterFormat::Toml,
+        _ => FrontMatterFormat::Yaml,
+    };
+    let stored = metadata.custom.get(FRONT_MATTER_KEY);
+
+    let body = if let Some(Value::String(raw)) = stored {
+        raw.clone()
+    } else {
+        let mut known: Vec<(&str, Value)> = Vec::new();
+        if let Some(title) = &metadata.title {
+            known.push(("title", title.clone().into()));
+        }
+        if let Some(description) = &metadata.description {
+            known.push(("description", description.clone().into()));
+        }
+        if !metadata.authors.is_empty() {
+            known.push(("authors", metadata.authors.clone().into()));
+        }
+        if let Some(language) = &metadata.language {
+            known.push(("language", language.clone().into()));
+        }
+        if known.is_empty() && stored.is_none() {
+            return Ok(None);
+        }
+        // Known fields in a fixed order, then the rest in key order
+        let mut body = String::new();
+        for (key, value) in known {
+            body.push_str(&serialize(
+                format,
+                Map::from_iter([(key.to_string(), value)]),
+            )?);
+        }
+        if let Some(Value::Object(rest)) = stored {
+            body.push_str(&serialize(format, rest.clone())?);
+        }
+        body
+    };
+
+    let fence = format.fence();
+    let body = body.trim_end_matches('\n');
+    if body.is_empty() {
+        Ok(Some(format!("{}\n{}\n", fence, fence)))
+    } else {
+        Ok(Some(format!("{}\n{}\n{}\n", fence, body, fence)))
+    }
+}
+
+fn serialize(format: FrontMatterFormat, fields: Map<String, Value>) -> Result<String> {
+    if fields.is_empty() {
+        return Ok(String::new());
+    }
+    let rendered = match format {
+        FrontMatterFormat::Yaml => serde_yaml::to_string(&fields)
+            .map(|s| format!("{}\n", s.trim_start_matches("---\n").trim_end()))
+            .map_err(|e| e.to_string()),
+        FrontMatterFormat::Toml => toml::to_string(&fields).map_err(|e| e.to_string()),
+    };
+    rendered.map_err(|e| TranslatorError::RenderError(format!("front matter: {}", e)))
+}
+
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => i.into(),
+        toml::Value::Float(f) => f.into(),
+        toml::Value::Boolean(b) => b.into(),
+        toml::Value::Datetime(dt) => dt.to_string().into(),
+        toml::Value::Array(items) => items.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(k, v)| (k, toml_to_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(md: &str) -> (DocumentMetadata, Option<String>) {
+        let lines: Vec<&str> = md.lines().collect();
+        let front_matter = extract(&lines).expect("front matter");
+        let mut metadata = DocumentMetadata::new();
+        let warning = apply(&front_matter, &mut metadata);
+        (metadata, warning)
+    }
+
+    #[test]
+    fn test_yaml_fields_map_to_metadata() {
+        let (meta, warning) = parse(
+            "---\ntitle: Guide\nauthors:\n  - Ada\n  - Grace\nlanguage: en\ntags: [a, b]\n---\n",
+        );
+        assert!(warning.is_none());
+        assert_eq!(meta.title.as_deref(), Some("Guide"));
+        assert_eq!(meta.authors, vec!["Ada", "Grace"]);
+        assert_eq!(meta.language.as_deref(), Some("en"));
+        assert_eq!(
+            meta.custom[FRONT_MATTER_KEY],
+            serde_json::json!({"tags": ["a", "b"]})
+        );
+    }
+
+    #[test]
+    fn test_toml_fields_map_to_metadata() {
+        let (meta, warning) =
+            parse("+++\ntitle = \"Guide\"\nauthors = \"Ada\"\ndate = 2024-01-02\n+++\n");
+        assert!(warning.is_none());
+        assert_eq!(meta.title.as_deref(), Some("Guide"));
+        assert_eq!(meta.authors, vec!["Ada"]);
+        assert_eq!(
+            meta.custom[FRONT_MATTER_KEY],
+            serde_json::json!({"date": "2024-01-02"})
+        );
+    }
+
+    #[test]
+    fn test_malformed_front_matter_is_kept_raw() {
+        let (meta, warning) = parse("---\ntitle: [unclosed\n---\n");
+        assert!(warning.unwrap().starts_with("malformed yaml front matter"));
+        assert_eq!(meta.title, None);
+        assert_eq!(meta.custom[FRONT_MATTER_KEY], "title: [unclosed");
+        assert_eq!(
+            render(&meta).unwrap().unwrap(),
+            "---\ntitle: [unclosed\n---\n"
+        );
+    }
+
+    #[test]
+    fn test_unterminated_fence_is_not_front_matter() {
+        let lines = ["---", "title: x"];
+        assert!(extract(&lines).is_none());
+    }
+}
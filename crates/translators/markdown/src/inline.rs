@@ -0,0 +1,358 @@
+//! Inline Markdown (emphasis, code spans, links) to and from rich text spans.
+//!
+//! Only the unambiguous subset is recognized: `**bold**`, `*italic*` (or the
+//! `__`/`_` forms at word boundaries), backtick code spans, and
+//! `[text](href)` links. A link whose href is `#blk_...` becomes a block
+//! reference. Everything else, including images, stays plain text.
+
+use std::str::FromStr;
+
+use ucm_core::{BlockId, InlineSpan};
+
+/// Parse inline Markdown into spans
+pub fn parse_inline(text: &str) -> Vec<InlineSpan> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.chars().next().unwrap_or_default();
+
+        if c == '\\' {
+            if let Some(next) = rest[1..].chars().next().filter(char::is_ascii_punctuation) {
+                plain.push(next);
+                i += 2;
+                continue;
+            }
+        }
+
+        if c == '`' {
+            let run = run_length(rest, '`');
+            if let Some(end) = code_span_end(text, i, run) {
+                flush(&mut plain, &mut spans);
+                spans.push(InlineSpan::Code(text[i + run..end].to_string()));
+                i = end + run;
+                continue;
+            }
+            plain.push_str(&rest[..run]);
+            i += run;
+            continue;
+        }
+
+        if c == '*' || c == '_' {
+            let run = run_length(rest, c);
+            let len = run.min(2);
+            if opens(text, i, c, len) {
+                if let Some(end) = find_closer(text, i + len, c, len) {
+                    flush(&mut plain, &mut spans);
+                    let inner = parse_inline(&text[i + len..end]);
+                    spans.push(if len == 2 {
+                        InlineSpan::Bold(inner)
+                    } else {
+                        InlineSpan::Italic(inner)
+                    });
+                    i = end + len;
+                    continue;
+                }
+            }
+            plain.push_str(&rest[..run]);
+            i += run;
+            continue;
+        }
+
+        if c == '!' && rest[1..].starts_with('[') {
+            // Images are not modeled, keep them verbatim
+            if let Some(link) = parse_link(text, i + 1) {
+                plain.push_str(&text[i..link.end]);
+                i = link.end;
+                continue;
+            }
+        }
+
+        if c == '[' {
+            if let Some(link) = parse_link(text, i) {
+                flush(&mut plain, &mut spans);
+                let inner = &text[link.text.0..link.text.1];
+                let block_id = link
+                    .href
+                    .strip_prefix('#')
+                    .and_then(|id| BlockId::from_str(id).ok());
+                spans.push(match block_id {
+                    Some(block_id) => InlineSpan::BlockRef {
+                        display: inner.to_string(),
+                        block_id,
+                    },
+                    None => InlineSpan::Link {
+                        text: parse_inline(inner),
+                        href: link.href,
+                    },
+                });
+                i = link.end;
+                continue;
+            }
+        }
+
+        plain.push(c);
+        i += c.len_utf8();
+    }
+
+    flush(&mut plain, &mut spans);
+    spans
+}
+
+/// Render spans back to inline Markdown, escaping plain text so that it
+/// parses back to the same spans
+pub fn render_inline(spans: &[InlineSpan]) -> String {
+    let mut out = String::new();
+    for span in spans {
+        match span {
+            InlineSpan::Plain(text) => push_escaped(text, &mut out),
+            InlineSpan::Bold(inner) => {
+                out.push_str("**");
+                out.push_str(&render_inline(inner));
+                out.push_str("**");
+            }
+            InlineSpan::Italic(inner) => {
+                out.push('*');
+                out.push_str(&render_inline(inner));
+                out.push('*');
+            }
+            InlineSpan::Code(code) => {
+                let fence = "`".repeat(longest_run(code, '`') + 1);
+                out.push_str(&fence);
+                out.push_str(code);
+                out.push_str(&fence);
+            }
+            InlineSpan::Link { text, href } => {
+                out.push('[');
+                out.push_str(&render_inline(text));
+                out.push_str("](");
+                out.push_str(href);
+                out.push(')');
+            }
+            InlineSpan::BlockRef { display, block_id } => {
+                out.push('[');
+                push_escaped(display, &mut out);
+                out.push_str(&format!("](#{})", block_id));
+            }
+        }
+    }
+    out
+}
+
+/// Byte range of a link's text and its href
+struct Link {
+    text: (usize, usize),
+    href: String,
+    end: usize,
+}
+
+/// `[text](href)` starting at `start`, which must be the `[`
+fn parse_link(text: &str, start: usize) -> Option<Link> {
+    let mut depth = 0;
+    let mut i = start;
+    let close = loop {
+        let c = text[i..].chars().next()?;
+        match c {
+            '\\' => i += text[i + 1..].chars().next().map_or(0, char::len_utf8),
+            '`' => {
+                let run = run_length(&text[i..], '`');
+                if let Some(end) = code_span_end(text, i, run) {
+                    i = end + run;
+                    continue;
+                }
+                i += run;
+                continue;
+            }
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    break i;
+                }
+            }
+            _ => {}
+        }
+        i += c.len_utf8();
+    };
+
+    let after = &text[close + 1..];
+    let href_len = after.strip_prefix('(')?.find(')')?;
+    let href = &after[1..1 + href_len];
+    if href.is_empty() || href.contains(char::is_whitespace) {
+        return None;
+    }
+    Some(Link {
+        text: (start + 1, close),
+        href: href.to_string(),
+        end: close + 2 + href_len + 1,
+    })
+}
+
+/// Whether a `len`-long run of `c` at `at` can open emphasis
+fn opens(text: &str, at: usize, c: char, len: usize) -> bool {
+    let next = text[at + len..].chars().next();
+    if next.map_or(true, char::is_whitespace) {
+        return false;
+    }
+    // Intraword underscores (snake_case) are literal
+    c != '_'
+        || !text[..at]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric)
+}
+
+/// Start of the run closing emphasis opened just before `from`
+fn find_closer(text: &str, from: usize, c: char, len: usize) -> Option<usize> {
+    let mut i = from;
+    while i < text.len() {
+        let rest = &text[i..];
+        let ch = rest.chars().next()?;
+        if ch == '\\' {
+            i += 1 + rest[1..].chars().next().map_or(0, char::len_utf8);
+            continue;
+        }
+        if ch == '`' {
+            let run = run_length(rest, '`');
+            i = code_span_end(text, i, run).map_or(i + run, |end| end + run);
+            continue;
+        }
+        if ch == c {
+            let run = run_length(rest, c);
+            let prev = text[..i].chars().next_back();
+            let next = text[i + run..].chars().next();
+            let closes = i > from
+                && prev.is_some_and(|p| !p.is_whitespace())
+                && (c != '_' || !next.is_some_and(char::is_alphanumeric));
+            if run == len && closes {
+                return Some(i);
+            }
+            i += run;
+            continue;
+        }
+        i += ch.len_utf8();
+    }
+    None
+}
+
+/// Start of the backtick run of exactly `run` closing a code span at `start`
+fn code_span_end(text: &str, start: usize, run: usize) -> Option<usize> {
+    let mut i = start + run;
+    while let Some(offset) = text[i..].find('`') {
+        let at = i + offset;
+        let len = run_length(&text[at..], '`');
+        if len == run {
+            return Some(at);
+        }
+        i = at + len;
+    }
+    None
+}
+
+fn run_length(text: &str, c: char) -> usize {
+    text.chars().take_while(|&ch| ch == c).count() * c.len_utf8()
+}
+
+fn longest_run(text: &str, c: char) -> usize {
+    text.split(|ch| ch != c).map(str::len).max().unwrap_or(0)
+}
+
+fn push_escaped(text: &str, out: &mut String) {
+    let chars: Vec<char> = text.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        let escape = match c {
+            '\\' | '*' | '`' | '[' => true,
+            '_' => {
+                let alnum = |ch: Option<&char>| ch.is_some_and(|ch| ch.is_alphanumeric());
+                !(alnum(i.checked_sub(1).and_then(|p| chars.get(p))) && alnum(chars.get(i + 1)))
+            }
+            _ => false,
+        };
+        if escape {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+}
+
+fn flush(plain: &mut String, spans: &mut Vec<InlineSpan>) {
+    if !plain.is_empty() {
+        spans.push(InlineSpan::Plain(std::mem::take(plain)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(s: &str) -> InlineSpan {
+        InlineSpan::plain(s)
+    }
+
+    #[test]
+    fn test_parse_formatting() {
+        let spans = parse_inline("Use **bold *and* more**, `code` and [the docs](https://x.io).");
+        assert_eq!(
+            spans,
+            vec![
+                plain("Use "),
+                InlineSpan::Bold(vec![
+                    plain("bold "),
+                    InlineSpan::Italic(vec![plain("and")]),
+                    plain(" more"),
+                ]),
+                plain(", "),
+                InlineSpan::Code("code".into()),
+                plain(" and "),
+                InlineSpan::Link {
+                    text: vec![plain("the docs")],
+                    href: "https://x.io".into(),
+                },
+                plain("."),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_literal_text_stays_plain() {
+        for text in [
+            "snake_case_name",
+            "2 * 3 * 4",
+            "unclosed **bold",
+            "![logo](logo.png)",
+            "[not a link] (x)",
+            "a ` stray backtick",
+        ] {
+            assert_eq!(parse_inline(text), vec![plain(text)], "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_block_reference() {
+        let id = BlockId::from_bytes([1; 12]);
+        let text = format!("See [the intro](#{}).", id);
+        let spans = parse_inline(&text);
+        assert_eq!(
+            spans[1],
+            InlineSpan::BlockRef {
+                display: "the intro".into(),
+                block_id: id,
+            }
+        );
+        assert_eq!(render_inline(&spans), text);
+    }
+
+    #[test]
+    fn test_render_escapes_plain_text() {
+        let spans = vec![plain("a*b [c] `d` _e_ f_g")];
+        let rendered = render_inline(&spans);
+        assert_eq!(rendered, r"a\*b \[c] \`d\` \_e\_ f_g");
+        assert_eq!(parse_inline(&rendered), spans);
+
+        let code = vec![InlineSpan::Code("a ` b".into())];
+        assert_eq!(render_inline(&code), "``a ` b``");
+        assert_eq!(parse_inline(&render_inline(&code)), code);
+    }
+}
@@ -1,15 +1,24 @@
 //! Markdown translator for UCP.
 //!
-//! Converts between Markdown and UCM documents.
+//! Converts between Markdown and UCM documents, merges edited Markdown
+//! back into a previously imported document, and imports CSV data as tables.
 
+mod csv;
 mod from_markdown;
+mod front_matter;
+mod inline;
+mod merge;
+mod references;
 mod to_markdown;
 
-pub use from_markdown::MarkdownParser;
-pub use to_markdown::MarkdownRenderer;
+pub use csv::{parse_csv, CsvOptions};
+pub use from_markdown::{MarkdownParser, ParseOptions, ParseOutput};
+pub use inline::{parse_inline, render_inline};
+pub use merge::{merge_markdown, merge_markdown_with_options, MergeOptions, MergeReport};
+pub use to_markdown::{CodeFenceStyle, MarkdownRenderer, MathStyle, RenderOptions};
 
 use thiserror::Error;
-use ucm_core::Document;
+use ucm_core::{BlockId, Document};
 
 #[derive(Debug, Error)]
 pub enum TranslatorError {
@@ -23,16 +32,72 @@ pub enum TranslatorError {
 
 pub type Result<T> = std::result::Result<T, TranslatorError>;
 
+/// Custom metadata key marking a block whose text is markdown rendered verbatim
+pub const RAW_MARKDOWN_KEY: &str = "raw_markdown";
+
+/// Document custom metadata key holding front matter fields not mapped to
+/// `DocumentMetadata` (or the raw text if it could not be parsed)
+pub const FRONT_MATTER_KEY: &str = "front_matter";
+
+/// Document custom metadata key recording the front matter syntax (`yaml` or `toml`)
+pub const FRONT_MATTER_FORMAT_KEY: &str = "front_matter_format";
+
+/// Custom metadata key holding a list item's marker (`-`, `*`, `1.`, ...)
+pub const LIST_MARKER_KEY: &str = "list_marker";
+
+/// Custom metadata key holding a task list item's `{"checked": bool}` state
+pub const TASK_KEY: &str = "task";
+
+/// Custom metadata key holding a footnote block's label as written in the source
+pub const FOOTNOTE_KEY: &str = "footnote";
+
+/// Custom metadata key marking the block that holds a document's footnotes
+pub const FOOTNOTES_SECTION_KEY: &str = "footnotes";
+
+/// Custom metadata key holding a code block's fence language as
+/// `{"raw": "js", "canonical": "javascript"}`
+pub const CODE_LANGUAGE_KEY: &str = "code_language";
+
+/// Document custom metadata key holding reference-link definitions as
+/// `[{"label", "url", "title"?}]`
+pub const REFERENCE_LINKS_KEY: &str = "reference_links";
+
 /// Parse markdown into a UCM document
 pub fn parse_markdown(markdown: &str) -> Result<Document> {
     MarkdownParser::new().parse(markdown)
 }
 
+/// Parse markdown, also reporting anything that was not converted faithfully
+pub fn parse_markdown_with_report(markdown: &str) -> Result<ParseOutput> {
+    MarkdownParser::new().parse_with_report(markdown)
+}
+
+/// Parse markdown with explicit handling of unsupported syntax
+pub fn parse_markdown_with_options(markdown: &str, options: ParseOptions) -> Result<Document> {
+    MarkdownParser::new().with_options(options).parse(markdown)
+}
+
 /// Render a UCM document to markdown
 pub fn render_markdown(doc: &Document) -> Result<String> {
     MarkdownRenderer::new().render(doc)
 }
 
+/// Render a UCM document to markdown with explicit options
+pub fn render_markdown_with_options(doc: &Document, options: RenderOptions) -> Result<String> {
+    MarkdownRenderer::new().with_options(options).render(doc)
+}
+
+/// Render one block and its descendants, with the subtree's top heading as H1
+pub fn render_markdown_block(
+    doc: &Document,
+    block_id: &BlockId,
+    options: RenderOptions,
+) -> Result<String> {
+    MarkdownRenderer::new()
+        .with_options(options)
+        .render_subtree(doc, block_id)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
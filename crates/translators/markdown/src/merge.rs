@@ -0,0 +1,471 @@
+//! Incremental re-import of edited markdown.
+//!
+//! Blocks are matched level by level, so a block can only match within the
+//! same heading path. Matched blocks are updated in place and keep their ids,
+//! tags, labels and edges; only footnote edges follow the edited markdown.
+
+use std::collections::{HashMap, HashSet};
+
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+use crate::{
+    MarkdownParser, Result, TranslatorError, CODE_LANGUAGE_KEY, FOOTNOTES_SECTION_KEY,
+    FOOTNOTE_KEY, FRONT_MATTER_FORMAT_KEY, FRONT_MATTER_KEY, LIST_MARKER_KEY, RAW_MARKDOWN_KEY,
+    REFERENCE_LINKS_KEY, TASK_KEY,
+};
+use ucm_core::id::{compute_content_hash, generate_block_id};
+use ucm_core::normalize::normalize_content;
+use ucm_core::{Block, BlockId, Content, Document, Edge, EdgeType};
+
+/// Metadata keys owned by the parser, refreshed on merge
+const PARSER_KEYS: [&str; 6] = [
+    RAW_MARKDOWN_KEY,
+    CODE_LANGUAGE_KEY,
+    LIST_MARKER_KEY,
+    TASK_KEY,
+    FOOTNOTE_KEY,
+    FOOTNOTES_SECTION_KEY,
+];
+
+/// Tuning for [`merge_markdown_with_options`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MergeOptions {
+    /// Minimum word overlap (0.0 - 1.0) for an edited block to keep its id
+    pub similarity_threshold: f64,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self {
+            similarity_threshold: 0.5,
+        }
+    }
+}
+
+/// What a merge changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    /// Blocks whose content did not change
+    pub unchanged: Vec<BlockId>,
+    /// Blocks updated in place
+    pub updated: Vec<BlockId>,
+    /// Blocks created for new content
+    pub added: Vec<BlockId>,
+    /// Blocks deleted because their content is gone
+    pub removed: Vec<BlockId>,
+}
+
+/// Merge edited markdown into a document previously imported from it
+pub fn merge_markdown(doc: &mut Document, markdown: &str) -> Result<MergeReport> {
+    merge_markdown_with_options(doc, markdown, MergeOptions::default())
+}
+
+/// Merge edited markdown with an explicit similarity threshold
+pub fn merge_markdown_with_options(
+    doc: &mut Document,
+    markdown: &str,
+    options: MergeOptions,
+) -> Result<MergeReport> {
+    let source = MarkdownParser::new().parse(markdown)?;
+    let mut merger = Merger {
+        doc,
+        source: &source,
+        options,
+        report: MergeReport::default(),
+        merged: HashMap::new(),
+    };
+    let (root, source_root) = (merger.doc.root, source.root);
+    merger.merge_children(&root, &source_root)?;
+    merger.relink_footnotes();
+
+    match source.metadata.custom.get(REFERENCE_LINKS_KEY) {
+        Some(links) => merger
+            .doc
+            .metadata
+            .custom
+            .insert(REFERENCE_LINKS_KEY.to_string(), links.clone()),
+        None => merger.doc.metadata.custom.remove(REFERENCE_LINKS_KEY),
+    };
+
+    if source.metadata.custom.contains_key(FRONT_MATTER_FORMAT_KEY) {
+        let meta = &mut merger.doc.metadata;
+        meta.title = source.metadata.title.clone();
+        meta.description = source.metadata.description.clone();
+        meta.authors = source.metadata.authors.clone();
+        meta.language = source.metadata.language.clone();
+        for key in [FRONT_MATTER_KEY, FRONT_MATTER_FORMAT_KEY] {
+            if let Some(value) = source.metadata.custom.get(key) {
+                meta.custom.insert(key.to_string(), value.clone());
+            }
+        }
+    }
+    Ok(merger.report)
+}
+
+struct Merger<'a> {
+    doc: &'a mut Document,
+    source: &'a Document,
+    options: MergeOptions,
+    report: MergeReport,
+    /// Document id each source block ended up as
+    merged: HashMap<BlockId, BlockId>,
+}
+
+impl Merger<'_> {
+    fn merge_children(&mut self, parent: &BlockId, source_parent: &BlockId) -> Result<()> {
+        let existing = self.doc.children(parent).to_vec();
+        let incoming = self.source.children(source_parent).to_vec();
+        let matches = self.match_blocks(&existing, &incoming);
+
+        // Delete first so re-added content cannot collide with a stale id
+        let matched: HashSet<BlockId> = matches.iter().flatten().copied().collect();
+        for id in existing.iter().filter(|id| !matched.contains(id)) {
+            let deleted = self
+                .doc
+                .delete_cascade(id)
+                .map_err(|e| TranslatorError::InvalidStructure(e.to_string()))?;
+            self.report.removed.extend(deleted.iter().map(|b| b.id));
+        }
+
+        let mut order = Vec::with_capacity(incoming.len());
+        for (source_id, matched) in incoming.iter().zip(matches) {
+            match matched {
+                Some(id) => {
+                    self.merged.insert(*source_id, id);
+                    self.update(&id, source_id);
+                    self.merge_children(&id, source_id)?;
+                    order.push(id);
+                }
+                None => order.push(self.add_subtree(parent, source_id)?),
+            }
+        }
+        self.doc.structure.insert(*parent, order);
+        Ok(())
+    }
+
+    /// Pair each incoming block with an existing one: the longest common
+    /// subsequence of unchanged blocks first, then unchanged blocks that
+    /// moved, then edited blocks by word overlap
+    fn match_blocks(&self, existing: &[BlockId], incoming: &[BlockId]) -> Vec<Option<BlockId>> {
+        let mut matches = vec![None; incoming.len()];
+        let mut taken = HashSet::new();
+
+        let old_keys: Vec<_> = existing
+            .iter()
+            .map(|id| self.doc.get_block(id).map(exact_key))
+            .collect();
+        let new_keys: Vec<_> = incoming
+            .iter()
+            .map(|id| Some(exact_key(&self.source.blocks[id])))
+            .collect();
+        for op in capture_diff_slices(Algorithm::Lcs, &old_keys, &new_keys) {
+            if let DiffOp::Equal {
+                old_index,
+                new_index,
+                len,
+            } = op
+            {
+                for i in 0..len {
+                    let (id, source_id) = (existing[old_index + i], incoming[new_index + i]);
+                    let exact = self
+                        .doc
+                        .get_block(&id)
+                        .and_then(|block| similarity(block, &self.source.blocks[&source_id]))
+                        == Some(1.0);
+                    if exact {
+                        taken.insert(id);
+                        matches[new_index + i] = Some(id);
+                    }
+                }
+            }
+        }
+
+        for exact_only in [true, false] {
+            for (slot, source_id) in matches.iter_mut().zip(incoming) {
+                if slot.is_some() {
+                    continue;
+                }
+                let source = &self.source.blocks[source_id];
+                let best = existing
+                    .iter()
+                    .filter(|id| !taken.contains(*id))
+                    .filter_map(|id| {
+                        let block = self.doc.get_block(id)?;
+                        let score = similarity(block, source)?;
+                        let accept = if exact_only {
+                            score >= 1.0
+                        } else {
+                            score >= self.options.similarity_threshold
+                        };
+                        accept.then_some((*id, score))
+                    })
+                    .fold(None, |best: Option<(BlockId, f64)>, candidate| match best {
+                        Some((_, score)) if score >= candidate.1 => best,
+                        _ => Some(candidate),
+                    });
+                if let Some((id, _)) = best {
+                    taken.insert(id);
+                    *slot = Some(id);
+                }
+            }
+        }
+        matches
+    }
+
+    fn update(&mut self, id: &BlockId, source_id: &BlockId) {
+        let source = &self.source.blocks[source_id];
+        let Some(block) = self.doc.get_block_mut(id) else {
+            return;
+        };
+        let same_keys = PARSER_KEYS
+            .iter()
+            .all(|k| block.metadata.custom.get(*k) == source.metadata.custom.get(*k));
+        if block.content == source.content && same_keys {
+            self.report.unchanged.push(*id);
+            return;
+        }
+
+        block.content = source.content.clone();
+        block.metadata.content_hash = compute_content_hash(&block.content);
+        for key in PARSER_KEYS {
+            match source.metadata.custom.get(key) {
+                Some(value) => block.metadata.custom.insert(key.to_string(), value.clone()),
+                None => block.metadata.custom.remove(key),
+            };
+        }
+        block.metadata.touch();
+        block.version.increment();
+        self.report.updated.push(*id);
+    }
+
+    fn add_subtree(&mut self, parent: &BlockId, source_id: &BlockId) -> Result<BlockId> {
+        let mut block = self.source.blocks[source_id].clone();
+        // Identical content elsewhere in the document would share the id
+        let mut salt = 0;
+        while self.doc.blocks.contains_key(&block.id) {
+            salt += 1;
+            let role = block
+                .metadata
+                .semantic_role
+                .as_ref()
+                .map(|r| r.category.as_str());
+            let namespace = format!("{}:{}", parent, salt);
+            block.id = generate_block_id(&block.content, role, Some(&namespace));
+        }
+
+        let id = self
+            .doc
+            .add_block(block, parent)
+            .map_err(|e| TranslatorError::InvalidStructure(e.to_string()))?;
+        self.merged.insert(*source_id, id);
+        self.report.added.push(id);
+        for child in self.source.children(source_id).to_vec() {
+            self.add_subtree(&id, &child)?;
+        }
+        Ok(id)
+    }
+
+    /// Point each block's footnote edges at the footnotes its merged text cites
+    fn relink_footnotes(&mut self) {
+        let is_footnote = |doc: &Document, id: &BlockId| {
+            doc.get_block(id)
+                .is_some_and(|b| b.metadata.custom.contains_key(FOOTNOTE_KEY))
+        };
+        for (source_id, id) in &self.merged {
+            let cited: Vec<BlockId> = self.source.blocks[source_id]
+                .edges_of_type(&EdgeType::References)
+                .iter()
+                .filter(|e| is_footnote(self.source, &e.target))
+                .filter_map(|e| self.merged.get(&e.target).copied())
+                .collect();
+            // Edges to footnotes, or copied from the source along with a new block
+            let current: Vec<BlockId> = self.doc.blocks[id]
+                .edges_of_type(&EdgeType::References)
+                .iter()
+                .filter(|e| is_footnote(self.doc, &e.target) || is_footnote(self.source, &e.target))
+                .map(|e| e.target)
+                .collect();
+            if current == cited {
+                continue;
+            }
+            let Some(block) = self.doc.get_block_mut(id) else {
+                continue;
+            };
+            for target in current {
+                block.remove_edge(&target, &EdgeType::References);
+            }
+            for target in cited {
+                block.add_edge(Edge::new(EdgeType::References, target));
+            }
+        }
+        self.doc.rebuild_indices();
+    }
+}
+
+/// Word overlap between two blocks, or `None` if they cannot match at all
+fn similarity(existing: &Block, incoming: &Block) -> Option<f64> {
+    let role = |b: &Block| b.metadata.semantic_role.as_ref().map(|r| r.category);
+    if kind(existing) != kind(incoming) || role(existing) != role(incoming) {
+        return None;
+    }
+    if existing.content == incoming.content {
+        return Some(1.0);
+    }
+
+    let (a, b) = (words(&existing.content), words(&incoming.content));
+    let a: HashSet<&str> = a.split_whitespace().collect();
+    let b: HashSet<&str> = b.split_whitespace().collect();
+    let union = a.union(&b).count();
+    if union == 0 {
+        return Some(0.0);
+    }
+    // Cap below 1.0 so edited blocks never take priority over exact matches
+    Some((a.intersection(&b).count() as f64 / union as f64).min(0.999))
+}
+
+/// Sequence key for the LCS pass; equal keys are checked with
+/// [`similarity`] before they count as unchanged
+fn exact_key(block: &Block) -> (&'static str, Option<String>, String) {
+    let role = block
+        .metadata
+        .semantic_role
+        .as_ref()
+        .map(|r| r.category.as_str().to_string());
+    (kind(block), role, normalize_content(&block.content))
+}
+
+/// Content type, with rich text counted as text so adding emphasis is an edit
+fn kind(block: &Block) -> &'static str {
+    match block.content_type() {
+        "rich_text" => "text",
+        other => other,
+    }
+}
+
+fn words(content: &Content) -> String {
+    match content {
+        Content::RichText(rich) => normalize_content(&Content::text(rich.plain_text())),
+        other => normalize_content(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_markdown, render_markdown};
+    use ucm_core::{Content, EdgeType};
+
+    const ORIGINAL: &str = "# Guide\n\n## Setup\n\nInstall the tool with cargo.\n\nRun the setup wizard once.\n\n## Usage\n\nCall the API from your code.\n";
+
+    fn find(doc: &Document, text: &str) -> BlockId {
+        *doc.blocks
+            .iter()
+            .find(|(_, b)| b.content == Content::text(text))
+            .map(|(id, _)| id)
+            .unwrap_or_else(|| panic!("no block with text {:?}", text))
+    }
+
+    #[test]
+    fn test_edges_on_unchanged_blocks_survive() {
+        let mut doc = parse_markdown(ORIGINAL).unwrap();
+        let install = find(&doc, "Install the tool with cargo.");
+        let usage = find(&doc, "Call the API from your code.");
+        let wizard = find(&doc, "Run the setup wizard once.");
+        doc.add_edge(&install, EdgeType::References, usage);
+        doc.get_block_mut(&install)
+            .unwrap()
+            .metadata
+            .tags
+            .push("reviewed".into());
+
+        let edited = "# Guide\n\n## Setup\n\nInstall the tool with cargo.\n\n## Usage\n\nCall the API from your code.\n\nCheck the logs.\n";
+        let report = merge_markdown(&mut doc, edited).unwrap();
+
+        assert_eq!(render_markdown(&doc).unwrap(), edited);
+        assert_eq!(report.removed, vec![wizard]);
+        assert_eq!(report.added.len(), 1);
+        assert!(report.updated.is_empty());
+
+        let block = doc.get_block(&install).unwrap();
+        assert!(block.has_tag("reviewed"));
+        assert_eq!(block.edges_of_type(&EdgeType::References)[0].target, usage);
+        assert!(doc.get_block(&usage).is_some());
+    }
+
+    #[test]
+    fn test_edited_block_keeps_id_above_threshold() {
+        let mut doc = parse_markdown(ORIGINAL).unwrap();
+        let install = find(&doc, "Install the tool with cargo.");
+
+        let edited = ORIGINAL.replace("with cargo.", "with cargo install.");
+        let report = merge_markdown(&mut doc, &edited).unwrap();
+
+        assert_eq!(report.updated, vec![install]);
+        assert!(report.removed.is_empty() && report.added.is_empty());
+        assert_eq!(
+            doc.get_block(&install).unwrap().content,
+            Content::text("Install the tool with cargo install.")
+        );
+    }
+
+    #[test]
+    fn test_threshold_controls_matching() {
+        let mut doc = parse_markdown(ORIGINAL).unwrap();
+        let install = find(&doc, "Install the tool with cargo.");
+
+        let edited = ORIGINAL.replace("with cargo.", "with cargo install.");
+        let options = MergeOptions {
+            similarity_threshold: 0.95,
+        };
+        let report = merge_markdown_with_options(&mut doc, &edited, options).unwrap();
+
+        assert_eq!(report.removed, vec![install]);
+        assert_eq!(report.added.len(), 1);
+    }
+
+    #[test]
+    fn test_reordered_sections_keep_ids() {
+        let mut doc = parse_markdown(ORIGINAL).unwrap();
+        let usage = find(&doc, "Usage");
+
+        let edited = "# Guide\n\n## Usage\n\nCall the API from your code.\n\n## Setup\n\nInstall the tool with cargo.\n\nRun the setup wizard once.\n";
+        let report = merge_markdown(&mut doc, edited).unwrap();
+
+        assert!(report.added.is_empty() && report.removed.is_empty());
+        let guide = doc.children(&doc.root)[0];
+        assert_eq!(doc.children(&guide)[0], usage);
+        assert_eq!(render_markdown(&doc).unwrap(), edited);
+    }
+
+    #[test]
+    fn test_footnote_edges_follow_edits() {
+        let original = "First[^1].\n\nSecond.\n\n[^1]: A note.\n";
+        let mut doc = parse_markdown(original).unwrap();
+        let first = find(&doc, "First[^1].");
+        let note = find(&doc, "A note.");
+
+        let edited = "First[^1].\n\nSecond[^2].\n\n[^1]: A note.\n\n[^2]: Another.\n";
+        merge_markdown(&mut doc, edited).unwrap();
+        assert_eq!(render_markdown(&doc).unwrap(), edited);
+
+        let targets = |doc: &Document, id| -> Vec<BlockId> {
+            doc.blocks[&id]
+                .edges_of_type(&EdgeType::References)
+                .iter()
+                .map(|e| e.target)
+                .collect()
+        };
+        assert_eq!(targets(&doc, first), vec![note]);
+        let second = find(&doc, "Second[^2].");
+        assert_eq!(targets(&doc, second), vec![find(&doc, "Another.")]);
+
+        // Dropping the citation drops the edge; the rest renumbers
+        let edited = "First.\n\nSecond[^2].\n\n[^2]: Another.\n";
+        merge_markdown(&mut doc, edited).unwrap();
+        assert_eq!(
+            render_markdown(&doc).unwrap(),
+            "First.\n\nSecond[^1].\n\n[^1]: Another.\n"
+        );
+        assert!(targets(&doc, find(&doc, "First.")).is_empty());
+    }
+}
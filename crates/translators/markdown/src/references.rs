@@ -0,0 +1,419 @@
+//! Footnotes and reference-style link definitions.
+//!
+//! Footnote definitions become blocks in a trailing footnotes section, linked
+//! from every block that cites them with a `References` edge. Rendering walks
+//! the citing blocks in document order and renumbers numeric labels, so moving
+//! blocks around keeps `[^1]`, `[^2]`, ... in reading order. Reference-link
+//! definitions are kept in document metadata and emitted at the end.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use ucm_core::{Block, BlockId, Content, Document, EdgeType};
+
+use crate::{
+    Result, TranslatorError, FOOTNOTES_SECTION_KEY, FOOTNOTE_KEY, RAW_MARKDOWN_KEY,
+    REFERENCE_LINKS_KEY,
+};
+
+/// A `[label]: url "title"` definition
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ReferenceLink {
+    pub label: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// A footnote definition found while parsing
+pub(crate) struct FootnoteDefinition {
+    pub label: String,
+    pub text: String,
+}
+
+/// Label of a `[^label]: text` line
+pub(crate) fn footnote_label(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix("[^")?;
+    let end = rest.find("]:")?;
+    (end > 0).then(|| &rest[..end])
+}
+
+/// Parse a footnote definition spanning `lines`, removing the continuation
+/// indent
+pub(crate) fn parse_footnote(lines: &[&str]) -> Option<FootnoteDefinition> {
+    let label = footnote_label(lines[0])?;
+    let first = lines[0][label.len() + 4..].trim();
+    let mut text: Vec<&str> = vec![first];
+    for line in &lines[1..] {
+        let dedented = line
+            .strip_prefix("    ")
+            .or_else(|| line.strip_prefix('\t'))
+            .unwrap_or_else(|| line.trim_start());
+        text.push(dedented.trim_end());
+    }
+    while text.last().is_some_and(|l| l.is_empty()) {
+        text.pop();
+    }
+    Some(FootnoteDefinition {
+        label: label.to_string(),
+        text: text.join("\n"),
+    })
+}
+
+/// Parse a `[label]: url "title"` line
+pub(crate) fn parse_reference_link(line: &str) -> Option<ReferenceLink> {
+    let line = line.trim();
+    let rest = line.strip_prefix('[')?;
+    let end = rest.find("]:")?;
+    let label = &rest[..end];
+    if label.is_empty() || label.starts_with('^') || label.contains(['[', ']']) {
+        return None;
+    }
+
+    let rest = rest[end + 2..].trim_start();
+    let (url, rest) = match rest.strip_prefix('<') {
+        Some(bracketed) => {
+            let close = bracketed.find('>')?;
+            (&bracketed[..close], &bracketed[close + 1..])
+        }
+        None => rest.split_at(rest.find(char::is_whitespace).unwrap_or(rest.len())),
+    };
+    if url.is_empty() {
+        return None;
+    }
+
+    let rest = rest.trim();
+    let title = if rest.is_empty() {
+        None
+    } else {
+        let quoted = [('"', '"'), ('\'', '\''), ('(', ')')]
+            .iter()
+            .find_map(|&(open, close)| rest.strip_prefix(open)?.strip_suffix(close))?;
+        Some(quoted.to_string())
+    };
+    Some(ReferenceLink {
+        label: label.to_string(),
+        url: url.to_string(),
+        title,
+    })
+}
+
+/// Add the footnotes section and link citing blocks to their footnotes
+pub(crate) fn attach_footnotes(
+    doc: &mut Document,
+    definitions: Vec<FootnoteDefinition>,
+) -> Result<()> {
+    if definitions.is_empty() {
+        return Ok(());
+    }
+
+    let root = doc.root;
+    let mut section = Block::new(Content::text("Footnotes"), None);
+    section
+        .metadata
+        .custom
+        .insert(FOOTNOTES_SECTION_KEY.to_string(), true.into());
+    let section = doc
+        .add_block(section, &root)
+        .map_err(|e| TranslatorError::InvalidStructure(e.to_string()))?;
+
+    let mut by_label = HashMap::new();
+    for definition in definitions {
+        let mut block = Block::new(Content::text(&definition.text), Some("footnote"));
+        block
+            .metadata
+            .custom
+            .insert(FOOTNOTE_KEY.to_string(), definition.label.clone().into());
+        let id = doc
+            .add_block(block, &section)
+            .map_err(|e| TranslatorError::InvalidStructure(e.to_string()))?;
+        by_label.insert(definition.label, id);
+    }
+
+    let citing: Vec<(BlockId, Vec<BlockId>)> = doc
+        .blocks
+        .values()
+        .filter(|b| !is_footnote(b) && !is_raw(b))
+        .filter_map(|b| {
+            let Content::Text(text) = &b.content else {
+                return None;
+            };
+            let mut targets = Vec::new();
+            for label in markers(&text.text) {
+                if let Some(id) = by_label.get(label) {
+                    if !targets.contains(id) {
+                        targets.push(*id);
+                    }
+                }
+            }
+            (!targets.is_empty()).then_some((b.id, targets))
+        })
+        .collect();
+    for (source, targets) in citing {
+        for target in targets {
+            doc.add_edge(&source, EdgeType::References, target);
+        }
+    }
+    Ok(())
+}
+
+/// Store reference-link definitions in the document metadata
+pub(crate) fn store_reference_links(doc: &mut Document, links: &[ReferenceLink]) -> Result<()> {
+    if links.is_empty() {
+        return Ok(());
+    }
+    let value =
+        serde_json::to_value(links).map_err(|e| TranslatorError::RenderError(e.to_string()))?;
+    doc.metadata
+        .custom
+        .insert(REFERENCE_LINKS_KEY.to_string(), value);
+    Ok(())
+}
+
+pub(crate) fn is_footnotes_section(block: &Block) -> bool {
+    block.metadata.custom.contains_key(FOOTNOTES_SECTION_KEY)
+}
+
+fn is_footnote(block: &Block) -> bool {
+    block.metadata.custom.contains_key(FOOTNOTE_KEY)
+}
+
+fn is_raw(block: &Block) -> bool {
+    block
+        .metadata
+        .custom
+        .get(RAW_MARKDOWN_KEY)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// Labels of the `[^label]` markers in `text`, in order
+fn markers(text: &str) -> impl Iterator<Item = &str> {
+    text.match_indices("[^").filter_map(move |(at, _)| {
+        let rest = &text[at + 2..];
+        let end = rest.find(']')?;
+        let label = &rest[..end];
+        // A definition line is not a citation
+        let is_definition = rest[end + 1..].starts_with(':') && at == 0;
+        (!label.is_empty() && !label.contains(char::is_whitespace) && !is_definition)
+            .then_some(label)
+    })
+}
+
+/// Footnote labels for one render, assigned in reading order
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Footnotes {
+    labels: HashMap<BlockId, String>,
+    order: Vec<BlockId>,
+}
+
+impl Footnotes {
+    /// Number the footnotes cited under `start`. Numeric labels are
+    /// renumbered from 1 by first citation; named labels are kept. With
+    /// `include_uncited`, footnotes nobody cites follow in section order.
+    pub(crate) fn number(doc: &Document, start: &BlockId, include_uncited: bool) -> Self {
+        let mut footnotes = Self::default();
+        let mut next = 1;
+        let mut assign = |footnotes: &mut Self, id: BlockId| {
+            if footnotes.labels.contains_key(&id) {
+                return;
+            }
+            let Some(label) = doc
+                .get_block(&id)
+                .and_then(|b| b.metadata.custom.get(FOOTNOTE_KEY))
+                .and_then(|v| v.as_str())
+            else {
+                return;
+            };
+            let label = if label.chars().all(|c| c.is_ascii_digit()) {
+                next += 1;
+                (next - 1).to_string()
+            } else {
+                label.to_string()
+            };
+            footnotes.labels.insert(id, label);
+            footnotes.order.push(id);
+        };
+
+        let mut stack = vec![*start];
+        while let Some(id) = stack.pop() {
+            let Some(block) = doc.get_block(&id) else {
+                continue;
+            };
+            if is_footnotes_section(block) {
+                continue;
+            }
+            for target in footnote_targets(doc, block) {
+                assign(&mut footnotes, target);
+            }
+            stack.extend(doc.children(&id).iter().rev());
+        }
+
+        if include_uncited {
+            let sections = doc
+                .children(&doc.root)
+                .iter()
+                .filter(|id| doc.get_block(id).is_some_and(is_footnotes_section));
+            for section in sections {
+                for id in doc.children(section) {
+                    assign(&mut footnotes, *id);
+                }
+            }
+        }
+        footnotes
+    }
+
+    /// `text` of `block` with its footnote markers relabelled
+    pub(crate) fn relabel<'a>(&self, doc: &Document, block: &Block, text: &'a str) -> Cow<'a, str> {
+        let renames: HashMap<&str, &str> = block
+            .edges_of_type(&EdgeType::References)
+            .into_iter()
+            .filter_map(|edge| {
+                let original = doc
+                    .get_block(&edge.target)?
+                    .metadata
+                    .custom
+                    .get(FOOTNOTE_KEY)?
+                    .as_str()?;
+                Some((original, self.labels.get(&edge.target)?.as_str()))
+            })
+            .filter(|(from, to)| from != to)
+            .collect();
+        if renames.is_empty() {
+            return Cow::Borrowed(text);
+        }
+
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(at) = rest.find("[^") {
+            out.push_str(&rest[..at + 2]);
+            rest = &rest[at + 2..];
+            if let Some(end) = rest.find(']') {
+                if let Some(to) = renames.get(&rest[..end]) {
+                    out.push_str(to);
+                    rest = &rest[end..];
+                }
+            }
+        }
+        out.push_str(rest);
+        Cow::Owned(out)
+    }
+
+    /// `[^n]: text` definitions in citation order
+    pub(crate) fn render(&self, doc: &Document, output: &mut String) {
+        for id in &self.order {
+            let Some(Content::Text(text)) = doc.get_block(id).map(|b| &b.content) else {
+                continue;
+            };
+            output.push_str(&format!("[^{}]: ", self.labels[id]));
+            for (i, line) in text.text.lines().enumerate() {
+                if i > 0 && !line.is_empty() {
+                    output.push_str("    ");
+                }
+                output.push_str(line);
+                output.push('\n');
+            }
+            output.push('\n');
+        }
+    }
+}
+
+/// Footnote blocks `block` cites, in the order its markers appear
+fn footnote_targets(doc: &Document, block: &Block) -> Vec<BlockId> {
+    let edges = block.edges_of_type(&EdgeType::References);
+    if edges.is_empty() {
+        return Vec::new();
+    }
+    let cited: HashMap<&str, BlockId> = edges
+        .iter()
+        .filter_map(|edge| {
+            let label = doc
+                .get_block(&edge.target)?
+                .metadata
+                .custom
+                .get(FOOTNOTE_KEY)?
+                .as_str()?;
+            Some((label, edge.target))
+        })
+        .collect();
+    match &block.content {
+        Content::Text(text) => markers(&text.text)
+            .filter_map(|label| cited.get(label).copied())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// `[label]: url "title"` lines for the stored definitions; with `only_cited`,
+/// just those whose label appears in `body`
+pub(crate) fn render_reference_links(
+    doc: &Document,
+    body: &str,
+    only_cited: bool,
+    output: &mut String,
+) {
+    let Some(links) = doc
+        .metadata
+        .custom
+        .get(REFERENCE_LINKS_KEY)
+        .and_then(|v| serde_json::from_value::<Vec<ReferenceLink>>(v.clone()).ok())
+    else {
+        return;
+    };
+    let body = body.to_lowercase();
+    let mut any = false;
+    for link in links {
+        if only_cited && !body.contains(&format!("[{}]", link.label.to_lowercase())) {
+            continue;
+        }
+        any = true;
+        output.push_str(&format!("[{}]: ", link.label));
+        if link.url.contains(char::is_whitespace) {
+            output.push_str(&format!("<{}>", link.url));
+        } else {
+            output.push_str(&link.url);
+        }
+        match link.title {
+            Some(title) if title.contains('"') => output.push_str(&format!(" ({})", title)),
+            Some(title) => output.push_str(&format!(" \"{}\"", title)),
+            None => {}
+        }
+        output.push('\n');
+    }
+    if any {
+        output.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reference_link() {
+        assert_eq!(
+            parse_reference_link("[Docs]: https://example.com \"The docs\""),
+            Some(ReferenceLink {
+                label: "Docs".into(),
+                url: "https://example.com".into(),
+                title: Some("The docs".into()),
+            })
+        );
+        let spaced = parse_reference_link("[a]: <my file.md> (Title)").unwrap();
+        assert_eq!(spaced.url, "my file.md");
+        assert_eq!(spaced.title.as_deref(), Some("Title"));
+
+        assert_eq!(parse_reference_link("[^1]: footnote"), None);
+        assert_eq!(parse_reference_link("[a]:"), None);
+        assert_eq!(parse_reference_link("[a]: url trailing words"), None);
+        assert_eq!(parse_reference_link("[a] not a definition"), None);
+    }
+
+    #[test]
+    fn test_markers_skip_definitions_and_spaces() {
+        let found: Vec<_> = markers("a[^1] b[^note], [^ x] and [^2]").collect();
+        assert_eq!(found, vec!["1", "note", "2"]);
+        assert_eq!(markers("[^1]: def").count(), 0);
+    }
+}
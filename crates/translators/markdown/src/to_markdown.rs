@@ -7,9 +7,12 @@
 //! The hybrid approach uses explicit roles when present, falling back to structural
 //! derivation for blocks without heading roles.
 
-use crate::{Result, TranslatorError};
+use crate::front_matter;
+use crate::inline::render_inline;
+use crate::references::{self, Footnotes};
+use crate::{Result, TranslatorError, LIST_MARKER_KEY, RAW_MARKDOWN_KEY, TASK_KEY};
 use ucm_core::metadata::RoleCategory;
-use ucm_core::{Block, BlockId, Cell, Content, Document, MediaSource, Row};
+use ucm_core::{Block, BlockId, Cell, Content, Document, FormContent, MediaSource, Row};
 
 /// Configuration for heading level derivation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -23,20 +26,66 @@ pub enum HeadingMode {
     Hybrid,
 }
 
+/// Fence characters used for code blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CodeFenceStyle {
+    /// ```` ``` ````
+    #[default]
+    Backtick,
+    /// `~~~`
+    Tilde,
+}
+
+/// Delimiters used for display math
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MathStyle {
+    /// `$$ ... $$`
+    #[default]
+    Dollar,
+    /// A ```` ```math ```` code fence
+    Fence,
+}
+
+/// Options controlling how the block tree is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RenderOptions {
+    /// Emit document metadata as front matter at the top
+    pub include_front_matter: bool,
+    /// Shift every heading by this many levels, clamped to H1-H6
+    pub heading_offset: i8,
+    /// Precede each block with a `<!-- ucp:blk_... -->` comment
+    pub emit_block_ids: bool,
+    /// Fence characters for code and JSON blocks
+    pub code_fence_style: CodeFenceStyle,
+    /// Render line breaks inside paragraphs and quotes as hard breaks
+    pub hard_line_breaks: bool,
+    /// Delimiters for display math (inline math always uses `$`)
+    pub math_style: MathStyle,
+}
+
 /// Markdown renderer that converts UCM to Markdown
+#[derive(Debug, Clone)]
 pub struct MarkdownRenderer {
     indent_size: usize,
+    options: RenderOptions,
     heading_mode: HeadingMode,
     /// Base heading level offset (0 = start at H1, 1 = start at H2, etc.)
     heading_offset: usize,
+    /// Levels added to every heading so a rendered subtree starts at H1
+    subtree_shift: i64,
+    /// Footnote labels for the render in progress
+    footnotes: Footnotes,
 }
 
 impl MarkdownRenderer {
     pub fn new() -> Self {
         Self {
             indent_size: 2,
+            options: RenderOptions::default(),
             heading_mode: HeadingMode::default(),
             heading_offset: 0,
+            subtree_shift: 0,
+            footnotes: Footnotes::default(),
         }
     }
 
@@ -57,17 +106,87 @@ impl MarkdownRenderer {
         self
     }
 
+    /// Set the render options
+    pub fn with_options(mut self, options: RenderOptions) -> Self {
+        self.options = options;
+        self
+    }
+
     pub fn render(&self, doc: &Document) -> Result<String> {
+        let renderer = Self {
+            footnotes: Footnotes::number(doc, &doc.root, true),
+            ..self.clone()
+        };
         let mut output = String::new();
-        self.render_block(doc, &doc.root, &mut output, 0)?;
+        renderer.render_block(doc, &doc.root, &mut output, 0)?;
+        renderer.render_definitions(doc, &mut output, false);
 
-        // Trim trailing whitespace but ensure single newline at end
-        let trimmed = output.trim_end();
-        if trimmed.is_empty() {
-            Ok(String::new())
+        let front_matter = if self.options.include_front_matter {
+            front_matter::render(&doc.metadata)?
         } else {
-            Ok(format!("{}\n", trimmed))
+            None
+        };
+        Ok(finish(&output, front_matter))
+    }
+
+    /// Render one block and its descendants.
+    ///
+    /// Headings are shifted so the subtree's top heading renders as H1 (plus
+    /// any `heading_offset`). Front matter is never included; footnotes and
+    /// link definitions are, when the subtree cites them.
+    pub fn render_subtree(&self, doc: &Document, block_id: &BlockId) -> Result<String> {
+        let block = doc.get_block(block_id).ok_or_else(|| {
+            TranslatorError::RenderError(format!("Block not found: {}", block_id))
+        })?;
+        let mut depth = 0;
+        let mut current = doc.parent(block_id);
+        while let Some(parent) = current {
+            depth += 1;
+            current = doc.parent(parent);
+        }
+
+        let renderer = Self {
+            subtree_shift: self
+                .min_heading_level(doc, block, depth)
+                .map_or(0, |level| 1 - level as i64),
+            footnotes: Footnotes::number(doc, block_id, false),
+            ..self.clone()
+        };
+        let mut output = String::new();
+        if is_list_item(block) {
+            renderer.render_list_item(doc, block, &mut output, 0)?;
+        } else {
+            renderer.render_block(doc, block_id, &mut output, depth)?;
         }
+        renderer.render_definitions(doc, &mut output, true);
+        Ok(finish(&output, None))
+    }
+
+    /// Reference-link definitions, then footnotes
+    fn render_definitions(&self, doc: &Document, output: &mut String, only_cited: bool) {
+        let body = output.clone();
+        references::render_reference_links(doc, &body, only_cited, output);
+        self.footnotes.render(doc, output);
+    }
+
+    /// Smallest heading level that rendering `block` at `depth` would produce
+    fn min_heading_level(&self, doc: &Document, block: &Block, depth: usize) -> Option<usize> {
+        if is_list_item(block) || references::is_footnotes_section(block) {
+            return None;
+        }
+        let own = match &block.content {
+            Content::Text(_) if !block.is_root() => {
+                let role = block.metadata.semantic_role.as_ref().map(|r| r.category);
+                self.resolve_heading_level(role, depth)
+            }
+            _ => None,
+        };
+        doc.children(&block.id)
+            .iter()
+            .filter_map(|id| doc.get_block(id))
+            .filter_map(|child| self.min_heading_level(doc, child, depth + 1))
+            .chain(own)
+            .min()
     }
 
     fn render_block(
@@ -81,42 +200,140 @@ impl MarkdownRenderer {
             TranslatorError::RenderError(format!("Block not found: {}", block_id))
         })?;
 
+        // Footnotes are emitted after everything else, in citation order
+        if references::is_footnotes_section(block) {
+            return Ok(());
+        }
+
         // Skip root block content (it's just a container)
         if !block.is_root() {
+            if self.options.emit_block_ids {
+                output.push_str(&block_id_comment(block_id));
+                output.push('\n');
+            }
             // Render content based on type and role, passing depth for structural heading derivation
-            self.render_content(block, output, depth)?;
+            self.render_content(doc, block, output, depth)?;
         }
 
         // Render children with incremented depth
         if let Some(children) = doc.structure.get(block_id) {
+            let mut in_list = false;
             for child_id in children {
+                if let Some(item) = doc.get_block(child_id).filter(|b| is_list_item(b)) {
+                    self.render_list_item(doc, item, output, 0)?;
+                    in_list = true;
+                    continue;
+                }
+                if std::mem::take(&mut in_list) {
+                    output.push('\n');
+                }
                 self.render_block(doc, child_id, output, depth + 1)?;
             }
+            if in_list {
+                output.push('\n');
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render_list_item(
+        &self,
+        doc: &Document,
+        block: &Block,
+        output: &mut String,
+        level: usize,
+    ) -> Result<()> {
+        let marker = block
+            .metadata
+            .custom
+            .get(LIST_MARKER_KEY)
+            .and_then(|v| v.as_str())
+            .unwrap_or("-");
+        output.push_str(&" ".repeat(level * self.indent_size));
+        output.push_str(marker);
+        output.push(' ');
+        if let Some(task) = block.metadata.custom.get(TASK_KEY) {
+            let checked = task
+                .get("checked")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            output.push_str(if checked { "[x] " } else { "[ ] " });
+        }
+        if let Content::Text(text) = &block.content {
+            output.push_str(&self.footnotes.relabel(doc, block, &text.text));
+        }
+        // A comment line would end the list, so ids trail the item instead
+        if self.options.emit_block_ids {
+            output.push(' ');
+            output.push_str(&block_id_comment(&block.id));
         }
+        output.push('\n');
 
+        for child_id in doc.children(&block.id) {
+            if let Some(child) = doc.get_block(child_id) {
+                self.render_list_item(doc, child, output, level + 1)?;
+            }
+        }
         Ok(())
     }
 
-    fn render_content(&self, block: &Block, output: &mut String, depth: usize) -> Result<()> {
+    fn render_content(
+        &self,
+        doc: &Document,
+        block: &Block,
+        output: &mut String,
+        depth: usize,
+    ) -> Result<()> {
         // Determine the effective role, considering heading mode
         let explicit_role = block.metadata.semantic_role.as_ref().map(|r| r.category);
 
+        // Preserved syntax is emitted exactly as it was parsed
+        let is_raw = block
+            .metadata
+            .custom
+            .get(RAW_MARKDOWN_KEY)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if let (true, Content::Text(text)) = (is_raw, &block.content) {
+            output.push_str(&text.text);
+            output.push_str("\n\n");
+            return Ok(());
+        }
+
         match &block.content {
             Content::Text(text) => {
-                self.render_text(&text.text, explicit_role, depth, output);
+                let text = self.footnotes.relabel(doc, block, &text.text);
+                self.render_text(&text, explicit_role, depth, output);
+            }
+            Content::RichText(rich) => {
+                let text = render_inline(&rich.spans);
+                let text = self.footnotes.relabel(doc, block, &text);
+                self.render_text(&text, explicit_role, depth, output);
             }
             Content::Code(code) => {
-                output.push_str("```");
-                output.push_str(&code.language);
-                output.push('\n');
-                output.push_str(&code.source);
-                output.push_str("\n```\n\n");
+                self.render_fenced(&code.language, &code.source, output);
+            }
+            Content::CompressedCode { language, .. } => {
+                let source = block.content.decompress().unwrap_or_default();
+                self.render_fenced(language, &source, output);
+            }
+            Content::Diagram(diagram) => {
+                self.render_fenced(diagram.dialect.fence_tag(), &diagram.source, output);
+            }
+            Content::Diff(diff) => {
+                self.render_fenced("diff", &diff.patch, output);
             }
             Content::Table(table) => {
                 self.render_table(&table.rows, output);
             }
+            Content::Spreadsheet(sheet) => {
+                self.render_table(&sheet.to_table().rows, output);
+            }
             Content::Math(math) => {
-                if math.display_mode {
+                if math.display_mode && self.options.math_style == MathStyle::Fence {
+                    self.render_fenced("math", &math.expression, output);
+                } else if math.display_mode {
                     output.push_str("$$\n");
                     output.push_str(&math.expression);
                     output.push_str("\n$$\n\n");
@@ -139,10 +356,13 @@ impl MarkdownRenderer {
                     src
                 ));
             }
+            Content::Form(form) => self.render_form(form, output),
+            Content::GeoJson(value) => {
+                let source = serde_json::to_string_pretty(value).unwrap_or_default();
+                self.render_fenced("geojson", &source, output);
+            }
             Content::Json { value, .. } => {
-                output.push_str("```json\n");
-                output.push_str(&value.to_string());
-                output.push_str("\n```\n\n");
+                self.render_fenced("json", &value.to_string(), output);
             }
             Content::Composite { children, .. } => {
                 output.push_str(&format!("[Composite: {} children]\n\n", children.len()));
@@ -226,7 +446,9 @@ impl MarkdownRenderer {
     ) {
         // Check for heading
         if let Some(level) = self.resolve_heading_level(explicit_role, depth) {
-            let hashes = "#".repeat(level);
+            let level = (level as i64 + self.subtree_shift + self.options.heading_offset as i64)
+                .clamp(1, 6);
+            let hashes = "#".repeat(level as usize);
             output.push_str(&hashes);
             output.push(' ');
             output.push_str(text);
@@ -239,6 +461,7 @@ impl MarkdownRenderer {
 
         match role_str {
             "quote" => {
+                let text = self.line_breaks(text);
                 for line in text.lines() {
                     output.push_str("> ");
                     output.push_str(line);
@@ -247,18 +470,75 @@ impl MarkdownRenderer {
                 output.push('\n');
             }
             "list" => {
-                output.push_str(text);
-                output.push_str("\n\n");
+                // Task lists keep their items as child blocks
+                if !text.is_empty() {
+                    output.push_str(text);
+                    output.push_str("\n\n");
+                }
             }
             _ => {
                 if !text.is_empty() {
-                    output.push_str(text);
+                    output.push_str(&self.line_breaks(text));
                     output.push_str("\n\n");
                 }
             }
         }
     }
 
+    /// Mark line breaks as hard breaks when requested
+    fn line_breaks<'a>(&self, text: &'a str) -> std::borrow::Cow<'a, str> {
+        if self.options.hard_line_breaks && text.contains('\n') {
+            text.lines().collect::<Vec<_>>().join("\\\n").into()
+        } else {
+            text.into()
+        }
+    }
+
+    /// Fence a code block, lengthening the fence past any run inside it
+    fn render_fenced(&self, info: &str, source: &str, output: &mut String) {
+        let ch = match self.options.code_fence_style {
+            CodeFenceStyle::Backtick => '`',
+            CodeFenceStyle::Tilde => '~',
+        };
+        let longest = source
+            .lines()
+            .map(|line| line.trim_start().chars().take_while(|c| *c == ch).count())
+            .max()
+            .unwrap_or(0);
+        let fence = ch.to_string().repeat(longest.max(2) + 1);
+
+        output.push_str(&fence);
+        output.push_str(info);
+        output.push('\n');
+        output.push_str(source);
+        output.push('\n');
+        output.push_str(&fence);
+        output.push_str("\n\n");
+    }
+
+    /// Description list: label as the term, answer type (and default) as the definition
+    fn render_form(&self, form: &FormContent, output: &mut String) {
+        if !form.title.is_empty() {
+            output.push_str(&format!("**{}**\n\n", form.title));
+        }
+        for field in &form.fields {
+            output.push_str(&field.label);
+            if field.required {
+                output.push_str(" *(required)*");
+            }
+            output.push_str("\n: ");
+            output.push_str(field.field_type.name());
+            let options = field.field_type.options();
+            if !options.is_empty() {
+                output.push_str(&format!(" ({})", options.join(", ")));
+            }
+            if let Some(default) = &field.default_value {
+                output.push_str(&format!("; default {}", default));
+            }
+            output.push_str("\n\n");
+        }
+    }
+
     fn render_table(&self, rows: &[Row], output: &mut String) {
         if rows.is_empty() {
             return;
@@ -269,7 +549,7 @@ impl MarkdownRenderer {
         output.push('|');
         for cell in &header.cells {
             output.push(' ');
-            output.push_str(&cell_to_string(cell));
+            output.push_str(&table_cell(cell));
             output.push_str(" |");
         }
         output.push('\n');
@@ -286,7 +566,7 @@ impl MarkdownRenderer {
             output.push('|');
             for cell in &row.cells {
                 output.push(' ');
-                output.push_str(&cell_to_string(cell));
+                output.push_str(&table_cell(cell));
                 output.push_str(" |");
             }
             output.push('\n');
@@ -295,6 +575,33 @@ impl MarkdownRenderer {
     }
 }
 
+/// Trim trailing whitespace, end with a single newline and prepend front matter
+fn finish(output: &str, front_matter: Option<String>) -> String {
+    let trimmed = output.trim_end();
+    match (front_matter, trimmed.is_empty()) {
+        (None, true) => String::new(),
+        (None, false) => format!("{}\n", trimmed),
+        (Some(front_matter), true) => front_matter,
+        (Some(front_matter), false) => format!("{}\n{}\n", front_matter, trimmed),
+    }
+}
+
+fn block_id_comment(id: &BlockId) -> String {
+    format!("<!-- ucp:{} -->", id)
+}
+
+fn is_list_item(block: &Block) -> bool {
+    block.metadata.custom.contains_key(LIST_MARKER_KEY)
+}
+
+/// Cell text safe inside a GFM table row: pipes escaped, line breaks as `<br>`
+fn table_cell(cell: &Cell) -> String {
+    cell_to_string(cell)
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
 fn cell_to_string(cell: &Cell) -> String {
     match cell {
         Cell::Null => String::new(),
@@ -340,4 +647,54 @@ mod tests {
         assert!(md.contains("```rust"));
         assert!(md.contains("fn main()"));
     }
+
+    #[test]
+    fn test_render_spreadsheet_as_evaluated_table() {
+        use ucm_core::{CellValue, SpreadsheetContent};
+
+        let sheet = SpreadsheetContent::new(vec!["item".into(), "cost".into()]).with_rows(vec![
+            vec![CellValue::Text("a|b".into()), CellValue::Number(2.0)],
+            vec![CellValue::Text("c".into()), CellValue::Number(3.5)],
+            vec![
+                CellValue::Text("total".into()),
+                CellValue::Formula("=SUM(B1:B2)".into()),
+            ],
+        ]);
+        let mut doc = Document::create();
+        let root = doc.root;
+        doc.add_block(Block::new(Content::Spreadsheet(sheet), None), &root)
+            .unwrap();
+
+        let md = MarkdownRenderer::new().render(&doc).unwrap();
+        assert_eq!(
+            md,
+            "| item | cost |\n| --- | --- |\n| a\\|b | 2 |\n| c | 3.5 |\n| total | 5.5 |\n"
+        );
+    }
+
+    #[test]
+    fn test_render_form_as_description_list() {
+        use ucm_core::{FieldType, FormContent, FormField};
+
+        let form = FormContent::new("Survey")
+            .with_field(FormField::new("name", "Name", FieldType::Text).required())
+            .with_field(
+                FormField::new(
+                    "tier",
+                    "Tier",
+                    FieldType::Select(vec!["free".into(), "pro".into()]),
+                )
+                .with_default(serde_json::json!("free")),
+            );
+        let mut doc = Document::create();
+        let root = doc.root;
+        doc.add_block(Block::new(Content::Form(form), None), &root)
+            .unwrap();
+
+        let md = MarkdownRenderer::new().render(&doc).unwrap();
+        assert_eq!(
+            md,
+            "**Survey**\n\nName *(required)*\n: text\n\nTier\n: select (free, pro); default \"free\"\n"
+        );
+    }
 }
@@ -3,7 +3,13 @@
 //! These tests verify that markdown parsing and rendering produces
 //! consistent, expected output across versions.
 
-use ucp_translator_markdown::{parse_markdown, render_markdown};
+use ucm_core::{Block, Content, Document, EdgeType, SourceSpan, TranslationWarningCode};
+use ucp_translator_markdown::{
+    parse_markdown, parse_markdown_with_options, parse_markdown_with_report, render_markdown,
+    render_markdown_block, render_markdown_with_options, CodeFenceStyle, MarkdownParser, MathStyle,
+    ParseOptions, RenderOptions, FOOTNOTES_SECTION_KEY, FOOTNOTE_KEY, FRONT_MATTER_KEY,
+    RAW_MARKDOWN_KEY, REFERENCE_LINKS_KEY,
+};
 
 const SIMPLE_MD: &str = include_str!("fixtures/simple.md");
 const COMPLEX_MD: &str = include_str!("fixtures/complex.md");
@@ -183,3 +189,349 @@ fn test_special_characters() {
     assert!(rendered.contains("<html>"), "HTML-like content preserved");
     assert!(rendered.contains("&"), "Ampersand preserved");
 }
+
+/// Documents that must render back byte-for-byte
+const ROUNDTRIP_CORPUS: &[(&str, &str)] = &[
+    (
+        "commonmark_blocks",
+        include_str!("fixtures/roundtrip/commonmark_blocks.md"),
+    ),
+    ("footnotes", include_str!("fixtures/roundtrip/footnotes.md")),
+    (
+        "definition_list",
+        include_str!("fixtures/roundtrip/definition_list.md"),
+    ),
+    (
+        "html_comments",
+        include_str!("fixtures/roundtrip/html_comments.md"),
+    ),
+    ("task_list", include_str!("fixtures/roundtrip/task_list.md")),
+    (
+        "nested_task_list",
+        include_str!("fixtures/roundtrip/nested_task_list.md"),
+    ),
+];
+
+#[test]
+fn test_roundtrip_corpus_is_byte_stable() {
+    for (name, md) in ROUNDTRIP_CORPUS {
+        let doc = parse_markdown(md).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        let rendered = render_markdown(&doc).expect("Failed to render");
+        assert_eq!(*md, rendered, "{} should roundtrip exactly", name);
+    }
+}
+
+#[test]
+fn test_unsupported_syntax_preserved_as_raw_blocks() {
+    let md = include_str!("fixtures/roundtrip/definition_list.md");
+    let doc = parse_markdown(md).expect("Failed to parse");
+
+    let raw: Vec<_> = doc
+        .blocks
+        .values()
+        .filter(|b| b.metadata.custom.contains_key(RAW_MARKDOWN_KEY))
+        .collect();
+    assert_eq!(raw.len(), 2, "Each definition list is one raw block");
+}
+
+#[test]
+fn test_strict_mode_rejects_unsupported_syntax() {
+    let md = "# Title\n\nApple\n: A fruit.\n";
+    let err = parse_markdown_with_options(md, ParseOptions { strict: true }).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Parse error at line 3: unsupported construct: definition list"
+    );
+
+    let md = "# Title\n\nPlain text.\n";
+    assert!(parse_markdown_with_options(md, ParseOptions { strict: true }).is_ok());
+}
+
+#[test]
+fn test_report_lists_lossy_conversions() {
+    let md = "\u{feff}# Title\n\n<!-- note -->\n\n| a | b |\n|:--|--:|\n| 1 | 2 |\n";
+    let output = parse_markdown_with_report(md).expect("Failed to parse");
+    let warnings = &output.report.warnings;
+    assert_eq!(warnings.len(), 3);
+
+    assert_eq!(warnings[0].code, TranslationWarningCode::EncodingFixed);
+    assert_eq!(warnings[1].code, TranslationWarningCode::PreservedRaw);
+    assert_eq!(warnings[1].source_span, Some(SourceSpan::line(3)));
+    let raw = warnings[1].block_id.expect("raw block id");
+    assert!(output.document.blocks[&raw]
+        .metadata
+        .custom
+        .contains_key(RAW_MARKDOWN_KEY));
+    assert_eq!(warnings[2].code, TranslationWarningCode::DroppedElement);
+    assert_eq!(warnings[2].source_span, Some(SourceSpan::lines(5, 7)));
+
+    // Plain markdown converts cleanly
+    let output = parse_markdown_with_report(SIMPLE_MD).unwrap();
+    assert!(output.report.is_clean());
+}
+
+#[test]
+fn test_footnotes_become_referenced_blocks() {
+    let md = "# Title\n\nFirst[^a] and [^b].\n\nAgain[^a].\n\n[^a]: Note A.\n\n[^b]: Note B\n    continued.\n";
+    let doc = parse_markdown(md).expect("Failed to parse");
+
+    let section = *doc.children(&doc.root).last().unwrap();
+    assert!(doc.blocks[&section]
+        .metadata
+        .custom
+        .contains_key(FOOTNOTES_SECTION_KEY));
+    let footnotes = doc.children(&section);
+    assert_eq!(footnotes.len(), 2);
+    assert_eq!(doc.blocks[&footnotes[0]].metadata.custom[FOOTNOTE_KEY], "a");
+    assert_eq!(
+        doc.blocks[&footnotes[1]].content,
+        Content::text("Note B\ncontinued.")
+    );
+
+    let heading = doc.children(&doc.root)[0];
+    let paragraphs = doc.children(&heading);
+    let targets = |id| -> Vec<_> {
+        doc.blocks[id]
+            .edges_of_type(&EdgeType::References)
+            .iter()
+            .map(|e| e.target)
+            .collect()
+    };
+    assert_eq!(targets(&paragraphs[0]), footnotes.to_vec());
+    assert_eq!(targets(&paragraphs[1]), vec![footnotes[0]]);
+
+    assert_eq!(render_markdown(&doc).unwrap(), md);
+}
+
+#[test]
+fn test_footnotes_renumber_after_move() {
+    let md = "A[^1].\n\nB[^2].\n\n[^1]: one\n\n[^2]: two\n";
+    let mut doc = parse_markdown(md).expect("Failed to parse");
+    let blocks = doc.children(&doc.root).to_vec();
+    let (a, b) = (blocks[0], blocks[1]);
+
+    doc.move_block_before(&b, &a).unwrap();
+    assert_eq!(
+        render_markdown(&doc).unwrap(),
+        "B[^1].\n\nA[^2].\n\n[^1]: two\n\n[^2]: one\n"
+    );
+
+    // Named labels keep their names; only their order follows the text
+    let md = "A[^x] and [^1].\n\nB[^2].\n\n[^x]: ex\n\n[^1]: one\n\n[^2]: two\n";
+    let mut doc = parse_markdown(md).expect("Failed to parse");
+    let blocks = doc.children(&doc.root).to_vec();
+    doc.move_block_before(&blocks[1], &blocks[0]).unwrap();
+    assert_eq!(
+        render_markdown(&doc).unwrap(),
+        "B[^1].\n\nA[^x] and [^2].\n\n[^1]: two\n\n[^x]: ex\n\n[^2]: one\n"
+    );
+}
+
+#[test]
+fn test_subtree_render_includes_cited_definitions() {
+    let md = "# One\n\nSee [docs][d][^1].\n\n# Two\n\nSee [api][a][^2].\n\n[d]: https://example.com/docs\n[a]: https://example.com/api \"API\"\n\n[^1]: first\n\n[^2]: second\n";
+    let doc = parse_markdown(md).expect("Failed to parse");
+    assert_eq!(
+        doc.metadata.custom[REFERENCE_LINKS_KEY],
+        serde_json::json!([
+            {"label": "d", "url": "https://example.com/docs"},
+            {"label": "a", "url": "https://example.com/api", "title": "API"}
+        ])
+    );
+    assert_eq!(render_markdown(&doc).unwrap(), md);
+
+    let two = doc.children(&doc.root)[1];
+    assert_eq!(
+        render_markdown_block(&doc, &two, RenderOptions::default()).unwrap(),
+        "# Two\n\nSee [api][a][^1].\n\n[a]: https://example.com/api \"API\"\n\n[^1]: second\n"
+    );
+}
+
+#[test]
+fn test_front_matter_maps_to_document_metadata() {
+    let md = "---\ntitle: Guide\nauthors:\n  - Ada\nlanguage: en\ntags:\n  - intro\n---\n\n# Intro\n\nText.\n";
+    let doc = parse_markdown(md).expect("Failed to parse");
+
+    assert_eq!(doc.metadata.title.as_deref(), Some("Guide"));
+    assert_eq!(doc.metadata.authors, vec!["Ada"]);
+    assert_eq!(doc.metadata.language.as_deref(), Some("en"));
+    assert_eq!(
+        doc.metadata.custom[FRONT_MATTER_KEY],
+        serde_json::json!({"tags": ["intro"]})
+    );
+
+    // Front matter is only emitted on request
+    assert_eq!(render_markdown(&doc).unwrap(), "# Intro\n\nText.\n");
+    let options = RenderOptions {
+        include_front_matter: true,
+        ..Default::default()
+    };
+    assert_eq!(render_markdown_with_options(&doc, options).unwrap(), md);
+}
+
+#[test]
+fn test_toml_front_matter_roundtrip() {
+    let md = "+++\ntitle = \"Guide\"\nweight = 3\n+++\n\nText.\n";
+    let doc = parse_markdown(md).expect("Failed to parse");
+    assert_eq!(doc.metadata.title.as_deref(), Some("Guide"));
+
+    let options = RenderOptions {
+        include_front_matter: true,
+        ..Default::default()
+    };
+    assert_eq!(render_markdown_with_options(&doc, options).unwrap(), md);
+}
+
+#[test]
+fn test_malformed_front_matter_is_a_warning() {
+    let md = "---\ntitle: [oops\n---\n\nText.\n";
+    let output = MarkdownParser::new()
+        .parse_with_report(md)
+        .expect("Malformed front matter must not fail the parse");
+
+    let warnings = &output.report.warnings;
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].code, TranslationWarningCode::InvalidMetadata);
+    assert_eq!(warnings[0].source_span, Some(SourceSpan::lines(1, 3)));
+    assert_eq!(output.document.metadata.title, None);
+    assert_eq!(output.document.block_count(), 2);
+}
+
+#[test]
+fn test_render_options_heading_offset_and_fences() {
+    let md = "# Title\n\n## Section\n\n```rust\nfn main() {}\n```\n";
+    let doc = parse_markdown(md).expect("Failed to parse");
+
+    let options = RenderOptions {
+        heading_offset: 2,
+        code_fence_style: CodeFenceStyle::Tilde,
+        ..Default::default()
+    };
+    assert_eq!(
+        render_markdown_with_options(&doc, options).unwrap(),
+        "### Title\n\n#### Section\n\n~~~rust\nfn main() {}\n~~~\n"
+    );
+
+    // Offsets clamp to H1-H6
+    let options = RenderOptions {
+        heading_offset: -3,
+        ..Default::default()
+    };
+    assert!(render_markdown_with_options(&doc, options)
+        .unwrap()
+        .starts_with("# Title\n\n# Section\n\n"));
+}
+
+#[test]
+fn test_render_options_block_ids_and_hard_breaks() {
+    let md = "# Title\n\nline one\nline two\n\n- [ ] todo\n";
+    let doc = parse_markdown(md).expect("Failed to parse");
+    let ids: Vec<String> = doc
+        .descendants(&doc.root)
+        .iter()
+        .map(|id| id.to_string())
+        .collect();
+
+    let options = RenderOptions {
+        emit_block_ids: true,
+        hard_line_breaks: true,
+        ..Default::default()
+    };
+    let rendered = render_markdown_with_options(&doc, options).unwrap();
+    for id in &ids {
+        assert_eq!(
+            rendered.matches(&format!("<!-- ucp:{} -->", id)).count(),
+            1,
+            "missing id comment for {}",
+            id
+        );
+    }
+    assert!(rendered.contains("line one\\\nline two\n"));
+    // Comment lines would end a list, so list items carry theirs inline
+    assert!(rendered.contains("- [ ] todo <!-- ucp:"));
+
+    // Defaults are unchanged
+    assert_eq!(render_markdown(&doc).unwrap(), md);
+}
+
+#[test]
+fn test_code_fence_outgrows_fences_in_source() {
+    let mut doc = Document::create();
+    let root = doc.root;
+    doc.add_block(
+        Block::new(
+            Content::code("markdown", "```rust\nfn main() {}\n```"),
+            None,
+        ),
+        &root,
+    )
+    .unwrap();
+
+    assert_eq!(
+        render_markdown(&doc).unwrap(),
+        "````markdown\n```rust\nfn main() {}\n```\n````\n"
+    );
+}
+
+#[test]
+fn test_render_block_subtree() {
+    let md = "# Guide\n\nIntro.\n\n## Install\n\nRun it.\n\n### Linux\n\nUse apt.\n\n## Usage\n\nCall it.\n";
+    let doc = parse_markdown(md).unwrap();
+    let find = |text: &str| {
+        doc.blocks
+            .values()
+            .find(|b| matches!(&b.content, Content::Text(t) if t.text == text))
+            .map(|b| b.id)
+            .unwrap()
+    };
+
+    // The section's heading becomes H1 and its descendants keep their spacing
+    let install = find("Install");
+    assert_eq!(
+        render_markdown_block(&doc, &install, RenderOptions::default()).unwrap(),
+        "# Install\n\nRun it.\n\n## Linux\n\nUse apt.\n"
+    );
+    let options = RenderOptions {
+        heading_offset: 1,
+        ..Default::default()
+    };
+    assert_eq!(
+        render_markdown_block(&doc, &install, options).unwrap(),
+        "## Install\n\nRun it.\n\n### Linux\n\nUse apt.\n"
+    );
+
+    // Leaf blocks render on their own
+    assert_eq!(
+        render_markdown_block(&doc, &find("Use apt."), RenderOptions::default()).unwrap(),
+        "Use apt.\n"
+    );
+
+    // The root renders the whole document
+    assert_eq!(
+        render_markdown_block(&doc, &doc.root, RenderOptions::default()).unwrap(),
+        md
+    );
+
+    let missing = "blk_000000000000000000000000".parse().unwrap();
+    assert!(render_markdown_block(&doc, &missing, RenderOptions::default()).is_err());
+}
+
+#[test]
+fn test_math_roundtrip_and_fence_style() {
+    let md = "# Proof\n\nThe mean $\\bar{x}$ is below.\n\n$$\n\\bar{x} = \\frac{1}{n}\\sum x_i\n$$\n\n$\\mu$\n";
+    let doc = parse_markdown(md).unwrap();
+    assert_eq!(render_markdown(&doc).unwrap(), md);
+
+    let options = RenderOptions {
+        math_style: MathStyle::Fence,
+        ..Default::default()
+    };
+    let fenced = render_markdown_with_options(&doc, options).unwrap();
+    assert!(fenced.contains("```math\n\\bar{x} = \\frac{1}{n}\\sum x_i\n```\n"));
+    // Inline math keeps its dollars
+    assert!(fenced.contains("\n$\\mu$\n"));
+    assert_eq!(
+        render_markdown(&parse_markdown(&fenced).unwrap()).unwrap(),
+        md
+    );
+}
@@ -0,0 +1,322 @@
+//! Diagnostics, hover and completion for UCL source.
+
+use serde_json::{json, Value};
+use ucl_parser::lexer::Lexer;
+use ucl_parser::{parse_any, ParseError, TokenKind};
+
+/// Section headers and commands: keyword, syntax, description
+pub const KEYWORDS: &[(&str, &str, &str)] = &[
+    (
+        "STRUCTURE",
+        "STRUCTURE",
+        "Section declaring parent to children adjacency.",
+    ),
+    ("BLOCKS", "BLOCKS", "Section defining block content."),
+    (
+        "COMMANDS",
+        "COMMANDS",
+        "Section of transformation commands.",
+    ),
+    (
+        "EDIT",
+        "EDIT <block_id> SET <path> <operator> <value> [WHERE <condition>]",
+        "Modify block content or metadata.",
+    ),
+    (
+        "MOVE",
+        "MOVE <block_id> TO <parent_id> [AT <index>]\nMOVE <block_id> BEFORE|AFTER <sibling_id>",
+        "Move a block to a new location in the document structure.",
+    ),
+    (
+        "APPEND",
+        "APPEND <parent_id> <content_type> [AT <index>] [WITH <properties>] :: <content>",
+        "Add a new block to the document.",
+    ),
+    (
+        "DELETE",
+        "DELETE <block_id> [CASCADE] [PRESERVE_CHILDREN]\nDELETE WHERE <condition>",
+        "Remove a block from the document.",
+    ),
+    (
+        "PRUNE",
+        "PRUNE UNREACHABLE [DRY_RUN]\nPRUNE WHERE <condition> [DRY_RUN]",
+        "Remove unreachable blocks or blocks matching a condition.",
+    ),
+    (
+        "FOLD",
+        "FOLD <block_id> [DEPTH <n>] [MAX_TOKENS <n>] [PRESERVE_TAGS <tags>]",
+        "Collapse content for context management.",
+    ),
+    (
+        "LINK",
+        "LINK <source_id | [<id>, ...]> <edge_type> <target_id> [WEIGHT <n>] [VALID [FROM <date>] [TO <date>]] [WITH <properties>]",
+        "Add a relationship edge between blocks.",
+    ),
+    (
+        "UNLINK",
+        "UNLINK <source_id> <edge_type> <target_id>",
+        "Remove a relationship edge between blocks.",
+    ),
+    (
+        "SNAPSHOT",
+        "SNAPSHOT CREATE|RESTORE|DELETE \"<name>\"\nSNAPSHOT LIST\nSNAPSHOT DIFF \"<a>\" \"<b>\"",
+        "Manage document versions.",
+    ),
+    (
+        "BEGIN",
+        "BEGIN TRANSACTION [\"<name>\"]",
+        "Start a transaction.",
+    ),
+    (
+        "COMMIT",
+        "COMMIT [\"<name>\"]",
+        "Commit the open transaction.",
+    ),
+    (
+        "ROLLBACK",
+        "ROLLBACK [\"<name>\"]",
+        "Discard the open transaction.",
+    ),
+    (
+        "ATOMIC",
+        "ATOMIC { <command> ... }",
+        "Execute multiple commands atomically.",
+    ),
+    (
+        "EXPLAIN",
+        "EXPLAIN <command>",
+        "Describe what a command would do instead of executing it.",
+    ),
+    (
+        "WRITE_SECTION",
+        "WRITE_SECTION <section_id> :: <markdown> [BASE_LEVEL <n>]",
+        "Replace a section's children with blocks parsed from Markdown.",
+    ),
+    (
+        "GOTO",
+        "GOTO <block_id>",
+        "Navigate the agent cursor to a block.",
+    ),
+    (
+        "BACK",
+        "BACK [<n>]",
+        "Return to a previous cursor position.",
+    ),
+    (
+        "EXPAND",
+        "EXPAND <block_id> DOWN|UP|BOTH|SEMANTIC [depth=N] [mode=MODE] [roles=...] [tags=...]",
+        "Expand the neighbourhood of a block.",
+    ),
+    (
+        "FOLLOW",
+        "FOLLOW <block_id> <edge_type>[,<edge_type>...] [<target_id>]",
+        "Follow edges from a block.",
+    ),
+    (
+        "PATH",
+        "PATH <block_id> TO <block_id> [max=N]",
+        "Find a path between two blocks.",
+    ),
+    (
+        "SEARCH",
+        "SEARCH \"<query>\" [limit=N] [min_similarity=F] [roles=...]",
+        "Semantic search over blocks.",
+    ),
+    (
+        "FIND",
+        "FIND [role=...] [tag=...] [label=...] [pattern=...]",
+        "Find blocks by role, tag, label or content pattern.",
+    ),
+    (
+        "VIEW",
+        "VIEW <block_id>|NEIGHBORHOOD [mode=...] [depth=N]",
+        "View a block or the cursor neighbourhood.",
+    ),
+    (
+        "CTX",
+        "CTX ADD|REMOVE|CLEAR|EXPAND|COMPRESS|PRUNE|RENDER|STATS|FOCUS ...",
+        "Emit context management events.",
+    ),
+];
+
+/// Content types usable in APPEND and BLOCKS
+pub const CONTENT_TYPES: &[(&str, &str)] = &[
+    ("text", "Plain or Markdown text."),
+    ("table", "Tabular data with optional schema."),
+    ("code", "Source code with a language hint."),
+    ("math", "Mathematical expression."),
+    ("media", "Image, audio or video reference."),
+    ("json", "Structured JSON value."),
+    ("binary", "Raw binary data."),
+    ("composite", "Container laid out from child blocks."),
+    ("form", "Form with typed fields."),
+    ("diff", "Unified diff, git patch or JSON Patch."),
+];
+
+/// Diagnostics for `text`; at most one, since the parser stops at the
+/// first error
+pub fn diagnostics(text: &str) -> Vec<Value> {
+    match parse_any(text) {
+        Ok(_) => Vec::new(),
+        Err(error) => vec![json!({
+            "range": error_range(text, &error),
+            "severity": 1,
+            "source": "ucl",
+            "message": error.to_string(),
+        })],
+    }
+}
+
+/// Markdown documentation for the keyword or content type under the cursor
+pub fn hover(text: &str, line: u32, character: u32) -> Option<String> {
+    let offset = offset_at(text, line, character)?;
+    let token = Lexer::new(text)
+        .filter_map(|t| t.ok())
+        .find(|t| t.span.start <= offset && offset < t.span.end.max(t.span.start + 1))?;
+    if matches!(
+        token.kind,
+        TokenKind::DoubleString(_) | TokenKind::SingleString(_) | TokenKind::TableLiteral(_)
+    ) {
+        return None;
+    }
+    let word = &text[token.span];
+    if let Some((name, syntax, description)) = KEYWORDS
+        .iter()
+        .find(|(name, _, _)| name.eq_ignore_ascii_case(word))
+    {
+        return Some(format!(
+            "**{}**: {}\n\n```ucl\n{}\n```",
+            name, description, syntax
+        ));
+    }
+    CONTENT_TYPES
+        .iter()
+        .find(|(name, _)| *name == word)
+        .map(|(name, description)| format!("**{}** content: {}", name, description))
+}
+
+/// Completion items: keywords, content types and the given block IDs
+pub fn completions(block_ids: &[String]) -> Vec<Value> {
+    const KEYWORD: u32 = 14;
+    const TYPE: u32 = 22;
+    const REFERENCE: u32 = 18;
+
+    let keywords = KEYWORDS.iter().map(|(name, syntax, description)| {
+        json!({
+            "label": name,
+            "kind": KEYWORD,
+            "detail": syntax,
+            "documentation": description,
+        })
+    });
+    let types = CONTENT_TYPES.iter().map(
+        |(name, description)| json!({"label": name, "kind": TYPE, "documentation": description}),
+    );
+    let ids = block_ids
+        .iter()
+        .map(|id| json!({"label": id, "kind": REFERENCE, "detail": "block"}));
+    keywords.chain(types).chain(ids).collect()
+}
+
+/// Range covering all of `text`
+pub fn full_range(text: &str) -> Value {
+    json!({"start": position_at(text, 0), "end": position_at(text, text.len())})
+}
+
+fn error_range(text: &str, error: &ParseError) -> Value {
+    let (start, end) = match error {
+        ParseError::UnexpectedToken { line, column, .. } if *line > 0 => {
+            let start = line_start(text, *line)
+                .map(|start| advance_chars(text, start, column.saturating_sub(1)))
+                .unwrap_or(text.len());
+            (start, word_end(text, start))
+        }
+        ParseError::InvalidSyntax { line, .. } if *line > 0 => {
+            let start = line_start(text, *line).unwrap_or(text.len());
+            let end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+            (start, end)
+        }
+        ParseError::LexerError { position } => {
+            let start = (*position).min(text.len());
+            (start, word_end(text, start))
+        }
+        _ => (text.len(), text.len()),
+    };
+    json!({"start": position_at(text, start), "end": position_at(text, end)})
+}
+
+/// Byte offset where 1-based `line` starts
+fn line_start(text: &str, line: usize) -> Option<usize> {
+    if line == 1 {
+        return Some(0);
+    }
+    text.match_indices('\n').nth(line - 2).map(|(i, _)| i + 1)
+}
+
+fn advance_chars(text: &str, start: usize, chars: usize) -> usize {
+    text[start..]
+        .char_indices()
+        .nth(chars)
+        .map_or(text.len(), |(i, _)| start + i)
+}
+
+fn word_end(text: &str, start: usize) -> usize {
+    text[start..]
+        .find(char::is_whitespace)
+        .map_or(text.len(), |i| start + i)
+}
+
+/// Byte offset of an LSP position (UTF-16 columns)
+pub fn offset_at(text: &str, line: u32, character: u32) -> Option<usize> {
+    let start = line_start(text, line as usize + 1)?;
+    let mut units = 0;
+    for (i, c) in text[start..].char_indices() {
+        if units >= character as usize || c == '\n' {
+            return Some(start + i);
+        }
+        units += c.len_utf16();
+    }
+    Some(text.len())
+}
+
+/// LSP position (UTF-16 columns) of a byte offset
+pub fn position_at(text: &str, offset: usize) -> Value {
+    let before = &text[..offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let character: usize = before[line_start..].chars().map(char::len_utf16).sum();
+    json!({"line": line, "character": character})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostic_points_at_bad_token() {
+        let text = "EDIT blk_111111111111 SET content.text = \"ok\"\nMOVE blk_111111111111 ??? x\n";
+        let diagnostics = diagnostics(text);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+        assert!(diagnostics[0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("line 2"));
+        assert!(super::diagnostics("EDIT blk_111111111111 SET content.text = \"ok\"").is_empty());
+    }
+
+    #[test]
+    fn test_hover_describes_command_and_content_type() {
+        let text = "append blk_111111111111 code :: \"x\"";
+        assert!(hover(text, 0, 2).unwrap().contains("Add a new block"));
+        assert!(hover(text, 0, 25).unwrap().starts_with("**code** content"));
+        assert_eq!(hover(text, 0, 33), None);
+    }
+
+    #[test]
+    fn test_positions_use_utf16_columns() {
+        let text = "a\n\u{1F600}b";
+        assert_eq!(offset_at(text, 1, 2), Some(6));
+        assert_eq!(position_at(text, 6), json!({"line": 1, "character": 2}));
+    }
+}
@@ -0,0 +1,17 @@
+//! # UCL Language Server
+//!
+//! A Language Server Protocol server for the Unified Content Language,
+//! speaking JSON-RPC over stdio. It reports parse errors as diagnostics on
+//! open and change, hovers command and content-type documentation, completes
+//! keywords, content types and block IDs, formats with
+//! [`ucl_parser::format`] and serves semantic tokens from
+//! [`ucl_parser::highlight`].
+//!
+//! Documents are synced in full; the server keeps no state beyond the open
+//! texts and the block IDs passed to [`Server::with_block_ids`].
+
+pub mod analysis;
+pub mod rpc;
+mod server;
+
+pub use server::{run, Server};
@@ -0,0 +1,44 @@
+//! `ucp-lsp`: UCL language server over stdio.
+//!
+//! Usage: `ucp-lsp [--document <portable.json>]`. With `--document`, block
+//! IDs from that UCP document are offered as completions.
+
+use std::io;
+
+use anyhow::{bail, Context, Result};
+use ucl_lsp::{run, Server};
+use ucm_core::Document;
+
+fn main() -> Result<()> {
+    let mut server = Server::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--document" => {
+                let path = args.next().context("--document needs a path")?;
+                server = server.with_block_ids(load_block_ids(&path)?);
+            }
+            // Editors commonly pass the transport explicitly
+            "--stdio" => {}
+            "-h" | "--help" => {
+                println!("Usage: ucp-lsp [--document <portable.json>]");
+                return Ok(());
+            }
+            other => bail!("unknown argument {}", other),
+        }
+    }
+
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    run(&mut server, &mut stdin.lock(), &mut stdout.lock())?;
+    Ok(())
+}
+
+fn load_block_ids(path: &str) -> Result<Vec<String>> {
+    let json = std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path))?;
+    let doc =
+        Document::from_portable_json(&json).with_context(|| format!("failed to parse {}", path))?;
+    let mut ids: Vec<String> = doc.blocks.keys().map(|id| id.to_string()).collect();
+    ids.sort();
+    Ok(ids)
+}
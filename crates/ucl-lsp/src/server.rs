@@ -0,0 +1,291 @@
+//! Request dispatch and open-document state.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use serde_json::{json, Value};
+use ucl_parser::highlight::{self, HighlightKind};
+
+use crate::analysis;
+use crate::rpc::{read_message, write_message};
+
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Language server state: open documents and the block IDs offered as
+/// completions
+#[derive(Debug, Default)]
+pub struct Server {
+    documents: HashMap<String, String>,
+    block_ids: Vec<String>,
+    shutdown: bool,
+    exited: bool,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Offer these block IDs as completions
+    pub fn with_block_ids(mut self, block_ids: Vec<String>) -> Self {
+        self.block_ids = block_ids;
+        self
+    }
+
+    /// Whether the client sent `exit`
+    pub fn exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Handle one incoming message, returning the messages to send back
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let Some(id) = message.get("id").cloned() else {
+            return self.notify(method, params);
+        };
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "completionProvider": {},
+                    "documentFormattingProvider": true,
+                    "semanticTokensProvider": {
+                        "legend": {
+                            "tokenTypes": HighlightKind::ALL.map(HighlightKind::token_type),
+                            "tokenModifiers": [],
+                        },
+                        "full": true,
+                    },
+                },
+                "serverInfo": {"name": "ucp-lsp", "version": env!("CARGO_PKG_VERSION")},
+            }),
+            "shutdown" => {
+                self.shutdown = true;
+                Value::Null
+            }
+            "textDocument/hover" => self
+                .text(params)
+                .and_then(|text| {
+                    let position = &params["position"];
+                    analysis::hover(
+                        text,
+                        position["line"].as_u64()? as u32,
+                        position["character"].as_u64()? as u32,
+                    )
+                })
+                .map_or(
+                    Value::Null,
+                    |value| json!({"contents": {"kind": "markdown", "value": value}}),
+                ),
+            "textDocument/completion" => Value::Array(analysis::completions(&self.block_ids)),
+            "textDocument/formatting" => {
+                self.text(params)
+                    .map_or(Value::Null, |text| match ucl_parser::format(text) {
+                        Ok(formatted) if formatted != text => json!([{
+                            "range": analysis::full_range(text),
+                            "newText": formatted,
+                        }]),
+                        Ok(_) => json!([]),
+                        Err(_) => Value::Null,
+                    })
+            }
+            "textDocument/semanticTokens/full" => self.text(params).map_or(Value::Null, |text| {
+                json!({"data": highlight::to_vscode_semantic_tokens(text, &highlight::tokens(text))})
+            }),
+            _ => {
+                return vec![json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {"code": METHOD_NOT_FOUND, "message": format!("unknown method {}", method)},
+                })]
+            }
+        };
+        vec![json!({"jsonrpc": "2.0", "id": id, "result": result})]
+    }
+
+    fn notify(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        match method {
+            "exit" => {
+                self.exited = true;
+                Vec::new()
+            }
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents.insert(uri.clone(), text.to_string());
+                vec![self.publish_diagnostics(&uri)]
+            }
+            "textDocument/didChange" => {
+                // Full sync: the last change holds the whole text
+                let changes = params["contentChanges"].as_array();
+                let Some(text) = changes.and_then(|c| c.last()?["text"].as_str()) else {
+                    return Vec::new();
+                };
+                self.documents.insert(uri.clone(), text.to_string());
+                vec![self.publish_diagnostics(&uri)]
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                vec![json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": {"uri": uri, "diagnostics": []},
+                })]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn publish_diagnostics(&self, uri: &str) -> Value {
+        let diagnostics = self
+            .documents
+            .get(uri)
+            .map(|text| analysis::diagnostics(text))
+            .unwrap_or_default();
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {"uri": uri, "diagnostics": diagnostics},
+        })
+    }
+
+    fn text(&self, params: &Value) -> Option<&str> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        self.documents.get(uri).map(String::as_str)
+    }
+}
+
+/// Serve messages from `reader` until the client exits or closes the stream
+pub fn run(
+    server: &mut Server,
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    while let Some(message) = read_message(reader)? {
+        for reply in server.handle(&message) {
+            write_message(writer, &reply)?;
+        }
+        if server.exited() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(server: &mut Server, text: &str) -> Vec<Value> {
+        server.handle(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {"textDocument": {"uri": "file:///a.ucl", "languageId": "ucl", "version": 1, "text": text}},
+        }))
+    }
+
+    fn request(server: &mut Server, method: &str, params: Value) -> Value {
+        let mut replies =
+            server.handle(&json!({"jsonrpc": "2.0", "id": 7, "method": method, "params": params}));
+        assert_eq!(replies.len(), 1);
+        replies.remove(0)
+    }
+
+    #[test]
+    fn test_session() {
+        let mut server = Server::new().with_block_ids(vec!["blk_aaaaaaaaaaaa".to_string()]);
+        let init = request(&mut server, "initialize", json!({}));
+        assert_eq!(init["result"]["capabilities"]["hoverProvider"], true);
+
+        let published = open(
+            &mut server,
+            "edit blk_111111111111 SET content.text =   \"x\"\n",
+        );
+        assert_eq!(published[0]["params"]["diagnostics"], json!([]));
+
+        let doc = json!({"textDocument": {"uri": "file:///a.ucl"}});
+        let hover = request(
+            &mut server,
+            "textDocument/hover",
+            json!({"textDocument": {"uri": "file:///a.ucl"}, "position": {"line": 0, "character": 1}}),
+        );
+        assert!(hover["result"]["contents"]["value"]
+            .as_str()
+            .unwrap()
+            .starts_with("**EDIT**"));
+
+        let completion = request(&mut server, "textDocument/completion", doc.clone());
+        let labels: Vec<_> = completion["result"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["label"].as_str().unwrap())
+            .collect();
+        assert!(labels.contains(&"APPEND"));
+        assert!(labels.contains(&"code"));
+        assert!(labels.contains(&"blk_aaaaaaaaaaaa"));
+
+        let edits = request(&mut server, "textDocument/formatting", doc);
+        assert_eq!(
+            edits["result"][0]["newText"],
+            "EDIT blk_111111111111 SET content.text = \"x\"\n"
+        );
+
+        let tokens = request(
+            &mut server,
+            "textDocument/semanticTokens/full",
+            json!({"textDocument": {"uri": "file:///a.ucl"}}),
+        );
+        assert_eq!(
+            tokens["result"]["data"].as_array().unwrap()[..5],
+            [0, 0, 4, 0, 0]
+        );
+        assert_eq!(
+            init["result"]["capabilities"]["semanticTokensProvider"]["legend"]["tokenTypes"][1],
+            "variable"
+        );
+
+        let unknown = request(&mut server, "workspace/symbol", json!({}));
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_did_change_reports_parse_errors() {
+        let mut server = Server::new();
+        open(&mut server, "");
+        let published = server.handle(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didChange",
+            "params": {
+                "textDocument": {"uri": "file:///a.ucl", "version": 2},
+                "contentChanges": [{"text": "MOVE blk_111111111111 nowhere"}],
+            },
+        }));
+        let diagnostics = &published[0]["params"]["diagnostics"];
+        assert_eq!(diagnostics.as_array().unwrap().len(), 1);
+        assert_eq!(diagnostics[0]["range"]["start"]["character"], 22);
+    }
+
+    #[test]
+    fn test_run_stops_on_exit() {
+        let mut input = Vec::new();
+        for message in [
+            json!({"jsonrpc": "2.0", "id": 1, "method": "shutdown"}),
+            json!({"jsonrpc": "2.0", "method": "exit"}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "shutdown"}),
+        ] {
+            write_message(&mut input, &message).unwrap();
+        }
+        let mut output = Vec::new();
+        run(&mut Server::new(), &mut input.as_slice(), &mut output).unwrap();
+        let mut output = output.as_slice();
+        let reply = read_message(&mut output).unwrap().unwrap();
+        assert_eq!(reply["id"], 1);
+        assert_eq!(read_message(&mut output).unwrap(), None);
+    }
+}
@@ -1,5 +1,6 @@
 //! Abstract Syntax Tree for UCL documents.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -55,6 +56,8 @@ pub enum ContentType {
     Json,
     Binary,
     Composite,
+    Form,
+    Diff,
 }
 
 impl ContentType {
@@ -68,6 +71,8 @@ impl ContentType {
             "json" => Some(Self::Json),
             "binary" => Some(Self::Binary),
             "composite" => Some(Self::Composite),
+            "form" => Some(Self::Form),
+            "diff" => Some(Self::Diff),
             _ => None,
         }
     }
@@ -89,6 +94,7 @@ pub enum Command {
     Transaction(TransactionCommand),
     Atomic(Vec<Command>),
     WriteSection(WriteSectionCommand),
+    Explain(ExplainCommand),
 
     // Agent traversal commands
     Goto(GotoCommand),
@@ -182,8 +188,13 @@ pub struct FoldCommand {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LinkCommand {
     pub source_id: String,
+    /// Further sources from the `[a, b]` form; non-empty makes this a hyperedge
+    pub additional_sources: Vec<String>,
     pub edge_type: String,
     pub target_id: String,
+    pub weight: Option<f64>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_to: Option<DateTime<Utc>>,
     pub metadata: HashMap<String, Value>,
 }
 
@@ -234,6 +245,12 @@ pub struct WriteSectionCommand {
     pub base_heading_level: Option<usize>,
 }
 
+/// EXPLAIN command - describe another command instead of executing it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExplainCommand {
+    pub inner: Box<Command>,
+}
+
 // ============================================================================
 // Agent Traversal Commands
 // ============================================================================
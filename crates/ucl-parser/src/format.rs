@@ -0,0 +1,153 @@
+//! Canonical layout for UCL source.
+
+use logos::Logos;
+
+use crate::lexer::TokenKind;
+use crate::parse_any;
+use crate::parser::ParseResult;
+
+const INDENT: &str = "    ";
+
+/// Format UCL source.
+///
+/// Command and section keywords that start a line are upper-cased, runs of
+/// spaces between tokens collapse to one, lines inside `{ ... }` are indented
+/// four spaces per level, blank lines collapse to one and each section header
+/// is preceded by a blank line. Strings, tables and comments are kept as
+/// written.
+///
+/// Fails if `input` does not parse. If the formatted text would parse
+/// differently, `input` is returned unchanged.
+pub fn format(input: &str) -> ParseResult<String> {
+    let original = parse_any(input)?;
+    let formatted = layout(input);
+    match parse_any(&formatted) {
+        Ok(doc) if doc == original => Ok(formatted),
+        _ => Ok(input.to_string()),
+    }
+}
+
+fn layout(input: &str) -> String {
+    let mut lines = vec![Vec::new()];
+    for (kind, span) in TokenKind::lexer(input).spanned() {
+        match kind {
+            Ok(TokenKind::Newline) => lines.push(Vec::new()),
+            kind => lines.last_mut().unwrap().push((kind.ok(), span)),
+        }
+    }
+
+    let mut out = String::new();
+    let mut depth = 0usize;
+    let mut blank = false;
+    for line in lines {
+        let Some((first, _)) = line.first() else {
+            blank = true;
+            continue;
+        };
+        let is_section = matches!(
+            first,
+            Some(TokenKind::Structure | TokenKind::Blocks | TokenKind::Commands)
+        );
+        if !out.is_empty() && (blank || is_section) {
+            out.push('\n');
+        }
+        blank = false;
+
+        let leading_closes = line
+            .iter()
+            .take_while(|(kind, _)| *kind == Some(TokenKind::RBrace))
+            .count();
+        for _ in 0..depth.saturating_sub(leading_closes) {
+            out.push_str(INDENT);
+        }
+
+        let mut prev_end = None;
+        for (i, (kind, span)) in line.iter().enumerate() {
+            if let Some(end) = prev_end {
+                let gap = &input[end..span.start];
+                if gap.chars().all(|c| c == ' ' || c == '\t') {
+                    if !gap.is_empty() {
+                        out.push(' ');
+                    }
+                } else {
+                    out.push_str(gap);
+                }
+            }
+            let text = &input[span.clone()];
+            if i == 0 && kind.as_ref().is_some_and(starts_statement) {
+                out.push_str(&text.to_uppercase());
+            } else {
+                out.push_str(text);
+            }
+            prev_end = Some(span.end);
+        }
+        out.push('\n');
+
+        for (kind, _) in &line {
+            match kind {
+                Some(TokenKind::LBrace) => depth += 1,
+                Some(TokenKind::RBrace) => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+    out
+}
+
+/// Keywords that begin a section or a command
+pub(crate) fn starts_statement(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Structure
+            | TokenKind::Blocks
+            | TokenKind::Commands
+            | TokenKind::Edit
+            | TokenKind::Move
+            | TokenKind::Append
+            | TokenKind::Delete
+            | TokenKind::Prune
+            | TokenKind::Link
+            | TokenKind::Unlink
+            | TokenKind::Fold
+            | TokenKind::Snapshot
+            | TokenKind::Begin
+            | TokenKind::Commit
+            | TokenKind::Rollback
+            | TokenKind::Atomic
+            | TokenKind::Explain
+            | TokenKind::WriteSection
+            | TokenKind::Goto
+            | TokenKind::Back
+            | TokenKind::Expand
+            | TokenKind::Follow
+            | TokenKind::Path
+            | TokenKind::Search
+            | TokenKind::Find
+            | TokenKind::View
+            | TokenKind::Ctx
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_layout() {
+        let input = "  structure\nblk_000000000000:   [blk_111111111111]\n\n\n\
+                     blocks\ntext #blk_111111111111 :: \"two  spaces\"\ncommands\n\
+                     edit blk_111111111111 SET content.text = \"x\"   \n\
+                     atomic {\nappend blk_000000000000 text :: \"a\"\n  }\n";
+        let expected = "STRUCTURE\nblk_000000000000: [blk_111111111111]\n\n\
+                        BLOCKS\ntext #blk_111111111111 :: \"two  spaces\"\n\n\
+                        COMMANDS\nEDIT blk_111111111111 SET content.text = \"x\"\n\
+                        ATOMIC {\n    APPEND blk_000000000000 text :: \"a\"\n}\n";
+        assert_eq!(format(input).unwrap(), expected);
+        assert_eq!(format(expected).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_rejects_invalid_input() {
+        assert!(format("EDIT blk_111111111111 SET").is_err());
+    }
+}
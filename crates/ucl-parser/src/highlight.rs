@@ -0,0 +1,191 @@
+//! Syntax highlighting tokens for editors.
+
+use logos::Logos;
+
+use crate::lexer::TokenKind;
+
+/// Highlight category of a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HighlightKind {
+    Keyword,
+    BlockId,
+    StringLiteral,
+    NumberLiteral,
+    Operator,
+    Identifier,
+    Comment,
+    ContentType,
+}
+
+impl HighlightKind {
+    /// All kinds, in semantic token legend order
+    pub const ALL: [HighlightKind; 8] = [
+        HighlightKind::Keyword,
+        HighlightKind::BlockId,
+        HighlightKind::StringLiteral,
+        HighlightKind::NumberLiteral,
+        HighlightKind::Operator,
+        HighlightKind::Identifier,
+        HighlightKind::Comment,
+        HighlightKind::ContentType,
+    ];
+
+    /// Standard LSP semantic token type name
+    pub fn token_type(self) -> &'static str {
+        match self {
+            HighlightKind::Keyword => "keyword",
+            HighlightKind::BlockId => "variable",
+            HighlightKind::StringLiteral => "string",
+            HighlightKind::NumberLiteral => "number",
+            HighlightKind::Operator => "operator",
+            HighlightKind::Identifier => "property",
+            HighlightKind::Comment => "comment",
+            HighlightKind::ContentType => "type",
+        }
+    }
+}
+
+/// A highlighted byte range of the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HighlightToken {
+    pub start: usize,
+    pub end: usize,
+    pub kind: HighlightKind,
+}
+
+/// Highlight tokens for `input`, in source order.
+///
+/// This only lexes, so it works on text that does not parse. Punctuation,
+/// newlines and unrecognised input produce no tokens.
+pub fn tokens(input: &str) -> Vec<HighlightToken> {
+    TokenKind::lexer(input)
+        .spanned()
+        .filter_map(|(kind, span)| {
+            Some(HighlightToken {
+                start: span.start,
+                end: span.end,
+                kind: classify(&kind.ok()?)?,
+            })
+        })
+        .collect()
+}
+
+fn classify(kind: &TokenKind) -> Option<HighlightKind> {
+    use TokenKind::*;
+    Some(match kind {
+        BlockId => HighlightKind::BlockId,
+        Identifier => HighlightKind::Identifier,
+        DoubleString(_) | SingleString(_) | TripleString(_) | CodeBlock(_) | TableLiteral(_) => {
+            HighlightKind::StringLiteral
+        }
+        Float(_) | Integer(_) => HighlightKind::NumberLiteral,
+        Eq | Ne | Gt | Ge | Lt | Le | PlusEq | MinusEq | PlusPlus | MinusMinus | DoubleColon => {
+            HighlightKind::Operator
+        }
+        TextType | TableType | CodeType | MathType | MediaType | JsonType | BinaryType
+        | CompositeType | FormType => HighlightKind::ContentType,
+        Comment => HighlightKind::Comment,
+        Colon | Comma | Dot | Hash | At_ | Dollar | LBracket | RBracket | LBrace | RBrace
+        | LParen | RParen | Newline => return None,
+        _ => HighlightKind::Keyword,
+    })
+}
+
+/// Encode `tokens` of `input` as LSP semantic token data.
+///
+/// Each token becomes five integers: line delta, start character delta
+/// (relative to the previous token when on the same line), length, the
+/// index of its kind in [`HighlightKind::ALL`] and no modifiers. Columns
+/// are UTF-16 code units, and tokens spanning lines are split per line.
+pub fn to_vscode_semantic_tokens(input: &str, tokens: &[HighlightToken]) -> Vec<u32> {
+    let mut data = Vec::with_capacity(tokens.len() * 5);
+    let (mut line, mut line_start, mut scanned) = (0u32, 0usize, 0usize);
+    let (mut prev_line, mut prev_char) = (0u32, 0u32);
+
+    for token in tokens {
+        let kind = HighlightKind::ALL
+            .iter()
+            .position(|k| *k == token.kind)
+            .unwrap_or(0) as u32;
+        let mut start = token.start;
+        for piece in input[token.start..token.end].split('\n') {
+            for (i, c) in input[scanned..start].char_indices() {
+                if c == '\n' {
+                    line += 1;
+                    line_start = scanned + i + 1;
+                }
+            }
+            scanned = start;
+
+            let length = utf16_len(piece);
+            if length > 0 {
+                let character = utf16_len(&input[line_start..start]);
+                let delta_char = if line == prev_line {
+                    character - prev_char
+                } else {
+                    character
+                };
+                data.extend([line - prev_line, delta_char, length, kind, 0]);
+                prev_line = line;
+                prev_char = character;
+            }
+            start += piece.len() + 1;
+        }
+    }
+    data
+}
+
+fn utf16_len(s: &str) -> u32 {
+    s.chars().map(|c| c.len_utf16() as u32).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens() {
+        let input =
+            "APPEND blk_111111111111 code :: \"x\" // note\nEDIT blk_111111111111 SET depth += 2";
+        let kinds: Vec<_> = tokens(input).iter().map(|t| t.kind).collect();
+        use HighlightKind::*;
+        assert_eq!(
+            kinds,
+            vec![
+                Keyword,
+                BlockId,
+                ContentType,
+                Operator,
+                StringLiteral,
+                Comment,
+                Keyword,
+                BlockId,
+                Keyword,
+                Keyword,
+                Operator,
+                NumberLiteral
+            ]
+        );
+        assert_eq!(
+            &input[tokens(input)[5].start..tokens(input)[5].end],
+            "// note"
+        );
+    }
+
+    #[test]
+    fn test_semantic_token_encoding() {
+        let input = "SET x = \"\u{e9}\na\"\n  y";
+        let data = to_vscode_semantic_tokens(input, &tokens(input));
+        assert_eq!(
+            data,
+            vec![
+                0, 0, 3, 0, 0, // SET
+                0, 4, 1, 5, 0, // x
+                0, 2, 1, 4, 0, // =
+                0, 2, 2, 2, 0, // "é
+                1, 0, 2, 2, 0, // a"
+                1, 2, 1, 5, 0, // y
+            ]
+        );
+    }
+}
@@ -0,0 +1,427 @@
+//! Incremental parsing for editors.
+//!
+//! Source is split into statements: section headers, structure entries,
+//! block definitions and commands, each starting on a new line outside any
+//! brackets. After an edit only the statements around the changed range are
+//! split and parsed again; statements before it are kept and those after it
+//! are shifted.
+
+use std::ops::Range;
+
+use logos::Logos;
+
+use crate::ast::{Command, UclDocument};
+use crate::format::starts_statement;
+use crate::lexer::TokenKind;
+use crate::parser::{ParseError, ParseResult, Parser};
+
+/// One statement of a [`UclTree`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct UclNode {
+    /// Byte range in the source, from the start of the statement's first
+    /// line up to the next statement
+    pub range: Range<usize>,
+    /// 1-based line of `range.start`
+    pub line: usize,
+    /// What the statement defines, or why it failed to parse
+    pub parsed: ParseResult<UclDocument>,
+    /// Section the statement appears in
+    section: Option<TokenKind>,
+}
+
+/// UCL source split into independently parsed statements
+#[derive(Debug, Clone, PartialEq)]
+pub struct UclTree {
+    source: String,
+    nodes: Vec<UclNode>,
+}
+
+impl UclTree {
+    /// Parse `source` from scratch
+    pub fn parse(source: &str) -> Self {
+        let nodes = Splitter::new(source, 0, 1, None).map(parse_chunk).collect();
+        Self {
+            source: source.to_string(),
+            nodes,
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn nodes(&self) -> &[UclNode] {
+        &self.nodes
+    }
+
+    /// Parse errors, one per failed statement, in source order
+    pub fn errors(&self) -> impl Iterator<Item = &ParseError> {
+        self.nodes
+            .iter()
+            .filter_map(|node| node.parsed.as_ref().err())
+    }
+}
+
+/// Commands of every statement that parsed, in source order
+pub fn tree_to_commands(tree: &UclTree) -> Vec<Command> {
+    tree.nodes
+        .iter()
+        .filter_map(|node| node.parsed.as_ref().ok())
+        .flat_map(|doc| doc.commands.iter().cloned())
+        .collect()
+}
+
+/// Keeps a [`UclTree`] up to date as its source is edited
+#[derive(Debug, Clone)]
+pub struct IncrementalParser {
+    tree: UclTree,
+    /// Statements parsed by the last edit
+    reparsed: usize,
+}
+
+impl IncrementalParser {
+    pub fn new(source: &str) -> Self {
+        let tree = UclTree::parse(source);
+        let reparsed = tree.nodes.len();
+        Self { tree, reparsed }
+    }
+
+    pub fn tree(&self) -> &UclTree {
+        &self.tree
+    }
+
+    /// Number of statements parsed by the last update
+    pub fn reparsed(&self) -> usize {
+        self.reparsed
+    }
+
+    /// Apply an edit that replaced `start_byte..old_end_byte` of the old
+    /// source with `start_byte..new_end_byte` of `new_text`, the whole new
+    /// source. Falls back to a full parse if the offsets don't describe
+    /// `new_text`.
+    pub fn edit(
+        &mut self,
+        start_byte: usize,
+        old_end_byte: usize,
+        new_end_byte: usize,
+        new_text: &str,
+    ) -> &UclTree {
+        let old_len = self.tree.source.len();
+        let consistent = start_byte <= old_end_byte
+            && start_byte <= new_end_byte
+            && old_end_byte <= old_len
+            && new_end_byte <= new_text.len()
+            && old_len - old_end_byte == new_text.len() - new_end_byte
+            && new_text.is_char_boundary(start_byte)
+            && self.tree.source[..start_byte] == new_text[..start_byte];
+        if !consistent {
+            *self = Self::new(new_text);
+            return &self.tree;
+        }
+
+        let old_nodes = std::mem::take(&mut self.tree.nodes);
+        // The statement before the edit may absorb the edited one
+        let first = old_nodes
+            .iter()
+            .position(|node| node.range.end > start_byte)
+            .unwrap_or(old_nodes.len())
+            .saturating_sub(1);
+        let (offset, line, section) = old_nodes.get(first).map_or((0, 1, None), |node| {
+            (node.range.start, node.line, node.section.clone())
+        });
+
+        let mut nodes: Vec<UclNode> = old_nodes[..first].to_vec();
+        let mut reparsed = 0;
+        let mut tail: &[UclNode] = &[];
+        let mut shift = (0isize, 0isize);
+        for chunk in Splitter::new(new_text, offset, line, section) {
+            if chunk.range.start >= new_end_byte {
+                // Old and new splits agree from here on
+                let old_start = chunk.range.start - new_end_byte + old_end_byte;
+                if let Ok(i) = old_nodes.binary_search_by_key(&old_start, |n| n.range.start) {
+                    if old_nodes[i].section == chunk.section {
+                        tail = &old_nodes[i..];
+                        shift = (
+                            new_end_byte as isize - old_end_byte as isize,
+                            chunk.line as isize - old_nodes[i].line as isize,
+                        );
+                        break;
+                    }
+                }
+            }
+            nodes.push(parse_chunk(chunk));
+            reparsed += 1;
+        }
+        for node in tail {
+            let mut node = node.clone();
+            node.range = shift_by(node.range.start, shift.0)..shift_by(node.range.end, shift.0);
+            node.line = shift_by(node.line, shift.1);
+            if let Err(error) = &mut node.parsed {
+                shift_error(error, shift.1, shift.0);
+            }
+            nodes.push(node);
+        }
+
+        self.tree = UclTree {
+            source: new_text.to_string(),
+            nodes,
+        };
+        self.reparsed = reparsed;
+        &self.tree
+    }
+}
+
+/// A statement's place in the source, before parsing
+struct Chunk<'a> {
+    range: Range<usize>,
+    line: usize,
+    section: Option<TokenKind>,
+    text: &'a str,
+}
+
+fn parse_chunk(chunk: Chunk) -> UclNode {
+    // Chunks start at a line start, so columns need no offset
+    let parsed = Parser::new(chunk.text)
+        .parse_statement(chunk.section.clone())
+        .map_err(|mut error| {
+            shift_error(
+                &mut error,
+                chunk.line as isize - 1,
+                chunk.range.start as isize,
+            );
+            error
+        });
+    UclNode {
+        range: chunk.range,
+        line: chunk.line,
+        parsed,
+        section: chunk.section,
+    }
+}
+
+/// Splits source into statements, starting at `offset`, which must be the
+/// start of a statement
+struct Splitter<'a> {
+    source: &'a str,
+    tokens: logos::SpannedIter<'a, TokenKind>,
+    offset: usize,
+    /// Current chunk
+    start: usize,
+    line: usize,
+    section: Option<TokenKind>,
+    /// Section after the tokens read so far
+    next_section: Option<TokenKind>,
+    current_line: usize,
+    line_begin: usize,
+    at_line_start: bool,
+    depth: usize,
+    done: bool,
+}
+
+impl<'a> Splitter<'a> {
+    fn new(source: &'a str, offset: usize, line: usize, section: Option<TokenKind>) -> Self {
+        Self {
+            source,
+            tokens: TokenKind::lexer(&source[offset..]).spanned(),
+            offset,
+            start: offset,
+            line,
+            section: section.clone(),
+            next_section: section,
+            current_line: line,
+            line_begin: offset,
+            at_line_start: true,
+            depth: 0,
+            done: false,
+        }
+    }
+
+    fn chunk(&self, end: usize) -> Chunk<'a> {
+        Chunk {
+            range: self.start..end,
+            line: self.line,
+            section: self.section.clone(),
+            text: &self.source[self.start..end],
+        }
+    }
+}
+
+impl<'a> Iterator for Splitter<'a> {
+    type Item = Chunk<'a>;
+
+    fn next(&mut self) -> Option<Chunk<'a>> {
+        if self.done {
+            return None;
+        }
+        loop {
+            let Some((kind, span)) = self.tokens.next() else {
+                self.done = true;
+                return (self.start < self.source.len()).then(|| self.chunk(self.source.len()));
+            };
+            let span = span.start + self.offset..span.end + self.offset;
+            let Ok(kind) = kind else {
+                self.at_line_start = false;
+                continue;
+            };
+            if kind == TokenKind::Newline {
+                self.current_line += 1;
+                self.line_begin = span.end;
+                self.at_line_start = true;
+                continue;
+            }
+
+            let mut emitted = None;
+            if self.at_line_start && self.depth == 0 && starts_chunk(&kind, &self.next_section) {
+                if self.line_begin > self.start {
+                    emitted = Some(self.chunk(self.line_begin));
+                    self.start = self.line_begin;
+                    self.line = self.current_line;
+                    self.section = self.next_section.clone();
+                }
+                if matches!(
+                    kind,
+                    TokenKind::Structure | TokenKind::Blocks | TokenKind::Commands
+                ) {
+                    self.next_section = Some(kind.clone());
+                }
+            }
+            self.at_line_start = false;
+            match kind {
+                TokenKind::LBrace | TokenKind::LBracket => self.depth += 1,
+                TokenKind::RBrace | TokenKind::RBracket => {
+                    self.depth = self.depth.saturating_sub(1)
+                }
+                _ => {}
+            }
+            // Strings and tables may span lines
+            self.current_line += self.source[span].matches('\n').count();
+            if emitted.is_some() {
+                return emitted;
+            }
+        }
+    }
+}
+
+/// Whether a line starting with `kind` starts a statement in `section`
+fn starts_chunk(kind: &TokenKind, section: &Option<TokenKind>) -> bool {
+    use TokenKind::*;
+    starts_statement(kind)
+        || match section {
+            Some(Structure) => *kind == BlockId,
+            Some(Blocks) => matches!(
+                kind,
+                TextType
+                    | TableType
+                    | CodeType
+                    | MathType
+                    | MediaType
+                    | JsonType
+                    | BinaryType
+                    | CompositeType
+                    | FormType
+                    | Diff
+            ),
+            _ => false,
+        }
+}
+
+fn shift_by(value: usize, delta: isize) -> usize {
+    (value as isize + delta) as usize
+}
+
+/// Move an error's position; line 0 means "at end of input" and is kept
+fn shift_error(error: &mut ParseError, lines: isize, bytes: isize) {
+    match error {
+        ParseError::UnexpectedToken { line, .. } | ParseError::InvalidSyntax { line, .. }
+            if *line > 0 =>
+        {
+            *line = shift_by(*line, lines)
+        }
+        ParseError::LexerError { position } => *position = shift_by(*position, bytes),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "STRUCTURE\nblk_000000000000: [blk_111111111111,\n  blk_222222222222]\n\n\
+                          BLOCKS\ntext #blk_111111111111 :: \"one\ntwo\"\n\
+                          code #blk_222222222222 :: \"x\"\n\
+                          COMMANDS\nEDIT blk_111111111111 SET content.text = \"a\"\n\
+                          // note\nATOMIC {\n  DELETE blk_222222222222\n}\n\
+                          MOVE blk_111111111111 TO blk_000000000000\n";
+
+    fn apply(parser: &mut IncrementalParser, start: usize, old_end: usize, insert: &str) {
+        let mut text = parser.tree().source().to_string();
+        text.replace_range(start..old_end, insert);
+        parser.edit(start, old_end, start + insert.len(), &text);
+        assert_eq!(
+            *parser.tree(),
+            UclTree::parse(&text),
+            "after edit of {:?}",
+            text
+        );
+    }
+
+    #[test]
+    fn test_statements() {
+        let tree = UclTree::parse(SOURCE);
+        let lines: Vec<_> = tree.nodes().iter().map(|n| n.line).collect();
+        assert_eq!(lines, vec![1, 2, 5, 6, 8, 9, 10, 12, 15]);
+        assert_eq!(tree.errors().count(), 0);
+        assert_eq!(
+            tree_to_commands(&tree),
+            crate::parse(SOURCE).unwrap().commands
+        );
+        let doc = tree.nodes()[1].parsed.as_ref().unwrap();
+        assert_eq!(doc.structure["blk_000000000000"].len(), 2);
+    }
+
+    #[test]
+    fn test_edit_reparses_only_touched_statements() {
+        let mut parser = IncrementalParser::new(SOURCE);
+        let at = SOURCE.find("content.text").unwrap();
+        apply(&mut parser, at, at + 7, "metadata");
+        assert_eq!(parser.reparsed(), 2);
+
+        // Breaking a command keeps the statements around it
+        let at = parser.tree().source().find("TO blk_0").unwrap() + 3;
+        apply(&mut parser, at, at + 16, "nowhere");
+        assert_eq!(parser.reparsed(), 2);
+        let error = parser.tree().errors().next().unwrap();
+        assert!(error.to_string().contains("line 15"), "{}", error);
+        assert_eq!(tree_to_commands(parser.tree()).len(), 2);
+
+        // Inserting lines shifts later errors
+        apply(&mut parser, 0, 0, "// header\n\n");
+        assert!(parser
+            .tree()
+            .errors()
+            .next()
+            .unwrap()
+            .to_string()
+            .contains("line 17"));
+
+        // Removing a section header changes how the rest is read
+        let at = parser.tree().source().find("COMMANDS").unwrap();
+        apply(&mut parser, at, at + 9, "");
+        assert_eq!(parser.tree().errors().count(), 1);
+        apply(&mut parser, at, at, "COMMANDS\n");
+        assert_eq!(parser.reparsed(), 5);
+    }
+
+    #[test]
+    fn test_edit_matches_full_parse_everywhere() {
+        let mut parser = IncrementalParser::new(SOURCE);
+        for (i, _) in SOURCE.char_indices().step_by(7) {
+            apply(&mut parser, i, i, "\n");
+            apply(&mut parser, i, i + 1, "");
+            apply(&mut parser, i, i, "{");
+            apply(&mut parser, i, i + 1, "");
+        }
+        apply(&mut parser, 0, SOURCE.len(), "");
+        assert!(parser.tree().nodes().is_empty());
+    }
+}
@@ -61,6 +61,10 @@ pub enum TokenKind {
     Link,
     #[regex("(?i)UNLINK")]
     Unlink,
+    #[regex("(?i)WEIGHT")]
+    Weight,
+    #[regex("(?i)VALID")]
+    Valid,
     #[regex("(?i)SNAPSHOT")]
     Snapshot,
     #[regex("(?i)CREATE")]
@@ -81,6 +85,8 @@ pub enum TokenKind {
     Rollback,
     #[regex("(?i)ATOMIC")]
     Atomic,
+    #[regex("(?i)EXPLAIN")]
+    Explain,
     #[regex("(?i)VIEW")]
     View,
     #[regex("(?i)FOLDED")]
@@ -295,6 +301,8 @@ pub enum TokenKind {
     BinaryType,
     #[token("composite")]
     CompositeType,
+    #[token("form")]
+    FormType,
 
     // Literals
     #[token("true")]
@@ -365,8 +373,9 @@ pub struct Token {
 pub struct Lexer<'a> {
     inner: logos::Lexer<'a, TokenKind>,
     line: usize,
-    column: usize,
-    last_newline_pos: usize,
+    /// Byte offset up to which newlines have been counted
+    scanned: usize,
+    line_start: usize,
 }
 
 impl<'a> Lexer<'a> {
@@ -374,8 +383,8 @@ impl<'a> Lexer<'a> {
         Self {
             inner: TokenKind::lexer(input),
             line: 1,
-            column: 1,
-            last_newline_pos: 0,
+            scanned: 0,
+            line_start: 0,
         }
     }
 
@@ -392,38 +401,26 @@ impl<'a> Iterator for Lexer<'a> {
             let kind = self.inner.next()?;
             let span = self.inner.span();
 
-            // Update line/column tracking
+            // Count newlines since the last token, including any inside it
             let source = self.inner.source();
-            for c in source[self.last_newline_pos..span.start].chars() {
+            for (i, c) in source[self.scanned..span.start].char_indices() {
                 if c == '\n' {
                     self.line += 1;
-                    self.column = 1;
-                    self.last_newline_pos = span.start;
-                } else {
-                    self.column += 1;
+                    self.line_start = self.scanned + i + 1;
                 }
             }
+            self.scanned = span.start;
+            let column = source[self.line_start..span.start].chars().count() + 1;
 
             match kind {
                 Ok(TokenKind::Comment) => continue, // Skip comments
-                Ok(TokenKind::Newline) => {
-                    self.line += 1;
-                    self.column = 1;
-                    self.last_newline_pos = span.end;
-                    // Return newline token for line-aware parsing
-                    return Some(Ok(Token {
-                        kind: TokenKind::Newline,
-                        span,
-                        line: self.line - 1,
-                        column: 1,
-                    }));
-                }
                 Ok(kind) => {
+                    // Newline tokens are returned for line-aware parsing
                     return Some(Ok(Token {
                         kind,
                         span,
                         line: self.line,
-                        column: self.column,
+                        column,
                     }));
                 }
                 Err(_) => return Some(Err(())),
@@ -467,6 +464,26 @@ mod tests {
         assert!(matches!(tokens[1].kind, TokenKind::SingleString(_)));
     }
 
+    #[test]
+    fn test_token_positions() {
+        let input = "EDIT blk_abc123def456 SET\n  text = \"a\nb\" x";
+        let tokens: Vec<_> = Lexer::new(input).filter_map(|r| r.ok()).collect();
+        let positions: Vec<_> = tokens.iter().map(|t| (t.line, t.column)).collect();
+        assert_eq!(
+            positions,
+            vec![
+                (1, 1),
+                (1, 6),
+                (1, 23),
+                (1, 26),
+                (2, 3),
+                (2, 8),
+                (2, 10),
+                (3, 4)
+            ]
+        );
+    }
+
     #[test]
     fn test_lex_operators() {
         let input = "= += -= != >= <=";
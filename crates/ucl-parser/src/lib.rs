@@ -18,10 +18,15 @@
 //! ```
 
 pub mod ast;
+pub mod format;
+pub mod highlight;
+pub mod incremental;
 pub mod lexer;
 pub mod parser;
 
 pub use ast::*;
+pub use format::format;
+pub use incremental::{tree_to_commands, IncrementalParser, UclTree};
 pub use lexer::{Token, TokenKind};
 pub use parser::{ParseError, ParseResult, Parser};
 
@@ -37,6 +42,25 @@ pub fn parse_commands(input: &str) -> ParseResult<Vec<Command>> {
     parser.parse_commands_only()
 }
 
+/// Parse a full document if `input` has a section header, otherwise
+/// commands only
+pub fn parse_any(input: &str) -> ParseResult<UclDocument> {
+    let has_section = lexer::Lexer::new(input).filter_map(|t| t.ok()).any(|t| {
+        matches!(
+            t.kind,
+            TokenKind::Structure | TokenKind::Blocks | TokenKind::Commands
+        )
+    });
+    if has_section {
+        parse(input)
+    } else {
+        Ok(UclDocument {
+            commands: parse_commands(input)?,
+            ..UclDocument::new()
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
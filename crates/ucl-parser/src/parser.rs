@@ -2,10 +2,11 @@
 
 use crate::ast::*;
 use crate::lexer::{Lexer, Token, TokenKind};
+use chrono::{DateTime, NaiveDate, Utc};
 use std::collections::HashMap;
 use thiserror::Error;
 
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, PartialEq, Error)]
 pub enum ParseError {
     #[error("Unexpected token at line {line}: expected {expected}, found {found}")]
     UnexpectedToken {
@@ -81,6 +82,41 @@ impl<'a> Parser<'a> {
         Ok(cmds)
     }
 
+    /// Parse one statement of incremental parsing: a section header and its
+    /// trailing input, a structure entry, a block definition or commands,
+    /// read as if inside `section`. Unlike [`Parser::parse_document`], any
+    /// input left over is an error.
+    pub(crate) fn parse_statement(
+        &mut self,
+        section: Option<TokenKind>,
+    ) -> ParseResult<UclDocument> {
+        let mut section = section;
+        if self.is_section_header() {
+            section = self.peek_kind();
+            self.advance();
+        }
+        let mut doc = UclDocument::new();
+        match section {
+            // Commands are accepted in any section, as in a full parse
+            _ if self.is_cmd_start() => {
+                while !self.is_at_end() {
+                    doc.commands.push(self.parse_command()?);
+                }
+            }
+            Some(TokenKind::Structure) => doc.structure = self.parse_structure()?,
+            Some(TokenKind::Blocks) => doc.blocks = self.parse_blocks()?,
+            _ => {
+                while !self.is_at_end() {
+                    doc.commands.push(self.parse_command()?);
+                }
+            }
+        }
+        if !self.is_at_end() {
+            return Err(self.error("end of statement"));
+        }
+        Ok(doc)
+    }
+
     fn parse_structure(&mut self) -> ParseResult<HashMap<String, Vec<String>>> {
         let mut structure = HashMap::new();
         while !self.is_at_end() && !self.is_section_header() {
@@ -163,6 +199,7 @@ impl<'a> Parser<'a> {
             Some(TokenKind::Commit) => self.parse_commit(),
             Some(TokenKind::Rollback) => self.parse_rollback(),
             Some(TokenKind::Atomic) => self.parse_atomic(),
+            Some(TokenKind::Explain) => self.parse_explain(),
             Some(TokenKind::WriteSection) => self.parse_write_section(),
 
             // Agent traversal commands
@@ -252,7 +289,8 @@ impl<'a> Parser<'a> {
         if self.check(TokenKind::With) {
             self.advance();
             while !self.check(TokenKind::DoubleColon) && !self.is_at_end() {
-                let k = self.expect_ident()?;
+                // `label` and `role` lex as keywords
+                let k = self.expect_ident_or_keyword()?;
                 self.expect(TokenKind::Eq)?;
                 props.insert(k, self.parse_value()?);
             }
@@ -360,22 +398,66 @@ impl<'a> Parser<'a> {
 
     fn parse_link(&mut self) -> ParseResult<Command> {
         self.advance();
-        let s = self.expect_block_id()?;
+        let (s, additional_sources) = if self.check(TokenKind::LBracket) {
+            self.advance();
+            let first = self.expect_block_id()?;
+            let mut rest = Vec::new();
+            while self.check(TokenKind::Comma) {
+                self.advance();
+                rest.push(self.expect_block_id()?);
+            }
+            self.expect(TokenKind::RBracket)?;
+            (first, rest)
+        } else {
+            (self.expect_block_id()?, Vec::new())
+        };
         let e = self.expect_ident()?;
         let t = self.expect_block_id()?;
+        let weight = if self.check(TokenKind::Weight) {
+            self.advance();
+            Some(self.expect_number()?)
+        } else {
+            None
+        };
+        let (mut valid_from, mut valid_to) = (None, None);
+        if self.check(TokenKind::Valid) {
+            self.advance();
+            if self.check(TokenKind::From) {
+                self.advance();
+                valid_from = Some(self.expect_date()?);
+            }
+            if self.check(TokenKind::To) {
+                self.advance();
+                valid_to = Some(self.expect_date()?);
+            }
+            if valid_from.is_none() && valid_to.is_none() {
+                return Err(self.error("FROM or TO"));
+            }
+        }
+        if !additional_sources.is_empty()
+            && (weight.is_some() || valid_from.is_some() || valid_to.is_some())
+        {
+            return Err(
+                self.error_with_hint("WEIGHT and VALID are not supported on multi-source links")
+            );
+        }
         let mut m = HashMap::new();
         if self.check(TokenKind::With) {
             self.advance();
             while !self.is_at_end() && !self.is_cmd_start() {
-                let k = self.expect_ident()?;
+                let k = self.expect_ident_or_keyword()?;
                 self.expect(TokenKind::Eq)?;
                 m.insert(k, self.parse_value()?);
             }
         }
         Ok(Command::Link(LinkCommand {
             source_id: s,
+            additional_sources,
             edge_type: e,
             target_id: t,
+            weight,
+            valid_from,
+            valid_to,
             metadata: m,
         }))
     }
@@ -487,6 +569,12 @@ impl<'a> Parser<'a> {
         Ok(Command::Atomic(cmds))
     }
 
+    fn parse_explain(&mut self) -> ParseResult<Command> {
+        self.advance();
+        let inner = Box::new(self.parse_command()?);
+        Ok(Command::Explain(ExplainCommand { inner }))
+    }
+
     // ========================================================================
     // Agent Traversal Command Parsers
     // ========================================================================
@@ -1424,6 +1512,15 @@ impl<'a> Parser<'a> {
                 self.advance();
                 Some(ContentType::Composite)
             }
+            Some(TokenKind::FormType) => {
+                self.advance();
+                Some(ContentType::Form)
+            }
+            // Shares the `DIFF` token with SNAPSHOT DIFF
+            Some(TokenKind::Diff) => {
+                self.advance();
+                Some(ContentType::Diff)
+            }
             _ => None,
         }
     }
@@ -1454,12 +1551,18 @@ impl<'a> Parser<'a> {
         }
     }
     fn expect_block_id(&mut self) -> ParseResult<String> {
-        if matches!(self.peek_kind(), Some(TokenKind::BlockId)) {
-            let span = self.tokens[self.pos].span.clone();
-            self.advance();
-            Ok(self.source[span].to_string())
-        } else {
-            Err(self.error("block ID"))
+        match self.peek_kind() {
+            Some(TokenKind::BlockId) => {
+                let span = self.tokens[self.pos].span.clone();
+                self.advance();
+                Ok(self.source[span].to_string())
+            }
+            // Short numeric ids (see `IdMapper`), resolved by the caller
+            Some(TokenKind::Integer(n)) if n >= 0 => {
+                self.advance();
+                Ok(n.to_string())
+            }
+            _ => Err(self.error("block ID")),
         }
     }
     fn expect_ident(&mut self) -> ParseResult<String> {
@@ -1483,6 +1586,8 @@ impl<'a> Parser<'a> {
                 | Some(TokenKind::JsonType)
                 | Some(TokenKind::BinaryType)
                 | Some(TokenKind::CompositeType)
+                | Some(TokenKind::FormType)
+                | Some(TokenKind::Diff)
                 | Some(TokenKind::True)
                 | Some(TokenKind::False)
                 | Some(TokenKind::Null)
@@ -1503,6 +1608,8 @@ impl<'a> Parser<'a> {
                 | Some(TokenKind::Full)
                 | Some(TokenKind::Preview)
                 | Some(TokenKind::MetadataToken)
+                | Some(TokenKind::Weight)
+                | Some(TokenKind::Valid)
         )
     }
     fn expect_ident_or_keyword(&mut self) -> ParseResult<String> {
@@ -1550,6 +1657,38 @@ impl<'a> Parser<'a> {
         }
     }
 
+    fn expect_number(&mut self) -> ParseResult<f64> {
+        match self.peek_kind() {
+            Some(TokenKind::Integer(n)) => {
+                self.advance();
+                Ok(n as f64)
+            }
+            Some(TokenKind::Float(n)) => {
+                self.advance();
+                Ok(n)
+            }
+            _ => Err(self.error("number")),
+        }
+    }
+
+    /// Parse a quoted RFC 3339 timestamp or `YYYY-MM-DD` date (midnight UTC).
+    fn expect_date(&mut self) -> ParseResult<DateTime<Utc>> {
+        let s = self.expect_str()?;
+        if let Ok(dt) = DateTime::parse_from_rfc3339(&s) {
+            return Ok(dt.with_timezone(&Utc));
+        }
+        NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|dt| dt.and_utc())
+            .ok_or_else(|| {
+                self.error_with_hint(&format!(
+                    "invalid date '{}': expected YYYY-MM-DD or an RFC 3339 timestamp",
+                    s
+                ))
+            })
+    }
+
     fn expect_eq_with_hint(&mut self, hint: &str) -> ParseResult<()> {
         if self.check(TokenKind::Eq) {
             self.advance();
@@ -1590,6 +1729,7 @@ impl<'a> Parser<'a> {
                 | Some(TokenKind::Commit)
                 | Some(TokenKind::Rollback)
                 | Some(TokenKind::Atomic)
+                | Some(TokenKind::Explain)
                 | Some(TokenKind::WriteSection)
                 // Agent traversal commands
                 | Some(TokenKind::Goto)
@@ -1643,6 +1783,96 @@ mod tests {
         assert!(r.is_ok(), "Parse error: {:?}", r.err());
     }
 
+    #[test]
+    fn test_parse_short_block_ids() {
+        let cmds = Parser::new("MOVE 3 AFTER 12\nAPPEND 1 text :: \"hi\"")
+            .parse_commands_only()
+            .unwrap();
+        match &cmds[0] {
+            Command::Move(m) => {
+                assert_eq!(m.block_id, "3");
+                assert_eq!(
+                    m.target,
+                    MoveTarget::After {
+                        sibling_id: "12".into()
+                    }
+                );
+            }
+            other => panic!("Expected Move, got {:?}", other),
+        }
+        assert!(Parser::new("DELETE -1").parse_commands_only().is_err());
+    }
+
+    #[test]
+    fn test_parse_link_weight() {
+        let cmds = Parser::new(
+            "LINK 1 references 2 WEIGHT 0.5 WITH weight = \"note\"\nLINK 1 supports 3 weight 2",
+        )
+        .parse_commands_only()
+        .unwrap();
+        match (&cmds[0], &cmds[1]) {
+            (Command::Link(a), Command::Link(b)) => {
+                assert_eq!(a.weight, Some(0.5));
+                assert!(a.metadata.contains_key("weight"));
+                assert_eq!(b.weight, Some(2.0));
+            }
+            other => panic!("Expected two links, got {:?}", other),
+        }
+        assert!(Parser::new("LINK 1 references 2 WEIGHT heavy")
+            .parse_commands_only()
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_link_validity() {
+        let cmds = Parser::new(
+            "LINK 1 references 2 VALID FROM \"2024-01-01\" TO \"2024-12-31T12:00:00Z\"\nLINK 1 supports 3 VALID TO '2025-06-30'",
+        )
+        .parse_commands_only()
+        .unwrap();
+        match (&cmds[0], &cmds[1]) {
+            (Command::Link(a), Command::Link(b)) => {
+                assert_eq!(
+                    a.valid_from.unwrap().to_rfc3339(),
+                    "2024-01-01T00:00:00+00:00"
+                );
+                assert_eq!(
+                    a.valid_to.unwrap().to_rfc3339(),
+                    "2024-12-31T12:00:00+00:00"
+                );
+                assert!(b.valid_from.is_none());
+                assert!(b.valid_to.is_some());
+            }
+            other => panic!("Expected two links, got {:?}", other),
+        }
+        assert!(Parser::new("LINK 1 references 2 VALID FROM \"soon\"")
+            .parse_commands_only()
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_link_multiple_sources() {
+        let cmds = Parser::new("LINK [1, 2] references 3 WITH note = 'joint'\nLINK [4] supports 5")
+            .parse_commands_only()
+            .unwrap();
+        match (&cmds[0], &cmds[1]) {
+            (Command::Link(a), Command::Link(b)) => {
+                assert_eq!(a.source_id, "1");
+                assert_eq!(a.additional_sources, vec!["2".to_string()]);
+                assert_eq!(a.target_id, "3");
+                assert!(a.metadata.contains_key("note"));
+                assert!(b.additional_sources.is_empty());
+            }
+            other => panic!("Expected two links, got {:?}", other),
+        }
+        assert!(Parser::new("LINK [1, 2 references 3")
+            .parse_commands_only()
+            .is_err());
+        assert!(Parser::new("LINK [1, 2] references 3 WEIGHT 2")
+            .parse_commands_only()
+            .is_err());
+    }
+
     // ========================================================================
     // Agent Traversal Command Tests
     // ========================================================================
@@ -1952,4 +2182,16 @@ mod tests {
         assert!(r.is_ok(), "Parse error: {:?}", r.err());
         assert_eq!(r.unwrap().len(), 4);
     }
+
+    #[test]
+    fn test_parse_explain() {
+        let r = Parser::new("explain DELETE blk_abc123def456 CASCADE\nGOTO blk_abc123def456")
+            .parse_commands_only()
+            .unwrap();
+        assert_eq!(r.len(), 2);
+        match &r[0] {
+            Command::Explain(e) => assert!(matches!(*e.inner, Command::Delete(ref d) if d.cascade)),
+            other => panic!("expected EXPLAIN, got {:?}", other),
+        }
+    }
 }
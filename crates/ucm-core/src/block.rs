@@ -36,6 +36,7 @@ pub struct Block {
     pub edges: Vec<Edge>,
 
     /// Version for optimistic concurrency control
+    #[serde(default = "Version::initial")]
     pub version: Version,
 }
 
@@ -3,7 +3,9 @@
 //! Each block contains typed content that can be text, tables, code,
 //! math expressions, media, JSON, or binary data.
 
+use crate::error::{Error, Result};
 use crate::id::BlockId;
+use crate::spreadsheet::SpreadsheetContent;
 use serde::{Deserialize, Serialize};
 
 /// The content payload of a block.
@@ -13,15 +15,45 @@ pub enum Content {
     /// Plain, markdown, or rich text
     Text(Text),
 
+    /// Text with inline formatting spans
+    RichText(RichText),
+
     /// Tabular data with optional schema
     Table(Table),
 
+    /// Typed cells with formulas
+    Spreadsheet(SpreadsheetContent),
+
     /// Source code with language hint
     Code(Code),
 
+    /// Source code stored zstd-compressed to save memory; see
+    /// [`Content::compress_code`] and [`Content::decompress`]
+    CompressedCode {
+        language: String,
+        #[serde(with = "base64_serde")]
+        compressed: Vec<u8>,
+        /// Length of the UTF-8 source in bytes
+        original_len: usize,
+    },
+
     /// Mathematical expressions
     Math(Math),
 
+    /// Diagram source (Mermaid, Graphviz, PlantUML)
+    Diagram(DiagramContent),
+
+    /// Patch or diff (unified, git, RFC 6902 JSON Patch)
+    Diff(DiffContent),
+
+    /// GeoJSON (RFC 7946) object, serialized under `value` since GeoJSON
+    /// carries its own `type` member
+    #[serde(rename = "geojson")]
+    GeoJson(#[serde(with = "geojson_serde")] serde_json::Value),
+
+    /// Survey or questionnaire with typed fields
+    Form(FormContent),
+
     /// Media references (images, audio, video)
     Media(Media),
 
@@ -49,13 +81,22 @@ pub enum Content {
 }
 
 impl Content {
-    /// Get the type tag for hashing and identification
+    /// Get the type tag for hashing and identification.
+    ///
+    /// Compressed code reports `code`, so compressing a block changes neither
+    /// its ID nor its content type.
     pub fn type_tag(&self) -> &'static str {
         match self {
             Content::Text(_) => "text",
+            Content::RichText(_) => "rich_text",
             Content::Table(_) => "table",
-            Content::Code(_) => "code",
+            Content::Spreadsheet(_) => "spreadsheet",
+            Content::Code(_) | Content::CompressedCode { .. } => "code",
             Content::Math(_) => "math",
+            Content::Diagram(_) => "diagram",
+            Content::Diff(_) => "diff",
+            Content::GeoJson(_) => "geojson",
+            Content::Form(_) => "form",
             Content::Media(_) => "media",
             Content::Json { .. } => "json",
             Content::Binary { .. } => "binary",
@@ -79,6 +120,11 @@ impl Content {
         })
     }
 
+    /// Create rich text content from inline spans
+    pub fn rich_text(spans: Vec<InlineSpan>) -> Self {
+        Content::RichText(RichText { spans })
+    }
+
     /// Create code content
     pub fn code(language: impl Into<String>, source: impl Into<String>) -> Self {
         Content::Code(Code {
@@ -88,6 +134,68 @@ impl Content {
         })
     }
 
+    /// Create compressed code content. Where the zstd encoder is unavailable
+    /// (wasm) the source is kept as plain [`Content::Code`].
+    pub fn compress_code(language: impl Into<String>, source: impl Into<String>) -> Self {
+        let language = language.into();
+        let source = source.into();
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Ok(mut compressed) = zstd::bulk::compress(source.as_bytes(), 0) {
+            compressed.shrink_to_fit();
+            return Content::CompressedCode {
+                language,
+                compressed,
+                original_len: source.len(),
+            };
+        }
+        Content::code(language, source)
+    }
+
+    /// Source of code content, decompressed if needed; `None` for other
+    /// content, or compressed code that cannot be decoded. Decoding works on
+    /// every target, so blocks compressed natively load in wasm.
+    pub fn decompress(&self) -> Option<String> {
+        match self {
+            Content::Code(code) => Some(code.source.clone()),
+            Content::CompressedCode {
+                compressed,
+                original_len,
+                ..
+            } => zstd_decode(compressed, *original_len)
+                .and_then(|bytes| String::from_utf8(bytes).ok()),
+            _ => None,
+        }
+    }
+
+    /// Language of code content, compressed or not
+    pub fn code_language(&self) -> Option<&str> {
+        match self {
+            Content::Code(code) => Some(&code.language),
+            Content::CompressedCode { language, .. } => Some(language),
+            _ => None,
+        }
+    }
+
+    /// Compressed code as plain [`Code`]; `None` for other content
+    pub fn decompressed_code(&self) -> Option<Code> {
+        match self {
+            Content::CompressedCode { language, .. } => {
+                Some(Code::new(language.clone(), self.decompress()?))
+            }
+            _ => None,
+        }
+    }
+
+    /// Create Mermaid diagram content
+    pub fn mermaid(source: impl Into<String>) -> Self {
+        Content::Diagram(DiagramContent::new(DiagramDialect::Mermaid, source))
+    }
+
+    /// Create diff content
+    pub fn diff(format: DiffFormat, patch: impl Into<String>) -> Self {
+        Content::Diff(DiffContent::new(format, patch))
+    }
+
     /// Create JSON content
     pub fn json(value: serde_json::Value) -> Self {
         Content::Json {
@@ -96,6 +204,34 @@ impl Content {
         }
     }
 
+    /// Create GeoJSON content, rejecting values that are not a GeoJSON object
+    pub fn new_geojson(value: serde_json::Value) -> Result<Self> {
+        let kind = value
+            .get("type")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| Error::Validation("GeoJSON object must have a string 'type'".into()))?;
+        let required = match kind {
+            "FeatureCollection" => "features",
+            "Feature" => "geometry",
+            "GeometryCollection" => "geometries",
+            "Point" | "MultiPoint" | "LineString" | "MultiLineString" | "Polygon"
+            | "MultiPolygon" => "coordinates",
+            other => {
+                return Err(Error::Validation(format!(
+                    "'{}' is not a GeoJSON type",
+                    other
+                )))
+            }
+        };
+        if value.get(required).is_none() {
+            return Err(Error::Validation(format!(
+                "GeoJSON {} is missing '{}'",
+                kind, required
+            )));
+        }
+        Ok(Content::GeoJson(value))
+    }
+
     /// Create table content from rows of strings
     pub fn table(rows: Vec<Vec<String>>) -> Self {
         let columns = if rows.is_empty() {
@@ -130,9 +266,16 @@ impl Content {
     pub fn is_empty(&self) -> bool {
         match self {
             Content::Text(t) => t.text.is_empty(),
+            Content::RichText(r) => r.plain_text().is_empty(),
             Content::Table(t) => t.rows.is_empty(),
+            Content::Spreadsheet(s) => s.rows.is_empty(),
             Content::Code(c) => c.source.is_empty(),
+            Content::CompressedCode { original_len, .. } => *original_len == 0,
             Content::Math(m) => m.expression.is_empty(),
+            Content::Diagram(d) => d.source.is_empty(),
+            Content::Diff(d) => d.patch.is_empty(),
+            Content::GeoJson(_) => false,
+            Content::Form(f) => f.fields.is_empty(),
             Content::Media(_) => false,
             Content::Json { value, .. } => value.is_null(),
             Content::Binary { data, .. } => data.is_empty(),
@@ -144,6 +287,7 @@ impl Content {
     pub fn size_bytes(&self) -> usize {
         match self {
             Content::Text(t) => t.text.len(),
+            Content::RichText(r) => r.spans.iter().map(InlineSpan::size_bytes).sum(),
             Content::Table(t) => {
                 t.columns.iter().map(|c| c.name.len()).sum::<usize>()
                     + t.rows
@@ -152,8 +296,14 @@ impl Content {
                         .map(|c| c.size_bytes())
                         .sum::<usize>()
             }
+            Content::Spreadsheet(s) => s.size_bytes(),
             Content::Code(c) => c.source.len(),
+            Content::CompressedCode { original_len, .. } => *original_len,
             Content::Math(m) => m.expression.len(),
+            Content::Diagram(d) => d.source.len(),
+            Content::Diff(d) => d.patch.len(),
+            Content::GeoJson(value) => value.to_string().len(),
+            Content::Form(f) => f.size_bytes(),
             Content::Media(m) => match &m.source {
                 MediaSource::Base64(s) => s.len(),
                 MediaSource::Url(s) => s.len(),
@@ -166,6 +316,27 @@ impl Content {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+fn zstd_decode(compressed: &[u8], original_len: usize) -> Option<Vec<u8>> {
+    zstd::bulk::decompress(compressed, original_len).ok()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn zstd_decode(compressed: &[u8], original_len: usize) -> Option<Vec<u8>> {
+    ruzstd_decode(compressed, original_len)
+}
+
+/// Pure-Rust zstd decoding, for targets without the C library
+#[cfg(any(target_arch = "wasm32", test))]
+fn ruzstd_decode(compressed: &[u8], original_len: usize) -> Option<Vec<u8>> {
+    use std::io::Read;
+
+    let mut decoder = ruzstd::decoding::StreamingDecoder::new(compressed).ok()?;
+    let mut bytes = Vec::with_capacity(original_len);
+    decoder.read_to_end(&mut bytes).ok()?;
+    (bytes.len() == original_len).then_some(bytes)
+}
+
 /// Text content
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Text {
@@ -184,6 +355,76 @@ pub enum TextFormat {
     Rich,
 }
 
+/// Text made of inline formatting spans
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct RichText {
+    pub spans: Vec<InlineSpan>,
+}
+
+impl RichText {
+    pub fn new(spans: Vec<InlineSpan>) -> Self {
+        Self { spans }
+    }
+
+    /// The text with all formatting dropped; block references show their
+    /// display text
+    pub fn plain_text(&self) -> String {
+        let mut out = String::new();
+        for span in &self.spans {
+            span.push_plain_text(&mut out);
+        }
+        out
+    }
+}
+
+/// An inline span of rich text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InlineSpan {
+    Plain(String),
+    Bold(Vec<InlineSpan>),
+    Italic(Vec<InlineSpan>),
+    Code(String),
+    Link { text: Vec<InlineSpan>, href: String },
+    BlockRef { display: String, block_id: BlockId },
+}
+
+impl InlineSpan {
+    pub fn plain(text: impl Into<String>) -> Self {
+        InlineSpan::Plain(text.into())
+    }
+
+    fn push_plain_text(&self, out: &mut String) {
+        match self {
+            InlineSpan::Plain(s) | InlineSpan::Code(s) => out.push_str(s),
+            InlineSpan::Bold(spans) | InlineSpan::Italic(spans) => {
+                for span in spans {
+                    span.push_plain_text(out);
+                }
+            }
+            InlineSpan::Link { text, .. } => {
+                for span in text {
+                    span.push_plain_text(out);
+                }
+            }
+            InlineSpan::BlockRef { display, .. } => out.push_str(display),
+        }
+    }
+
+    fn size_bytes(&self) -> usize {
+        match self {
+            InlineSpan::Plain(s) | InlineSpan::Code(s) => s.len(),
+            InlineSpan::Bold(spans) | InlineSpan::Italic(spans) => {
+                spans.iter().map(InlineSpan::size_bytes).sum()
+            }
+            InlineSpan::Link { text, href } => {
+                text.iter().map(InlineSpan::size_bytes).sum::<usize>() + href.len()
+            }
+            InlineSpan::BlockRef { display, .. } => display.len() + 12,
+        }
+    }
+}
+
 /// Table content
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Table {
@@ -403,6 +644,11 @@ impl Code {
         }
     }
 
+    /// The language in canonical form, see [`canonical_language`]
+    pub fn canonical_language(&self) -> String {
+        canonical_language(&self.language)
+    }
+
     pub fn line_count(&self) -> usize {
         self.source.lines().count()
     }
@@ -417,6 +663,37 @@ impl Code {
     }
 }
 
+/// Normalize a code language tag to its canonical name.
+///
+/// Only the first word of a fence info string counts (`rust ignore` is
+/// `rust`), case is ignored, and common aliases map to one name (`js` and
+/// `node` are `javascript`). Unknown languages are returned lowercased.
+pub fn canonical_language(tag: &str) -> String {
+    let tag = tag.split_whitespace().next().unwrap_or("").to_lowercase();
+    let canonical = match tag.as_str() {
+        "" | "plain" | "plaintext" | "txt" => "text",
+        "rs" => "rust",
+        "py" | "py3" | "python3" => "python",
+        "js" | "node" | "mjs" | "cjs" => "javascript",
+        "ts" => "typescript",
+        "jsx" => "javascript",
+        "tsx" => "typescript",
+        "sh" | "shell" | "zsh" => "bash",
+        "yml" => "yaml",
+        "rb" => "ruby",
+        "golang" => "go",
+        "c++" | "cc" | "hpp" => "cpp",
+        "cs" | "c#" => "csharp",
+        "kt" => "kotlin",
+        "md" => "markdown",
+        "htm" | "xhtml" => "html",
+        "ps1" | "pwsh" => "powershell",
+        "tex" | "latex" => "latex",
+        _ => return tag,
+    };
+    canonical.to_string()
+}
+
 /// Line range for code highlights
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LineRange {
@@ -470,6 +747,210 @@ pub enum MathFormat {
     AsciiMath,
 }
 
+/// Diagram described in a text-based diagram language
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiagramContent {
+    pub dialect: DiagramDialect,
+    pub source: String,
+}
+
+impl DiagramContent {
+    pub fn new(dialect: DiagramDialect, source: impl Into<String>) -> Self {
+        Self {
+            dialect,
+            source: source.into(),
+        }
+    }
+}
+
+/// Diagram language
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagramDialect {
+    Mermaid,
+    Graphviz,
+    PlantUml,
+}
+
+impl DiagramDialect {
+    /// Dialect named by a code fence info string (`mermaid`, `dot`,
+    /// `plantuml`), ignoring case and anything after the first word
+    pub fn from_fence_tag(tag: &str) -> Option<Self> {
+        let tag = tag.split_whitespace().next().unwrap_or("").to_lowercase();
+        match tag.as_str() {
+            "mermaid" => Some(DiagramDialect::Mermaid),
+            "dot" | "graphviz" => Some(DiagramDialect::Graphviz),
+            "plantuml" | "puml" => Some(DiagramDialect::PlantUml),
+            _ => None,
+        }
+    }
+
+    /// Info string used when writing the diagram as a code fence
+    pub fn fence_tag(&self) -> &'static str {
+        match self {
+            DiagramDialect::Mermaid => "mermaid",
+            DiagramDialect::Graphviz => "dot",
+            DiagramDialect::PlantUml => "plantuml",
+        }
+    }
+}
+
+/// Patch text together with the format it is written in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffContent {
+    pub format: DiffFormat,
+    pub patch: String,
+}
+
+impl DiffContent {
+    pub fn new(format: DiffFormat, patch: impl Into<String>) -> Self {
+        Self {
+            format,
+            patch: patch.into(),
+        }
+    }
+}
+
+/// Diff format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffFormat {
+    /// `diff -u` output
+    #[default]
+    Unified,
+    /// `git diff` / `git format-patch` output, including `diff --git` headers
+    GitPatch,
+    /// RFC 6902 JSON Patch document
+    JsonPatch,
+}
+
+impl DiffFormat {
+    /// Format named by `unified`, `git_patch` or `json_patch`, or a short
+    /// alias such as `git` or `json`; case and dashes are ignored
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace('-', "_").as_str() {
+            "unified" | "diff" => Some(DiffFormat::Unified),
+            "git_patch" | "git" | "patch" => Some(DiffFormat::GitPatch),
+            "json_patch" | "json" => Some(DiffFormat::JsonPatch),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiffFormat::Unified => "unified",
+            DiffFormat::GitPatch => "git_patch",
+            DiffFormat::JsonPatch => "json_patch",
+        }
+    }
+}
+
+/// Form with an ordered list of fields
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormContent {
+    pub title: String,
+    #[serde(default)]
+    pub fields: Vec<FormField>,
+}
+
+impl FormContent {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn with_field(mut self, field: FormField) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// Look up a field by id
+    pub fn field(&self, id: &str) -> Option<&FormField> {
+        self.fields.iter().find(|f| f.id == id)
+    }
+
+    fn size_bytes(&self) -> usize {
+        self.title.len()
+            + self
+                .fields
+                .iter()
+                .map(|f| f.id.len() + f.label.len())
+                .sum::<usize>()
+    }
+}
+
+/// Single form field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormField {
+    pub id: String,
+    pub label: String,
+    pub field_type: FieldType,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<serde_json::Value>,
+}
+
+impl FormField {
+    pub fn new(id: impl Into<String>, label: impl Into<String>, field_type: FieldType) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            field_type,
+            required: false,
+            default_value: None,
+        }
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn with_default(mut self, value: serde_json::Value) -> Self {
+        self.default_value = Some(value);
+        self
+    }
+}
+
+/// Kind of answer a form field accepts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldType {
+    Text,
+    Number,
+    Boolean,
+    /// One of the listed options
+    Select(Vec<String>),
+    /// Any subset of the listed options
+    MultiSelect(Vec<String>),
+    Date,
+}
+
+impl FieldType {
+    /// Lowercase name, e.g. `multi_select`
+    pub fn name(&self) -> &'static str {
+        match self {
+            FieldType::Text => "text",
+            FieldType::Number => "number",
+            FieldType::Boolean => "boolean",
+            FieldType::Select(_) => "select",
+            FieldType::MultiSelect(_) => "multi_select",
+            FieldType::Date => "date",
+        }
+    }
+
+    /// Options for select fields, empty otherwise
+    pub fn options(&self) -> &[String] {
+        match self {
+            FieldType::Select(options) | FieldType::MultiSelect(options) => options,
+            _ => &[],
+        }
+    }
+}
+
 /// Media content
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Media {
@@ -598,6 +1079,34 @@ mod base64_serde {
     }
 }
 
+mod geojson_serde {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize)]
+    struct Repr<'a> {
+        value: &'a serde_json::Value,
+    }
+
+    #[derive(Deserialize)]
+    struct OwnedRepr {
+        value: serde_json::Value,
+    }
+
+    pub fn serialize<S>(value: &serde_json::Value, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Repr { value }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<serde_json::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(OwnedRepr::deserialize(deserializer)?.value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -640,6 +1149,40 @@ mod tests {
         assert_eq!(code.get_lines(2, 3), Some("line2\nline3".to_string()));
     }
 
+    #[test]
+    fn test_canonical_language() {
+        assert_eq!(canonical_language("js"), "javascript");
+        assert_eq!(canonical_language("Rust ignore"), "rust");
+        assert_eq!(canonical_language(""), "text");
+        assert_eq!(canonical_language("Haskell"), "haskell");
+        assert_eq!(Code::new("py", "x = 1").canonical_language(), "python");
+    }
+
+    #[test]
+    fn test_rich_text_content() {
+        let content = Content::rich_text(vec![
+            InlineSpan::plain("See "),
+            InlineSpan::Bold(vec![InlineSpan::Italic(vec![InlineSpan::plain("this")])]),
+            InlineSpan::Link {
+                text: vec![InlineSpan::Code("x".into())],
+                href: "https://example.com".into(),
+            },
+            InlineSpan::BlockRef {
+                display: " intro".into(),
+                block_id: BlockId::root(),
+            },
+        ]);
+        assert_eq!(content.type_tag(), "rich_text");
+        match &content {
+            Content::RichText(r) => assert_eq!(r.plain_text(), "See thisx intro"),
+            _ => panic!("Expected RichText content"),
+        }
+
+        let json = serde_json::to_string(&content).unwrap();
+        let parsed: Content = serde_json::from_str(&json).unwrap();
+        assert_eq!(content, parsed);
+    }
+
     #[test]
     fn test_content_serialization() {
         let content = Content::text("Hello");
@@ -647,4 +1190,102 @@ mod tests {
         let parsed: Content = serde_json::from_str(&json).unwrap();
         assert_eq!(content, parsed);
     }
+
+    #[test]
+    fn test_form_serialization() {
+        let form = FormContent::new("Survey")
+            .with_field(FormField::new("name", "Name", FieldType::Text).required())
+            .with_field(
+                FormField::new(
+                    "colour",
+                    "Colour",
+                    FieldType::Select(vec!["red".into(), "blue".into()]),
+                )
+                .with_default(serde_json::json!("red")),
+            );
+        let content = Content::Form(form);
+        assert_eq!(content.type_tag(), "form");
+
+        let json = serde_json::to_value(&content).unwrap();
+        assert_eq!(json["fields"][1]["field_type"]["select"][1], "blue");
+        assert_eq!(json["fields"][0]["field_type"], "text");
+        let parsed: Content = serde_json::from_value(json).unwrap();
+        assert_eq!(content, parsed);
+    }
+
+    #[test]
+    fn test_diff_serialization() {
+        let content = Content::diff(DiffFormat::GitPatch, "diff --git a/x b/x\n");
+        assert_eq!(content.type_tag(), "diff");
+
+        let json = serde_json::to_value(&content).unwrap();
+        assert_eq!(json["format"], "git_patch");
+        let parsed: Content = serde_json::from_value(json).unwrap();
+        assert_eq!(content, parsed);
+
+        assert_eq!(DiffFormat::parse("JSON-Patch"), Some(DiffFormat::JsonPatch));
+        assert_eq!(DiffFormat::parse("svn"), None);
+    }
+
+    #[test]
+    fn test_geojson_validation_and_serialization() {
+        let point = serde_json::json!({"type": "Point", "coordinates": [1.0, 2.0]});
+        let content = Content::new_geojson(point.clone()).unwrap();
+        assert_eq!(content.type_tag(), "geojson");
+
+        let json = serde_json::to_value(&content).unwrap();
+        assert_eq!(json, serde_json::json!({"type": "geojson", "value": point}));
+        let parsed: Content = serde_json::from_value(json).unwrap();
+        assert_eq!(content, parsed);
+
+        assert!(Content::new_geojson(serde_json::json!({"type": "Circle"})).is_err());
+        assert!(Content::new_geojson(serde_json::json!({"type": "Feature"})).is_err());
+        assert!(Content::new_geojson(serde_json::json!([1, 2])).is_err());
+    }
+
+    #[test]
+    fn test_compressed_code_round_trip() {
+        let source = "fn main() {\n    println!(\"hi\");\n}\n".repeat(50);
+        let content = Content::compress_code("rust", source.clone());
+        let Content::CompressedCode {
+            compressed,
+            original_len,
+            ..
+        } = &content
+        else {
+            panic!("expected compressed code");
+        };
+        assert_eq!(*original_len, source.len());
+        assert!(compressed.len() < source.len() / 4);
+        assert_eq!(content.type_tag(), "code");
+        assert_eq!(content.code_language(), Some("rust"));
+        assert_eq!(content.size_bytes(), source.len());
+        assert_eq!(content.decompress().as_deref(), Some(source.as_str()));
+        assert_eq!(
+            crate::normalize::normalize_content(&content),
+            crate::normalize::normalize_content(&Content::code("rust", source))
+        );
+
+        let json = serde_json::to_string(&content).unwrap();
+        let parsed: Content = serde_json::from_str(&json).unwrap();
+        assert_eq!(content, parsed);
+        assert_eq!(Content::text("x").decompress(), None);
+    }
+
+    #[test]
+    fn test_compressed_code_decodes_without_zstd_library() {
+        let source = "def main():\n    print('hi')\n".repeat(40);
+        let Content::CompressedCode {
+            compressed,
+            original_len,
+            ..
+        } = Content::compress_code("python", source.clone())
+        else {
+            panic!("expected compressed code");
+        };
+        let decoded = ruzstd_decode(&compressed, original_len).unwrap();
+        assert_eq!(String::from_utf8(decoded).unwrap(), source);
+        assert_eq!(ruzstd_decode(&compressed, original_len + 1), None);
+        assert_eq!(ruzstd_decode(b"not zstd", 8), None);
+    }
 }
@@ -1,13 +1,16 @@
 //! Document - a collection of blocks with hierarchical structure.
 
 use crate::block::{Block, BlockState};
-use crate::edge::EdgeIndex;
+use crate::content::{canonical_language, Content};
+use crate::edge::{EdgeId, EdgeIndex, HyperEdge};
 use crate::error::{Error, ErrorCode, Result, ValidationIssue};
-use crate::id::BlockId;
+use crate::id::{BlockId, ContentHash};
 use crate::metadata::TokenModel;
+use crate::normalize::normalize_content;
 use crate::version::DocumentVersion;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::str::FromStr;
 
@@ -49,9 +52,11 @@ pub struct DocumentMetadata {
     pub authors: Vec<String>,
 
     /// Creation timestamp
+    #[serde(default = "Utc::now")]
     pub created_at: DateTime<Utc>,
 
     /// Last modification timestamp
+    #[serde(default = "Utc::now")]
     pub modified_at: DateTime<Utc>,
 
     /// Language (ISO 639-1)
@@ -94,6 +99,8 @@ pub struct DocumentIndices {
     pub by_content_type: HashMap<String, HashSet<BlockId>>,
     /// Blocks by label
     pub by_label: HashMap<String, BlockId>,
+    /// Code blocks by canonical language
+    pub by_language: HashMap<String, HashSet<BlockId>>,
 }
 
 impl DocumentIndices {
@@ -128,6 +135,14 @@ impl DocumentIndices {
         if let Some(label) = &block.metadata.label {
             self.by_label.insert(label.clone(), *id);
         }
+
+        // Index code by language
+        if let Some(language) = block.content.code_language() {
+            self.by_language
+                .entry(canonical_language(language))
+                .or_default()
+                .insert(*id);
+        }
     }
 
     /// Remove a block from indices
@@ -153,6 +168,12 @@ impl DocumentIndices {
         if let Some(label) = &block.metadata.label {
             self.by_label.remove(label);
         }
+
+        if let Some(language) = block.content.code_language() {
+            if let Some(set) = self.by_language.get_mut(&canonical_language(language)) {
+                set.remove(id);
+            }
+        }
     }
 
     /// Rebuild all indices from blocks
@@ -161,6 +182,7 @@ impl DocumentIndices {
         self.by_role.clear();
         self.by_content_type.clear();
         self.by_label.clear();
+        self.by_language.clear();
 
         for block in blocks.values() {
             self.index_block(block);
@@ -184,31 +206,109 @@ impl DocumentIndices {
     pub fn find_by_label(&self, label: &str) -> Option<BlockId> {
         self.by_label.get(label).cloned()
     }
+
+    /// Find code blocks by canonical language
+    pub fn find_by_language(&self, language: &str) -> HashSet<BlockId> {
+        self.by_language.get(language).cloned().unwrap_or_default()
+    }
+}
+
+/// Content fingerprints of interned blocks, used by [`Document::intern_block`]
+#[derive(Debug, Clone, Default)]
+pub struct ContentCache {
+    by_fingerprint: HashMap<ContentHash, BlockId>,
+}
+
+impl ContentCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// SHA-256 of the content type and its normalized form
+    pub fn fingerprint(content: &Content) -> ContentHash {
+        let mut hasher = Sha256::new();
+        hasher.update(content.type_tag().as_bytes());
+        hasher.update(b":");
+        hasher.update(normalize_content(content).as_bytes());
+        ContentHash(hasher.finalize().into())
+    }
+
+    /// Block first interned with this fingerprint
+    pub fn get(&self, fingerprint: &ContentHash) -> Option<BlockId> {
+        self.by_fingerprint.get(fingerprint).copied()
+    }
+
+    pub fn insert(&mut self, fingerprint: ContentHash, id: BlockId) {
+        self.by_fingerprint.insert(fingerprint, id);
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_fingerprint.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_fingerprint.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.by_fingerprint.clear();
+    }
+}
+
+/// Per-document behavior settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocumentConfig {
+    /// Let [`Document::intern_block`] reuse a block with identical content
+    /// instead of adding a duplicate
+    pub dedup_content: bool,
+}
+
+impl Default for DocumentConfig {
+    fn default() -> Self {
+        Self {
+            dedup_content: true,
+        }
+    }
+}
+
+/// Current version of the [`PortableDocument`] JSON schema
+pub const PORTABLE_SCHEMA_VERSION: u32 = 1;
+
+fn default_schema_version() -> u32 {
+    PORTABLE_SCHEMA_VERSION
 }
 
 /// Canonical JSON-safe representation of a [`Document`].
 ///
 /// This is the shared storage/interchange form for UCP documents and graph-backed
-/// workflows that need deterministic serialization.
+/// workflows that need deterministic serialization. Maps are key-ordered and
+/// children keep their document order. Unknown fields are ignored and missing
+/// optional fields take defaults, so older and newer writers interoperate.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortableDocument {
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
     pub id: String,
     pub root: String,
+    #[serde(default)]
     pub structure: BTreeMap<String, Vec<String>>,
     pub blocks: BTreeMap<String, Block>,
+    #[serde(default)]
     pub metadata: DocumentMetadata,
+    #[serde(default)]
     pub version: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hyperedges: Vec<HyperEdge>,
 }
 
 impl PortableDocument {
     pub fn from_document(doc: &Document) -> Self {
         let mut structure = BTreeMap::new();
         for (parent, children) in &doc.structure {
-            let mut ordered = children.clone();
-            ordered.sort_by_key(|id| id.to_string());
             structure.insert(
                 parent.to_string(),
-                ordered.into_iter().map(|id| id.to_string()).collect(),
+                children.iter().map(|id| id.to_string()).collect(),
             );
         }
 
@@ -218,12 +318,14 @@ impl PortableDocument {
         }
 
         Self {
+            schema_version: PORTABLE_SCHEMA_VERSION,
             id: doc.id.0.clone(),
             root: doc.root.to_string(),
             structure,
             blocks,
             metadata: doc.metadata.clone(),
             version: doc.version.counter,
+            hyperedges: doc.hyperedges.clone(),
         }
     }
 
@@ -258,15 +360,46 @@ impl PortableDocument {
             metadata: self.metadata.clone(),
             indices: DocumentIndices::default(),
             edge_index: EdgeIndex::default(),
+            hyperedges: self.hyperedges.clone(),
             version: DocumentVersion {
                 counter: self.version,
                 timestamp: Utc::now(),
                 state_hash: [0u8; 8],
             },
+            config: DocumentConfig::default(),
+            content_cache: ContentCache::default(),
         };
         doc.rebuild_indices();
         Ok(doc)
     }
+
+    /// Encode as JSON
+    pub fn to_json(&self, pretty: bool) -> Result<String> {
+        let json = if pretty {
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string(self)
+        };
+        json.map_err(|e| Error::new(ErrorCode::E901SerializationError, e.to_string()))
+    }
+
+    /// Decode from JSON produced by [`PortableDocument::to_json`]
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| Error::new(ErrorCode::E901SerializationError, e.to_string()))
+    }
+
+    /// Encode as MessagePack (field names are kept, so the layout mirrors JSON)
+    pub fn to_msgpack(&self) -> Result<Vec<u8>> {
+        rmp_serde::to_vec_named(self)
+            .map_err(|e| Error::new(ErrorCode::E901SerializationError, e.to_string()))
+    }
+
+    /// Decode from MessagePack produced by [`PortableDocument::to_msgpack`]
+    pub fn from_msgpack(bytes: &[u8]) -> Result<Self> {
+        rmp_serde::from_slice(bytes)
+            .map_err(|e| Error::new(ErrorCode::E901SerializationError, e.to_string()))
+    }
 }
 
 /// A UCM document is a collection of blocks with hierarchical structure.
@@ -293,8 +426,16 @@ pub struct Document {
     /// Edge index for relationship traversal
     pub edge_index: EdgeIndex,
 
+    /// Multi-source edges; each source is also recorded in `edge_index`
+    pub hyperedges: Vec<HyperEdge>,
+
     /// Document version for concurrency control
     pub version: DocumentVersion,
+
+    pub config: DocumentConfig,
+
+    /// Fingerprints of blocks added through [`Document::intern_block`]
+    pub content_cache: ContentCache,
 }
 
 impl Document {
@@ -314,7 +455,10 @@ impl Document {
             metadata: DocumentMetadata::new(),
             indices: DocumentIndices::new(),
             edge_index: EdgeIndex::new(),
+            hyperedges: Vec::new(),
             version: DocumentVersion::initial(),
+            config: DocumentConfig::default(),
+            content_cache: ContentCache::new(),
         }
     }
 
@@ -331,12 +475,32 @@ impl Document {
         portable.to_document()
     }
 
+    /// Serialize the full document to portable JSON
+    pub fn to_portable_json(&self) -> Result<String> {
+        self.to_portable().to_json(false)
+    }
+
+    /// Serialize the full document to indented portable JSON
+    pub fn to_portable_json_pretty(&self) -> Result<String> {
+        self.to_portable().to_json(true)
+    }
+
+    /// Deserialize a document from portable JSON
+    pub fn from_portable_json(json: &str) -> Result<Self> {
+        PortableDocument::from_json(json)?.to_document()
+    }
+
     /// Set document metadata
     pub fn with_metadata(mut self, metadata: DocumentMetadata) -> Self {
         self.metadata = metadata;
         self
     }
 
+    pub fn with_config(mut self, config: DocumentConfig) -> Self {
+        self.config = config;
+        self
+    }
+
     /// Get a block by ID
     pub fn get_block(&self, id: &BlockId) -> Option<&Block> {
         self.blocks.get(id)
@@ -396,6 +560,47 @@ impl Document {
         Ok(id)
     }
 
+    /// Add a block unless one with the same content was interned before, in
+    /// which case `block` is dropped and the existing block is also attached
+    /// under `parent` (once), so it is shared by every parent it was interned
+    /// under. Fails with [`Error::CycleDetected`] if `parent` is the existing
+    /// block or one of its descendants.
+    ///
+    /// Only content is compared, not role or metadata. With
+    /// [`DocumentConfig::dedup_content`] off this is [`Document::add_block`].
+    pub fn intern_block(&mut self, block: Block, parent: &BlockId) -> Result<BlockId> {
+        if !self.config.dedup_content {
+            return self.add_block(block, parent);
+        }
+        if !self.blocks.contains_key(parent) {
+            return Err(Error::BlockNotFound(parent.to_string()));
+        }
+
+        let fingerprint = ContentCache::fingerprint(&block.content);
+        if let Some(existing) = self.content_cache.get(&fingerprint) {
+            // The cached block may have been deleted or edited since
+            let unchanged = self
+                .blocks
+                .get(&existing)
+                .is_some_and(|cached| ContentCache::fingerprint(&cached.content) == fingerprint);
+            if unchanged {
+                if existing == *parent || self.descendants(&existing).contains(parent) {
+                    return Err(Error::CycleDetected(existing.to_string()));
+                }
+                let children = self.structure.entry(*parent).or_default();
+                if !children.contains(&existing) {
+                    children.push(existing);
+                    self.touch();
+                }
+                return Ok(existing);
+            }
+        }
+
+        let id = self.add_block(block, parent)?;
+        self.content_cache.insert(fingerprint, id);
+        Ok(id)
+    }
+
     /// Add a block at a specific position
     pub fn add_block_at(
         &mut self,
@@ -438,6 +643,46 @@ impl Document {
         }
     }
 
+    /// Add a hyperedge after checking that its sources and target exist
+    pub fn add_hyperedge(&mut self, hyperedge: HyperEdge) -> Result<EdgeId> {
+        if hyperedge.sources.is_empty() {
+            return Err(Error::Validation(
+                "hyperedge needs at least one source".to_string(),
+            ));
+        }
+        for id in hyperedge.sources.iter().chain([&hyperedge.target]) {
+            if !self.blocks.contains_key(id) {
+                return Err(Error::BlockNotFound(id.to_string()));
+            }
+        }
+
+        let edge = hyperedge.to_edge();
+        for source in &hyperedge.sources {
+            self.edge_index.add_edge(source, &edge);
+        }
+        let id = hyperedge.id.clone();
+        self.hyperedges.push(hyperedge);
+        self.touch();
+        Ok(id)
+    }
+
+    /// Remove a hyperedge by ID
+    pub fn remove_hyperedge(&mut self, id: &EdgeId) -> Option<HyperEdge> {
+        let pos = self.hyperedges.iter().position(|h| &h.id == id)?;
+        let removed = self.hyperedges.remove(pos);
+        self.rebuild_edge_index();
+        self.touch();
+        Some(removed)
+    }
+
+    /// Hyperedges that have `source` among their sources
+    pub fn hyperedges_from<'a>(
+        &'a self,
+        source: &'a BlockId,
+    ) -> impl Iterator<Item = &'a HyperEdge> + 'a {
+        self.hyperedges.iter().filter(move |h| h.has_source(source))
+    }
+
     /// Remove a block from the structure (makes it orphaned)
     pub fn remove_from_structure(&mut self, id: &BlockId) -> bool {
         let mut removed = false;
@@ -474,6 +719,14 @@ impl Document {
         // Remove from indices
         self.indices.remove_block(&block);
 
+        // A hyperedge without one of its endpoints no longer holds
+        let hyperedge_count = self.hyperedges.len();
+        self.hyperedges
+            .retain(|h| h.target != *id && !h.has_source(id));
+        if self.hyperedges.len() != hyperedge_count {
+            self.rebuild_edge_index();
+        }
+
         self.touch();
         Ok(block)
     }
@@ -702,6 +955,47 @@ impl Document {
         pruned
     }
 
+    /// Store code blocks whose source is longer than `threshold_bytes` as
+    /// [`Content::CompressedCode`]; returns how many were compressed.
+    ///
+    /// IDs, content hashes and fingerprints are unchanged. Blocks with line
+    /// highlights are left alone since the compressed form cannot hold them.
+    pub fn compress_large_blocks(&mut self, threshold_bytes: usize) -> usize {
+        let mut compressed = 0;
+        for block in self.blocks.values_mut() {
+            let Content::Code(code) = &block.content else {
+                continue;
+            };
+            if code.source.len() <= threshold_bytes || !code.highlights.is_empty() {
+                continue;
+            }
+            let packed = Content::compress_code(code.language.clone(), code.source.clone());
+            if matches!(packed, Content::CompressedCode { .. }) {
+                block.content = packed;
+                compressed += 1;
+            }
+        }
+        compressed
+    }
+
+    /// Code blocks in `language`, matched by canonical name so `js` finds
+    /// `javascript` blocks. Reachable blocks come first, in document order.
+    pub fn find_blocks_by_language(&self, language: &str) -> Vec<BlockId> {
+        let mut matches = self.indices.find_by_language(&canonical_language(language));
+        let mut ids = Vec::new();
+        let mut stack = vec![self.root];
+        while let Some(id) = stack.pop() {
+            if matches.remove(&id) {
+                ids.push(id);
+            }
+            stack.extend(self.children(&id).iter().rev());
+        }
+        let mut orphans: Vec<BlockId> = matches.into_iter().collect();
+        orphans.sort_by_key(|id| id.to_string());
+        ids.extend(orphans);
+        ids
+    }
+
     /// Get total block count
     pub fn block_count(&self) -> usize {
         self.blocks.len()
@@ -750,6 +1044,19 @@ impl Document {
                 }
             }
         }
+        for hyperedge in &self.hyperedges {
+            for id in hyperedge.sources.iter().chain([&hyperedge.target]) {
+                if !self.blocks.contains_key(id) {
+                    issues.push(ValidationIssue::error(
+                        ErrorCode::E001BlockNotFound,
+                        format!(
+                            "Hyperedge {} references non-existent block {}",
+                            hyperedge.id, id
+                        ),
+                    ));
+                }
+            }
+        }
 
         issues
     }
@@ -796,20 +1103,28 @@ impl Document {
     /// Rebuild all indices
     pub fn rebuild_indices(&mut self) {
         self.indices.rebuild(&self.blocks);
+        self.rebuild_edge_index();
+    }
 
+    fn rebuild_edge_index(&mut self) {
         self.edge_index.clear();
         for block in self.blocks.values() {
             for edge in &block.edges {
                 self.edge_index.add_edge(&block.id, edge);
             }
         }
+        for hyperedge in &self.hyperedges {
+            let edge = hyperedge.to_edge();
+            for source in &hyperedge.sources {
+                self.edge_index.add_edge(source, &edge);
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::content::Content;
 
     #[test]
     fn test_document_creation() {
@@ -829,6 +1144,92 @@ mod tests {
         assert!(doc.is_reachable(&id));
     }
 
+    #[test]
+    fn test_intern_block_reuses_identical_content() {
+        let mut doc = Document::create();
+        let root = doc.root;
+
+        let first = doc
+            .intern_block(Block::new(Content::text("Repeated"), Some("intro")), &root)
+            .unwrap();
+        let second = doc
+            .intern_block(Block::new(Content::text("Repeated"), Some("body")), &root)
+            .unwrap();
+        let code = doc
+            .intern_block(Block::new(Content::code("", "Repeated"), None), &root)
+            .unwrap();
+        assert_eq!(first, second);
+        assert_ne!(first, code);
+        assert_eq!(doc.block_count(), 3);
+        assert_eq!(doc.content_cache.len(), 2);
+
+        doc.delete_block(&first).unwrap();
+        let again = doc
+            .intern_block(Block::new(Content::text("Repeated"), Some("body")), &root)
+            .unwrap();
+        assert_ne!(again, first);
+        assert!(doc.is_reachable(&again));
+    }
+
+    #[test]
+    fn test_intern_block_attaches_existing_block_under_each_parent() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let first_parent = doc
+            .add_block(Block::new(Content::text("src/a.rs"), None), &root)
+            .unwrap();
+        let second_parent = doc
+            .add_block(Block::new(Content::text("src/b.rs"), None), &root)
+            .unwrap();
+
+        let first = doc
+            .intern_block(
+                Block::new(Content::text("fn util() {}"), None),
+                &first_parent,
+            )
+            .unwrap();
+        let second = doc
+            .intern_block(
+                Block::new(Content::text("fn util() {}"), None),
+                &second_parent,
+            )
+            .unwrap();
+        doc.intern_block(
+            Block::new(Content::text("fn util() {}"), None),
+            &second_parent,
+        )
+        .unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(doc.block_count(), 4);
+        assert_eq!(doc.children(&first_parent), [first]);
+        assert_eq!(doc.children(&second_parent), [first]);
+
+        // Sharing the block under itself would form a cycle
+        assert!(matches!(
+            doc.intern_block(Block::new(Content::text("fn util() {}"), None), &first),
+            Err(Error::CycleDetected(_))
+        ));
+    }
+
+    #[test]
+    fn test_intern_block_without_dedup_adds_every_block() {
+        let mut doc = Document::create().with_config(DocumentConfig {
+            dedup_content: false,
+        });
+        let root = doc.root;
+
+        let first = doc
+            .intern_block(Block::new(Content::text("Repeated"), Some("intro")), &root)
+            .unwrap();
+        let second = doc
+            .intern_block(Block::new(Content::text("Repeated"), Some("body")), &root)
+            .unwrap();
+        assert_ne!(first, second);
+        assert_eq!(doc.block_count(), 3);
+        assert!(doc.content_cache.is_empty());
+    }
+
     #[test]
     fn test_move_block() {
         let mut doc = Document::create();
@@ -918,4 +1319,146 @@ mod tests {
         assert!(doc.indices.find_by_tag("important").contains(&id));
         assert_eq!(doc.indices.find_by_label("My Block"), Some(id));
     }
+
+    #[test]
+    fn test_find_blocks_by_language() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let section = doc
+            .add_block(Block::new(Content::text("Section"), None), &root)
+            .unwrap();
+        let first = doc
+            .add_block(Block::new(Content::code("js", "let a;"), None), &section)
+            .unwrap();
+        doc.add_block(Block::new(Content::code("rust", "fn f() {}"), None), &root)
+            .unwrap();
+        let second = doc
+            .add_block(
+                Block::new(Content::code("JavaScript", "let b;"), None),
+                &root,
+            )
+            .unwrap();
+
+        assert_eq!(doc.find_blocks_by_language("node"), vec![first, second]);
+        assert!(doc.find_blocks_by_language("python").is_empty());
+
+        doc.delete_block(&first).unwrap();
+        assert_eq!(doc.find_blocks_by_language("javascript"), vec![second]);
+    }
+
+    #[test]
+    fn test_compress_large_blocks_keeps_identity() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let source = "def f():\n    return 1\n".repeat(100);
+        let large = doc
+            .add_block(Block::new(Content::code("py", source.clone()), None), &root)
+            .unwrap();
+        let small = doc
+            .add_block(Block::new(Content::code("py", "x = 1"), None), &root)
+            .unwrap();
+        let hash = doc.get_block(&large).unwrap().metadata.content_hash;
+
+        assert_eq!(doc.compress_large_blocks(1024), 1);
+        assert_eq!(doc.compress_large_blocks(1024), 0);
+        let block = doc.get_block(&large).unwrap();
+        assert!(matches!(block.content, Content::CompressedCode { .. }));
+        assert_eq!(block.content.decompress(), Some(source.clone()));
+        assert_eq!(block.metadata.content_hash, hash);
+        assert_eq!(
+            crate::id::generate_block_id(&block.content, None, None),
+            crate::id::generate_block_id(&Content::code("py", source), None, None)
+        );
+        assert!(matches!(
+            doc.get_block(&small).unwrap().content,
+            Content::Code(_)
+        ));
+
+        doc.rebuild_indices();
+        assert_eq!(doc.find_blocks_by_language("python"), vec![large, small]);
+    }
+
+    #[test]
+    fn test_portable_msgpack_roundtrip() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let heading = doc
+            .add_block(
+                Block::new(Content::text("Intro"), Some("heading1")).with_tag("important"),
+                &root,
+            )
+            .unwrap();
+        doc.add_block(
+            Block::new(Content::code("rust", "fn main() {}"), None),
+            &heading,
+        )
+        .unwrap();
+        doc.add_block(
+            Block::new(
+                Content::json(serde_json::json!({"a": [1, 2.5, null]})),
+                None,
+            ),
+            &heading,
+        )
+        .unwrap();
+
+        let portable = doc.to_portable();
+        let bytes = portable.to_msgpack().unwrap();
+        let json = serde_json::to_vec(&portable).unwrap();
+        assert!(bytes.len() < json.len());
+
+        let decoded = PortableDocument::from_msgpack(&bytes).unwrap();
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&portable).unwrap()
+        );
+
+        let restored = decoded.to_document().unwrap();
+        assert_eq!(restored.block_count(), 4);
+        assert!(restored.indices.find_by_tag("important").contains(&heading));
+    }
+
+    #[test]
+    fn test_portable_msgpack_rejects_garbage() {
+        let err = PortableDocument::from_msgpack(&[0xc1, 0x00]).unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::E901SerializationError));
+    }
+
+    #[test]
+    fn test_hyperedges() {
+        use crate::edge::EdgeType;
+
+        let mut doc = Document::create();
+        let root = doc.root;
+        let a = doc
+            .add_block(Block::new(Content::text("A"), None), &root)
+            .unwrap();
+        let b = doc
+            .add_block(Block::new(Content::text("B"), None), &root)
+            .unwrap();
+        let c = doc
+            .add_block(Block::new(Content::text("C"), None), &root)
+            .unwrap();
+
+        let id = doc
+            .add_hyperedge(HyperEdge::new(vec![a, b], EdgeType::References, c))
+            .unwrap();
+        assert!(doc
+            .add_hyperedge(HyperEdge::new(Vec::new(), EdgeType::References, c))
+            .is_err());
+        assert_eq!(doc.edge_index.incoming_to(&c).len(), 2);
+        assert_eq!(doc.hyperedges_from(&b).count(), 1);
+
+        let restored = Document::from_portable_json(&doc.to_portable_json().unwrap()).unwrap();
+        assert_eq!(restored.hyperedges, doc.hyperedges);
+        assert_eq!(restored.edge_index.incoming_to(&c).len(), 2);
+
+        let mut removed = doc.clone();
+        assert!(removed.remove_hyperedge(&id).is_some());
+        assert!(removed.edge_index.incoming_to(&c).is_empty());
+
+        doc.delete_block(&a).unwrap();
+        assert!(doc.hyperedges.is_empty());
+        assert!(doc.edge_index.incoming_to(&c).is_empty());
+    }
 }
@@ -10,6 +10,7 @@ use std::collections::HashMap;
 use std::error::Error as StdError;
 use std::fmt;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// An edge represents an explicit relationship between blocks
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -18,10 +19,21 @@ pub struct Edge {
     pub edge_type: EdgeType,
     /// Target block
     pub target: BlockId,
+    /// Numeric weight for weighted graph algorithms; path finding treats it
+    /// as the cost of following the edge
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+    /// Start of the period in which the relationship holds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<DateTime<Utc>>,
+    /// End of the period in which the relationship holds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_to: Option<DateTime<Utc>>,
     /// Edge-specific metadata
     #[serde(default, skip_serializing_if = "EdgeMetadata::is_empty")]
     pub metadata: EdgeMetadata,
     /// When the edge was created
+    #[serde(default = "Utc::now")]
     pub created_at: DateTime<Utc>,
 }
 
@@ -31,6 +43,9 @@ impl Edge {
         Self {
             edge_type,
             target,
+            weight: None,
+            valid_from: None,
+            valid_to: None,
             metadata: EdgeMetadata::default(),
             created_at: Utc::now(),
         }
@@ -42,6 +57,28 @@ impl Edge {
         self
     }
 
+    /// Set the edge weight
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Limit the relationship to a time window; either end may be open
+    pub fn with_validity(
+        mut self,
+        valid_from: Option<DateTime<Utc>>,
+        valid_to: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.valid_from = valid_from;
+        self.valid_to = valid_to;
+        self
+    }
+
+    /// Whether the relationship ended before `now`
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        self.valid_to.is_some_and(|to| to < now)
+    }
+
     /// Add confidence score
     pub fn with_confidence(mut self, confidence: f32) -> Self {
         self.metadata.confidence = Some(confidence);
@@ -55,6 +92,82 @@ impl Edge {
     }
 }
 
+/// Hyperedge identifier
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EdgeId(pub String);
+
+impl EdgeId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn generate() -> Self {
+        // The counter keeps IDs unique when several are created in the same tick
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let ts = Utc::now().timestamp_nanos_opt().unwrap_or(0);
+        let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+        Self(format!("edge_{:x}_{:x}", ts, seq))
+    }
+}
+
+impl fmt::Display for EdgeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A relationship with several sources and one target, such as two blocks
+/// that jointly cite a third.
+///
+/// Hyperedges live on the [`Document`](crate::Document) rather than on a
+/// block. The edge index records one entry per source, so incoming lookups
+/// on the target see every source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HyperEdge {
+    pub id: EdgeId,
+    pub sources: Vec<BlockId>,
+    pub edge_type: EdgeType,
+    pub target: BlockId,
+    #[serde(default, skip_serializing_if = "EdgeMetadata::is_empty")]
+    pub metadata: EdgeMetadata,
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl HyperEdge {
+    /// Create a new hyperedge with a generated ID
+    pub fn new(sources: Vec<BlockId>, edge_type: EdgeType, target: BlockId) -> Self {
+        Self {
+            id: EdgeId::generate(),
+            sources,
+            edge_type,
+            target,
+            metadata: EdgeMetadata::default(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Add metadata to the hyperedge
+    pub fn with_metadata(mut self, metadata: EdgeMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Whether `block` is one of the sources
+    pub fn has_source(&self, block: &BlockId) -> bool {
+        self.sources.contains(block)
+    }
+
+    /// The pairwise edge each source contributes to the edge index
+    pub fn to_edge(&self) -> Edge {
+        Edge {
+            metadata: self.metadata.clone(),
+            created_at: self.created_at,
+            ..Edge::new(self.edge_type.clone(), self.target)
+        }
+    }
+}
+
 /// Types of relationships between blocks
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -398,6 +511,7 @@ impl EdgeIndex {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
 
     fn make_id(n: u8) -> BlockId {
         BlockId::from_bytes([n; 12])
@@ -411,6 +525,31 @@ mod tests {
 
         assert_eq!(edge.edge_type, EdgeType::References);
         assert_eq!(edge.metadata.confidence, Some(0.95));
+        assert_eq!(edge.weight, None);
+    }
+
+    #[test]
+    fn test_edge_weight_serialization() {
+        let edge = Edge::new(EdgeType::References, make_id(2)).with_weight(2.5);
+        let json = serde_json::to_value(&edge).unwrap();
+        assert_eq!(json["weight"], 2.5);
+        assert_eq!(serde_json::from_value::<Edge>(json).unwrap(), edge);
+
+        let unweighted = serde_json::to_value(Edge::new(EdgeType::References, make_id(2))).unwrap();
+        assert!(unweighted.get("weight").is_none());
+    }
+
+    #[test]
+    fn test_edge_validity_window() {
+        let day = |d: u32| Utc.with_ymd_and_hms(2024, 1, d, 0, 0, 0).unwrap();
+        let edge = Edge::new(EdgeType::References, make_id(2)).with_validity(None, Some(day(10)));
+        assert!(!edge.is_expired_at(day(10)));
+        assert!(edge.is_expired_at(day(11)));
+        assert!(!Edge::new(EdgeType::References, make_id(2)).is_expired_at(day(11)));
+
+        let json = serde_json::to_value(&edge).unwrap();
+        assert!(json.get("valid_from").is_none());
+        assert_eq!(serde_json::from_value::<Edge>(json).unwrap(), edge);
     }
 
     #[test]
@@ -2,6 +2,8 @@
 
 use thiserror::Error;
 
+use crate::id::BlockId;
+
 /// Result type alias using UCM Error
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -282,6 +284,8 @@ pub struct ValidationIssue {
     pub message: String,
     pub location: Option<Location>,
     pub suggestion: Option<String>,
+    /// Block the issue is about, if any
+    pub block_id: Option<BlockId>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -299,6 +303,7 @@ impl ValidationIssue {
             message: message.into(),
             location: None,
             suggestion: None,
+            block_id: None,
         }
     }
 
@@ -309,6 +314,7 @@ impl ValidationIssue {
             message: message.into(),
             location: None,
             suggestion: None,
+            block_id: None,
         }
     }
 
@@ -319,6 +325,7 @@ impl ValidationIssue {
             message: message.into(),
             location: None,
             suggestion: None,
+            block_id: None,
         }
     }
 
@@ -331,6 +338,56 @@ impl ValidationIssue {
         self.suggestion = Some(suggestion.into());
         self
     }
+
+    pub fn with_block_id(mut self, block_id: BlockId) -> Self {
+        self.block_id = Some(block_id);
+        self
+    }
+}
+
+/// Validation result
+#[derive(Debug, Clone)]
+pub struct ValidationResult {
+    pub valid: bool,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationResult {
+    pub fn valid() -> Self {
+        Self {
+            valid: true,
+            issues: Vec::new(),
+        }
+    }
+
+    pub fn invalid(issues: Vec<ValidationIssue>) -> Self {
+        let has_errors = issues
+            .iter()
+            .any(|i| i.severity == ValidationSeverity::Error);
+        Self {
+            valid: !has_errors,
+            issues,
+        }
+    }
+
+    pub fn errors(&self) -> Vec<&ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == ValidationSeverity::Error)
+            .collect()
+    }
+
+    pub fn warnings(&self) -> Vec<&ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == ValidationSeverity::Warning)
+            .collect()
+    }
+
+    pub fn merge(&mut self, other: ValidationResult) {
+        self.issues.extend(other.issues);
+        self.valid = self.valid && other.valid;
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,180 @@
+//! Checking JSON values against a subset of JSON Schema.
+//!
+//! Supports `type`, `enum`, `const`, `required`, `properties`,
+//! `additionalProperties: false`, `items`, `minLength`/`maxLength`,
+//! `minimum`/`maximum` and `minItems`/`maxItems`. Other keywords are ignored.
+
+use serde_json::Value;
+
+/// Every way `value` breaks `schema`, as `path: problem` messages
+pub fn schema_violations(schema: &Value, value: &Value) -> Vec<String> {
+    let mut violations = Vec::new();
+    check(schema, value, "$", &mut violations);
+    violations
+}
+
+fn check(schema: &Value, value: &Value, path: &str, out: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let names: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !names.is_empty() && !names.iter().any(|name| has_type(value, name)) {
+            out.push(format!(
+                "{}: expected {}, found {}",
+                path,
+                names.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+    if let Some(Value::Array(options)) = schema.get("enum") {
+        if !options.contains(value) {
+            out.push(format!(
+                "{}: {} is not one of the allowed values",
+                path, value
+            ));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            out.push(format!("{}: expected {}", path, expected));
+        }
+    }
+
+    match value {
+        Value::String(s) => {
+            let len = s.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if len < min {
+                    out.push(format!("{}: shorter than {} characters", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if len > max {
+                    out.push(format!("{}: longer than {} characters", path, max));
+                }
+            }
+        }
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or(f64::NAN);
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if n < min {
+                    out.push(format!("{}: less than {}", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if n > max {
+                    out.push(format!("{}: greater than {}", path, max));
+                }
+            }
+        }
+        Value::Array(items) => {
+            let len = items.len() as u64;
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if len < min {
+                    out.push(format!("{}: fewer than {} items", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if len > max {
+                    out.push(format!("{}: more than {} items", path, max));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    check(item_schema, item, &format!("{}[{}]", path, i), out);
+                }
+            }
+        }
+        Value::Object(fields) => {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !fields.contains_key(name) {
+                        out.push(format!("{}: missing required property {:?}", path, name));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (name, field) in fields {
+                match properties.and_then(|p| p.get(name)) {
+                    Some(field_schema) => {
+                        check(field_schema, field, &format!("{}.{}", path, name), out)
+                    }
+                    None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                        out.push(format!("{}: unexpected property {:?}", path, name));
+                    }
+                    None => {}
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "integer" => value.as_i64().is_some() || value.as_u64().is_some(),
+        "number" => value.is_number(),
+        other => type_name(value) == other,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_schema_violations() {
+        let schema = json!({
+            "type": "object",
+            "required": ["text"],
+            "properties": {
+                "text": {"type": "string", "minLength": 3},
+                "format": {"enum": ["plain", "markdown"]},
+                "tags": {"type": "array", "items": {"type": "integer"}, "maxItems": 2}
+            },
+            "additionalProperties": false
+        });
+        assert!(schema_violations(&schema, &json!({"text": "hello"})).is_empty());
+        assert_eq!(
+            schema_violations(
+                &schema,
+                &json!({"text": "hi", "format": "html", "tags": [1, "x", 3], "extra": 1})
+            ),
+            vec![
+                "$: unexpected property \"extra\"",
+                "$.format: \"html\" is not one of the allowed values",
+                "$.tags: more than 2 items",
+                "$.tags[1]: expected integer, found string",
+                "$.text: shorter than 3 characters",
+            ]
+        );
+        assert_eq!(
+            schema_violations(&schema, &json!([])),
+            vec!["$: expected object, found array"]
+        );
+        assert_eq!(
+            schema_violations(&schema, &json!({})),
+            vec!["$: missing required property \"text\""]
+        );
+    }
+}
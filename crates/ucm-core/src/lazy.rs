@@ -0,0 +1,342 @@
+//! Documents whose block content stays on disk until it is read.
+//!
+//! [`LazyDocument::write`] stores a document as one record per block followed
+//! by a header holding the tree and each record's location. [`LazyDocument::open`]
+//! memory-maps that file and keeps only the header in memory; block content is
+//! decoded on first access. Hyperedges and the document config live in the
+//! header.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+
+use crate::block::Block;
+use crate::content::Content;
+use crate::document::{
+    ContentCache, Document, DocumentConfig, DocumentId, DocumentIndices, DocumentMetadata,
+};
+use crate::edge::{EdgeIndex, HyperEdge};
+use crate::error::{Error, Result};
+use crate::id::BlockId;
+use crate::version::DocumentVersion;
+
+const MAGIC: &[u8; 8] = b"UCMLAZY1";
+const TRAILER_LEN: usize = 8;
+
+/// Where a block's content currently lives
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContentRef {
+    /// Decoded and cached in memory; boxed so unloaded blocks stay small
+    Loaded(Box<Content>),
+    /// Not read yet; `offset` and `length` locate the block's record in the
+    /// mapped file
+    NotLoaded { offset: u64, length: usize },
+}
+
+/// Read-only view of a document written by [`LazyDocument::write`].
+///
+/// Structure and document metadata are in memory; block content and block
+/// metadata are read from the memory-mapped file when asked for.
+#[derive(Debug, Clone)]
+pub struct LazyDocument {
+    pub id: DocumentId,
+    pub root: BlockId,
+    pub structure: HashMap<BlockId, Vec<BlockId>>,
+    pub metadata: DocumentMetadata,
+    pub version: DocumentVersion,
+    pub hyperedges: Vec<HyperEdge>,
+    pub config: DocumentConfig,
+    contents: HashMap<BlockId, ContentRef>,
+    records: HashMap<BlockId, (u64, usize)>,
+    mmap: Arc<Mmap>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    id: DocumentId,
+    root: BlockId,
+    structure: Vec<(BlockId, Vec<BlockId>)>,
+    metadata: DocumentMetadata,
+    version: DocumentVersion,
+    /// Block id, record offset, record length
+    blocks: Vec<(BlockId, u64, usize)>,
+    // Absent in files written before these were stored
+    #[serde(default)]
+    hyperedges: Vec<HyperEdge>,
+    #[serde(default)]
+    config: DocumentConfig,
+}
+
+/// The part of a block record needed for [`LazyDocument::get_block_content`]
+#[derive(Deserialize)]
+struct ContentRecord {
+    content: Content,
+}
+
+impl LazyDocument {
+    /// Write `doc` in the lazy file format
+    pub fn write(doc: &Document, path: impl AsRef<Path>) -> Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(MAGIC)?;
+        let mut offset = MAGIC.len() as u64;
+
+        let mut ids: Vec<&BlockId> = doc.blocks.keys().collect();
+        ids.sort_by_key(|id| id.to_string());
+        let mut blocks = Vec::with_capacity(ids.len());
+        for id in ids {
+            let record = serde_json::to_vec(&doc.blocks[id])?;
+            out.write_all(&record)?;
+            blocks.push((*id, offset, record.len()));
+            offset += record.len() as u64;
+        }
+
+        let mut structure: Vec<_> = doc
+            .structure
+            .iter()
+            .map(|(parent, children)| (*parent, children.clone()))
+            .collect();
+        structure.sort_by_key(|(parent, _)| parent.to_string());
+        let header = serde_json::to_vec(&Header {
+            id: doc.id.clone(),
+            root: doc.root,
+            structure,
+            metadata: doc.metadata.clone(),
+            version: doc.version.clone(),
+            blocks,
+            hyperedges: doc.hyperedges.clone(),
+            config: doc.config.clone(),
+        })?;
+        out.write_all(&header)?;
+        out.write_all(&(header.len() as u64).to_le_bytes())?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Map a file written by [`LazyDocument::write`], reading only its header
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is only read. Like any mmap, it assumes the file
+        // is not truncated or rewritten while mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        let malformed =
+            |reason: &str| Error::Validation(format!("malformed lazy document: {}", reason));
+        if mmap.len() < MAGIC.len() + TRAILER_LEN || &mmap[..MAGIC.len()] != MAGIC {
+            return Err(malformed("missing header"));
+        }
+        let trailer_start = mmap.len() - TRAILER_LEN;
+        let mut trailer = [0u8; TRAILER_LEN];
+        trailer.copy_from_slice(&mmap[trailer_start..]);
+        let header_len = usize::try_from(u64::from_le_bytes(trailer))
+            .ok()
+            .filter(|len| *len <= trailer_start - MAGIC.len())
+            .ok_or_else(|| malformed("bad header length"))?;
+        let header_start = trailer_start - header_len;
+        let header: Header = serde_json::from_slice(&mmap[header_start..trailer_start])?;
+
+        let mut contents = HashMap::with_capacity(header.blocks.len());
+        let mut records = HashMap::with_capacity(header.blocks.len());
+        for (id, offset, length) in header.blocks {
+            let in_bounds = usize::try_from(offset)
+                .ok()
+                .and_then(|start| start.checked_add(length))
+                .is_some_and(|end| offset >= MAGIC.len() as u64 && end <= header_start);
+            if !in_bounds {
+                return Err(malformed(&format!("record for {} is out of bounds", id)));
+            }
+            contents.insert(id, ContentRef::NotLoaded { offset, length });
+            records.insert(id, (offset, length));
+        }
+
+        Ok(Self {
+            id: header.id,
+            root: header.root,
+            structure: header.structure.into_iter().collect(),
+            metadata: header.metadata,
+            version: header.version,
+            hyperedges: header.hyperedges,
+            config: header.config,
+            contents,
+            records,
+            mmap: Arc::new(mmap),
+        })
+    }
+
+    /// Number of blocks, including the root
+    pub fn block_count(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn contains_block(&self, id: &BlockId) -> bool {
+        self.records.contains_key(id)
+    }
+
+    pub fn children(&self, parent: &BlockId) -> &[BlockId] {
+        self.structure
+            .get(parent)
+            .map(|children| children.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Whether `id`'s content has been loaded, without loading it
+    pub fn content_ref(&self, id: &BlockId) -> Option<&ContentRef> {
+        self.contents.get(id)
+    }
+
+    /// Content of `id`, decoded from the file on first access and cached
+    pub fn get_block_content(&mut self, id: &BlockId) -> Result<&Content> {
+        let slot = self
+            .contents
+            .get_mut(id)
+            .ok_or_else(|| Error::BlockNotFound(id.to_string()))?;
+        if let ContentRef::NotLoaded { offset, length } = *slot {
+            let record: ContentRecord = serde_json::from_slice(record(&self.mmap, offset, length))?;
+            *slot = ContentRef::Loaded(Box::new(record.content));
+        }
+        match slot {
+            ContentRef::Loaded(content) => Ok(content),
+            ContentRef::NotLoaded { .. } => unreachable!("content was just loaded"),
+        }
+    }
+
+    /// Decode the whole block, including metadata and edges
+    pub fn get_block(&self, id: &BlockId) -> Result<Block> {
+        let (offset, length) = self
+            .records
+            .get(id)
+            .ok_or_else(|| Error::BlockNotFound(id.to_string()))?;
+        Ok(serde_json::from_slice(record(
+            &self.mmap, *offset, *length,
+        ))?)
+    }
+
+    /// Decode every block into a regular in-memory [`Document`]
+    pub fn into_document(self) -> Result<Document> {
+        let blocks = self
+            .records
+            .keys()
+            .map(|id| Ok((*id, self.get_block(id)?)))
+            .collect::<Result<HashMap<_, _>>>()?;
+        let mut doc = Document {
+            id: self.id,
+            root: self.root,
+            structure: self.structure,
+            blocks,
+            metadata: self.metadata,
+            indices: DocumentIndices::default(),
+            edge_index: EdgeIndex::default(),
+            hyperedges: self.hyperedges,
+            version: self.version,
+            config: self.config,
+            content_cache: ContentCache::default(),
+        };
+        doc.rebuild_indices();
+        Ok(doc)
+    }
+}
+
+fn record(mmap: &Mmap, offset: u64, length: usize) -> &[u8] {
+    // Bounds were checked in `LazyDocument::open`
+    let start = offset as usize;
+    &mmap[start..start + length]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edge::{Edge, EdgeType, HyperEdge};
+
+    fn sample() -> (Document, BlockId, BlockId) {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let intro = doc
+            .add_block(Block::new(Content::text("Intro"), Some("intro")), &root)
+            .unwrap();
+        let code = doc
+            .add_block(
+                Block::new(Content::code("rust", "fn main() {}"), None),
+                &intro,
+            )
+            .unwrap();
+        doc.get_block_mut(&code)
+            .unwrap()
+            .edges
+            .push(Edge::new(EdgeType::References, intro));
+        doc.rebuild_indices();
+        (doc, intro, code)
+    }
+
+    #[test]
+    fn test_content_loads_on_first_access() {
+        let (doc, intro, code) = sample();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("doc.ucml");
+        LazyDocument::write(&doc, &path).unwrap();
+
+        let mut lazy = LazyDocument::open(&path).unwrap();
+        assert_eq!(lazy.block_count(), 3);
+        assert_eq!(lazy.children(&intro), [code]);
+        assert!(matches!(
+            lazy.content_ref(&code),
+            Some(ContentRef::NotLoaded { .. })
+        ));
+
+        assert_eq!(
+            lazy.get_block_content(&code).unwrap(),
+            &Content::code("rust", "fn main() {}")
+        );
+        assert!(matches!(
+            lazy.content_ref(&code),
+            Some(ContentRef::Loaded(_))
+        ));
+        assert!(matches!(
+            lazy.content_ref(&intro),
+            Some(ContentRef::NotLoaded { .. })
+        ));
+        assert_eq!(lazy.get_block(&code).unwrap().edges.len(), 1);
+
+        let missing = BlockId::from_bytes([7; 12]);
+        assert!(matches!(
+            lazy.get_block_content(&missing),
+            Err(Error::BlockNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_into_document_round_trips() {
+        let (mut doc, intro, code) = sample();
+        let root = doc.root;
+        let hyperedge = HyperEdge::new(vec![intro, code], EdgeType::DerivedFrom, root);
+        doc.add_hyperedge(hyperedge.clone()).unwrap();
+        doc.config.dedup_content = false;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("doc.ucml");
+        LazyDocument::write(&doc, &path).unwrap();
+
+        let restored = LazyDocument::open(&path).unwrap().into_document().unwrap();
+        assert_eq!(restored.id, doc.id);
+        assert_eq!(restored.children(&intro), [code]);
+        assert_eq!(restored.blocks[&code].content, doc.blocks[&code].content);
+        assert_eq!(restored.edge_index.outgoing_from(&code).len(), 2);
+        assert_eq!(restored.hyperedges, vec![hyperedge]);
+        assert_eq!(restored.edge_index.incoming_to(&root).len(), 2);
+        assert!(!restored.config.dedup_content);
+    }
+
+    #[test]
+    fn test_rejects_truncated_file() {
+        let (doc, _, _) = sample();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("doc.ucml");
+        LazyDocument::write(&doc, &path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 3]).unwrap();
+
+        assert!(LazyDocument::open(&path).is_err());
+    }
+}
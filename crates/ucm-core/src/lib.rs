@@ -12,6 +12,7 @@
 //! - [`Content`] - Typed content (text, table, code, etc.)
 //! - [`Document`] - A collection of blocks with hierarchical structure
 //! - [`Edge`] - Explicit relationships between blocks
+//! - [`LazyDocument`] - A memory-mapped document that loads block content on demand
 //!
 //! ## Example
 //!
@@ -30,19 +31,34 @@ pub mod document;
 pub mod edge;
 pub mod error;
 pub mod id;
+pub mod json_schema;
+pub mod lazy;
 pub mod metadata;
 pub mod normalize;
+pub mod schema;
+pub mod spreadsheet;
+pub mod stats;
+pub mod translation;
 pub mod version;
 
 pub use block::{Block, BlockState};
 pub use content::{
-    BinaryEncoding, Cell, Code, Column, CompositeLayout, Content, DataType, Dimensions, JsonSchema,
-    LineRange, Math, MathFormat, Media, MediaSource, MediaType, Row, Table, TableSchema, Text,
-    TextFormat,
+    canonical_language, BinaryEncoding, Cell, Code, Column, CompositeLayout, Content, DataType,
+    DiagramContent, DiagramDialect, DiffContent, DiffFormat, Dimensions, FieldType, FormContent,
+    FormField, InlineSpan, JsonSchema, LineRange, Math, MathFormat, Media, MediaSource, MediaType,
+    RichText, Row, Table, TableSchema, Text, TextFormat,
 };
-pub use document::{Document, DocumentId, DocumentMetadata, PortableDocument};
-pub use edge::{Edge, EdgeIndex, EdgeMetadata, EdgeType};
-pub use error::{Error, ErrorCode, Result, ValidationIssue, ValidationSeverity};
+pub use document::{
+    ContentCache, Document, DocumentConfig, DocumentId, DocumentMetadata, PortableDocument,
+    PORTABLE_SCHEMA_VERSION,
+};
+pub use edge::{Edge, EdgeId, EdgeIndex, EdgeMetadata, EdgeType, HyperEdge};
+pub use error::{Error, ErrorCode, Result, ValidationIssue, ValidationResult, ValidationSeverity};
 pub use id::{BlockId, ContentHash, IdGenerator, IdGeneratorConfig};
+pub use lazy::{ContentRef, LazyDocument};
 pub use metadata::{BlockMetadata, RoleCategory, SemanticRole, TokenEstimate, TokenModel};
+pub use schema::{DocumentSchema, SectionPattern, StructurePattern};
+pub use spreadsheet::{CellValue, SpreadsheetContent};
+pub use stats::DocumentStats;
+pub use translation::{SourceSpan, TranslationReport, TranslationWarning, TranslationWarningCode};
 pub use version::{DocumentVersion, Version};
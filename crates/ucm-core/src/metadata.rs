@@ -37,9 +37,11 @@ pub struct BlockMetadata {
     pub content_hash: ContentHash,
 
     /// Creation timestamp
+    #[serde(default = "Utc::now")]
     pub created_at: DateTime<Utc>,
 
     /// Last modification timestamp
+    #[serde(default = "Utc::now")]
     pub modified_at: DateTime<Utc>,
 
     /// Custom key-value metadata
@@ -390,9 +392,16 @@ impl TokenEstimate {
         match content {
             Content::Text(text) => Self::estimate_text(&text.text),
             Content::Code(code) => Self::estimate_code(&code.source, &code.language),
+            Content::CompressedCode { language, .. } => {
+                Self::estimate_code(&content.decompress().unwrap_or_default(), language)
+            }
             Content::Table(table) => Self::estimate_table(&table.columns, &table.rows),
-            Content::Json { value, .. } => Self::estimate_json(value),
+            Content::Json { value, .. } | Content::GeoJson(value) => Self::estimate_json(value),
             Content::Math(math) => Self::estimate_text(&math.expression),
+            Content::Diagram(diagram) => {
+                Self::estimate_code(&diagram.source, diagram.dialect.fence_tag())
+            }
+            Content::Diff(diff) => Self::estimate_code(&diff.patch, "diff"),
             _ => Self::default_estimate(),
         }
     }
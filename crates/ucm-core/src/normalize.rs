@@ -4,7 +4,11 @@
 //! identical hashes, regardless of superficial differences like whitespace
 //! or Unicode representation.
 
-use crate::content::{Cell, Code, Column, Content, Math, Media, MediaSource, Row, Table, Text};
+use crate::content::{
+    Cell, Code, Column, Content, DiagramContent, DiffContent, Math, Media, MediaSource, RichText,
+    Row, Table, Text,
+};
+use crate::spreadsheet::SpreadsheetContent;
 use unicode_normalization::UnicodeNormalization;
 
 /// Normalization configuration
@@ -77,10 +81,33 @@ pub enum LineEndingNorm {
 pub fn normalize_content(content: &Content) -> String {
     match content {
         Content::Text(text) => normalize_text_content(text),
+        Content::RichText(rich) => normalize_rich_text_content(rich),
         Content::Code(code) => normalize_code_content(code),
+        // Same as the uncompressed code, so fingerprints ignore compression.
+        // Undecodable bytes hash as they are rather than as empty source.
+        Content::CompressedCode {
+            language,
+            compressed,
+            ..
+        } => match content.decompress() {
+            Some(source) => normalize_code_content(&Code::new(language.clone(), source)),
+            None => format!(
+                "compressed:{}:{}",
+                language,
+                hex::encode(sha256_hash(compressed))
+            ),
+        },
         Content::Table(table) => normalize_table_content(table),
         Content::Math(math) => normalize_math_content(math),
+        Content::Diagram(diagram) => normalize_diagram_content(diagram),
+        Content::Diff(diff) => normalize_diff_content(diff),
+        Content::Spreadsheet(sheet) => normalize_spreadsheet_content(sheet),
         Content::Media(media) => normalize_media_content(media),
+        Content::GeoJson(value) => format!("geojson:{}", canonical_json(value)),
+        Content::Form(form) => {
+            let value = serde_json::to_value(form).unwrap_or(serde_json::Value::Null);
+            format!("form:{}", canonical_json(&value))
+        }
         Content::Json { value, .. } => canonical_json(value),
         Content::Binary {
             data, mime_type, ..
@@ -104,6 +131,12 @@ fn normalize_text_content(text: &Text) -> String {
     )
 }
 
+fn normalize_rich_text_content(rich: &RichText) -> String {
+    // Span structure is part of the identity, so bold and plain text differ
+    let spans = serde_json::to_value(&rich.spans).unwrap_or(serde_json::Value::Null);
+    format!("rich:{}", canonical_json(&spans))
+}
+
 fn normalize_code_content(code: &Code) -> String {
     // Code preserves whitespace but normalizes line endings
     let config = NormalizationConfig {
@@ -169,6 +202,39 @@ fn normalize_math_content(math: &Math) -> String {
     format!("{:?}:{}", math.format, normalized_expr)
 }
 
+fn normalize_spreadsheet_content(sheet: &SpreadsheetContent) -> String {
+    let value = serde_json::to_value(sheet).unwrap_or(serde_json::Value::Null);
+    format!("sheet:{}", canonical_json(&value))
+}
+
+fn normalize_diagram_content(diagram: &DiagramContent) -> String {
+    // Layout can depend on whitespace, so only line endings are normalized
+    let config = NormalizationConfig {
+        whitespace: WhitespaceNorm::Preserve,
+        line_endings: LineEndingNorm::LF,
+        ..Default::default()
+    };
+    format!(
+        "{}:{}",
+        diagram.dialect.fence_tag(),
+        normalize_text(&diagram.source, config)
+    )
+}
+
+fn normalize_diff_content(diff: &DiffContent) -> String {
+    // Context lines and hunk offsets are whitespace-sensitive
+    let config = NormalizationConfig {
+        whitespace: WhitespaceNorm::Preserve,
+        line_endings: LineEndingNorm::LF,
+        ..Default::default()
+    };
+    format!(
+        "diff:{}:{}",
+        diff.format.as_str(),
+        normalize_text(&diff.patch, config)
+    )
+}
+
 fn normalize_media_content(media: &Media) -> String {
     let source = match &media.source {
         MediaSource::Url(url) => format!("url:{}", url),
@@ -370,6 +436,19 @@ mod tests {
         assert!(normalized.starts_with("rust:"));
     }
 
+    #[test]
+    fn test_undecodable_compressed_code_is_not_empty_source() {
+        let corrupt = |bytes: &[u8]| Content::CompressedCode {
+            language: "rust".to_string(),
+            compressed: bytes.to_vec(),
+            original_len: 4,
+        };
+        let normalized = normalize_content(&corrupt(b"bad"));
+        assert!(normalized.starts_with("compressed:rust:"));
+        assert_ne!(normalized, normalize_content(&Content::code("rust", "")));
+        assert_ne!(normalized, normalize_content(&corrupt(b"worse")));
+    }
+
     #[test]
     fn test_is_cjk() {
         assert!(is_cjk_character('中'));
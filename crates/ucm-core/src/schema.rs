@@ -0,0 +1,433 @@
+//! Document schemas: the roles, block content and hierarchy a document of a
+//! given kind (meeting notes, incident report, RFC, ...) must have.
+//!
+//! Roles are written as strings in the `SemanticRole` dotted form, e.g.
+//! `"heading1"` or `"intro.hook"`. A role in a schema matches any block role
+//! that agrees on every part the schema spells out, so `"intro"` also
+//! matches a block tagged `"intro.hook"`.
+
+use crate::block::Block;
+use crate::content::{Content, JsonSchema};
+use crate::document::Document;
+use crate::error::{Error, ErrorCode, Result, ValidationIssue, ValidationResult};
+use crate::id::{generate_block_id, BlockId};
+use crate::json_schema::schema_violations;
+use crate::metadata::SemanticRole;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The shape a document must have
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentSchema {
+    pub name: String,
+    /// Roles that at least one block must carry
+    #[serde(default, with = "role_list")]
+    pub required_roles: Vec<SemanticRole>,
+    /// JSON Schemas checked against the serialized content of blocks with
+    /// the keyed role, e.g. `{"type": "text", "text": ..., "format": ...}`
+    #[serde(default, with = "role_map")]
+    pub block_schemas: HashMap<SemanticRole, JsonSchema>,
+    #[serde(default)]
+    pub structure_pattern: StructurePattern,
+}
+
+/// Expected hierarchy below the document root
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StructurePattern {
+    /// Any hierarchy is accepted
+    #[default]
+    Any,
+    /// The root's children include these sections in this order. Other
+    /// blocks may sit between them.
+    Sections { sections: Vec<SectionPattern> },
+}
+
+/// One expected section and the sections expected below it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionPattern {
+    #[serde(with = "role_str")]
+    pub role: SemanticRole,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<SectionPattern>,
+}
+
+impl SectionPattern {
+    pub fn new(role: SemanticRole) -> Self {
+        Self {
+            role,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn with_child(mut self, child: SectionPattern) -> Self {
+        self.children.push(child);
+        self
+    }
+}
+
+impl DocumentSchema {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            required_roles: Vec::new(),
+            block_schemas: HashMap::new(),
+            structure_pattern: StructurePattern::Any,
+        }
+    }
+
+    pub fn with_required_role(mut self, role: SemanticRole) -> Self {
+        self.required_roles.push(role);
+        self
+    }
+
+    pub fn with_block_schema(mut self, role: SemanticRole, schema: JsonSchema) -> Self {
+        self.block_schemas.insert(role, schema);
+        self
+    }
+
+    pub fn with_structure(mut self, pattern: StructurePattern) -> Self {
+        self.structure_pattern = pattern;
+        self
+    }
+
+    /// Parse a schema from JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| Error::new(ErrorCode::E102InvalidValue, e.to_string()))
+    }
+
+    /// Create a skeleton document with an empty placeholder block for every
+    /// section in the structure pattern, then for each required role the
+    /// pattern does not already cover. The schema name is recorded in the
+    /// document's `custom.schema` metadata.
+    pub fn instantiate(&self) -> Document {
+        let mut doc = Document::create();
+        doc.metadata
+            .custom
+            .insert("schema".to_string(), serde_json::json!(self.name));
+        let root = doc.root;
+
+        if let StructurePattern::Sections { sections } = &self.structure_pattern {
+            self.add_placeholders(&mut doc, &root, sections, "");
+        }
+        let mut missing = Vec::new();
+        for role in &self.required_roles {
+            let present = doc.blocks.values().any(|block| has_role(block, role));
+            if !present {
+                missing.push(role.clone());
+            }
+        }
+        for (i, role) in missing.into_iter().enumerate() {
+            let path = format!("required/{}", i);
+            self.add_placeholder(&mut doc, &root, role, &path);
+        }
+        doc
+    }
+
+    fn add_placeholders(
+        &self,
+        doc: &mut Document,
+        parent: &BlockId,
+        sections: &[SectionPattern],
+        path: &str,
+    ) {
+        for (i, section) in sections.iter().enumerate() {
+            let path = format!("{}/{}", path, i);
+            let id = self.add_placeholder(doc, parent, section.role.clone(), &path);
+            self.add_placeholders(doc, &id, &section.children, &path);
+        }
+    }
+
+    fn add_placeholder(
+        &self,
+        doc: &mut Document,
+        parent: &BlockId,
+        role: SemanticRole,
+        path: &str,
+    ) -> BlockId {
+        let content = Content::text("");
+        // Every placeholder has the same content, so the position keeps IDs apart
+        let namespace = format!("{}{}", self.name, path);
+        let role_name = role.to_string();
+        let id = generate_block_id(&content, Some(&role_name), Some(&namespace));
+        let mut block = Block::with_id(id, content);
+        block.metadata.semantic_role = Some(role);
+        doc.add_block(block, parent)
+            .expect("placeholder parent was just added")
+    }
+}
+
+impl Document {
+    /// Check the document against a [`DocumentSchema`]
+    pub fn validate_against_schema(&self, schema: &DocumentSchema) -> ValidationResult {
+        let mut issues = Vec::new();
+
+        for role in &schema.required_roles {
+            if !self.blocks.values().any(|block| has_role(block, role)) {
+                issues.push(ValidationIssue::error(
+                    ErrorCode::E200SchemaViolation,
+                    format!(
+                        "Schema '{}' requires a block with role {}",
+                        schema.name, role
+                    ),
+                ));
+            }
+        }
+
+        let mut blocks: Vec<&Block> = self.blocks.values().collect();
+        blocks.sort_by_key(|block| block.id.0);
+        let mut roles: Vec<(&SemanticRole, &JsonSchema)> = schema.block_schemas.iter().collect();
+        roles.sort_by_key(|(role, _)| role.to_string());
+        for (role, json_schema) in roles {
+            let value = match json_schema {
+                JsonSchema::Inline(value) => value,
+                JsonSchema::Uri(uri) => {
+                    issues.push(ValidationIssue::warning(
+                        ErrorCode::E200SchemaViolation,
+                        format!(
+                            "Schema for role {} is a URI ({}) and was not checked",
+                            role, uri
+                        ),
+                    ));
+                    continue;
+                }
+            };
+            for block in blocks.iter().filter(|block| has_role(block, role)) {
+                let content = serde_json::to_value(&block.content).unwrap_or_default();
+                for violation in schema_violations(value, &content) {
+                    issues.push(
+                        ValidationIssue::error(
+                            ErrorCode::E200SchemaViolation,
+                            format!("Block {} content {}", block.id, violation),
+                        )
+                        .with_block_id(block.id),
+                    );
+                }
+            }
+        }
+
+        if let StructurePattern::Sections { sections } = &schema.structure_pattern {
+            self.match_sections(&self.root, sections, &mut issues);
+        }
+
+        ValidationResult::invalid(issues)
+    }
+
+    fn match_sections(
+        &self,
+        parent: &BlockId,
+        sections: &[SectionPattern],
+        issues: &mut Vec<ValidationIssue>,
+    ) {
+        let mut children = self.children(parent).iter();
+        for section in sections {
+            let found = children.find(|id| {
+                self.get_block(id)
+                    .is_some_and(|block| has_role(block, &section.role))
+            });
+            match found {
+                Some(id) => self.match_sections(id, &section.children, issues),
+                None => {
+                    issues.push(
+                        ValidationIssue::error(
+                            ErrorCode::E202InvalidStructure,
+                            format!(
+                                "Expected a {} block under {} after the preceding sections",
+                                section.role, parent
+                            ),
+                        )
+                        .with_block_id(*parent),
+                    );
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn has_role(block: &Block, expected: &SemanticRole) -> bool {
+    let Some(actual) = &block.metadata.semantic_role else {
+        return false;
+    };
+    actual.category == expected.category
+        && expected
+            .subcategory
+            .as_ref()
+            .map_or(true, |sub| actual.subcategory.as_ref() == Some(sub))
+        && expected
+            .qualifier
+            .as_ref()
+            .map_or(true, |qual| actual.qualifier.as_ref() == Some(qual))
+}
+
+fn parse_role<E: serde::de::Error>(s: &str) -> std::result::Result<SemanticRole, E> {
+    SemanticRole::parse(s).ok_or_else(|| E::custom(format!("unknown semantic role '{}'", s)))
+}
+
+mod role_str {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        role: &SemanticRole,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(role)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<SemanticRole, D::Error> {
+        parse_role(&String::deserialize(deserializer)?)
+    }
+}
+
+mod role_list {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        roles: &[SemanticRole],
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(roles.iter().map(|role| role.to_string()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Vec<SemanticRole>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|s| parse_role(s))
+            .collect()
+    }
+}
+
+mod role_map {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+    use std::collections::BTreeMap;
+
+    pub fn serialize<S: Serializer>(
+        schemas: &HashMap<SemanticRole, JsonSchema>,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let sorted: BTreeMap<String, &JsonSchema> = schemas
+            .iter()
+            .map(|(role, schema)| (role.to_string(), schema))
+            .collect();
+        sorted.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<HashMap<SemanticRole, JsonSchema>, D::Error> {
+        HashMap::<String, JsonSchema>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(role, schema)| Ok((parse_role(&role)?, schema)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::RoleCategory;
+    use serde_json::json;
+
+    fn incident_report() -> DocumentSchema {
+        DocumentSchema::from_json(
+            &json!({
+                "name": "incident_report",
+                "required_roles": ["title", "conclusion"],
+                "block_schemas": {
+                    "title": {"properties": {"text": {"type": "string", "minLength": 1}}}
+                },
+                "structure_pattern": {
+                    "kind": "sections",
+                    "sections": [
+                        {"role": "title"},
+                        {"role": "heading1", "children": [{"role": "paragraph"}]}
+                    ]
+                }
+            })
+            .to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_schema_json_round_trip() {
+        let schema = incident_report();
+        assert_eq!(
+            schema.required_roles,
+            vec![
+                SemanticRole::new(RoleCategory::Title),
+                SemanticRole::new(RoleCategory::Conclusion)
+            ]
+        );
+        let json = serde_json::to_string(&schema).unwrap();
+        assert_eq!(DocumentSchema::from_json(&json).unwrap(), schema);
+        assert!(DocumentSchema::from_json(r#"{"name": "x", "required_roles": ["nope"]}"#).is_err());
+    }
+
+    #[test]
+    fn test_instantiate_builds_skeleton() {
+        let schema = incident_report();
+        let doc = schema.instantiate();
+
+        let roles: Vec<String> = doc
+            .children(&doc.root)
+            .iter()
+            .map(|id| {
+                doc.get_block(id)
+                    .unwrap()
+                    .metadata
+                    .semantic_role
+                    .as_ref()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(roles, ["title", "heading1", "conclusion"]);
+        assert_eq!(doc.metadata.custom["schema"], json!("incident_report"));
+
+        // Placeholders satisfy the shape but not the title's content schema
+        let result = doc.validate_against_schema(&schema);
+        assert_eq!(result.errors().len(), 1);
+        assert!(result.errors()[0].message.contains("shorter than 1"));
+    }
+
+    #[test]
+    fn test_validate_reports_missing_roles_and_structure() {
+        let schema = incident_report();
+        let mut doc = Document::create();
+        let root = doc.root;
+        let heading = doc
+            .add_block(
+                Block::new(Content::text("Timeline"), Some("heading1")),
+                &root,
+            )
+            .unwrap();
+        doc.add_block(Block::new(Content::text("Outage"), Some("title")), &root)
+            .unwrap();
+        doc.add_block(
+            Block::new(Content::text("At 10:00"), Some("paragraph")),
+            &heading,
+        )
+        .unwrap();
+
+        let result = doc.validate_against_schema(&schema);
+        assert!(!result.valid);
+        let codes: Vec<ErrorCode> = result.issues.iter().map(|i| i.code).collect();
+        assert_eq!(
+            codes,
+            [
+                ErrorCode::E200SchemaViolation,
+                ErrorCode::E202InvalidStructure
+            ]
+        );
+        assert!(result.issues[0].message.contains("conclusion"));
+    }
+}
@@ -0,0 +1,643 @@
+//! Spreadsheet content: typed cells under a header row, with formulas.
+//!
+//! Formulas use A1 references into the data rows (`A1` is the first cell of
+//! the first row below the headers) and support `SUM`, `AVG` (or `AVERAGE`)
+//! and `COUNT` over cells, ranges (`B1:B4`) and number literals, or a single
+//! reference or number on its own. Text and booleans in a range are ignored,
+//! as in common spreadsheet applications.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::content::{Cell, Column, DataType, Row, Table};
+
+/// How far past the data rows and columns a formula may be placed
+pub const MAX_FORMULA_OVERHANG: usize = 1024;
+
+/// A grid of cells under a header row
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SpreadsheetContent {
+    #[serde(default)]
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<CellValue>>,
+    /// Formulas keyed by zero-based (row, column), taking precedence over
+    /// the cell at that position
+    #[serde(
+        default,
+        skip_serializing_if = "BTreeMap::is_empty",
+        with = "formula_entries"
+    )]
+    pub formulas: BTreeMap<(usize, usize), String>,
+}
+
+impl SpreadsheetContent {
+    pub fn new(headers: Vec<String>) -> Self {
+        Self {
+            headers,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_rows(mut self, rows: Vec<Vec<CellValue>>) -> Self {
+        self.rows = rows;
+        self
+    }
+
+    pub fn with_formula(mut self, row: usize, col: usize, formula: impl Into<String>) -> Self {
+        self.formulas.insert((row, col), formula.into());
+        self
+    }
+
+    /// The formula that determines the cell at (row, col), if any
+    pub fn formula_at(&self, row: usize, col: usize) -> Option<&str> {
+        if let Some(formula) = self.formulas.get(&(row, col)) {
+            return Some(formula);
+        }
+        match self.rows.get(row).and_then(|r| r.get(col)) {
+            Some(CellValue::Formula(formula)) => Some(formula),
+            _ => None,
+        }
+    }
+
+    /// The rows with every formula replaced by its value. A formula that
+    /// cannot be evaluated becomes an error text: `#NAME?` for an unknown
+    /// function, `#VALUE!` for a malformed formula, `#DIV/0!` for an average
+    /// of nothing and `#CYCLE!` for a circular reference.
+    ///
+    /// The grid grows to cover the positions in `formulas`; cells added that
+    /// way without a formula of their own are empty text. Formulas more than
+    /// [`MAX_FORMULA_OVERHANG`] rows below the data or columns right of it
+    /// are ignored, so one stray position cannot force a huge grid.
+    pub fn evaluate(&self) -> Vec<Vec<CellValue>> {
+        let (height, width) = self.extent();
+        let mut grid = self.rows.clone();
+        for &(r, c) in self.formulas.keys() {
+            if r >= height || c >= width {
+                continue;
+            }
+            if grid.len() <= r {
+                grid.resize_with(r + 1, Vec::new);
+            }
+            if grid[r].len() <= c {
+                grid[r].resize_with(c + 1, || CellValue::Text(String::new()));
+            }
+        }
+
+        let mut evaluator = Evaluator::new(self);
+        grid.into_iter()
+            .enumerate()
+            .map(|(r, row)| {
+                row.into_iter()
+                    .enumerate()
+                    .map(|(c, cell)| {
+                        if self.formula_at(r, c).is_none() {
+                            return cell;
+                        }
+                        match evaluator.evaluate((r, c)) {
+                            Ok(value) => value.unwrap_or(CellValue::Number(0.0)),
+                            Err(e) => CellValue::Text(e.code().to_string()),
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Rows and columns covered by the data and by formulas within
+    /// [`MAX_FORMULA_OVERHANG`] of it
+    fn extent(&self) -> (usize, usize) {
+        let data_width = self
+            .rows
+            .iter()
+            .map(Vec::len)
+            .chain([self.headers.len()])
+            .max()
+            .unwrap_or(0);
+        let row_limit = self.rows.len() + MAX_FORMULA_OVERHANG;
+        let col_limit = data_width + MAX_FORMULA_OVERHANG;
+        self.formulas
+            .keys()
+            .filter(|&&(r, c)| r < row_limit && c < col_limit)
+            .fold((self.rows.len(), data_width), |(height, width), &(r, c)| {
+                (height.max(r + 1), width.max(c + 1))
+            })
+    }
+
+    /// The evaluated sheet as a table, with the headers as its first row
+    pub fn to_table(&self) -> Table {
+        let columns = self
+            .headers
+            .iter()
+            .map(|name| Column::new(name.clone()).with_type(DataType::Text))
+            .collect();
+        let mut rows = Vec::with_capacity(self.rows.len() + 1);
+        if !self.headers.is_empty() {
+            rows.push(Row::new(
+                self.headers.iter().cloned().map(Cell::Text).collect(),
+            ));
+        }
+        rows.extend(
+            self.evaluate()
+                .into_iter()
+                .map(|row| Row::new(row.into_iter().map(CellValue::into_cell).collect())),
+        );
+        Table::new(columns).with_rows(rows)
+    }
+
+    pub(crate) fn size_bytes(&self) -> usize {
+        self.headers.iter().map(String::len).sum::<usize>()
+            + self
+                .rows
+                .iter()
+                .flatten()
+                .map(CellValue::size_bytes)
+                .sum::<usize>()
+            + self.formulas.values().map(String::len).sum::<usize>()
+    }
+}
+
+/// A spreadsheet cell.
+///
+/// In JSON a cell is a bare number, boolean or string, and a formula is
+/// `{"formula": "=SUM(A1:A3)"}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "CellRepr", into = "CellRepr")]
+pub enum CellValue {
+    Number(f64),
+    Text(String),
+    Boolean(bool),
+    Formula(String),
+}
+
+impl CellValue {
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            CellValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn into_cell(self) -> Cell {
+        match self {
+            CellValue::Number(n) => Cell::Number(n),
+            CellValue::Text(s) => Cell::Text(s),
+            CellValue::Boolean(b) => Cell::Boolean(b),
+            CellValue::Formula(f) if f.starts_with('=') => Cell::Text(f),
+            CellValue::Formula(f) => Cell::Text(format!("={}", f)),
+        }
+    }
+
+    fn size_bytes(&self) -> usize {
+        match self {
+            CellValue::Number(_) => 8,
+            CellValue::Boolean(_) => 1,
+            CellValue::Text(s) | CellValue::Formula(s) => s.len(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum CellRepr {
+    Number(f64),
+    Boolean(bool),
+    Text(String),
+    Formula { formula: String },
+}
+
+impl From<CellRepr> for CellValue {
+    fn from(repr: CellRepr) -> Self {
+        match repr {
+            CellRepr::Number(n) => CellValue::Number(n),
+            CellRepr::Boolean(b) => CellValue::Boolean(b),
+            CellRepr::Text(s) => CellValue::Text(s),
+            CellRepr::Formula { formula } => CellValue::Formula(formula),
+        }
+    }
+}
+
+impl From<CellValue> for CellRepr {
+    fn from(value: CellValue) -> Self {
+        match value {
+            CellValue::Number(n) => CellRepr::Number(n),
+            CellValue::Boolean(b) => CellRepr::Boolean(b),
+            CellValue::Text(s) => CellRepr::Text(s),
+            CellValue::Formula(formula) => CellRepr::Formula { formula },
+        }
+    }
+}
+
+/// Formulas as a list of `{row, col, formula}`, since JSON object keys
+/// cannot be tuples
+mod formula_entries {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Entry {
+        row: usize,
+        col: usize,
+        formula: String,
+    }
+
+    pub fn serialize<S: Serializer>(
+        formulas: &BTreeMap<(usize, usize), String>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        formulas
+            .iter()
+            .map(|(&(row, col), formula)| Entry {
+                row,
+                col,
+                formula: formula.clone(),
+            })
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<(usize, usize), String>, D::Error> {
+        Ok(Vec::<Entry>::deserialize(deserializer)?
+            .into_iter()
+            .map(|e| ((e.row, e.col), e.formula))
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FormulaError {
+    Name,
+    Value,
+    DivZero,
+    Cycle,
+}
+
+impl FormulaError {
+    fn code(self) -> &'static str {
+        match self {
+            FormulaError::Name => "#NAME?",
+            FormulaError::Value => "#VALUE!",
+            FormulaError::DivZero => "#DIV/0!",
+            FormulaError::Cycle => "#CYCLE!",
+        }
+    }
+}
+
+type Position = (usize, usize);
+
+/// A formula parsed into the parts evaluation needs
+enum Formula {
+    Number(f64),
+    Ref(Position),
+    Call { name: String, args: Vec<Arg> },
+}
+
+/// A function argument; a single reference is a one-cell range
+enum Arg {
+    Number(f64),
+    Range(Position, Position),
+}
+
+impl Formula {
+    fn parse(text: &str) -> Result<Self, FormulaError> {
+        let text = text.trim();
+        let expr = text.strip_prefix('=').unwrap_or(text).trim();
+
+        let Some(open) = expr.find('(') else {
+            if let Ok(n) = expr.parse::<f64>() {
+                return Ok(Formula::Number(n));
+            }
+            return parse_cell_ref(expr)
+                .map(Formula::Ref)
+                .ok_or(FormulaError::Value);
+        };
+
+        let name = expr[..open].trim().to_ascii_uppercase();
+        if !matches!(name.as_str(), "SUM" | "AVG" | "AVERAGE" | "COUNT") {
+            return Err(FormulaError::Name);
+        }
+        let args = expr[open + 1..]
+            .strip_suffix(')')
+            .ok_or(FormulaError::Value)?
+            .trim();
+        let args = if args.is_empty() {
+            Vec::new()
+        } else {
+            args.split(',')
+                .map(|arg| Arg::parse(arg.trim()))
+                .collect::<Result<_, _>>()?
+        };
+        Ok(Formula::Call { name, args })
+    }
+}
+
+impl Arg {
+    fn parse(arg: &str) -> Result<Self, FormulaError> {
+        if let Ok(n) = arg.parse::<f64>() {
+            return Ok(Arg::Number(n));
+        }
+        match arg.split_once(':') {
+            Some((a, b)) => Ok(Arg::Range(
+                parse_cell_ref(a.trim()).ok_or(FormulaError::Value)?,
+                parse_cell_ref(b.trim()).ok_or(FormulaError::Value)?,
+            )),
+            None => {
+                let pos = parse_cell_ref(arg).ok_or(FormulaError::Value)?;
+                Ok(Arg::Range(pos, pos))
+            }
+        }
+    }
+}
+
+/// Evaluates formulas, caching each cell so shared references are computed
+/// once. Dependencies are resolved with an explicit stack, so long reference
+/// chains do not recurse.
+struct Evaluator<'a> {
+    sheet: &'a SpreadsheetContent,
+    done: HashMap<Position, Result<CellValue, FormulaError>>,
+    /// Formulas whose dependencies are still being evaluated
+    active: HashSet<Position>,
+    /// Grid extent; ranges are clamped to it and formulas outside it ignored
+    height: usize,
+    width: usize,
+}
+
+impl<'a> Evaluator<'a> {
+    fn new(sheet: &'a SpreadsheetContent) -> Self {
+        let (height, width) = sheet.extent();
+        Self {
+            sheet,
+            done: HashMap::new(),
+            active: HashSet::new(),
+            height,
+            width,
+        }
+    }
+
+    fn formula_text(&self, pos: Position) -> Option<&'a str> {
+        if pos.0 >= self.height || pos.1 >= self.width {
+            return None;
+        }
+        self.sheet.formula_at(pos.0, pos.1)
+    }
+
+    /// Evaluate the cell at `pos` after every formula it depends on, in
+    /// depth-first post-order
+    fn evaluate(&mut self, pos: Position) -> Result<Option<CellValue>, FormulaError> {
+        let mut stack = vec![(pos, false)];
+        while let Some((pos, expanded)) = stack.pop() {
+            if self.done.contains_key(&pos) {
+                continue;
+            }
+            let Some(text) = self.formula_text(pos) else {
+                continue;
+            };
+            let formula = Formula::parse(text);
+            if expanded {
+                self.active.remove(&pos);
+                let result = formula.and_then(|formula| self.apply(&formula));
+                self.done.insert(pos, result);
+            } else if self.active.insert(pos) {
+                stack.push((pos, true));
+                if let Ok(formula) = formula {
+                    stack.extend(self.dependencies(&formula).map(|dep| (dep, false)));
+                }
+            }
+            // An active formula reached again is part of a cycle; it stays
+            // unevaluated and `cell` reports the cycle to its dependents
+        }
+        self.cell(pos)
+    }
+
+    /// Formula cells that `formula` reads
+    fn dependencies<'f>(&'f self, formula: &'f Formula) -> impl Iterator<Item = Position> + 'f {
+        let ranges: Vec<(Position, Position)> = match formula {
+            Formula::Number(_) => Vec::new(),
+            Formula::Ref(pos) => vec![(*pos, *pos)],
+            Formula::Call { args, .. } => args
+                .iter()
+                .filter_map(|arg| match arg {
+                    Arg::Range(start, end) => Some((*start, *end)),
+                    Arg::Number(_) => None,
+                })
+                .collect(),
+        };
+        ranges
+            .into_iter()
+            .flat_map(move |(start, end)| self.range(start, end))
+            .filter(move |&pos| self.formula_text(pos).is_some())
+    }
+
+    /// Positions in a range, clamped to the grid
+    fn range(&self, start: Position, end: Position) -> impl Iterator<Item = Position> {
+        let rows = start.0.min(end.0)..=start.0.max(end.0).min(self.height.saturating_sub(1));
+        let cols = start.1.min(end.1)..=start.1.max(end.1).min(self.width.saturating_sub(1));
+        rows.flat_map(move |r| cols.clone().map(move |c| (r, c)))
+    }
+
+    /// Value of a cell, `None` when it is empty or outside the grid. Formula
+    /// cells must have been evaluated already; one that was not is in a cycle.
+    fn cell(&self, pos: Position) -> Result<Option<CellValue>, FormulaError> {
+        if self.formula_text(pos).is_none() {
+            return Ok(self
+                .sheet
+                .rows
+                .get(pos.0)
+                .and_then(|r| r.get(pos.1))
+                .cloned());
+        }
+        match self.done.get(&pos) {
+            Some(done) => done.clone().map(Some),
+            None => Err(FormulaError::Cycle),
+        }
+    }
+
+    fn apply(&self, formula: &Formula) -> Result<CellValue, FormulaError> {
+        let (name, args) = match formula {
+            Formula::Number(n) => return Ok(CellValue::Number(*n)),
+            Formula::Ref(pos) => return Ok(self.cell(*pos)?.unwrap_or(CellValue::Number(0.0))),
+            Formula::Call { name, args } => (name, args),
+        };
+
+        let mut numbers = Vec::new();
+        for arg in args {
+            match arg {
+                Arg::Number(n) => numbers.push(*n),
+                Arg::Range(start, end) => {
+                    for pos in self.range(*start, *end) {
+                        if let Some(CellValue::Number(n)) = self.cell(pos)? {
+                            numbers.push(n);
+                        }
+                    }
+                }
+            }
+        }
+
+        let sum: f64 = numbers.iter().sum();
+        match name.as_str() {
+            "SUM" => Ok(CellValue::Number(sum)),
+            "COUNT" => Ok(CellValue::Number(numbers.len() as f64)),
+            _ if numbers.is_empty() => Err(FormulaError::DivZero),
+            _ => Ok(CellValue::Number(sum / numbers.len() as f64)),
+        }
+    }
+}
+
+/// Zero-based (row, column) of an A1 reference; `$` markers are ignored
+fn parse_cell_ref(text: &str) -> Option<Position> {
+    let text: String = text.chars().filter(|&c| c != '$').collect();
+    let split = text.find(|c: char| !c.is_ascii_alphabetic())?;
+    let (letters, digits) = text.split_at(split);
+    if letters.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let col = letters.bytes().try_fold(0usize, |acc, b| {
+        acc.checked_mul(26)?
+            .checked_add((b.to_ascii_uppercase() - b'A') as usize + 1)
+    })?;
+    let row: usize = digits.parse().ok()?;
+    Some((row.checked_sub(1)?, col - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sales() -> SpreadsheetContent {
+        SpreadsheetContent::new(vec!["item".into(), "q1".into(), "q2".into()]).with_rows(vec![
+            vec![
+                CellValue::Text("apples".into()),
+                CellValue::Number(3.0),
+                CellValue::Number(5.0),
+            ],
+            vec![
+                CellValue::Text("pears".into()),
+                CellValue::Number(1.0),
+                CellValue::Text("n/a".into()),
+            ],
+            vec![
+                CellValue::Text("total".into()),
+                CellValue::Formula("=SUM(B1:B2)".into()),
+                CellValue::Formula("=sum(C1:C2)".into()),
+            ],
+        ])
+    }
+
+    #[test]
+    fn test_evaluate_functions() {
+        let mut sheet =
+            sales()
+                .with_formula(3, 1, "=AVG(B1:B3)")
+                .with_formula(3, 2, "COUNT(B1:C2, 7)");
+        sheet.rows.push(vec![
+            CellValue::Text("stats".into()),
+            CellValue::Number(0.0),
+            CellValue::Number(0.0),
+        ]);
+
+        let values = sheet.evaluate();
+        assert_eq!(values[2][1], CellValue::Number(4.0));
+        assert_eq!(values[2][2], CellValue::Number(5.0));
+        // The total row's formula counts toward the average
+        assert_eq!(values[3][1], CellValue::Number(8.0 / 3.0));
+        assert_eq!(values[3][2], CellValue::Number(4.0));
+        assert_eq!(values[0][0], CellValue::Text("apples".into()));
+    }
+
+    #[test]
+    fn test_formula_outside_rows_extends_grid() {
+        let sheet = sales().with_formula(4, 1, "=SUM(B1:B2)");
+        assert_eq!(sheet.formula_at(4, 1), Some("=SUM(B1:B2)"));
+
+        let values = sheet.evaluate();
+        assert_eq!(values.len(), 5);
+        assert!(values[3].is_empty());
+        assert_eq!(
+            values[4],
+            vec![CellValue::Text(String::new()), CellValue::Number(4.0)]
+        );
+        assert_eq!(sheet.to_table().rows.len(), 6);
+    }
+
+    #[test]
+    fn test_far_away_formula_is_ignored() {
+        let sheet = sales()
+            .with_formula(5_000_000, 1, "=SUM(B1:B2)")
+            .with_formula(3, 0, "=SUM(B1:ZZZ1000000)");
+
+        let values = sheet.evaluate();
+        assert_eq!(values.len(), 4);
+        // 3 + 5 + 1 from the data plus the two totals in row 3
+        assert_eq!(values[3], vec![CellValue::Number(18.0)]);
+    }
+
+    #[test]
+    fn test_deep_reference_chain() {
+        let depth = 100_000;
+        let mut rows: Vec<_> = (2..=depth)
+            .map(|next| vec![CellValue::Formula(format!("=A{}", next))])
+            .collect();
+        rows.push(vec![CellValue::Number(7.0)]);
+        let sheet = SpreadsheetContent::new(vec!["a".into()]).with_rows(rows);
+
+        let values = sheet.evaluate();
+        assert_eq!(values.len(), depth);
+        assert!(values.iter().all(|row| row[0] == CellValue::Number(7.0)));
+    }
+
+    #[test]
+    fn test_formula_errors() {
+        let sheet = SpreadsheetContent::new(vec!["a".into()]).with_rows(vec![
+            vec![CellValue::Formula("=A2".into())],
+            vec![CellValue::Formula("=A1".into())],
+            vec![CellValue::Formula("=MEDIAN(A1:A2)".into())],
+            vec![CellValue::Formula("=AVG(B1:B9)".into())],
+            vec![CellValue::Formula("=SUM(A1:".into())],
+        ]);
+
+        let codes: Vec<_> = sheet
+            .evaluate()
+            .into_iter()
+            .map(|row| row[0].clone())
+            .collect();
+        assert_eq!(
+            codes,
+            ["#CYCLE!", "#CYCLE!", "#NAME?", "#DIV/0!", "#VALUE!"]
+                .map(|code| CellValue::Text(code.into()))
+        );
+    }
+
+    #[test]
+    fn test_cell_references() {
+        assert_eq!(parse_cell_ref("A1"), Some((0, 0)));
+        assert_eq!(parse_cell_ref("$AB$12"), Some((11, 27)));
+        assert_eq!(parse_cell_ref("A0"), None);
+        assert_eq!(parse_cell_ref("12"), None);
+    }
+
+    #[test]
+    fn test_json_shape() {
+        let sheet = sales().with_formula(0, 2, "=B1*2");
+        let json = serde_json::to_value(&sheet).unwrap();
+        assert_eq!(
+            json["rows"][2],
+            serde_json::json!(["total", {"formula": "=SUM(B1:B2)"}, {"formula": "=sum(C1:C2)"}])
+        );
+        assert_eq!(
+            json["formulas"],
+            serde_json::json!([{"row": 0, "col": 2, "formula": "=B1*2"}])
+        );
+        let back: SpreadsheetContent = serde_json::from_value(json).unwrap();
+        assert_eq!(back, sheet);
+
+        let content = crate::Content::Spreadsheet(sheet);
+        let json = serde_json::to_string(&content).unwrap();
+        assert_eq!(
+            serde_json::from_str::<crate::Content>(&json).unwrap(),
+            content
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Summary statistics over a document's blocks, structure and edges.
+
+use crate::document::Document;
+use crate::id::BlockId;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Counts and sizes describing a document
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct DocumentStats {
+    /// Blocks in the document, including the root and orphans
+    pub total_blocks: usize,
+    /// Blocks per content type tag (`text`, `code`, ...)
+    pub block_count_by_type: HashMap<String, usize>,
+    /// Block edges plus hyperedges
+    pub total_edges: usize,
+    pub edge_count_by_type: HashMap<String, usize>,
+    /// Deepest level reachable from the root, which is level 0
+    pub max_depth: usize,
+    pub average_children_per_block: f64,
+    pub total_content_bytes: usize,
+    /// Blocks not reachable from the root
+    pub orphan_blocks: usize,
+    /// Heading blocks (`heading1` to `heading6`)
+    pub section_count: usize,
+    /// Snapshots are not stored in the document, so this is 0 unless the
+    /// holder of the snapshots fills it in
+    pub snapshot_count: usize,
+}
+
+impl Document {
+    /// Compute [`DocumentStats`] in a single pass over the blocks
+    pub fn statistics(&self) -> DocumentStats {
+        let mut stats = DocumentStats {
+            total_blocks: self.blocks.len(),
+            ..DocumentStats::default()
+        };
+
+        let mut child_links = 0;
+        for block in self.blocks.values() {
+            *stats
+                .block_count_by_type
+                .entry(block.content.type_tag().to_string())
+                .or_default() += 1;
+            stats.total_content_bytes += block.content.size_bytes();
+            child_links += self.children(&block.id).len();
+            for edge in &block.edges {
+                *stats
+                    .edge_count_by_type
+                    .entry(edge.edge_type.as_str())
+                    .or_default() += 1;
+            }
+            stats.total_edges += block.edges.len();
+            let is_section = block.metadata.semantic_role.as_ref().is_some_and(|role| {
+                role.category
+                    .as_str()
+                    .strip_prefix("heading")
+                    .is_some_and(|level| level.parse::<usize>().is_ok())
+            });
+            if is_section {
+                stats.section_count += 1;
+            }
+        }
+        for hyperedge in &self.hyperedges {
+            *stats
+                .edge_count_by_type
+                .entry(hyperedge.edge_type.as_str())
+                .or_default() += 1;
+            stats.total_edges += 1;
+        }
+        if stats.total_blocks > 0 {
+            stats.average_children_per_block = child_links as f64 / stats.total_blocks as f64;
+        }
+
+        let mut reachable: HashSet<BlockId> = HashSet::new();
+        let mut stack = vec![(self.root, 0)];
+        while let Some((id, depth)) = stack.pop() {
+            if !self.blocks.contains_key(&id) || !reachable.insert(id) {
+                continue;
+            }
+            stats.max_depth = stats.max_depth.max(depth);
+            stack.extend(self.children(&id).iter().map(|child| (*child, depth + 1)));
+        }
+        stats.orphan_blocks = stats.total_blocks - reachable.len();
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Block, Content, EdgeType};
+
+    #[test]
+    fn test_statistics() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let heading = doc
+            .add_block(Block::new(Content::text("Intro"), Some("heading1")), &root)
+            .unwrap();
+        let para = doc
+            .add_block(
+                Block::new(Content::text("Hello"), Some("paragraph")),
+                &heading,
+            )
+            .unwrap();
+        let code = doc
+            .add_block(
+                Block::new(Content::code("rust", "fn x() {}"), None),
+                &heading,
+            )
+            .unwrap();
+        doc.add_edge(&para, EdgeType::References, code);
+        let orphan = Block::new(Content::text("lost"), None);
+        doc.blocks.insert(orphan.id, orphan);
+
+        let stats = doc.statistics();
+        assert_eq!(stats.total_blocks, 5);
+        assert_eq!(stats.block_count_by_type["text"], 4);
+        assert_eq!(stats.block_count_by_type["code"], 1);
+        assert_eq!(stats.total_edges, 1);
+        assert_eq!(stats.edge_count_by_type["references"], 1);
+        assert_eq!(stats.max_depth, 2);
+        assert_eq!(stats.average_children_per_block, 3.0 / 5.0);
+        assert_eq!(stats.total_content_bytes, 5 + 5 + 9 + 4);
+        assert_eq!(stats.orphan_blocks, 1);
+        assert_eq!(stats.section_count, 1);
+        assert_eq!(stats.snapshot_count, 0);
+    }
+}
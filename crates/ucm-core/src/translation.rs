@@ -0,0 +1,193 @@
+//! Diagnostics reported by format translators.
+//!
+//! Translators record what they could not carry over faithfully, so a lossy
+//! import is visible instead of content silently disappearing.
+
+use serde::{Deserialize, Serialize};
+
+use crate::BlockId;
+
+/// What kind of loss or repair a warning describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslationWarningCode {
+    /// Source content with no block representation was left out
+    DroppedElement,
+    /// Nested structure was collapsed, e.g. a table inside a table cell
+    FlattenedStructure,
+    /// Syntax the translator does not model was kept verbatim
+    PreservedRaw,
+    /// Document metadata could not be read
+    InvalidMetadata,
+    /// The input's encoding was repaired, e.g. a byte order mark removed
+    EncodingFixed,
+}
+
+impl TranslationWarningCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DroppedElement => "dropped_element",
+            Self::FlattenedStructure => "flattened_structure",
+            Self::PreservedRaw => "preserved_raw",
+            Self::InvalidMetadata => "invalid_metadata",
+            Self::EncodingFixed => "encoding_fixed",
+        }
+    }
+}
+
+impl std::fmt::Display for TranslationWarningCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Lines of the source a warning refers to (1-based, inclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceSpan {
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl SourceSpan {
+    pub fn line(line: usize) -> Self {
+        Self {
+            start_line: line,
+            end_line: line,
+        }
+    }
+
+    pub fn lines(start_line: usize, end_line: usize) -> Self {
+        Self {
+            start_line,
+            end_line,
+        }
+    }
+}
+
+impl std::fmt::Display for SourceSpan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.start_line == self.end_line {
+            write!(f, "line {}", self.start_line)
+        } else {
+            write!(f, "lines {}-{}", self.start_line, self.end_line)
+        }
+    }
+}
+
+/// A single non-fatal problem found while translating
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranslationWarning {
+    pub code: TranslationWarningCode,
+    pub message: String,
+    /// Where in the source, if the translator tracks positions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_span: Option<SourceSpan>,
+    /// Block that holds what was kept, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<BlockId>,
+}
+
+impl TranslationWarning {
+    pub fn new(code: TranslationWarningCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            source_span: None,
+            block_id: None,
+        }
+    }
+
+    pub fn with_span(mut self, span: SourceSpan) -> Self {
+        self.source_span = Some(span);
+        self
+    }
+
+    pub fn with_block(mut self, block_id: BlockId) -> Self {
+        self.block_id = Some(block_id);
+        self
+    }
+}
+
+impl std::fmt::Display for TranslationWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(span) = &self.source_span {
+            write!(f, "{}: ", span)?;
+        }
+        write!(f, "{} [{}]", self.message, self.code)
+    }
+}
+
+/// Everything a translator reported while producing a document
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranslationReport {
+    pub warnings: Vec<TranslationWarning>,
+}
+
+impl TranslationReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, warning: TranslationWarning) {
+        self.warnings.push(warning);
+    }
+
+    /// True if nothing was lost or repaired
+    pub fn is_clean(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Warnings with the given code
+    pub fn with_code(
+        &self,
+        code: TranslationWarningCode,
+    ) -> impl Iterator<Item = &TranslationWarning> {
+        self.warnings.iter().filter(move |w| w.code == code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warning_display() {
+        let warning = TranslationWarning::new(
+            TranslationWarningCode::PreservedRaw,
+            "definition list kept as raw markdown",
+        )
+        .with_span(SourceSpan::lines(3, 5));
+        assert_eq!(
+            warning.to_string(),
+            "lines 3-5: definition list kept as raw markdown [preserved_raw]"
+        );
+
+        let warning = TranslationWarning::new(TranslationWarningCode::DroppedElement, "x");
+        assert_eq!(warning.to_string(), "x [dropped_element]");
+    }
+
+    #[test]
+    fn test_report_filters_by_code() {
+        let mut report = TranslationReport::new();
+        assert!(report.is_clean());
+        report.push(TranslationWarning::new(
+            TranslationWarningCode::EncodingFixed,
+            "byte order mark removed",
+        ));
+        report.push(TranslationWarning::new(
+            TranslationWarningCode::DroppedElement,
+            "<video> dropped",
+        ));
+        assert!(!report.is_clean());
+        assert_eq!(
+            report
+                .with_code(TranslationWarningCode::DroppedElement)
+                .count(),
+            1
+        );
+        assert_eq!(
+            serde_json::to_value(&report.warnings[0]).unwrap(),
+            serde_json::json!({"code": "encoding_fixed", "message": "byte order mark removed"})
+        );
+    }
+}
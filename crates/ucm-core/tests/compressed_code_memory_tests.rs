@@ -0,0 +1,76 @@
+//! Heap footprint of compressed code blocks against plain ones.
+//!
+//! Kept in its own test binary because it installs a counting global
+//! allocator and must not run alongside other tests.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ucm_core::Content;
+
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocated() -> usize {
+    ALLOCATED.load(Ordering::SeqCst)
+}
+
+fn source(i: usize) -> String {
+    let mut source = String::with_capacity(2048);
+    let mut line = 0;
+    while source.len() < 2000 {
+        source.push_str(&format!(
+            "pub fn handler_{}_{}(request: &Request) -> Result<Response> {{ validate(request)?; Ok(Response::ok()) }}\n",
+            i, line
+        ));
+        line += 1;
+    }
+    source
+}
+
+#[test]
+fn test_compressed_code_footprint_of_10k_blocks() {
+    let sources: Vec<String> = (0..10_000).map(source).collect();
+
+    let before = allocated();
+    let plain: Vec<Content> = sources
+        .iter()
+        .map(|source| Content::code("rust", source.clone()))
+        .collect();
+    let uncompressed = allocated() - before;
+    drop(plain);
+
+    let before = allocated();
+    let packed: Vec<Content> = sources
+        .iter()
+        .map(|source| Content::compress_code("rust", source.clone()))
+        .collect();
+    let compressed = allocated() - before;
+
+    assert!(
+        compressed * 10 < uncompressed * 3,
+        "compressed {} bytes vs uncompressed {} bytes",
+        compressed,
+        uncompressed
+    );
+    assert_eq!(
+        packed[42].decompress().as_deref(),
+        Some(sources[42].as_str())
+    );
+}
@@ -0,0 +1,76 @@
+//! Heap footprint of a lazily loaded document against the in-memory one.
+//!
+//! Kept in its own test binary because it installs a counting global
+//! allocator and must not run alongside other tests.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ucm_core::{Block, Content, Document, LazyDocument};
+
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocated() -> usize {
+    ALLOCATED.load(Ordering::SeqCst)
+}
+
+#[test]
+fn test_lazy_document_footprint_of_100k_blocks() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("large.ucml");
+
+    let before = allocated();
+    let mut doc = Document::create();
+    let root = doc.root;
+    let mut section = root;
+    for i in 0..100_000usize {
+        let text = format!(
+            "Paragraph {} {}",
+            i,
+            "lorem ipsum dolor sit amet ".repeat(8)
+        );
+        let block = Block::new(Content::text(text), Some("paragraph"));
+        if i % 100 == 0 {
+            section = doc.add_block(block, &root).unwrap();
+        } else {
+            doc.add_block(block, &section).unwrap();
+        }
+    }
+    let full = allocated() - before;
+    LazyDocument::write(&doc, &path).unwrap();
+    drop(doc);
+
+    let before = allocated();
+    let mut lazy = LazyDocument::open(&path).unwrap();
+    let footprint = allocated().saturating_sub(before);
+
+    assert_eq!(lazy.block_count(), 100_001);
+    let first = lazy.children(&root)[0];
+    assert!(matches!(
+        lazy.get_block_content(&first).unwrap(),
+        Content::Text(_)
+    ));
+    assert!(
+        footprint * 5 < full,
+        "lazy {} bytes vs full {} bytes",
+        footprint,
+        full
+    );
+}
@@ -0,0 +1,198 @@
+//! Portable JSON round-trip tests.
+
+use proptest::prelude::*;
+use serde_json::{json, Value};
+use ucm_core::{
+    Block, BlockId, Content, Document, EdgeType, PortableDocument, PORTABLE_SCHEMA_VERSION,
+};
+
+/// One block to add: parent picked from the blocks added so far
+#[derive(Debug, Clone)]
+struct BlockSpec {
+    parent: usize,
+    kind: u8,
+    text: String,
+    tags: Vec<String>,
+    label: Option<String>,
+}
+
+fn block_spec() -> impl Strategy<Value = BlockSpec> {
+    (
+        any::<usize>(),
+        0u8..5,
+        "[a-zA-Z0-9 .,!?\\n]{0,40}",
+        prop::collection::vec("[a-z]{1,8}", 0..3),
+        prop::option::of("[a-z_]{1,12}"),
+    )
+        .prop_map(|(parent, kind, text, tags, label)| BlockSpec {
+            parent,
+            kind,
+            text,
+            tags,
+            label,
+        })
+}
+
+fn document() -> impl Strategy<Value = Document> {
+    (
+        prop::collection::vec(block_spec(), 0..30),
+        prop::collection::vec((any::<usize>(), any::<usize>()), 0..10),
+        prop::option::of("[A-Za-z ]{1,20}"),
+        prop::collection::vec("[A-Za-z]{1,10}", 0..3),
+        prop::collection::btree_map("[a-z]{1,6}", "[a-z0-9]{0,10}", 0..4),
+    )
+        .prop_map(|(specs, edges, title, authors, custom)| {
+            let mut doc = Document::create();
+            let mut ids = vec![doc.root];
+
+            for (i, spec) in specs.into_iter().enumerate() {
+                // Suffix with the index so content-derived ids never collide
+                let text = format!("{} #{}", spec.text, i);
+                let (content, role) = match spec.kind {
+                    0 => (Content::text(text), Some("paragraph")),
+                    1 => (Content::text(text), Some("heading2")),
+                    2 => (Content::code("rust", text), Some("code")),
+                    3 => (
+                        Content::table(vec![vec!["a".into(), text], vec!["1".into(), "2".into()]]),
+                        None,
+                    ),
+                    _ => (Content::json(json!({ "text": text, "n": i })), None),
+                };
+                let mut block = Block::new(content, role);
+                for tag in spec.tags {
+                    block = block.with_tag(tag);
+                }
+                if let Some(label) = spec.label {
+                    block = block.with_label(format!("{}_{}", label, i));
+                }
+                let parent = ids[spec.parent % ids.len()];
+                ids.push(doc.add_block(block, &parent).unwrap());
+            }
+
+            for (source, target) in edges {
+                let source = ids[source % ids.len()];
+                let target = ids[target % ids.len()];
+                doc.add_edge(&source, EdgeType::References, target);
+            }
+
+            doc.metadata.title = title;
+            doc.metadata.authors = authors;
+            doc.metadata.custom = custom.into_iter().map(|(k, v)| (k, v.into())).collect();
+            doc
+        })
+}
+
+fn assert_same(doc: &Document, restored: &Document) {
+    assert_eq!(restored.id, doc.id);
+    assert_eq!(restored.root, doc.root);
+    assert_eq!(restored.structure, doc.structure);
+    assert_eq!(restored.blocks, doc.blocks);
+    assert_eq!(restored.metadata, doc.metadata);
+    assert_eq!(restored.version.counter, doc.version.counter);
+}
+
+proptest! {
+    #[test]
+    fn prop_portable_json_roundtrip(doc in document()) {
+        let json = doc.to_portable_json().unwrap();
+        let restored = Document::from_portable_json(&json).unwrap();
+        assert_same(&doc, &restored);
+
+        // Re-encoding yields the same JSON
+        let reencoded: Value = serde_json::from_str(&restored.to_portable_json().unwrap()).unwrap();
+        prop_assert_eq!(reencoded, serde_json::from_str::<Value>(&json).unwrap());
+    }
+
+    #[test]
+    fn prop_portable_json_keeps_edges_indexed(doc in document()) {
+        let restored = Document::from_portable_json(&doc.to_portable_json_pretty().unwrap()).unwrap();
+        for id in doc.blocks.keys() {
+            let mut expected: Vec<String> = doc
+                .edge_index
+                .outgoing_from(id)
+                .iter()
+                .map(|(_, target)| target.to_string())
+                .collect();
+            let mut actual: Vec<String> = restored
+                .edge_index
+                .outgoing_from(id)
+                .iter()
+                .map(|(_, target)| target.to_string())
+                .collect();
+            expected.sort();
+            actual.sort();
+            prop_assert_eq!(actual, expected);
+        }
+    }
+}
+
+#[test]
+fn test_children_keep_document_order() {
+    let mut doc = Document::create();
+    let root = doc.root;
+    let ids: Vec<BlockId> = ["zeta", "alpha", "mid"]
+        .iter()
+        .map(|t| {
+            doc.add_block(Block::new(Content::text(*t), None), &root)
+                .unwrap()
+        })
+        .collect();
+
+    let restored = Document::from_portable_json(&doc.to_portable_json().unwrap()).unwrap();
+    assert_eq!(restored.children(&root), ids.as_slice());
+}
+
+#[test]
+fn test_schema_version_is_written() {
+    let json: Value =
+        serde_json::from_str(&Document::create().to_portable_json().unwrap()).unwrap();
+    assert_eq!(json["schema_version"], PORTABLE_SCHEMA_VERSION);
+}
+
+#[test]
+fn test_unknown_fields_ignored_and_missing_fields_defaulted() {
+    let mut doc = Document::create();
+    let root = doc.root;
+    let child = doc
+        .add_block(Block::new(Content::text("Hello"), Some("paragraph")), &root)
+        .unwrap();
+    doc.add_edge(&child, EdgeType::References, root);
+
+    let mut json: Value = serde_json::from_str(&doc.to_portable_json().unwrap()).unwrap();
+    // A newer writer adding fields
+    json["future_field"] = json!({"nested": true});
+    json["blocks"][child.to_string()]["future_block_field"] = json!(1);
+    // An older or minimal writer leaving optional fields out
+    let object = json.as_object_mut().unwrap();
+    object.remove("schema_version");
+    object.remove("version");
+    object.remove("metadata");
+    let block = json["blocks"][child.to_string()].as_object_mut().unwrap();
+    block.remove("version");
+    block["edges"][0]
+        .as_object_mut()
+        .unwrap()
+        .remove("created_at");
+    let metadata = block["metadata"].as_object_mut().unwrap();
+    metadata.remove("created_at");
+    metadata.remove("modified_at");
+
+    let restored = Document::from_portable_json(&json.to_string()).unwrap();
+    assert_eq!(restored.children(&root), &[child]);
+    let block = restored.get_block(&child).unwrap();
+    assert_eq!(block.content, Content::text("Hello"));
+    assert_eq!(block.version.counter, 1);
+    assert_eq!(block.edges[0].target, root);
+
+    let portable = PortableDocument::from_json(&json.to_string()).unwrap();
+    assert_eq!(portable.schema_version, PORTABLE_SCHEMA_VERSION);
+}
+
+#[test]
+fn test_invalid_json_is_a_serialization_error() {
+    let err = Document::from_portable_json("{\"id\": 1}").unwrap_err();
+    assert_eq!(
+        err.code(),
+        Some(ucm_core::ErrorCode::E901SerializationError)
+    );
+}
@@ -0,0 +1,703 @@
+//! CRDT wrapper around [`Document`].
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use ucm_core::id::compute_content_hash;
+use ucm_core::{Block, BlockId, Content, Document};
+
+use crate::error::{CrdtError, Result};
+use crate::op::{ActorId, Op, OpId, OpKind};
+
+/// Materialized state: the document plus add-wins tag bookkeeping
+#[derive(Debug, Clone)]
+struct Replica {
+    doc: Document,
+    /// Live add ops per (block, tag); the tag is present while non-empty
+    tags: HashMap<(BlockId, String), BTreeSet<OpId>>,
+}
+
+impl Replica {
+    fn from_base(base: &Document) -> Self {
+        let mut tags = HashMap::new();
+        for (id, block) in &base.blocks {
+            for tag in &block.metadata.tags {
+                tags.insert((*id, tag.clone()), BTreeSet::from([OpId::base()]));
+            }
+        }
+        Self {
+            doc: base.clone(),
+            tags,
+        }
+    }
+
+    /// Apply an op. Ops that no longer make sense (e.g. edits to a block
+    /// deleted concurrently) are skipped, so replay never fails.
+    fn apply(&mut self, op: &Op) {
+        let doc = &mut self.doc;
+        match &op.kind {
+            OpKind::InsertBlock {
+                parent,
+                after,
+                block,
+            } => {
+                if doc.blocks.contains_key(&block.id) || !doc.blocks.contains_key(parent) {
+                    return;
+                }
+                let index = position(doc, parent, after.as_ref());
+                if doc.add_block_at(Block::clone(block), parent, index).is_ok() {
+                    for tag in &block.metadata.tags {
+                        self.tags
+                            .entry((block.id, tag.clone()))
+                            .or_default()
+                            .insert(op.id.clone());
+                    }
+                }
+            }
+            OpKind::MoveBlock {
+                block_id,
+                parent,
+                after,
+            } => {
+                if block_id == &doc.root || after.as_ref() == Some(block_id) {
+                    return;
+                }
+                if !doc.blocks.contains_key(block_id)
+                    || !doc.blocks.contains_key(parent)
+                    || doc.is_ancestor(block_id, parent)
+                {
+                    return;
+                }
+                doc.remove_from_structure(block_id);
+                let index = position(doc, parent, after.as_ref());
+                let _ = doc.move_block_at(block_id, parent, index);
+            }
+            OpKind::DeleteBlock { block_id } => {
+                if block_id != &doc.root && doc.blocks.contains_key(block_id) {
+                    let _ = doc.delete_cascade(block_id);
+                }
+            }
+            OpKind::SetContent { block_id, content } => {
+                update_block(doc, block_id, |block| set_content(block, content.clone()));
+            }
+            OpKind::SetLabel { block_id, label } => {
+                update_block(doc, block_id, |block| block.metadata.label = label.clone());
+            }
+            OpKind::SetSummary { block_id, summary } => {
+                update_block(doc, block_id, |block| {
+                    block.metadata.summary = summary.clone()
+                });
+            }
+            OpKind::SetTitle { title } => {
+                doc.metadata.title = title.clone();
+                doc.metadata.touch();
+            }
+            OpKind::AddTag { block_id, tag } => {
+                if !doc.blocks.contains_key(block_id) {
+                    return;
+                }
+                self.tags
+                    .entry((*block_id, tag.clone()))
+                    .or_default()
+                    .insert(op.id.clone());
+                update_block(doc, block_id, |block| {
+                    if !block.has_tag(tag) {
+                        block.metadata.tags.push(tag.clone());
+                    }
+                });
+            }
+            OpKind::RemoveTag {
+                block_id,
+                tag,
+                observed,
+            } => {
+                let key = (*block_id, tag.clone());
+                let Some(live) = self.tags.get_mut(&key) else {
+                    return;
+                };
+                for id in observed {
+                    live.remove(id);
+                }
+                if live.is_empty() {
+                    self.tags.remove(&key);
+                    update_block(doc, block_id, |block| {
+                        block.metadata.tags.retain(|t| t != tag)
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Index at which to insert after `after` (front when `None`, end when the
+/// anchor is no longer a child of `parent`)
+fn position(doc: &Document, parent: &BlockId, after: Option<&BlockId>) -> usize {
+    let children = doc.children(parent);
+    match after {
+        None => 0,
+        Some(anchor) => children
+            .iter()
+            .position(|child| child == anchor)
+            .map(|pos| pos + 1)
+            .unwrap_or(children.len()),
+    }
+}
+
+/// Mutate a block in place, keeping document indices in sync
+fn update_block(doc: &mut Document, id: &BlockId, f: impl FnOnce(&mut Block)) {
+    let Some(block) = doc.blocks.get_mut(id) else {
+        return;
+    };
+    let before = block.clone();
+    f(block);
+    let after = block.clone();
+    doc.indices.remove_block(&before);
+    doc.indices.index_block(&after);
+    doc.metadata.touch();
+}
+
+/// Replace content without regenerating the block id
+fn set_content(block: &mut Block, content: Content) {
+    block.metadata.content_hash = compute_content_hash(&content);
+    block.content = content;
+    block.metadata.touch();
+    block.version.increment();
+}
+
+/// A [`Document`] that can be edited concurrently by several peers and merged
+/// without conflicts.
+///
+/// Every local edit is recorded as an [`Op`]. [`CrdtDocument::merge`] pulls in
+/// the ops of another peer forked from the same base and replays the combined
+/// log in op-id order, so all peers converge on the same state:
+///
+/// - scalar fields (content, label, summary, title, parent) are last-write-wins
+/// - tags are an add-wins set: a removal only cancels the adds it had seen
+/// - concurrent inserts are all kept, except that block ids are content-derived,
+///   so identical blocks inserted concurrently collapse into one
+/// - deletes remove the subtree; concurrent edits to it are dropped
+#[derive(Debug, Clone)]
+pub struct CrdtDocument {
+    actor: ActorId,
+    base: Document,
+    ops: Vec<Op>,
+    seen: HashSet<OpId>,
+    clock: u64,
+    replica: Replica,
+}
+
+impl CrdtDocument {
+    /// Start tracking edits to `doc` as `actor`
+    pub fn new(doc: Document, actor: impl Into<ActorId>) -> Self {
+        let replica = Replica::from_base(&doc);
+        Self {
+            actor: actor.into(),
+            base: doc,
+            ops: Vec::new(),
+            seen: HashSet::new(),
+            clock: 0,
+            replica,
+        }
+    }
+
+    /// Create a replica for another peer, sharing base and history
+    pub fn fork(&self, actor: impl Into<ActorId>) -> Self {
+        Self {
+            actor: actor.into(),
+            ..self.clone()
+        }
+    }
+
+    /// This peer's actor id
+    pub fn actor(&self) -> &ActorId {
+        &self.actor
+    }
+
+    /// All known ops, in replay order
+    pub fn ops(&self) -> &[Op] {
+        &self.ops
+    }
+
+    /// Current Lamport clock
+    pub fn clock(&self) -> u64 {
+        self.clock
+    }
+
+    /// Current merged state
+    pub fn document(&self) -> &Document {
+        &self.replica.doc
+    }
+
+    /// Snapshot of the merged state
+    pub fn to_document(&self) -> Document {
+        self.replica.doc.clone()
+    }
+
+    /// Append a block as the last child of `parent`
+    pub fn insert_block(&mut self, parent: &BlockId, block: Block) -> Result<BlockId> {
+        let after = self.document().children(parent).last().copied();
+        self.insert_block_after(parent, after.as_ref(), block)
+    }
+
+    /// Insert a block under `parent`, directly after `after` (or first)
+    pub fn insert_block_after(
+        &mut self,
+        parent: &BlockId,
+        after: Option<&BlockId>,
+        block: Block,
+    ) -> Result<BlockId> {
+        self.require(parent)?;
+        if let Some(anchor) = after {
+            self.require(anchor)?;
+        }
+        if self.document().blocks.contains_key(&block.id) {
+            return Err(CrdtError::BlockExists(block.id));
+        }
+        let id = block.id;
+        self.record(OpKind::InsertBlock {
+            parent: *parent,
+            after: after.copied(),
+            block: Box::new(block),
+        });
+        Ok(id)
+    }
+
+    /// Move a block to the end of `parent`'s children
+    pub fn move_block(&mut self, id: &BlockId, parent: &BlockId) -> Result<()> {
+        let after = self
+            .document()
+            .children(parent)
+            .iter()
+            .rfind(|child| *child != id)
+            .copied();
+        self.move_block_after(id, parent, after.as_ref())
+    }
+
+    /// Move a block under `parent`, directly after `after` (or first)
+    pub fn move_block_after(
+        &mut self,
+        id: &BlockId,
+        parent: &BlockId,
+        after: Option<&BlockId>,
+    ) -> Result<()> {
+        self.require_non_root(id)?;
+        self.require(parent)?;
+        if let Some(anchor) = after {
+            self.require(anchor)?;
+        }
+        if self.document().is_ancestor(id, parent) {
+            return Err(CrdtError::CycleDetected(*id));
+        }
+        self.record(OpKind::MoveBlock {
+            block_id: *id,
+            parent: *parent,
+            after: after.copied(),
+        });
+        Ok(())
+    }
+
+    /// Delete a block and its descendants
+    pub fn delete_block(&mut self, id: &BlockId) -> Result<()> {
+        self.require_non_root(id)?;
+        self.record(OpKind::DeleteBlock { block_id: *id });
+        Ok(())
+    }
+
+    /// Replace a block's content (the block keeps its id)
+    pub fn set_content(&mut self, id: &BlockId, content: Content) -> Result<()> {
+        self.require(id)?;
+        self.record(OpKind::SetContent {
+            block_id: *id,
+            content,
+        });
+        Ok(())
+    }
+
+    /// Set or clear a block's label
+    pub fn set_label(&mut self, id: &BlockId, label: Option<String>) -> Result<()> {
+        self.require(id)?;
+        self.record(OpKind::SetLabel {
+            block_id: *id,
+            label,
+        });
+        Ok(())
+    }
+
+    /// Set or clear a block's summary
+    pub fn set_summary(&mut self, id: &BlockId, summary: Option<String>) -> Result<()> {
+        self.require(id)?;
+        self.record(OpKind::SetSummary {
+            block_id: *id,
+            summary,
+        });
+        Ok(())
+    }
+
+    /// Set or clear the document title
+    pub fn set_title(&mut self, title: Option<String>) {
+        self.record(OpKind::SetTitle { title });
+    }
+
+    /// Add a tag to a block
+    pub fn add_tag(&mut self, id: &BlockId, tag: impl Into<String>) -> Result<()> {
+        self.require(id)?;
+        self.record(OpKind::AddTag {
+            block_id: *id,
+            tag: tag.into(),
+        });
+        Ok(())
+    }
+
+    /// Remove a tag from a block (concurrent adds from other peers survive)
+    pub fn remove_tag(&mut self, id: &BlockId, tag: impl Into<String>) -> Result<()> {
+        self.require(id)?;
+        let tag = tag.into();
+        let observed = self
+            .replica
+            .tags
+            .get(&(*id, tag.clone()))
+            .map(|live| live.iter().cloned().collect())
+            .unwrap_or_default();
+        self.record(OpKind::RemoveTag {
+            block_id: *id,
+            tag,
+            observed,
+        });
+        Ok(())
+    }
+
+    /// Merge another peer's ops into this document
+    ///
+    /// Returns the number of new ops applied. Merging is idempotent and
+    /// commutative: peers that have exchanged all ops hold identical state.
+    pub fn merge(&mut self, other: &CrdtDocument) -> Result<usize> {
+        if self.base.id != other.base.id {
+            return Err(CrdtError::DocumentMismatch {
+                this: self.base.id.to_string(),
+                other: other.base.id.to_string(),
+            });
+        }
+        Ok(self.apply_ops(other.ops.iter().cloned()))
+    }
+
+    /// Apply ops received from another peer, ignoring ones already known
+    ///
+    /// Returns the number of new ops applied.
+    pub fn apply_ops(&mut self, ops: impl IntoIterator<Item = Op>) -> usize {
+        let latest = self.ops.last().map(|op| op.id.clone());
+        let mut fresh: Vec<Op> = ops
+            .into_iter()
+            .filter(|op| self.seen.insert(op.id.clone()))
+            .collect();
+        if fresh.is_empty() {
+            return 0;
+        }
+        fresh.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let count = fresh.len();
+        let in_order = match latest {
+            Some(latest) => fresh[0].id > latest,
+            None => true,
+        };
+        for op in &fresh {
+            self.clock = self.clock.max(op.id.counter);
+        }
+        self.ops.extend(fresh);
+
+        if in_order {
+            // Everything new sorts after our log; apply incrementally
+            for op in &self.ops[self.ops.len() - count..] {
+                self.replica.apply(op);
+            }
+        } else {
+            self.ops.sort_by(|a, b| a.id.cmp(&b.id));
+            self.replica = Replica::from_base(&self.base);
+            for op in &self.ops {
+                self.replica.apply(op);
+            }
+        }
+        count
+    }
+
+    fn record(&mut self, kind: OpKind) {
+        self.clock += 1;
+        let op = Op {
+            id: OpId::new(self.clock, self.actor.clone()),
+            kind,
+        };
+        self.seen.insert(op.id.clone());
+        self.replica.apply(&op);
+        self.ops.push(op);
+    }
+
+    fn require(&self, id: &BlockId) -> Result<()> {
+        if self.document().blocks.contains_key(id) {
+            Ok(())
+        } else {
+            Err(CrdtError::BlockNotFound(*id))
+        }
+    }
+
+    fn require_non_root(&self, id: &BlockId) -> Result<()> {
+        if id == &self.document().root {
+            return Err(CrdtError::RootBlock);
+        }
+        self.require(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ucm_core::DocumentId;
+
+    fn text(block: &Block) -> &str {
+        match &block.content {
+            Content::Text(t) => &t.text,
+            _ => panic!("expected text content"),
+        }
+    }
+
+    /// Root -> intro, body
+    fn base() -> (Document, BlockId, BlockId) {
+        let mut doc = Document::new(DocumentId::new("shared"));
+        let root = doc.root;
+        let intro = doc
+            .add_block(
+                Block::new(Content::text("Intro"), Some("heading1")).with_tag("draft"),
+                &root,
+            )
+            .unwrap();
+        let body = doc
+            .add_block(Block::new(Content::text("Body"), Some("paragraph")), &root)
+            .unwrap();
+        (doc, intro, body)
+    }
+
+    fn converge(a: &mut CrdtDocument, b: &mut CrdtDocument) {
+        let b_snapshot = b.clone();
+        a.merge(&b_snapshot).unwrap();
+        b.merge(a).unwrap();
+    }
+
+    fn assert_same(a: &CrdtDocument, b: &CrdtDocument) {
+        let a = a.document();
+        let b = b.document();
+        assert_eq!(a.structure, b.structure);
+        assert_eq!(a.blocks.len(), b.blocks.len());
+        for (id, block) in &a.blocks {
+            let other = &b.blocks[id];
+            assert_eq!(block.content, other.content);
+            assert_eq!(block.metadata.label, other.metadata.label);
+            assert_eq!(block.metadata.tags, other.metadata.tags);
+        }
+        assert_eq!(a.metadata.title, b.metadata.title);
+    }
+
+    #[test]
+    fn test_local_edits() {
+        let (doc, intro, _) = base();
+        let mut crdt = CrdtDocument::new(doc, "alice");
+
+        crdt.set_content(&intro, Content::text("Introduction"))
+            .unwrap();
+        crdt.set_label(&intro, Some("intro".into())).unwrap();
+        crdt.add_tag(&intro, "reviewed").unwrap();
+
+        let doc = crdt.to_document();
+        let block = doc.get_block(&intro).unwrap();
+        assert_eq!(text(block), "Introduction");
+        assert_eq!(doc.indices.find_by_label("intro"), Some(intro));
+        assert!(doc.indices.find_by_tag("reviewed").contains(&intro));
+        assert_eq!(crdt.ops().len(), 3);
+        assert_eq!(crdt.clock(), 3);
+    }
+
+    #[test]
+    fn test_concurrent_scalar_edits_last_write_wins() {
+        let (doc, intro, _) = base();
+        let mut alice = CrdtDocument::new(doc, "alice");
+        let mut bob = alice.fork("bob");
+
+        alice
+            .set_content(&intro, Content::text("Alice's intro"))
+            .unwrap();
+        bob.set_content(&intro, Content::text("Bob's intro"))
+            .unwrap();
+        bob.set_title(Some("Bob's title".into()));
+
+        converge(&mut alice, &mut bob);
+        assert_same(&alice, &bob);
+
+        // Same counter: actor id breaks the tie ("bob" > "alice")
+        let block = alice.document().get_block(&intro).unwrap();
+        assert_eq!(text(block), "Bob's intro");
+        assert_eq!(
+            alice.document().metadata.title.as_deref(),
+            Some("Bob's title")
+        );
+
+        // A later write wins regardless of actor
+        alice
+            .set_content(&intro, Content::text("Alice again"))
+            .unwrap();
+        bob.merge(&alice).unwrap();
+        let block = bob.document().get_block(&intro).unwrap();
+        assert_eq!(text(block), "Alice again");
+    }
+
+    #[test]
+    fn test_concurrent_tags_union() {
+        let (doc, intro, _) = base();
+        let mut alice = CrdtDocument::new(doc, "alice");
+        let mut bob = alice.fork("bob");
+
+        alice.add_tag(&intro, "important").unwrap();
+        bob.add_tag(&intro, "reviewed").unwrap();
+        // Bob removes the base tag while Alice re-adds it concurrently
+        bob.remove_tag(&intro, "draft").unwrap();
+        alice.add_tag(&intro, "draft").unwrap();
+
+        converge(&mut alice, &mut bob);
+        assert_same(&alice, &bob);
+
+        let tags = &alice.document().get_block(&intro).unwrap().metadata.tags;
+        for tag in ["important", "reviewed", "draft"] {
+            assert!(tags.iter().any(|t| t == tag), "missing {}", tag);
+        }
+
+        // Once the remove has observed every add, the tag is gone
+        bob.remove_tag(&intro, "draft").unwrap();
+        alice.merge(&bob).unwrap();
+        let tags = &alice.document().get_block(&intro).unwrap().metadata.tags;
+        assert!(!tags.iter().any(|t| t == "draft"));
+    }
+
+    #[test]
+    fn test_concurrent_inserts_keep_both_in_same_order() {
+        let (doc, intro, body) = base();
+        let root = doc.root;
+        let mut alice = CrdtDocument::new(doc, "alice");
+        let mut bob = alice.fork("bob");
+
+        let a = alice
+            .insert_block_after(
+                &root,
+                Some(&intro),
+                Block::new(Content::text("From Alice"), None),
+            )
+            .unwrap();
+        let b = bob
+            .insert_block_after(
+                &root,
+                Some(&intro),
+                Block::new(Content::text("From Bob"), None),
+            )
+            .unwrap();
+
+        converge(&mut alice, &mut bob);
+        assert_same(&alice, &bob);
+
+        let children = alice.document().children(&root);
+        assert_eq!(children.len(), 4);
+        assert_eq!(children[0], intro);
+        assert!(children.contains(&a) && children.contains(&b));
+        assert_eq!(children[3], body);
+    }
+
+    #[test]
+    fn test_delete_wins_over_concurrent_edit() {
+        let (doc, _, body) = base();
+        let mut alice = CrdtDocument::new(doc, "alice");
+        let mut bob = alice.fork("bob");
+
+        let child = alice
+            .insert_block(&body, Block::new(Content::text("Child"), None))
+            .unwrap();
+        bob.merge(&alice).unwrap();
+
+        alice.delete_block(&body).unwrap();
+        bob.set_content(&child, Content::text("Edited child"))
+            .unwrap();
+        bob.add_tag(&body, "kept?").unwrap();
+
+        converge(&mut alice, &mut bob);
+        assert_same(&alice, &bob);
+        assert!(alice.document().get_block(&body).is_none());
+        assert!(alice.document().get_block(&child).is_none());
+    }
+
+    #[test]
+    fn test_concurrent_moves_do_not_create_cycles() {
+        let (doc, intro, body) = base();
+        let mut alice = CrdtDocument::new(doc, "alice");
+        let mut bob = alice.fork("bob");
+
+        alice.move_block(&intro, &body).unwrap();
+        bob.move_block(&body, &intro).unwrap();
+
+        converge(&mut alice, &mut bob);
+        assert_same(&alice, &bob);
+
+        // Alice's move (1@alice) applies first; Bob's would form a cycle
+        let doc = alice.document();
+        assert_eq!(doc.parent(&intro), Some(&body));
+        assert_eq!(doc.parent(&body), Some(&doc.root));
+    }
+
+    #[test]
+    fn test_merge_is_idempotent_and_commutative() {
+        let (doc, intro, body) = base();
+        let mut alice = CrdtDocument::new(doc, "alice");
+        let mut bob = alice.fork("bob");
+        let mut carol = alice.fork("carol");
+
+        alice.set_label(&intro, Some("a".into())).unwrap();
+        bob.set_label(&intro, Some("b".into())).unwrap();
+        carol.add_tag(&body, "c").unwrap();
+
+        let mut left = alice.clone();
+        left.merge(&bob).unwrap();
+        left.merge(&carol).unwrap();
+
+        let mut right = carol.clone();
+        right.merge(&bob).unwrap();
+        right.merge(&alice).unwrap();
+
+        assert_same(&left, &right);
+        assert_eq!(left.merge(&right).unwrap(), 0);
+        assert_eq!(left.ops().len(), 3);
+    }
+
+    #[test]
+    fn test_merge_rejects_unrelated_document() {
+        let (doc, _, _) = base();
+        let mut alice = CrdtDocument::new(doc, "alice");
+        let other = CrdtDocument::new(Document::new(DocumentId::new("other")), "bob");
+        assert!(matches!(
+            alice.merge(&other),
+            Err(CrdtError::DocumentMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_local_ops() {
+        let (doc, intro, _) = base();
+        let root = doc.root;
+        let mut crdt = CrdtDocument::new(doc, "alice");
+        let missing = BlockId::from_hex("aabbccdd11223344").unwrap();
+
+        assert!(matches!(
+            crdt.delete_block(&root),
+            Err(CrdtError::RootBlock)
+        ));
+        assert!(matches!(
+            crdt.set_label(&missing, None),
+            Err(CrdtError::BlockNotFound(_))
+        ));
+        assert!(matches!(
+            crdt.move_block(&root, &intro),
+            Err(CrdtError::RootBlock)
+        ));
+        assert!(crdt.ops().is_empty());
+    }
+}
@@ -0,0 +1,26 @@
+//! Error types for CRDT documents.
+
+use thiserror::Error;
+use ucm_core::BlockId;
+
+/// CRDT document error
+#[derive(Debug, Error)]
+pub enum CrdtError {
+    #[error("Block not found: {0}")]
+    BlockNotFound(BlockId),
+
+    #[error("Block already exists: {0}")]
+    BlockExists(BlockId),
+
+    #[error("Cycle detected moving block: {0}")]
+    CycleDetected(BlockId),
+
+    #[error("Cannot modify the root block")]
+    RootBlock,
+
+    #[error("Cannot merge document {other} into {this}")]
+    DocumentMismatch { this: String, other: String },
+}
+
+/// Result type for CRDT operations
+pub type Result<T> = std::result::Result<T, CrdtError>;
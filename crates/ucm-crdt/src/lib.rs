@@ -0,0 +1,38 @@
+//! # UCM CRDT
+//!
+//! Conflict-free concurrent editing for Unified Content Model documents.
+//!
+//! [`CrdtDocument`] wraps a [`Document`](ucm_core::Document) and records every
+//! edit as an [`Op`]. Peers forked from the same base exchange ops with
+//! [`CrdtDocument::merge`] and converge on the same state: last-write-wins for
+//! scalar fields, add-wins union for tags. Op ids use Automerge's
+//! `counter@actor` scheme and ordering.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use ucm_core::{Block, Content, Document};
+//! use ucm_crdt::CrdtDocument;
+//!
+//! let mut doc = Document::create();
+//! let root = doc.root;
+//! let intro = doc.add_block(Block::new(Content::text("Intro"), None), &root).unwrap();
+//!
+//! let mut alice = CrdtDocument::new(doc, "alice");
+//! let mut bob = alice.fork("bob");
+//!
+//! alice.set_content(&intro, Content::text("Hello")).unwrap();
+//! bob.add_tag(&intro, "reviewed").unwrap();
+//!
+//! alice.merge(&bob).unwrap();
+//! let merged = alice.to_document();
+//! assert!(merged.get_block(&intro).unwrap().has_tag("reviewed"));
+//! ```
+
+mod document;
+pub mod error;
+pub mod op;
+
+pub use document::CrdtDocument;
+pub use error::{CrdtError, Result};
+pub use op::{ActorId, Op, OpId, OpKind};
@@ -0,0 +1,200 @@
+//! Operation log entries.
+//!
+//! Operation ids follow Automerge's `counter@actor` scheme: a Lamport counter
+//! paired with the id of the peer that produced the operation. Ops are
+//! totally ordered by counter, then actor, which respects causality and
+//! gives every peer the same replay order.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use ucm_core::{Block, BlockId, Content};
+
+/// Identifier of a peer producing operations
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ActorId(pub String);
+
+impl ActorId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ActorId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<&str> for ActorId {
+    fn from(s: &str) -> Self {
+        Self::new(s)
+    }
+}
+
+impl From<String> for ActorId {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+/// Unique, totally ordered operation id (`counter@actor`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct OpId {
+    pub counter: u64,
+    pub actor: ActorId,
+}
+
+impl OpId {
+    pub fn new(counter: u64, actor: impl Into<ActorId>) -> Self {
+        Self {
+            counter,
+            actor: actor.into(),
+        }
+    }
+
+    /// Pseudo-op standing in for state inherited from the base document
+    pub(crate) fn base() -> Self {
+        Self::new(0, "")
+    }
+}
+
+impl Ord for OpId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.counter
+            .cmp(&other.counter)
+            .then_with(|| self.actor.cmp(&other.actor))
+    }
+}
+
+impl PartialOrd for OpId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for OpId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.counter, self.actor)
+    }
+}
+
+impl FromStr for OpId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (counter, actor) = s
+            .split_once('@')
+            .ok_or_else(|| format!("Invalid op id: {}", s))?;
+        let counter = counter
+            .parse()
+            .map_err(|_| format!("Invalid op counter: {}", counter))?;
+        Ok(Self::new(counter, actor))
+    }
+}
+
+/// A single change to a document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum OpKind {
+    /// Insert a block under `parent`, directly after `after` (or first)
+    InsertBlock {
+        parent: BlockId,
+        after: Option<BlockId>,
+        block: Box<Block>,
+    },
+    /// Move a block under `parent`, directly after `after` (or first)
+    MoveBlock {
+        block_id: BlockId,
+        parent: BlockId,
+        after: Option<BlockId>,
+    },
+    /// Delete a block and its descendants
+    DeleteBlock { block_id: BlockId },
+    /// Replace block content (last write wins)
+    SetContent { block_id: BlockId, content: Content },
+    /// Replace block label (last write wins)
+    SetLabel {
+        block_id: BlockId,
+        label: Option<String>,
+    },
+    /// Replace block summary (last write wins)
+    SetSummary {
+        block_id: BlockId,
+        summary: Option<String>,
+    },
+    /// Replace document title (last write wins)
+    SetTitle { title: Option<String> },
+    /// Add a tag (add-wins set)
+    AddTag { block_id: BlockId, tag: String },
+    /// Remove a tag, cancelling only the adds this peer had observed
+    RemoveTag {
+        block_id: BlockId,
+        tag: String,
+        observed: Vec<OpId>,
+    },
+}
+
+/// An operation with its id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Op {
+    pub id: OpId,
+    #[serde(flatten)]
+    pub kind: OpKind,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_op_id_ordering() {
+        let a = OpId::new(1, "bob");
+        let b = OpId::new(2, "alice");
+        let c = OpId::new(2, "bob");
+        assert!(a < b);
+        assert!(b < c);
+    }
+
+    #[test]
+    fn test_op_id_display_roundtrip() {
+        let id = OpId::new(42, "peer-1");
+        assert_eq!(id.to_string(), "42@peer-1");
+        assert_eq!("42@peer-1".parse::<OpId>().unwrap(), id);
+        assert!("42".parse::<OpId>().is_err());
+        assert!("x@peer".parse::<OpId>().is_err());
+    }
+
+    #[test]
+    fn test_op_serialization() {
+        let op = Op {
+            id: OpId::new(3, "alice"),
+            kind: OpKind::SetLabel {
+                block_id: BlockId::root(),
+                label: Some("intro".into()),
+            },
+        };
+        let json = serde_json::to_value(&op).unwrap();
+        assert_eq!(json["op"], "set_label");
+        let decoded: Op = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, op);
+
+        let op = Op {
+            id: OpId::new(4, "bob"),
+            kind: OpKind::InsertBlock {
+                parent: BlockId::root(),
+                after: None,
+                block: Box::new(Block::new(Content::text("Hello"), Some("paragraph"))),
+            },
+        };
+        let json = serde_json::to_string(&op).unwrap();
+        let decoded: Op = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, op);
+    }
+}
@@ -0,0 +1,223 @@
+//! Patching a document with a [`DocumentDiff`].
+
+use ucm_core::id::compute_content_hash;
+use ucm_core::Document;
+
+use crate::diff::{BlockOp, DocumentDiff, StructureOp};
+use crate::error::{DiffError, Result};
+
+/// Apply `diff` to `doc`, turning the diff's old document into its new one.
+///
+/// Removals run first, then additions, content changes, moves and reorders.
+/// `Modify` ops check the block still holds `old_content`. On error `doc` is
+/// left unchanged.
+pub fn diff_apply(doc: &mut Document, diff: &DocumentDiff) -> Result<()> {
+    let mut next = doc.clone();
+
+    for op in &diff.block_ops {
+        if let BlockOp::Remove(id) = op {
+            next.delete_block(id)
+                .map_err(|_| DiffError::BlockNotFound(*id))?;
+        }
+    }
+    for op in &diff.block_ops {
+        if let BlockOp::Add(block) = op {
+            if next.blocks.contains_key(&block.id) {
+                return Err(DiffError::BlockExists(block.id));
+            }
+            next.blocks.insert(block.id, block.clone());
+        }
+    }
+    for op in &diff.block_ops {
+        if let BlockOp::Modify {
+            id,
+            old_content,
+            new_content,
+        } = op
+        {
+            let block = next
+                .get_block_mut(id)
+                .ok_or(DiffError::BlockNotFound(*id))?;
+            if &block.content != old_content {
+                return Err(DiffError::ContentMismatch(*id));
+            }
+            block.metadata.content_hash = compute_content_hash(new_content);
+            block.content = new_content.clone();
+            block.metadata.touch();
+            block.version.increment();
+        }
+    }
+
+    // Detach every moved block before inserting any, then insert in
+    // ascending target index so earlier insertions don't shift later ones
+    let mut moves = Vec::new();
+    for op in &diff.structure_ops {
+        if let StructureOp::Move {
+            id,
+            to_parent,
+            to_index,
+            ..
+        } = op
+        {
+            for block in [id, to_parent] {
+                if !next.blocks.contains_key(block) {
+                    return Err(DiffError::BlockNotFound(*block));
+                }
+            }
+            next.remove_from_structure(id);
+            moves.push((*to_index, *id, *to_parent));
+        }
+    }
+    moves.sort_by_key(|(index, _, _)| *index);
+    for (index, id, parent) in moves {
+        let children = next.structure.entry(parent).or_default();
+        children.insert(index.min(children.len()), id);
+    }
+
+    for op in &diff.structure_ops {
+        if let StructureOp::Reorder {
+            parent, new_order, ..
+        } = op
+        {
+            for block in std::iter::once(parent).chain(new_order) {
+                if !next.blocks.contains_key(block) {
+                    return Err(DiffError::BlockNotFound(*block));
+                }
+            }
+            for child in new_order {
+                next.remove_from_structure(child);
+            }
+            next.structure.insert(*parent, new_order.clone());
+        }
+    }
+
+    next.rebuild_indices();
+    next.metadata.touch();
+    *doc = next;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff_documents;
+    use ucm_core::{Block, BlockId, Content};
+
+    fn text(doc: &mut Document, parent: &BlockId, value: &str) -> BlockId {
+        doc.add_block(Block::new(Content::text(value), None), parent)
+            .unwrap()
+    }
+
+    /// Root with sections A (a1, a2, a3) and B (b1)
+    fn base() -> (Document, [BlockId; 6]) {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let a = text(&mut doc, &root, "A");
+        let b = text(&mut doc, &root, "B");
+        let a1 = text(&mut doc, &a, "a1");
+        let a2 = text(&mut doc, &a, "a2");
+        let a3 = text(&mut doc, &a, "a3");
+        let b1 = text(&mut doc, &b, "b1");
+        (doc, [a, b, a1, a2, a3, b1])
+    }
+
+    fn assert_same_tree(left: &Document, right: &Document) {
+        let mut ids: Vec<_> = left.blocks.keys().collect();
+        ids.sort_by_key(|id| id.to_string());
+        let mut other: Vec<_> = right.blocks.keys().collect();
+        other.sort_by_key(|id| id.to_string());
+        assert_eq!(ids, other);
+        for id in ids {
+            assert_eq!(left.blocks[id].content, right.blocks[id].content);
+            assert_eq!(left.children(id), right.children(id), "children of {}", id);
+        }
+    }
+
+    #[test]
+    fn test_identical_documents_have_empty_diff() {
+        let (doc, _) = base();
+        assert!(diff_documents(&doc, &doc.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_round_trip_applies_every_kind_of_change() {
+        let (old, [a, b, a1, a2, a3, b1]) = base();
+        let mut new = old.clone();
+        new.get_block_mut(&a1).unwrap().content = Content::text("a1 edited");
+        new.delete_block(&b1).unwrap();
+        new.move_block_at(&a3, &a, 0).unwrap();
+        new.move_block(&a2, &b).unwrap();
+        let added = text(&mut new, &a, "a4");
+
+        let diff = diff_documents(&old, &new);
+        assert!(diff.block_ops.contains(&BlockOp::Remove(b1)));
+        assert!(diff.block_ops.iter().any(|op| matches!(
+            op,
+            BlockOp::Add(block) if block.id == added
+        )));
+        assert!(diff.structure_ops.contains(&StructureOp::Move {
+            id: a2,
+            from_parent: a,
+            to_parent: b,
+            from_index: 1,
+            to_index: 0,
+        }));
+        assert!(diff.structure_ops.contains(&StructureOp::Reorder {
+            parent: a,
+            old_order: vec![a1, a2, a3],
+            new_order: vec![a3, a1, added],
+        }));
+
+        let mut patched = old.clone();
+        diff_apply(&mut patched, &diff).unwrap();
+        assert_same_tree(&patched, &new);
+        assert!(patched.indices.find_by_type("text").contains(&added));
+    }
+
+    #[test]
+    fn test_moves_alone_need_no_reorder() {
+        let (old, [a, b, _, a2, _, _]) = base();
+        let mut new = old.clone();
+        new.move_block_at(&a2, &b, 0).unwrap();
+
+        let diff = diff_documents(&old, &new);
+        assert!(diff.block_ops.is_empty());
+        assert_eq!(diff.structure_ops.len(), 1);
+        assert!(matches!(
+            diff.structure_ops[0],
+            StructureOp::Move { id, from_parent, .. } if id == a2 && from_parent == a
+        ));
+
+        let mut patched = old.clone();
+        diff_apply(&mut patched, &diff).unwrap();
+        assert_same_tree(&patched, &new);
+    }
+
+    #[test]
+    fn test_stale_modify_leaves_document_unchanged() {
+        let (old, [_, _, a1, _, _, b1]) = base();
+        let mut new = old.clone();
+        new.delete_block(&b1).unwrap();
+        new.get_block_mut(&a1).unwrap().content = Content::text("edited");
+        let diff = diff_documents(&old, &new);
+
+        let mut stale = old.clone();
+        stale.get_block_mut(&a1).unwrap().content = Content::text("concurrent");
+        let err = diff_apply(&mut stale, &diff).unwrap_err();
+        assert!(matches!(err, DiffError::ContentMismatch(id) if id == a1));
+        assert!(stale.get_block(&b1).is_some());
+    }
+
+    #[test]
+    fn test_diff_serializes_to_json() {
+        let (old, [a, _, a1, _, _, _]) = base();
+        let mut new = old.clone();
+        new.move_block_at(&a1, &a, 2).unwrap();
+        let diff = diff_documents(&old, &new);
+
+        let json = serde_json::to_value(&diff).unwrap();
+        assert!(json["structure_ops"][0]["reorder"]["new_order"].is_array());
+        let back: DocumentDiff = serde_json::from_value(json).unwrap();
+        assert_eq!(back, diff);
+    }
+}
@@ -0,0 +1,178 @@
+//! Computing block and structure differences between documents.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use ucm_core::{Block, BlockId, Content, Document};
+
+use crate::myers::common_subsequence;
+
+/// Edit script turning one document into another, see [`diff_documents`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocumentDiff {
+    pub block_ops: Vec<BlockOp>,
+    pub structure_ops: Vec<StructureOp>,
+}
+
+/// Change to the set of blocks or their content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockOp {
+    /// Block only in the newer document; placed by its parent's
+    /// [`StructureOp::Reorder`]
+    Add(Block),
+    /// Block only in the older document
+    Remove(BlockId),
+    /// Block in both documents with different content
+    Modify {
+        id: BlockId,
+        old_content: Content,
+        new_content: Content,
+    },
+}
+
+/// Change to where blocks sit in the tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StructureOp {
+    /// Block now under a different parent
+    Move {
+        id: BlockId,
+        from_parent: BlockId,
+        to_parent: BlockId,
+        from_index: usize,
+        to_index: usize,
+    },
+    /// Children of `parent` that moves and removals alone do not explain
+    Reorder {
+        parent: BlockId,
+        old_order: Vec<BlockId>,
+        new_order: Vec<BlockId>,
+    },
+}
+
+impl DocumentDiff {
+    /// True if the documents have the same blocks, content and structure
+    pub fn is_empty(&self) -> bool {
+        self.block_ops.is_empty() && self.structure_ops.is_empty()
+    }
+}
+
+/// Diff `a` against `b`, matching blocks by id.
+///
+/// Content is compared per block; metadata and edges of surviving blocks are
+/// not diffed. For structure, each parent's old and new child lists are
+/// aligned with Myers' algorithm. Children outside the common subsequence
+/// that changed parent become [`StructureOp::Move`]s; if any other child is
+/// out of place (added, reordered among its siblings, or detached), the
+/// parent gets a [`StructureOp::Reorder`] with its full child lists.
+///
+/// Both documents are assumed to share a root. Ops are sorted by block id so
+/// output is stable across runs.
+pub fn diff_documents(a: &Document, b: &Document) -> DocumentDiff {
+    let mut diff = DocumentDiff::default();
+
+    let mut added: Vec<&Block> = b
+        .blocks
+        .values()
+        .filter(|block| !a.blocks.contains_key(&block.id))
+        .collect();
+    added.sort_by_key(|block| block.id.to_string());
+    let mut removed: Vec<BlockId> = a
+        .blocks
+        .keys()
+        .filter(|id| !b.blocks.contains_key(id))
+        .copied()
+        .collect();
+    removed.sort_by_key(|id| id.to_string());
+    let mut modified: Vec<(&Block, &Block)> = b
+        .blocks
+        .values()
+        .filter_map(|new| Some((a.get_block(&new.id)?, new)))
+        .filter(|(old, new)| old.content != new.content)
+        .collect();
+    modified.sort_by_key(|(_, new)| new.id.to_string());
+
+    diff.block_ops
+        .extend(removed.into_iter().map(BlockOp::Remove));
+    diff.block_ops
+        .extend(added.into_iter().map(|block| BlockOp::Add(block.clone())));
+    diff.block_ops
+        .extend(modified.into_iter().map(|(old, new)| BlockOp::Modify {
+            id: new.id,
+            old_content: old.content.clone(),
+            new_content: new.content.clone(),
+        }));
+
+    let old_parents = positions(a);
+    let new_parents = positions(b);
+    let mut moved: Vec<_> = new_parents
+        .iter()
+        .filter_map(|(id, &to)| Some((id, *old_parents.get(id)?, to)))
+        .filter(|(_, from, to)| from.0 != to.0)
+        .collect();
+    moved.sort_by_key(|(id, _, _)| id.to_string());
+    diff.structure_ops.extend(moved.into_iter().map(
+        |(id, (from_parent, from_index), (to_parent, to_index))| StructureOp::Move {
+            id: *id,
+            from_parent,
+            to_parent,
+            from_index,
+            to_index,
+        },
+    ));
+
+    // A child needs nothing beyond a Move or Remove when it crossed parents
+    // or left the document; anything else out of place forces a Reorder
+    let crossed = |id: &BlockId| match (old_parents.get(id), new_parents.get(id)) {
+        (Some((from, _)), Some((to, _))) => from != to,
+        _ => false,
+    };
+    let mut parents: Vec<&BlockId> = b.structure.keys().chain(a.structure.keys()).collect();
+    parents.sort_by_key(|id| id.to_string());
+    parents.dedup();
+    for parent in parents {
+        if !b.blocks.contains_key(parent) {
+            continue;
+        }
+        let (old_order, new_order) = (a.children(parent), b.children(parent));
+        if old_order == new_order {
+            continue;
+        }
+        let common = common_subsequence(old_order, new_order);
+        let kept_old: HashSet<usize> = common.iter().map(|(i, _)| *i).collect();
+        let kept_new: HashSet<usize> = common.iter().map(|(_, j)| *j).collect();
+        let explained_removal = |(i, id): (usize, &BlockId)| {
+            kept_old.contains(&i) || !b.blocks.contains_key(id) || crossed(id)
+        };
+        let explained_insertion = |(j, id): (usize, &BlockId)| kept_new.contains(&j) || crossed(id);
+        if old_order.iter().enumerate().all(explained_removal)
+            && new_order.iter().enumerate().all(explained_insertion)
+        {
+            continue;
+        }
+        diff.structure_ops.push(StructureOp::Reorder {
+            parent: *parent,
+            old_order: old_order.to_vec(),
+            new_order: new_order.to_vec(),
+        });
+    }
+
+    diff
+}
+
+/// Parent and index among its siblings
+type Position = (BlockId, usize);
+
+/// Position of every attached block
+fn positions(doc: &Document) -> HashMap<BlockId, Position> {
+    doc.structure
+        .iter()
+        .flat_map(|(parent, children)| {
+            children
+                .iter()
+                .enumerate()
+                .map(move |(index, id)| (*id, (*parent, index)))
+        })
+        .collect()
+}
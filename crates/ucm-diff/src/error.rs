@@ -0,0 +1,47 @@
+//! Error types for applying and merging document diffs.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ucm_core::{BlockId, Content};
+
+/// Diff application error
+#[derive(Debug, Error)]
+pub enum DiffError {
+    #[error("Block not found: {0}")]
+    BlockNotFound(BlockId),
+
+    #[error("Block already exists: {0}")]
+    BlockExists(BlockId),
+
+    #[error("Content of block {0} does not match the diff's old content")]
+    ContentMismatch(BlockId),
+}
+
+/// Blocks both sides of a three-way merge edited differently
+#[derive(Debug, Clone, PartialEq, Error, Serialize, Deserialize)]
+#[error("Merge conflict in {} block(s)", conflicts.len())]
+pub struct MergeConflict {
+    pub conflicts: Vec<BlockConflict>,
+}
+
+/// Three-way merge error
+#[derive(Debug, Error)]
+pub enum MergeError {
+    #[error(transparent)]
+    Conflict(#[from] MergeConflict),
+
+    #[error("Failed to apply merged changes: {0}")]
+    Apply(#[from] DiffError),
+}
+
+/// Content of one conflicting block on each side and in the base
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockConflict {
+    pub block_id: BlockId,
+    pub our_content: Content,
+    pub their_content: Content,
+    pub base_content: Content,
+}
+
+/// Result type for diff operations
+pub type Result<T> = std::result::Result<T, DiffError>;
@@ -0,0 +1,41 @@
+//! # UCM Diff
+//!
+//! Block-level diff and patch for Unified Content Model documents.
+//!
+//! [`diff_documents`] compares two documents, matching blocks by id, and
+//! returns a [`DocumentDiff`]: block ops (add, remove, modify content) and
+//! structure ops (moves between parents, sibling reorders). Sibling order is
+//! aligned per parent with Myers' O(ND) algorithm, so only parents whose
+//! children changed beyond moves and removals carry a reorder.
+//! [`diff_apply`] patches the older document into the newer one, and
+//! [`merge_documents`] combines two documents edited from a common base.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use ucm_core::{Block, Content, Document};
+//! use ucm_diff::{diff_apply, diff_documents};
+//!
+//! let mut old = Document::create();
+//! let root = old.root;
+//! let intro = old.add_block(Block::new(Content::text("Intro"), None), &root).unwrap();
+//!
+//! let mut new = old.clone();
+//! new.get_block_mut(&intro).unwrap().content = Content::text("Hello");
+//! new.add_block(Block::new(Content::text("Body"), None), &root).unwrap();
+//!
+//! let diff = diff_documents(&old, &new);
+//! diff_apply(&mut old, &diff).unwrap();
+//! assert_eq!(old.children(&root), new.children(&root));
+//! ```
+
+mod apply;
+mod diff;
+pub mod error;
+mod merge;
+mod myers;
+
+pub use apply::diff_apply;
+pub use diff::{diff_documents, BlockOp, DocumentDiff, StructureOp};
+pub use error::{BlockConflict, DiffError, MergeConflict, MergeError, Result};
+pub use merge::merge_documents;
@@ -0,0 +1,354 @@
+//! Three-way merge of concurrently edited documents.
+
+use std::collections::{HashMap, HashSet};
+
+use ucm_core::{BlockId, Document};
+
+use crate::apply::diff_apply;
+use crate::diff::{diff_documents, BlockOp, DocumentDiff, StructureOp};
+use crate::error::{BlockConflict, MergeConflict, MergeError};
+
+/// Merge `ours` and `theirs`, both edited from `base`.
+///
+/// Non-conflicting ops from `diff(base, ours)` and `diff(base, theirs)` are
+/// combined and applied to `base`:
+///
+/// - content edited on one side, or identically on both, is kept; different
+///   edits to the same block are returned as a [`MergeError::Conflict`]
+/// - a removal wins over a concurrent edit, and drops blocks the other side
+///   added or moved beneath the removed block
+/// - when both sides move a block or reorder the same siblings, ours wins;
+///   blocks only the other side added or moved in are kept after the sibling
+///   that precedes them on that side, following any of ours inserted there
+pub fn merge_documents(
+    base: &Document,
+    ours: &Document,
+    theirs: &Document,
+) -> Result<Document, MergeError> {
+    let ours_diff = diff_documents(base, ours);
+    let theirs_diff = diff_documents(base, theirs);
+
+    let mut conflicts = Vec::new();
+    let mut theirs_modified = HashMap::new();
+    for op in &theirs_diff.block_ops {
+        if let BlockOp::Modify {
+            id, new_content, ..
+        } = op
+        {
+            theirs_modified.insert(*id, new_content);
+        }
+    }
+    for op in &ours_diff.block_ops {
+        if let BlockOp::Modify {
+            id,
+            old_content,
+            new_content,
+        } = op
+        {
+            match theirs_modified.get(id) {
+                Some(theirs) if *theirs != new_content => conflicts.push(BlockConflict {
+                    block_id: *id,
+                    our_content: new_content.clone(),
+                    their_content: (*theirs).clone(),
+                    base_content: old_content.clone(),
+                }),
+                _ => {}
+            }
+        }
+    }
+    if !conflicts.is_empty() {
+        return Err(MergeConflict { conflicts }.into());
+    }
+
+    let mut removed = HashSet::new();
+    for op in ours_diff.block_ops.iter().chain(&theirs_diff.block_ops) {
+        if let BlockOp::Remove(id) = op {
+            removed.insert(*id);
+        }
+    }
+
+    let parents = MergedParents {
+        base: parent_map(base),
+        ours: parent_map(ours),
+        theirs: parent_map(theirs),
+    };
+    let candidates: HashSet<BlockId> = base
+        .blocks
+        .keys()
+        .chain(ours.blocks.keys())
+        .chain(theirs.blocks.keys())
+        .filter(|id| !removed.contains(id))
+        .copied()
+        .collect();
+    let mut merged_parent: HashMap<BlockId, Option<BlockId>> = candidates
+        .iter()
+        .map(|id| (*id, parents.resolve(id)))
+        .collect();
+    parents.break_cycles(&mut merged_parent);
+
+    // Blocks under a removed block go with it
+    let dropped: HashSet<BlockId> = candidates
+        .iter()
+        .filter(|id| {
+            let mut current = **id;
+            while let Some(Some(parent)) = merged_parent.get(&current) {
+                if removed.contains(parent) {
+                    return true;
+                }
+                current = *parent;
+            }
+            false
+        })
+        .copied()
+        .collect();
+    let kept = |id: &BlockId| candidates.contains(id) && !dropped.contains(id);
+
+    let mut merged = DocumentDiff::default();
+    for id in removed.iter().chain(&dropped) {
+        if base.blocks.contains_key(id) {
+            merged.block_ops.push(BlockOp::Remove(*id));
+        }
+    }
+    let mut seen = HashSet::new();
+    for op in ours_diff.block_ops.iter().chain(&theirs_diff.block_ops) {
+        let id = match op {
+            BlockOp::Add(block) => &block.id,
+            BlockOp::Modify { id, .. } => id,
+            BlockOp::Remove(_) => continue,
+        };
+        if kept(id) && seen.insert(*id) {
+            merged.block_ops.push(op.clone());
+        }
+    }
+
+    let mut members: HashMap<BlockId, HashSet<BlockId>> = HashMap::new();
+    for (id, parent) in &merged_parent {
+        if let (true, Some(parent)) = (kept(id), parent) {
+            members.entry(*parent).or_default().insert(*id);
+        }
+    }
+    let mut parent_ids: HashSet<BlockId> = members.keys().copied().collect();
+    for doc in [base, ours, theirs] {
+        parent_ids.extend(doc.structure.keys().filter(|id| kept(id)));
+    }
+    let no_members = HashSet::new();
+    for parent in parent_ids {
+        let old_order = base.children(&parent);
+        let (primary, secondary) = if ours.children(&parent) != old_order {
+            (ours.children(&parent), theirs.children(&parent))
+        } else {
+            (theirs.children(&parent), ours.children(&parent))
+        };
+        let new_order = merge_order(
+            primary,
+            secondary,
+            members.get(&parent).unwrap_or(&no_members),
+        );
+        if new_order != old_order {
+            merged.structure_ops.push(StructureOp::Reorder {
+                parent,
+                old_order: old_order.to_vec(),
+                new_order,
+            });
+        }
+    }
+
+    let mut doc = base.clone();
+    diff_apply(&mut doc, &merged)?;
+    Ok(doc)
+}
+
+struct MergedParents {
+    base: HashMap<BlockId, BlockId>,
+    ours: HashMap<BlockId, BlockId>,
+    theirs: HashMap<BlockId, BlockId>,
+}
+
+impl MergedParents {
+    /// Theirs wins only where ours kept the base parent
+    fn resolve(&self, id: &BlockId) -> Option<BlockId> {
+        let (base, ours, theirs) = (self.base.get(id), self.ours.get(id), self.theirs.get(id));
+        match (ours, theirs) {
+            (Some(ours), _) if Some(ours) != base => Some(*ours),
+            (_, Some(theirs)) => Some(*theirs),
+            (ours, None) => ours.copied(),
+        }
+    }
+
+    /// Each side is a tree, but taking some parents from each can close a
+    /// loop (ours moves X under Y, theirs Y under X). Fall back to ours'
+    /// parent for every block on a loop until none remain.
+    fn break_cycles(&self, merged_parent: &mut HashMap<BlockId, Option<BlockId>>) {
+        let mut ids: Vec<BlockId> = merged_parent.keys().copied().collect();
+        ids.sort_by_key(|id| id.to_string());
+        for id in ids {
+            loop {
+                let mut path = vec![id];
+                let mut current = id;
+                let cycle_start = loop {
+                    match merged_parent.get(&current) {
+                        Some(Some(parent)) => current = *parent,
+                        _ => break None,
+                    }
+                    if let Some(index) = path.iter().position(|seen| *seen == current) {
+                        break Some(index);
+                    }
+                    path.push(current);
+                };
+                let Some(start) = cycle_start else {
+                    break;
+                };
+                for block in &path[start..] {
+                    merged_parent.insert(*block, self.ours.get(block).copied());
+                }
+            }
+        }
+    }
+}
+
+fn parent_map(doc: &Document) -> HashMap<BlockId, BlockId> {
+    doc.structure
+        .iter()
+        .flat_map(|(parent, children)| children.iter().map(move |id| (*id, *parent)))
+        .collect()
+}
+
+/// `primary`'s order, keeping only `members`, with members missing from it
+/// inserted after their nearest already-placed predecessor in `secondary`.
+/// Concurrent insertions at the same spot go primary first.
+fn merge_order(
+    primary: &[BlockId],
+    secondary: &[BlockId],
+    members: &HashSet<BlockId>,
+) -> Vec<BlockId> {
+    let mut order: Vec<BlockId> = primary
+        .iter()
+        .filter(|id| members.contains(id))
+        .copied()
+        .collect();
+    for (i, id) in secondary.iter().enumerate() {
+        if !members.contains(id) || order.contains(id) {
+            continue;
+        }
+        let mut at = secondary[..i]
+            .iter()
+            .rev()
+            .find_map(|prev| order.iter().position(|placed| placed == prev))
+            .map_or(0, |index| index + 1);
+        while at < order.len() && !secondary.contains(&order[at]) {
+            at += 1;
+        }
+        order.insert(at, *id);
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ucm_core::{Block, Content};
+
+    fn text(doc: &mut Document, parent: &BlockId, value: &str) -> BlockId {
+        doc.add_block(Block::new(Content::text(value), None), parent)
+            .unwrap()
+    }
+
+    fn set(doc: &mut Document, id: &BlockId, value: &str) {
+        doc.get_block_mut(id).unwrap().content = Content::text(value);
+    }
+
+    fn texts(doc: &Document, parent: &BlockId) -> Vec<String> {
+        doc.children(parent)
+            .iter()
+            .map(|id| match &doc.blocks[id].content {
+                Content::Text(t) => t.text.clone(),
+                other => panic!("unexpected content {:?}", other),
+            })
+            .collect()
+    }
+
+    /// Root with sections A (a1, a2) and B (b1)
+    fn base() -> (Document, [BlockId; 5]) {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let a = text(&mut doc, &root, "A");
+        let b = text(&mut doc, &root, "B");
+        let a1 = text(&mut doc, &a, "a1");
+        let a2 = text(&mut doc, &a, "a2");
+        let b1 = text(&mut doc, &b, "b1");
+        (doc, [a, b, a1, a2, b1])
+    }
+
+    #[test]
+    fn test_merge_combines_independent_edits() {
+        let (base, [a, b, a1, a2, b1]) = base();
+        let mut ours = base.clone();
+        set(&mut ours, &a1, "a1 ours");
+        text(&mut ours, &a, "a3 ours");
+        let mut theirs = base.clone();
+        set(&mut theirs, &b1, "b1 theirs");
+        text(&mut theirs, &a, "a3 theirs");
+        theirs.move_block_at(&a2, &b, 0).unwrap();
+
+        let merged = merge_documents(&base, &ours, &theirs).unwrap();
+        assert_eq!(texts(&merged, &a), ["a1 ours", "a3 ours", "a3 theirs"]);
+        assert_eq!(texts(&merged, &b), ["a2", "b1 theirs"]);
+        assert_eq!(merged.block_count(), base.block_count() + 2);
+        assert!(merged.indices.find_by_type("text").contains(&a2));
+    }
+
+    #[test]
+    fn test_same_block_edited_differently_conflicts() {
+        let (base, [_, _, a1, a2, _]) = base();
+        let mut ours = base.clone();
+        set(&mut ours, &a1, "ours");
+        set(&mut ours, &a2, "same");
+        let mut theirs = base.clone();
+        set(&mut theirs, &a1, "theirs");
+        set(&mut theirs, &a2, "same");
+
+        let MergeError::Conflict(err) = merge_documents(&base, &ours, &theirs).unwrap_err() else {
+            panic!("expected a merge conflict");
+        };
+        assert_eq!(err.conflicts.len(), 1);
+        let conflict = &err.conflicts[0];
+        assert_eq!(conflict.block_id, a1);
+        assert_eq!(conflict.our_content, Content::text("ours"));
+        assert_eq!(conflict.their_content, Content::text("theirs"));
+        assert_eq!(conflict.base_content, Content::text("a1"));
+    }
+
+    #[test]
+    fn test_removal_wins_and_takes_new_children() {
+        let (base, [a, b, a1, _, b1]) = base();
+        let mut ours = base.clone();
+        ours.delete_cascade(&b).unwrap();
+        let mut theirs = base.clone();
+        set(&mut theirs, &b1, "edited");
+        let added = text(&mut theirs, &b, "b2");
+        set(&mut theirs, &a1, "kept");
+
+        let merged = merge_documents(&base, &ours, &theirs).unwrap();
+        assert_eq!(merged.children(&merged.root), [a]);
+        assert!(merged.get_block(&b1).is_none());
+        assert!(merged.get_block(&added).is_none());
+        assert_eq!(texts(&merged, &a), ["kept", "a2"]);
+    }
+
+    #[test]
+    fn test_ours_wins_conflicting_moves() {
+        let (base, [a, b, a1, a2, b1]) = base();
+        let root = base.root;
+        let mut ours = base.clone();
+        ours.move_block(&a2, &root).unwrap();
+        ours.move_block(&a, &b).unwrap();
+        let mut theirs = base.clone();
+        theirs.move_block(&a2, &b).unwrap();
+        theirs.move_block(&b, &a).unwrap();
+
+        let merged = merge_documents(&base, &ours, &theirs).unwrap();
+        assert_eq!(merged.children(&root), [b, a2]);
+        assert_eq!(merged.children(&b), [b1, a]);
+        assert_eq!(merged.children(&a), [a1]);
+    }
+}
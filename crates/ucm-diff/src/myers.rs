@@ -0,0 +1,89 @@
+//! Myers' O((N+M)D) longest common subsequence.
+
+/// Index pairs `(i, j)` with `a[i] == b[j]` forming a longest common
+/// subsequence of `a` and `b`, in increasing order.
+///
+/// Greedy forward search over diagonals, keeping each round's frontier so
+/// the path can be walked back from `(N, M)`. See Myers, "An O(ND)
+/// Difference Algorithm and Its Variations" (1986).
+pub(crate) fn common_subsequence<T: PartialEq>(a: &[T], b: &[T]) -> Vec<(usize, usize)> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = (n + m) as usize;
+    if max == 0 {
+        return Vec::new();
+    }
+    let offset = max as isize;
+    let at = |k: isize| (k + offset) as usize;
+
+    // frontier[k] is the furthest x reached on diagonal k = x - y
+    let mut frontier = vec![0isize; 2 * max + 2];
+    let mut trace = Vec::new();
+    'search: for d in 0..=max as isize {
+        trace.push(frontier.clone());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && frontier[at(k - 1)] < frontier[at(k + 1)]) {
+                frontier[at(k + 1)]
+            } else {
+                frontier[at(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            frontier[at(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, frontier) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && frontier[at(k - 1)] < frontier[at(k + 1)]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = frontier[at(prev_k)];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            pairs.push((x as usize, y as usize));
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    pairs.reverse();
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lcs(a: &str, b: &str) -> String {
+        let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+        common_subsequence(&a, &b)
+            .into_iter()
+            .map(|(i, j)| {
+                assert_eq!(a[i], b[j]);
+                a[i]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_common_subsequence() {
+        // Example from the paper: D = 5, LCS length 4
+        assert_eq!(lcs("ABCABBA", "CBABAC").len(), 4);
+        assert_eq!(lcs("abc", "abc"), "abc");
+        assert_eq!(lcs("abc", "xyz"), "");
+        assert_eq!(lcs("", "abc"), "");
+        assert_eq!(lcs("abcd", "bcda"), "bcd");
+    }
+}
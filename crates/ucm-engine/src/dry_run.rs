@@ -0,0 +1,59 @@
+//! Recording of operations the engine would apply in dry-run mode.
+
+use serde::{Deserialize, Serialize};
+use ucm_core::BlockId;
+
+use crate::operation::Operation;
+
+/// One operation skipped by a dry run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DryRunEntry {
+    /// Command keyword, e.g. `EDIT` or `SNAPSHOT`
+    pub command_type: String,
+    /// Block the operation targets, if any
+    pub block_id: Option<BlockId>,
+    /// What the operation would have done
+    pub description: String,
+}
+
+impl DryRunEntry {
+    pub(crate) fn for_operation(op: &Operation, description: String) -> Self {
+        let (command_type, block_id) = match op {
+            Operation::Edit { block_id, .. } => ("EDIT", Some(*block_id)),
+            Operation::Move { block_id, .. } | Operation::MoveToTarget { block_id, .. } => {
+                ("MOVE", Some(*block_id))
+            }
+            Operation::Append { parent_id, .. } => ("APPEND", Some(*parent_id)),
+            Operation::Delete { block_id, .. } => ("DELETE", Some(*block_id)),
+            Operation::Prune { .. } => ("PRUNE", None),
+            Operation::Link { source, .. } => ("LINK", Some(*source)),
+            Operation::HyperLink { target, .. } => ("LINK", Some(*target)),
+            Operation::Unlink { source, .. } => ("UNLINK", Some(*source)),
+            Operation::CreateSnapshot { .. } | Operation::RestoreSnapshot { .. } => {
+                ("SNAPSHOT", None)
+            }
+            Operation::WriteSection { section_id, .. } => ("WRITE_SECTION", Some(*section_id)),
+        };
+        Self {
+            command_type: command_type.to_string(),
+            block_id,
+            description,
+        }
+    }
+}
+
+/// Operations recorded while the engine is in dry-run mode, in order
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DryRunLog {
+    pub entries: Vec<DryRunEntry>,
+}
+
+impl DryRunLog {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
@@ -1,11 +1,13 @@
 //! Main transformation engine.
 
+use crate::dry_run::{DryRunEntry, DryRunLog};
 use crate::operation::{EditOperator, MoveTarget, Operation, OperationResult, PruneCondition};
 use crate::snapshot::SnapshotManager;
 use crate::transaction::{TransactionId, TransactionManager};
 use crate::validate::{ValidationPipeline, ValidationResult};
+use std::sync::Mutex;
 use tracing::{debug, info, instrument, warn};
-use ucm_core::{Block, Content, Document, Edge, Error, Result};
+use ucm_core::{Block, Content, Document, Edge, Error, HyperEdge, InlineSpan, Result};
 
 /// Configuration for the engine
 #[derive(Debug, Clone)]
@@ -37,6 +39,8 @@ pub struct Engine {
     validator: ValidationPipeline,
     transactions: TransactionManager,
     snapshots: SnapshotManager,
+    dry_run: bool,
+    dry_run_log: Mutex<DryRunLog>,
 }
 
 impl Engine {
@@ -47,6 +51,8 @@ impl Engine {
             validator: ValidationPipeline::new(),
             transactions: TransactionManager::new(),
             snapshots: SnapshotManager::new(),
+            dry_run: false,
+            dry_run_log: Mutex::new(DryRunLog::default()),
         }
     }
 
@@ -57,6 +63,8 @@ impl Engine {
             validator: ValidationPipeline::new(),
             transactions: TransactionManager::new(),
             snapshots: SnapshotManager::new(),
+            dry_run: false,
+            dry_run_log: Mutex::new(DryRunLog::default()),
         }
     }
 
@@ -112,11 +120,215 @@ impl Engine {
         Ok(results)
     }
 
+    /// Record mutations in the dry-run log instead of applying them.
+    /// Enabling dry-run mode starts a new log.
+    pub fn set_dry_run(&mut self, enabled: bool) {
+        if enabled && !self.dry_run {
+            *self
+                .dry_run_log
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner()) = DryRunLog::default();
+        }
+        self.dry_run = enabled;
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Operations recorded since dry-run mode was last enabled
+    pub fn dry_run_log(&self) -> DryRunLog {
+        self.dry_run_log
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn record_dry_run(&self, op: &Operation, doc: &Document) {
+        let entry = DryRunEntry::for_operation(op, self.explain(op, doc));
+        debug!("Dry run: {}", entry.description);
+        self.dry_run_log
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries
+            .push(entry);
+    }
+
     /// Validate a document
     pub fn validate(&self, doc: &Document) -> ValidationResult {
         self.validator.validate_document(doc)
     }
 
+    /// Describe what `op` would do to `doc`, without executing it
+    pub fn explain(&self, op: &Operation, doc: &Document) -> String {
+        let block = |id: &ucm_core::BlockId| describe_block(doc, id);
+        match op {
+            Operation::Edit {
+                block_id,
+                path,
+                value,
+                operator,
+            } => match operator {
+                EditOperator::Set => {
+                    format!("Would set {} of {} to {}", path, block(block_id), value)
+                }
+                EditOperator::Append => {
+                    format!("Would append {} to {} of {}", value, path, block(block_id))
+                }
+                EditOperator::Remove => {
+                    format!(
+                        "Would remove {} from {} of {}",
+                        value,
+                        path,
+                        block(block_id)
+                    )
+                }
+                EditOperator::Increment => {
+                    format!("Would increment {} of {}", path, block(block_id))
+                }
+                EditOperator::Decrement => {
+                    format!("Would decrement {} of {}", path, block(block_id))
+                }
+            },
+            Operation::Move {
+                block_id,
+                new_parent,
+                index,
+            } => format!(
+                "Would move {} to {}{}",
+                block(block_id),
+                block(new_parent),
+                at_position(*index)
+            ),
+            Operation::MoveToTarget { block_id, target } => match target {
+                MoveTarget::ToParent { parent_id, index } => format!(
+                    "Would move {} to {}{}",
+                    block(block_id),
+                    block(parent_id),
+                    at_position(*index)
+                ),
+                MoveTarget::Before { sibling_id } => {
+                    format!(
+                        "Would move {} before {}",
+                        block(block_id),
+                        block(sibling_id)
+                    )
+                }
+                MoveTarget::After { sibling_id } => {
+                    format!("Would move {} after {}", block(block_id), block(sibling_id))
+                }
+            },
+            Operation::Append {
+                parent_id,
+                content,
+                label,
+                semantic_role,
+                index,
+                ..
+            } => {
+                let mut text = format!(
+                    "Would append a new {} block to {}{}",
+                    capitalize(content.type_tag()),
+                    block(parent_id),
+                    at_position(*index)
+                );
+                if let Some(label) = label {
+                    text.push_str(&format!(" with label '{}'", label));
+                }
+                if let Some(role) = semantic_role {
+                    text.push_str(&format!(" as {}", role));
+                }
+                text
+            }
+            Operation::Delete {
+                block_id,
+                cascade,
+                preserve_children,
+            } => {
+                let children = doc.children(block_id).len();
+                if *preserve_children && children > 0 {
+                    format!(
+                        "Would delete {}, moving its {} children to its parent",
+                        block(block_id),
+                        children
+                    )
+                } else if *cascade {
+                    format!(
+                        "Would delete {} and its {} descendants",
+                        block(block_id),
+                        doc.descendants(block_id).len()
+                    )
+                } else {
+                    format!("Would delete {}", block(block_id))
+                }
+            }
+            Operation::Prune { condition } => match condition {
+                None | Some(PruneCondition::Unreachable) => {
+                    let count = doc.blocks.keys().filter(|id| !doc.is_reachable(id)).count();
+                    format!("Would remove {} unreachable blocks", count)
+                }
+                Some(PruneCondition::TagContains(tag)) => {
+                    let count = doc.blocks.values().filter(|b| b.has_tag(tag)).count();
+                    format!("Would remove {} blocks tagged '{}'", count, tag)
+                }
+                Some(PruneCondition::Custom(condition)) => {
+                    format!("Would remove blocks matching {}", condition)
+                }
+            },
+            Operation::Link {
+                source,
+                edge_type,
+                target,
+                ..
+            } => format!(
+                "Would link {} to {} with a {} edge",
+                block(source),
+                block(target),
+                edge_type.as_str()
+            ),
+            Operation::HyperLink {
+                sources,
+                edge_type,
+                target,
+                ..
+            } => format!(
+                "Would link {} blocks to {} with a {} hyperedge",
+                sources.len(),
+                block(target),
+                edge_type.as_str()
+            ),
+            Operation::Unlink {
+                source,
+                edge_type,
+                target,
+            } => format!(
+                "Would remove the {} edge from {} to {}",
+                edge_type.as_str(),
+                block(source),
+                block(target)
+            ),
+            Operation::CreateSnapshot { name, .. } => {
+                if self.snapshots.exists(name) {
+                    format!("Would replace snapshot '{}'", name)
+                } else {
+                    format!("Would create snapshot '{}'", name)
+                }
+            }
+            Operation::RestoreSnapshot { name } => {
+                if self.snapshots.exists(name) {
+                    format!("Would restore snapshot '{}'", name)
+                } else {
+                    format!("Would fail to restore missing snapshot '{}'", name)
+                }
+            }
+            Operation::WriteSection { section_id, .. } => format!(
+                "Would replace the {} children of {} with blocks parsed from Markdown",
+                doc.children(section_id).len(),
+                block(section_id)
+            ),
+        }
+    }
+
     /// Begin a transaction
     pub fn begin_transaction(&mut self) -> TransactionId {
         self.transactions.begin()
@@ -154,6 +366,14 @@ impl Engine {
         doc: &Document,
         description: Option<String>,
     ) -> Result<()> {
+        if self.dry_run {
+            let op = Operation::CreateSnapshot {
+                name: name.into(),
+                description,
+            };
+            self.record_dry_run(&op, doc);
+            return Ok(());
+        }
         self.snapshots.create(name, doc, description)?;
         Ok(())
     }
@@ -179,6 +399,10 @@ impl Engine {
 
     // Internal operation execution
     fn execute_internal(&self, doc: &mut Document, op: Operation) -> Result<OperationResult> {
+        if self.dry_run {
+            self.record_dry_run(&op, doc);
+            return Ok(OperationResult::success(Vec::new()).with_warning("Dry run: not applied"));
+        }
         match op {
             Operation::Edit {
                 block_id,
@@ -219,7 +443,23 @@ impl Engine {
                 edge_type,
                 target,
                 metadata,
-            } => self.execute_link(doc, &source, edge_type, &target, metadata),
+                weight,
+                valid_from,
+                valid_to,
+            } => {
+                let edge = Edge {
+                    weight,
+                    ..Edge::new(edge_type, target).with_validity(valid_from, valid_to)
+                };
+                self.execute_link(doc, &source, edge, metadata)
+            }
+
+            Operation::HyperLink {
+                sources,
+                edge_type,
+                target,
+                metadata,
+            } => self.execute_hyperlink(doc, sources, edge_type, target, metadata),
 
             Operation::Unlink {
                 source,
@@ -281,6 +521,17 @@ impl Engine {
                 block.version.increment();
                 return Ok(OperationResult::success(vec![*block_id]));
             }
+            // Text written into rich text arrives unformatted
+            if let Content::RichText(ref mut rich) = block.content {
+                let text = value.as_str().unwrap_or_default();
+                match operator {
+                    EditOperator::Set => rich.spans = vec![InlineSpan::plain(text)],
+                    EditOperator::Append => rich.spans.push(InlineSpan::plain(text)),
+                    _ => {}
+                }
+                block.version.increment();
+                return Ok(OperationResult::success(vec![*block_id]));
+            }
         }
 
         // Handle metadata paths
@@ -327,8 +578,25 @@ impl Engine {
                     block.metadata.summary = value.as_str().map(String::from);
                 }
                 _ => {
-                    // Custom metadata
-                    block.metadata.custom.insert(meta_path.to_string(), value);
+                    // Custom metadata; dotted paths address nested objects
+                    let mut segments = meta_path.split('.');
+                    let key = segments.next().unwrap_or_default().to_string();
+                    let mut slot = block
+                        .metadata
+                        .custom
+                        .entry(key)
+                        .or_insert(serde_json::Value::Null);
+                    for segment in segments {
+                        if !slot.is_object() {
+                            *slot = serde_json::json!({});
+                        }
+                        slot = slot
+                            .as_object_mut()
+                            .expect("slot was just made an object")
+                            .entry(segment)
+                            .or_insert(serde_json::Value::Null);
+                    }
+                    *slot = value;
                 }
             }
             block.version.increment();
@@ -488,18 +756,16 @@ impl Engine {
         &self,
         doc: &mut Document,
         source: &ucm_core::BlockId,
-        edge_type: ucm_core::EdgeType,
-        target: &ucm_core::BlockId,
+        mut edge: Edge,
         metadata: Option<serde_json::Value>,
     ) -> Result<OperationResult> {
         if !doc.blocks.contains_key(source) {
             return Err(Error::BlockNotFound(source.to_string()));
         }
-        if !doc.blocks.contains_key(target) {
-            return Err(Error::BlockNotFound(target.to_string()));
+        if !doc.blocks.contains_key(&edge.target) {
+            return Err(Error::BlockNotFound(edge.target.to_string()));
         }
 
-        let mut edge = Edge::new(edge_type, *target);
         if let Some(meta) = metadata {
             if let Some(obj) = meta.as_object() {
                 for (k, v) in obj {
@@ -518,6 +784,26 @@ impl Engine {
         Ok(OperationResult::success(vec![*source]))
     }
 
+    fn execute_hyperlink(
+        &self,
+        doc: &mut Document,
+        sources: Vec<ucm_core::BlockId>,
+        edge_type: ucm_core::EdgeType,
+        target: ucm_core::BlockId,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<OperationResult> {
+        let mut hyperedge = HyperEdge::new(sources, edge_type, target);
+        if let Some(obj) = metadata.as_ref().and_then(|meta| meta.as_object()) {
+            for (k, v) in obj {
+                hyperedge.metadata.custom.insert(k.clone(), v.clone());
+            }
+        }
+
+        let affected = hyperedge.sources.clone();
+        doc.add_hyperedge(hyperedge)?;
+        Ok(OperationResult::success(affected))
+    }
+
     fn execute_unlink(
         &self,
         doc: &mut Document,
@@ -581,6 +867,31 @@ impl Engine {
     }
 }
 
+/// A block ID with its label, or its content type when unlabelled
+fn describe_block(doc: &Document, id: &ucm_core::BlockId) -> String {
+    match doc.get_block(id) {
+        Some(block) => match &block.metadata.label {
+            Some(label) => format!("{} '{}'", id, label),
+            None => format!("{} ({})", id, block.content.type_tag()),
+        },
+        None => format!("{} (not found)", id),
+    }
+}
+
+fn at_position(index: Option<usize>) -> String {
+    index
+        .map(|i| format!(" at position {}", i))
+        .unwrap_or_default()
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
 impl Default for Engine {
     fn default() -> Self {
         Self::new()
@@ -616,6 +927,104 @@ mod tests {
         assert_eq!(doc.block_count(), 2);
     }
 
+    #[test]
+    fn test_dry_run_records_without_mutating() {
+        let mut engine = Engine::new();
+        engine.set_dry_run(true);
+        let mut doc = Document::new(DocumentId::new("test"));
+        let root = doc.root;
+
+        let append = Operation::Append {
+            parent_id: root,
+            content: Content::text("Hello"),
+            label: None,
+            tags: Vec::new(),
+            semantic_role: None,
+            index: None,
+        };
+        let result = engine.execute(&mut doc, append.clone()).unwrap();
+        assert!(result.success && result.affected_blocks.is_empty());
+
+        let txn = engine.begin_transaction();
+        engine.add_to_transaction(&txn, append).unwrap();
+        engine.commit_transaction(&txn, &mut doc).unwrap();
+        engine.create_snapshot("v1", &doc, None).unwrap();
+
+        assert_eq!(doc.block_count(), 1);
+        assert!(engine.list_snapshots().is_empty());
+        let log = engine.dry_run_log();
+        let types: Vec<_> = log
+            .entries
+            .iter()
+            .map(|e| e.command_type.as_str())
+            .collect();
+        assert_eq!(types, vec!["APPEND", "APPEND", "SNAPSHOT"]);
+        assert_eq!(log.entries[0].block_id, Some(root));
+        assert_eq!(log.entries[2].description, "Would create snapshot 'v1'");
+
+        engine.set_dry_run(false);
+        engine.set_dry_run(true);
+        assert!(engine.dry_run_log().is_empty());
+    }
+
+    #[test]
+    fn test_explain_resolves_blocks_without_executing() {
+        let engine = Engine::new();
+        let mut doc = Document::new(DocumentId::new("test"));
+        let root = doc.root;
+        let section = doc
+            .add_block(
+                Block::new(Content::text("Intro"), None).with_label("Introduction"),
+                &root,
+            )
+            .unwrap();
+        let child = doc
+            .add_block(
+                Block::new(Content::code("rust", "fn main() {}"), None),
+                &section,
+            )
+            .unwrap();
+
+        let append = Operation::Append {
+            parent_id: section,
+            content: Content::text("Hello"),
+            label: Some("Greeting".into()),
+            tags: Vec::new(),
+            semantic_role: None,
+            index: Some(0),
+        };
+        assert_eq!(
+            engine.explain(&append, &doc),
+            format!(
+                "Would append a new Text block to {} 'Introduction' at position 0 with label 'Greeting'",
+                section
+            )
+        );
+        let delete = Operation::Delete {
+            block_id: section,
+            cascade: true,
+            preserve_children: false,
+        };
+        assert_eq!(
+            engine.explain(&delete, &doc),
+            format!(
+                "Would delete {} 'Introduction' and its 1 descendants",
+                section
+            )
+        );
+        let edit = Operation::Edit {
+            block_id: child,
+            path: "content.text".into(),
+            value: serde_json::json!("x"),
+            operator: EditOperator::Set,
+        };
+        assert_eq!(
+            engine.explain(&edit, &doc),
+            format!("Would set content.text of {} (code) to \"x\"", child)
+        );
+        assert_eq!(doc.block_count(), 3);
+    }
+
     #[test]
     fn test_engine_edit() {
         let engine = Engine::new();
@@ -647,6 +1056,62 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_engine_edit_nested_custom_metadata() {
+        let engine = Engine::new();
+        let mut doc = Document::new(DocumentId::new("test"));
+        let root = doc.root;
+        let id = doc
+            .add_block(Block::new(Content::text("Todo"), None), &root)
+            .unwrap();
+        doc.get_block_mut(&id)
+            .unwrap()
+            .metadata
+            .custom
+            .insert("task".into(), serde_json::json!({"checked": false}));
+
+        let result = engine
+            .execute(
+                &mut doc,
+                Operation::Edit {
+                    block_id: id,
+                    path: "metadata.task.checked".into(),
+                    value: serde_json::json!(true),
+                    operator: EditOperator::Set,
+                },
+            )
+            .unwrap();
+
+        assert!(result.success);
+        let custom = &doc.get_block(&id).unwrap().metadata.custom;
+        assert_eq!(custom["task"], serde_json::json!({"checked": true}));
+        assert!(!custom.contains_key("task.checked"));
+    }
+
+    #[test]
+    fn test_edit_task_checked_renders_checkbox() {
+        let engine = Engine::new();
+        let mut doc = ucp_translator_markdown::parse_markdown("- [ ] ship it\n").unwrap();
+        let list = doc.children(&doc.root)[0];
+        let item = doc.children(&list)[0];
+
+        let result = engine
+            .execute(
+                &mut doc,
+                Operation::Edit {
+                    block_id: item,
+                    path: "metadata.task.checked".into(),
+                    value: serde_json::json!(true),
+                    operator: EditOperator::Set,
+                },
+            )
+            .unwrap();
+
+        assert!(result.success);
+        let rendered = ucp_translator_markdown::render_markdown(&doc).unwrap();
+        assert_eq!(rendered, "- [x] ship it\n");
+    }
+
     #[test]
     fn test_engine_transaction() {
         let mut engine = Engine::new();
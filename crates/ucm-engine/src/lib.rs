@@ -9,6 +9,7 @@
 //! - Validation pipeline
 
 pub mod config;
+pub mod dry_run;
 pub mod engine;
 pub mod error;
 pub mod operation;
@@ -18,8 +19,9 @@ pub mod transaction;
 pub mod traversal;
 pub mod validate;
 
+pub use dry_run::{DryRunEntry, DryRunLog};
 pub use engine::Engine;
 pub use operation::{EditOperator, MoveTarget, Operation, OperationResult, PruneCondition};
-pub use snapshot::{Snapshot, SnapshotId, SnapshotManager};
+pub use snapshot::{BlockMove, DocumentDiff, Snapshot, SnapshotId, SnapshotManager};
 pub use transaction::{Transaction, TransactionId, TransactionManager, TransactionState};
 pub use validate::{ValidationPipeline, ValidationResult};
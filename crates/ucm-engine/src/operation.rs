@@ -1,5 +1,6 @@
 //! Operations that can be applied to documents.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use ucm_core::{BlockId, Content, EdgeType};
 
@@ -67,6 +68,20 @@ pub enum Operation {
         edge_type: EdgeType,
         target: BlockId,
         metadata: Option<serde_json::Value>,
+        #[serde(default)]
+        weight: Option<f64>,
+        #[serde(default)]
+        valid_from: Option<DateTime<Utc>>,
+        #[serde(default)]
+        valid_to: Option<DateTime<Utc>>,
+    },
+
+    /// Add a hyperedge from several sources to one target
+    HyperLink {
+        sources: Vec<BlockId>,
+        edge_type: EdgeType,
+        target: BlockId,
+        metadata: Option<serde_json::Value>,
     },
 
     /// Remove an edge
@@ -207,10 +222,33 @@ impl Operation {
                 source,
                 edge_type,
                 target,
+                weight,
                 ..
-            } => {
-                format!("LINK {} {} {}", source, edge_type.as_str(), target)
-            }
+            } => match weight {
+                Some(weight) => format!(
+                    "LINK {} {} {} WEIGHT {}",
+                    source,
+                    edge_type.as_str(),
+                    target,
+                    weight
+                ),
+                None => format!("LINK {} {} {}", source, edge_type.as_str(), target),
+            },
+            Operation::HyperLink {
+                sources,
+                edge_type,
+                target,
+                ..
+            } => format!(
+                "LINK [{}] {} {}",
+                sources
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                edge_type.as_str(),
+                target
+            ),
             Operation::Unlink {
                 source,
                 edge_type,
@@ -3,7 +3,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use ucm_core::{Document, DocumentVersion, Error, PortableDocument, Result};
+use ucm_core::{BlockId, Document, DocumentVersion, Error, PortableDocument, Result};
 
 /// Snapshot identifier
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -94,6 +94,68 @@ pub enum SnapshotChange {
     },
 }
 
+/// Block-level differences between two documents, each list sorted by block id
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentDiff {
+    /// Blocks only in the newer document
+    pub added: Vec<BlockId>,
+    /// Blocks only in the older document
+    pub removed: Vec<BlockId>,
+    /// Blocks whose content changed
+    pub modified: Vec<BlockId>,
+    /// Blocks that now have a different parent
+    pub moved: Vec<BlockMove>,
+}
+
+/// A block whose parent changed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockMove {
+    pub id: BlockId,
+    pub from: Option<BlockId>,
+    pub to: Option<BlockId>,
+}
+
+impl DocumentDiff {
+    /// Compare `before` with `after`
+    pub fn between(before: &Document, after: &Document) -> Self {
+        let mut diff = Self::default();
+        for (id, block) in &after.blocks {
+            let Some(old) = before.get_block(id) else {
+                diff.added.push(*id);
+                continue;
+            };
+            if old.content != block.content {
+                diff.modified.push(*id);
+            }
+            let (from, to) = (before.parent(id).copied(), after.parent(id).copied());
+            if from != to {
+                diff.moved.push(BlockMove { id: *id, from, to });
+            }
+        }
+        diff.removed = before
+            .blocks
+            .keys()
+            .filter(|id| !after.blocks.contains_key(id))
+            .copied()
+            .collect();
+
+        // Ids sort by their hex form so output is stable across runs
+        diff.added.sort_by_key(|id| id.to_string());
+        diff.removed.sort_by_key(|id| id.to_string());
+        diff.modified.sort_by_key(|id| id.to_string());
+        diff.moved.sort_by_key(|m| m.id.to_string());
+        diff
+    }
+
+    /// True if the documents have the same blocks, content and structure
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.moved.is_empty()
+    }
+}
+
 /// Manages document snapshots
 #[derive(Debug, Default)]
 pub struct SnapshotManager {
@@ -161,6 +223,14 @@ impl SnapshotManager {
         }
     }
 
+    /// Compare two snapshots
+    pub fn diff(&self, from: &str, to: &str) -> Result<DocumentDiff> {
+        Ok(DocumentDiff::between(
+            &self.restore(from)?,
+            &self.restore(to)?,
+        ))
+    }
+
     /// Get a snapshot by name
     pub fn get(&self, name: &str) -> Option<&Snapshot> {
         self.snapshots.get(&SnapshotId::new(name))
@@ -169,7 +239,7 @@ impl SnapshotManager {
     /// List all snapshots
     pub fn list(&self) -> Vec<&Snapshot> {
         let mut snapshots: Vec<_> = self.snapshots.values().collect();
-        snapshots.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        snapshots.sort_by_key(|s| std::cmp::Reverse(s.created_at));
         snapshots
     }
 
@@ -247,6 +317,46 @@ mod tests {
         assert!(!mgr.exists("v1"));
     }
 
+    #[test]
+    fn test_snapshot_diff() {
+        let mut mgr = SnapshotManager::new();
+        let mut doc = Document::create();
+        let root = doc.root;
+        let section = doc
+            .add_block(Block::new(Content::text("Section"), None), &root)
+            .unwrap();
+        let kept = doc
+            .add_block(Block::new(Content::text("Kept"), None), &root)
+            .unwrap();
+        let dropped = doc
+            .add_block(Block::new(Content::text("Dropped"), None), &root)
+            .unwrap();
+        mgr.create("v1", &doc, None).unwrap();
+
+        doc.delete_block(&dropped).unwrap();
+        doc.get_block_mut(&kept).unwrap().content = Content::text("Kept, edited");
+        doc.move_block(&kept, &section).unwrap();
+        let added = doc
+            .add_block(Block::new(Content::text("New"), None), &root)
+            .unwrap();
+        mgr.create("v2", &doc, None).unwrap();
+
+        let diff = mgr.diff("v1", "v2").unwrap();
+        assert_eq!(diff.added, vec![added]);
+        assert_eq!(diff.removed, vec![dropped]);
+        assert_eq!(diff.modified, vec![kept]);
+        assert_eq!(
+            diff.moved,
+            vec![BlockMove {
+                id: kept,
+                from: Some(root),
+                to: Some(section),
+            }]
+        );
+        assert!(mgr.diff("v1", "v1").unwrap().is_empty());
+        assert!(mgr.diff("v1", "missing").is_err());
+    }
+
     #[test]
     fn test_snapshot_eviction() {
         let mut mgr = SnapshotManager::with_max_snapshots(2);
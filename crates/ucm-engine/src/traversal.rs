@@ -5,7 +5,8 @@
 //! and semantic traversal.
 
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use ucm_core::{Block, BlockId, Content, Document, EdgeType};
 
 use crate::error::Result;
@@ -118,6 +119,33 @@ impl TraversalResult {
     }
 }
 
+/// Queue entry for [`TraversalEngine::find_path`], ordered so the
+/// cheapest candidate pops first from a max-heap
+struct PathCandidate {
+    cost: f64,
+    block: BlockId,
+}
+
+impl PartialEq for PathCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PathCandidate {}
+
+impl PartialOrd for PathCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PathCandidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
 /// Configuration for the traversal engine
 #[derive(Debug, Clone)]
 pub struct TraversalConfig {
@@ -126,6 +154,11 @@ pub struct TraversalConfig {
     pub default_preview_length: usize,
     pub include_orphans: bool,
     pub cache_enabled: bool,
+    /// Make [`TraversalEngine::find_path`] minimise total edge weight
+    /// instead of hop count
+    pub use_edge_weights: bool,
+    /// Report hyperedges alongside block edges in traversal results
+    pub include_hyperedges: bool,
 }
 
 impl Default for TraversalConfig {
@@ -136,6 +169,8 @@ impl Default for TraversalConfig {
             default_preview_length: 100,
             include_orphans: false,
             cache_enabled: true,
+            use_edge_weights: false,
+            include_hyperedges: false,
         }
     }
 }
@@ -262,6 +297,92 @@ impl TraversalEngine {
         Ok(paths)
     }
 
+    /// Find the cheapest path between two nodes, following children and
+    /// outgoing edges.
+    ///
+    /// Every step costs 1, so by default this is the path with the fewest
+    /// hops. With `use_edge_weights` an edge costs its weight instead (1 when
+    /// unset, negative weights count as 0). Returns `None` when `to` cannot
+    /// be reached from `from`.
+    pub fn find_path(
+        &self,
+        doc: &Document,
+        from: &BlockId,
+        to: &BlockId,
+    ) -> Result<Option<Vec<BlockId>>> {
+        let mut costs: HashMap<BlockId, f64> = HashMap::from([(*from, 0.0)]);
+        let mut previous: HashMap<BlockId, BlockId> = HashMap::new();
+        let mut queue = BinaryHeap::from([PathCandidate {
+            cost: 0.0,
+            block: *from,
+        }]);
+
+        while let Some(PathCandidate { cost, block }) = queue.pop() {
+            if block == *to {
+                let mut path = vec![block];
+                let mut current = block;
+                while let Some(prev) = previous.get(&current) {
+                    path.push(*prev);
+                    current = *prev;
+                }
+                path.reverse();
+                return Ok(Some(path));
+            }
+            // Stale entry; a cheaper route to this block was already expanded
+            if cost > costs[&block] {
+                continue;
+            }
+            for (next, step) in self.path_steps(doc, &block) {
+                let next_cost = cost + step;
+                if costs.get(&next).map_or(true, |known| next_cost < *known) {
+                    costs.insert(next, next_cost);
+                    previous.insert(next, block);
+                    queue.push(PathCandidate {
+                        cost: next_cost,
+                        block: next,
+                    });
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Edges leaving `block`, plus hyperedges it is a source of when
+    /// `include_hyperedges` is set
+    fn outgoing_edges<'a>(
+        &self,
+        doc: &'a Document,
+        block: &'a Block,
+    ) -> impl Iterator<Item = (&'a EdgeType, BlockId)> + 'a {
+        let include_hyperedges = self.config.include_hyperedges;
+        let hyperedges = doc
+            .hyperedges_from(&block.id)
+            .filter(move |_| include_hyperedges);
+        block
+            .edges
+            .iter()
+            .map(|edge| (&edge.edge_type, edge.target))
+            .chain(hyperedges.map(|h| (&h.edge_type, h.target)))
+    }
+
+    fn path_steps(&self, doc: &Document, block: &BlockId) -> Vec<(BlockId, f64)> {
+        let children = doc.children(block).iter().map(|child| (*child, 1.0));
+        let edges = doc
+            .get_block(block)
+            .into_iter()
+            .flat_map(|b| &b.edges)
+            .map(|edge| {
+                let cost = if self.config.use_edge_weights {
+                    edge.weight.unwrap_or(1.0).max(0.0)
+                } else {
+                    1.0
+                };
+                (edge.target, cost)
+            });
+        children.chain(edges).collect()
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn find_paths_recursive(
         &self,
@@ -395,11 +516,7 @@ impl TraversalEngine {
         let mut seen = HashSet::new();
         let mut nodes = Vec::new();
 
-        for node in up_result
-            .nodes
-            .into_iter()
-            .chain(down_result.nodes.into_iter())
-        {
+        for node in up_result.nodes.into_iter().chain(down_result.nodes) {
             if seen.insert(node.id) {
                 nodes.push(node);
             }
@@ -500,14 +617,12 @@ impl TraversalEngine {
                     nodes.push(node);
 
                     // Collect edges
-                    for edge in &block.edges {
-                        if filter.edge_types.is_empty()
-                            || filter.edge_types.contains(&edge.edge_type)
-                        {
+                    for (edge_type, target) in self.outgoing_edges(doc, block) {
+                        if filter.edge_types.is_empty() || filter.edge_types.contains(edge_type) {
                             edges.push(TraversalEdge {
                                 source: node_id,
-                                target: edge.target,
-                                edge_type: edge.edge_type.clone(),
+                                target,
+                                edge_type: edge_type.clone(),
                             });
                         }
                     }
@@ -641,12 +756,12 @@ impl TraversalEngine {
                 nodes.push(node);
 
                 // Collect edges
-                for edge in &block.edges {
-                    if filter.edge_types.is_empty() || filter.edge_types.contains(&edge.edge_type) {
+                for (edge_type, target) in self.outgoing_edges(doc, block) {
+                    if filter.edge_types.is_empty() || filter.edge_types.contains(edge_type) {
                         edges.push(TraversalEdge {
                             source: node_id,
-                            target: edge.target,
-                            edge_type: edge.edge_type.clone(),
+                            target,
+                            edge_type: edge_type.clone(),
                         });
                     }
                 }
@@ -783,10 +898,17 @@ impl TraversalEngine {
     fn extract_content_text(&self, content: &Content) -> String {
         match content {
             Content::Text(t) => t.text.clone(),
+            Content::RichText(r) => r.plain_text(),
             Content::Code(c) => c.source.clone(),
+            Content::CompressedCode { .. } => content.decompress().unwrap_or_default(),
             Content::Table(t) => format!("Table: {} rows", t.rows.len()),
+            Content::Spreadsheet(s) => format!("Spreadsheet: {} rows", s.rows.len()),
             Content::Math(m) => m.expression.clone(),
+            Content::Diagram(d) => d.source.clone(),
+            Content::Diff(d) => d.patch.clone(),
             Content::Media(m) => m.alt_text.clone().unwrap_or_else(|| "Media".to_string()),
+            Content::GeoJson(_) => "GeoJSON data".to_string(),
+            Content::Form(f) => format!("Form: {} ({} fields)", f.title, f.fields.len()),
             Content::Json { .. } => "JSON data".to_string(),
             Content::Binary { .. } => "Binary data".to_string(),
             Content::Composite { children, .. } => {
@@ -805,7 +927,7 @@ impl Default for TraversalEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ucm_core::DocumentId;
+    use ucm_core::{DocumentId, Edge, HyperEdge};
 
     fn create_test_document() -> Document {
         let mut doc = Document::new(DocumentId::new("test"));
@@ -930,6 +1052,70 @@ mod tests {
         assert!(!result.nodes.is_empty());
     }
 
+    #[test]
+    fn test_find_path_uses_edge_weights() {
+        let mut doc = Document::new(DocumentId::new("test"));
+        let root = doc.root;
+        let [a, b, c] = ["a", "b", "c"].map(|text| {
+            doc.add_block(Block::new(Content::text(text), None), &root)
+                .unwrap()
+        });
+        // a -> c directly is heavy; a -> b -> c is two light hops
+        for (source, edge) in [
+            (a, Edge::new(EdgeType::References, c).with_weight(5.0)),
+            (a, Edge::new(EdgeType::References, b).with_weight(1.0)),
+            (b, Edge::new(EdgeType::References, c).with_weight(1.5)),
+        ] {
+            doc.get_block_mut(&source).unwrap().add_edge(edge);
+        }
+
+        let hops = TraversalEngine::new();
+        assert_eq!(hops.find_path(&doc, &a, &c).unwrap(), Some(vec![a, c]));
+
+        let weighted = TraversalEngine::with_config(TraversalConfig {
+            use_edge_weights: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            weighted.find_path(&doc, &a, &c).unwrap(),
+            Some(vec![a, b, c])
+        );
+        assert_eq!(weighted.find_path(&doc, &c, &a).unwrap(), None);
+    }
+
+    #[test]
+    fn test_include_hyperedges() {
+        let mut doc = Document::new(DocumentId::new("test"));
+        let root = doc.root;
+        let [a, b, c] = ["a", "b", "c"].map(|text| {
+            doc.add_block(Block::new(Content::text(text), None), &root)
+                .unwrap()
+        });
+        doc.add_hyperedge(HyperEdge::new(vec![a, b], EdgeType::References, c))
+            .unwrap();
+
+        let hyperedge_targets = |include_hyperedges| {
+            let engine = TraversalEngine::with_config(TraversalConfig {
+                include_hyperedges,
+                ..Default::default()
+            });
+            let result = engine
+                .navigate(
+                    &doc,
+                    None,
+                    NavigateDirection::BreadthFirst,
+                    None,
+                    None,
+                    TraversalOutput::StructureOnly,
+                )
+                .unwrap();
+            result.edges.iter().filter(|edge| edge.target == c).count()
+        };
+
+        assert_eq!(hyperedge_targets(false), 0);
+        assert_eq!(hyperedge_targets(true), 2);
+    }
+
     #[test]
     fn test_max_depth_limit() {
         let doc = create_test_document();
@@ -1,53 +1,11 @@
 //! Validation pipeline for documents and operations.
 
+use ucm_core::metadata::RoleCategory;
 use ucm_core::{
-    Block, BlockId, Document, Error, ErrorCode, Result, ValidationIssue, ValidationSeverity,
+    Block, BlockId, Content, DiffFormat, Document, Error, ErrorCode, Result, ValidationIssue,
 };
 
-/// Validation result
-#[derive(Debug, Clone)]
-pub struct ValidationResult {
-    pub valid: bool,
-    pub issues: Vec<ValidationIssue>,
-}
-
-impl ValidationResult {
-    pub fn valid() -> Self {
-        Self {
-            valid: true,
-            issues: Vec::new(),
-        }
-    }
-
-    pub fn invalid(issues: Vec<ValidationIssue>) -> Self {
-        let has_errors = issues
-            .iter()
-            .any(|i| i.severity == ValidationSeverity::Error);
-        Self {
-            valid: !has_errors,
-            issues,
-        }
-    }
-
-    pub fn errors(&self) -> Vec<&ValidationIssue> {
-        self.issues
-            .iter()
-            .filter(|i| i.severity == ValidationSeverity::Error)
-            .collect()
-    }
-
-    pub fn warnings(&self) -> Vec<&ValidationIssue> {
-        self.issues
-            .iter()
-            .filter(|i| i.severity == ValidationSeverity::Warning)
-            .collect()
-    }
-
-    pub fn merge(&mut self, other: ValidationResult) {
-        self.issues.extend(other.issues);
-        self.valid = self.valid && other.valid;
-    }
-}
+pub use ucm_core::ValidationResult;
 
 /// Resource limits for validation
 #[derive(Debug, Clone)]
@@ -75,17 +33,48 @@ impl Default for ResourceLimits {
 #[derive(Debug, Clone)]
 pub struct ValidationPipeline {
     limits: ResourceLimits,
+    feature_collection_roles: Vec<RoleCategory>,
+    schema_rules: Vec<(RoleCategory, serde_json::Value)>,
+    #[cfg(feature = "tree-sitter")]
+    check_code_syntax: bool,
 }
 
 impl ValidationPipeline {
     pub fn new() -> Self {
+        Self::with_limits(ResourceLimits::default())
+    }
+
+    pub fn with_limits(limits: ResourceLimits) -> Self {
         Self {
-            limits: ResourceLimits::default(),
+            limits,
+            feature_collection_roles: Vec::new(),
+            schema_rules: Vec::new(),
+            #[cfg(feature = "tree-sitter")]
+            check_code_syntax: false,
         }
     }
 
-    pub fn with_limits(limits: ResourceLimits) -> Self {
-        Self { limits }
+    /// Require GeoJSON blocks with this semantic role to hold a `FeatureCollection`
+    pub fn with_feature_collection_role(mut self, role: RoleCategory) -> Self {
+        self.feature_collection_roles.push(role);
+        self
+    }
+
+    /// Check the content of blocks with this semantic role against a JSON
+    /// Schema. Content is checked in its serialized form, e.g.
+    /// `{"type": "text", "text": ..., "format": ...}`; see
+    /// [`ucm_core::json_schema`] for the supported keywords.
+    pub fn with_schema_rule(mut self, role: RoleCategory, schema: serde_json::Value) -> Self {
+        self.schema_rules.push((role, schema));
+        self
+    }
+
+    /// Also parse code blocks in their declared language and warn about
+    /// syntax errors. Languages without a parser are skipped.
+    #[cfg(feature = "tree-sitter")]
+    pub fn with_code_syntax_check(mut self, enabled: bool) -> Self {
+        self.check_code_syntax = enabled;
+        self
     }
 
     /// Validate a document
@@ -116,15 +105,59 @@ impl ValidationPipeline {
         // Check for orphans (warning)
         let orphans = doc.find_orphans();
         for orphan in orphans {
-            result.issues.push(ValidationIssue::warning(
-                ErrorCode::E203OrphanedBlock,
-                format!("Block {} is unreachable from root", orphan),
-            ));
+            result.issues.push(
+                ValidationIssue::warning(
+                    ErrorCode::E203OrphanedBlock,
+                    format!("Block {} is unreachable from root", orphan),
+                )
+                .with_block_id(orphan),
+            );
+        }
+
+        #[cfg(feature = "tree-sitter")]
+        if self.check_code_syntax {
+            result.merge(self.validate_code_syntax(doc));
         }
 
         result
     }
 
+    /// Warn about code blocks that fail to parse, with lines relative to the block
+    #[cfg(feature = "tree-sitter")]
+    fn validate_code_syntax(&self, doc: &Document) -> ValidationResult {
+        let mut issues = Vec::new();
+        for block in doc.blocks.values() {
+            let decompressed = block.content.decompressed_code();
+            let code = match (&block.content, &decompressed) {
+                (ucm_core::Content::Code(code), _) | (_, Some(code)) => code,
+                _ => continue,
+            };
+            let Some(errors) = ucp_codegraph::check_code_syntax(&code.language, &code.source)
+            else {
+                continue;
+            };
+            for error in errors {
+                issues.push(
+                    ValidationIssue::warning(
+                        ErrorCode::E103UnexpectedToken,
+                        format!(
+                            "Block {} ({}) line {}: {}",
+                            block.id,
+                            code.canonical_language(),
+                            error.line,
+                            error.message
+                        ),
+                    )
+                    .with_location(ucm_core::error::Location::new(error.line, error.column))
+                    .with_block_id(block.id),
+                );
+            }
+        }
+        // Block order is arbitrary; keep the report stable
+        issues.sort_by(|a, b| a.message.cmp(&b.message));
+        ValidationResult::invalid(issues)
+    }
+
     /// Validate document structure
     fn validate_structure(&self, doc: &Document) -> ValidationResult {
         let mut issues = Vec::new();
@@ -199,6 +232,51 @@ impl ValidationPipeline {
             ));
         }
 
+        if let Content::GeoJson(value) = &block.content {
+            let role = block.metadata.semantic_role.as_ref().map(|r| r.category);
+            let kind = value.get("type").and_then(|t| t.as_str()).unwrap_or("");
+            if role.is_some_and(|r| self.feature_collection_roles.contains(&r))
+                && kind != "FeatureCollection"
+            {
+                issues.push(ValidationIssue::error(
+                    ErrorCode::E200SchemaViolation,
+                    format!(
+                        "Block {} must hold a GeoJSON FeatureCollection, found {}",
+                        block.id, kind
+                    ),
+                ));
+            }
+        }
+
+        if let Content::Diff(diff) = &block.content {
+            if diff.format == DiffFormat::JsonPatch {
+                if let Err(e) = serde_json::from_str::<json_patch::Patch>(&diff.patch) {
+                    issues.push(ValidationIssue::error(
+                        ErrorCode::E200SchemaViolation,
+                        format!(
+                            "Block {} is not a valid RFC 6902 JSON Patch: {}",
+                            block.id, e
+                        ),
+                    ));
+                }
+            }
+        }
+
+        let role = block.metadata.semantic_role.as_ref().map(|r| r.category);
+        let schemas = self
+            .schema_rules
+            .iter()
+            .filter(|(rule_role, _)| Some(*rule_role) == role);
+        for (_, schema) in schemas {
+            let content = serde_json::to_value(&block.content).unwrap_or_default();
+            for violation in ucm_core::json_schema::schema_violations(schema, &content) {
+                issues.push(ValidationIssue::error(
+                    ErrorCode::E200SchemaViolation,
+                    format!("Block {} content {}", block.id, violation),
+                ));
+            }
+        }
+
         // Check edge targets exist
         for edge in &block.edges {
             if !doc.blocks.contains_key(&edge.target) {
@@ -212,6 +290,10 @@ impl ValidationPipeline {
             }
         }
 
+        let issues = issues
+            .into_iter()
+            .map(|issue| issue.with_block_id(block.id))
+            .collect();
         ValidationResult::invalid(issues)
     }
 
@@ -291,7 +373,6 @@ impl Default for ValidationPipeline {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ucm_core::Content;
 
     #[test]
     fn test_valid_document() {
@@ -336,4 +417,113 @@ mod tests {
         let result = validator.validate_document(&doc);
         assert!(!result.valid);
     }
+
+    #[test]
+    fn test_feature_collection_role() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let point = serde_json::json!({"type": "Point", "coordinates": [0.0, 0.0]});
+        doc.add_block(
+            Block::new(Content::new_geojson(point).unwrap(), Some("sidebar")),
+            &root,
+        )
+        .unwrap();
+
+        assert!(ValidationPipeline::new().validate_document(&doc).valid);
+        let result = ValidationPipeline::new()
+            .with_feature_collection_role(RoleCategory::Sidebar)
+            .validate_document(&doc);
+        assert!(!result.valid);
+        assert_eq!(result.errors()[0].code, ErrorCode::E200SchemaViolation);
+    }
+
+    #[test]
+    fn test_json_patch_diff() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        doc.add_block(
+            Block::new(
+                Content::diff(
+                    DiffFormat::JsonPatch,
+                    r#"[{"op": "replace", "path": "/title", "value": "New"}]"#,
+                ),
+                None,
+            ),
+            &root,
+        )
+        .unwrap();
+        // Unified diffs are not parsed
+        doc.add_block(
+            Block::new(Content::diff(DiffFormat::Unified, "not json"), None),
+            &root,
+        )
+        .unwrap();
+        assert!(ValidationPipeline::new().validate_document(&doc).valid);
+
+        let id = doc
+            .add_block(
+                Block::new(
+                    Content::diff(DiffFormat::JsonPatch, r#"[{"op": "rename", "path": "/a"}]"#),
+                    None,
+                ),
+                &root,
+            )
+            .unwrap();
+        let result = ValidationPipeline::new().validate_document(&doc);
+        assert!(!result.valid);
+        assert_eq!(result.errors()[0].code, ErrorCode::E200SchemaViolation);
+        assert_eq!(result.errors()[0].block_id, Some(id));
+    }
+
+    #[test]
+    fn test_schema_rule() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let id = doc
+            .add_block(Block::new(Content::text("Hi"), Some("note")), &root)
+            .unwrap();
+        doc.add_block(Block::new(Content::text("No"), None), &root)
+            .unwrap();
+
+        let schema = serde_json::json!({
+            "properties": {"text": {"type": "string", "minLength": 3}}
+        });
+        let result = ValidationPipeline::new()
+            .with_schema_rule(RoleCategory::Note, schema)
+            .validate_document(&doc);
+        assert!(!result.valid);
+        assert_eq!(result.errors().len(), 1);
+        assert_eq!(result.errors()[0].code, ErrorCode::E200SchemaViolation);
+        assert_eq!(result.errors()[0].block_id, Some(id));
+        assert!(result.errors()[0].message.contains("$.text"));
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn test_code_syntax_check() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        for (language, source) in [
+            ("rs", "fn main() {\n    let x = ;\n}"),
+            ("python", "print('ok')"),
+            ("haskell", "main = ("),
+        ] {
+            doc.add_block(Block::new(Content::code(language, source), None), &root)
+                .unwrap();
+        }
+
+        assert!(ValidationPipeline::new()
+            .validate_document(&doc)
+            .warnings()
+            .is_empty());
+
+        let result = ValidationPipeline::new()
+            .with_code_syntax_check(true)
+            .validate_document(&doc);
+        assert!(result.valid);
+        let warnings = result.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("(rust) line 2"));
+        assert_eq!(warnings[0].location.as_ref().unwrap().line, 2);
+    }
 }
@@ -0,0 +1,99 @@
+//! Compare protobuf and JSON serialization for a large document.
+//!
+//! ```text
+//! cargo run --release -p ucm-proto --example proto_vs_json -- [blocks]
+//! ```
+
+use std::env;
+use std::time::{Duration, Instant};
+
+use ucm_core::{Block, Content, Document, PortableDocument};
+use ucm_proto::ProtoCodec;
+
+const ITERATIONS: u32 = 5;
+
+fn main() {
+    let blocks = env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(10_000);
+    let doc = build_document(blocks);
+
+    let (json_encode, json) = timed(|| serde_json::to_vec(&doc.to_portable()).unwrap());
+    let (json_decode, _) = timed(|| {
+        let portable: PortableDocument = serde_json::from_slice(&json).unwrap();
+        portable.to_document().unwrap()
+    });
+
+    let (proto_encode, proto) = timed(|| doc.to_proto().unwrap());
+    let (proto_decode, _) = timed(|| Document::from_proto(&proto).unwrap());
+
+    println!("document: {} blocks", doc.block_count());
+    println!(
+        "{:<8} {:>12} {:>14} {:>14}",
+        "format", "bytes", "encode (ms)", "decode (ms)"
+    );
+    report("json", json.len(), json_encode, json_decode);
+    report("proto", proto.len(), proto_encode, proto_decode);
+    println!(
+        "proto size: {:.1}% of json",
+        proto.len() as f64 / json.len() as f64 * 100.0
+    );
+}
+
+fn build_document(blocks: usize) -> Document {
+    let mut doc = Document::create();
+    let root = doc.root;
+    let mut section = root;
+
+    for i in 0..blocks {
+        let block = if i % 50 == 0 {
+            Block::new(
+                Content::text(format!("Section {}", i / 50)),
+                Some("heading2"),
+            )
+        } else if i % 7 == 0 {
+            Block::new(
+                Content::code("rust", format!("fn item_{}() -> usize {{ {} }}", i, i)),
+                Some("code"),
+            )
+        } else {
+            Block::new(
+                Content::text(format!(
+                    "Paragraph {} with some representative prose for the benchmark.",
+                    i
+                )),
+                Some("paragraph"),
+            )
+            .with_tag("generated")
+        };
+
+        let is_heading = i % 50 == 0;
+        let parent = if is_heading { root } else { section };
+        let id = doc.add_block(block, &parent).unwrap();
+        if is_heading {
+            section = id;
+        }
+    }
+
+    doc
+}
+
+fn timed<T>(mut f: impl FnMut() -> T) -> (Duration, T) {
+    let start = Instant::now();
+    let mut result = f();
+    for _ in 1..ITERATIONS {
+        result = f();
+    }
+    (start.elapsed() / ITERATIONS, result)
+}
+
+fn report(format: &str, bytes: usize, encode: Duration, decode: Duration) {
+    println!(
+        "{:<8} {:>12} {:>14.2} {:>14.2}",
+        format,
+        bytes,
+        encode.as_secs_f64() * 1000.0,
+        decode.as_secs_f64() * 1000.0
+    );
+}
@@ -0,0 +1,784 @@
+//! Conversion between UCM core types and protobuf messages.
+
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use prost::Message;
+use ucm_core::document::DocumentIndices;
+use ucm_core::metadata::RoleCategory;
+use ucm_core::{
+    BinaryEncoding, Block, BlockId, BlockMetadata, Code, CompositeLayout, Content, ContentCache,
+    ContentHash, DiagramContent, DiagramDialect, DiffContent, DiffFormat, Document, DocumentConfig,
+    DocumentId, DocumentMetadata, DocumentVersion, Edge, EdgeId, EdgeIndex, EdgeMetadata, EdgeType,
+    HyperEdge, JsonSchema, LineRange, Math, MathFormat, SemanticRole, Text, TextFormat,
+    TokenEstimate, Version,
+};
+
+use crate::error::{ProtoError, Result};
+use crate::messages as pb;
+
+/// Protobuf encoding for UCM types.
+pub trait ProtoCodec: Sized {
+    /// Encode to protobuf bytes
+    fn to_proto(&self) -> Result<Vec<u8>>;
+
+    /// Decode from protobuf bytes
+    fn from_proto(bytes: &[u8]) -> Result<Self>;
+}
+
+impl ProtoCodec for Document {
+    fn to_proto(&self) -> Result<Vec<u8>> {
+        Ok(document_to_message(self)?.encode_to_vec())
+    }
+
+    fn from_proto(bytes: &[u8]) -> Result<Self> {
+        document_from_message(pb::Document::decode(bytes)?)
+    }
+}
+
+impl ProtoCodec for Block {
+    fn to_proto(&self) -> Result<Vec<u8>> {
+        Ok(block_to_message(self)?.encode_to_vec())
+    }
+
+    fn from_proto(bytes: &[u8]) -> Result<Self> {
+        block_from_message(pb::Block::decode(bytes)?)
+    }
+}
+
+// Document
+
+fn document_to_message(doc: &Document) -> Result<pb::Document> {
+    let mut structure: Vec<pb::Structure> = doc
+        .structure
+        .iter()
+        .map(|(parent, children)| pb::Structure {
+            parent: parent.as_bytes().to_vec(),
+            children: children.iter().map(|c| c.as_bytes().to_vec()).collect(),
+        })
+        .collect();
+    structure.sort_by(|a, b| a.parent.cmp(&b.parent));
+
+    let mut blocks: Vec<&Block> = doc.blocks.values().collect();
+    blocks.sort_by_key(|b| b.id.0);
+
+    Ok(pb::Document {
+        id: doc.id.0.clone(),
+        root: doc.root.as_bytes().to_vec(),
+        structure,
+        blocks: blocks
+            .into_iter()
+            .map(block_to_message)
+            .collect::<Result<Vec<_>>>()?,
+        metadata: Some(document_metadata_to_message(&doc.metadata)),
+        version: Some(pb::DocumentVersion {
+            counter: doc.version.counter,
+            timestamp: Some(timestamp_to_message(&doc.version.timestamp)),
+            state_hash: doc.version.state_hash.to_vec(),
+        }),
+        hyperedges: doc.hyperedges.iter().map(hyperedge_to_message).collect(),
+    })
+}
+
+fn document_from_message(msg: pb::Document) -> Result<Document> {
+    let root = block_id_from_bytes("root", &msg.root)?;
+
+    let mut structure = HashMap::new();
+    for entry in msg.structure {
+        let parent = block_id_from_bytes("structure.parent", &entry.parent)?;
+        let children = entry
+            .children
+            .iter()
+            .map(|c| block_id_from_bytes("structure.children", c))
+            .collect::<Result<Vec<_>>>()?;
+        structure.insert(parent, children);
+    }
+
+    let mut blocks = HashMap::new();
+    for block in msg.blocks {
+        let block = block_from_message(block)?;
+        blocks.insert(block.id, block);
+    }
+
+    let metadata = document_metadata_from_message(
+        msg.metadata
+            .ok_or(ProtoError::MissingField("document.metadata"))?,
+    )?;
+
+    let version = msg
+        .version
+        .ok_or(ProtoError::MissingField("document.version"))?;
+    let state_hash: [u8; 8] = version
+        .state_hash
+        .as_slice()
+        .try_into()
+        .map_err(|_| ProtoError::invalid("document.version.state_hash", "expected 8 bytes"))?;
+
+    let hyperedges = msg
+        .hyperedges
+        .into_iter()
+        .map(hyperedge_from_message)
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut doc = Document {
+        id: DocumentId::new(msg.id),
+        root,
+        structure,
+        blocks,
+        metadata,
+        indices: DocumentIndices::default(),
+        edge_index: EdgeIndex::default(),
+        hyperedges,
+        version: DocumentVersion {
+            counter: version.counter,
+            timestamp: timestamp_from_message("document.version.timestamp", version.timestamp)?,
+            state_hash,
+        },
+        config: DocumentConfig::default(),
+        content_cache: ContentCache::default(),
+    };
+    doc.rebuild_indices();
+    Ok(doc)
+}
+
+fn document_metadata_to_message(metadata: &DocumentMetadata) -> pb::DocumentMetadata {
+    pb::DocumentMetadata {
+        title: metadata.title.clone(),
+        description: metadata.description.clone(),
+        authors: metadata.authors.clone(),
+        created_at: Some(timestamp_to_message(&metadata.created_at)),
+        modified_at: Some(timestamp_to_message(&metadata.modified_at)),
+        language: metadata.language.clone(),
+        custom: custom_to_message(&metadata.custom),
+    }
+}
+
+fn document_metadata_from_message(msg: pb::DocumentMetadata) -> Result<DocumentMetadata> {
+    Ok(DocumentMetadata {
+        title: msg.title,
+        description: msg.description,
+        authors: msg.authors,
+        created_at: timestamp_from_message("document.metadata.created_at", msg.created_at)?,
+        modified_at: timestamp_from_message("document.metadata.modified_at", msg.modified_at)?,
+        language: msg.language,
+        custom: custom_from_message(msg.custom)?,
+    })
+}
+
+// Block
+
+fn block_to_message(block: &Block) -> Result<pb::Block> {
+    Ok(pb::Block {
+        id: block.id.as_bytes().to_vec(),
+        content: Some(content_to_message(&block.content)?),
+        metadata: Some(block_metadata_to_message(&block.metadata)),
+        edges: block.edges.iter().map(edge_to_message).collect(),
+        version: Some(pb::Version {
+            counter: block.version.counter,
+            timestamp: Some(timestamp_to_message(&block.version.timestamp)),
+        }),
+    })
+}
+
+fn block_from_message(msg: pb::Block) -> Result<Block> {
+    let version = msg
+        .version
+        .ok_or(ProtoError::MissingField("block.version"))?;
+
+    Ok(Block {
+        id: block_id_from_bytes("block.id", &msg.id)?,
+        content: content_from_message(
+            msg.content
+                .ok_or(ProtoError::MissingField("block.content"))?,
+        )?,
+        metadata: block_metadata_from_message(
+            msg.metadata
+                .ok_or(ProtoError::MissingField("block.metadata"))?,
+        )?,
+        edges: msg
+            .edges
+            .into_iter()
+            .map(edge_from_message)
+            .collect::<Result<Vec<_>>>()?,
+        version: Version {
+            counter: version.counter,
+            timestamp: timestamp_from_message("block.version.timestamp", version.timestamp)?,
+        },
+    })
+}
+
+fn block_metadata_to_message(metadata: &BlockMetadata) -> pb::BlockMetadata {
+    pb::BlockMetadata {
+        semantic_role: metadata
+            .semantic_role
+            .as_ref()
+            .map(|role| pb::SemanticRole {
+                category: role.category.as_str().to_string(),
+                subcategory: role.subcategory.clone(),
+                qualifier: role.qualifier.clone(),
+            }),
+        label: metadata.label.clone(),
+        tags: metadata.tags.clone(),
+        summary: metadata.summary.clone(),
+        token_estimate: metadata.token_estimate.map(|t| pb::TokenEstimate {
+            gpt4: t.gpt4,
+            claude: t.claude,
+            llama: t.llama,
+            generic: t.generic,
+        }),
+        content_hash: metadata.content_hash.as_bytes().to_vec(),
+        created_at: Some(timestamp_to_message(&metadata.created_at)),
+        modified_at: Some(timestamp_to_message(&metadata.modified_at)),
+        custom: custom_to_message(&metadata.custom),
+    }
+}
+
+fn block_metadata_from_message(msg: pb::BlockMetadata) -> Result<BlockMetadata> {
+    let semantic_role = match msg.semantic_role {
+        Some(role) => Some(SemanticRole {
+            category: RoleCategory::from_str(&role.category)
+                .map_err(|e| ProtoError::invalid("block.metadata.semantic_role", e.to_string()))?,
+            subcategory: role.subcategory,
+            qualifier: role.qualifier,
+        }),
+        None => None,
+    };
+
+    let content_hash: [u8; 32] = msg
+        .content_hash
+        .as_slice()
+        .try_into()
+        .map_err(|_| ProtoError::invalid("block.metadata.content_hash", "expected 32 bytes"))?;
+
+    Ok(BlockMetadata {
+        semantic_role,
+        label: msg.label,
+        tags: msg.tags,
+        summary: msg.summary,
+        token_estimate: msg.token_estimate.map(|t| TokenEstimate {
+            gpt4: t.gpt4,
+            claude: t.claude,
+            llama: t.llama,
+            generic: t.generic,
+        }),
+        content_hash: ContentHash::from_bytes(content_hash),
+        created_at: timestamp_from_message("block.metadata.created_at", msg.created_at)?,
+        modified_at: timestamp_from_message("block.metadata.modified_at", msg.modified_at)?,
+        custom: custom_from_message(msg.custom)?,
+    })
+}
+
+// Content
+
+fn content_to_message(content: &Content) -> Result<pb::Content> {
+    use pb::content::Kind;
+
+    let kind = match content {
+        Content::Text(text) => Kind::Text(pb::Text {
+            text: text.text.clone(),
+            format: match text.format {
+                TextFormat::Plain => pb::TextFormat::Plain,
+                TextFormat::Markdown => pb::TextFormat::Markdown,
+                TextFormat::Rich => pb::TextFormat::Rich,
+            } as i32,
+        }),
+        Content::RichText(rich) => Kind::RichText(pb::JsonPayload {
+            json: serde_json::to_string(rich)?,
+        }),
+        Content::Table(table) => Kind::Table(pb::JsonPayload {
+            json: serde_json::to_string(table)?,
+        }),
+        Content::Spreadsheet(sheet) => Kind::Spreadsheet(pb::JsonPayload {
+            json: serde_json::to_string(sheet)?,
+        }),
+        Content::Code(code) => Kind::Code(pb::Code {
+            language: code.language.clone(),
+            source: code.source.clone(),
+            highlights: code
+                .highlights
+                .iter()
+                .map(|r| pb::LineRange {
+                    start: r.start as u64,
+                    end: r.end as u64,
+                })
+                .collect(),
+        }),
+        Content::Math(math) => Kind::Math(pb::Math {
+            format: match math.format {
+                MathFormat::LaTeX => pb::MathFormat::Latex,
+                MathFormat::MathML => pb::MathFormat::Mathml,
+                MathFormat::AsciiMath => pb::MathFormat::Asciimath,
+            } as i32,
+            expression: math.expression.clone(),
+            display_mode: math.display_mode,
+        }),
+        Content::Diagram(diagram) => Kind::Diagram(pb::Diagram {
+            dialect: match diagram.dialect {
+                DiagramDialect::Mermaid => pb::DiagramDialect::Mermaid,
+                DiagramDialect::Graphviz => pb::DiagramDialect::Graphviz,
+                DiagramDialect::PlantUml => pb::DiagramDialect::Plantuml,
+            } as i32,
+            source: diagram.source.clone(),
+        }),
+        Content::Diff(diff) => Kind::Diff(pb::Diff {
+            format: match diff.format {
+                DiffFormat::Unified => pb::DiffFormat::Unified,
+                DiffFormat::GitPatch => pb::DiffFormat::GitPatch,
+                DiffFormat::JsonPatch => pb::DiffFormat::JsonPatch,
+            } as i32,
+            patch: diff.patch.clone(),
+        }),
+        Content::GeoJson(value) => Kind::Geojson(pb::JsonPayload {
+            json: value.to_string(),
+        }),
+        Content::Form(form) => Kind::Form(pb::JsonPayload {
+            json: serde_json::to_string(form)?,
+        }),
+        Content::CompressedCode {
+            language,
+            compressed,
+            original_len,
+        } => Kind::CompressedCode(pb::CompressedCode {
+            language: language.clone(),
+            compressed: compressed.clone(),
+            original_len: *original_len as u64,
+        }),
+        Content::Media(media) => Kind::Media(pb::JsonPayload {
+            json: serde_json::to_string(media)?,
+        }),
+        Content::Json { value, schema } => Kind::Json(pb::Json {
+            value: value.to_string(),
+            schema: schema.as_ref().map(serde_json::to_string).transpose()?,
+        }),
+        Content::Binary {
+            mime_type,
+            data,
+            encoding,
+        } => Kind::Binary(pb::Binary {
+            mime_type: mime_type.clone(),
+            data: data.clone(),
+            encoding: match encoding {
+                BinaryEncoding::Raw => pb::BinaryEncoding::Raw,
+                BinaryEncoding::Base64 => pb::BinaryEncoding::Base64,
+                BinaryEncoding::Hex => pb::BinaryEncoding::Hex,
+            } as i32,
+        }),
+        Content::Composite { layout, children } => {
+            let (layout, grid_columns) = match layout {
+                CompositeLayout::Vertical => (pb::CompositeLayout::Vertical, 0),
+                CompositeLayout::Horizontal => (pb::CompositeLayout::Horizontal, 0),
+                CompositeLayout::Grid(columns) => (pb::CompositeLayout::Grid, *columns as u64),
+                CompositeLayout::Tabs => (pb::CompositeLayout::Tabs, 0),
+            };
+            Kind::Composite(pb::Composite {
+                layout: layout as i32,
+                grid_columns,
+                children: children.iter().map(|c| c.as_bytes().to_vec()).collect(),
+            })
+        }
+    };
+
+    Ok(pb::Content { kind: Some(kind) })
+}
+
+fn content_from_message(msg: pb::Content) -> Result<Content> {
+    use pb::content::Kind;
+
+    let kind = msg.kind.ok_or(ProtoError::MissingField("content.kind"))?;
+    Ok(match kind {
+        Kind::Text(text) => Content::Text(Text {
+            text: text.text,
+            format: match enum_value::<pb::TextFormat>("content.text.format", text.format)? {
+                pb::TextFormat::Plain => TextFormat::Plain,
+                pb::TextFormat::Markdown => TextFormat::Markdown,
+                pb::TextFormat::Rich => TextFormat::Rich,
+            },
+        }),
+        Kind::Table(payload) => Content::Table(serde_json::from_str(&payload.json)?),
+        Kind::Code(code) => Content::Code(Code {
+            language: code.language,
+            source: code.source,
+            highlights: code
+                .highlights
+                .into_iter()
+                .map(|r| LineRange::new(r.start as usize, r.end as usize))
+                .collect(),
+        }),
+        Kind::Math(math) => Content::Math(Math {
+            format: match enum_value::<pb::MathFormat>("content.math.format", math.format)? {
+                pb::MathFormat::Latex => MathFormat::LaTeX,
+                pb::MathFormat::Mathml => MathFormat::MathML,
+                pb::MathFormat::Asciimath => MathFormat::AsciiMath,
+            },
+            expression: math.expression,
+            display_mode: math.display_mode,
+        }),
+        Kind::Diagram(diagram) => Content::Diagram(DiagramContent {
+            dialect: match enum_value::<pb::DiagramDialect>(
+                "content.diagram.dialect",
+                diagram.dialect,
+            )? {
+                pb::DiagramDialect::Mermaid => DiagramDialect::Mermaid,
+                pb::DiagramDialect::Graphviz => DiagramDialect::Graphviz,
+                pb::DiagramDialect::Plantuml => DiagramDialect::PlantUml,
+            },
+            source: diagram.source,
+        }),
+        Kind::Diff(diff) => Content::Diff(DiffContent {
+            format: match enum_value::<pb::DiffFormat>("content.diff.format", diff.format)? {
+                pb::DiffFormat::Unified => DiffFormat::Unified,
+                pb::DiffFormat::GitPatch => DiffFormat::GitPatch,
+                pb::DiffFormat::JsonPatch => DiffFormat::JsonPatch,
+            },
+            patch: diff.patch,
+        }),
+        Kind::Media(payload) => Content::Media(serde_json::from_str(&payload.json)?),
+        Kind::Spreadsheet(payload) => Content::Spreadsheet(serde_json::from_str(&payload.json)?),
+        Kind::RichText(payload) => Content::RichText(serde_json::from_str(&payload.json)?),
+        Kind::Geojson(payload) => Content::GeoJson(serde_json::from_str(&payload.json)?),
+        Kind::Form(payload) => Content::Form(serde_json::from_str(&payload.json)?),
+        Kind::CompressedCode(code) => Content::CompressedCode {
+            language: code.language,
+            compressed: code.compressed,
+            original_len: usize::try_from(code.original_len).map_err(|_| {
+                ProtoError::invalid("content.compressed_code.original_len", "too large")
+            })?,
+        },
+        Kind::Json(json) => Content::Json {
+            value: serde_json::from_str(&json.value)?,
+            schema: json
+                .schema
+                .map(|s| serde_json::from_str::<JsonSchema>(&s))
+                .transpose()?,
+        },
+        Kind::Binary(binary) => Content::Binary {
+            mime_type: binary.mime_type,
+            data: binary.data,
+            encoding: match enum_value::<pb::BinaryEncoding>(
+                "content.binary.encoding",
+                binary.encoding,
+            )? {
+                pb::BinaryEncoding::Raw => BinaryEncoding::Raw,
+                pb::BinaryEncoding::Base64 => BinaryEncoding::Base64,
+                pb::BinaryEncoding::Hex => BinaryEncoding::Hex,
+            },
+        },
+        Kind::Composite(composite) => Content::Composite {
+            layout: match enum_value::<pb::CompositeLayout>(
+                "content.composite.layout",
+                composite.layout,
+            )? {
+                pb::CompositeLayout::Vertical => CompositeLayout::Vertical,
+                pb::CompositeLayout::Horizontal => CompositeLayout::Horizontal,
+                pb::CompositeLayout::Grid => CompositeLayout::Grid(composite.grid_columns as usize),
+                pb::CompositeLayout::Tabs => CompositeLayout::Tabs,
+            },
+            children: composite
+                .children
+                .iter()
+                .map(|c| block_id_from_bytes("content.composite.children", c))
+                .collect::<Result<Vec<_>>>()?,
+        },
+    })
+}
+
+// Edge
+
+fn edge_to_message(edge: &Edge) -> pb::Edge {
+    pb::Edge {
+        edge_type: edge.edge_type.as_str(),
+        target: edge.target.as_bytes().to_vec(),
+        metadata: if edge.metadata.is_empty() {
+            None
+        } else {
+            Some(pb::EdgeMetadata {
+                confidence: edge.metadata.confidence,
+                description: edge.metadata.description.clone(),
+                custom: custom_to_message(&edge.metadata.custom),
+            })
+        },
+        created_at: Some(timestamp_to_message(&edge.created_at)),
+        weight: edge.weight,
+        valid_from: edge.valid_from.as_ref().map(timestamp_to_message),
+        valid_to: edge.valid_to.as_ref().map(timestamp_to_message),
+    }
+}
+
+fn edge_from_message(msg: pb::Edge) -> Result<Edge> {
+    // `EdgeType::from_str` lowercases its input, which would mangle custom names
+    let edge_type = match msg.edge_type.strip_prefix("custom:") {
+        Some(name) => EdgeType::Custom(name.to_string()),
+        None => EdgeType::from_str(&msg.edge_type)
+            .map_err(|e| ProtoError::invalid("edge.edge_type", e.to_string()))?,
+    };
+
+    let metadata = match msg.metadata {
+        Some(metadata) => EdgeMetadata {
+            confidence: metadata.confidence,
+            description: metadata.description,
+            custom: custom_from_message(metadata.custom)?,
+        },
+        None => EdgeMetadata::default(),
+    };
+
+    Ok(Edge {
+        edge_type,
+        target: block_id_from_bytes("edge.target", &msg.target)?,
+        weight: msg.weight,
+        valid_from: msg
+            .valid_from
+            .map(|ts| timestamp_from_message("edge.valid_from", Some(ts)))
+            .transpose()?,
+        valid_to: msg
+            .valid_to
+            .map(|ts| timestamp_from_message("edge.valid_to", Some(ts)))
+            .transpose()?,
+        metadata,
+        created_at: timestamp_from_message("edge.created_at", msg.created_at)?,
+    })
+}
+
+fn hyperedge_to_message(hyperedge: &HyperEdge) -> pb::HyperEdge {
+    pb::HyperEdge {
+        id: hyperedge.id.0.clone(),
+        sources: hyperedge
+            .sources
+            .iter()
+            .map(|s| s.as_bytes().to_vec())
+            .collect(),
+        edge: Some(edge_to_message(&hyperedge.to_edge())),
+    }
+}
+
+fn hyperedge_from_message(msg: pb::HyperEdge) -> Result<HyperEdge> {
+    let edge = edge_from_message(msg.edge.ok_or(ProtoError::MissingField("hyperedge.edge"))?)?;
+    Ok(HyperEdge {
+        id: EdgeId::new(msg.id),
+        sources: msg
+            .sources
+            .iter()
+            .map(|s| block_id_from_bytes("hyperedge.sources", s))
+            .collect::<Result<Vec<_>>>()?,
+        edge_type: edge.edge_type,
+        target: edge.target,
+        metadata: edge.metadata,
+        created_at: edge.created_at,
+    })
+}
+
+// Helpers
+
+fn block_id_from_bytes(field: &'static str, bytes: &[u8]) -> Result<BlockId> {
+    let bytes: [u8; 12] = bytes.try_into().map_err(|_| {
+        ProtoError::invalid(field, format!("expected 12 bytes, got {}", bytes.len()))
+    })?;
+    Ok(BlockId::from_bytes(bytes))
+}
+
+fn timestamp_to_message(ts: &DateTime<Utc>) -> pb::Timestamp {
+    pb::Timestamp {
+        seconds: ts.timestamp(),
+        nanos: ts.timestamp_subsec_nanos() as i32,
+    }
+}
+
+fn timestamp_from_message(field: &'static str, ts: Option<pb::Timestamp>) -> Result<DateTime<Utc>> {
+    let ts = ts.ok_or(ProtoError::MissingField(field))?;
+    DateTime::from_timestamp(ts.seconds, ts.nanos as u32)
+        .ok_or_else(|| ProtoError::invalid(field, "timestamp out of range"))
+}
+
+fn enum_value<E: TryFrom<i32>>(field: &'static str, value: i32) -> Result<E> {
+    E::try_from(value).map_err(|_| ProtoError::invalid(field, format!("unknown value {}", value)))
+}
+
+fn custom_to_message(custom: &HashMap<String, serde_json::Value>) -> BTreeMap<String, String> {
+    custom
+        .iter()
+        .map(|(k, v)| (k.clone(), v.to_string()))
+        .collect()
+}
+
+fn custom_from_message(
+    custom: BTreeMap<String, String>,
+) -> Result<HashMap<String, serde_json::Value>> {
+    custom
+        .into_iter()
+        .map(|(k, v)| Ok((k, serde_json::from_str(&v)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ucm_core::{Cell, Column, InlineSpan, MediaSource, Row, Table};
+
+    fn sample_document() -> Document {
+        let mut doc = Document::create();
+        doc.metadata.title = Some("Proto".to_string());
+        doc.metadata.authors = vec!["ada".to_string()];
+        doc.metadata
+            .custom
+            .insert("source".to_string(), serde_json::json!({"kind": "test"}));
+        let root = doc.root;
+
+        let heading = Block::new(Content::text("Intro"), Some("heading1"))
+            .with_label("intro")
+            .with_tag("important");
+        let heading_id = doc.add_block(heading, &root).unwrap();
+
+        let code = Block::new(Content::code("rust", "fn main() {}"), Some("code"));
+        let code_id = doc.add_block(code, &heading_id).unwrap();
+
+        let mut table = Table::new(vec![Column::new("name"), Column::new("age")]);
+        table.add_row(Row::new(vec![Cell::from("Alice"), Cell::from(30i64)]));
+        doc.add_block(Block::new(Content::Table(table), None), &heading_id)
+            .unwrap();
+
+        let media = ucm_core::Media::image(MediaSource::url("https://example.com/a.png"))
+            .with_alt("diagram");
+        doc.add_block(Block::new(Content::Media(media), None), &root)
+            .unwrap();
+
+        let linked = Block::new(Content::markdown("See *code*"), Some("paragraph")).with_edge(
+            Edge::new(EdgeType::Custom("DependsOn".to_string()), code_id)
+                .with_confidence(0.5)
+                .with_weight(0.25)
+                .with_validity(None, DateTime::from_timestamp(1_735_603_200, 0)),
+        );
+        let linked_id = doc.add_block(linked, &root).unwrap();
+        doc.add_hyperedge(HyperEdge::new(
+            vec![heading_id, linked_id],
+            EdgeType::References,
+            code_id,
+        ))
+        .unwrap();
+
+        let rich = Content::rich_text(vec![
+            InlineSpan::plain("See "),
+            InlineSpan::BlockRef {
+                display: "the code".to_string(),
+                block_id: code_id,
+            },
+        ]);
+        doc.add_block(Block::new(rich, Some("paragraph")), &root)
+            .unwrap();
+
+        doc.add_block(
+            Block::new(Content::mermaid("graph TD\n  A --> B\n"), None),
+            &root,
+        )
+        .unwrap();
+
+        doc.add_block(
+            Block::new(
+                Content::diff(DiffFormat::JsonPatch, r#"[{"op":"remove","path":"/a"}]"#),
+                None,
+            ),
+            &root,
+        )
+        .unwrap();
+
+        let sheet = ucm_core::SpreadsheetContent::new(vec!["n".to_string()])
+            .with_rows(vec![
+                vec![ucm_core::CellValue::Number(2.0)],
+                vec![ucm_core::CellValue::Formula("=SUM(A1)".to_string())],
+            ])
+            .with_formula(2, 0, "=COUNT(A1:A2)");
+        doc.add_block(Block::new(Content::Spreadsheet(sheet), None), &root)
+            .unwrap();
+
+        let form = ucm_core::FormContent::new("Survey").with_field(
+            ucm_core::FormField::new("age", "Age", ucm_core::FieldType::Number).required(),
+        );
+        doc.add_block(Block::new(Content::Form(form), None), &root)
+            .unwrap();
+
+        let point = serde_json::json!({"type": "Point", "coordinates": [4.9, 52.4]});
+        doc.add_block(
+            Block::new(Content::new_geojson(point).unwrap(), None),
+            &root,
+        )
+        .unwrap();
+
+        doc.add_block(
+            Block::new(
+                Content::Composite {
+                    layout: CompositeLayout::Grid(3),
+                    children: vec![code_id],
+                },
+                None,
+            ),
+            &root,
+        )
+        .unwrap();
+
+        doc
+    }
+
+    #[test]
+    fn test_document_round_trip() {
+        let doc = sample_document();
+        let decoded = Document::from_proto(&doc.to_proto().unwrap()).unwrap();
+
+        assert_eq!(decoded.id, doc.id);
+        assert_eq!(decoded.root, doc.root);
+        assert_eq!(decoded.structure, doc.structure);
+        assert_eq!(decoded.blocks, doc.blocks);
+        assert_eq!(decoded.metadata, doc.metadata);
+        assert_eq!(decoded.version, doc.version);
+        assert_eq!(decoded.hyperedges, doc.hyperedges);
+        assert!(decoded.indices.find_by_label("intro").is_some());
+    }
+
+    #[test]
+    fn test_block_round_trip() {
+        let block = Block::new(
+            Content::json(serde_json::json!({"a": [1, 2, 3]})),
+            Some("metadata"),
+        )
+        .with_tag("json");
+
+        let decoded = Block::from_proto(&block.to_proto().unwrap()).unwrap();
+        assert_eq!(decoded, block);
+    }
+
+    #[test]
+    fn test_custom_edge_type_preserves_case() {
+        let doc = sample_document();
+        let decoded = Document::from_proto(&doc.to_proto().unwrap()).unwrap();
+
+        let has_custom = decoded
+            .blocks
+            .values()
+            .flat_map(|b| &b.edges)
+            .any(|e| e.edge_type == EdgeType::Custom("DependsOn".to_string()));
+        assert!(has_custom);
+    }
+
+    #[test]
+    fn test_invalid_bytes_rejected() {
+        assert!(matches!(
+            Document::from_proto(&[0xff, 0xff, 0xff]),
+            Err(ProtoError::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn test_missing_content_rejected() {
+        let mut msg = block_to_message(&Block::new(Content::text("x"), None)).unwrap();
+        msg.content = None;
+
+        assert!(matches!(
+            Block::from_proto(&msg.encode_to_vec()),
+            Err(ProtoError::MissingField("block.content"))
+        ));
+    }
+
+    #[test]
+    fn test_encoding_is_deterministic() {
+        let doc = sample_document();
+        assert_eq!(doc.to_proto().unwrap(), doc.clone().to_proto().unwrap());
+    }
+}
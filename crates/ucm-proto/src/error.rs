@@ -0,0 +1,37 @@
+//! Error types for protobuf serialization.
+
+use thiserror::Error;
+
+/// Protobuf serialization error
+#[derive(Debug, Error)]
+pub enum ProtoError {
+    #[error("Decode error: {0}")]
+    Decode(#[from] prost::DecodeError),
+
+    #[error("Missing field: {0}")]
+    MissingField(&'static str),
+
+    #[error("Invalid {field}: {message}")]
+    InvalidField {
+        field: &'static str,
+        message: String,
+    },
+
+    #[error("JSON payload error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Core error: {0}")]
+    Core(#[from] ucm_core::Error),
+}
+
+impl ProtoError {
+    pub(crate) fn invalid(field: &'static str, message: impl Into<String>) -> Self {
+        Self::InvalidField {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+/// Result type for protobuf serialization
+pub type Result<T> = std::result::Result<T, ProtoError>;
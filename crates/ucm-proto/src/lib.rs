@@ -0,0 +1,32 @@
+//! # UCM Proto
+//!
+//! Protocol Buffers serialization for the Unified Content Model.
+//!
+//! The schema lives in `proto/ucm.proto`; [`messages`] holds the matching
+//! prost types. Import [`ProtoCodec`] to get `to_proto` / `from_proto` on
+//! [`Document`](ucm_core::Document) and [`Block`](ucm_core::Block).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use ucm_core::{Block, Content, Document};
+//! use ucm_proto::ProtoCodec;
+//!
+//! let mut doc = Document::create();
+//! let root = doc.root;
+//! doc.add_block(Block::new(Content::text("Hello"), Some("intro")), &root).unwrap();
+//!
+//! let bytes = doc.to_proto().unwrap();
+//! let decoded = Document::from_proto(&bytes).unwrap();
+//! assert_eq!(decoded.block_count(), doc.block_count());
+//! ```
+
+mod convert;
+pub mod error;
+pub mod messages;
+
+pub use convert::ProtoCodec;
+pub use error::{ProtoError, Result};
+
+/// MIME type used when protobuf-encoded documents travel over HTTP or FFI
+pub const CONTENT_TYPE: &str = "application/x-protobuf";
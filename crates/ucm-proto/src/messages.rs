@@ -0,0 +1,362 @@
+//! Protocol Buffers message types.
+//!
+//! These mirror `proto/ucm.proto` (package `ucm.v1`) and are kept by hand so
+//! the crate builds without `protoc`.
+
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, PartialEq, Eq, ::prost::Message)]
+pub struct Timestamp {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Document {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub root: Vec<u8>,
+    #[prost(message, repeated, tag = "3")]
+    pub structure: Vec<Structure>,
+    #[prost(message, repeated, tag = "4")]
+    pub blocks: Vec<Block>,
+    #[prost(message, optional, tag = "5")]
+    pub metadata: Option<DocumentMetadata>,
+    #[prost(message, optional, tag = "6")]
+    pub version: Option<DocumentVersion>,
+    #[prost(message, repeated, tag = "7")]
+    pub hyperedges: Vec<HyperEdge>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Structure {
+    #[prost(bytes = "vec", tag = "1")]
+    pub parent: Vec<u8>,
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub children: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DocumentMetadata {
+    #[prost(string, optional, tag = "1")]
+    pub title: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    pub description: Option<String>,
+    #[prost(string, repeated, tag = "3")]
+    pub authors: Vec<String>,
+    #[prost(message, optional, tag = "4")]
+    pub created_at: Option<Timestamp>,
+    #[prost(message, optional, tag = "5")]
+    pub modified_at: Option<Timestamp>,
+    #[prost(string, optional, tag = "6")]
+    pub language: Option<String>,
+    #[prost(btree_map = "string, string", tag = "7")]
+    pub custom: BTreeMap<String, String>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DocumentVersion {
+    #[prost(uint64, tag = "1")]
+    pub counter: u64,
+    #[prost(message, optional, tag = "2")]
+    pub timestamp: Option<Timestamp>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub state_hash: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Block {
+    #[prost(bytes = "vec", tag = "1")]
+    pub id: Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub content: Option<Content>,
+    #[prost(message, optional, tag = "3")]
+    pub metadata: Option<BlockMetadata>,
+    #[prost(message, repeated, tag = "4")]
+    pub edges: Vec<Edge>,
+    #[prost(message, optional, tag = "5")]
+    pub version: Option<Version>,
+}
+
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct Version {
+    #[prost(uint64, tag = "1")]
+    pub counter: u64,
+    #[prost(message, optional, tag = "2")]
+    pub timestamp: Option<Timestamp>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Content {
+    #[prost(
+        oneof = "content::Kind",
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15"
+    )]
+    pub kind: Option<content::Kind>,
+}
+
+pub mod content {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Kind {
+        #[prost(message, tag = "1")]
+        Text(super::Text),
+        #[prost(message, tag = "2")]
+        Table(super::JsonPayload),
+        #[prost(message, tag = "3")]
+        Code(super::Code),
+        #[prost(message, tag = "4")]
+        Math(super::Math),
+        #[prost(message, tag = "5")]
+        Media(super::JsonPayload),
+        #[prost(message, tag = "6")]
+        Json(super::Json),
+        #[prost(message, tag = "7")]
+        Binary(super::Binary),
+        #[prost(message, tag = "8")]
+        Composite(super::Composite),
+        #[prost(message, tag = "9")]
+        RichText(super::JsonPayload),
+        #[prost(message, tag = "10")]
+        Diagram(super::Diagram),
+        #[prost(message, tag = "11")]
+        Spreadsheet(super::JsonPayload),
+        #[prost(message, tag = "12")]
+        Geojson(super::JsonPayload),
+        #[prost(message, tag = "13")]
+        Form(super::JsonPayload),
+        #[prost(message, tag = "14")]
+        CompressedCode(super::CompressedCode),
+        #[prost(message, tag = "15")]
+        Diff(super::Diff),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum TextFormat {
+    Plain = 0,
+    Markdown = 1,
+    Rich = 2,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Text {
+    #[prost(string, tag = "1")]
+    pub text: String,
+    #[prost(enumeration = "TextFormat", tag = "2")]
+    pub format: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct JsonPayload {
+    #[prost(string, tag = "1")]
+    pub json: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ::prost::Message)]
+pub struct LineRange {
+    #[prost(uint64, tag = "1")]
+    pub start: u64,
+    #[prost(uint64, tag = "2")]
+    pub end: u64,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Code {
+    #[prost(string, tag = "1")]
+    pub language: String,
+    #[prost(string, tag = "2")]
+    pub source: String,
+    #[prost(message, repeated, tag = "3")]
+    pub highlights: Vec<LineRange>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CompressedCode {
+    #[prost(string, tag = "1")]
+    pub language: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub compressed: Vec<u8>,
+    #[prost(uint64, tag = "3")]
+    pub original_len: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum MathFormat {
+    Latex = 0,
+    Mathml = 1,
+    Asciimath = 2,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Math {
+    #[prost(enumeration = "MathFormat", tag = "1")]
+    pub format: i32,
+    #[prost(string, tag = "2")]
+    pub expression: String,
+    #[prost(bool, tag = "3")]
+    pub display_mode: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum DiagramDialect {
+    Mermaid = 0,
+    Graphviz = 1,
+    Plantuml = 2,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Diagram {
+    #[prost(enumeration = "DiagramDialect", tag = "1")]
+    pub dialect: i32,
+    #[prost(string, tag = "2")]
+    pub source: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum DiffFormat {
+    Unified = 0,
+    GitPatch = 1,
+    JsonPatch = 2,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Diff {
+    #[prost(enumeration = "DiffFormat", tag = "1")]
+    pub format: i32,
+    #[prost(string, tag = "2")]
+    pub patch: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Json {
+    #[prost(string, tag = "1")]
+    pub value: String,
+    #[prost(string, optional, tag = "2")]
+    pub schema: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum BinaryEncoding {
+    Raw = 0,
+    Base64 = 1,
+    Hex = 2,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Binary {
+    #[prost(string, tag = "1")]
+    pub mime_type: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub data: Vec<u8>,
+    #[prost(enumeration = "BinaryEncoding", tag = "3")]
+    pub encoding: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CompositeLayout {
+    Vertical = 0,
+    Horizontal = 1,
+    Grid = 2,
+    Tabs = 3,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Composite {
+    #[prost(enumeration = "CompositeLayout", tag = "1")]
+    pub layout: i32,
+    #[prost(uint64, tag = "2")]
+    pub grid_columns: u64,
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub children: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SemanticRole {
+    #[prost(string, tag = "1")]
+    pub category: String,
+    #[prost(string, optional, tag = "2")]
+    pub subcategory: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    pub qualifier: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ::prost::Message)]
+pub struct TokenEstimate {
+    #[prost(uint32, tag = "1")]
+    pub gpt4: u32,
+    #[prost(uint32, tag = "2")]
+    pub claude: u32,
+    #[prost(uint32, tag = "3")]
+    pub llama: u32,
+    #[prost(uint32, tag = "4")]
+    pub generic: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BlockMetadata {
+    #[prost(message, optional, tag = "1")]
+    pub semantic_role: Option<SemanticRole>,
+    #[prost(string, optional, tag = "2")]
+    pub label: Option<String>,
+    #[prost(string, repeated, tag = "3")]
+    pub tags: Vec<String>,
+    #[prost(string, optional, tag = "4")]
+    pub summary: Option<String>,
+    #[prost(message, optional, tag = "5")]
+    pub token_estimate: Option<TokenEstimate>,
+    #[prost(bytes = "vec", tag = "6")]
+    pub content_hash: Vec<u8>,
+    #[prost(message, optional, tag = "7")]
+    pub created_at: Option<Timestamp>,
+    #[prost(message, optional, tag = "8")]
+    pub modified_at: Option<Timestamp>,
+    #[prost(btree_map = "string, string", tag = "9")]
+    pub custom: BTreeMap<String, String>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EdgeMetadata {
+    #[prost(float, optional, tag = "1")]
+    pub confidence: Option<f32>,
+    #[prost(string, optional, tag = "2")]
+    pub description: Option<String>,
+    #[prost(btree_map = "string, string", tag = "3")]
+    pub custom: BTreeMap<String, String>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Edge {
+    #[prost(string, tag = "1")]
+    pub edge_type: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub target: Vec<u8>,
+    #[prost(message, optional, tag = "3")]
+    pub metadata: Option<EdgeMetadata>,
+    #[prost(message, optional, tag = "4")]
+    pub created_at: Option<Timestamp>,
+    #[prost(double, optional, tag = "5")]
+    pub weight: Option<f64>,
+    #[prost(message, optional, tag = "6")]
+    pub valid_from: Option<Timestamp>,
+    #[prost(message, optional, tag = "7")]
+    pub valid_to: Option<Timestamp>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HyperEdge {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub sources: Vec<Vec<u8>>,
+    #[prost(message, optional, tag = "3")]
+    pub edge: Option<Edge>,
+}
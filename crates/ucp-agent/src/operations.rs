@@ -5,14 +5,15 @@ use crate::error::{AgentError, AgentSessionId, Result};
 use crate::rag::{RagProvider, RagSearchOptions, RagSearchResults};
 use crate::safety::{CircuitBreaker, DepthGuard, GlobalLimits};
 use crate::session::{AgentSession, SessionConfig};
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use std::time::Instant;
 use ucm_core::{BlockId, Document, EdgeType};
 use ucm_engine::traversal::{NavigateDirection, TraversalEngine, TraversalFilter, TraversalOutput};
 use ucp_codegraph::{
-    is_codegraph_document, render_codegraph_context_prompt, CodeGraphContextUpdate,
-    CodeGraphDetailLevel, CodeGraphRenderConfig,
+    find_symbols, is_codegraph_document, render_codegraph_context_prompt, CodeGraphContextUpdate,
+    CodeGraphDetailLevel, CodeGraphRenderConfig, SymbolQuery,
 };
 
 /// Result of a navigation operation.
@@ -450,7 +451,9 @@ impl AgentTraversal {
                 down.extend(up);
                 down
             }
-            ExpandDirection::Semantic => self.expand_semantic(&doc, &block_id, options.depth)?,
+            ExpandDirection::Semantic => {
+                self.expand_semantic(&doc, &block_id, options.depth, session.filter_expired_edges)?
+            }
         };
 
         let total_blocks: usize = levels.iter().map(|l| l.len()).sum();
@@ -529,6 +532,9 @@ impl AgentTraversal {
     }
 
     /// Find blocks by pattern (no RAG required).
+    ///
+    /// In CodeGraph documents the pattern is also matched fuzzily against
+    /// symbol names, and those symbols come first, best match first.
     pub fn find_by_pattern(
         &self,
         session_id: &AgentSessionId,
@@ -565,6 +571,22 @@ impl AgentTraversal {
             .transpose()
             .map_err(|e| AgentError::Internal(format!("Invalid regex pattern: {}", e)))?;
 
+        let symbol_ranks: HashMap<BlockId, usize> = match pattern {
+            Some(pattern) if is_codegraph_document(&doc) => find_symbols(
+                &doc,
+                &SymbolQuery {
+                    name_contains: Some(pattern.to_string()),
+                    fuzzy: true,
+                    ..Default::default()
+                },
+            )
+            .into_iter()
+            .enumerate()
+            .map(|(rank, hit)| (hit.block_id, rank))
+            .collect(),
+            _ => HashMap::new(),
+        };
+
         for block in doc.blocks.values() {
             total_searched += 1;
 
@@ -598,13 +620,14 @@ impl AgentTraversal {
             // Filter by content pattern
             if let Some(ref re) = regex {
                 let content = self.extract_content_text(&block.content);
-                if !re.is_match(&content) {
+                if !re.is_match(&content) && !symbol_ranks.contains_key(&block.id) {
                     continue;
                 }
             }
 
             matches.push(block.id);
         }
+        matches.sort_by_key(|id| symbol_ranks.get(id).copied().unwrap_or(usize::MAX));
 
         // Store results for CTX ADD RESULTS
         drop(sessions);
@@ -1165,12 +1188,19 @@ impl AgentTraversal {
     fn extract_content_text(&self, content: &ucm_core::Content) -> String {
         match content {
             ucm_core::Content::Text(t) => t.text.clone(),
+            ucm_core::Content::RichText(r) => r.plain_text(),
             ucm_core::Content::Code(c) => c.source.clone(),
+            ucm_core::Content::CompressedCode { .. } => content.decompress().unwrap_or_default(),
             ucm_core::Content::Table(t) => format!("Table: {} rows", t.rows.len()),
+            ucm_core::Content::Spreadsheet(s) => format!("Spreadsheet: {} rows", s.rows.len()),
             ucm_core::Content::Math(m) => m.expression.clone(),
+            ucm_core::Content::Diagram(d) => d.source.clone(),
+            ucm_core::Content::Diff(d) => d.patch.clone(),
             ucm_core::Content::Media(m) => {
                 m.alt_text.clone().unwrap_or_else(|| "Media".to_string())
             }
+            ucm_core::Content::GeoJson(_) => "GeoJSON data".to_string(),
+            ucm_core::Content::Form(f) => format!("Form: {} ({} fields)", f.title, f.fields.len()),
             ucm_core::Content::Json { .. } => "JSON data".to_string(),
             ucm_core::Content::Binary { .. } => "Binary data".to_string(),
             ucm_core::Content::Composite { children, .. } => {
@@ -1254,17 +1284,22 @@ impl AgentTraversal {
         doc: &Document,
         block_id: &BlockId,
         depth: usize,
+        filter_expired_edges: bool,
     ) -> Result<Vec<Vec<BlockId>>> {
         let mut levels = Vec::new();
         let mut visited = std::collections::HashSet::new();
         let mut current_level = vec![*block_id];
         visited.insert(*block_id);
+        let now = Utc::now();
 
         for _ in 0..depth {
             let mut next_level = Vec::new();
 
             for id in &current_level {
                 for (_, target) in doc.edge_index.outgoing_from(id) {
+                    if filter_expired_edges && Self::all_edges_expired(doc, id, target, now) {
+                        continue;
+                    }
                     if !visited.contains(target) {
                         visited.insert(*target);
                         next_level.push(*target);
@@ -1283,6 +1318,23 @@ impl AgentTraversal {
         Ok(levels)
     }
 
+    /// True when every edge from `source` to `target` has a `valid_to` before `now`.
+    fn all_edges_expired(
+        doc: &Document,
+        source: &BlockId,
+        target: &BlockId,
+        now: DateTime<Utc>,
+    ) -> bool {
+        doc.get_block(source).is_some_and(|block| {
+            let mut edges = block
+                .edges
+                .iter()
+                .filter(|edge| edge.target == *target)
+                .peekable();
+            edges.peek().is_some() && edges.all(|edge| edge.is_expired_at(now))
+        })
+    }
+
     fn bfs_path(
         &self,
         doc: &Document,
@@ -1428,4 +1480,50 @@ mod tests {
             Err(AgentError::MaxSessionsReached { max: 2 })
         ));
     }
+
+    #[test]
+    fn test_semantic_expand_filters_expired_edges() {
+        use ucm_core::{Block, Edge};
+
+        let mut doc = create_test_document();
+        let root = doc.root;
+        let source = doc
+            .add_block(Block::new(ucm_core::Content::text("src"), None), &root)
+            .unwrap();
+        let stale = doc
+            .add_block(Block::new(ucm_core::Content::text("old"), None), &root)
+            .unwrap();
+        let live = doc
+            .add_block(Block::new(ucm_core::Content::text("new"), None), &root)
+            .unwrap();
+        let expired = Edge::new(EdgeType::References, stale)
+            .with_validity(None, Some(Utc::now() - chrono::Duration::days(1)));
+        doc.blocks
+            .get_mut(&source)
+            .unwrap()
+            .edges
+            .push(expired.clone());
+        doc.edge_index.add_edge(&source, &expired);
+        doc.add_edge(&source, EdgeType::References, live);
+
+        let traversal = AgentTraversal::new(doc);
+        let expand = |config: SessionConfig| {
+            let session = traversal.create_session(config).unwrap();
+            traversal
+                .expand(
+                    &session,
+                    source,
+                    ExpandDirection::Semantic,
+                    ExpandOptions::new().with_depth(1),
+                )
+                .unwrap()
+                .levels
+        };
+
+        assert_eq!(expand(SessionConfig::default())[0].len(), 2);
+        assert_eq!(
+            expand(SessionConfig::default().with_filter_expired_edges(true)),
+            vec![vec![live]]
+        );
+    }
 }
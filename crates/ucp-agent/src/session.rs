@@ -94,6 +94,8 @@ pub struct SessionConfig {
     pub capabilities: AgentCapabilities,
     /// Initial view mode.
     pub view_mode: ViewMode,
+    /// Skip edges whose `valid_to` has passed during semantic expansion.
+    pub filter_expired_edges: bool,
 }
 
 impl SessionConfig {
@@ -125,6 +127,11 @@ impl SessionConfig {
         self.view_mode = mode;
         self
     }
+
+    pub fn with_filter_expired_edges(mut self, filter: bool) -> Self {
+        self.filter_expired_edges = filter;
+        self
+    }
 }
 
 /// Agent session state - tracks individual agent's position and history.
@@ -157,6 +164,8 @@ pub struct AgentSession {
     pub focus_block: Option<BlockId>,
     /// Codegraph-specific working set state when the active document is a codegraph.
     pub codegraph_context: Option<CodeGraphContextSession>,
+    /// Skip expired edges during semantic expansion.
+    pub filter_expired_edges: bool,
 }
 
 impl AgentSession {
@@ -177,6 +186,7 @@ impl AgentSession {
             context_blocks: HashSet::new(),
             focus_block: None,
             codegraph_context: None,
+            filter_expired_edges: config.filter_expired_edges,
         }
     }
 
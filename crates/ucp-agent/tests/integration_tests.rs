@@ -398,6 +398,21 @@ fn test_find_by_pattern() {
     traversal.close_session(&session_id).unwrap();
 }
 
+#[test]
+fn test_find_by_pattern_matches_codegraph_symbols_fuzzily() {
+    let doc = create_codegraph_document();
+    let util = ucp_codegraph::resolve_codegraph_selector(&doc, "symbol:src/util.rs::util").unwrap();
+    let traversal = AgentTraversal::new(doc);
+    let session_id = traversal.create_session(SessionConfig::default()).unwrap();
+
+    let result = traversal
+        .find_by_pattern(&session_id, None, None, None, Some("utill"))
+        .unwrap();
+    // No content contains the typo; `mod util;` and `fn util` match fuzzily
+    assert_eq!(result.matches.len(), 2);
+    assert!(result.matches.contains(&util));
+}
+
 #[tokio::test]
 async fn test_search_without_rag_provider() {
     let doc = create_test_document();
@@ -0,0 +1,558 @@
+//! JSON command form.
+//!
+//! Some models produce JSON far more reliably than UCL. This module converts
+//! the JSON command objects described by
+//! [`PromptBuilderMode::JsonCommands`](ucp_llm::PromptBuilderMode) into the
+//! same [`Command`] values the UCL parser produces:
+//!
+//! ```json
+//! [{"op": "edit", "block": "blk_000000000003", "path": "content.text", "value": "Hi"}]
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::{Map, Value as Json};
+use ucl_parser::{
+    AppendCommand, Command, ContentType, DeleteCommand, EditCommand, LinkCommand, MoveCommand,
+    MoveTarget, Operator, Path, PathSegment, SnapshotCommand, TransactionCommand, UnlinkCommand,
+    Value,
+};
+
+/// An invalid element of a JSON command array
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonCommandError {
+    /// Position of the element (`atomic` bodies use `parent.child`)
+    pub index: String,
+    pub message: String,
+}
+
+impl fmt::Display for JsonCommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "command [{}]: {}", self.index, self.message)
+    }
+}
+
+/// All errors found while converting a JSON command array
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonCommandErrors(pub Vec<JsonCommandError>);
+
+impl fmt::Display for JsonCommandErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<String> = self.0.iter().map(|e| e.to_string()).collect();
+        write!(f, "{}", messages.join("; "))
+    }
+}
+
+impl std::error::Error for JsonCommandErrors {}
+
+/// Convert JSON command objects into UCL commands.
+///
+/// Accepts an array of command objects, a single command object, or an
+/// object with a `commands` array. Every element is validated; all errors are
+/// reported together, each tagged with the element's index.
+pub fn parse_json_commands(value: &Json) -> Result<Vec<Command>, JsonCommandErrors> {
+    let items: Vec<&Json> = match value {
+        Json::Array(items) => items.iter().collect(),
+        Json::Object(obj) if !obj.contains_key("op") && obj.contains_key("commands") => {
+            match &obj["commands"] {
+                Json::Array(items) => items.iter().collect(),
+                other => {
+                    return Err(JsonCommandErrors(vec![JsonCommandError {
+                        index: "commands".into(),
+                        message: format!("expected an array, got {}", type_name(other)),
+                    }]))
+                }
+            }
+        }
+        Json::Object(_) => vec![value],
+        other => {
+            return Err(JsonCommandErrors(vec![JsonCommandError {
+                index: "-".into(),
+                message: format!(
+                    "expected an array of command objects, got {}",
+                    type_name(other)
+                ),
+            }]))
+        }
+    };
+
+    let mut commands = Vec::new();
+    let mut errors = Vec::new();
+    for (i, item) in items.into_iter().enumerate() {
+        match parse_command(item, &i.to_string()) {
+            Ok(cmd) => commands.push(cmd),
+            Err(mut errs) => errors.append(&mut errs),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(commands)
+    } else {
+        Err(JsonCommandErrors(errors))
+    }
+}
+
+fn parse_command(item: &Json, index: &str) -> Result<Command, Vec<JsonCommandError>> {
+    let err = |message: String| {
+        vec![JsonCommandError {
+            index: index.to_string(),
+            message,
+        }]
+    };
+
+    let obj = item
+        .as_object()
+        .ok_or_else(|| err(format!("expected an object, got {}", type_name(item))))?;
+    let fields = Fields { obj };
+    let op = fields.required_str("op").map_err(err)?;
+
+    let allowed: &[&str] = match op {
+        "edit" => &["op", "block", "path", "value", "operator"],
+        "append" => &[
+            "op",
+            "parent",
+            "content_type",
+            "content",
+            "label",
+            "role",
+            "index",
+        ],
+        "move" => &["op", "block", "to", "index", "before", "after"],
+        "delete" => &["op", "block", "cascade", "preserve_children"],
+        "link" | "unlink" => &["op", "source", "edge_type", "target"],
+        "snapshot" => &["op", "action", "name", "description", "other"],
+        "begin" | "commit" | "rollback" => &["op", "name"],
+        "atomic" => &["op", "commands"],
+        other => return Err(err(format!("unknown op \"{}\"", other))),
+    };
+    let mut unknown: Vec<&str> = obj
+        .keys()
+        .map(String::as_str)
+        .filter(|k| !allowed.contains(k))
+        .collect();
+    if !unknown.is_empty() {
+        unknown.sort_unstable();
+        return Err(err(format!(
+            "unknown field(s) for \"{}\": {}",
+            op,
+            unknown.join(", ")
+        )));
+    }
+
+    if op == "atomic" {
+        let body = fields.required("commands").map_err(err)?;
+        let body = body.as_array().ok_or_else(|| {
+            err(format!(
+                "\"commands\" must be an array, got {}",
+                type_name(body)
+            ))
+        })?;
+        let mut commands = Vec::new();
+        let mut errors = Vec::new();
+        for (i, child) in body.iter().enumerate() {
+            match parse_command(child, &format!("{}.{}", index, i)) {
+                Ok(cmd) => commands.push(cmd),
+                Err(mut errs) => errors.append(&mut errs),
+            }
+        }
+        return if errors.is_empty() {
+            Ok(Command::Atomic(commands))
+        } else {
+            Err(errors)
+        };
+    }
+
+    parse_simple(op, &fields).map_err(err)
+}
+
+fn parse_simple(op: &str, f: &Fields<'_>) -> Result<Command, String> {
+    Ok(match op {
+        "edit" => {
+            let operator = match f.optional_str("operator")? {
+                None | Some("set") => Operator::Set,
+                Some("append") => Operator::Append,
+                Some("remove") => Operator::Remove,
+                Some("increment") => Operator::Increment,
+                Some("decrement") => Operator::Decrement,
+                Some(other) => return Err(format!("unknown operator \"{}\"", other)),
+            };
+            Command::Edit(EditCommand {
+                block_id: f.block_id("block")?,
+                path: parse_path(f.required_str("path")?)?,
+                operator,
+                value: json_to_value(f.required("value")?),
+                condition: None,
+            })
+        }
+        "append" => {
+            let content_type = match f.optional_str("content_type")? {
+                None => ContentType::Text,
+                Some(s) => ContentType::parse_content_type(s)
+                    .ok_or_else(|| format!("unknown content_type \"{}\"", s))?,
+            };
+            let mut properties = HashMap::new();
+            for key in ["label", "role"] {
+                if let Some(v) = f.optional_str(key)? {
+                    properties.insert(key.to_string(), Value::String(v.to_string()));
+                }
+            }
+            Command::Append(AppendCommand {
+                parent_id: f.block_id("parent")?,
+                content_type,
+                properties,
+                content: f.required_str("content")?.to_string(),
+                index: f.optional_index("index")?,
+            })
+        }
+        "move" => {
+            let targets: Vec<&str> = ["to", "before", "after"]
+                .into_iter()
+                .filter(|k| f.obj.contains_key(*k))
+                .collect();
+            let target = match targets.as_slice() {
+                ["to"] => MoveTarget::ToParent {
+                    parent_id: f.block_id("to")?,
+                    index: f.optional_index("index")?,
+                },
+                [side] if f.obj.contains_key("index") => {
+                    return Err(format!("\"index\" cannot be combined with \"{}\"", side))
+                }
+                ["before"] => MoveTarget::Before {
+                    sibling_id: f.block_id("before")?,
+                },
+                ["after"] => MoveTarget::After {
+                    sibling_id: f.block_id("after")?,
+                },
+                [] => {
+                    return Err("missing target: set one of \"to\", \"before\" or \"after\"".into())
+                }
+                _ => {
+                    return Err(format!(
+                    "conflicting targets {}: set exactly one of \"to\", \"before\" or \"after\"",
+                    targets.join(", ")
+                ))
+                }
+            };
+            Command::Move(MoveCommand {
+                block_id: f.block_id("block")?,
+                target,
+            })
+        }
+        "delete" => {
+            let cascade = f.optional_bool("cascade")?.unwrap_or(false);
+            let preserve_children = f.optional_bool("preserve_children")?.unwrap_or(false);
+            if cascade && preserve_children {
+                return Err("\"cascade\" and \"preserve_children\" are mutually exclusive".into());
+            }
+            Command::Delete(DeleteCommand {
+                block_id: Some(f.block_id("block")?),
+                cascade,
+                preserve_children,
+                condition: None,
+            })
+        }
+        "link" => Command::Link(LinkCommand {
+            source_id: f.block_id("source")?,
+            additional_sources: Vec::new(),
+            edge_type: f.edge_type()?,
+            target_id: f.block_id("target")?,
+            weight: None,
+            valid_from: None,
+            valid_to: None,
+            metadata: HashMap::new(),
+        }),
+        "unlink" => Command::Unlink(UnlinkCommand {
+            source_id: f.block_id("source")?,
+            edge_type: f.edge_type()?,
+            target_id: f.block_id("target")?,
+        }),
+        "snapshot" => {
+            let name = || f.required_str("name").map(str::to_string);
+            Command::Snapshot(match f.required_str("action")? {
+                "create" => SnapshotCommand::Create {
+                    name: name()?,
+                    description: f.optional_str("description")?.map(str::to_string),
+                },
+                "restore" => SnapshotCommand::Restore { name: name()? },
+                "list" => SnapshotCommand::List,
+                "delete" => SnapshotCommand::Delete { name: name()? },
+                "diff" => SnapshotCommand::Diff {
+                    name1: name()?,
+                    name2: f.required_str("other")?.to_string(),
+                },
+                other => return Err(format!("unknown snapshot action \"{}\"", other)),
+            })
+        }
+        _ => {
+            let name = f.optional_str("name")?.map(str::to_string);
+            Command::Transaction(match op {
+                "begin" => TransactionCommand::Begin { name },
+                "commit" => TransactionCommand::Commit { name },
+                _ => TransactionCommand::Rollback { name },
+            })
+        }
+    })
+}
+
+/// Typed field access with descriptive errors
+struct Fields<'a> {
+    obj: &'a Map<String, Json>,
+}
+
+impl<'a> Fields<'a> {
+    fn required(&self, key: &str) -> Result<&'a Json, String> {
+        self.obj
+            .get(key)
+            .ok_or_else(|| format!("missing required field \"{}\"", key))
+    }
+
+    fn required_str(&self, key: &str) -> Result<&'a str, String> {
+        let v = self.required(key)?;
+        v.as_str()
+            .ok_or_else(|| format!("\"{}\" must be a string, got {}", key, type_name(v)))
+    }
+
+    fn optional_str(&self, key: &str) -> Result<Option<&'a str>, String> {
+        match self.obj.get(key) {
+            None | Some(Json::Null) => Ok(None),
+            Some(Json::String(s)) => Ok(Some(s)),
+            Some(v) => Err(format!(
+                "\"{}\" must be a string, got {}",
+                key,
+                type_name(v)
+            )),
+        }
+    }
+
+    fn optional_bool(&self, key: &str) -> Result<Option<bool>, String> {
+        match self.obj.get(key) {
+            None | Some(Json::Null) => Ok(None),
+            Some(Json::Bool(b)) => Ok(Some(*b)),
+            Some(v) => Err(format!(
+                "\"{}\" must be a boolean, got {}",
+                key,
+                type_name(v)
+            )),
+        }
+    }
+
+    fn optional_index(&self, key: &str) -> Result<Option<usize>, String> {
+        match self.obj.get(key) {
+            None | Some(Json::Null) => Ok(None),
+            Some(v) => v
+                .as_u64()
+                .map(|n| Some(n as usize))
+                .ok_or_else(|| format!("\"{}\" must be a non-negative integer", key)),
+        }
+    }
+
+    /// Block ids may be full ids or short numeric ids
+    fn block_id(&self, key: &str) -> Result<String, String> {
+        match self.required(key)? {
+            Json::String(s) if !s.trim().is_empty() => Ok(s.trim().to_string()),
+            Json::Number(n) if n.is_u64() => Ok(n.to_string()),
+            v => Err(format!(
+                "\"{}\" must be a block id (string or integer), got {}",
+                key,
+                type_name(v)
+            )),
+        }
+    }
+
+    fn edge_type(&self) -> Result<String, String> {
+        let edge_type = self.required_str("edge_type")?;
+        if edge_type.parse::<ucm_core::EdgeType>().is_err() {
+            return Err(format!("unknown edge_type \"{}\"", edge_type));
+        }
+        Ok(edge_type.to_string())
+    }
+}
+
+/// Parse a dotted path such as `content.text` or `metadata.tags[0]`
+fn parse_path(path: &str) -> Result<Path, String> {
+    if let Some(expr) = path.strip_prefix('$') {
+        return Ok(Path::new(vec![PathSegment::JsonPath(expr.to_string())]));
+    }
+
+    let mut segments = Vec::new();
+    for part in path.split('.') {
+        let (name, indices) = match part.find('[') {
+            Some(pos) => part.split_at(pos),
+            None => (part, ""),
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!("invalid path \"{}\"", path));
+        }
+        segments.push(PathSegment::Property(name.to_string()));
+
+        let mut rest = indices;
+        while !rest.is_empty() {
+            let index = rest
+                .strip_prefix('[')
+                .and_then(|r| r.split_once(']'))
+                .and_then(|(n, tail)| n.parse::<i64>().ok().map(|n| (n, tail)));
+            match index {
+                Some((n, tail)) => {
+                    segments.push(PathSegment::Index(n));
+                    rest = tail;
+                }
+                None => return Err(format!("invalid path \"{}\"", path)),
+            }
+        }
+    }
+    Ok(Path::new(segments))
+}
+
+/// Convert a JSON value, treating `{"$ref": id}` as a block reference
+fn json_to_value(value: &Json) -> Value {
+    match value {
+        Json::Null => Value::Null,
+        Json::Bool(b) => Value::Bool(*b),
+        Json::Number(n) => Value::Number(n.as_f64().unwrap_or_default()),
+        Json::String(s) => Value::String(s.clone()),
+        Json::Array(items) => Value::Array(items.iter().map(json_to_value).collect()),
+        Json::Object(obj) => match (obj.len(), obj.get("$ref")) {
+            (1, Some(Json::String(id))) => Value::BlockRef(id.clone()),
+            _ => Value::Object(
+                obj.iter()
+                    .map(|(k, v)| (k.clone(), json_to_value(v)))
+                    .collect(),
+            ),
+        },
+    }
+}
+
+fn type_name(value: &Json) -> &'static str {
+    match value {
+        Json::Null => "null",
+        Json::Bool(_) => "a boolean",
+        Json::Number(_) => "a number",
+        Json::String(_) => "a string",
+        Json::Array(_) => "an array",
+        Json::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_edit() {
+        let commands = parse_json_commands(&json!([
+            {"op": "edit", "block": "blk_000000000003", "path": "content.text", "value": "Hi"},
+            {"op": "edit", "block": 3, "path": "metadata.tags[0]", "value": "x", "operator": "append"},
+        ]))
+        .unwrap();
+
+        let Command::Edit(edit) = &commands[0] else {
+            panic!("expected EDIT");
+        };
+        assert_eq!(edit.block_id, "blk_000000000003");
+        assert_eq!(edit.path.to_string(), "content.text");
+        assert_eq!(edit.operator, Operator::Set);
+        assert_eq!(edit.value, Value::String("Hi".into()));
+
+        let Command::Edit(edit) = &commands[1] else {
+            panic!("expected EDIT");
+        };
+        assert_eq!(edit.block_id, "3");
+        assert_eq!(
+            edit.path.segments,
+            vec![
+                PathSegment::Property("metadata".into()),
+                PathSegment::Property("tags".into()),
+                PathSegment::Index(0),
+            ]
+        );
+        assert_eq!(edit.operator, Operator::Append);
+    }
+
+    #[test]
+    fn test_parse_structural_commands() {
+        let commands = parse_json_commands(&json!({"commands": [
+            {"op": "append", "parent": "blk_000000000001", "content_type": "code", "content": "x = 1", "label": "ex", "index": 0},
+            {"op": "move", "block": "blk_000000000002", "after": "blk_000000000003"},
+            {"op": "delete", "block": "blk_000000000004", "cascade": true},
+            {"op": "link", "source": "blk_000000000002", "edge_type": "references", "target": "blk_000000000003"},
+            {"op": "snapshot", "action": "create", "name": "v1"},
+            {"op": "atomic", "commands": [{"op": "begin"}, {"op": "commit", "name": "t"}]},
+        ]}))
+        .unwrap();
+        assert_eq!(commands.len(), 6);
+
+        let Command::Append(append) = &commands[0] else {
+            panic!("expected APPEND");
+        };
+        assert_eq!(append.content_type, ContentType::Code);
+        assert_eq!(append.index, Some(0));
+        assert_eq!(append.properties["label"], Value::String("ex".into()));
+
+        assert!(matches!(
+            &commands[1],
+            Command::Move(MoveCommand { target: MoveTarget::After { sibling_id }, .. })
+                if sibling_id == "blk_000000000003"
+        ));
+        assert!(matches!(&commands[2], Command::Delete(d) if d.cascade));
+        assert!(matches!(&commands[5], Command::Atomic(body) if body.len() == 2));
+    }
+
+    #[test]
+    fn test_single_object_is_accepted() {
+        let commands = parse_json_commands(&json!(
+            {"op": "delete", "block": "blk_000000000004"}
+        ))
+        .unwrap();
+        assert_eq!(commands.len(), 1);
+    }
+
+    #[test]
+    fn test_errors_are_reported_per_element() {
+        let err = parse_json_commands(&json!([
+            {"op": "edit", "block": "blk_000000000003", "path": "content.text", "value": "ok"},
+            {"op": "edit", "block": "blk_000000000003", "value": "no path"},
+            {"op": "move", "block": "blk_000000000002", "to": "blk_000000000001", "before": "blk_000000000003"},
+            {"op": "teleport"},
+            "EDIT blk_000000000003 SET text = \"x\"",
+            {"op": "delete", "block": "blk_000000000004", "force": true},
+            {"op": "atomic", "commands": [{"op": "link", "source": 1, "edge_type": "likes", "target": 2}]},
+        ]))
+        .unwrap_err();
+
+        let messages: Vec<String> = err.0.iter().map(|e| e.to_string()).collect();
+        assert_eq!(messages.len(), 6);
+        assert_eq!(messages[0], "command [1]: missing required field \"path\"");
+        assert!(messages[1].starts_with("command [2]: conflicting targets to, before"));
+        assert_eq!(messages[2], "command [3]: unknown op \"teleport\"");
+        assert_eq!(messages[3], "command [4]: expected an object, got a string");
+        assert_eq!(
+            messages[4],
+            "command [5]: unknown field(s) for \"delete\": force"
+        );
+        assert_eq!(messages[5], "command [6.0]: unknown edge_type \"likes\"");
+    }
+
+    #[test]
+    fn test_invalid_paths_are_rejected() {
+        for path in ["", "content..text", "tags[x]", "content text"] {
+            let result = parse_json_commands(&json!(
+                {"op": "edit", "block": "blk_000000000003", "path": path, "value": 1}
+            ));
+            assert!(result.is_err(), "{path:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_block_refs_are_preserved() {
+        let commands = parse_json_commands(&json!(
+            {"op": "edit", "block": 1, "path": "metadata.custom.see", "value": {"$ref": "blk_000000000002"}}
+        ))
+        .unwrap();
+        let Command::Edit(edit) = &commands[0] else {
+            panic!("expected EDIT");
+        };
+        assert_eq!(edit.value, Value::BlockRef("blk_000000000002".into()));
+    }
+}
@@ -10,6 +10,7 @@
 //!
 //! - [`UcpClient`] - Main entry point for document manipulation
 //! - [`CodeGraphBuildResult`] - Code analysis for repositories
+//! - [`parse_json_commands`] - JSON command form for structured-output models
 //!
 //! ## Example
 //!
@@ -25,23 +26,28 @@
 
 use std::str::FromStr;
 
+mod json_commands;
+mod short_ids;
+
+pub use json_commands::{parse_json_commands, JsonCommandError, JsonCommandErrors};
 use ucl_parser::{parse, parse_commands, UclDocument};
 pub use ucm_core::PortableDocument;
-use ucm_core::{Block, BlockId, Content, Document, EdgeType, Error, Result};
+use ucm_core::{Block, BlockId, Content, DiffFormat, Document, EdgeType, Error, Result};
 use ucm_engine::{Engine, Operation, OperationResult};
+use ucp_llm::IdMapper;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub use ucp_codegraph::{
     approximate_prompt_tokens, build_code_graph, build_code_graph_incremental,
-    canonical_codegraph_json, canonical_fingerprint, codegraph_prompt_projection,
+    canonical_codegraph_json, canonical_fingerprint, code_graph_stats, codegraph_prompt_projection,
     codegraph_prompt_projection_with_config, export_codegraph_context,
     export_codegraph_context_with_config, is_codegraph_document, render_codegraph_context_prompt,
-    resolve_codegraph_selector, validate_code_graph_profile, CodeGraphBuildInput,
-    CodeGraphBuildResult, CodeGraphBuildStatus, CodeGraphCoderef, CodeGraphContextEdgeExport,
-    CodeGraphContextExport, CodeGraphContextFrontierAction, CodeGraphContextHeuristics,
-    CodeGraphContextNodeExport, CodeGraphContextSession, CodeGraphContextSummary,
-    CodeGraphContextUpdate, CodeGraphDetailLevel, CodeGraphDiagnostic, CodeGraphExpandMode,
-    CodeGraphExportConfig, CodeGraphExportMode, CodeGraphExportOmissionDetail,
+    resolve_codegraph_selector, update_code_graph, validate_code_graph_profile,
+    CodeGraphBuildInput, CodeGraphBuildResult, CodeGraphBuildStatus, CodeGraphCoderef,
+    CodeGraphContextEdgeExport, CodeGraphContextExport, CodeGraphContextFrontierAction,
+    CodeGraphContextHeuristics, CodeGraphContextNodeExport, CodeGraphContextSession,
+    CodeGraphContextSummary, CodeGraphContextUpdate, CodeGraphDetailLevel, CodeGraphDiagnostic,
+    CodeGraphExpandMode, CodeGraphExportConfig, CodeGraphExportMode, CodeGraphExportOmissionDetail,
     CodeGraphExportOmissionExplanation, CodeGraphExportOmissionReason,
     CodeGraphExportOmissionReport, CodeGraphExtractorConfig, CodeGraphFindQuery,
     CodeGraphHiddenLevelSummary, CodeGraphIncrementalBuildInput, CodeGraphIncrementalStats,
@@ -53,9 +59,22 @@ pub use ucp_codegraph::{
     CodeGraphSelectionOriginKind, CodeGraphSelectorResolutionExplanation, CodeGraphSessionDiff,
     CodeGraphSessionEvent, CodeGraphSessionMutation, CodeGraphSessionMutationKind,
     CodeGraphSessionPersistenceMetadata, CodeGraphSeverity, CodeGraphStats,
-    CodeGraphTraversalConfig, CodeGraphValidationResult, HydratedSourceExcerpt,
-    CODEGRAPH_EXTRACTOR_VERSION, CODEGRAPH_PROFILE_MARKER, CODEGRAPH_PROFILE_VERSION,
+    CodeGraphTraversalConfig, CodeGraphUpdateInput, CodeGraphValidationResult,
+    HydratedSourceExcerpt, SymbolDepth, CODEGRAPH_EXTRACTOR_VERSION, CODEGRAPH_PROFILE_MARKER,
+    CODEGRAPH_PROFILE_VERSION,
 };
+
+/// Dependency queries over codegraph documents
+#[cfg(not(target_arch = "wasm32"))]
+pub mod codegraph {
+    pub use ucp_codegraph::{
+        dependencies_of, dependents_of, diff_code_graphs, find_dead_code, find_symbols,
+        CodeGraphChangeKind, CodeGraphDiff, CodeGraphDiffEdge, CodeGraphDiffNode,
+        CodeGraphDiffSummary, CodeGraphFileRename, CodeGraphNodeChange, CodeGraphStore,
+        ImpactEntry, SymbolHit, SymbolQuery,
+    };
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub use ucp_graph::{
     GraphDetailLevel, GraphExport, GraphExportEdge, GraphExportNode, GraphFindQuery,
@@ -65,6 +84,15 @@ pub use ucp_graph::{
     GraphStoreObservability, GraphStoreStats, InMemoryGraphStore, SqliteGraphStore,
 };
 
+/// Outcome of [`UcpClient::execute_ucl_with_mapper`]
+#[derive(Debug, Clone)]
+pub struct MappedExecution {
+    /// Per-operation results, with full block ids
+    pub results: Vec<OperationResult>,
+    /// Short ids registered for blocks created by the commands, in creation order
+    pub new_ids: Vec<(u32, BlockId)>,
+}
+
 /// UCP client for document manipulation
 pub struct UcpClient {
     engine: Engine,
@@ -85,8 +113,78 @@ impl UcpClient {
 
     /// Execute UCL commands on a document
     pub fn execute_ucl(&self, doc: &mut Document, ucl: &str) -> Result<Vec<OperationResult>> {
+        let ops = self.ucl_to_operations(ucl)?;
+        self.engine.execute_batch(doc, ops)
+    }
+
+    /// Parse UCL commands into engine operations without executing them
+    pub fn ucl_to_operations(&self, ucl: &str) -> Result<Vec<Operation>> {
         let commands =
             parse_commands(ucl).map_err(|e| Error::Internal(format!("Parse error: {}", e)))?;
+        self.commands_to_operations(commands)
+    }
+
+    /// Describe what UCL commands would do to `doc`, one line per
+    /// operation, without executing them. `EXPLAIN` prefixes are accepted.
+    pub fn explain(&self, doc: &Document, ucl: &str) -> Result<String> {
+        self.explain_with(&self.engine, doc, ucl)
+    }
+
+    /// Like [`UcpClient::explain`], checking snapshots against `engine`
+    pub fn explain_with(&self, engine: &Engine, doc: &Document, ucl: &str) -> Result<String> {
+        let commands =
+            parse_commands(ucl).map_err(|e| Error::Internal(format!("Parse error: {}", e)))?;
+        let mut lines = Vec::new();
+        for mut cmd in commands {
+            while let ucl_parser::Command::Explain(e) = cmd {
+                cmd = *e.inner;
+            }
+            let keyword = command_keyword(&cmd);
+            let ops = self.commands_to_operations(vec![cmd])?;
+            if ops.is_empty() {
+                lines.push(format!("{} would not change the document", keyword));
+            }
+            lines.extend(ops.iter().map(|op| engine.explain(op, doc)));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// Execute UCL that may use short ids from an [`IdMapper`] prompt.
+    ///
+    /// Every block-id position in the parsed commands is resolved through the
+    /// mapper (full ids pass through unchanged). Blocks created by the
+    /// commands are registered in the mapper so follow-up prompts can refer
+    /// to them by short id.
+    pub fn execute_ucl_with_mapper(
+        &self,
+        doc: &mut Document,
+        ucl: &str,
+        mapper: &mut IdMapper,
+    ) -> Result<MappedExecution> {
+        let mut commands =
+            parse_commands(ucl).map_err(|e| Error::Internal(format!("Parse error: {}", e)))?;
+        short_ids::resolve_block_ids(&mut commands, mapper)?;
+
+        let ops = self.commands_to_operations(commands)?;
+        let results = self.engine.execute_batch(doc, ops)?;
+
+        let mut new_ids = Vec::new();
+        for id in results.iter().flat_map(|r| &r.affected_blocks) {
+            if mapper.to_short_id(id).is_none() && doc.get_block(id).is_some() {
+                new_ids.push((mapper.register(id), *id));
+            }
+        }
+        Ok(MappedExecution { results, new_ids })
+    }
+
+    /// Execute JSON commands (see [`parse_json_commands`]) on a document
+    pub fn execute_json_commands(
+        &self,
+        doc: &mut Document,
+        commands: &serde_json::Value,
+    ) -> Result<Vec<OperationResult>> {
+        let commands = parse_json_commands(commands)
+            .map_err(|e| Error::Internal(format!("Invalid JSON commands: {}", e)))?;
 
         let ops = self.commands_to_operations(commands)?;
         self.engine.execute_batch(doc, ops)
@@ -159,6 +257,27 @@ impl UcpClient {
                     let content = match a.content_type {
                         ucl_parser::ContentType::Text => Content::text(&a.content),
                         ucl_parser::ContentType::Code => Content::code("", &a.content),
+                        ucl_parser::ContentType::Form => {
+                            Content::Form(serde_json::from_str(&a.content).map_err(|e| {
+                                Error::Validation(format!("Invalid form content: {}", e))
+                            })?)
+                        }
+                        ucl_parser::ContentType::Diff => {
+                            let format = match a.properties.get("format") {
+                                None => DiffFormat::Unified,
+                                Some(ucl_parser::Value::String(s)) => DiffFormat::parse(s)
+                                    .ok_or_else(|| {
+                                        Error::Validation(format!("Unknown diff format: {}", s))
+                                    })?,
+                                Some(other) => {
+                                    return Err(Error::Validation(format!(
+                                        "Invalid diff format: {:?}",
+                                        other
+                                    )))
+                                }
+                            };
+                            Content::diff(format, &a.content)
+                        }
                         _ => Content::text(&a.content),
                     };
                     ops.push(Operation::Append {
@@ -249,12 +368,29 @@ impl UcpClient {
                         .map_err(|_| Error::InvalidBlockId(l.target_id.clone()))?;
                     let edge_type =
                         EdgeType::from_str(&l.edge_type).unwrap_or(EdgeType::References);
-                    ops.push(Operation::Link {
-                        source,
-                        edge_type,
-                        target,
-                        metadata: None,
-                    });
+                    if l.additional_sources.is_empty() {
+                        ops.push(Operation::Link {
+                            source,
+                            edge_type,
+                            target,
+                            metadata: None,
+                            weight: l.weight,
+                            valid_from: l.valid_from,
+                            valid_to: l.valid_to,
+                        });
+                    } else {
+                        let mut sources = vec![source];
+                        for id in &l.additional_sources {
+                            sources
+                                .push(id.parse().map_err(|_| Error::InvalidBlockId(id.clone()))?);
+                        }
+                        ops.push(Operation::HyperLink {
+                            sources,
+                            edge_type,
+                            target,
+                            metadata: None,
+                        });
+                    }
                 }
                 ucl_parser::Command::Snapshot(s) => match s {
                     ucl_parser::SnapshotCommand::Create { name, description } => {
@@ -265,6 +401,8 @@ impl UcpClient {
                     }
                     _ => {}
                 },
+                // Dry run: described by `explain`, never executed
+                ucl_parser::Command::Explain(_) => {}
                 _ => {} // Other commands
             }
         }
@@ -272,6 +410,34 @@ impl UcpClient {
     }
 }
 
+fn command_keyword(cmd: &ucl_parser::Command) -> &'static str {
+    use ucl_parser::Command::*;
+    match cmd {
+        Edit(_) => "EDIT",
+        Move(_) => "MOVE",
+        Append(_) => "APPEND",
+        Delete(_) => "DELETE",
+        Prune(_) => "PRUNE",
+        Fold(_) => "FOLD",
+        Link(_) => "LINK",
+        Unlink(_) => "UNLINK",
+        Snapshot(_) => "SNAPSHOT",
+        Transaction(_) => "Transaction control",
+        Atomic(_) => "ATOMIC",
+        WriteSection(_) => "WRITE_SECTION",
+        Explain(_) => "EXPLAIN",
+        Goto(_) => "GOTO",
+        Back(_) => "BACK",
+        Expand(_) => "EXPAND",
+        Follow(_) => "FOLLOW",
+        Path(_) => "PATH",
+        Search(_) => "SEARCH",
+        Find(_) => "FIND",
+        View(_) => "VIEW",
+        Context(_) => "CTX",
+    }
+}
+
 impl Default for UcpClient {
     fn default() -> Self {
         Self::new()
@@ -301,4 +467,166 @@ mod tests {
         assert_eq!(doc.block_count(), 2);
         assert!(doc.get_block(&id).is_some());
     }
+
+    #[test]
+    fn test_execute_ucl_with_mapper() {
+        let client = UcpClient::new();
+        let mut doc = client.create_document();
+        let root = doc.root;
+        let intro = client.add_text(&mut doc, &root, "Intro", None).unwrap();
+        let mut mapper = IdMapper::from_document(&doc);
+        let intro_short = mapper.to_short_id(&intro).unwrap();
+        let next_short = mapper.len() as u32 + 1;
+
+        let ucl = format!(
+            "EDIT {} SET content.text = \"Hello\"\nAPPEND {} text :: \"Body\"",
+            intro_short, intro_short
+        );
+        let execution = client
+            .execute_ucl_with_mapper(&mut doc, &ucl, &mut mapper)
+            .unwrap();
+
+        assert!(execution.results.iter().all(|r| r.success));
+        assert_eq!(execution.new_ids.len(), 1);
+        let (short, created) = execution.new_ids[0];
+        assert_eq!(short, next_short);
+        assert_eq!(mapper.to_block_id(short), Some(&created));
+        assert_eq!(doc.children(&intro), &[created]);
+
+        let err = client
+            .execute_ucl_with_mapper(&mut doc, "DELETE 99", &mut mapper)
+            .unwrap_err();
+        assert!(err.to_string().contains("short id 99"));
+    }
+
+    #[test]
+    fn test_explain_does_not_execute() {
+        let client = UcpClient::new();
+        let mut doc = client.create_document();
+        let root = doc.root;
+        let intro = client.add_text(&mut doc, &root, "Intro", None).unwrap();
+
+        let ucl = format!(
+            "EXPLAIN APPEND {} text WITH label=\"Introduction\" :: \"Hi\"\nGOTO {}",
+            intro, intro
+        );
+        let explanation = client.explain(&doc, &ucl).unwrap();
+        assert_eq!(
+            explanation,
+            format!(
+                "Would append a new Text block to {} (text) with label 'Introduction'\n\
+                 GOTO would not change the document",
+                intro
+            )
+        );
+        assert!(client.execute_ucl(&mut doc, &ucl).unwrap().is_empty());
+        assert!(doc.children(&intro).is_empty());
+    }
+
+    #[test]
+    fn test_append_form_from_ucl() {
+        let client = UcpClient::new();
+        let mut doc = client.create_document();
+        let ucl = format!(
+            r#"APPEND {} form WITH label = "Survey" :: {{"title": "Feedback", "fields": [{{"id": "score", "label": "Score", "field_type": {{"select": ["1", "2", "3"]}}, "required": true}}]}}"#,
+            doc.root
+        );
+        let results = client.execute_ucl(&mut doc, &ucl).unwrap();
+        assert!(results.iter().all(|r| r.success));
+
+        let block = doc.get_block(&doc.children(&doc.root)[0]).unwrap();
+        assert_eq!(block.metadata.label.as_deref(), Some("Survey"));
+        let Content::Form(form) = &block.content else {
+            panic!("expected form, got {:?}", block.content);
+        };
+        assert_eq!(form.title, "Feedback");
+        let score = form.field("score").unwrap();
+        assert!(score.required);
+        assert_eq!(score.field_type.options(), ["1", "2", "3"]);
+
+        let bad = format!(r#"APPEND {} form :: {{"fields": []}}"#, doc.root);
+        assert!(client.execute_ucl(&mut doc, &bad).is_err());
+    }
+
+    #[test]
+    fn test_append_diff_from_ucl() {
+        let client = UcpClient::new();
+        let mut doc = client.create_document();
+        let ucl = format!(
+            r#"APPEND {} diff WITH format = "json_patch" :: '[{{"op": "remove", "path": "/a"}}]'"#,
+            doc.root
+        );
+        let results = client.execute_ucl(&mut doc, &ucl).unwrap();
+        assert!(results.iter().all(|r| r.success));
+
+        let block = doc.get_block(&doc.children(&doc.root)[0]).unwrap();
+        let Content::Diff(diff) = &block.content else {
+            panic!("expected diff, got {:?}", block.content);
+        };
+        assert_eq!(diff.format, DiffFormat::JsonPatch);
+        assert_eq!(diff.patch, r#"[{"op": "remove", "path": "/a"}]"#);
+
+        let bad = format!(r#"APPEND {} diff WITH format = "svn" :: "x""#, doc.root);
+        assert!(client.execute_ucl(&mut doc, &bad).is_err());
+    }
+
+    #[test]
+    fn test_link_weight_from_ucl() {
+        let client = UcpClient::new();
+        let mut doc = client.create_document();
+        let root = doc.root;
+        let a = client.add_text(&mut doc, &root, "a", None).unwrap();
+        let b = client.add_text(&mut doc, &root, "b", None).unwrap();
+        client
+            .execute_ucl(&mut doc, &format!("LINK {} references {} WEIGHT 0.5", a, b))
+            .unwrap();
+
+        let edge = &doc.get_block(&a).unwrap().edges[0];
+        assert_eq!(edge.target, b);
+        assert_eq!(edge.weight, Some(0.5));
+    }
+
+    #[test]
+    fn test_multi_source_link_from_ucl() {
+        let client = UcpClient::new();
+        let mut doc = client.create_document();
+        let root = doc.root;
+        let a = client.add_text(&mut doc, &root, "a", None).unwrap();
+        let b = client.add_text(&mut doc, &root, "b", None).unwrap();
+        let c = client.add_text(&mut doc, &root, "c", None).unwrap();
+        client
+            .execute_ucl(&mut doc, &format!("LINK [{}, {}] references {}", a, b, c))
+            .unwrap();
+
+        assert_eq!(doc.hyperedges.len(), 1);
+        assert_eq!(doc.hyperedges[0].sources, vec![a, b]);
+        assert_eq!(doc.hyperedges[0].target, c);
+        assert!(doc.get_block(&a).unwrap().edges.is_empty());
+    }
+
+    #[test]
+    fn test_execute_json_commands() {
+        let client = UcpClient::new();
+        let mut doc = client.create_document();
+        let root = doc.root;
+        let id = client.add_text(&mut doc, &root, "Old", None).unwrap();
+
+        let commands = serde_json::json!([
+            {"op": "edit", "block": id.to_string(), "path": "content.text", "value": "New"},
+            {"op": "append", "parent": root.to_string(), "content": "Second"},
+        ]);
+        client.execute_json_commands(&mut doc, &commands).unwrap();
+
+        assert_eq!(doc.block_count(), 3);
+        match &doc.get_block(&id).unwrap().content {
+            Content::Text(text) => assert_eq!(text.text, "New"),
+            other => panic!("unexpected content: {:?}", other),
+        }
+
+        let invalid = serde_json::json!([{"op": "edit", "block": id.to_string()}]);
+        let err = client
+            .execute_json_commands(&mut doc, &invalid)
+            .unwrap_err();
+        assert!(err.to_string().contains("command [0]"));
+    }
 }
@@ -0,0 +1,186 @@
+//! Short id resolution for parsed commands.
+//!
+//! Prompts rendered through an [`IdMapper`] show blocks as `1`, `2`, ...
+//! Instead of string-replacing ids in the model's UCL, the parsed commands
+//! are rewritten position by position, so an id can never be missed or
+//! substituted inside a string literal.
+
+use ucl_parser::{Command, ContextAddTarget, ContextCommand, MoveTarget, Value, ViewTarget};
+use ucm_core::{BlockId, Error, Result};
+use ucp_llm::IdMapper;
+
+/// Rewrite every block-id position in `commands` to a full block id.
+///
+/// Both short ids and full ids are accepted. Unknown short ids are an error.
+pub(crate) fn resolve_block_ids(commands: &mut [Command], mapper: &IdMapper) -> Result<()> {
+    let resolver = Resolver { mapper };
+    commands
+        .iter_mut()
+        .try_for_each(|cmd| resolver.command(cmd))
+}
+
+struct Resolver<'a> {
+    mapper: &'a IdMapper,
+}
+
+impl Resolver<'_> {
+    fn id(&self, id: &mut String) -> Result<()> {
+        if id.parse::<BlockId>().is_ok() {
+            return Ok(());
+        }
+        let short: u32 = id.parse().map_err(|_| Error::InvalidBlockId(id.clone()))?;
+        let block_id = self
+            .mapper
+            .to_block_id(short)
+            .ok_or_else(|| Error::BlockNotFound(format!("short id {}", short)))?;
+        *id = block_id.to_string();
+        Ok(())
+    }
+
+    fn opt(&self, id: &mut Option<String>) -> Result<()> {
+        id.as_mut().map_or(Ok(()), |id| self.id(id))
+    }
+
+    fn value(&self, value: &mut Value) -> Result<()> {
+        match value {
+            Value::BlockRef(id) => self.id(id),
+            Value::Array(items) => items.iter_mut().try_for_each(|v| self.value(v)),
+            Value::Object(map) => map.values_mut().try_for_each(|v| self.value(v)),
+            _ => Ok(()),
+        }
+    }
+
+    fn command(&self, cmd: &mut Command) -> Result<()> {
+        match cmd {
+            Command::Edit(e) => {
+                self.id(&mut e.block_id)?;
+                self.value(&mut e.value)
+            }
+            Command::Move(m) => {
+                self.id(&mut m.block_id)?;
+                match &mut m.target {
+                    MoveTarget::ToParent { parent_id, .. } => self.id(parent_id),
+                    MoveTarget::Before { sibling_id } | MoveTarget::After { sibling_id } => {
+                        self.id(sibling_id)
+                    }
+                }
+            }
+            Command::Append(a) => {
+                self.id(&mut a.parent_id)?;
+                a.properties.values_mut().try_for_each(|v| self.value(v))
+            }
+            Command::Delete(d) => self.opt(&mut d.block_id),
+            Command::Fold(f) => self.id(&mut f.block_id),
+            Command::Link(l) => {
+                self.id(&mut l.source_id)?;
+                l.additional_sources
+                    .iter_mut()
+                    .try_for_each(|s| self.id(s))?;
+                self.id(&mut l.target_id)?;
+                l.metadata.values_mut().try_for_each(|v| self.value(v))
+            }
+            Command::Unlink(u) => {
+                self.id(&mut u.source_id)?;
+                self.id(&mut u.target_id)
+            }
+            Command::Atomic(body) => body.iter_mut().try_for_each(|c| self.command(c)),
+            Command::Explain(e) => self.command(&mut e.inner),
+            Command::WriteSection(w) => self.id(&mut w.section_id),
+            Command::Goto(g) => self.id(&mut g.block_id),
+            Command::Expand(e) => self.id(&mut e.block_id),
+            Command::Follow(f) => {
+                self.id(&mut f.source_id)?;
+                self.opt(&mut f.target_id)
+            }
+            Command::Path(p) => {
+                self.id(&mut p.from_id)?;
+                self.id(&mut p.to_id)
+            }
+            Command::View(v) => match &mut v.target {
+                ViewTarget::Block(id) => self.id(id),
+                ViewTarget::Neighborhood => Ok(()),
+            },
+            Command::Context(c) => match c {
+                ContextCommand::Add(add) => match &mut add.target {
+                    ContextAddTarget::Block(id) => self.id(id),
+                    ContextAddTarget::Children { parent_id } => self.id(parent_id),
+                    ContextAddTarget::Path { from_id, to_id } => {
+                        self.id(from_id)?;
+                        self.id(to_id)
+                    }
+                    ContextAddTarget::Results => Ok(()),
+                },
+                ContextCommand::Remove { block_id } => self.id(block_id),
+                ContextCommand::Focus { block_id } => self.opt(block_id),
+                _ => Ok(()),
+            },
+            Command::Prune(_)
+            | Command::Snapshot(_)
+            | Command::Transaction(_)
+            | Command::Back(_)
+            | Command::Search(_)
+            | Command::Find(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ucl_parser::parse_commands;
+    use ucm_core::{Block, Content, Document};
+
+    fn mapped_doc() -> (Document, IdMapper, BlockId) {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let intro = doc
+            .add_block(Block::new(Content::text("Intro"), None), &root)
+            .unwrap();
+        let mapper = IdMapper::from_document(&doc);
+        (doc, mapper, intro)
+    }
+
+    #[test]
+    fn test_resolves_short_and_full_ids() {
+        let (doc, mapper, intro) = mapped_doc();
+        let root_short = mapper.to_short_id(&doc.root).unwrap();
+        let intro_short = mapper.to_short_id(&intro).unwrap();
+
+        let ucl = format!(
+            "MOVE {} TO {}\nLINK {} references {}\nEDIT {} SET content.text = \"see 1\"",
+            intro_short, root_short, intro, root_short, intro_short
+        );
+        let mut commands = parse_commands(&ucl).unwrap();
+        resolve_block_ids(&mut commands, &mapper).unwrap();
+
+        let Command::Move(m) = &commands[0] else {
+            panic!("expected MOVE");
+        };
+        assert_eq!(m.block_id, intro.to_string());
+        assert_eq!(
+            m.target,
+            MoveTarget::ToParent {
+                parent_id: doc.root.to_string(),
+                index: None
+            }
+        );
+        let Command::Link(l) = &commands[1] else {
+            panic!("expected LINK");
+        };
+        assert_eq!(l.source_id, intro.to_string());
+        assert_eq!(l.target_id, doc.root.to_string());
+        // String literals are never rewritten
+        let Command::Edit(e) = &commands[2] else {
+            panic!("expected EDIT");
+        };
+        assert_eq!(e.value, Value::String("see 1".into()));
+    }
+
+    #[test]
+    fn test_unknown_short_id_is_reported() {
+        let (_, mapper, _) = mapped_doc();
+        let mut commands = parse_commands("DELETE 42").unwrap();
+        let err = resolve_block_ids(&mut commands, &mapper).unwrap_err();
+        assert_eq!(err.to_string(), "Block not found: short id 42");
+    }
+}
@@ -1,11 +1,14 @@
 //! CLI definition and command dispatch
 
 use clap::{Parser, Subcommand};
+use clap_complete::Shell;
+use std::path::PathBuf;
 
 use crate::commands::{
-    agent, block, codegraph, document, edge, export, find, import, llm, nav, prune, snapshot, tree,
-    tx, ucl, validate,
+    agent, agent_shell, block, codegraph, completions, diff, document, edge, export, find, import,
+    llm, merge, nav, prune, repl, snapshot, tree, tx, ucl, validate,
 };
+pub use crate::output::OutputFormat;
 
 /// UCP - Unified Content Protocol CLI
 ///
@@ -31,13 +34,6 @@ pub struct Cli {
     pub command: Commands,
 }
 
-#[derive(Clone, Copy, Debug, Default, clap::ValueEnum)]
-pub enum OutputFormat {
-    #[default]
-    Text,
-    Json,
-}
-
 #[derive(Subcommand)]
 pub enum Commands {
     // ===== Document Management =====
@@ -72,6 +68,14 @@ pub enum Commands {
         /// Maximum nesting depth
         #[arg(long)]
         max_depth: Option<usize>,
+
+        /// Warn about code blocks that fail to parse in their language
+        #[arg(long)]
+        check_code: bool,
+
+        /// Also check the document against a document schema (JSON file)
+        #[arg(long)]
+        schema: Option<String>,
     },
 
     // ===== Block Operations =====
@@ -132,6 +136,10 @@ pub enum Commands {
         /// Show block IDs
         #[arg(long)]
         ids: bool,
+
+        /// Print document statistics after the tree
+        #[arg(long)]
+        stats: bool,
     },
 
     /// Prune orphaned or tagged blocks
@@ -168,15 +176,72 @@ pub enum Commands {
     #[command(subcommand)]
     Export(ExportCommands),
 
+    /// Compare two documents, or two snapshots with --snapshot
+    Diff {
+        /// Older document file (or snapshot name)
+        from: String,
+
+        /// Newer document file (or snapshot name)
+        to: String,
+
+        /// Treat FROM and TO as snapshot names in the --input document
+        #[arg(long)]
+        snapshot: bool,
+
+        /// Document holding the snapshots
+        #[arg(short, long, visible_alias = "doc", requires = "snapshot")]
+        input: Option<String>,
+    },
+
+    /// Three-way merge of two documents edited from a common base
+    Merge {
+        /// Common ancestor document file
+        base: String,
+
+        /// Our edited document file
+        ours: String,
+
+        /// Their edited document file
+        theirs: String,
+
+        /// Output file (prints to stdout if omitted)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
     // ===== UCL Execution =====
     /// UCL (Unified Content Language) operations
     #[command(subcommand)]
     Ucl(UclCommands),
 
+    /// Interactive session running UCL commands line by line
+    Repl {
+        /// Document to open, JSON or Markdown (starts with an empty document
+        /// if not specified)
+        #[arg(short, long, visible_alias = "doc")]
+        input: Option<String>,
+    },
+
+    /// Print a shell completion script
+    Completions {
+        /// Shell to generate for
+        shell: Shell,
+
+        /// Write the script into this directory instead of stdout
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+
+    /// List snapshot names for shell completion
+    #[command(name = "__complete-snapshots", hide = true)]
+    CompleteSnapshots {
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+
     // ===== Agent Traversal =====
     /// Agent traversal operations
-    #[command(subcommand)]
-    Agent(AgentCommands),
+    Agent(AgentArgs),
 
     // ===== LLM Integration =====
     /// LLM integration utilities
@@ -232,6 +297,45 @@ pub enum BlockCommands {
         tags: Option<String>,
     },
 
+    /// Add a table block from a CSV or TSV file
+    AddTable {
+        /// Input file path
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Parent block ID (uses root if not specified)
+        #[arg(short, long)]
+        parent: Option<String>,
+
+        /// CSV file to import
+        #[arg(long)]
+        from_csv: String,
+
+        /// Field delimiter (sniffed from comma, tab and semicolon if not specified)
+        #[arg(long)]
+        delimiter: Option<char>,
+
+        /// Treat the first row as a header
+        #[arg(long, conflicts_with = "no_header")]
+        header: bool,
+
+        /// Treat the first row as data
+        #[arg(long)]
+        no_header: bool,
+
+        /// Maximum number of rows to import
+        #[arg(long, default_value = "10000")]
+        max_rows: usize,
+
+        /// Block label
+        #[arg(long)]
+        label: Option<String>,
+    },
+
     /// Get a block by ID
     Get {
         /// Input file path
@@ -379,6 +483,10 @@ pub enum EdgeCommands {
         /// Confidence score (0.0 - 1.0)
         #[arg(long)]
         confidence: Option<f64>,
+
+        /// Weight used by weighted path finding
+        #[arg(long)]
+        weight: Option<f64>,
     },
 
     /// Remove an edge
@@ -609,6 +717,19 @@ pub enum ImportCommands {
         /// Output UCP document file
         #[arg(short, long)]
         output: Option<String>,
+
+        /// Merge into an existing UCP document, keeping ids of matching blocks
+        #[arg(long)]
+        merge: Option<String>,
+
+        /// Re-import on every save, merging into the output document
+        #[arg(long, requires = "output")]
+        watch: bool,
+
+        /// Minimum word overlap (0.0-1.0) for an edited block to keep its id
+        /// when merging or watching
+        #[arg(long, default_value = "0.5")]
+        similarity: f64,
     },
 
     /// Import from HTML
@@ -628,6 +749,16 @@ pub enum ImportCommands {
         #[arg(long)]
         extract_links: bool,
     },
+
+    /// Import from portable JSON (as written by `export json`)
+    Json {
+        /// Input JSON file
+        file: String,
+
+        /// Output UCP document file
+        #[arg(short, long)]
+        output: Option<String>,
+    },
 }
 
 // ===== Export Subcommands =====
@@ -643,6 +774,10 @@ pub enum ExportCommands {
         /// Output Markdown file
         #[arg(short, long)]
         output: Option<String>,
+
+        /// Export only this block and its descendants, top heading as H1
+        #[arg(long)]
+        block: Option<String>,
     },
 
     /// Export to JSON
@@ -659,6 +794,40 @@ pub enum ExportCommands {
         #[arg(long)]
         pretty: bool,
     },
+
+    /// Export to Word (.docx)
+    Docx {
+        /// Input UCP file
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Output .docx file
+        #[arg(short, long)]
+        output: String,
+
+        /// Directory for resolving relative image paths (defaults to the input file's directory)
+        #[arg(long)]
+        base_dir: Option<String>,
+    },
+
+    /// Export the short-id LLM prompt projection
+    Prompt {
+        /// Input UCP file
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Output text file
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Export only this block and its descendants
+        #[arg(long)]
+        section: Option<String>,
+
+        /// PromptBuilderConfig JSON file; its system prompt is written first
+        #[arg(long)]
+        config: Option<String>,
+    },
 }
 
 // ===== UCL Subcommands =====
@@ -682,6 +851,18 @@ pub enum UclCommands {
         /// UCL file to execute
         #[arg(short = 'F', long = "file")]
         file: Option<String>,
+
+        /// Re-run the file against the input document whenever it changes
+        #[arg(long, requires = "file", conflicts_with = "commands")]
+        watch: bool,
+
+        /// Reset to this snapshot before each run instead of the saved document
+        #[arg(long, requires = "watch")]
+        base_snapshot: Option<String>,
+
+        /// Report what the commands would do without changing the document
+        #[arg(long, conflicts_with_all = ["watch", "output"])]
+        dry_run: bool,
     },
 
     /// Parse and validate UCL without executing
@@ -698,6 +879,17 @@ pub enum UclCommands {
 
 // ===== Agent Subcommands =====
 
+#[derive(clap::Args)]
+#[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
+pub struct AgentArgs {
+    /// Open an interactive traversal session on this document
+    #[arg(long)]
+    pub doc: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<AgentCommands>,
+}
+
 #[derive(Subcommand)]
 pub enum AgentCommands {
     /// Session management
@@ -1088,6 +1280,7 @@ pub enum LlmCommands {
 #[derive(Subcommand)]
 pub enum CodegraphCommands {
     /// Build a CodeGraphProfile v1 document from a repository
+    #[command(visible_alias = "extract")]
     Build {
         /// Repository root path
         repo: String,
@@ -1131,9 +1324,14 @@ pub enum CodegraphCommands {
         /// Explicit path for the incremental state file
         #[arg(long)]
         state_file: Option<String>,
+
+        /// Also write a binary store that `impact` and `find` can open without loading content
+        #[arg(long)]
+        store: Option<String>,
     },
 
     /// Inspect and validate an existing CodeGraph document
+    #[command(visible_alias = "validate")]
     Inspect {
         /// Input document path (reads stdin if omitted)
         #[arg(short, long)]
@@ -1151,6 +1349,85 @@ pub enum CodegraphCommands {
         output: Option<String>,
     },
 
+    /// Show the files that transitively depend on a path
+    Impact {
+        /// Repository-relative file path
+        path: String,
+
+        /// Input document path (reads stdin if omitted)
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Show what the path depends on instead
+        #[arg(long)]
+        dependencies: bool,
+
+        /// Maximum number of hops to follow
+        #[arg(long)]
+        max_depth: Option<usize>,
+    },
+
+    /// Find symbols by name, kind, language or path
+    Find {
+        /// Substring of the symbol name
+        query: String,
+
+        /// Input document path (reads stdin if omitted)
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Only symbols of this kind (e.g. function, struct, class)
+        #[arg(long)]
+        kind: Option<String>,
+
+        /// Only symbols in this language (e.g. rust, python)
+        #[arg(long)]
+        language: Option<String>,
+
+        /// Only exported symbols
+        #[arg(long)]
+        exported: bool,
+
+        /// Only symbols in files under this path
+        #[arg(long)]
+        path: Option<String>,
+
+        /// Also match names that are close to the query, e.g. with typos
+        #[arg(long)]
+        fuzzy: bool,
+
+        /// Maximum number of results
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Compare two CodeGraph documents, e.g. built at different commits
+    Diff {
+        /// Old document path
+        old: String,
+
+        /// New document path
+        new: String,
+    },
+
+    /// Show node and edge counts for a CodeGraph document
+    Stats {
+        /// Input document path (reads stdin if omitted)
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+
+    /// List symbols that nothing in the graph references
+    DeadCode {
+        /// Input document path (reads stdin if omitted)
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Also report exported symbols
+        #[arg(long)]
+        include_exported: bool,
+    },
+
     /// Stateful codegraph working-set context operations
     #[command(subcommand)]
     Context(CodegraphContextCommands),
@@ -1549,7 +1826,16 @@ impl Cli {
                 input,
                 max_blocks,
                 max_depth,
-            } => validate::validate(input, max_blocks, max_depth, self.format),
+                check_code,
+                schema,
+            } => validate::validate(
+                input,
+                max_blocks,
+                max_depth,
+                check_code,
+                schema,
+                self.format,
+            ),
 
             // Block Operations
             Commands::Block(cmd) => block::handle(cmd, self.format),
@@ -1567,7 +1853,12 @@ impl Cli {
                 limit,
             } => find::find(input, role, tag, pattern, limit, self.format),
             Commands::Orphans { input } => find::orphans(input, self.format),
-            Commands::Tree { input, depth, ids } => tree::tree(input, depth, ids, self.format),
+            Commands::Tree {
+                input,
+                depth,
+                ids,
+                stats,
+            } => tree::tree(input, depth, ids, stats, self.format),
             Commands::Prune { input, output, tag } => prune::prune(input, output, tag, self.format),
 
             // Transactions
@@ -1575,6 +1866,18 @@ impl Cli {
 
             // Snapshots
             Commands::Snapshot(cmd) => snapshot::handle(cmd, self.format),
+            Commands::Diff {
+                from,
+                to,
+                snapshot,
+                input,
+            } => diff::diff(from, to, snapshot, input, self.format),
+            Commands::Merge {
+                base,
+                ours,
+                theirs,
+                output,
+            } => merge::merge(base, ours, theirs, output, self.format),
 
             // Import/Export
             Commands::Import(cmd) => import::handle(cmd, self.format),
@@ -1582,9 +1885,16 @@ impl Cli {
 
             // UCL
             Commands::Ucl(cmd) => ucl::handle(cmd, self.format),
+            Commands::Repl { input } => repl::repl(input, self.format),
+            Commands::Completions { shell, dir } => completions::generate(shell, dir),
+            Commands::CompleteSnapshots { input } => completions::snapshot_names(input),
 
             // Agent
-            Commands::Agent(cmd) => agent::handle(cmd, self.format),
+            Commands::Agent(AgentArgs { doc, command }) => match (command, doc) {
+                (Some(cmd), _) => agent::handle(cmd, self.format),
+                (None, Some(doc)) => agent_shell::run(doc, self.format),
+                (None, None) => Err(anyhow::anyhow!("Pass --doc <file> or a subcommand")),
+            },
 
             // LLM
             Commands::Llm(cmd) => llm::handle(cmd, self.format),
@@ -1091,7 +1091,7 @@ fn resolve_selectors(doc: &ucm_core::Document, selectors: &str) -> Result<Vec<Bl
         .collect()
 }
 
-fn resolve_selector(doc: &ucm_core::Document, selector: &str) -> Result<BlockId> {
+pub(super) fn resolve_selector(doc: &ucm_core::Document, selector: &str) -> Result<BlockId> {
     BlockId::from_str(selector)
         .ok()
         .or_else(|| {
@@ -0,0 +1,416 @@
+//! Interactive agent traversal session
+
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use dialoguer::{BasicHistory, Input};
+use std::io::{BufRead, IsTerminal};
+use ucm_core::{BlockId, Document};
+use ucp_agent::{
+    AgentSessionId, AgentTraversal, BlockView, ExpandDirection, ExpandOptions, SessionConfig,
+    ViewMode,
+};
+
+use crate::cli::OutputFormat;
+use crate::commands::agent::resolve_selector;
+use crate::output::{content_preview, print_error, print_info, print_success};
+
+const HELP: &str = "\
+  nav <block>                Move to a block
+  back [steps]               Go back in navigation history
+  expand <block> [depth]     List descendants (depth defaults to 3)
+  view [block]               Show the current or given block
+  find --role <role> [--tag <tag>]
+                             Find blocks by semantic role and/or tag
+  context add <block>        Add a block to the context window
+  context clear              Empty the context window
+  context show               List the context window
+  help                       Show this help
+  quit                       Leave the session
+
+Blocks are block ids, or selectors such as file paths in a codegraph.";
+
+/// What to do after a line has been handled
+#[derive(Debug, PartialEq, Eq)]
+enum Step {
+    Continue,
+    Quit,
+}
+
+struct Shell {
+    traversal: AgentTraversal,
+    session: AgentSessionId,
+    /// Copy of the traversed document for selectors and previews
+    doc: Document,
+    format: OutputFormat,
+}
+
+/// Open a traversal session on `path` and run commands until `quit`
+pub fn run(path: String, format: OutputFormat) -> Result<()> {
+    let doc = crate::output::read_document(Some(path.clone()))?;
+    let mut shell = Shell::new(doc, format)?;
+
+    if std::io::stdin().is_terminal() {
+        print_info(&format!(
+            "{} ({} blocks), session {}. Type help for commands.",
+            path,
+            shell.doc.block_count(),
+            shell.session
+        ));
+        let mut history = BasicHistory::new().max_entries(500).no_duplicates(true);
+        // Ctrl-D / Ctrl-C end the session
+        while let Ok(line) = Input::<String>::new()
+            .with_prompt(format!("agent {}", shell.position()?))
+            .allow_empty(true)
+            .report(false)
+            .history_with(&mut history)
+            .interact_text()
+        {
+            if shell.handle_line(&line) == Step::Quit {
+                break;
+            }
+        }
+    } else {
+        // Piped input: run each line as if it were typed
+        for line in std::io::stdin().lock().lines() {
+            if shell.handle_line(&line?) == Step::Quit {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl Shell {
+    fn new(doc: Document, format: OutputFormat) -> Result<Self> {
+        let traversal = AgentTraversal::new(doc.clone());
+        let session = traversal.create_session(SessionConfig::new().with_name("cli"))?;
+        Ok(Self {
+            traversal,
+            session,
+            doc,
+            format,
+        })
+    }
+
+    fn handle_line(&mut self, line: &str) -> Step {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        self.dispatch(&words).unwrap_or_else(|e| {
+            print_error(&e.to_string());
+            Step::Continue
+        })
+    }
+
+    fn dispatch(&mut self, words: &[&str]) -> Result<Step> {
+        match words {
+            [] => {}
+            ["quit" | "exit" | "q"] => return Ok(Step::Quit),
+            ["help" | "?"] => println!("{}", HELP),
+            ["nav", target] => self.nav(target)?,
+            ["back"] => self.back(1)?,
+            ["back", steps] => self.back(steps.parse().map_err(|_| anyhow!("Invalid steps"))?)?,
+            ["expand", target] => self.expand(target, 3)?,
+            ["expand", target, depth] => {
+                self.expand(target, depth.parse().map_err(|_| anyhow!("Invalid depth"))?)?
+            }
+            ["view"] => {
+                let position = self.position()?;
+                self.view(position)?
+            }
+            ["view", target] => {
+                let id = self.resolve(target)?;
+                self.view(id)?
+            }
+            ["find", args @ ..] => self.find(args)?,
+            ["context", "add", target] => self.context_add(target)?,
+            ["context", "clear"] => self.context_clear()?,
+            ["context", "show"] => self.context_show()?,
+            [command, ..] => return Err(anyhow!("Unknown command {} (try help)", command)),
+        }
+        Ok(Step::Continue)
+    }
+
+    fn resolve(&self, selector: &str) -> Result<BlockId> {
+        resolve_selector(&self.doc, selector)
+    }
+
+    fn position(&self) -> Result<BlockId> {
+        let sessions = self.traversal.get_session(&self.session)?;
+        Ok(sessions[&self.session].cursor.position)
+    }
+
+    fn nav(&self, target: &str) -> Result<()> {
+        let id = self.resolve(target)?;
+        let result = self.traversal.navigate_to(&self.session, id)?;
+        let hood = &result.neighborhood;
+        match self.format {
+            OutputFormat::Json => print_json(&serde_json::json!({
+                "position": result.position,
+                "ancestors": hood.ancestors,
+                "children": hood.children,
+                "siblings": hood.siblings,
+            }))?,
+            OutputFormat::Text => {
+                print_success(&format!("Moved to {}", result.position));
+                self.print_preview_line("", &result.position);
+                println!(
+                    "  {} children, {} siblings, {} connections",
+                    hood.children.len(),
+                    hood.siblings.len(),
+                    hood.connections.len()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn back(&self, steps: usize) -> Result<()> {
+        let result = self.traversal.go_back(&self.session, steps)?;
+        match self.format {
+            OutputFormat::Json => print_json(&serde_json::json!({ "position": result.position }))?,
+            OutputFormat::Text => print_success(&format!("Moved back to {}", result.position)),
+        }
+        Ok(())
+    }
+
+    fn expand(&self, target: &str, depth: usize) -> Result<()> {
+        let id = self.resolve(target)?;
+        let result = self.traversal.expand(
+            &self.session,
+            id,
+            ExpandDirection::Down,
+            ExpandOptions::new().with_depth(depth),
+        )?;
+        match self.format {
+            OutputFormat::Json => print_json(&serde_json::json!({
+                "root": result.root,
+                "levels": result.levels,
+                "total": result.total_blocks,
+            }))?,
+            OutputFormat::Text => {
+                println!("Expanded {} (depth {})", result.root, depth);
+                for (level, ids) in result.levels.iter().enumerate() {
+                    for id in ids {
+                        self.print_preview_line(&"  ".repeat(level + 1), id);
+                    }
+                }
+                println!("{} blocks", result.total_blocks);
+            }
+        }
+        Ok(())
+    }
+
+    fn view(&self, id: BlockId) -> Result<()> {
+        let view = self
+            .traversal
+            .view_block(&self.session, id, ViewMode::Full)?;
+        match self.format {
+            OutputFormat::Json => print_json(&block_view_json(&view))?,
+            OutputFormat::Text => {
+                println!("{}", "─".repeat(60).dimmed());
+                println!("{}: {}", "ID".cyan().bold(), view.block_id);
+                if let Some(role) = &view.role {
+                    println!("{}: {}", "Role".cyan().bold(), role);
+                }
+                if !view.tags.is_empty() {
+                    println!("{}: {}", "Tags".cyan().bold(), view.tags.join(", "));
+                }
+                println!(
+                    "{}: {} children, {} in / {} out edges",
+                    "Links".cyan().bold(),
+                    view.children_count,
+                    view.incoming_edges,
+                    view.outgoing_edges
+                );
+                if let Some(content) = &view.content {
+                    println!("{}", "─".repeat(60).dimmed());
+                    println!("{}", content);
+                }
+                println!("{}", "─".repeat(60).dimmed());
+            }
+        }
+        Ok(())
+    }
+
+    fn find(&self, args: &[&str]) -> Result<()> {
+        let mut role = None;
+        let mut tag = None;
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            let value = args.next().copied();
+            match (*flag, value) {
+                ("--role", Some(value)) => role = Some(value),
+                ("--tag", Some(value)) => tag = Some(value),
+                _ => return Err(anyhow!("Usage: find --role <role> [--tag <tag>]")),
+            }
+        }
+        if role.is_none() && tag.is_none() {
+            return Err(anyhow!("Usage: find --role <role> [--tag <tag>]"));
+        }
+
+        let result = self
+            .traversal
+            .find_by_pattern(&self.session, role, tag, None, None)?;
+        match self.format {
+            OutputFormat::Json => print_json(&serde_json::json!({
+                "count": result.matches.len(),
+                "blocks": result.matches,
+            }))?,
+            OutputFormat::Text => {
+                if result.matches.is_empty() {
+                    println!("No matching blocks found");
+                } else {
+                    println!("Found {} blocks:", result.matches.len());
+                    for id in &result.matches {
+                        self.print_preview_line("  ", id);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn context_add(&self, target: &str) -> Result<()> {
+        let id = self.resolve(target)?;
+        self.traversal.context_add(&self.session, id, None, None)?;
+        let total = self.context_blocks()?.len();
+        match self.format {
+            OutputFormat::Json => print_json(&serde_json::json!({
+                "added": id,
+                "total": total,
+            }))?,
+            OutputFormat::Text => {
+                print_success(&format!("Added {} to context ({} total)", id, total))
+            }
+        }
+        Ok(())
+    }
+
+    fn context_clear(&self) -> Result<()> {
+        self.traversal.context_clear(&self.session)?;
+        match self.format {
+            OutputFormat::Json => print_json(&serde_json::json!({ "count": 0 }))?,
+            OutputFormat::Text => print_success("Context cleared"),
+        }
+        Ok(())
+    }
+
+    fn context_show(&self) -> Result<()> {
+        let blocks = self.context_blocks()?;
+        match self.format {
+            OutputFormat::Json => print_json(&serde_json::json!({
+                "count": blocks.len(),
+                "blocks": blocks,
+            }))?,
+            OutputFormat::Text => {
+                println!("{}", "Context Window:".cyan().bold());
+                if blocks.is_empty() {
+                    println!("  (empty)");
+                }
+                for id in &blocks {
+                    self.print_preview_line("  ", id);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Context window blocks in id order, so output is stable
+    fn context_blocks(&self) -> Result<Vec<BlockId>> {
+        let sessions = self.traversal.get_session(&self.session)?;
+        let mut blocks: Vec<BlockId> = sessions[&self.session]
+            .context_blocks
+            .iter()
+            .copied()
+            .collect();
+        blocks.sort_by_key(|id| id.to_string());
+        Ok(blocks)
+    }
+
+    fn print_preview_line(&self, indent: &str, id: &BlockId) {
+        let preview = self
+            .doc
+            .get_block(id)
+            .map(|block| content_preview(&block.content, 60))
+            .unwrap_or_default();
+        println!(
+            "{}[{}] {}",
+            indent,
+            id.to_string().yellow(),
+            preview.lines().next().unwrap_or("").dimmed()
+        );
+    }
+}
+
+fn block_view_json(view: &BlockView) -> serde_json::Value {
+    serde_json::json!({
+        "block_id": view.block_id,
+        "role": view.role,
+        "tags": view.tags,
+        "content": view.content,
+        "children_count": view.children_count,
+        "incoming_edges": view.incoming_edges,
+        "outgoing_edges": view.outgoing_edges,
+    })
+}
+
+fn print_json(value: &serde_json::Value) -> Result<()> {
+    println!("{}", serde_json::to_string(value)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ucm_core::{Block, Content};
+
+    fn shell() -> (Shell, BlockId, BlockId) {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let intro = doc
+            .add_block(Block::new(Content::text("Intro"), Some("intro")), &root)
+            .unwrap();
+        let detail = doc
+            .add_block(Block::new(Content::text("Detail"), None), &intro)
+            .unwrap();
+        (Shell::new(doc, OutputFormat::Json).unwrap(), intro, detail)
+    }
+
+    #[test]
+    fn test_navigation_and_back() {
+        let (mut shell, intro, detail) = shell();
+        let root = shell.doc.root;
+
+        shell.handle_line(&format!("nav {}", intro));
+        shell.handle_line(&format!("nav {}", detail));
+        assert_eq!(shell.position().unwrap(), detail);
+        shell.handle_line("back");
+        assert_eq!(shell.position().unwrap(), intro);
+
+        // Errors are reported and the session carries on
+        assert_eq!(
+            shell.handle_line("nav blk_000000000000000000000000"),
+            Step::Continue
+        );
+        assert_eq!(shell.handle_line("bogus"), Step::Continue);
+        assert_eq!(shell.position().unwrap(), intro);
+        assert_eq!(
+            shell.handle_line(&format!("expand {} 2", root)),
+            Step::Continue
+        );
+        assert_eq!(shell.handle_line("quit"), Step::Quit);
+    }
+
+    #[test]
+    fn test_context_add_and_clear() {
+        let (mut shell, intro, detail) = shell();
+
+        shell.handle_line(&format!("context add {}", intro));
+        shell.handle_line(&format!("context add {}", detail));
+        shell.handle_line(&format!("context add {}", intro));
+        assert_eq!(shell.context_blocks().unwrap().len(), 2);
+
+        shell.handle_line("context clear");
+        assert!(shell.context_blocks().unwrap().is_empty());
+    }
+}
@@ -5,6 +5,7 @@ use serde::Serialize;
 use std::str::FromStr;
 use ucm_core::{Block, BlockId, Content};
 use ucm_engine::{EditOperator, Engine, MoveTarget, Operation};
+use ucp_translator_markdown::{parse_csv, CsvOptions};
 
 use crate::cli::{BlockCommands, OutputFormat};
 use crate::output::{
@@ -62,6 +63,26 @@ pub fn handle(cmd: BlockCommands, format: OutputFormat) -> Result<()> {
             },
             format,
         ),
+        BlockCommands::AddTable {
+            input,
+            output,
+            parent,
+            from_csv,
+            delimiter,
+            header,
+            no_header,
+            max_rows,
+            label,
+        } => {
+            let mut options = CsvOptions::new().with_max_rows(max_rows);
+            if let Some(delimiter) = delimiter {
+                options = options.with_delimiter(delimiter);
+            }
+            if header || no_header {
+                options = options.with_header(header);
+            }
+            add_table(input, output, parent, from_csv, options, label, format)
+        }
         BlockCommands::Get {
             input,
             id,
@@ -241,6 +262,54 @@ fn add(args: AddArgs, format: OutputFormat) -> Result<()> {
     Ok(())
 }
 
+fn add_table(
+    input: Option<String>,
+    output: Option<String>,
+    parent: Option<String>,
+    csv_path: String,
+    options: CsvOptions,
+    label: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut doc = if input.is_some() {
+        read_document(input)?
+    } else {
+        ucm_core::Document::create()
+    };
+
+    let data = std::fs::read_to_string(&csv_path)?;
+    let content = parse_csv(&data, options).map_err(|e| anyhow!("{}: {}", csv_path, e))?;
+    let Content::Table(table) = &content else {
+        unreachable!("parse_csv always returns table content");
+    };
+    let (rows, columns) = (table.row_count(), table.column_count());
+
+    let parent_id = parse_block_id(parent, "parent")?.unwrap_or(doc.root);
+    let mut block = Block::new(content, Some("table"));
+    block.metadata.label = label;
+    let block_id = doc.add_block(block, &parent_id)?;
+
+    write_document(&doc, output)?;
+
+    match format {
+        OutputFormat::Text => {
+            print_success(&format!("Table added ({} rows, {} columns)", rows, columns));
+            println!("Block ID: {}", block_id);
+        }
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "block_id": block_id,
+                "rows": rows,
+                "columns": columns,
+                "status": "success"
+            });
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+    }
+
+    Ok(())
+}
+
 fn get(input: Option<String>, id: String, metadata_only: bool, format: OutputFormat) -> Result<()> {
     let doc = read_document(input)?;
     let block_id = BlockId::from_str(&id).map_err(|_| anyhow!("Invalid block ID: {}", id))?;
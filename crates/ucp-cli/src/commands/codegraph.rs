@@ -4,9 +4,13 @@ use serde::Serialize;
 use std::path::PathBuf;
 use std::process::Command;
 use std::str::FromStr;
-use ucm_core::{BlockId, Document};
+use ucm_core::{BlockId, Document, PortableDocument};
+use ucp_api::codegraph::{
+    dependencies_of, dependents_of, diff_code_graphs, find_dead_code, find_symbols, CodeGraphStore,
+    ImpactEntry, SymbolHit, SymbolQuery,
+};
 use ucp_api::{
-    build_code_graph, build_code_graph_incremental, canonical_fingerprint,
+    build_code_graph, build_code_graph_incremental, canonical_fingerprint, code_graph_stats,
     codegraph_prompt_projection, export_codegraph_context_with_config, is_codegraph_document,
     render_codegraph_context_prompt, resolve_codegraph_selector, validate_code_graph_profile,
     CodeGraphBuildInput, CodeGraphBuildStatus, CodeGraphContextExport,
@@ -16,9 +20,7 @@ use ucp_api::{
 };
 
 use crate::cli::{CodegraphCommands, CodegraphContextCommands, OutputFormat};
-use crate::output::{
-    print_error, print_success, print_warning, read_document, write_output, DocumentJson,
-};
+use crate::output::{print_error, print_success, print_warning, read_document, write_output};
 use crate::state::{
     read_stateful_document, write_stateful_document, AgentSessionState,
     CodeGraphSessionPreferences, StatefulDocument,
@@ -38,6 +40,7 @@ pub fn handle(cmd: CodegraphCommands, format: OutputFormat) -> Result<()> {
             allow_partial,
             incremental,
             state_file,
+            store,
         } => build(
             repo,
             commit,
@@ -50,10 +53,43 @@ pub fn handle(cmd: CodegraphCommands, format: OutputFormat) -> Result<()> {
             allow_partial,
             incremental,
             state_file,
+            store,
             format,
         ),
         CodegraphCommands::Inspect { input } => inspect(input, format),
         CodegraphCommands::Prompt { input, output } => prompt(input, output, format),
+        CodegraphCommands::Impact {
+            path,
+            input,
+            dependencies,
+            max_depth,
+        } => impact(path, input, dependencies, max_depth, format),
+        CodegraphCommands::Find {
+            query,
+            input,
+            kind,
+            language,
+            exported,
+            path,
+            fuzzy,
+            limit,
+        } => {
+            let query = SymbolQuery {
+                name_contains: Some(query),
+                kind,
+                language,
+                exported_only: exported,
+                path_prefix: path,
+                fuzzy,
+            };
+            find(input, &query, limit, format)
+        }
+        CodegraphCommands::Diff { old, new } => diff(old, new, format),
+        CodegraphCommands::Stats { input } => stats(input, format),
+        CodegraphCommands::DeadCode {
+            input,
+            include_exported,
+        } => dead_code(input, include_exported, format),
         CodegraphCommands::Context(cmd) => context(cmd, format),
     }
 }
@@ -71,6 +107,7 @@ fn build(
     allow_partial: bool,
     incremental: bool,
     state_file: Option<String>,
+    store: Option<String>,
     format: OutputFormat,
 ) -> Result<()> {
     let repository_path = PathBuf::from(&repo);
@@ -108,12 +145,15 @@ fn build(
     }
     .context("failed to build code graph")?;
 
-    let doc_json = DocumentJson::from_document(&result.document);
+    let doc_json = PortableDocument::from_document(&result.document);
 
     if let Some(path) = &output {
         let serialized = serde_json::to_string_pretty(&doc_json)?;
         std::fs::write(path, serialized)?;
     }
+    if let Some(path) = &store {
+        CodeGraphStore::write(path, &result.document)?;
+    }
 
     match format {
         OutputFormat::Json => {
@@ -126,7 +166,7 @@ fn build(
                 incremental: Option<ucp_api::CodeGraphIncrementalStats>,
                 incremental_state_file: Option<String>,
                 diagnostics: Vec<ucp_api::CodeGraphDiagnostic>,
-                document: DocumentJson,
+                document: PortableDocument,
             }
 
             let payload = JsonBuildOutput {
@@ -317,30 +357,189 @@ fn prompt(input: Option<String>, output: Option<String>, format: OutputFormat) -
     let doc = read_document(input)?;
     let projection = codegraph_prompt_projection(&doc);
 
-    match format {
-        OutputFormat::Json => {
+    match (format, output) {
+        (OutputFormat::Json, None) => {
             #[derive(Serialize)]
             struct PromptResult {
                 projection: String,
             }
             println!(
                 "{}",
-                serde_json::to_string_pretty(&PromptResult {
-                    projection: projection.clone(),
-                })?
+                serde_json::to_string_pretty(&PromptResult { projection })?
             );
-            if let Some(path) = output {
-                write_output(&projection, Some(path))?;
+        }
+        (format, output) => write_output(&projection, output, format)?,
+    }
+
+    Ok(())
+}
+
+fn impact(
+    path: String,
+    input: Option<String>,
+    dependencies: bool,
+    max_depth: Option<usize>,
+    format: OutputFormat,
+) -> Result<()> {
+    let doc = read_query_document(input)?;
+    ensure_codegraph_document(&doc)?;
+    let max_depth = max_depth.unwrap_or(usize::MAX);
+    let entries = if dependencies {
+        dependencies_of(&doc, &path, max_depth)
+    } else {
+        dependents_of(&doc, &path, max_depth)
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&entries)?),
+        OutputFormat::Text => {
+            println!("{}", path.bold());
+            print_impact_tree(&entries, None, 1);
+        }
+    }
+    Ok(())
+}
+
+fn find(
+    input: Option<String>,
+    query: &SymbolQuery,
+    limit: usize,
+    format: OutputFormat,
+) -> Result<()> {
+    let doc = read_query_document(input)?;
+    ensure_codegraph_document(&doc)?;
+    let mut hits = find_symbols(&doc, query);
+    hits.truncate(limit);
+
+    match format {
+        OutputFormat::Json => {
+            let hits = hits
+                .iter()
+                .map(symbol_hit_json)
+                .collect::<Result<Vec<_>>>()?;
+            println!("{}", serde_json::to_string_pretty(&hits)?);
+        }
+        OutputFormat::Text => {
+            if hits.is_empty() {
+                print_warning("No matching symbols");
+            }
+            for hit in &hits {
+                let span = match (hit.start_line, hit.end_line) {
+                    (Some(start), Some(end)) => format!("{}:{}-{}", hit.path, start, end),
+                    _ => hit.path.clone(),
+                };
+                println!(
+                    "{} {} {} {}",
+                    hit.name.bold(),
+                    hit.kind.dimmed(),
+                    span,
+                    hit.logical_key.dimmed()
+                );
             }
         }
+    }
+    Ok(())
+}
+
+/// `hit` with `blk_` ids that other commands accept
+fn symbol_hit_json(hit: &SymbolHit) -> Result<serde_json::Value> {
+    let mut value = serde_json::to_value(hit)?;
+    value["block_id"] = hit.block_id.to_string().into();
+    if let Some(file) = hit.file_block_id {
+        value["file_block_id"] = file.to_string().into();
+    }
+    Ok(value)
+}
+
+fn diff(old: String, new: String, format: OutputFormat) -> Result<()> {
+    let old_doc = read_document(Some(old))?;
+    ensure_codegraph_document(&old_doc)?;
+    let new_doc = read_document(Some(new))?;
+    ensure_codegraph_document(&new_doc)?;
+    let diff = diff_code_graphs(&old_doc, &new_doc);
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+        OutputFormat::Text => print!("{}", diff.render_summary()),
+    }
+    Ok(())
+}
+
+fn stats(input: Option<String>, format: OutputFormat) -> Result<()> {
+    let doc = read_query_document(input)?;
+    ensure_codegraph_document(&doc)?;
+    let stats = code_graph_stats(&doc);
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&stats)?),
         OutputFormat::Text => {
-            write_output(&projection, output)?;
+            println!(
+                "nodes: total={} repositories={} directories={} files={} symbols={} nested_symbols={}",
+                stats.total_nodes,
+                stats.repository_nodes,
+                stats.directory_nodes,
+                stats.file_nodes,
+                stats.symbol_nodes,
+                stats.nested_symbol_nodes
+            );
+            println!(
+                "edges: total={} references={} exports={} calls={}",
+                stats.total_edges, stats.reference_edges, stats.export_edges, stats.call_edges
+            );
+            if !stats.languages.is_empty() {
+                let joined = stats
+                    .languages
+                    .iter()
+                    .map(|(lang, count)| format!("{}:{}", lang, count))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                println!("languages: {joined}");
+            }
         }
     }
+    Ok(())
+}
+
+fn dead_code(input: Option<String>, include_exported: bool, format: OutputFormat) -> Result<()> {
+    let doc = read_query_document(input)?;
+    ensure_codegraph_document(&doc)?;
+    let hits = find_dead_code(&doc, include_exported);
 
+    match format {
+        OutputFormat::Json => {
+            let hits = hits
+                .iter()
+                .map(symbol_hit_json)
+                .collect::<Result<Vec<_>>>()?;
+            println!("{}", serde_json::to_string_pretty(&hits)?);
+        }
+        OutputFormat::Text => {
+            if hits.is_empty() {
+                print_success("No unreferenced symbols");
+            }
+            for hit in &hits {
+                let location = match hit.start_line {
+                    Some(line) => format!("{}:{}", hit.path, line),
+                    None => hit.path.clone(),
+                };
+                println!("{} {} {}", hit.name.bold(), hit.kind.dimmed(), location);
+            }
+        }
+    }
     Ok(())
 }
 
+/// Nest each entry under the path it was first reached from
+fn print_impact_tree(entries: &[ImpactEntry], parent: Option<&str>, depth: usize) {
+    for entry in entries
+        .iter()
+        .filter(|e| e.depth == depth && e.via.as_deref() == parent)
+    {
+        println!("{}└─ {}", "   ".repeat(depth - 1), entry.path);
+        print_impact_tree(entries, Some(&entry.path), depth + 1);
+    }
+}
+
 fn context(cmd: CodegraphContextCommands, format: OutputFormat) -> Result<()> {
     match cmd {
         CodegraphContextCommands::Init {
@@ -1605,6 +1804,17 @@ fn context_prune(
     Ok(())
 }
 
+/// Read a codegraph for `impact` or `find`; a store file is opened without
+/// decoding block content, which neither command needs
+fn read_query_document(input: Option<String>) -> Result<Document> {
+    match input {
+        Some(path) if CodeGraphStore::is_store(&path) => {
+            Ok(CodeGraphStore::open(&path)?.into_skeleton())
+        }
+        input => read_document(input),
+    }
+}
+
 fn ensure_codegraph_document(doc: &Document) -> Result<()> {
     if is_codegraph_document(doc) {
         Ok(())
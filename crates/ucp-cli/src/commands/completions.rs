@@ -1,22 +1,172 @@
+//! Shell completion scripts
+
 use std::{io, path::PathBuf};
 
 use anyhow::Result;
 use clap::CommandFactory;
-use clap_complete::{generate, generate_to, Shell};
+use clap_complete::{Generator, Shell};
 
 use crate::cli::Cli;
+use crate::state::read_stateful_document;
+
+/// Bash: complete snapshot names for `snapshot restore` and `.ucl` files for
+/// `ucl ... --file`, deferring to the generated `_ucp` otherwise
+const BASH_HOOK: &str = r#"
+_ucp_dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local i input=""
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${COMP_WORDS[i]}" in
+            -i|--input) input="${COMP_WORDS[i+1]}" ;;
+        esac
+    done
+    if [[ "${COMP_WORDS[1]}" == snapshot && "${COMP_WORDS[2]}" == restore && -n "$input" && "$prev" != -* && "$cur" != -* ]]; then
+        COMPREPLY=($(compgen -W "$(ucp __complete-snapshots --input "$input" 2>/dev/null)" -- "$cur"))
+        return 0
+    fi
+    if [[ "${COMP_WORDS[1]}" == ucl && ( "$prev" == -F || "$prev" == --file ) ]]; then
+        compopt -o filenames 2>/dev/null
+        COMPREPLY=($(compgen -f -X '!*.ucl' -- "$cur") $(compgen -d -- "$cur"))
+        return 0
+    fi
+    _ucp "$@"
+}
+
+complete -F _ucp_dynamic -o nosort -o bashdefault -o default ucp
+"#;
+
+/// Zsh: same as the bash hook; spliced in before the script registers `_ucp`
+const ZSH_HOOK: &str = r#"
+_ucp_dynamic() {
+    local i input
+    for ((i = 2; i < CURRENT; i++)); do
+        case ${words[i]} in
+            -i|--input) input=${(Q)words[i+1]} ;;
+        esac
+    done
+    if [[ ${words[2]} == snapshot && ${words[3]} == restore && -n $input && ${words[CURRENT-1]} != -* && $PREFIX != -* ]]; then
+        local -a names
+        names=(${(f)"$(ucp __complete-snapshots --input $input 2>/dev/null)"})
+        compadd -a names
+        return
+    fi
+    if [[ ${words[2]} == ucl && ${words[CURRENT-1]} == (-F|--file) ]]; then
+        _files -g '*.ucl'
+        return
+    fi
+    _ucp "$@"
+}
+"#;
 
+/// Fish: extra completions added alongside the generated ones
+const FISH_HOOK: &str = r#"
+function __ucp_input
+    set -l tokens (commandline -opc)
+    for i in (seq (count $tokens))
+        if contains -- $tokens[$i] -i --input
+            set -l next (math $i + 1)
+            echo $tokens[$next]
+        end
+    end
+end
+complete -c ucp -n "__fish_seen_subcommand_from snapshot; and __fish_seen_subcommand_from restore; and test -n (__ucp_input)" -f -a "(ucp __complete-snapshots --input (__ucp_input) 2>/dev/null)"
+complete -c ucp -n "__fish_seen_subcommand_from ucl" -s F -l file -r -k -a "(__fish_complete_suffix .ucl)"
+"#;
+
+/// Print (or write into `directory`) the completion script for `shell`
 pub fn generate(shell: Shell, directory: Option<PathBuf>) -> Result<()> {
-    let mut command = Cli::command();
+    let script = script(shell);
 
     if let Some(dir) = directory {
         std::fs::create_dir_all(&dir)?;
-        let path = generate_to(shell, &mut command, "ucp", dir)?;
+        let path = dir.join(shell.file_name("ucp"));
+        std::fs::write(&path, script)?;
         eprintln!("Generated completion: {}", path.display());
     } else {
-        let mut stdout = io::stdout();
-        generate(shell, &mut command, "ucp", &mut stdout);
+        io::Write::write_all(&mut io::stdout(), script.as_bytes())?;
     }
 
     Ok(())
 }
+
+/// List snapshot names in `input`, one per line. Failures print nothing so
+/// a bad path never spills errors into the user's prompt.
+pub fn snapshot_names(input: Option<String>) -> Result<()> {
+    // Without a file the document would be read from stdin, which a shell
+    // completion cannot provide
+    let Some(input) = input else {
+        return Ok(());
+    };
+    if let Ok(doc) = read_stateful_document(Some(input)) {
+        for snapshot in &doc.state().snapshots {
+            println!("{}", snapshot.name);
+        }
+    }
+    Ok(())
+}
+
+/// The generated script for `shell` with dynamic hooks where supported
+fn script(shell: Shell) -> String {
+    let mut buf = Vec::new();
+    clap_complete::generate(shell, &mut Cli::command(), "ucp", &mut buf);
+    let script = String::from_utf8_lossy(&buf).into_owned();
+
+    match shell {
+        Shell::Bash => script + BASH_HOOK,
+        Shell::Fish => script + FISH_HOOK,
+        // The script either runs `_ucp` (autoloaded from fpath) or registers
+        // it with compdef; route both through the hook instead
+        Shell::Zsh => {
+            let hooked = script
+                .replace("    _ucp \"$@\"\nelse", "    _ucp_dynamic \"$@\"\nelse")
+                .replace("compdef _ucp ucp", "compdef _ucp_dynamic ucp");
+            match hooked.rfind("\nif [ \"$funcstack[1]\" = \"_ucp\" ]") {
+                Some(at) => format!("{}{}{}", &hooked[..at], ZSH_HOOK, &hooked[at..]),
+                None => script,
+            }
+        }
+        _ => script,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripts_cover_top_level_commands() {
+        for shell in [
+            Shell::Bash,
+            Shell::Zsh,
+            Shell::Fish,
+            Shell::PowerShell,
+            Shell::Elvish,
+        ] {
+            let script = script(shell);
+            for command in ["create", "block", "snapshot", "ucl", "completions"] {
+                assert!(
+                    script.contains(command),
+                    "{:?} script is missing {}",
+                    shell,
+                    command
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_dynamic_hooks_are_installed() {
+        let bash = script(Shell::Bash);
+        assert!(
+            bash.ends_with("complete -F _ucp_dynamic -o nosort -o bashdefault -o default ucp\n")
+        );
+
+        let zsh = script(Shell::Zsh);
+        assert!(zsh.contains("_ucp_dynamic() {"));
+        assert!(zsh.contains("    _ucp_dynamic \"$@\"\nelse"));
+        assert!(zsh.contains("compdef _ucp_dynamic ucp"));
+        assert!(zsh.find("_ucp_dynamic() {") < zsh.find("compdef _ucp_dynamic ucp"));
+
+        assert!(script(Shell::Fish).contains("__complete-snapshots"));
+    }
+}
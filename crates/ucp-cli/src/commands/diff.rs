@@ -0,0 +1,183 @@
+//! Compare two documents or two snapshots of one document
+
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use serde::Serialize;
+use ucm_core::{BlockId, Content, Document};
+use ucm_engine::DocumentDiff;
+
+use crate::cli::OutputFormat;
+use crate::output::content_preview;
+use crate::state::read_stateful_document;
+
+/// Width of content previews in text output
+const PREVIEW_LEN: usize = 60;
+
+/// Compare two document files, or with `snapshots` two snapshots in `input`
+pub fn diff(
+    from: String,
+    to: String,
+    snapshots: bool,
+    input: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let (before, after) = if snapshots {
+        let stateful = read_stateful_document(input)?;
+        let restore = |name: &str| {
+            stateful
+                .state()
+                .snapshots
+                .iter()
+                .find(|s| s.name == name)
+                .ok_or_else(|| anyhow!("Snapshot '{}' not found", name))?
+                .restore()
+        };
+        (restore(&from)?, restore(&to)?)
+    } else {
+        let read = |path: &str| read_stateful_document(Some(path.to_string())).map(|s| s.document);
+        (read(&from)?, read(&to)?)
+    };
+    report(&from, &to, &before, &after, format)
+}
+
+/// Print the differences between `before` and `after`
+fn report(
+    from: &str,
+    to: &str,
+    before: &Document,
+    after: &Document,
+    format: OutputFormat,
+) -> Result<()> {
+    let diff = DocumentDiff::between(before, after);
+    match format {
+        OutputFormat::Json => {
+            let result = DiffOutput::new(from, to, &diff, before, after);
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        OutputFormat::Text => print_diff(from, to, &diff, before, after),
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct DiffOutput<'a> {
+    from: &'a str,
+    to: &'a str,
+    added: Vec<BlockEntry<'a>>,
+    removed: Vec<BlockEntry<'a>>,
+    modified: Vec<ModifiedEntry<'a>>,
+    moved: Vec<MovedEntry>,
+}
+
+#[derive(Serialize)]
+struct BlockEntry<'a> {
+    id: String,
+    content: Option<&'a Content>,
+}
+
+#[derive(Serialize)]
+struct ModifiedEntry<'a> {
+    id: String,
+    old: Option<&'a Content>,
+    new: Option<&'a Content>,
+}
+
+#[derive(Serialize)]
+struct MovedEntry {
+    id: String,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+impl<'a> DiffOutput<'a> {
+    fn new(
+        from: &'a str,
+        to: &'a str,
+        diff: &DocumentDiff,
+        before: &'a Document,
+        after: &'a Document,
+    ) -> Self {
+        let content = |doc: &'a Document, id: &BlockId| doc.get_block(id).map(|b| &b.content);
+        let entries = |doc: &'a Document, ids: &[BlockId]| {
+            ids.iter()
+                .map(|id| BlockEntry {
+                    id: id.to_string(),
+                    content: content(doc, id),
+                })
+                .collect()
+        };
+        Self {
+            from,
+            to,
+            added: entries(after, &diff.added),
+            removed: entries(before, &diff.removed),
+            modified: diff
+                .modified
+                .iter()
+                .map(|id| ModifiedEntry {
+                    id: id.to_string(),
+                    old: content(before, id),
+                    new: content(after, id),
+                })
+                .collect(),
+            moved: diff
+                .moved
+                .iter()
+                .map(|m| MovedEntry {
+                    id: m.id.to_string(),
+                    from: m.from.map(|id| id.to_string()),
+                    to: m.to.map(|id| id.to_string()),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn print_diff(from: &str, to: &str, diff: &DocumentDiff, before: &Document, after: &Document) {
+    let preview = |doc: &Document, id: &BlockId| {
+        doc.get_block(id)
+            .map(|b| content_preview(&b.content, PREVIEW_LEN).replace('\n', " "))
+            .unwrap_or_default()
+    };
+
+    println!("{}", format!("Diff: {} → {}", from, to).cyan().bold());
+    println!("{}", "─".repeat(40));
+
+    if !diff.added.is_empty() {
+        println!("{} ({}):", "Added".green().bold(), diff.added.len());
+        for id in &diff.added {
+            println!("  {}", format!("+ {} {}", id, preview(after, id)).green());
+        }
+    }
+
+    if !diff.removed.is_empty() {
+        println!("{} ({}):", "Removed".red().bold(), diff.removed.len());
+        for id in &diff.removed {
+            println!("  {}", format!("- {} {}", id, preview(before, id)).red());
+        }
+    }
+
+    if !diff.modified.is_empty() {
+        println!("{} ({}):", "Modified".yellow().bold(), diff.modified.len());
+        for id in &diff.modified {
+            println!(
+                "  ~ {} {} → {}",
+                id,
+                preview(before, id).red(),
+                preview(after, id).green()
+            );
+        }
+    }
+
+    if !diff.moved.is_empty() {
+        println!("{} ({}):", "Moved".blue().bold(), diff.moved.len());
+        let parent = |id: &Option<BlockId>| id.map_or("(none)".to_string(), |id| id.to_string());
+        for m in &diff.moved {
+            println!("  > {} {} → {}", m.id, parent(&m.from), parent(&m.to));
+        }
+    }
+
+    if diff.is_empty() {
+        println!("No differences found");
+    }
+}
@@ -5,9 +5,7 @@ use serde::Serialize;
 use ucm_core::{Document, TokenModel};
 
 use crate::cli::OutputFormat;
-use crate::output::{
-    print_document_info, print_success, read_document, write_document, DocumentJson,
-};
+use crate::output::{print_document_info, print_success, read_document, write_document};
 
 /// Create a new document
 pub fn create(output: Option<String>, title: Option<String>, format: OutputFormat) -> Result<()> {
@@ -28,8 +26,7 @@ pub fn create(output: Option<String>, title: Option<String>, format: OutputForma
                 // In text mode without output, show the document info
                 print_success("Created new document");
                 print_document_info(&doc);
-                let doc_json = DocumentJson::from_document(&doc);
-                println!("\n{}", serde_json::to_string_pretty(&doc_json)?);
+                println!("\n{}", doc.to_portable_json_pretty()?);
             }
         }
     }
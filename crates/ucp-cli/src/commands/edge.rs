@@ -45,6 +45,7 @@ pub fn handle(cmd: EdgeCommands, format: OutputFormat) -> Result<()> {
             target,
             description,
             confidence,
+            weight,
         } => add(
             AddEdgeArgs {
                 input,
@@ -54,6 +55,7 @@ pub fn handle(cmd: EdgeCommands, format: OutputFormat) -> Result<()> {
                 target,
                 description,
                 confidence,
+                weight,
             },
             format,
         ),
@@ -102,6 +104,10 @@ struct AddEdgeArgs {
     /// Edge confidence score
     #[arg(long)]
     confidence: Option<f64>,
+
+    /// Edge weight
+    #[arg(long)]
+    weight: Option<f64>,
 }
 
 fn add(args: AddEdgeArgs, format: OutputFormat) -> Result<()> {
@@ -134,6 +140,9 @@ fn add(args: AddEdgeArgs, format: OutputFormat) -> Result<()> {
         edge_type: et,
         target: target_id,
         metadata,
+        weight: args.weight,
+        valid_from: None,
+        valid_to: None,
     };
 
     let result = engine.execute(&mut doc, op)?;
@@ -1,37 +1,60 @@
 //! Export commands
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use ucm_core::{BlockId, Document};
+use ucp_llm::{IdMapper, PromptBuilderConfig};
 
 use crate::cli::{ExportCommands, OutputFormat};
-use crate::output::{read_document, write_output, DocumentJson};
+use crate::output::{print_written, read_document, write_output};
 
 pub fn handle(cmd: ExportCommands, format: OutputFormat) -> Result<()> {
     match cmd {
-        ExportCommands::Markdown { input, output } => markdown(input, output, format),
+        ExportCommands::Markdown {
+            input,
+            output,
+            block,
+        } => markdown(input, output, block, format),
         ExportCommands::Json {
             input,
             output,
             pretty,
         } => json(input, output, pretty, format),
+        ExportCommands::Docx {
+            input,
+            output,
+            base_dir,
+        } => docx(input, output, base_dir, format),
+        ExportCommands::Prompt {
+            input,
+            output,
+            section,
+            config,
+        } => prompt(input, output, section, config, format),
     }
 }
 
-fn markdown(input: Option<String>, output: Option<String>, format: OutputFormat) -> Result<()> {
+fn markdown(
+    input: Option<String>,
+    output: Option<String>,
+    block: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
     let doc = read_document(input)?;
-    let md = ucp_translator_markdown::render_markdown(&doc)?;
-
-    match format {
-        OutputFormat::Json => {
-            // In JSON mode, return the markdown as a JSON string
-            println!("{}", serde_json::to_string(&md)?);
-        }
-        OutputFormat::Text => {
-            if output.is_some() {
-                write_output(&md, output)?;
-            } else {
-                println!("{}", md);
-            }
+    let md = match block {
+        Some(id) => {
+            let block_id =
+                BlockId::from_str(&id).map_err(|_| anyhow!("Invalid block ID: {}", id))?;
+            ucp_translator_markdown::render_markdown_block(&doc, &block_id, Default::default())?
         }
+        None => ucp_translator_markdown::render_markdown(&doc)?,
+    };
+
+    match (format, output) {
+        // Without a file, JSON mode returns the markdown as a JSON string
+        (OutputFormat::Json, None) => println!("{}", serde_json::to_string(&md)?),
+        (format, output) => write_output(&md, output, format)?,
     }
 
     Ok(())
@@ -41,17 +64,99 @@ fn json(
     input: Option<String>,
     output: Option<String>,
     pretty: bool,
-    _format: OutputFormat,
+    format: OutputFormat,
 ) -> Result<()> {
     let doc = read_document(input)?;
-    let doc_json = DocumentJson::from_document(&doc);
-
     let json_str = if pretty {
-        serde_json::to_string_pretty(&doc_json)?
+        doc.to_portable_json_pretty()?
     } else {
-        serde_json::to_string(&doc_json)?
+        doc.to_portable_json()?
     };
 
-    write_output(&json_str, output)?;
+    write_output(&json_str, output, format)?;
     Ok(())
 }
+
+fn docx(
+    input: Option<String>,
+    output: String,
+    base_dir: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    // Images referenced relative to the document resolve next to it
+    let base_dir = base_dir.map(PathBuf::from).or_else(|| {
+        input
+            .as_deref()
+            .and_then(|path| Path::new(path).parent())
+            .map(Path::to_path_buf)
+    });
+    let doc = read_document(input)?;
+
+    let mut renderer = ucp_translator_docx::DocxRenderer::new();
+    if let Some(dir) = base_dir {
+        renderer = renderer.with_base_dir(dir);
+    }
+    let bytes = renderer.render(&doc);
+    std::fs::write(&output, &bytes)?;
+
+    print_written(&output, bytes.len(), format);
+    Ok(())
+}
+
+fn prompt(
+    input: Option<String>,
+    output: Option<String>,
+    section: Option<String>,
+    config: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let doc = read_document(input)?;
+    let root = match section {
+        Some(id) => {
+            let block_id =
+                BlockId::from_str(&id).map_err(|_| anyhow!("Invalid block ID: {}", id))?;
+            if doc.get_block(&block_id).is_none() {
+                return Err(anyhow!("Block not found: {}", id));
+            }
+            block_id
+        }
+        None => doc.root,
+    };
+
+    let mut parts = Vec::new();
+    if let Some(path) = config {
+        let config: PromptBuilderConfig = serde_json::from_str(&std::fs::read_to_string(&path)?)
+            .map_err(|e| anyhow!("Invalid prompt config {}: {}", path, e))?;
+        parts.push(config.build().build_system_prompt());
+    }
+    // Short ids come from the whole document so they match other exports
+    let mapper = IdMapper::from_document(&doc);
+    parts.push(id_legend(&mapper, &doc, &root));
+    parts.push(mapper.section_to_prompt(&doc, &root));
+    let text = parts.join("\n\n") + "\n";
+
+    match (format, output) {
+        // Without a file, JSON mode returns the prompt as a JSON string
+        (OutputFormat::Json, None) => println!("{}", serde_json::to_string(&text)?),
+        (format, output) => write_output(&text, output, format)?,
+    }
+
+    Ok(())
+}
+
+/// `ID Mapping:` table for the blocks under `root`, in short-id order
+fn id_legend(mapper: &IdMapper, doc: &Document, root: &BlockId) -> String {
+    let mut entries: Vec<(u32, BlockId)> = std::iter::once(*root)
+        .chain(doc.descendants(root))
+        .filter_map(|id| mapper.to_short_id(&id).map(|short| (short, id)))
+        .collect();
+    entries.sort_by_key(|(short, _)| *short);
+    std::iter::once("ID Mapping:".to_string())
+        .chain(
+            entries
+                .into_iter()
+                .map(|(short, id)| format!("  {} = {}", short, id)),
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+}
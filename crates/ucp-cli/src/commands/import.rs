@@ -1,25 +1,49 @@
 //! Import commands
 
 use anyhow::Result;
+use colored::Colorize;
+use std::path::Path;
+use ucm_core::{BlockId, Document, TranslationReport};
+use ucp_translator_markdown::MergeReport;
 
 use crate::cli::{ImportCommands, OutputFormat};
-use crate::output::{print_success, read_file, write_document};
+use crate::commands::watch;
+use crate::output::{
+    print_error, print_info, print_success, print_warning, read_document, read_file, write_document,
+};
 
 pub fn handle(cmd: ImportCommands, format: OutputFormat) -> Result<()> {
     match cmd {
-        ImportCommands::Markdown { file, output } => markdown(file, output, format),
+        ImportCommands::Markdown {
+            file,
+            output: Some(output),
+            merge,
+            watch: true,
+            similarity,
+        } => markdown_watch(file, output, merge, similarity, format),
+        ImportCommands::Markdown {
+            file,
+            output,
+            merge: Some(existing),
+            similarity,
+            ..
+        } => markdown_merge(file, existing, output, similarity, format),
+        ImportCommands::Markdown { file, output, .. } => markdown(file, output, format),
         ImportCommands::Html {
             file,
             output,
             extract_images,
             extract_links,
         } => html(file, output, extract_images, extract_links, format),
+        ImportCommands::Json { file, output } => json(file, output, format),
     }
 }
 
 fn markdown(file: String, output: Option<String>, format: OutputFormat) -> Result<()> {
     let content = read_file(&file)?;
-    let doc = ucp_translator_markdown::parse_markdown(&content)?;
+    let parsed = ucp_translator_markdown::parse_markdown_with_report(&content)?;
+    print_report(&file, &parsed.report);
+    let doc = parsed.document;
 
     match format {
         OutputFormat::Json => {
@@ -34,6 +58,127 @@ fn markdown(file: String, output: Option<String>, format: OutputFormat) -> Resul
     Ok(())
 }
 
+fn markdown_merge(
+    file: String,
+    existing: String,
+    output: Option<String>,
+    similarity: f64,
+    format: OutputFormat,
+) -> Result<()> {
+    let content = read_file(&file)?;
+    let mut doc = read_document(Some(existing))?;
+    let options = ucp_translator_markdown::MergeOptions {
+        similarity_threshold: similarity,
+    };
+    let report = ucp_translator_markdown::merge_markdown_with_options(&mut doc, &content, options)?;
+
+    if let OutputFormat::Text = format {
+        print_merge(&file, &report);
+    }
+    write_document(&doc, output)?;
+
+    Ok(())
+}
+
+fn print_merge(file: &str, report: &MergeReport) {
+    print_success(&format!(
+        "Merged {} ({} unchanged, {} updated, {} added, {} removed)",
+        file,
+        report.unchanged.len(),
+        report.updated.len(),
+        report.added.len(),
+        report.removed.len()
+    ));
+    for id in &report.removed {
+        println!("  removed {}", id);
+    }
+}
+
+/// Keep `output` in sync with `file`, merging each save so unchanged blocks
+/// keep their ids. Runs until interrupted.
+fn markdown_watch(
+    file: String,
+    output: String,
+    merge: Option<String>,
+    similarity: f64,
+    format: OutputFormat,
+) -> Result<()> {
+    // Continue from an earlier run's output so ids survive restarts
+    let base = merge.or_else(|| Path::new(&output).exists().then(|| output.clone()));
+    let mut doc = base.map(|path| read_document(Some(path))).transpose()?;
+    let options = ucp_translator_markdown::MergeOptions {
+        similarity_threshold: similarity,
+    };
+
+    if let OutputFormat::Text = format {
+        print_info(&format!("Watching {} (Ctrl-C to stop)", file));
+    }
+    watch::watch_file(&file, || {
+        if let Err(e) = sync(&mut doc, &file, &output, options, format) {
+            match format {
+                OutputFormat::Json => println!(
+                    "{}",
+                    serde_json::json!({ "success": false, "errors": [e.to_string()] })
+                ),
+                OutputFormat::Text => print_error(&e.to_string()),
+            }
+        }
+    })
+}
+
+/// Bring `doc` up to date with `file` and write it to `output`
+fn sync(
+    doc: &mut Option<Document>,
+    file: &str,
+    output: &str,
+    options: ucp_translator_markdown::MergeOptions,
+    format: OutputFormat,
+) -> Result<()> {
+    let content = read_file(file)?;
+    let report = match doc {
+        Some(doc) => ucp_translator_markdown::merge_markdown_with_options(doc, &content, options)?,
+        None => {
+            let parsed = ucp_translator_markdown::parse_markdown_with_report(&content)?;
+            print_report(file, &parsed.report);
+            let imported = doc.insert(parsed.document);
+            MergeReport {
+                added: imported.blocks.keys().copied().collect(),
+                ..Default::default()
+            }
+        }
+    };
+    if let Some(doc) = doc {
+        write_document(doc, Some(output.to_string()))?;
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let ids = |ids: &[BlockId]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+            println!(
+                "{}",
+                serde_json::json!({
+                    "success": true,
+                    "unchanged": ids(&report.unchanged),
+                    "updated": ids(&report.updated),
+                    "added": ids(&report.added),
+                    "removed": ids(&report.removed),
+                })
+            );
+        }
+        OutputFormat::Text => {
+            print!(
+                "{} ",
+                chrono::Local::now()
+                    .format("[%H:%M:%S]")
+                    .to_string()
+                    .dimmed()
+            );
+            print_merge(file, &report);
+        }
+    }
+    Ok(())
+}
+
 fn html(
     file: String,
     output: Option<String>,
@@ -50,7 +195,9 @@ fn html(
     };
 
     let parser = ucp_translator_html::HtmlParser::with_config(config);
-    let doc = parser.parse(&content)?;
+    let parsed = parser.parse_with_report(&content)?;
+    print_report(&file, &parsed.report);
+    let doc = parsed.document;
 
     match format {
         OutputFormat::Json => {
@@ -64,3 +211,62 @@ fn html(
 
     Ok(())
 }
+
+/// Warn about anything the translator could not carry over. Goes to stderr,
+/// so JSON output stays parseable.
+fn print_report(file: &str, report: &TranslationReport) {
+    for warning in &report.warnings {
+        print_warning(&format!("{}: {}", file, warning));
+    }
+}
+
+fn json(file: String, output: Option<String>, format: OutputFormat) -> Result<()> {
+    let content = read_file(&file)?;
+    let doc = ucm_core::Document::from_portable_json(&content)?;
+
+    if let OutputFormat::Text = format {
+        print_success(&format!("Imported {} ({} blocks)", file, doc.block_count()));
+    }
+    write_document(&doc, output)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_sync_keeps_ids_of_unchanged_blocks() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("notes.md");
+        let file = file.to_str().unwrap();
+        let output = dir.path().join("notes.json");
+        let output = output.to_str().unwrap();
+        let options = ucp_translator_markdown::MergeOptions::default();
+        let paragraph = |doc: &Document, text: &str| {
+            doc.blocks
+                .values()
+                .find(|b| b.content == ucm_core::Content::text(text))
+                .map(|b| b.id)
+        };
+
+        std::fs::write(file, "# Notes\n\nKeep me.\n\nChange me.\n").unwrap();
+        let mut doc = None;
+        sync(&mut doc, file, output, options, OutputFormat::Json).unwrap();
+        let kept = paragraph(doc.as_ref().unwrap(), "Keep me.").unwrap();
+
+        std::fs::write(file, "# Notes\n\nNew first.\n\nKeep me.\n\nChanged.\n").unwrap();
+        sync(&mut doc, file, output, options, OutputFormat::Json).unwrap();
+        let written = read_document(Some(output.to_string())).unwrap();
+        assert_eq!(paragraph(&written, "Keep me."), Some(kept));
+        assert!(paragraph(&written, "New first.").is_some());
+        assert!(paragraph(&written, "Change me.").is_none());
+
+        // A failed read leaves the last good document in place
+        std::fs::remove_file(file).unwrap();
+        assert!(sync(&mut doc, file, output, options, OutputFormat::Json).is_err());
+        assert_eq!(paragraph(doc.as_ref().unwrap(), "Keep me."), Some(kept));
+    }
+}
@@ -57,7 +57,7 @@ fn id_map(input: Option<String>, output: Option<String>, format: OutputFormat) -
     match format {
         OutputFormat::Json => {
             let json = serde_json::to_string_pretty(&mappings)?;
-            write_output(&json, output)?;
+            write_output(&json, output, format)?;
         }
         OutputFormat::Text => {
             println!("{}", "ID Mapping:".cyan().bold());
@@ -0,0 +1,81 @@
+//! Three-way merge of documents edited from a common base
+
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use serde::Serialize;
+use ucm_core::Content;
+use ucm_diff::{merge_documents, BlockConflict, MergeError};
+
+use crate::cli::OutputFormat;
+use crate::output::{content_preview, write_output};
+use crate::state::read_stateful_document;
+
+/// Width of content previews in conflict listings
+const PREVIEW_LEN: usize = 60;
+
+/// Merge OURS and THEIRS against BASE, writing the result to `output`
+pub fn merge(
+    base: String,
+    ours: String,
+    theirs: String,
+    output: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let read = |path: &str| read_stateful_document(Some(path.to_string())).map(|s| s.document);
+    let (base_doc, ours_doc, theirs_doc) = (read(&base)?, read(&ours)?, read(&theirs)?);
+
+    match merge_documents(&base_doc, &ours_doc, &theirs_doc) {
+        Ok(merged) => write_output(&merged.to_portable_json_pretty()?, output, format),
+        Err(MergeError::Apply(e)) => Err(anyhow!("{}", e)),
+        Err(MergeError::Conflict(conflict)) => {
+            match format {
+                OutputFormat::Json => {
+                    let result = ConflictOutput {
+                        conflicts: conflict.conflicts.iter().map(ConflictEntry::new).collect(),
+                    };
+                    println!("{}", serde_json::to_string_pretty(&result)?);
+                }
+                OutputFormat::Text => {
+                    let preview =
+                        |content| content_preview(content, PREVIEW_LEN).replace('\n', " ");
+                    println!(
+                        "{} ({}):",
+                        "Conflicts".red().bold(),
+                        conflict.conflicts.len()
+                    );
+                    for c in &conflict.conflicts {
+                        println!("  {}", c.block_id);
+                        println!("    base:   {}", preview(&c.base_content));
+                        println!("    ours:   {}", preview(&c.our_content).green());
+                        println!("    theirs: {}", preview(&c.their_content).yellow());
+                    }
+                }
+            }
+            Err(anyhow!("{}", conflict))
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ConflictOutput<'a> {
+    conflicts: Vec<ConflictEntry<'a>>,
+}
+
+#[derive(Serialize)]
+struct ConflictEntry<'a> {
+    id: String,
+    base: &'a Content,
+    ours: &'a Content,
+    theirs: &'a Content,
+}
+
+impl<'a> ConflictEntry<'a> {
+    fn new(conflict: &'a BlockConflict) -> Self {
+        Self {
+            id: conflict.block_id.to_string(),
+            base: &conflict.base_content,
+            ours: &conflict.our_content,
+            theirs: &conflict.their_content,
+        }
+    }
+}
@@ -1,18 +1,24 @@
 //! CLI command implementations
 
 pub mod agent;
+pub mod agent_shell;
 pub mod block;
 pub mod codegraph;
+pub mod completions;
+pub mod diff;
 pub mod document;
 pub mod edge;
 pub mod export;
 pub mod find;
 pub mod import;
 pub mod llm;
+pub mod merge;
 pub mod nav;
 pub mod prune;
+pub mod repl;
 pub mod snapshot;
 pub mod tree;
 pub mod tx;
 pub mod ucl;
 pub mod validate;
+pub mod watch;
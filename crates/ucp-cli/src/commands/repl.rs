@@ -0,0 +1,594 @@
+//! Interactive UCL session
+
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use regex::Regex;
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::FileHistory;
+use rustyline::validate::Validator;
+use rustyline::{CompletionType, Config, Context, Editor, Helper};
+use std::io::{BufRead, IsTerminal};
+use std::path::{Path, PathBuf};
+use ucm_core::{Block, Document};
+use ucm_engine::Engine;
+
+use crate::cli::OutputFormat;
+use crate::commands::ucl::command_to_operation;
+use crate::commands::validate::ValidationResultJson;
+use crate::output::{
+    content_preview, print_block_table, print_error, print_info, print_success, print_tree,
+    print_validation_issues, BlockSummary,
+};
+use crate::state::{
+    read_stateful_document, write_stateful_document, SnapshotInfo, StatefulDocument,
+};
+
+/// UCL keywords offered by tab completion
+const KEYWORDS: &[&str] = &[
+    "EDIT",
+    "SET",
+    "MOVE",
+    "TO",
+    "BEFORE",
+    "AFTER",
+    "APPEND",
+    "WITH",
+    "DELETE",
+    "CASCADE",
+    "PRESERVE_CHILDREN",
+    "PRUNE",
+    "UNREACHABLE",
+    "WHERE",
+    "LINK",
+    "UNLINK",
+    "SNAPSHOT",
+    "CREATE",
+    "RESTORE",
+    "BEGIN",
+    "TRANSACTION",
+    "COMMIT",
+    "ROLLBACK",
+    "WRITE_SECTION",
+    "text",
+    "table",
+    "code",
+    "math",
+    "media",
+    "json",
+];
+
+const META_COMMANDS: &[&str] = &[
+    ":save",
+    ":load",
+    ":snapshot",
+    ":undo",
+    ":tree",
+    ":find",
+    ":help",
+    ":quit",
+];
+
+const HELP: &str = "\
+UCL commands run immediately, e.g. APPEND blk_ff0000000000000000000000 text :: \"Hello\"
+A line ending in :: or leaving a { open starts a block that runs at the first
+line ending in ;; (any line may end in ;;).
+
+  :save [path]      Write the document (defaults to the loaded file)
+  :load <path>      Replace the session document with a file
+  :snapshot [name]  Record a snapshot (kept in the file on :save)
+  :undo             Revert the last UCL command or block
+  :tree             Show the document tree
+  :find <regex>     List blocks whose content matches
+  :help             Show this help
+  :quit             Leave the session
+
+Meta-commands may also start with a dot (.save, .tree, ...). Files ending in
+.md are read and written as Markdown. Tab completes UCL keywords and block
+ids; history is kept in ~/.ucp_history.";
+
+/// Document states kept for :undo
+const MAX_UNDO: usize = 50;
+
+/// History entries kept in memory and in the history file
+const MAX_HISTORY: usize = 500;
+
+/// What to do after a line has been handled
+#[derive(Debug, PartialEq, Eq)]
+enum Step {
+    Continue,
+    Quit,
+}
+
+struct Session {
+    doc: StatefulDocument,
+    path: Option<String>,
+    engine: Engine,
+    format: OutputFormat,
+    /// Lines of an unfinished multi-line block
+    pending: String,
+    /// Document states before each UCL run, oldest first
+    undo: Vec<Document>,
+}
+
+/// Start an interactive session on a document (or a new empty one)
+pub fn repl(input: Option<String>, format: OutputFormat) -> Result<()> {
+    let doc = match &input {
+        Some(path) => open(path)?,
+        None => StatefulDocument::from_document(Document::create()),
+    };
+    let mut session = Session::new(doc, input, format);
+
+    if std::io::stdin().is_terminal() {
+        print_info(&format!(
+            "{} ({} blocks). Type :help for commands.",
+            session.path.as_deref().unwrap_or("New document"),
+            session.doc.document.block_count()
+        ));
+        let history = history_path();
+        let mut editor = editor(history.as_deref())?;
+        loop {
+            editor.set_helper(Some(ReplHelper::new(&session.doc.document)));
+            let prompt = if session.pending.is_empty() {
+                "ucp> "
+            } else {
+                "...> "
+            };
+            let line = match editor.readline(prompt) {
+                Ok(line) => line,
+                // Ctrl-D / Ctrl-C end the session
+                Err(ReadlineError::Eof | ReadlineError::Interrupted) => break,
+                Err(e) => return Err(e.into()),
+            };
+            if let Some(path) = &history {
+                // History is a convenience; failing to persist it is not an error
+                let _ = editor.append_history(path);
+            }
+            if session.handle_line(&line) == Step::Quit {
+                break;
+            }
+        }
+    } else {
+        // Piped input: run each line as if it were typed
+        for line in std::io::stdin().lock().lines() {
+            if session.handle_line(&line?) == Step::Quit {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl Session {
+    fn new(doc: StatefulDocument, path: Option<String>, format: OutputFormat) -> Self {
+        Self {
+            doc,
+            path,
+            engine: Engine::new(),
+            format,
+            pending: String::new(),
+            undo: Vec::new(),
+        }
+    }
+
+    fn handle_line(&mut self, line: &str) -> Step {
+        let result = self.dispatch(line.trim());
+        result.unwrap_or_else(|e| {
+            print_error(&e.to_string());
+            Step::Continue
+        })
+    }
+
+    fn dispatch(&mut self, line: &str) -> Result<Step> {
+        if self.pending.is_empty() {
+            if line.is_empty() {
+                return Ok(Step::Continue);
+            }
+            if let Some(command) = line.strip_prefix([':', '.']) {
+                return self.meta(command);
+            }
+        }
+
+        let in_block = !self.pending.is_empty();
+        self.pending.push_str(line);
+        self.pending.push('\n');
+        let ucl = match self.pending.trim_end().strip_suffix(";;") {
+            Some(ucl) => ucl.to_string(),
+            None if in_block || opens_block(line) => return Ok(Step::Continue),
+            None => self.pending.clone(),
+        };
+        self.pending.clear();
+        if !ucl.trim().is_empty() {
+            self.execute(&ucl)?;
+        }
+        Ok(Step::Continue)
+    }
+
+    /// Run a meta-command, given without its leading `:` or `.`
+    fn meta(&mut self, line: &str) -> Result<Step> {
+        let (command, arg) = match line.split_once(char::is_whitespace) {
+            Some((command, arg)) => (command, Some(arg.trim()).filter(|a| !a.is_empty())),
+            None => (line, None),
+        };
+        match command {
+            "q" | "quit" | "exit" => return Ok(Step::Quit),
+            "help" | "h" => println!("{}", HELP),
+            "save" => {
+                let path = arg
+                    .map(str::to_string)
+                    .or_else(|| self.path.clone())
+                    .ok_or_else(|| anyhow!("Usage: :save <path>"))?;
+                save(&self.doc, &path)?;
+                print_success(&format!("Saved to {}", path));
+                self.path = Some(path);
+            }
+            "load" => {
+                let path = arg.ok_or_else(|| anyhow!("Usage: :load <path>"))?;
+                self.doc = open(path)?;
+                self.undo.clear();
+                self.path = Some(path.to_string());
+                print_success(&format!(
+                    "Loaded {} ({} blocks)",
+                    path,
+                    self.doc.document.block_count()
+                ));
+            }
+            "snapshot" => {
+                let snapshots = &self.doc.state().snapshots;
+                let name = match arg {
+                    Some(name) => name.to_string(),
+                    None => format!("snapshot-{}", snapshots.len() + 1),
+                };
+                if snapshots.iter().any(|s| s.name == name) {
+                    return Err(anyhow!("Snapshot '{}' already exists", name));
+                }
+                let snapshot = SnapshotInfo::create(name.clone(), None, &self.doc.document)?;
+                self.doc.state_mut().snapshots.push(snapshot);
+                print_success(&format!("Snapshot '{}' created", name));
+            }
+            "undo" => {
+                let previous = self.undo.pop().ok_or_else(|| anyhow!("Nothing to undo"))?;
+                self.doc.document = previous;
+                print_success(&format!(
+                    "Reverted to version {} ({} blocks)",
+                    self.doc.document.version.counter,
+                    self.doc.document.block_count()
+                ));
+            }
+            "tree" => print_tree(&self.doc.document, None, false),
+            "find" => {
+                let pattern = arg.ok_or_else(|| anyhow!("Usage: :find <regex>"))?;
+                self.find(&Regex::new(pattern)?)?;
+            }
+            other => return Err(anyhow!("Unknown command :{} (try :help)", other)),
+        }
+        Ok(Step::Continue)
+    }
+
+    fn find(&self, pattern: &Regex) -> Result<()> {
+        let doc = &self.doc.document;
+        let mut matches: Vec<&Block> = doc
+            .blocks
+            .values()
+            .filter(|block| pattern.is_match(&content_preview(&block.content, 10000)))
+            .collect();
+        matches.sort_by_key(|block| block.id.to_string());
+
+        match self.format {
+            OutputFormat::Json => {
+                let summaries: Vec<BlockSummary> = matches
+                    .iter()
+                    .map(|b| BlockSummary::from_block(b))
+                    .collect();
+                println!("{}", serde_json::to_string(&summaries)?);
+            }
+            OutputFormat::Text if matches.is_empty() => println!("No matching blocks found"),
+            OutputFormat::Text => print_block_table(&matches),
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, ucl: &str) -> Result<()> {
+        let commands =
+            ucl_parser::parse_commands(ucl).map_err(|e| anyhow!("Parse error: {}", e))?;
+        let doc = &mut self.doc.document;
+        let blocks_before = doc.block_count();
+        let before = doc.clone();
+
+        let mut affected = Vec::new();
+        let mut errors = Vec::new();
+        for command in &commands {
+            let result = command_to_operation(command)
+                .and_then(|op| self.engine.execute(doc, op).map_err(Into::into));
+            match result {
+                Ok(result) if result.success => affected.extend(result.affected_blocks),
+                Ok(result) => errors.push(result.error.unwrap_or_else(|| "failed".into())),
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+
+        if doc.version != before.version {
+            if self.undo.len() == MAX_UNDO {
+                self.undo.remove(0);
+            }
+            self.undo.push(before);
+        }
+
+        let validation = self.engine.validate(doc);
+        let blocks_after = doc.block_count();
+        match self.format {
+            OutputFormat::Json => {
+                let result = serde_json::json!({
+                    "success": errors.is_empty(),
+                    "affected_blocks": affected,
+                    "errors": errors,
+                    "validation": ValidationResultJson::from(&validation),
+                    "block_count": blocks_after,
+                    "version": doc.version.counter,
+                });
+                println!("{}", serde_json::to_string(&result)?);
+            }
+            OutputFormat::Text => {
+                for id in &affected {
+                    println!("  {}", id.to_string().cyan());
+                }
+                for error in &errors {
+                    print_error(error);
+                }
+                print_validation_issues(&validation.issues);
+                println!(
+                    "{} blocks ({:+}), version {}",
+                    blocks_after,
+                    blocks_after as i64 - blocks_before as i64,
+                    doc.version.counter
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether a line leaves content unfinished: it ends at `::` or leaves a `{`
+/// open
+fn opens_block(line: &str) -> bool {
+    line.ends_with("::") || line.matches('{').count() > line.matches('}').count()
+}
+
+/// Read a JSON document, or Markdown when the path ends in `.md`
+fn open(path: &str) -> Result<StatefulDocument> {
+    if is_markdown(path) {
+        let markdown = std::fs::read_to_string(path)?;
+        let doc = ucp_translator_markdown::parse_markdown(&markdown)?;
+        return Ok(StatefulDocument::from_document(doc));
+    }
+    read_stateful_document(Some(path.to_string()))
+}
+
+/// Write the document as JSON, or as Markdown when the path ends in `.md`
+fn save(doc: &StatefulDocument, path: &str) -> Result<()> {
+    if is_markdown(path) {
+        let markdown = ucp_translator_markdown::render_markdown(&doc.document)?;
+        std::fs::write(path, markdown)?;
+        return Ok(());
+    }
+    write_stateful_document(doc, Some(path.to_string()))
+}
+
+fn is_markdown(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+}
+
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".ucp_history"))
+}
+
+/// Line editor with completion and a history that is loaded from `history`
+/// and, as lines are entered, appended to it
+fn editor(history: Option<&Path>) -> Result<Editor<ReplHelper, FileHistory>> {
+    let config = Config::builder()
+        .max_history_size(MAX_HISTORY)?
+        .history_ignore_dups(true)?
+        .history_ignore_space(true)
+        .auto_add_history(true)
+        .completion_type(CompletionType::List)
+        .build();
+    let mut editor = Editor::with_config(config)?;
+    if let Some(path) = history.filter(|p| p.exists()) {
+        // An unreadable history file only costs the old entries
+        let _ = editor.load_history(path);
+    }
+    Ok(editor)
+}
+
+/// Completes the word before the cursor from UCL keywords, meta-commands
+/// and the current document's block ids
+struct ReplHelper {
+    words: Vec<String>,
+}
+
+impl ReplHelper {
+    fn new(doc: &Document) -> Self {
+        let mut words: Vec<String> = KEYWORDS
+            .iter()
+            .chain(META_COMMANDS)
+            .map(|w| w.to_string())
+            .collect();
+        words.extend(doc.blocks.keys().map(|id| id.to_string()));
+        words.sort();
+        Self { words }
+    }
+
+    /// Start of the word being completed and the words it may become; a
+    /// single candidate is followed by a space
+    fn candidates(&self, input: &str) -> (usize, Vec<String>) {
+        let start = input.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let word = &input[start..];
+        if word.is_empty() {
+            return (start, Vec::new());
+        }
+
+        let mut matches: Vec<String> = self
+            .words
+            .iter()
+            .filter(|w| w.len() >= word.len() && w[..word.len()].eq_ignore_ascii_case(word))
+            .cloned()
+            .collect();
+        if let [only] = matches.as_mut_slice() {
+            only.push(' ');
+        }
+        (start, matches)
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let (start, words) = self.candidates(&line[..pos]);
+        let pairs = words
+            .into_iter()
+            .map(|word| Pair {
+                display: word.trim_end().to_string(),
+                replacement: word,
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completes_keywords_and_block_ids() {
+        let doc = Document::create();
+        let helper = ReplHelper::new(&doc);
+        let single = |start: usize, word: &str| (start, vec![format!("{} ", word)]);
+
+        assert_eq!(helper.candidates("app"), single(0, "APPEND"));
+        assert_eq!(
+            helper.candidates("APPEND blk_ff"),
+            single(7, &doc.root.to_string())
+        );
+        assert_eq!(helper.candidates(":sa"), single(0, ":save"));
+        assert_eq!(
+            helper.candidates("MOVE blk_1 pres"),
+            single(11, "PRESERVE_CHILDREN")
+        );
+        assert_eq!(helper.candidates("TRANS"), single(0, "TRANSACTION"));
+        // The editor lists ambiguous matches and extends to their common prefix
+        assert_eq!(
+            helper.candidates("CO"),
+            (0, vec!["COMMIT".to_string(), "code".to_string()])
+        );
+        assert_eq!(helper.candidates("xyz"), (0, Vec::new()));
+        assert_eq!(helper.candidates("EDIT "), (5, Vec::new()));
+    }
+
+    #[test]
+    fn test_session_runs_ucl_and_meta_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("doc.json").to_string_lossy().into_owned();
+        let mut session = Session::new(
+            StatefulDocument::from_document(Document::create()),
+            None,
+            OutputFormat::Json,
+        );
+
+        let root = session.doc.document.root;
+        assert_eq!(
+            session.handle_line(&format!("APPEND {} text :: \"Hello\"", root)),
+            Step::Continue
+        );
+        assert_eq!(session.doc.document.block_count(), 2);
+
+        // Errors are reported and the session carries on
+        assert_eq!(session.handle_line("NOT UCL"), Step::Continue);
+        assert_eq!(session.handle_line(":save"), Step::Continue);
+        assert!(!std::path::Path::new(&path).exists());
+
+        session.handle_line(":snapshot");
+        session.handle_line(&format!(":save {}", path));
+        assert_eq!(session.path.as_deref(), Some(path.as_str()));
+
+        session.doc = StatefulDocument::from_document(Document::create());
+        session.handle_line(&format!(":load {}", path));
+        assert_eq!(session.doc.document.block_count(), 2);
+        assert_eq!(session.doc.state().snapshots[0].name, "snapshot-1");
+
+        assert_eq!(session.handle_line(":quit"), Step::Quit);
+    }
+
+    #[test]
+    fn test_session_blocks_undo_and_markdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.md");
+        std::fs::write(&path, "# Notes\n\nFirst\n").unwrap();
+        let path = path.to_string_lossy().into_owned();
+
+        let mut session =
+            Session::new(open(&path).unwrap(), Some(path.clone()), OutputFormat::Json);
+        let root = session.doc.document.root;
+        let blocks = session.doc.document.block_count();
+
+        // A line ending in :: collects lines until one ends in ;;
+        session.handle_line(&format!("APPEND {} text ::", root));
+        session.handle_line("");
+        session.handle_line("\"Second\"");
+        assert_eq!(session.doc.document.block_count(), blocks);
+        session.handle_line(";;");
+        assert!(session.pending.is_empty());
+        assert_eq!(session.doc.document.block_count(), blocks + 1);
+
+        session.handle_line(&format!("APPEND {} text :: \"Third\";;", root));
+        assert_eq!(session.doc.document.block_count(), blocks + 2);
+
+        // A failed command leaves nothing to undo
+        session.handle_line("DELETE blk_000000000000000000000000");
+        assert_eq!(session.handle_line(".undo"), Step::Continue);
+        assert_eq!(session.doc.document.block_count(), blocks + 1);
+
+        session.handle_line(".save");
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.contains("Second"));
+        assert!(!saved.contains("Third"));
+    }
+
+    #[test]
+    fn test_history_persists_between_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history");
+
+        let mut first = editor(Some(&path)).unwrap();
+        first.add_history_entry("APPEND a").unwrap();
+        first.add_history_entry(":tree").unwrap();
+        first.append_history(&path).unwrap();
+
+        let second = editor(Some(&path)).unwrap();
+        let entries: Vec<&String> = second.history().iter().collect();
+        assert_eq!(entries, ["APPEND a", ":tree"]);
+    }
+}
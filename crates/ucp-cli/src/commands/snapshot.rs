@@ -5,6 +5,7 @@ use colored::Colorize;
 use serde::Serialize;
 
 use crate::cli::{OutputFormat, SnapshotCommands};
+use crate::commands;
 use crate::output::print_success;
 use crate::state::{read_stateful_document, write_stateful_document, SnapshotInfo};
 
@@ -209,95 +210,5 @@ fn delete(
 }
 
 fn diff(input: Option<String>, from: String, to: String, format: OutputFormat) -> Result<()> {
-    let stateful = read_stateful_document(input)?;
-
-    let snap_from = stateful
-        .state()
-        .snapshots
-        .iter()
-        .find(|s| s.name == from)
-        .ok_or_else(|| anyhow!("Snapshot '{}' not found", from))?;
-
-    let snap_to = stateful
-        .state()
-        .snapshots
-        .iter()
-        .find(|s| s.name == to)
-        .ok_or_else(|| anyhow!("Snapshot '{}' not found", to))?;
-
-    let doc_from = snap_from.restore()?;
-    let doc_to = snap_to.restore()?;
-
-    // Collect block IDs
-    let ids_from: std::collections::HashSet<_> = doc_from.blocks.keys().cloned().collect();
-    let ids_to: std::collections::HashSet<_> = doc_to.blocks.keys().cloned().collect();
-
-    let added: Vec<_> = ids_to.difference(&ids_from).collect();
-    let removed: Vec<_> = ids_from.difference(&ids_to).collect();
-    let common: Vec<_> = ids_from.intersection(&ids_to).collect();
-
-    // Check for modified blocks (same ID but different content)
-    let mut modified = Vec::new();
-    for id in &common {
-        let block_from = doc_from.get_block(id);
-        let block_to = doc_to.get_block(id);
-        if let (Some(bf), Some(bt)) = (block_from, block_to) {
-            // Simple comparison - could be more sophisticated
-            if serde_json::to_string(&bf.content)? != serde_json::to_string(&bt.content)? {
-                modified.push(*id);
-            }
-        }
-    }
-
-    match format {
-        OutputFormat::Json => {
-            #[derive(Serialize)]
-            struct DiffResult {
-                from: String,
-                to: String,
-                added: Vec<String>,
-                removed: Vec<String>,
-                modified: Vec<String>,
-            }
-            let result = DiffResult {
-                from,
-                to,
-                added: added.iter().map(|id| id.to_string()).collect(),
-                removed: removed.iter().map(|id| id.to_string()).collect(),
-                modified: modified.iter().map(|id| id.to_string()).collect(),
-            };
-            println!("{}", serde_json::to_string_pretty(&result)?);
-        }
-        OutputFormat::Text => {
-            println!("{}", format!("Diff: {} → {}", from, to).cyan().bold());
-            println!("{}", "─".repeat(40));
-
-            if !added.is_empty() {
-                println!("{} ({}):", "Added".green().bold(), added.len());
-                for id in &added {
-                    println!("  + {}", id);
-                }
-            }
-
-            if !removed.is_empty() {
-                println!("{} ({}):", "Removed".red().bold(), removed.len());
-                for id in &removed {
-                    println!("  - {}", id);
-                }
-            }
-
-            if !modified.is_empty() {
-                println!("{} ({}):", "Modified".yellow().bold(), modified.len());
-                for id in &modified {
-                    println!("  ~ {}", id);
-                }
-            }
-
-            if added.is_empty() && removed.is_empty() && modified.is_empty() {
-                println!("No differences found");
-            }
-        }
-    }
-
-    Ok(())
+    commands::diff::diff(from, to, true, input, format)
 }
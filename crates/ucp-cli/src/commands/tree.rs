@@ -4,16 +4,23 @@ use anyhow::Result;
 use serde::Serialize;
 
 use crate::cli::OutputFormat;
-use crate::output::{print_tree, read_document};
+use crate::output::{print_document_stats, print_tree};
+use crate::state::read_stateful_document;
 
 /// Display document as a tree
 pub fn tree(
     input: Option<String>,
     depth: Option<usize>,
     ids: bool,
+    stats: bool,
     format: OutputFormat,
 ) -> Result<()> {
-    let doc = read_document(input)?;
+    let stateful = read_stateful_document(input)?;
+    let doc = &stateful.document;
+    let stats = stats.then(|| ucm_core::DocumentStats {
+        snapshot_count: stateful.state().snapshots.len(),
+        ..doc.statistics()
+    });
 
     match format {
         OutputFormat::Json => {
@@ -55,11 +62,23 @@ pub fn tree(
                 })
             }
 
-            let tree = build_tree(&doc, &doc.root, 0, depth);
-            println!("{}", serde_json::to_string_pretty(&tree)?);
+            let tree = build_tree(doc, &doc.root, 0, depth);
+            match stats {
+                Some(stats) => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&serde_json::json!({
+                        "tree": tree,
+                        "stats": stats,
+                    }))?
+                ),
+                None => println!("{}", serde_json::to_string_pretty(&tree)?),
+            }
         }
         OutputFormat::Text => {
-            print_tree(&doc, depth, ids);
+            print_tree(doc, depth, ids);
+            if let Some(stats) = &stats {
+                print_document_stats(stats);
+            }
         }
     }
 
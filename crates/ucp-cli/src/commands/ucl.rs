@@ -3,20 +3,34 @@
 use anyhow::Result;
 use serde::Serialize;
 use std::str::FromStr;
-use ucm_core::{BlockId, Content, EdgeType};
-use ucm_engine::{EditOperator, Engine, MoveTarget, Operation, PruneCondition};
+use ucm_core::{BlockId, Content, DiffFormat, Document, EdgeType};
+use ucm_engine::{
+    DryRunEntry, DryRunLog, EditOperator, Engine, MoveTarget, Operation, OperationResult,
+    PruneCondition,
+};
 
 use crate::cli::{OutputFormat, UclCommands};
+use crate::commands::watch;
 use crate::output::{print_error, print_success, read_document, read_file, write_document};
 
 pub fn handle(cmd: UclCommands, format: OutputFormat) -> Result<()> {
     match cmd {
+        UclCommands::Exec {
+            input,
+            output,
+            file: Some(file),
+            watch: true,
+            base_snapshot,
+            ..
+        } => watch::watch(input, output, file, base_snapshot, format),
         UclCommands::Exec {
             input,
             output,
             commands,
             file,
-        } => exec(input, output, commands, file, format),
+            dry_run,
+            ..
+        } => exec(input, output, commands, file, dry_run, format),
         UclCommands::Parse { commands, file } => parse(commands, file, format),
     }
 }
@@ -26,6 +40,7 @@ fn exec(
     output: Option<String>,
     commands: Option<String>,
     file: Option<String>,
+    dry_run: bool,
     format: OutputFormat,
 ) -> Result<()> {
     let mut doc = read_document(input)?;
@@ -46,25 +61,11 @@ fn exec(
     let parsed =
         ucl_parser::parse_commands(&ucl).map_err(|e| anyhow::anyhow!("Parse error: {:?}", e))?;
 
-    // Convert to operations and execute
-    let mut results = Vec::new();
-    let engine = Engine::new();
-
-    for cmd in &parsed {
-        match command_to_operation(cmd) {
-            Ok(op) => {
-                let result = engine.execute(&mut doc, op)?;
-                results.push(result);
-            }
-            Err(e) => {
-                results.push(ucm_engine::OperationResult {
-                    success: false,
-                    affected_blocks: vec![],
-                    warnings: vec![],
-                    error: Some(e.to_string()),
-                });
-            }
-        }
+    let mut engine = Engine::new();
+    engine.set_dry_run(dry_run);
+    let results = execute_commands(&engine, &mut doc, &parsed)?;
+    if dry_run {
+        return report_dry_run(&engine.dry_run_log(), &results, format);
     }
 
     let success_count = results.iter().filter(|r| r.success).count();
@@ -101,6 +102,43 @@ fn exec(
     Ok(())
 }
 
+fn report_dry_run(
+    log: &DryRunLog,
+    results: &[OperationResult],
+    format: OutputFormat,
+) -> Result<()> {
+    let errors: Vec<_> = results.iter().filter_map(|r| r.error.clone()).collect();
+    match format {
+        OutputFormat::Json => {
+            #[derive(Serialize)]
+            struct DryRunResult<'a> {
+                dry_run: bool,
+                operations: &'a [DryRunEntry],
+                errors: Vec<String>,
+            }
+            let result = DryRunResult {
+                dry_run: true,
+                operations: &log.entries,
+                errors,
+            };
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        OutputFormat::Text => {
+            print_success(&format!(
+                "Dry run: {} operations would be applied",
+                log.len()
+            ));
+            for entry in &log.entries {
+                println!("  {}", entry.description);
+            }
+            for error in errors {
+                print_error(&error);
+            }
+        }
+    }
+    Ok(())
+}
+
 fn parse(commands: Option<String>, file: Option<String>, format: OutputFormat) -> Result<()> {
     // Get UCL from argument, file, or stdin
     let ucl = if let Some(cmd) = commands {
@@ -159,7 +197,29 @@ fn parse(commands: Option<String>, file: Option<String>, format: OutputFormat) -
     Ok(())
 }
 
-fn command_to_operation(cmd: &ucl_parser::Command) -> Result<Operation> {
+/// Run parsed commands in order; commands that cannot be converted to an
+/// operation produce a failed result instead of stopping the run
+pub(crate) fn execute_commands(
+    engine: &Engine,
+    doc: &mut Document,
+    commands: &[ucl_parser::Command],
+) -> Result<Vec<OperationResult>> {
+    let mut results = Vec::new();
+    for cmd in commands {
+        match command_to_operation(cmd) {
+            Ok(op) => results.push(engine.execute(doc, op)?),
+            Err(e) => results.push(OperationResult {
+                success: false,
+                affected_blocks: vec![],
+                warnings: vec![],
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+    Ok(results)
+}
+
+pub(crate) fn command_to_operation(cmd: &ucl_parser::Command) -> Result<Operation> {
     match cmd {
         ucl_parser::Command::Edit(e) => {
             let block_id = BlockId::from_str(&e.block_id)
@@ -183,6 +243,19 @@ fn command_to_operation(cmd: &ucl_parser::Command) -> Result<Operation> {
             let content = match a.content_type {
                 ucl_parser::ContentType::Text => Content::text(&a.content),
                 ucl_parser::ContentType::Code => Content::code("", &a.content),
+                ucl_parser::ContentType::Form => Content::Form(
+                    serde_json::from_str(&a.content)
+                        .map_err(|e| anyhow::anyhow!("Invalid form content: {}", e))?,
+                ),
+                ucl_parser::ContentType::Diff => {
+                    let format = match a.properties.get("format") {
+                        None => DiffFormat::Unified,
+                        Some(ucl_parser::Value::String(s)) => DiffFormat::parse(s)
+                            .ok_or_else(|| anyhow::anyhow!("Unknown diff format: {}", s))?,
+                        Some(other) => anyhow::bail!("Invalid diff format: {:?}", other),
+                    };
+                    Content::diff(format, &a.content)
+                }
                 _ => Content::text(&a.content),
             };
             Ok(Operation::Append {
@@ -251,11 +324,29 @@ fn command_to_operation(cmd: &ucl_parser::Command) -> Result<Operation> {
             let target = BlockId::from_str(&l.target_id)
                 .map_err(|_| anyhow::anyhow!("Invalid target ID: {}", l.target_id))?;
             let edge_type = EdgeType::from_str(&l.edge_type).unwrap_or(EdgeType::References);
+            if !l.additional_sources.is_empty() {
+                let mut sources = vec![source];
+                for id in &l.additional_sources {
+                    sources.push(
+                        BlockId::from_str(id)
+                            .map_err(|_| anyhow::anyhow!("Invalid source ID: {}", id))?,
+                    );
+                }
+                return Ok(Operation::HyperLink {
+                    sources,
+                    edge_type,
+                    target,
+                    metadata: None,
+                });
+            }
             Ok(Operation::Link {
                 source,
                 edge_type,
                 target,
                 metadata: None,
+                weight: l.weight,
+                valid_from: l.valid_from,
+                valid_to: l.valid_to,
             })
         }
         ucl_parser::Command::Unlink(u) => {
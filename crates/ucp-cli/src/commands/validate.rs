@@ -2,6 +2,7 @@
 
 use anyhow::Result;
 use serde::Serialize;
+use ucm_core::DocumentSchema;
 use ucm_engine::validate::ResourceLimits;
 use ucm_engine::ValidationPipeline;
 
@@ -10,7 +11,7 @@ use crate::output::{print_validation_result, read_document};
 
 /// Serializable version of ValidationResult
 #[derive(Serialize)]
-struct ValidationResultJson {
+pub(crate) struct ValidationResultJson {
     valid: bool,
     issues: Vec<ValidationIssueJson>,
 }
@@ -20,6 +21,10 @@ struct ValidationIssueJson {
     severity: String,
     code: String,
     message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<usize>,
 }
 
 impl From<&ucm_engine::ValidationResult> for ValidationResultJson {
@@ -33,6 +38,8 @@ impl From<&ucm_engine::ValidationResult> for ValidationResultJson {
                     severity: format!("{:?}", i.severity),
                     code: format!("{:?}", i.code),
                     message: i.message.clone(),
+                    line: i.location.as_ref().map(|l| l.line),
+                    column: i.location.as_ref().map(|l| l.column),
                 })
                 .collect(),
         }
@@ -44,6 +51,8 @@ pub fn validate(
     input: Option<String>,
     max_blocks: Option<usize>,
     max_depth: Option<usize>,
+    check_code: bool,
+    schema: Option<String>,
     format: OutputFormat,
 ) -> Result<()> {
     let doc = read_document(input)?;
@@ -58,8 +67,13 @@ pub fn validate(
         limits.max_nesting_depth = max;
     }
 
-    let pipeline = ValidationPipeline::with_limits(limits);
-    let result = pipeline.validate_document(&doc);
+    let pipeline = ValidationPipeline::with_limits(limits).with_code_syntax_check(check_code);
+    let mut result = pipeline.validate_document(&doc);
+
+    if let Some(path) = schema {
+        let schema = DocumentSchema::from_json(&std::fs::read_to_string(&path)?)?;
+        result.merge(doc.validate_against_schema(&schema));
+    }
 
     match format {
         OutputFormat::Json => {
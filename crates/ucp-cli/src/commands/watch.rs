@@ -0,0 +1,277 @@
+//! Re-run a UCL file against a baseline document whenever it changes
+
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+use ucm_core::{BlockId, Document};
+use ucm_engine::Engine;
+
+use crate::cli::OutputFormat;
+use crate::commands::ucl::execute_commands;
+use crate::output::{content_preview, print_error, print_info, write_document};
+use crate::state::read_stateful_document;
+
+/// How long to wait for an editor to finish writing before re-running
+const SETTLE: Duration = Duration::from_millis(100);
+
+/// Blocks that differ between the baseline and the result of a run
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct BlockChanges {
+    #[serde(serialize_with = "block_ids")]
+    added: Vec<BlockId>,
+    #[serde(serialize_with = "block_ids")]
+    removed: Vec<BlockId>,
+    /// Content changed or the block moved to another parent
+    #[serde(serialize_with = "block_ids")]
+    modified: Vec<BlockId>,
+}
+
+/// Serialize ids in their `blk_...` form
+fn block_ids<S: serde::Serializer>(ids: &[BlockId], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(ids.iter().map(|id| id.to_string()))
+}
+
+impl BlockChanges {
+    fn between(before: &Document, after: &Document) -> Self {
+        let mut changes = Self::default();
+        for (id, block) in &after.blocks {
+            match before.get_block(id) {
+                None => changes.added.push(*id),
+                Some(old)
+                    if old.content != block.content || before.parent(id) != after.parent(id) =>
+                {
+                    changes.modified.push(*id)
+                }
+                Some(_) => {}
+            }
+        }
+        changes.removed = before
+            .blocks
+            .keys()
+            .filter(|id| !after.blocks.contains_key(id))
+            .copied()
+            .collect();
+        for ids in [
+            &mut changes.added,
+            &mut changes.removed,
+            &mut changes.modified,
+        ] {
+            ids.sort_by_key(|id| id.to_string());
+        }
+        changes
+    }
+}
+
+/// Outcome of executing the file once
+#[derive(Debug, Serialize)]
+struct Run {
+    success: bool,
+    commands_executed: usize,
+    commands_succeeded: usize,
+    errors: Vec<String>,
+    #[serde(flatten)]
+    changes: BlockChanges,
+}
+
+/// Watch `file`, executing it against a fresh copy of the baseline on every
+/// save. Runs until interrupted.
+pub fn watch(
+    input: Option<String>,
+    output: Option<String>,
+    file: String,
+    base_snapshot: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let stateful = read_stateful_document(input)?;
+    let baseline = match base_snapshot {
+        Some(name) => stateful
+            .state()
+            .snapshots
+            .iter()
+            .find(|s| s.name == name)
+            .ok_or_else(|| anyhow!("Snapshot '{}' not found", name))?
+            .restore()?,
+        None => stateful.document,
+    };
+
+    if let OutputFormat::Text = format {
+        print_info(&format!("Watching {} (Ctrl-C to stop)", file));
+    }
+    watch_file(&file, || rerun(&baseline, &file, output.as_deref(), format))
+}
+
+/// Call `on_save` once, then again every time `file` is written. Runs until
+/// interrupted.
+pub fn watch_file(file: &str, mut on_save: impl FnMut()) -> Result<()> {
+    let path = Path::new(file);
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Not a file: {}", file))?
+        .to_owned();
+    // Watch the directory: editors often save by replacing the file
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+    on_save();
+
+    for event in &rx {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                print_error(&format!("Watch error: {}", e));
+                continue;
+            }
+        };
+        let touches_file = event
+            .paths
+            .iter()
+            .any(|p| p.file_name() == Some(file_name.as_os_str()));
+        if !touches_file || !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+            continue;
+        }
+        std::thread::sleep(SETTLE);
+        while rx.try_recv().is_ok() {}
+        on_save();
+    }
+
+    Ok(())
+}
+
+/// Execute the file once and report; errors are printed, never fatal
+fn rerun(baseline: &Document, file: &str, output: Option<&str>, format: OutputFormat) {
+    let result = std::fs::read_to_string(file)
+        .map_err(Into::into)
+        .and_then(|ucl| run(baseline, &ucl))
+        .and_then(|(doc, run)| {
+            report(&doc, &run, format)?;
+            if let Some(path) = output {
+                write_document(&doc, Some(path.to_string()))?;
+            }
+            Ok(())
+        });
+    if let Err(e) = result {
+        match format {
+            OutputFormat::Json => {
+                println!(
+                    "{}",
+                    serde_json::json!({ "success": false, "errors": [e.to_string()] })
+                )
+            }
+            OutputFormat::Text => print_error(&e.to_string()),
+        }
+    }
+}
+
+/// Execute `ucl` against a copy of `baseline`
+fn run(baseline: &Document, ucl: &str) -> Result<(Document, Run)> {
+    let parsed = ucl_parser::parse_commands(ucl).map_err(|e| anyhow!("Parse error: {}", e))?;
+    let mut doc = baseline.clone();
+    let results = execute_commands(&Engine::new(), &mut doc, &parsed)?;
+
+    let errors: Vec<String> = results
+        .iter()
+        .filter(|r| !r.success)
+        .map(|r| r.error.clone().unwrap_or_else(|| "failed".into()))
+        .collect();
+    let run = Run {
+        success: errors.is_empty(),
+        commands_executed: results.len(),
+        commands_succeeded: results.len() - errors.len(),
+        errors,
+        changes: BlockChanges::between(baseline, &doc),
+    };
+    Ok((doc, run))
+}
+
+fn report(doc: &Document, run: &Run, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string(run)?),
+        OutputFormat::Text => {
+            println!(
+                "{} {}/{} commands succeeded",
+                chrono::Local::now()
+                    .format("[%H:%M:%S]")
+                    .to_string()
+                    .dimmed(),
+                run.commands_succeeded,
+                run.commands_executed
+            );
+            for error in &run.errors {
+                print_error(error);
+            }
+            let preview = |id: &BlockId| {
+                doc.get_block(id)
+                    .map(|b| content_preview(&b.content, 60))
+                    .unwrap_or_default()
+            };
+            for id in &run.changes.added {
+                println!("  {} {} {}", "+".green(), id, preview(id));
+            }
+            for id in &run.changes.modified {
+                println!("  {} {} {}", "~".yellow(), id, preview(id));
+            }
+            for id in &run.changes.removed {
+                println!("  {} {}", "-".red(), id);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ucm_core::{Block, Content};
+
+    #[test]
+    fn test_runs_start_from_the_baseline() {
+        let mut baseline = Document::create();
+        let root = baseline.root;
+        let existing = baseline
+            .add_block(Block::new(Content::text("Draft"), None), &root)
+            .unwrap();
+
+        let ucl = format!(
+            "EDIT {} SET content.text = \"Final\"\nAPPEND {} text :: \"New\"",
+            existing, root
+        );
+        let (doc, first) = run(&baseline, &ucl).unwrap();
+        assert!(first.success);
+        assert_eq!(first.commands_executed, 2);
+        assert_eq!(first.changes.added.len(), 1);
+        assert_eq!(first.changes.modified, vec![existing]);
+        assert!(first.changes.removed.is_empty());
+        assert_eq!(doc.block_count(), 3);
+
+        // Re-running the same file yields the same document, not a second append
+        let (again, second) = run(&baseline, &ucl).unwrap();
+        assert_eq!(again.block_count(), 3);
+        assert_eq!(second.changes, first.changes);
+
+        let (_, deleted) = run(&baseline, &format!("DELETE {}", existing)).unwrap();
+        assert_eq!(deleted.changes.removed, vec![existing]);
+    }
+
+    #[test]
+    fn test_errors_are_reported_per_run() {
+        let baseline = Document::create();
+        assert!(run(&baseline, "NOT UCL").is_err());
+
+        let err = run(&baseline, "DELETE blk_000000000000000000000001").unwrap_err();
+        assert!(err.to_string().contains("Block not found"));
+
+        // Commands that cannot become operations fail without stopping the run
+        let (_, failed) = run(&baseline, "EDIT blk_1 SET content.text = \"x\"").unwrap();
+        assert!(!failed.success);
+        assert_eq!(failed.errors, vec!["Invalid block ID: blk_1"]);
+    }
+}
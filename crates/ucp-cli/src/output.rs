@@ -1,147 +1,19 @@
 //! Output formatting utilities
 
 use colored::Colorize;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::Serialize;
 use std::io;
 use tabled::{Table, Tabled};
-use ucm_core::{Block, BlockId, Document, Edge};
-
-use crate::cli::OutputFormat;
-
-/// Serializable document representation for JSON I/O
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DocumentJson {
-    pub id: String,
-    pub root: String,
-    pub structure: HashMap<String, Vec<String>>,
-    pub blocks: HashMap<String, Block>,
-    #[serde(default)]
-    pub metadata: DocumentMetadataJson,
-    #[serde(default)]
-    pub version: u64,
-}
-
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct DocumentMetadataJson {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub title: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<String>,
-    #[serde(default)]
-    pub authors: Vec<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub created_at: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub modified_at: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub language: Option<String>,
-    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub custom: HashMap<String, serde_json::Value>,
-}
-
-impl DocumentJson {
-    /// Convert Document to JSON-serializable form
-    pub fn from_document(doc: &Document) -> Self {
-        let structure: HashMap<String, Vec<String>> = doc
-            .structure
-            .iter()
-            .map(|(k, v)| (k.to_string(), v.iter().map(|id| id.to_string()).collect()))
-            .collect();
-
-        let blocks: HashMap<String, Block> = doc
-            .blocks
-            .iter()
-            .map(|(k, v)| (k.to_string(), v.clone()))
-            .collect();
-
-        Self {
-            id: doc.id.0.clone(),
-            root: doc.root.to_string(),
-            structure,
-            blocks,
-            metadata: DocumentMetadataJson {
-                title: doc.metadata.title.clone(),
-                description: doc.metadata.description.clone(),
-                authors: doc.metadata.authors.clone(),
-                created_at: Some(doc.metadata.created_at.to_rfc3339()),
-                modified_at: Some(doc.metadata.modified_at.to_rfc3339()),
-                language: doc.metadata.language.clone(),
-                custom: doc.metadata.custom.clone(),
-            },
-            version: doc.version.counter,
-        }
-    }
-
-    /// Convert back to Document
-    pub fn to_document(&self) -> anyhow::Result<Document> {
-        use std::str::FromStr;
-        use ucm_core::{DocumentId, DocumentMetadata};
-
-        let root = BlockId::from_str(&self.root)
-            .map_err(|_| anyhow::anyhow!("Invalid root block ID: {}", self.root))?;
-
-        let mut structure: HashMap<BlockId, Vec<BlockId>> = HashMap::new();
-        for (k, v) in &self.structure {
-            let parent = BlockId::from_str(k)
-                .map_err(|_| anyhow::anyhow!("Invalid block ID in structure: {}", k))?;
-            let children: Result<Vec<BlockId>, _> = v
-                .iter()
-                .map(|id| {
-                    BlockId::from_str(id)
-                        .map_err(|_| anyhow::anyhow!("Invalid block ID in structure: {}", id))
-                })
-                .collect();
-            structure.insert(parent, children?);
-        }
-
-        let mut blocks: HashMap<BlockId, Block> = HashMap::new();
-        for (k, v) in &self.blocks {
-            let id =
-                BlockId::from_str(k).map_err(|_| anyhow::anyhow!("Invalid block ID: {}", k))?;
-            blocks.insert(id, v.clone());
-        }
-
-        let now = chrono::Utc::now();
-        let metadata = DocumentMetadata {
-            title: self.metadata.title.clone(),
-            description: self.metadata.description.clone(),
-            authors: self.metadata.authors.clone(),
-            created_at: self
-                .metadata
-                .created_at
-                .as_ref()
-                .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
-                .map(|dt| dt.with_timezone(&chrono::Utc))
-                .unwrap_or(now),
-            modified_at: self
-                .metadata
-                .modified_at
-                .as_ref()
-                .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
-                .map(|dt| dt.with_timezone(&chrono::Utc))
-                .unwrap_or(now),
-            language: self.metadata.language.clone(),
-            custom: self.metadata.custom.clone(),
-        };
-
-        let mut doc = Document {
-            id: DocumentId::new(&self.id),
-            root,
-            structure,
-            blocks,
-            metadata,
-            indices: Default::default(),
-            edge_index: Default::default(),
-            version: ucm_core::DocumentVersion {
-                counter: self.version,
-                timestamp: chrono::Utc::now(),
-                state_hash: [0u8; 8],
-            },
-        };
-        doc.rebuild_indices();
-        Ok(doc)
-    }
+use ucm_core::{Block, BlockId, Document, DocumentStats, Edge};
+
+/// How command results are printed, chosen with the global `--format` flag
+#[derive(Clone, Copy, Debug, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// A JSON value on stdout, for scripts
+    Json,
 }
 
 /// Print a value in the specified format
@@ -245,13 +117,36 @@ pub fn print_block(block: &Block, show_content: bool) {
 pub fn content_preview(content: &ucm_core::Content, max_len: usize) -> String {
     let full = match content {
         ucm_core::Content::Text(text) => text.text.clone(),
+        ucm_core::Content::RichText(rich) => rich.plain_text(),
         ucm_core::Content::Code(code) => format!("```{}\n{}\n```", code.language, code.source),
+        ucm_core::Content::CompressedCode { language, .. } => format!(
+            "```{}\n{}\n```",
+            language,
+            content.decompress().unwrap_or_default()
+        ),
         ucm_core::Content::Table(table) => format!(
             "Table: {} columns, {} rows",
             table.columns.len(),
             table.rows.len()
         ),
+        ucm_core::Content::Spreadsheet(sheet) => format!(
+            "Spreadsheet: {} columns, {} rows",
+            sheet.headers.len(),
+            sheet.rows.len()
+        ),
         ucm_core::Content::Math(math) => format!("Math: {}", math.expression),
+        ucm_core::Content::Diagram(diagram) => {
+            format!(
+                "```{}\n{}\n```",
+                diagram.dialect.fence_tag(),
+                diagram.source
+            )
+        }
+        ucm_core::Content::Diff(diff) => format!("```diff\n{}\n```", diff.patch),
+        ucm_core::Content::GeoJson(value) => format!("GeoJSON: {}", value),
+        ucm_core::Content::Form(form) => {
+            format!("Form: {} ({} fields)", form.title, form.fields.len())
+        }
         ucm_core::Content::Media(media) => {
             format!("Media: {:?} - {:?}", media.media_type, media.source)
         }
@@ -313,6 +208,50 @@ pub fn print_document_info(doc: &Document) {
     println!("{}", "═".repeat(60).cyan());
 }
 
+/// Print document statistics
+pub fn print_document_stats(stats: &DocumentStats) {
+    fn print_counts(counts: &std::collections::HashMap<String, usize>) {
+        let mut counts: Vec<_> = counts.iter().collect();
+        counts.sort();
+        for (name, count) in counts {
+            println!("  {}: {}", name, count);
+        }
+    }
+
+    println!("{}", "═".repeat(60).cyan());
+    println!("{}", "Document Statistics".cyan().bold());
+    println!("{}", "═".repeat(60).cyan());
+
+    println!(
+        "{}: {}",
+        "Blocks".white().bold(),
+        stats.total_blocks.to_string().green()
+    );
+    print_counts(&stats.block_count_by_type);
+    println!(
+        "{}: {}",
+        "Edges".white().bold(),
+        stats.total_edges.to_string().blue()
+    );
+    print_counts(&stats.edge_count_by_type);
+    println!("{}: {}", "Max Depth".white(), stats.max_depth);
+    println!(
+        "{}: {:.2}",
+        "Avg Children".white(),
+        stats.average_children_per_block
+    );
+    println!("{}: {}", "Content Bytes".white(), stats.total_content_bytes);
+    println!("{}: {}", "Sections".white(), stats.section_count);
+    println!("{}: {}", "Snapshots".white(), stats.snapshot_count);
+    println!(
+        "{}: {}",
+        "Orphans".white(),
+        stats.orphan_blocks.to_string().yellow()
+    );
+
+    println!("{}", "═".repeat(60).cyan());
+}
+
 /// Print a tree representation of the document
 pub fn print_tree(doc: &Document, max_depth: Option<usize>, show_ids: bool) {
     fn print_tree_recursive(
@@ -487,14 +426,19 @@ pub fn print_validation_result(result: &ucm_engine::ValidationResult) {
 
     if !result.issues.is_empty() {
         println!("\n{}", "Issues:".yellow().bold());
-        for issue in &result.issues {
-            let severity_str = match issue.severity {
-                ucm_core::ValidationSeverity::Error => "ERROR".red().bold(),
-                ucm_core::ValidationSeverity::Warning => "WARN".yellow().bold(),
-                ucm_core::ValidationSeverity::Info => "INFO".blue().bold(),
-            };
-            println!("  {} {}", severity_str, issue.message);
-        }
+        print_validation_issues(&result.issues);
+    }
+}
+
+/// Print validation issues one per line with their severity
+pub fn print_validation_issues(issues: &[ucm_core::ValidationIssue]) {
+    for issue in issues {
+        let severity_str = match issue.severity {
+            ucm_core::ValidationSeverity::Error => "ERROR".red().bold(),
+            ucm_core::ValidationSeverity::Warning => "WARN".yellow().bold(),
+            ucm_core::ValidationSeverity::Info => "INFO".blue().bold(),
+        };
+        println!("  {} {}", severity_str, issue.message);
     }
 }
 
@@ -508,14 +452,12 @@ pub fn read_document(input: Option<String>) -> anyhow::Result<Document> {
         buffer
     };
 
-    let doc_json: DocumentJson = serde_json::from_str(&json)?;
-    doc_json.to_document()
+    Ok(Document::from_portable_json(&json)?)
 }
 
 /// Write document to file or stdout
 pub fn write_document(doc: &Document, output: Option<String>) -> anyhow::Result<()> {
-    let doc_json = DocumentJson::from_document(doc);
-    let json = serde_json::to_string_pretty(&doc_json)?;
+    let json = doc.to_portable_json_pretty()?;
 
     if let Some(path) = output {
         std::fs::write(&path, &json)?;
@@ -526,16 +468,28 @@ pub fn write_document(doc: &Document, output: Option<String>) -> anyhow::Result<
     Ok(())
 }
 
+/// Report a file written by a command
+pub fn print_written(path: &str, bytes: usize, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::json!({ "output": path, "bytes": bytes })),
+        OutputFormat::Text => print_success(&format!("Written to {}", path)),
+    }
+}
+
 /// Read content from file
 pub fn read_file(path: &str) -> anyhow::Result<String> {
     Ok(std::fs::read_to_string(path)?)
 }
 
 /// Write content to file or stdout
-pub fn write_output(content: &str, output: Option<String>) -> anyhow::Result<()> {
+pub fn write_output(
+    content: &str,
+    output: Option<String>,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
     if let Some(path) = output {
         std::fs::write(&path, content)?;
-        print_success(&format!("Written to {}", path));
+        print_written(&path, content.len(), format);
     } else {
         println!("{}", content);
     }
@@ -545,12 +499,12 @@ pub fn write_output(content: &str, output: Option<String>) -> anyhow::Result<()>
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ucm_core::{Block, Content};
+    use ucm_core::{Block, Content, PortableDocument};
 
     #[test]
     fn test_document_json_roundtrip() {
         let doc = Document::create();
-        let json = DocumentJson::from_document(&doc);
+        let json = PortableDocument::from_document(&doc);
         let restored = json.to_document().expect("Should restore document");
 
         assert_eq!(doc.id.0, restored.id.0);
@@ -566,7 +520,7 @@ mod tests {
             .add_block(block, &doc.root.clone())
             .expect("Should add block");
 
-        let json = DocumentJson::from_document(&doc);
+        let json = PortableDocument::from_document(&doc);
         let restored = json.to_document().expect("Should restore document");
 
         assert!(restored.get_block(&block_id).is_some());
@@ -582,7 +536,7 @@ mod tests {
             .custom
             .insert("profile".to_string(), serde_json::json!("codegraph"));
 
-        let json = DocumentJson::from_document(&doc);
+        let json = PortableDocument::from_document(&doc);
         let restored = json.to_document().expect("Should restore document");
 
         assert_eq!(restored.metadata.title, Some("Test Title".to_string()));
@@ -5,11 +5,9 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use ucm_core::{BlockId, Document};
+use ucm_core::{BlockId, Document, PortableDocument};
 use ucp_api::CodeGraphContextSession;
 
-use crate::output::DocumentJson;
-
 /// Complete CLI state that can be serialized with the document
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CliState {
@@ -152,19 +150,17 @@ impl SnapshotInfo {
         description: Option<String>,
         doc: &Document,
     ) -> anyhow::Result<Self> {
-        let doc_json = DocumentJson::from_document(doc);
         Ok(Self {
             name,
             description,
             created_at: chrono::Utc::now().to_rfc3339(),
             block_count: doc.block_count(),
-            document_json: serde_json::to_string(&doc_json)?,
+            document_json: doc.to_portable_json()?,
         })
     }
 
     pub fn restore(&self) -> anyhow::Result<Document> {
-        let doc_json: DocumentJson = serde_json::from_str(&self.document_json)?;
-        doc_json.to_document()
+        Ok(Document::from_portable_json(&self.document_json)?)
     }
 }
 
@@ -180,21 +176,19 @@ pub struct TransactionState {
 
 impl TransactionState {
     pub fn new(name: Option<String>, doc: &Document) -> anyhow::Result<Self> {
-        let doc_json = DocumentJson::from_document(doc);
         Ok(Self {
             name,
             started_at: chrono::Utc::now().to_rfc3339(),
             savepoints: Vec::new(),
-            original_document: serde_json::to_string(&doc_json)?,
+            original_document: doc.to_portable_json()?,
         })
     }
 
     pub fn create_savepoint(&mut self, name: String, doc: &Document) -> anyhow::Result<()> {
-        let doc_json = DocumentJson::from_document(doc);
         self.savepoints.push(SavepointInfo {
             name,
             created_at: chrono::Utc::now().to_rfc3339(),
-            document_json: serde_json::to_string(&doc_json)?,
+            document_json: doc.to_portable_json()?,
         });
         Ok(())
     }
@@ -203,16 +197,16 @@ impl TransactionState {
     pub fn rollback_to_savepoint(&self, name: &str) -> anyhow::Result<Option<Document>> {
         for savepoint in self.savepoints.iter().rev() {
             if savepoint.name == name {
-                let doc_json: DocumentJson = serde_json::from_str(&savepoint.document_json)?;
-                return Ok(Some(doc_json.to_document()?));
+                return Ok(Some(Document::from_portable_json(
+                    &savepoint.document_json,
+                )?));
             }
         }
         Ok(None)
     }
 
     pub fn get_original_document(&self) -> anyhow::Result<Document> {
-        let doc_json: DocumentJson = serde_json::from_str(&self.original_document)?;
-        doc_json.to_document()
+        Ok(Document::from_portable_json(&self.original_document)?)
     }
 }
 
@@ -235,7 +229,7 @@ pub struct StatefulDocument {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct StatefulDocumentJson {
     #[serde(flatten)]
-    document: DocumentJson,
+    document: PortableDocument,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     cli_state: Option<CliState>,
 }
@@ -286,8 +280,8 @@ pub fn read_stateful_document(input: Option<String>) -> anyhow::Result<StatefulD
         });
     }
 
-    // Try as plain DocumentJson
-    if let Ok(doc_json) = serde_json::from_str::<DocumentJson>(&json) {
+    // Try as plain PortableDocument
+    if let Ok(doc_json) = serde_json::from_str::<PortableDocument>(&json) {
         let doc = doc_json.to_document()?;
         return Ok(StatefulDocument::from_document(doc));
     }
@@ -300,7 +294,7 @@ pub fn write_stateful_document(
     doc: &StatefulDocument,
     output: Option<String>,
 ) -> anyhow::Result<()> {
-    let doc_json = DocumentJson::from_document(&doc.document);
+    let doc_json = PortableDocument::from_document(&doc.document);
 
     // Create combined JSON
     let stateful_json = StatefulDocumentJson {
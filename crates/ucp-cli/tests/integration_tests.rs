@@ -79,6 +79,7 @@ fn test_block_subcommands() {
     assert!(out.contains("move"));
     assert!(out.contains("list"));
     assert!(out.contains("update"));
+    assert!(out.contains("add-table"));
 }
 
 #[test]
@@ -131,6 +132,7 @@ fn test_import_subcommands() {
 
     assert!(out.contains("markdown"));
     assert!(out.contains("html"));
+    assert!(out.contains("json"));
 }
 
 #[test]
@@ -140,6 +142,8 @@ fn test_export_subcommands() {
 
     assert!(out.contains("markdown"));
     assert!(out.contains("json"));
+    assert!(out.contains("docx"));
+    assert!(out.contains("prompt"));
 }
 
 #[test]
@@ -151,6 +155,29 @@ fn test_ucl_subcommands() {
     assert!(out.contains("parse"));
 }
 
+#[test]
+fn test_completions_for_every_shell() {
+    for shell in ["bash", "zsh", "fish", "powershell", "elvish"] {
+        let output = run_cli(&["completions", shell]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        let script = stdout(&output);
+        for command in ["create", "block", "snapshot", "import", "export", "ucl"] {
+            assert!(script.contains(command), "{} lacks {}", shell, command);
+        }
+    }
+    assert!(!run_cli(&["completions", "tcsh"]).status.success());
+}
+
+#[test]
+fn test_ucl_watch_requires_file() {
+    let output = run_cli(&["ucl", "exec", "--watch", "-c", "DELETE blk_1"]);
+    assert!(!output.status.success());
+
+    let output = run_cli(&["ucl", "exec", "-F", "x.ucl", "--base-snapshot", "v1"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--watch"));
+}
+
 #[test]
 fn test_agent_subcommands() {
     let output = run_cli(&["agent", "--help"]);
@@ -187,6 +214,8 @@ fn test_codegraph_subcommands() {
     assert!(out.contains("build"));
     assert!(out.contains("inspect"));
     assert!(out.contains("prompt"));
+    assert!(out.contains("stats"));
+    assert!(out.contains("dead-code"));
 }
 
 #[test]
@@ -282,6 +311,24 @@ mod with_temp_file {
         assert!(tree.get("children").is_some());
     }
 
+    #[test]
+    fn test_tree_stats() {
+        let temp_file = create_temp_doc();
+        let path = temp_file.path().to_str().unwrap();
+
+        let output = run_cli(&["tree", "--input", path, "--stats", "--format", "json"]);
+        let out = stdout(&output);
+
+        let result: serde_json::Value =
+            serde_json::from_str(&out).expect("Output should be valid JSON");
+        assert!(result["tree"].get("children").is_some());
+        assert!(result["stats"]["total_blocks"].as_u64().unwrap() >= 1);
+        assert_eq!(result["stats"]["orphan_blocks"], 0);
+
+        let output = run_cli(&["tree", "--input", path, "--stats"]);
+        assert!(stdout(&output).contains("Document Statistics"));
+    }
+
     #[test]
     fn test_block_list() {
         let temp_file = create_temp_doc();
@@ -326,6 +373,71 @@ mod with_temp_file {
         assert!(result.get("issues").is_some());
     }
 
+    #[test]
+    fn test_validate_schema() {
+        let temp_file = create_temp_doc();
+        let path = temp_file.path().to_str().unwrap();
+        let mut schema = NamedTempFile::new().expect("Failed to create temp file");
+        schema
+            .write_all(br#"{"name": "note", "required_roles": ["title"]}"#)
+            .unwrap();
+
+        let output = run_cli(&[
+            "--format",
+            "json",
+            "validate",
+            "--input",
+            path,
+            "--schema",
+            schema.path().to_str().unwrap(),
+        ]);
+        assert!(!output.status.success());
+        let result: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(result["valid"], false);
+        let issues = result["issues"].as_array().unwrap();
+        assert!(issues
+            .iter()
+            .any(|issue| issue["code"] == "E200SchemaViolation"
+                && issue["message"].as_str().unwrap().contains("title")));
+    }
+
+    #[test]
+    fn test_validate_check_code() {
+        let mut markdown = NamedTempFile::new().expect("Failed to create temp file");
+        markdown
+            .write_all(b"# Code\n\n```rs\nfn main() {\n    let x = ;\n}\n```\n")
+            .unwrap();
+        let doc_file = NamedTempFile::new().expect("Failed to create temp file");
+        let doc_path = doc_file.path().to_str().unwrap();
+        let output = run_cli(&[
+            "import",
+            "markdown",
+            markdown.path().to_str().unwrap(),
+            "-o",
+            doc_path,
+        ]);
+        assert!(output.status.success(), "{}", stderr(&output));
+
+        let output = run_cli(&["--format", "json", "validate", "--input", doc_path]);
+        let result: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(result["issues"], serde_json::json!([]));
+
+        let output = run_cli(&[
+            "--format",
+            "json",
+            "validate",
+            "--input",
+            doc_path,
+            "--check-code",
+        ]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        let result: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        let issue = &result["issues"][0];
+        assert_eq!(issue["severity"], "Warning");
+        assert_eq!(issue["line"], 2);
+        assert!(issue["message"].as_str().unwrap().contains("(rust) line 2"));
+    }
+
     #[test]
     fn test_nav_children() {
         let temp_file = create_temp_doc();
@@ -368,6 +480,301 @@ mod with_temp_file {
         assert!(doc.get("blocks").is_some());
     }
 
+    #[test]
+    fn test_export_import_json_roundtrip() {
+        let temp_file = create_temp_doc();
+        let path = temp_file.path().to_str().unwrap();
+
+        let exported = NamedTempFile::new().expect("Failed to create temp file");
+        let exported_path = exported.path().to_str().unwrap();
+        let output = run_cli(&[
+            "export",
+            "json",
+            "--input",
+            path,
+            "--output",
+            exported_path,
+            "--pretty",
+        ]);
+        assert!(output.status.success(), "{}", stderr(&output));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(exported_path).unwrap()).unwrap();
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["metadata"]["title"], "Test Document");
+
+        let output = run_cli(&["--format", "json", "import", "json", exported_path]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        let imported: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(imported, json);
+    }
+
+    #[test]
+    fn test_import_json_rejects_invalid_document() {
+        let mut file = NamedTempFile::new().expect("Failed to create temp file");
+        file.write_all(b"{\"id\": 1}").unwrap();
+
+        let output = run_cli(&["import", "json", file.path().to_str().unwrap()]);
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn test_block_add_table_from_csv() {
+        let temp_file = create_temp_doc();
+        let path = temp_file.path().to_str().unwrap();
+        let mut csv = NamedTempFile::new().expect("Failed to create temp file");
+        csv.write_all(b"name,note\n\"Smith, J.\",a|b\n").unwrap();
+
+        let output = run_cli(&[
+            "--format",
+            "json",
+            "block",
+            "add-table",
+            "--input",
+            path,
+            "--output",
+            path,
+            "--from-csv",
+            csv.path().to_str().unwrap(),
+        ]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        let result: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(result["rows"], 2);
+        assert_eq!(result["columns"], 2);
+
+        let output = run_cli(&["export", "markdown", "--input", path]);
+        assert!(stdout(&output).contains("| Smith, J. | a\\|b |"));
+    }
+
+    #[test]
+    fn test_block_add_table_reports_ragged_row() {
+        let mut csv = NamedTempFile::new().expect("Failed to create temp file");
+        csv.write_all(b"a,b\n1,2\n3\n").unwrap();
+
+        let output = run_cli(&[
+            "block",
+            "add-table",
+            "--from-csv",
+            csv.path().to_str().unwrap(),
+        ]);
+        assert!(!output.status.success());
+        assert!(stderr(&output).contains("row 3 has 1 fields, expected 2"));
+    }
+
+    #[test]
+    fn test_import_prints_translation_warnings() {
+        let mut md = NamedTempFile::new().expect("Failed to create temp file");
+        write!(md, "# Title\n\n<!-- draft -->\n\nText.\n").unwrap();
+        let path = md.path().to_str().unwrap();
+
+        let output = run_cli(&["import", "markdown", path, "--format", "json"]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert!(
+            stderr(&output).contains("line 3: HTML comment kept as raw markdown [preserved_raw]")
+        );
+        let doc: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(doc["blocks"].as_object().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_ucl_exec_dry_run() {
+        let temp_file = create_temp_doc();
+        let path = temp_file.path().to_str().unwrap();
+        let before = std::fs::read_to_string(path).unwrap();
+        let append = "APPEND blk_ff0000000000000000000000 text :: \"More\"";
+
+        let output = run_cli(&[
+            "ucl",
+            "exec",
+            "-i",
+            path,
+            "-c",
+            append,
+            "--dry-run",
+            "--format",
+            "json",
+        ]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        let result: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(result["dry_run"], true);
+        assert_eq!(result["operations"][0]["command_type"], "APPEND");
+        assert!(result["operations"][0]["description"]
+            .as_str()
+            .unwrap()
+            .starts_with("Would append a new Text block"));
+        assert_eq!(std::fs::read_to_string(path).unwrap(), before);
+    }
+
+    #[test]
+    fn test_diff_documents_and_snapshots() {
+        let temp_file = create_temp_doc();
+        let path = temp_file.path().to_str().unwrap();
+        let edited = NamedTempFile::new().expect("Failed to create temp output");
+        let edited_path = edited.path().to_str().unwrap();
+        let root = "blk_ff0000000000000000000000";
+
+        let append = format!("APPEND {} text :: \"More\"", root);
+        let output = run_cli(&["ucl", "exec", "-i", path, "-o", edited_path, "-c", &append]);
+        assert!(output.status.success(), "{}", stderr(&output));
+
+        let output = run_cli(&["diff", path, edited_path, "--format", "json"]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        let diff: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(diff["added"].as_array().unwrap().len(), 1);
+        assert_eq!(diff["added"][0]["content"]["text"], "More");
+        assert!(diff["removed"].as_array().unwrap().is_empty());
+
+        let output = run_cli(&["diff", edited_path, path]);
+        assert!(stdout(&output).contains("Removed (1)"));
+
+        // Snapshots of one document, recorded in a REPL session
+        let script = format!(
+            ":snapshot before\nEDIT {} SET content.text = \"Changed\"\n:snapshot after\n:save {}\n",
+            root, path
+        );
+        let mut child = Command::new("cargo")
+            .args(["run", "-q", "-p", "ucp-cli", "--", "repl", "-i", path])
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .expect("Failed to execute command");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(script.as_bytes())
+            .unwrap();
+        assert!(child.wait().unwrap().success());
+
+        let output = run_cli(&[
+            "diff",
+            "--snapshot",
+            "before",
+            "after",
+            "--doc",
+            path,
+            "--format",
+            "json",
+        ]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        let diff: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(diff["modified"][0]["id"], root);
+        assert_eq!(diff["modified"][0]["old"]["text"], "Hello World");
+        assert_eq!(diff["modified"][0]["new"]["text"], "Changed");
+
+        let output = run_cli(&["diff", "--snapshot", "before", "missing", "-i", path]);
+        assert!(!output.status.success());
+        assert!(stderr(&output).contains("Snapshot 'missing' not found"));
+    }
+
+    #[test]
+    fn test_merge_combines_edits_and_reports_conflicts() {
+        let base = create_temp_doc();
+        let base_path = base.path().to_str().unwrap();
+        let root = "blk_ff0000000000000000000000";
+        let edit = |command: String| {
+            let file = NamedTempFile::new().expect("Failed to create temp output");
+            let path = file.path().to_str().unwrap();
+            let output = run_cli(&["ucl", "exec", "-i", base_path, "-o", path, "-c", &command]);
+            assert!(output.status.success(), "{}", stderr(&output));
+            file
+        };
+        let ours = edit(format!("APPEND {} text :: \"Ours\"", root));
+        let theirs = edit(format!("APPEND {} text :: \"Theirs\"", root));
+        let merged = NamedTempFile::new().expect("Failed to create temp output");
+        let merged_path = merged.path().to_str().unwrap();
+
+        let output = run_cli(&[
+            "merge",
+            base_path,
+            ours.path().to_str().unwrap(),
+            theirs.path().to_str().unwrap(),
+            "-o",
+            merged_path,
+        ]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        let doc: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(merged_path).unwrap()).unwrap();
+        assert_eq!(doc["structure"][root].as_array().unwrap().len(), 2);
+
+        let ours = edit(format!("EDIT {} SET content.text = \"Ours\"", root));
+        let theirs = edit(format!("EDIT {} SET content.text = \"Theirs\"", root));
+        let output = run_cli(&[
+            "merge",
+            base_path,
+            ours.path().to_str().unwrap(),
+            theirs.path().to_str().unwrap(),
+            "--format",
+            "json",
+        ]);
+        assert!(!output.status.success());
+        assert!(stderr(&output).contains("Merge conflict in 1 block(s)"));
+        let conflict: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(conflict["conflicts"][0]["id"], root);
+        assert_eq!(conflict["conflicts"][0]["base"]["text"], "Hello World");
+        assert_eq!(conflict["conflicts"][0]["theirs"]["text"], "Theirs");
+    }
+
+    #[test]
+    fn test_complete_snapshot_names() {
+        let temp_file = create_temp_doc();
+        let path = temp_file.path().to_str().unwrap();
+        for name in ["before", "after"] {
+            let output = run_cli(&["snapshot", "create", "-i", path, "-o", path, name]);
+            assert!(output.status.success(), "{}", stderr(&output));
+        }
+
+        let output = run_cli(&["__complete-snapshots", "--input", path]);
+        assert!(output.status.success());
+        assert_eq!(stdout(&output), "before\nafter\n");
+
+        // Completion never fails loudly
+        let output = run_cli(&["__complete-snapshots", "--input", "/nonexistent/doc.json"]);
+        assert!(output.status.success());
+        assert!(stdout(&output).is_empty());
+    }
+
+    #[test]
+    fn test_repl_runs_piped_session() {
+        use std::process::Stdio;
+
+        let saved = NamedTempFile::new().expect("Failed to create temp file");
+        let saved_path = saved.path().to_str().unwrap();
+        let script = format!(
+            "APPEND blk_ff0000000000000000000000 text :: \"From the REPL\"\n:snapshot first\n:save {}\n:quit\nAPPEND blk_ff0000000000000000000000 text :: \"Never run\"\n",
+            saved_path
+        );
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run", "-q", "-p", "ucp-cli", "--", "--format", "json", "repl",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to execute command");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(script.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success(), "{}", stderr(&output));
+
+        let result: serde_json::Value =
+            serde_json::from_str(stdout(&output).lines().next().unwrap()).unwrap();
+        assert_eq!(result["success"], true);
+        assert_eq!(result["block_count"], 2);
+        assert_eq!(result["affected_blocks"].as_array().unwrap().len(), 1);
+
+        let doc: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(saved_path).unwrap()).unwrap();
+        assert_eq!(doc["blocks"].as_object().unwrap().len(), 2);
+        assert_eq!(doc["cli_state"]["snapshots"][0]["name"], "first");
+    }
+
     #[test]
     fn test_export_markdown() {
         let temp_file = create_temp_doc();
@@ -378,6 +785,156 @@ mod with_temp_file {
         assert!(output.status.success() || !stderr(&output).contains("Error"));
     }
 
+    #[test]
+    fn test_export_markdown_block() {
+        let mut markdown = NamedTempFile::new().expect("Failed to create temp file");
+        markdown
+            .write_all(b"# Guide\n\n## Install\n\nRun it.\n")
+            .unwrap();
+        let doc_file = NamedTempFile::new().expect("Failed to create temp file");
+        let doc_path = doc_file.path().to_str().unwrap();
+        let output = run_cli(&[
+            "import",
+            "markdown",
+            markdown.path().to_str().unwrap(),
+            "-o",
+            doc_path,
+        ]);
+        assert!(output.status.success(), "{}", stderr(&output));
+
+        let doc: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(doc_path).unwrap()).unwrap();
+        let first_child = |id: &str| doc["structure"][id][0].as_str().unwrap().to_string();
+        let install = first_child(&first_child(doc["root"].as_str().unwrap()));
+
+        let output = run_cli(&[
+            "--format", "json", "export", "markdown", "--input", doc_path, "--block", &install,
+        ]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        let md: String = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(md, "# Install\n\nRun it.\n");
+
+        let output = run_cli(&[
+            "export",
+            "markdown",
+            "--input",
+            doc_path,
+            "--block",
+            "blk_000000000000000000000000",
+        ]);
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn test_export_prompt_section_and_config() {
+        let mut markdown = NamedTempFile::new().expect("Failed to create temp file");
+        markdown
+            .write_all(b"# Guide\n\nIntro.\n\n## Install\n\nRun it.\n")
+            .unwrap();
+        let doc_file = NamedTempFile::new().expect("Failed to create temp file");
+        let doc_path = doc_file.path().to_str().unwrap();
+        let output = run_cli(&[
+            "import",
+            "markdown",
+            markdown.path().to_str().unwrap(),
+            "-o",
+            doc_path,
+        ]);
+        assert!(output.status.success(), "{}", stderr(&output));
+
+        let output = run_cli(&["export", "prompt", "--input", doc_path]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        let text = stdout(&output);
+        assert!(text.starts_with("ID Mapping:\n  1 = blk_"), "{}", text);
+        assert!(text.contains("Document structure:"));
+        assert!(text.contains("content=\"Intro.\""));
+
+        let doc: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(doc_path).unwrap()).unwrap();
+        let guide = doc["structure"][doc["root"].as_str().unwrap()][0]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let install = doc["structure"][&guide][1].as_str().unwrap().to_string();
+
+        let mut config = NamedTempFile::new().expect("Failed to create temp file");
+        config
+            .write_all(br#"{"capabilities": ["edit"], "rules": ["Only edit the install steps"]}"#)
+            .unwrap();
+        let prompt_file = NamedTempFile::new().expect("Failed to create temp file");
+        let output = run_cli(&[
+            "export",
+            "prompt",
+            "--input",
+            doc_path,
+            "--section",
+            &install,
+            "--config",
+            config.path().to_str().unwrap(),
+            "-o",
+            prompt_file.path().to_str().unwrap(),
+        ]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        let text = std::fs::read_to_string(prompt_file.path()).unwrap();
+        assert!(text.contains("Only edit the install steps"));
+        assert!(text.contains("content=\"Run it.\""));
+        assert!(!text.contains("Intro."));
+        assert!(text.find("ID Mapping:") > text.find("## UCL Command Reference"));
+    }
+
+    #[test]
+    fn test_export_to_file_reports_json() {
+        let temp_file = create_temp_doc();
+        let path = temp_file.path().to_str().unwrap();
+
+        for kind in ["markdown", "json"] {
+            let exported = NamedTempFile::new().expect("Failed to create temp file");
+            let exported_path = exported.path().to_str().unwrap();
+            let output = run_cli(&[
+                "--format",
+                "json",
+                "export",
+                kind,
+                "--input",
+                path,
+                "-o",
+                exported_path,
+            ]);
+            assert!(output.status.success(), "{}", stderr(&output));
+
+            let written = std::fs::read_to_string(exported_path).unwrap();
+            let result: serde_json::Value =
+                serde_json::from_str(&stdout(&output)).expect("Output should be valid JSON");
+            assert_eq!(result["output"], exported_path);
+            assert_eq!(result["bytes"], written.len());
+        }
+    }
+
+    #[test]
+    fn test_export_docx() {
+        let temp_file = create_temp_doc();
+        let path = temp_file.path().to_str().unwrap();
+
+        let exported = NamedTempFile::new().expect("Failed to create temp file");
+        let exported_path = exported.path().to_str().unwrap();
+        let output = run_cli(&[
+            "--format",
+            "json",
+            "export",
+            "docx",
+            "--input",
+            path,
+            "-o",
+            exported_path,
+        ]);
+        assert!(output.status.success(), "{}", stderr(&output));
+
+        let bytes = std::fs::read(exported_path).unwrap();
+        assert!(bytes.starts_with(b"PK"));
+        let result: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(result["bytes"], bytes.len());
+    }
+
     #[test]
     fn test_ucl_exec_with_file_short_flag() {
         let temp_doc = create_temp_doc();
@@ -612,10 +1169,250 @@ mod workflow_tests {
             .expect("projection string");
         assert!(projection.contains("CodeGraph projection"));
         assert!(projection.contains("summary: files="));
-        assert!(projection.contains("- file src/lib.rs [rust]"));
+        assert!(projection.contains("] file src/lib.rs [rust]"));
         assert!(projection.contains("function add(a: i32, b: i32) -> i32"));
     }
 
+    #[test]
+    fn test_codegraph_impact_prints_dependent_tree() {
+        use tempfile::tempdir;
+
+        let repo = tempdir().expect("temp repo");
+        std::fs::write(repo.path().join("app.py"), "import mid\n").expect("write app.py");
+        std::fs::write(repo.path().join("mid.py"), "import base\n").expect("write mid.py");
+        std::fs::write(repo.path().join("base.py"), "x = 1\n").expect("write base.py");
+
+        let doc_out = tempfile::NamedTempFile::new().expect("doc output");
+        let doc_path = doc_out.path().to_str().unwrap().to_string();
+        let repo_path = repo.path().to_str().unwrap().to_string();
+        let output = run_cli(&[
+            "codegraph",
+            "build",
+            repo_path.as_str(),
+            "--commit",
+            "impact",
+            "--output",
+            doc_path.as_str(),
+            "--allow-partial",
+        ]);
+        assert!(
+            output.status.success(),
+            "codegraph build failed: {}",
+            stderr(&output)
+        );
+
+        let impact = run_cli(&["codegraph", "impact", "base.py", "--input", &doc_path]);
+        assert!(
+            impact.status.success(),
+            "codegraph impact failed: {}",
+            stderr(&impact)
+        );
+        assert!(stdout(&impact).contains("└─ mid.py\n   └─ app.py"));
+
+        let deps = run_cli(&[
+            "codegraph",
+            "impact",
+            "app.py",
+            "--dependencies",
+            "--max-depth",
+            "1",
+            "--input",
+            &doc_path,
+            "--format",
+            "json",
+        ]);
+        let entries: serde_json::Value =
+            serde_json::from_str(&stdout(&deps)).expect("impact output json");
+        assert_eq!(entries[0]["path"], "mid.py");
+        assert_eq!(entries.as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_codegraph_diff_summarizes_symbol_changes() {
+        use tempfile::tempdir;
+
+        let repo = tempdir().expect("temp repo");
+        let repo_path = repo.path().to_str().unwrap().to_string();
+        let build = |source: &str, out: &tempfile::NamedTempFile| {
+            std::fs::write(repo.path().join("lib.py"), source).expect("write lib.py");
+            let output = run_cli(&[
+                "codegraph",
+                "build",
+                repo_path.as_str(),
+                "--commit",
+                "diff",
+                "--output",
+                out.path().to_str().unwrap(),
+                "--allow-partial",
+            ]);
+            assert!(
+                output.status.success(),
+                "codegraph build failed: {}",
+                stderr(&output)
+            );
+        };
+        let old = tempfile::NamedTempFile::new().expect("old output");
+        let new = tempfile::NamedTempFile::new().expect("new output");
+        build("def keep(a):\n    pass\n", &old);
+        build(
+            "def keep(a, b):\n    pass\n\ndef added():\n    pass\n",
+            &new,
+        );
+
+        let old_path = old.path().to_str().unwrap();
+        let new_path = new.path().to_str().unwrap();
+        let diff = run_cli(&["codegraph", "diff", old_path, new_path]);
+        assert!(
+            diff.status.success(),
+            "codegraph diff failed: {}",
+            stderr(&diff)
+        );
+        let text = stdout(&diff);
+        assert!(text.contains("- `symbol:lib.py::added`"), "{}", text);
+        assert!(text.contains("1 signatures changed"), "{}", text);
+
+        let json = run_cli(&["codegraph", "diff", old_path, new_path, "--format", "json"]);
+        let value: serde_json::Value =
+            serde_json::from_str(&stdout(&json)).expect("diff output json");
+        assert_eq!(value["summary"]["symbols_added"], 1);
+    }
+
+    #[test]
+    fn test_codegraph_find_symbols() {
+        use tempfile::tempdir;
+
+        let repo = tempdir().expect("temp repo");
+        std::fs::write(
+            repo.path().join("lib.py"),
+            "def validate_profile():\n    pass\n\ndef render():\n    pass\n",
+        )
+        .expect("write lib.py");
+        let graph = tempfile::NamedTempFile::new().expect("graph output");
+        let graph_path = graph.path().to_str().unwrap();
+        let build = run_cli(&[
+            "codegraph",
+            "build",
+            repo.path().to_str().unwrap(),
+            "--output",
+            graph_path,
+            "--allow-partial",
+        ]);
+        assert!(build.status.success(), "{}", stderr(&build));
+
+        let text = run_cli(&["codegraph", "find", "profile", "--input", graph_path]);
+        assert!(text.status.success(), "{}", stderr(&text));
+        assert!(stdout(&text).contains("validate_profile"));
+        assert!(!stdout(&text).contains("render"));
+
+        let json = run_cli(&[
+            "codegraph",
+            "find",
+            "valdate profile",
+            "--fuzzy",
+            "--input",
+            graph_path,
+            "--format",
+            "json",
+        ]);
+        let hits: serde_json::Value = serde_json::from_str(&stdout(&json)).expect("find json");
+        assert_eq!(hits[0]["logical_key"], "symbol:lib.py::validate_profile");
+        assert_eq!(hits[0]["path"], "lib.py");
+        assert!(hits[0]["file_block_id"]
+            .as_str()
+            .unwrap()
+            .starts_with("blk_"));
+    }
+
+    #[test]
+    fn test_codegraph_stats_and_dead_code() {
+        use tempfile::tempdir;
+
+        let repo = tempdir().expect("temp repo");
+        std::fs::write(
+            repo.path().join("lib.rs"),
+            "pub fn api() {\n    helper();\n}\n\nfn helper() {}\n\nfn unused() {}\n",
+        )
+        .expect("write lib.rs");
+        let graph = tempfile::NamedTempFile::new().expect("graph output");
+        let graph_path = graph.path().to_str().unwrap();
+        let build = run_cli(&[
+            "codegraph",
+            "extract",
+            repo.path().to_str().unwrap(),
+            "--output",
+            graph_path,
+            "--allow-partial",
+        ]);
+        assert!(build.status.success(), "{}", stderr(&build));
+
+        let stats = run_cli(&[
+            "codegraph",
+            "stats",
+            "--input",
+            graph_path,
+            "--format",
+            "json",
+        ]);
+        assert!(stats.status.success(), "{}", stderr(&stats));
+        let value: serde_json::Value = serde_json::from_str(&stdout(&stats)).expect("stats json");
+        assert_eq!(value["symbol_nodes"], 3);
+        assert_eq!(value["languages"]["rust"], 1);
+
+        let dead = run_cli(&["codegraph", "dead-code", "--input", graph_path]);
+        assert!(dead.status.success(), "{}", stderr(&dead));
+        let text = stdout(&dead);
+        assert!(text.contains("unused"), "{}", text);
+        assert!(!text.contains("helper"), "{}", text);
+        assert!(!text.contains("api"), "{}", text);
+    }
+
+    #[test]
+    fn test_codegraph_store_answers_impact_and_find() {
+        use tempfile::tempdir;
+
+        let repo = tempdir().expect("temp repo");
+        std::fs::write(
+            repo.path().join("app.py"),
+            "import base
+",
+        )
+        .expect("write app.py");
+        std::fs::write(repo.path().join("base.py"), "def helper():\n    pass\n")
+            .expect("write base.py");
+        let out = tempdir().expect("output dir");
+        let json_path = out.path().join("graph.json");
+        let store_path = out.path().join("graph.ucgs");
+        let build = run_cli(&[
+            "codegraph",
+            "build",
+            repo.path().to_str().unwrap(),
+            "--output",
+            json_path.to_str().unwrap(),
+            "--store",
+            store_path.to_str().unwrap(),
+            "--allow-partial",
+        ]);
+        assert!(build.status.success(), "{}", stderr(&build));
+
+        for args in [
+            &["codegraph", "impact", "base.py", "--format", "json"][..],
+            &["codegraph", "find", "help", "--format", "json"][..],
+        ] {
+            let from_json = run_cli(&[args, &["--input", json_path.to_str().unwrap()]].concat());
+            let from_store = run_cli(&[args, &["--input", store_path.to_str().unwrap()]].concat());
+            assert!(from_store.status.success(), "{}", stderr(&from_store));
+            assert_eq!(stdout(&from_store), stdout(&from_json));
+        }
+        let impact = run_cli(&[
+            "codegraph",
+            "impact",
+            "base.py",
+            "--input",
+            store_path.to_str().unwrap(),
+        ]);
+        assert!(stdout(&impact).contains("app.py"));
+    }
+
     #[test]
     fn test_codegraph_incremental_build_workflow() {
         use tempfile::tempdir;
@@ -1716,3 +2513,49 @@ mod error_handling_tests {
         assert!(err.contains("error") || err.contains("required") || err.contains("Usage"));
     }
 }
+
+#[test]
+fn test_import_markdown_merge_keeps_ids() {
+    let dir = std::env::temp_dir().join(format!("ucp-merge-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let original = dir.join("original.md");
+    let edited = dir.join("edited.md");
+    let doc_path = dir.join("doc.json");
+    std::fs::write(&original, "# Title\n\nKeep me.\n\nDrop me.\n").unwrap();
+    std::fs::write(&edited, "# Title\n\nKeep me.\n\nAdd me.\n").unwrap();
+
+    let output = run_cli(&[
+        "import",
+        "markdown",
+        original.to_str().unwrap(),
+        "--format",
+        "json",
+    ]);
+    std::fs::write(&doc_path, stdout(&output)).unwrap();
+    let before: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+
+    let output = run_cli(&[
+        "import",
+        "markdown",
+        edited.to_str().unwrap(),
+        "--merge",
+        doc_path.to_str().unwrap(),
+        "--format",
+        "json",
+    ]);
+    let after: serde_json::Value =
+        serde_json::from_str(&stdout(&output)).expect("Output should be valid JSON");
+    std::fs::remove_dir_all(&dir).ok();
+
+    let id_of = |doc: &serde_json::Value, text: &str| {
+        doc["blocks"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .find(|(_, b)| b["content"]["text"] == text)
+            .map(|(id, _)| id.clone())
+    };
+    assert_eq!(id_of(&after, "Keep me."), id_of(&before, "Keep me."));
+    assert!(id_of(&after, "Drop me.").is_none());
+    assert!(id_of(&after, "Add me.").is_some());
+}
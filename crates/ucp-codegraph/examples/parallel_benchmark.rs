@@ -0,0 +1,97 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Result};
+use ucp_codegraph::{build_code_graph, CodeGraphBuildInput, CodeGraphExtractorConfig};
+
+fn main() -> Result<()> {
+    let files = parse_args()?;
+    let repo_root = create_repo_root();
+    write_fixture(&repo_root, files)?;
+
+    let (sequential_elapsed, sequential) = timed_build(&repo_root, Some(1))?;
+    let (parallel_elapsed, parallel) = timed_build(&repo_root, None)?;
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+
+    println!("Parallel benchmark repo: {}", repo_root.display());
+    println!("files: {files} threads: {threads}");
+    println!("- sequential: {} ms", sequential_elapsed.as_millis());
+    println!("- parallel: {} ms", parallel_elapsed.as_millis());
+    println!(
+        "  speedup={:.2}x matches_sequential={}",
+        sequential_elapsed.as_secs_f64() / parallel_elapsed.as_secs_f64(),
+        sequential == parallel
+    );
+
+    fs::remove_dir_all(&repo_root)?;
+    Ok(())
+}
+
+fn parse_args() -> Result<usize> {
+    let mut files = 1000usize;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--files" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --files"))?;
+                files = value.parse()?;
+            }
+            other => bail!("unknown argument: {other}"),
+        }
+    }
+    Ok(files)
+}
+
+fn create_repo_root() -> PathBuf {
+    let suffix = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    env::temp_dir().join(format!(
+        "ucp-codegraph-parallel-benchmark-{}-{suffix}",
+        process::id()
+    ))
+}
+
+/// Rust modules with a few items each, every one importing its predecessor
+fn write_fixture(repo_root: &Path, files: usize) -> Result<()> {
+    let src = repo_root.join("src");
+    fs::create_dir_all(&src)?;
+    let mut lib_rs = String::new();
+    for i in 0..files {
+        lib_rs.push_str(&format!("pub mod m{i};\n"));
+        let mut source = String::new();
+        if i > 0 {
+            source.push_str(&format!("use crate::m{}::Item{};\n\n", i - 1, i - 1));
+        }
+        source.push_str(&format!(
+            "/// Item {i}\npub struct Item{i} {{\n    pub value: i32,\n}}\n\n\
+             impl Item{i} {{\n    pub fn new(value: i32) -> Self {{\n        Self {{ value }}\n    }}\n\n\
+             \x20   pub fn double(&self) -> i32 {{\n        self.value * 2\n    }}\n}}\n\n\
+             pub fn make{i}() -> Item{i} {{\n    Item{i}::new({i})\n}}\n"
+        ));
+        fs::write(src.join(format!("m{i}.rs")), source)?;
+    }
+    fs::write(src.join("lib.rs"), lib_rs)?;
+    Ok(())
+}
+
+/// Elapsed time and canonical fingerprint of one full build
+fn timed_build(repo_root: &Path, parallelism: Option<usize>) -> Result<(Duration, String)> {
+    let input = CodeGraphBuildInput {
+        repository_path: repo_root.to_path_buf(),
+        commit_hash: "benchmark-parallel".to_string(),
+        config: CodeGraphExtractorConfig {
+            parallelism,
+            ..CodeGraphExtractorConfig::default()
+        },
+    };
+    let start = Instant::now();
+    let result = build_code_graph(&input)?;
+    Ok((start.elapsed(), result.canonical_fingerprint))
+}
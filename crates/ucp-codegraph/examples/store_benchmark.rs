@@ -0,0 +1,129 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Result};
+use ucm_core::Document;
+use ucp_codegraph::{
+    build_code_graph, canonical_fingerprint, dependents_of, find_symbols, CodeGraphBuildInput,
+    CodeGraphExtractorConfig, CodeGraphStore, ImpactEntry, SymbolHit, SymbolQuery,
+};
+
+fn main() -> Result<()> {
+    let files = parse_args()?;
+    let repo_root = create_repo_root();
+    write_fixture(&repo_root, files)?;
+
+    let result = build_code_graph(&CodeGraphBuildInput {
+        repository_path: repo_root.clone(),
+        commit_hash: "benchmark-store".to_string(),
+        config: CodeGraphExtractorConfig::default(),
+    })?;
+    let json_path = repo_root.join("graph.json");
+    let store_path = repo_root.join("graph.ucgs");
+    fs::write(&json_path, result.document.to_portable_json()?)?;
+    CodeGraphStore::write(&store_path, &result.document)?;
+
+    let start = Instant::now();
+    let doc = Document::from_portable_json(&fs::read_to_string(&json_path)?)?;
+    let json_answers = query(&doc);
+    let json_elapsed = start.elapsed();
+
+    let start = Instant::now();
+    let store = CodeGraphStore::open(&store_path)?;
+    let store_answers = query(store.skeleton());
+    let store_elapsed = start.elapsed();
+
+    let round_trip = canonical_fingerprint(&store.to_document()?)? == result.canonical_fingerprint;
+
+    println!("Store benchmark repo: {}", repo_root.display());
+    println!("files: {files} blocks: {}", store.block_count());
+    println!(
+        "- json: {} ms ({} bytes)",
+        json_elapsed.as_millis(),
+        fs::metadata(&json_path)?.len()
+    );
+    println!(
+        "- store: {} ms ({} bytes)",
+        store_elapsed.as_millis(),
+        fs::metadata(&store_path)?.len()
+    );
+    println!(
+        "  speedup={:.2}x same_answers={} fingerprint_round_trip={}",
+        json_elapsed.as_secs_f64() / store_elapsed.as_secs_f64(),
+        json_answers == store_answers,
+        round_trip
+    );
+
+    drop(store);
+    fs::remove_dir_all(&repo_root)?;
+    Ok(())
+}
+
+/// One impact and one symbol query, as the CLI would run them
+fn query(doc: &Document) -> (Vec<ImpactEntry>, Vec<SymbolHit>) {
+    let impact = dependents_of(doc, "src/m0.rs", usize::MAX);
+    let hits = find_symbols(
+        doc,
+        &SymbolQuery {
+            name_contains: Some("make1".to_string()),
+            ..Default::default()
+        },
+    );
+    (impact, hits)
+}
+
+fn parse_args() -> Result<usize> {
+    let mut files = 1000usize;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--files" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("missing value for --files"))?;
+                files = value.parse()?;
+            }
+            other => bail!("unknown argument: {other}"),
+        }
+    }
+    Ok(files)
+}
+
+fn create_repo_root() -> PathBuf {
+    let suffix = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    env::temp_dir().join(format!(
+        "ucp-codegraph-store-benchmark-{}-{suffix}",
+        process::id()
+    ))
+}
+
+/// Documented Rust modules, every one importing its predecessor
+fn write_fixture(repo_root: &Path, files: usize) -> Result<()> {
+    let src = repo_root.join("src");
+    fs::create_dir_all(&src)?;
+    let mut lib_rs = String::new();
+    for i in 0..files {
+        lib_rs.push_str(&format!("pub mod m{i};\n"));
+        let mut source = String::new();
+        if i > 0 {
+            source.push_str(&format!("use crate::m{}::Item{};\n\n", i - 1, i - 1));
+        }
+        source.push_str(&format!(
+            "/// Item {i} holds a value that is doubled on request and compared\n\
+             /// against its neighbours when the chain is walked.\n\
+             pub struct Item{i} {{\n    pub value: i32,\n}}\n\n\
+             impl Item{i} {{\n    /// Wrap `value`\n    pub fn new(value: i32) -> Self {{\n        Self {{ value }}\n    }}\n\n\
+             \x20   /// Twice the wrapped value\n    pub fn double(&self) -> i32 {{\n        self.value * 2\n    }}\n}}\n\n\
+             /// Build item {i}\npub fn make{i}() -> Item{i} {{\n    Item{i}::new({i})\n}}\n"
+        ));
+        fs::write(src.join(format!("m{i}.rs")), source)?;
+    }
+    fs::write(src.join("lib.rs"), lib_rs)?;
+    Ok(())
+}
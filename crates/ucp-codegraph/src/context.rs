@@ -1199,8 +1199,8 @@ impl CodeGraphContextSession {
             let _ = writeln!(out, "\nfilesystem:");
             for block_id in repository_nodes
                 .into_iter()
-                .chain(directory_nodes.into_iter())
-                .chain(file_nodes.into_iter())
+                .chain(directory_nodes)
+                .chain(file_nodes)
             {
                 let block = match doc.get_block(&block_id) {
                     Some(block) => block,
@@ -2929,7 +2929,7 @@ fn focus_distances(
         let mut neighbors: Vec<BlockId> = index
             .outgoing_edges(&block_id)
             .into_iter()
-            .chain(index.incoming_edges(&block_id).into_iter())
+            .chain(index.incoming_edges(&block_id))
             .map(|edge| edge.other)
             .collect();
         neighbors.extend(doc.children(&block_id));
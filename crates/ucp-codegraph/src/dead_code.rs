@@ -0,0 +1,82 @@
+use std::collections::HashSet;
+
+use ucm_core::{BlockId, Document, EdgeType};
+
+use crate::search::{find_symbols, owning_file, SymbolHit, SymbolQuery};
+
+/// Symbols that no edge in the graph points at, ordered by logical key.
+///
+/// A file's `exports` edge to its own symbol and a symbol calling itself do
+/// not count as uses; a re-export from another file does. Exported symbols
+/// are public API and are only reported with `include_exported`. The graph
+/// only sees references it could resolve statically, so treat the result as
+/// candidates for review rather than proof.
+pub fn find_dead_code(doc: &Document, include_exported: bool) -> Vec<SymbolHit> {
+    let used = used_blocks(doc);
+    find_symbols(doc, &SymbolQuery::default())
+        .into_iter()
+        .filter(|hit| include_exported || !hit.exported)
+        .filter(|hit| !used.contains(&hit.block_id))
+        .collect()
+}
+
+fn used_blocks(doc: &Document) -> HashSet<BlockId> {
+    let mut used = HashSet::new();
+    for block in doc.blocks.values() {
+        for edge in &block.edges {
+            if edge.target == block.id {
+                continue;
+            }
+            let own_export = matches!(&edge.edge_type, EdgeType::Custom(name) if name == "exports")
+                && owning_file(doc, &edge.target) == Some(block.id);
+            if !own_export {
+                used.insert(edge.target);
+            }
+        }
+    }
+    used
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{build_code_graph, CodeGraphBuildInput, CodeGraphExtractorConfig};
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn sample() -> Document {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(
+            dir.path().join("src/lib.rs"),
+            "pub fn api() {\n    helper();\n}\n\nfn helper() {}\n\nfn unused() {\n    unused();\n}\n\npub fn orphan() {}\n",
+        )
+        .unwrap();
+        build_code_graph(&CodeGraphBuildInput {
+            repository_path: dir.path().to_path_buf(),
+            commit_hash: "dead".to_string(),
+            config: CodeGraphExtractorConfig::default(),
+        })
+        .unwrap()
+        .document
+    }
+
+    fn names(hits: &[SymbolHit]) -> Vec<&str> {
+        hits.iter().map(|hit| hit.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_reports_unreferenced_private_symbols() {
+        let doc = sample();
+        assert_eq!(names(&find_dead_code(&doc, false)), ["unused"]);
+    }
+
+    #[test]
+    fn test_include_exported_adds_public_api() {
+        let doc = sample();
+        assert_eq!(
+            names(&find_dead_code(&doc, true)),
+            ["api", "orphan", "unused"]
+        );
+    }
+}
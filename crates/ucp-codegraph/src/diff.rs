@@ -0,0 +1,556 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use ucm_core::{Block, Content, Document};
+
+use crate::model::{
+    META_CODEREF, META_DOC, META_EXPORTED, META_LOGICAL_KEY, META_NODE_CLASS, META_SYMBOL_KIND,
+};
+
+/// Difference between two codegraph documents, keyed on logical keys
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CodeGraphDiff {
+    pub summary: CodeGraphDiffSummary,
+    pub added: Vec<CodeGraphDiffNode>,
+    pub removed: Vec<CodeGraphDiffNode>,
+    pub modified: Vec<CodeGraphNodeChange>,
+    pub renamed: Vec<CodeGraphFileRename>,
+    pub added_edges: Vec<CodeGraphDiffEdge>,
+    pub removed_edges: Vec<CodeGraphDiffEdge>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeGraphDiffSummary {
+    pub files_added: usize,
+    pub files_removed: usize,
+    pub files_renamed: usize,
+    pub symbols_added: usize,
+    pub symbols_removed: usize,
+    pub symbols_modified: usize,
+    pub public_symbols_added: usize,
+    pub public_symbols_removed: usize,
+    pub signatures_changed: usize,
+    pub edges_added: usize,
+    pub edges_removed: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeGraphDiffNode {
+    pub logical_key: String,
+    pub node_class: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_kind: Option<String>,
+    #[serde(default)]
+    pub exported: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeGraphNodeChange {
+    pub logical_key: String,
+    /// Key in the old document when the node moved with a renamed file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_key: Option<String>,
+    pub node_class: String,
+    pub changes: Vec<CodeGraphChangeKind>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeGraphChangeKind {
+    /// Start or end position moved
+    Span,
+    /// Kind, modifiers, inputs, output or type changed
+    Signature,
+    Exported,
+    Doc,
+    /// Any other content or metadata change
+    Metadata,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeGraphFileRename {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CodeGraphDiffEdge {
+    pub source: String,
+    pub relation: String,
+    pub target: String,
+}
+
+impl CodeGraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.renamed.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+
+    /// Markdown summary for a pull request comment
+    pub fn render_summary(&self) -> String {
+        let s = &self.summary;
+        let mut out = String::from("### CodeGraph diff\n\n");
+        if self.is_empty() {
+            out.push_str("No structural changes.\n");
+            return out;
+        }
+        let _ = writeln!(
+            out,
+            "- symbols: +{} / -{} / ~{} ({} public added, {} public removed, {} signatures changed)",
+            s.symbols_added,
+            s.symbols_removed,
+            s.symbols_modified,
+            s.public_symbols_added,
+            s.public_symbols_removed,
+            s.signatures_changed
+        );
+        let _ = writeln!(
+            out,
+            "- files: +{} / -{} / {} renamed",
+            s.files_added, s.files_removed, s.files_renamed
+        );
+        let _ = writeln!(out, "- edges: +{} / -{}", s.edges_added, s.edges_removed);
+
+        let public = |nodes: &[CodeGraphDiffNode]| -> Vec<String> {
+            nodes
+                .iter()
+                .filter(|n| n.node_class == "symbol" && n.exported)
+                .map(|n| n.logical_key.clone())
+                .collect()
+        };
+        let signatures: Vec<String> = self
+            .modified
+            .iter()
+            .filter(|c| c.changes.contains(&CodeGraphChangeKind::Signature))
+            .map(|c| c.logical_key.clone())
+            .collect();
+        let renames: Vec<String> = self
+            .renamed
+            .iter()
+            .map(|r| format!("{} -> {}", r.from, r.to))
+            .collect();
+        for (title, items) in [
+            ("Public symbols added", public(&self.added)),
+            ("Public symbols removed", public(&self.removed)),
+            ("Signatures changed", signatures),
+            ("Renamed files", renames),
+        ] {
+            if items.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "\n**{}**", title);
+            for item in items {
+                let _ = writeln!(out, "- `{}`", item);
+            }
+        }
+        out
+    }
+}
+
+/// Compare two codegraph documents. Repository nodes are skipped since
+/// their commit metadata changes on every build. A removed file and an
+/// added file holding the same non-empty set of symbol names are reported
+/// as a rename, and their symbols are compared across the move.
+pub fn diff_code_graphs(old: &Document, new: &Document) -> CodeGraphDiff {
+    let old_nodes = index_nodes(old);
+    let new_nodes = index_nodes(new);
+    let renames = detect_renames(&old_nodes, &new_nodes);
+    let key_map = renamed_keys(&old_nodes, &renames);
+    let map_key = |key: &str| key_map.get(key).cloned().unwrap_or_else(|| key.to_string());
+
+    let mut diff = CodeGraphDiff {
+        renamed: renames
+            .iter()
+            .map(|(from, to)| CodeGraphFileRename {
+                from: from.clone(),
+                to: to.clone(),
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    let mut matched = BTreeSet::new();
+    for (key, old_node) in &old_nodes {
+        let new_key = map_key(key);
+        let Some(new_node) = new_nodes.get(&new_key) else {
+            diff.removed.push(old_node.summary());
+            continue;
+        };
+        matched.insert(new_key.clone());
+        let changes = old_node.changes(new_node);
+        if !changes.is_empty() {
+            diff.modified.push(CodeGraphNodeChange {
+                previous_key: (new_key != *key).then(|| key.clone()),
+                logical_key: new_key,
+                node_class: new_node.class.clone(),
+                changes,
+            });
+        }
+    }
+    diff.added = new_nodes
+        .iter()
+        .filter(|(key, _)| !matched.contains(*key))
+        .map(|(_, node)| node.summary())
+        .collect();
+    diff.modified
+        .sort_by(|a, b| a.logical_key.cmp(&b.logical_key));
+
+    let old_edges: BTreeSet<CodeGraphDiffEdge> = edge_set(old)
+        .into_iter()
+        .map(|e| CodeGraphDiffEdge {
+            source: map_key(&e.source),
+            relation: e.relation,
+            target: map_key(&e.target),
+        })
+        .collect();
+    let new_edges = edge_set(new);
+    diff.added_edges = new_edges.difference(&old_edges).cloned().collect();
+    diff.removed_edges = old_edges.difference(&new_edges).cloned().collect();
+
+    diff.summary = summarize(&diff);
+    diff
+}
+
+fn summarize(diff: &CodeGraphDiff) -> CodeGraphDiffSummary {
+    let count = |nodes: &[CodeGraphDiffNode], class: &str, public_only: bool| {
+        nodes
+            .iter()
+            .filter(|n| n.node_class == class && (!public_only || n.exported))
+            .count()
+    };
+    CodeGraphDiffSummary {
+        files_added: count(&diff.added, "file", false),
+        files_removed: count(&diff.removed, "file", false),
+        files_renamed: diff.renamed.len(),
+        symbols_added: count(&diff.added, "symbol", false),
+        symbols_removed: count(&diff.removed, "symbol", false),
+        symbols_modified: diff
+            .modified
+            .iter()
+            .filter(|c| c.node_class == "symbol")
+            .count(),
+        public_symbols_added: count(&diff.added, "symbol", true),
+        public_symbols_removed: count(&diff.removed, "symbol", true),
+        signatures_changed: diff
+            .modified
+            .iter()
+            .filter(|c| c.changes.contains(&CodeGraphChangeKind::Signature))
+            .count(),
+        edges_added: diff.added_edges.len(),
+        edges_removed: diff.removed_edges.len(),
+    }
+}
+
+/// Comparable view of one codegraph block
+struct NodeView {
+    class: String,
+    key: String,
+    path: Option<String>,
+    symbol_kind: Option<String>,
+    exported: bool,
+    span: Value,
+    signature: Value,
+    doc: Option<String>,
+    rest: Value,
+}
+
+impl NodeView {
+    fn from_block(block: &Block) -> Option<Self> {
+        let custom = &block.metadata.custom;
+        let class = custom.get(META_NODE_CLASS)?.as_str()?.to_string();
+        let key = custom.get(META_LOGICAL_KEY)?.as_str()?.to_string();
+        if class == "repository" {
+            return None;
+        }
+        let coderef = custom.get(META_CODEREF).cloned().unwrap_or(Value::Null);
+        let mut content = match &block.content {
+            Content::Json { value, .. } => value.clone(),
+            _ => Value::Null,
+        };
+        let mut signature = serde_json::Map::new();
+        if let Some(fields) = content.as_object_mut() {
+            fields.remove("coderef");
+            fields.remove("description");
+            fields.remove("exported");
+            for field in ["kind", "modifiers", "inputs", "output", "type"] {
+                if let Some(value) = fields.remove(field) {
+                    signature.insert(field.to_string(), value);
+                }
+            }
+        }
+        let mut metadata = custom.clone();
+        for field in [META_CODEREF, META_LOGICAL_KEY, META_DOC] {
+            metadata.remove(field);
+        }
+
+        Some(Self {
+            path: coderef
+                .get("path")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            symbol_kind: custom
+                .get(META_SYMBOL_KIND)
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            exported: custom
+                .get(META_EXPORTED)
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            span: json!([
+                coderef.get("start_line"),
+                coderef.get("start_col"),
+                coderef.get("end_line"),
+                coderef.get("end_col"),
+            ]),
+            signature: Value::Object(signature),
+            doc: block.metadata.summary.clone(),
+            rest: json!([content, metadata]),
+            class,
+            key,
+        })
+    }
+
+    fn summary(&self) -> CodeGraphDiffNode {
+        CodeGraphDiffNode {
+            logical_key: self.key.clone(),
+            node_class: self.class.clone(),
+            symbol_kind: self.symbol_kind.clone(),
+            exported: self.exported,
+        }
+    }
+
+    fn changes(&self, new: &NodeView) -> Vec<CodeGraphChangeKind> {
+        let mut changes = Vec::new();
+        if self.span != new.span {
+            changes.push(CodeGraphChangeKind::Span);
+        }
+        if self.signature != new.signature {
+            changes.push(CodeGraphChangeKind::Signature);
+        }
+        if self.exported != new.exported {
+            changes.push(CodeGraphChangeKind::Exported);
+        }
+        if self.doc != new.doc {
+            changes.push(CodeGraphChangeKind::Doc);
+        }
+        if self.rest != new.rest {
+            changes.push(CodeGraphChangeKind::Metadata);
+        }
+        changes
+    }
+}
+
+fn index_nodes(doc: &Document) -> BTreeMap<String, NodeView> {
+    doc.blocks
+        .values()
+        .filter_map(NodeView::from_block)
+        .map(|node| (node.key.clone(), node))
+        .collect()
+}
+
+/// Pair removed and added files whose symbol names match exactly
+fn detect_renames(
+    old: &BTreeMap<String, NodeView>,
+    new: &BTreeMap<String, NodeView>,
+) -> Vec<(String, String)> {
+    let file_symbols = |nodes: &BTreeMap<String, NodeView>, others: &BTreeMap<String, NodeView>| {
+        let mut files: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for node in nodes.values() {
+            if node.class == "file" && !others.contains_key(&node.key) {
+                if let Some(path) = &node.path {
+                    files.entry(path.clone()).or_default();
+                }
+            }
+        }
+        for node in nodes.values().filter(|n| n.class == "symbol") {
+            let Some(path) = &node.path else {
+                continue;
+            };
+            if let (Some(names), Some(name)) = (
+                files.get_mut(path),
+                node.key.strip_prefix(&format!("symbol:{}::", path)),
+            ) {
+                names.insert(name.to_string());
+            }
+        }
+        files
+    };
+    let removed = file_symbols(old, new);
+    let mut added = file_symbols(new, old);
+
+    let mut renames = Vec::new();
+    for (from, names) in removed {
+        if names.is_empty() {
+            continue;
+        }
+        let Some(to) = added
+            .iter()
+            .find(|(_, candidate)| **candidate == names)
+            .map(|(path, _)| path.clone())
+        else {
+            continue;
+        };
+        added.remove(&to);
+        renames.push((from, to));
+    }
+    renames
+}
+
+fn renamed_keys(
+    old: &BTreeMap<String, NodeView>,
+    renames: &[(String, String)],
+) -> BTreeMap<String, String> {
+    let mut map = BTreeMap::new();
+    for (from, to) in renames {
+        let prefix = format!("symbol:{}::", from);
+        for key in old.keys() {
+            if key == &format!("file:{}", from) {
+                map.insert(key.clone(), format!("file:{}", to));
+            } else if let Some(rest) = key.strip_prefix(&prefix) {
+                map.insert(key.clone(), format!("symbol:{}::{}", to, rest));
+            }
+        }
+    }
+    map
+}
+
+fn edge_set(doc: &Document) -> BTreeSet<CodeGraphDiffEdge> {
+    let key_of = |block: &Block| {
+        block
+            .metadata
+            .custom
+            .get(META_LOGICAL_KEY)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    let mut edges = BTreeSet::new();
+    for block in doc.blocks.values() {
+        let Some(source) = key_of(block) else {
+            continue;
+        };
+        for edge in &block.edges {
+            let Some(target) = doc.get_block(&edge.target).and_then(key_of) else {
+                continue;
+            };
+            let relation = edge
+                .metadata
+                .custom
+                .get("relation")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| edge.edge_type.as_str());
+            edges.insert(CodeGraphDiffEdge {
+                source: source.clone(),
+                relation,
+                target,
+            });
+        }
+    }
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{build_code_graph, CodeGraphBuildInput, CodeGraphExtractorConfig};
+    use std::fs;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    fn build(files: &[(&str, &str)]) -> Document {
+        let dir = tempdir().unwrap();
+        for (name, source) in files {
+            let path = dir.path().join(name);
+            fs::create_dir_all(path.parent().unwrap_or(Path::new("."))).unwrap();
+            fs::write(path, source).unwrap();
+        }
+        build_code_graph(&CodeGraphBuildInput {
+            repository_path: dir.path().to_path_buf(),
+            commit_hash: "diff".to_string(),
+            config: CodeGraphExtractorConfig::default(),
+        })
+        .unwrap()
+        .document
+    }
+
+    #[test]
+    fn test_diff_classifies_symbols_and_edges() {
+        let old = build(&[
+            (
+                "src/lib.rs",
+                "mod util;\npub fn keep() -> i32 { 1 }\npub fn gone() {}\npub fn widen(a: i32) -> i32 { a }\n",
+            ),
+            ("src/util.rs", "pub fn helper() {}\n"),
+        ]);
+        let new = build(&[
+            (
+                "src/lib.rs",
+                "pub fn keep() -> i32 { 1 }\npub fn widen(a: i64) -> i64 { a }\npub fn fresh() {}\nfn private() {}\n",
+            ),
+            ("src/util.rs", "pub fn helper() {}\n"),
+        ]);
+
+        let diff = diff_code_graphs(&old, &new);
+        assert_eq!(diff.summary.symbols_added, 2);
+        assert_eq!(diff.summary.public_symbols_added, 1);
+        assert_eq!(diff.summary.public_symbols_removed, 1);
+        assert_eq!(diff.summary.signatures_changed, 1);
+        assert_eq!(diff.removed[0].logical_key, "symbol:src/lib.rs::gone");
+        let widen = diff
+            .modified
+            .iter()
+            .find(|c| c.logical_key == "symbol:src/lib.rs::widen")
+            .unwrap();
+        assert_eq!(
+            widen.changes,
+            vec![CodeGraphChangeKind::Span, CodeGraphChangeKind::Signature]
+        );
+        assert!(diff
+            .removed_edges
+            .iter()
+            .any(|e| e.source == "file:src/lib.rs" && e.target == "file:src/util.rs"));
+
+        let comment = diff.render_summary();
+        assert!(comment.contains("1 signatures changed"));
+        assert!(comment.contains("- `symbol:src/lib.rs::fresh`"));
+        assert!(diff_code_graphs(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn test_moved_file_is_reported_as_rename() {
+        let old = build(&[
+            ("app.py", "import jobs\n"),
+            ("jobs.py", "def run():\n    pass\n\ndef stop():\n    pass\n"),
+        ]);
+        let new = build(&[
+            ("app.py", "import tasks\n"),
+            (
+                "tasks.py",
+                "def run():\n    pass\n\ndef stop():\n    pass\n",
+            ),
+        ]);
+
+        let diff = diff_code_graphs(&old, &new);
+        assert_eq!(
+            diff.renamed,
+            vec![CodeGraphFileRename {
+                from: "jobs.py".into(),
+                to: "tasks.py".into()
+            }]
+        );
+        assert_eq!(diff.summary.symbols_added, 0);
+        assert_eq!(diff.summary.symbols_removed, 0);
+        assert_eq!(diff.summary.files_added, 0);
+        assert!(diff
+            .modified
+            .iter()
+            .all(|c| !c.logical_key.starts_with("symbol:tasks.py")));
+        assert!(diff.added_edges.is_empty(), "{:?}", diff.added_edges);
+    }
+}
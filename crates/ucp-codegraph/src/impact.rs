@@ -0,0 +1,214 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ucm_core::{Block, Document, EdgeType};
+
+use crate::model::{META_CODEREF, META_LOGICAL_KEY, META_NODE_CLASS};
+
+/// A file reached from the queried path through `references` edges
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImpactEntry {
+    pub path: String,
+    pub logical_key: String,
+    /// Hops from the queried path; direct neighbours are at depth 1
+    pub depth: usize,
+    /// Path this entry was first reached from, `None` at depth 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub via: Option<String>,
+}
+
+/// Files that transitively depend on `path`, up to `max_depth` hops
+pub fn dependents_of(doc: &Document, path: &str, max_depth: usize) -> Vec<ImpactEntry> {
+    let graph = FileGraph::from_document(doc);
+    walk(&graph, &graph.dependents, path, max_depth)
+}
+
+/// Files that `path` transitively depends on, up to `max_depth` hops
+pub fn dependencies_of(doc: &Document, path: &str, max_depth: usize) -> Vec<ImpactEntry> {
+    let graph = FileGraph::from_document(doc);
+    walk(&graph, &graph.dependencies, path, max_depth)
+}
+
+#[derive(Default)]
+struct FileGraph {
+    logical_keys: BTreeMap<String, String>,
+    dependencies: BTreeMap<String, BTreeSet<String>>,
+    dependents: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl FileGraph {
+    /// Collapse `references` edges to file -> file; symbol endpoints count
+    /// as the file they are declared in
+    fn from_document(doc: &Document) -> Self {
+        let mut graph = FileGraph::default();
+        for block in doc.blocks.values() {
+            if node_class(block) == Some("file") {
+                if let (Some(path), Some(key)) = (coderef_path(block), logical_key(block)) {
+                    graph.logical_keys.insert(path.to_string(), key.to_string());
+                }
+            }
+        }
+
+        for block in doc.blocks.values() {
+            let Some(source) = coderef_path(block) else {
+                continue;
+            };
+            for edge in &block.edges {
+                if edge.edge_type != EdgeType::References {
+                    continue;
+                }
+                let Some(target) = doc.get_block(&edge.target).and_then(coderef_path) else {
+                    continue;
+                };
+                if source == target {
+                    continue;
+                }
+                graph
+                    .dependencies
+                    .entry(source.to_string())
+                    .or_default()
+                    .insert(target.to_string());
+                graph
+                    .dependents
+                    .entry(target.to_string())
+                    .or_default()
+                    .insert(source.to_string());
+            }
+        }
+        graph
+    }
+}
+
+/// Breadth-first so every file gets its shortest distance; the visited set
+/// makes cycles terminate
+fn walk(
+    graph: &FileGraph,
+    adjacency: &BTreeMap<String, BTreeSet<String>>,
+    start: &str,
+    max_depth: usize,
+) -> Vec<ImpactEntry> {
+    let start = start.trim_start_matches("./");
+    let mut visited = BTreeSet::from([start.to_string()]);
+    let mut queue = VecDeque::from([(start.to_string(), 0usize)]);
+    let mut entries = Vec::new();
+
+    while let Some((path, depth)) = queue.pop_front() {
+        if depth >= max_depth {
+            continue;
+        }
+        for next in adjacency.get(&path).into_iter().flatten() {
+            if !visited.insert(next.clone()) {
+                continue;
+            }
+            entries.push(ImpactEntry {
+                path: next.clone(),
+                logical_key: graph
+                    .logical_keys
+                    .get(next)
+                    .cloned()
+                    .unwrap_or_else(|| format!("file:{}", next)),
+                depth: depth + 1,
+                via: (depth > 0).then(|| path.clone()),
+            });
+            queue.push_back((next.clone(), depth + 1));
+        }
+    }
+
+    entries.sort_by(|a, b| (a.depth, &a.path).cmp(&(b.depth, &b.path)));
+    entries
+}
+
+fn node_class(block: &Block) -> Option<&str> {
+    block
+        .metadata
+        .custom
+        .get(META_NODE_CLASS)
+        .and_then(Value::as_str)
+}
+
+fn logical_key(block: &Block) -> Option<&str> {
+    block
+        .metadata
+        .custom
+        .get(META_LOGICAL_KEY)
+        .and_then(Value::as_str)
+}
+
+fn coderef_path(block: &Block) -> Option<&str> {
+    block
+        .metadata
+        .custom
+        .get(META_CODEREF)
+        .and_then(|value| value.get("path"))
+        .and_then(Value::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{build_code_graph, CodeGraphBuildInput, CodeGraphExtractorConfig};
+    use std::fs;
+    use tempfile::tempdir;
+
+    /// `app` imports `left` and `right`, which both import `base`; `base`
+    /// imports `app` back to close a cycle
+    fn diamond_with_cycle() -> Document {
+        let dir = tempdir().unwrap();
+        let files = [
+            ("app.py", "import left\nimport right\n"),
+            ("left.py", "import base\n"),
+            ("right.py", "import base\n"),
+            ("base.py", "import app\n"),
+            ("lonely.py", "x = 1\n"),
+        ];
+        for (name, source) in files {
+            fs::write(dir.path().join(name), source).unwrap();
+        }
+        build_code_graph(&CodeGraphBuildInput {
+            repository_path: dir.path().to_path_buf(),
+            commit_hash: "impact".to_string(),
+            config: CodeGraphExtractorConfig::default(),
+        })
+        .unwrap()
+        .document
+    }
+
+    fn summary(entries: &[ImpactEntry]) -> Vec<(usize, &str, Option<&str>)> {
+        entries
+            .iter()
+            .map(|e| (e.depth, e.path.as_str(), e.via.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn test_dependents_walk_diamond_and_stop_at_cycle() {
+        let doc = diamond_with_cycle();
+        let entries = dependents_of(&doc, "base.py", usize::MAX);
+        assert_eq!(
+            summary(&entries),
+            vec![
+                (1, "left.py", None),
+                (1, "right.py", None),
+                (2, "app.py", Some("left.py")),
+            ]
+        );
+        assert_eq!(entries[0].logical_key, "file:left.py");
+
+        assert_eq!(summary(&dependents_of(&doc, "base.py", 1)).len(), 2);
+        assert!(dependents_of(&doc, "lonely.py", usize::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_dependencies_follow_edges_forward() {
+        let doc = diamond_with_cycle();
+        assert_eq!(
+            summary(&dependencies_of(&doc, "./app.py", usize::MAX)),
+            vec![
+                (1, "left.py", None),
+                (1, "right.py", None),
+                (2, "base.py", Some("left.py")),
+            ]
+        );
+    }
+}
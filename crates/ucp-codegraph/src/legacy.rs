@@ -12,9 +12,10 @@ mod languages {
     pub(super) mod ts_js;
 }
 
+pub use analyze::check_code_syntax;
 pub use build::build_code_graph;
-pub use canonical::{canonical_codegraph_json, canonical_fingerprint};
-pub use incremental::build_code_graph_incremental;
+pub use canonical::{canonical_codegraph_json, canonical_fingerprint, code_graph_stats};
+pub use incremental::{build_code_graph_incremental, update_code_graph};
 pub use validate::validate_code_graph_profile;
 
 use analyze::{analyze_file, is_python_package_init};
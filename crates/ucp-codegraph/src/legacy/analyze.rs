@@ -1,4 +1,4 @@
-use tree_sitter::{Language, Parser};
+use tree_sitter::{Language, Node, Parser};
 
 use crate::model::*;
 
@@ -65,6 +65,49 @@ pub(super) fn analyze_file(path: &str, source: &str, language: CodeLanguage) ->
     analysis
 }
 
+/// Parse `source` as `language` and list its syntax errors.
+///
+/// The language is canonicalized first, so aliases like `rs` work. Returns
+/// `None` if there is no parser for the language.
+pub fn check_code_syntax(language: &str, source: &str) -> Option<Vec<CodeSyntaxError>> {
+    let language = CodeLanguage::from_canonical(&ucm_core::canonical_language(language))?;
+    let mut parser = Parser::new();
+    parser.set_language(&language_for(language)).ok()?;
+    let tree = parser.parse(source, None)?;
+
+    let mut errors = Vec::new();
+    collect_syntax_errors(tree.root_node(), source, &mut errors);
+    Some(errors)
+}
+
+fn collect_syntax_errors(node: Node, source: &str, errors: &mut Vec<CodeSyntaxError>) {
+    if !node.has_error() {
+        return;
+    }
+    if node.is_error() || node.is_missing() {
+        let position = node.start_position();
+        let message = if node.is_missing() {
+            format!("missing {}", node.kind())
+        } else {
+            let text = node.utf8_text(source.as_bytes()).unwrap_or("");
+            match text.split_whitespace().next() {
+                Some(token) => format!("unexpected `{}`", token),
+                None => "syntax error".to_string(),
+            }
+        };
+        errors.push(CodeSyntaxError {
+            line: position.row + 1,
+            column: position.column + 1,
+            message,
+        });
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_syntax_errors(child, source, errors);
+    }
+}
+
 pub(super) fn language_for(language: CodeLanguage) -> Language {
     match language {
         CodeLanguage::Rust => tree_sitter_rust::LANGUAGE.into(),
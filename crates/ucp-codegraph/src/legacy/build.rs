@@ -1,10 +1,12 @@
 use anyhow::{anyhow, Context, Result};
+use rayon::prelude::*;
 use serde_json::json;
 use sha2::{Digest, Sha256};
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::path::Path;
-use ucm_core::{Block, BlockId, Content, Document, DocumentId, Edge, EdgeType};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use ucm_core::{Block, BlockId, Content, Document, DocumentId, Edge, EdgeType, LazyDocument};
 
 use crate::model::*;
 
@@ -12,11 +14,11 @@ use super::languages::ts_js::extend_unique_block_ids;
 use super::{
     alias_scope_key, analyze_file, ancestor_directories, canonical_fingerprint,
     collect_repository_files, compare_extracted_symbols, compute_stats, format_coderef,
-    format_line_range, normalize_path, normalize_temporal_fields, parent_directory_id,
-    parent_id_for_file, resolve_alias_target_ids, resolve_import, resolve_relationship_target_ids,
-    resolve_usage_target_ids, sanitize_identifier, sort_edges,
-    sort_structure_children_by_logical_key, unique_symbol_logical_key, validate_code_graph_profile,
-    GitignoreMatcher,
+    format_line_range, is_repository_call, normalize_path, normalize_temporal_fields,
+    parent_directory_id, parent_id_for_file, resolve_alias_target_ids, resolve_import,
+    resolve_relationship_target_ids, resolve_usage_target_ids, sanitize_identifier, sort_edges,
+    sort_structure_children_by_logical_key, truncate_text, unique_symbol_logical_key,
+    validate_code_graph_profile, GitignoreMatcher,
 };
 
 pub fn build_code_graph(input: &CodeGraphBuildInput) -> Result<CodeGraphBuildResult> {
@@ -75,14 +77,32 @@ pub fn build_code_graph(input: &CodeGraphBuildInput) -> Result<CodeGraphBuildRes
         BTreeMap::new();
     let mut default_exported_top_level_symbol_ids: BTreeMap<String, Vec<BlockId>> = BTreeMap::new();
     let mut file_analyses = Vec::new();
-    let mut used_symbol_keys: HashSet<String> = HashSet::new();
 
-    for file in files {
+    // Reading, parsing and block construction run on worker threads; blocks
+    // are then added in `relative_path` order (the order `files` is in) so
+    // output does not depend on scheduling
+    let outcomes = map_parallel(
+        files.iter().collect(),
+        input.config.parallelism,
+        |file: &RepoFile| match fs::read_to_string(&file.absolute_path) {
+            Err(err) => SourceOutcome::Unreadable(err),
+            Ok(source) if source.len() > input.config.max_file_bytes => {
+                SourceOutcome::TooLarge(source.len())
+            }
+            Ok(source) => SourceOutcome::Analyzed(Box::new(FileAnalysisOutput::new(
+                file,
+                analyze_file(&file.relative_path, &source, file.language),
+                &input.config,
+            ))),
+        },
+    );
+
+    for (file, outcome) in files.into_iter().zip(outcomes) {
         let parent_id = parent_id_for_file(&file.relative_path, repo_block_id, &directory_ids);
 
-        let source = match fs::read_to_string(&file.absolute_path) {
-            Ok(s) => s,
-            Err(err) => {
+        let output = match outcome {
+            SourceOutcome::Analyzed(output) => *output,
+            SourceOutcome::Unreadable(err) => {
                 let diag = CodeGraphDiagnostic::error(
                     "CG2003",
                     format!("failed to read source file: {}", err),
@@ -98,69 +118,41 @@ pub fn build_code_graph(input: &CodeGraphBuildInput) -> Result<CodeGraphBuildRes
                     err
                 ));
             }
+            SourceOutcome::TooLarge(len) => {
+                diagnostics.push(
+                    CodeGraphDiagnostic::warning(
+                        "CG2008",
+                        format!(
+                            "file skipped due to size limit ({} bytes > {} bytes)",
+                            len, input.config.max_file_bytes
+                        ),
+                    )
+                    .with_path(file.relative_path.clone()),
+                );
+                continue;
+            }
         };
 
-        if source.len() > input.config.max_file_bytes {
-            diagnostics.push(
-                CodeGraphDiagnostic::warning(
-                    "CG2008",
-                    format!(
-                        "file skipped due to size limit ({} bytes > {} bytes)",
-                        source.len(),
-                        input.config.max_file_bytes
-                    ),
-                )
-                .with_path(file.relative_path.clone()),
-            );
-            continue;
-        }
-
-        let FileAnalysis {
-            file_description,
-            mut symbols,
-            imports,
-            relationships,
-            usages,
-            aliases,
-            export_bindings,
+        let FileAnalysisOutput {
+            file_block,
+            symbol_blocks,
             default_exported_symbol_names,
-            diagnostics: analysis_diagnostics,
-            ..
-        } = analyze_file(&file.relative_path, &source, file.language);
+            record,
+            diagnostics: file_diagnostics,
+        } = output;
 
-        let file_block = make_file_block(
-            &file.relative_path,
-            file.language.as_str(),
-            file_description.as_deref(),
-        );
         let file_block_id = doc.add_block(file_block, &parent_id)?;
         file_ids.insert(file.relative_path.clone(), file_block_id);
+        diagnostics.extend(file_diagnostics);
 
-        for diag in &analysis_diagnostics {
-            diagnostics.push(diag.clone().with_path(file.relative_path.clone()));
-        }
-
-        symbols.sort_by(compare_extracted_symbols);
         let mut symbol_ids_by_identity: BTreeMap<String, BlockId> = BTreeMap::new();
 
-        for symbol in &symbols {
+        for (symbol, symbol_block) in symbol_blocks {
             let parent_block_id = symbol
                 .parent_identity
                 .as_ref()
                 .and_then(|identity| symbol_ids_by_identity.get(identity).copied())
                 .unwrap_or(file_block_id);
-            let logical_key = unique_symbol_logical_key(
-                &file.relative_path,
-                &symbol.qualified_name,
-                symbol.start_line,
-                &mut used_symbol_keys,
-            );
-            let symbol_block = make_symbol_block(
-                &logical_key,
-                &file.relative_path,
-                file.language.as_str(),
-                symbol,
-            );
             let symbol_id = doc.add_block(symbol_block, &parent_block_id)?;
             symbol_ids_by_identity.insert(symbol.identity.clone(), symbol_id);
             symbol_ids_by_file_identity.insert(
@@ -201,15 +193,7 @@ pub fn build_code_graph(input: &CodeGraphBuildInput) -> Result<CodeGraphBuildRes
             }
         }
 
-        file_analyses.push(FileAnalysisRecord {
-            file: file.relative_path,
-            language: file.language,
-            imports,
-            relationships,
-            usages,
-            aliases,
-            export_bindings,
-        });
+        file_analyses.push(record);
     }
 
     let known_files: BTreeSet<String> = file_ids.keys().cloned().collect();
@@ -239,7 +223,8 @@ pub fn build_code_graph(input: &CodeGraphBuildInput) -> Result<CodeGraphBuildRes
     let mut pending_wildcard_reexport_edges: BTreeSet<(String, String, String, Vec<String>)> =
         BTreeSet::new();
     let mut pending_relationship_edges: Vec<(BlockId, BlockId, String, String)> = Vec::new();
-    let mut pending_usage_edges: Vec<(BlockId, BlockId, String)> = Vec::new();
+    let mut pending_usage_edges: Vec<(BlockId, BlockId, String, UsageKind, f32)> = Vec::new();
+    let mut unresolved_calls: BTreeSet<(String, String)> = BTreeSet::new();
 
     for (file, exports) in &exported_top_level_symbol_ids {
         let entry = exported_symbol_targets_by_file
@@ -586,7 +571,7 @@ pub fn build_code_graph(input: &CodeGraphBuildInput) -> Result<CodeGraphBuildRes
                 continue;
             };
 
-            for target_id in resolve_usage_target_ids(
+            let target_ids = resolve_usage_target_ids(
                 &record.file,
                 record.language,
                 usage,
@@ -599,8 +584,28 @@ pub fn build_code_graph(input: &CodeGraphBuildInput) -> Result<CodeGraphBuildRes
                 &alias_records_by_scope,
                 &aliased_symbol_targets_by_scope,
                 &known_files,
-            ) {
-                let edge = (*source_id, target_id, usage.target_expr.clone());
+            );
+            if target_ids.is_empty()
+                && is_repository_call(
+                    &record.file,
+                    record.language,
+                    usage,
+                    &imported_module_targets_by_file,
+                    &alias_names_by_scope,
+                    &alias_records_by_scope,
+                )
+            {
+                unresolved_calls.insert((record.file.clone(), usage.target_expr.clone()));
+            }
+            let confidence = usage_confidence(usage.kind, target_ids.len());
+            for target_id in target_ids {
+                let edge = (
+                    *source_id,
+                    target_id,
+                    usage.target_expr.clone(),
+                    usage.kind,
+                    confidence,
+                );
                 if !pending_usage_edges.contains(&edge) {
                     pending_usage_edges.push(edge);
                 }
@@ -757,19 +762,19 @@ pub fn build_code_graph(input: &CodeGraphBuildInput) -> Result<CodeGraphBuildRes
         }
     }
 
-    for (source_id, target_id, raw_target) in pending_usage_edges {
-        let mut edge = Edge::new(EdgeType::Custom("uses_symbol".to_string()), target_id);
-        edge.metadata
-            .custom
-            .insert("relation".to_string(), json!("uses_symbol"));
-        edge.metadata
-            .custom
-            .insert("raw_target".to_string(), json!(raw_target));
+    for (source_id, target_id, raw_target, kind, confidence) in pending_usage_edges {
         if let Some(source_block) = doc.get_block_mut(&source_id) {
-            source_block.edges.push(edge);
+            push_usage_edges(source_block, kind, target_id, &raw_target, confidence);
         }
     }
 
+    for (path, target) in unresolved_calls {
+        diagnostics.push(
+            CodeGraphDiagnostic::info("CG2012", format!("unresolved call '{}'", target))
+                .with_path(path),
+        );
+    }
+
     sort_structure_children_by_logical_key(&mut doc);
     sort_edges(&mut doc);
     normalize_temporal_fields(&mut doc);
@@ -796,7 +801,7 @@ pub fn build_code_graph(input: &CodeGraphBuildInput) -> Result<CodeGraphBuildRes
         CodeGraphBuildStatus::Success
     };
 
-    Ok(CodeGraphBuildResult {
+    CodeGraphBuildResult {
         document: doc,
         diagnostics,
         stats,
@@ -804,7 +809,32 @@ pub fn build_code_graph(input: &CodeGraphBuildInput) -> Result<CodeGraphBuildRes
         canonical_fingerprint: fingerprint,
         status,
         incremental: None,
-    })
+        lazy_document: None,
+    }
+    .with_lazy_document(input.config.lazy_loading)
+}
+
+static LAZY_SPILL_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+impl CodeGraphBuildResult {
+    /// Spill the document to a temporary file and map it back lazily
+    pub(super) fn with_lazy_document(mut self, enabled: bool) -> Result<Self> {
+        if !enabled {
+            return Ok(self);
+        }
+        let path = std::env::temp_dir().join(format!(
+            "ucp-codegraph-{}-{}.ucml",
+            std::process::id(),
+            LAZY_SPILL_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        LazyDocument::write(&self.document, &path)
+            .with_context(|| format!("failed to write lazy document {}", path.display()))?;
+        let lazy = LazyDocument::open(&path);
+        // The mapping outlives the directory entry where the OS allows it
+        let _ = fs::remove_file(&path);
+        self.lazy_document = Some(lazy.context("failed to map lazy document")?);
+        Ok(self)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -815,6 +845,108 @@ pub(super) struct LoadedRepoFile {
     pub diagnostics: Vec<CodeGraphDiagnostic>,
 }
 
+enum SourceOutcome {
+    Unreadable(std::io::Error),
+    TooLarge(usize),
+    Analyzed(Box<FileAnalysisOutput>),
+}
+
+/// The blocks and resolution inputs for one file, built off the main thread
+struct FileAnalysisOutput {
+    file_block: Block,
+    /// In `compare_extracted_symbols` order, so parents precede children
+    symbol_blocks: Vec<(ExtractedSymbol, Block)>,
+    default_exported_symbol_names: BTreeSet<String>,
+    record: FileAnalysisRecord,
+    diagnostics: Vec<CodeGraphDiagnostic>,
+}
+
+impl FileAnalysisOutput {
+    fn new(file: &RepoFile, analysis: FileAnalysis, config: &CodeGraphExtractorConfig) -> Self {
+        let FileAnalysis {
+            file_description,
+            mut symbols,
+            imports,
+            relationships,
+            usages,
+            aliases,
+            export_bindings,
+            default_exported_symbol_names,
+            diagnostics,
+            ..
+        } = analysis;
+
+        let file_block = make_file_block(
+            &file.relative_path,
+            file.language.as_str(),
+            file_description.as_deref(),
+            config.max_doc_chars,
+        );
+
+        // Logical keys start with the file path, so they only need to be
+        // unique within the file
+        let mut used_symbol_keys = HashSet::new();
+        retain_symbol_depth(&mut symbols, config.symbol_depth);
+        symbols.sort_by(compare_extracted_symbols);
+        let symbol_blocks = symbols
+            .into_iter()
+            .map(|symbol| {
+                let logical_key = unique_symbol_logical_key(
+                    &file.relative_path,
+                    &symbol.qualified_name,
+                    symbol.start_line,
+                    &mut used_symbol_keys,
+                );
+                let block = make_symbol_block(
+                    &logical_key,
+                    &file.relative_path,
+                    file.language.as_str(),
+                    &symbol,
+                    config.max_doc_chars,
+                );
+                (symbol, block)
+            })
+            .collect();
+
+        Self {
+            file_block,
+            symbol_blocks,
+            default_exported_symbol_names,
+            record: FileAnalysisRecord {
+                file: file.relative_path.clone(),
+                language: file.language,
+                imports,
+                relationships,
+                usages,
+                aliases,
+                export_bindings,
+            },
+            diagnostics: diagnostics
+                .into_iter()
+                .map(|diag| diag.with_path(file.relative_path.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// Map `items` in order, across `parallelism` threads (see
+/// [`CodeGraphExtractorConfig::parallelism`])
+pub(super) fn map_parallel<T, R, F>(items: Vec<T>, parallelism: Option<usize>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync + Send,
+{
+    match parallelism {
+        Some(0 | 1) => items.into_iter().map(f).collect(),
+        None => items.into_par_iter().map(f).collect(),
+        Some(threads) => match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+            Ok(pool) => pool.install(|| items.into_par_iter().map(f).collect()),
+            Err(_) => items.into_iter().map(f).collect(),
+        },
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(super) struct AnalyzedRepoFile {
     pub relative_path: String,
@@ -830,6 +962,13 @@ pub(super) struct AssembledCodeGraph {
     pub dependencies_by_file: BTreeMap<String, Vec<String>>,
 }
 
+/// Drop symbols below `depth`; members' own members go with them
+fn retain_symbol_depth(symbols: &mut Vec<ExtractedSymbol>, depth: SymbolDepth) {
+    if depth == SymbolDepth::TopLevel {
+        symbols.retain(|symbol| symbol.parent_identity.is_none());
+    }
+}
+
 pub(super) fn hash_source(source: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(source.as_bytes());
@@ -974,11 +1113,13 @@ pub(super) fn assemble_code_graph_from_analyzed_files(
             &analyzed_file.relative_path,
             analyzed_file.language.as_str(),
             analysis.file_description.as_deref(),
+            config.max_doc_chars,
         );
         let file_block_id = doc.add_block(file_block, &parent_id)?;
         file_ids.insert(analyzed_file.relative_path.clone(), file_block_id);
 
         let mut symbols = analysis.symbols.clone();
+        retain_symbol_depth(&mut symbols, config.symbol_depth);
         symbols.sort_by(compare_extracted_symbols);
         let mut symbol_ids_by_identity: BTreeMap<String, BlockId> = BTreeMap::new();
 
@@ -999,6 +1140,7 @@ pub(super) fn assemble_code_graph_from_analyzed_files(
                 &analyzed_file.relative_path,
                 analyzed_file.language.as_str(),
                 symbol,
+                config.max_doc_chars,
             );
             let symbol_id = doc.add_block(symbol_block, &parent_block_id)?;
             symbol_ids_by_identity.insert(symbol.identity.clone(), symbol_id);
@@ -1086,7 +1228,8 @@ pub(super) fn assemble_code_graph_from_analyzed_files(
     let mut pending_wildcard_reexport_edges: BTreeSet<(String, String, String, Vec<String>)> =
         BTreeSet::new();
     let mut pending_relationship_edges: Vec<(BlockId, BlockId, String, String)> = Vec::new();
-    let mut pending_usage_edges: Vec<(BlockId, BlockId, String)> = Vec::new();
+    let mut pending_usage_edges: Vec<(BlockId, BlockId, String, UsageKind, f32)> = Vec::new();
+    let mut unresolved_calls: BTreeSet<(String, String)> = BTreeSet::new();
 
     for (file, exports) in &exported_top_level_symbol_ids {
         let entry = exported_symbol_targets_by_file
@@ -1445,7 +1588,7 @@ pub(super) fn assemble_code_graph_from_analyzed_files(
                 continue;
             };
 
-            for target_id in resolve_usage_target_ids(
+            let target_ids = resolve_usage_target_ids(
                 &record.file,
                 record.language,
                 usage,
@@ -1458,7 +1601,21 @@ pub(super) fn assemble_code_graph_from_analyzed_files(
                 &alias_records_by_scope,
                 &aliased_symbol_targets_by_scope,
                 &known_files,
-            ) {
+            );
+            if target_ids.is_empty()
+                && is_repository_call(
+                    &record.file,
+                    record.language,
+                    usage,
+                    &imported_module_targets_by_file,
+                    &alias_names_by_scope,
+                    &alias_records_by_scope,
+                )
+            {
+                unresolved_calls.insert((record.file.clone(), usage.target_expr.clone()));
+            }
+            let confidence = usage_confidence(usage.kind, target_ids.len());
+            for target_id in target_ids {
                 if let Some(target_file) = symbol_file_by_id.get(&target_id) {
                     if target_file != &record.file {
                         dependencies_by_file
@@ -1467,7 +1624,13 @@ pub(super) fn assemble_code_graph_from_analyzed_files(
                             .insert(target_file.clone());
                     }
                 }
-                let edge = (*source_id, target_id, usage.target_expr.clone());
+                let edge = (
+                    *source_id,
+                    target_id,
+                    usage.target_expr.clone(),
+                    usage.kind,
+                    confidence,
+                );
                 if !pending_usage_edges.contains(&edge) {
                     pending_usage_edges.push(edge);
                 }
@@ -1624,19 +1787,19 @@ pub(super) fn assemble_code_graph_from_analyzed_files(
         }
     }
 
-    for (source_id, target_id, raw_target) in pending_usage_edges {
-        let mut edge = Edge::new(EdgeType::Custom("uses_symbol".to_string()), target_id);
-        edge.metadata
-            .custom
-            .insert("relation".to_string(), json!("uses_symbol"));
-        edge.metadata
-            .custom
-            .insert("raw_target".to_string(), json!(raw_target));
+    for (source_id, target_id, raw_target, kind, confidence) in pending_usage_edges {
         if let Some(source_block) = doc.get_block_mut(&source_id) {
-            source_block.edges.push(edge);
+            push_usage_edges(source_block, kind, target_id, &raw_target, confidence);
         }
     }
 
+    for (path, target) in unresolved_calls {
+        diagnostics.push(
+            CodeGraphDiagnostic::info("CG2012", format!("unresolved call '{}'", target))
+                .with_path(path),
+        );
+    }
+
     sort_structure_children_by_logical_key(&mut doc);
     sort_edges(&mut doc);
     normalize_temporal_fields(&mut doc);
@@ -1670,6 +1833,7 @@ pub(super) fn assemble_code_graph_from_analyzed_files(
             canonical_fingerprint: fingerprint,
             status,
             incremental: None,
+            lazy_document: None,
         },
         dependencies_by_file: dependencies_by_file
             .into_iter()
@@ -1767,7 +1931,13 @@ pub(super) fn make_directory_block(path: &str) -> Block {
     block
 }
 
-pub(super) fn make_file_block(path: &str, language: &str, description: Option<&str>) -> Block {
+pub(super) fn make_file_block(
+    path: &str,
+    language: &str,
+    description: Option<&str>,
+    max_doc_chars: usize,
+) -> Block {
+    let description = doc_text(description, max_doc_chars);
     let coderef = json!({
         "path": path,
         "display": path,
@@ -1775,7 +1945,7 @@ pub(super) fn make_file_block(path: &str, language: &str, description: Option<&s
     let mut content = serde_json::Map::new();
     content.insert("coderef".to_string(), coderef.clone());
     content.insert("language".to_string(), json!(language));
-    if let Some(description) = description {
+    if let Some(description) = &description {
         content.insert("description".to_string(), json!(description));
     }
 
@@ -1784,7 +1954,7 @@ pub(super) fn make_file_block(path: &str, language: &str, description: Option<&s
         Some("custom.file"),
     );
     block.metadata.label = Some(path.to_string());
-    block.metadata.summary = description.map(|value| value.to_string());
+    block.metadata.summary = description;
     block
         .metadata
         .custom
@@ -1809,7 +1979,9 @@ pub(super) fn make_symbol_block(
     path: &str,
     language: &str,
     symbol: &ExtractedSymbol,
+    max_doc_chars: usize,
 ) -> Block {
+    let doc = doc_text(symbol.description.as_deref(), max_doc_chars);
     let line_range = format_line_range(symbol.start_line, symbol.end_line);
     let coderef = json!({
         "path": path,
@@ -1825,7 +1997,7 @@ pub(super) fn make_symbol_block(
     content.insert("kind".to_string(), json!(symbol.kind));
     content.insert("coderef".to_string(), coderef.clone());
     content.insert("exported".to_string(), json!(symbol.exported));
-    if let Some(description) = &symbol.description {
+    if let Some(description) = &doc {
         content.insert("description".to_string(), json!(description));
     }
     if !symbol.modifiers.is_empty() {
@@ -1847,7 +2019,7 @@ pub(super) fn make_symbol_block(
     );
 
     block.metadata.label = Some(symbol.name.clone());
-    block.metadata.summary = symbol.description.clone();
+    block.metadata.summary = doc.clone();
     block
         .metadata
         .custom
@@ -1876,5 +2048,70 @@ pub(super) fn make_symbol_block(
         .metadata
         .custom
         .insert(META_EXPORTED.to_string(), json!(symbol.exported));
+    if let Some(doc) = doc {
+        block
+            .metadata
+            .custom
+            .insert(META_DOC.to_string(), json!(doc));
+    }
     block
 }
+
+/// Whitespace-normalized doc text capped at `max_chars`; `0` disables docs
+fn doc_text(raw: Option<&str>, max_chars: usize) -> Option<String> {
+    if max_chars == 0 {
+        return None;
+    }
+    truncate_text(raw?, max_chars)
+}
+
+/// Add the edges for one usage: `uses_symbol` for every reference, as
+/// before `calls` existed, plus `calls` when the reference is a call. The
+/// confidence is split between the candidates when a name resolves to more
+/// than one symbol; plain references start lower because locals that
+/// shadow a top-level name are not tracked.
+fn push_usage_edges(
+    block: &mut Block,
+    kind: UsageKind,
+    target_id: BlockId,
+    raw_target: &str,
+    confidence: f32,
+) {
+    let relations: &[&str] = match kind {
+        UsageKind::Call => &["calls", "uses_symbol"],
+        UsageKind::Reference => &["uses_symbol"],
+    };
+    for relation in relations {
+        let edge = usage_edge(relation, target_id, raw_target, confidence);
+        // A symbol that both calls and passes a name gets one `uses_symbol`
+        let duplicate = block.edges.iter().any(|existing| {
+            existing.edge_type == edge.edge_type
+                && existing.target == edge.target
+                && existing.metadata.custom.get("raw_target")
+                    == edge.metadata.custom.get("raw_target")
+        });
+        if !duplicate {
+            block.edges.push(edge);
+        }
+    }
+}
+
+fn usage_edge(relation: &str, target_id: BlockId, raw_target: &str, confidence: f32) -> Edge {
+    let mut edge =
+        Edge::new(EdgeType::Custom(relation.to_string()), target_id).with_confidence(confidence);
+    edge.metadata
+        .custom
+        .insert("relation".to_string(), json!(relation));
+    edge.metadata
+        .custom
+        .insert("raw_target".to_string(), json!(raw_target));
+    edge
+}
+
+fn usage_confidence(kind: UsageKind, candidates: usize) -> f32 {
+    let base = match kind {
+        UsageKind::Call => 1.0,
+        UsageKind::Reference => 0.8,
+    };
+    base / candidates.max(1) as f32
+}
@@ -90,12 +90,23 @@ pub fn canonical_codegraph_json(doc: &Document) -> Result<String> {
                 .get(&edge.target)
                 .cloned()
                 .unwrap_or_else(|| edge.target.to_string());
-            edge_entries.push(json!({
+            let mut entry = json!({
                 "source": source_key,
                 "edge_type": edge.edge_type.as_str(),
                 "target": target_key,
                 "metadata": normalized_edge_metadata(edge),
-            }));
+            });
+            // Only weighted edges carry the key, so existing fingerprints hold
+            if let Some(weight) = edge.weight {
+                entry["weight"] = json!(weight);
+            }
+            if let Some(from) = edge.valid_from {
+                entry["valid_from"] = json!(from.to_rfc3339());
+            }
+            if let Some(to) = edge.valid_to {
+                entry["valid_to"] = json!(to.to_rfc3339());
+            }
+            edge_entries.push(entry);
         }
     }
 
@@ -199,6 +210,12 @@ pub(super) fn sort_edges(doc: &mut Document) {
     }
 }
 
+/// Node and edge counts for an existing CodeGraph document, the same figures
+/// a build reports in [`crate::CodeGraphBuildResult::stats`]
+pub fn code_graph_stats(doc: &Document) -> CodeGraphStats {
+    compute_stats(doc)
+}
+
 pub(super) fn compute_stats(doc: &Document) -> CodeGraphStats {
     let mut stats = CodeGraphStats::default();
 
@@ -223,7 +240,16 @@ pub(super) fn compute_stats(doc: &Document) -> CodeGraphStats {
                     *stats.languages.entry(lang.to_string()).or_default() += 1;
                 }
             }
-            Some("symbol") => stats.symbol_nodes += 1,
+            Some("symbol") => {
+                stats.symbol_nodes += 1;
+                let parent_class = doc
+                    .parent(id)
+                    .and_then(|parent| doc.get_block(parent))
+                    .and_then(node_class);
+                if parent_class.as_deref() == Some("symbol") {
+                    stats.nested_symbol_nodes += 1;
+                }
+            }
             _ => {}
         }
 
@@ -232,6 +258,7 @@ pub(super) fn compute_stats(doc: &Document) -> CodeGraphStats {
             match &edge.edge_type {
                 EdgeType::References => stats.reference_edges += 1,
                 EdgeType::Custom(name) if name == "exports" => stats.export_edges += 1,
+                EdgeType::Custom(name) if name == "calls" => stats.call_edges += 1,
                 _ => {}
             }
         }
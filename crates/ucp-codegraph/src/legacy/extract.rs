@@ -14,7 +14,8 @@ pub(super) fn extract_symbol_description(
     node: Node<'_>,
 ) -> Option<String> {
     match language {
-        CodeLanguage::Rust => extract_preceding_symbol_comment(source, node, language),
+        CodeLanguage::Rust => extract_preceding_symbol_comment(source, node, language)
+            .or_else(|| extract_rust_inner_doc(source, node)),
         CodeLanguage::Python => extract_python_symbol_description(source, node)
             .or_else(|| extract_preceding_symbol_comment(source, node, language)),
         CodeLanguage::TypeScript | CodeLanguage::JavaScript => {
@@ -671,10 +672,8 @@ pub(super) fn extract_leading_block_comment(lines: &[&str]) -> Option<String> {
     let first = lines.first()?.trim_start();
     let rest = if let Some(rest) = first.strip_prefix("/**") {
         rest
-    } else if let Some(rest) = first.strip_prefix("/*") {
-        rest
     } else {
-        return None;
+        first.strip_prefix("/*")?
     };
 
     let mut raw = String::new();
@@ -746,7 +745,7 @@ pub(super) fn normalize_description_text(raw: &str) -> Option<String> {
         return None;
     }
 
-    truncate_text(&lines.join(" "), 200)
+    truncate_text(&lines.join(" "), usize::MAX)
 }
 
 pub(super) fn extract_python_symbol_description(source: &str, node: Node<'_>) -> Option<String> {
@@ -811,6 +810,26 @@ pub(super) fn extract_preceding_symbol_comment(
         .or_else(|| extract_preceding_line_comment_block_before(&lines, index, language))
 }
 
+/// Inline modules may document themselves with `//!` lines at the top of their body
+pub(super) fn extract_rust_inner_doc(source: &str, node: Node<'_>) -> Option<String> {
+    if node.kind() != "mod_item" {
+        return None;
+    }
+    let body = node.child_by_field_name("body")?;
+    let collected: Vec<&str> = source
+        .lines()
+        .skip(body.start_position().row + 1)
+        .map(str::trim_start)
+        .take_while(|line| line.starts_with("//!"))
+        .map(|line| line["//!".len()..].trim())
+        .collect();
+    if collected.is_empty() {
+        None
+    } else {
+        normalize_description_text(&collected.join("\n"))
+    }
+}
+
 pub(super) fn extract_preceding_block_comment_before(
     lines: &[&str],
     end_index: usize,
@@ -882,6 +901,8 @@ pub(super) fn extract_preceding_line_comment_block_before(
 
 pub(super) fn strip_symbol_line_comment_prefix(line: &str, language: CodeLanguage) -> Option<&str> {
     match language {
+        // Inner `//!` docs belong to the enclosing module, not the next item
+        CodeLanguage::Rust if line.starts_with("//!") => None,
         CodeLanguage::Rust => line.strip_prefix("///").or_else(|| line.strip_prefix("//")),
         CodeLanguage::Python => line.strip_prefix('#'),
         CodeLanguage::TypeScript | CodeLanguage::JavaScript => line.strip_prefix("//"),
@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
 use regex::Regex;
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::fs;
 use std::path::Path;
 use ucm_core::BlockId;
@@ -156,6 +156,45 @@ pub(super) fn collect_repository_files_recursive(
     Ok(())
 }
 
+/// The repository file at `rel`, if a full scan would include it
+pub(super) fn repository_file(
+    root: &Path,
+    rel: &str,
+    config: &CodeGraphExtractorConfig,
+    matcher: &GitignoreMatcher,
+) -> Option<RepoFile> {
+    let absolute_path = root.join(rel);
+    if !absolute_path.is_file() || (!config.include_hidden && is_hidden_path(rel)) {
+        return None;
+    }
+    let dirs = ancestor_directories(rel);
+    let excluded = dirs.iter().any(|dir| {
+        let name = dir.rsplit('/').next().unwrap_or(dir);
+        config.exclude_dirs.iter().any(|excluded| excluded == name) || matcher.is_ignored(dir, true)
+    });
+    if excluded || matcher.is_ignored(rel, false) {
+        return None;
+    }
+
+    let ext = Path::new(rel)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    let included = config
+        .include_extensions
+        .iter()
+        .any(|include| include.trim_start_matches('.').eq_ignore_ascii_case(&ext));
+    if !included {
+        return None;
+    }
+    Some(RepoFile {
+        absolute_path,
+        relative_path: rel.to_string(),
+        language: extension_language(&ext)?,
+    })
+}
+
 pub(super) fn extension_language(ext: &str) -> Option<CodeLanguage> {
     match ext {
         "rs" => Some(CodeLanguage::Rust),
@@ -305,6 +344,9 @@ pub(super) fn is_hidden_path(path: &str) -> bool {
     path.split('/').any(|part| part.starts_with('.'))
 }
 
+/// Rules from every `.gitignore` in the repository, ordered root first and
+/// then by depth so that, as in git, the last matching rule wins and deeper
+/// files override shallower ones
 #[derive(Debug, Clone)]
 pub(super) struct GitignoreMatcher {
     rules: Vec<GitignoreRule>,
@@ -314,48 +356,90 @@ pub(super) struct GitignoreMatcher {
 pub(super) struct GitignoreRule {
     pub(super) regex: Regex,
     pub(super) directory_only: bool,
+    /// `!pattern`: re-include a path an earlier rule ignored
+    pub(super) negated: bool,
+    /// Directory holding the `.gitignore`, relative to the repository root
+    pub(super) base: String,
 }
 
 impl GitignoreMatcher {
     pub(super) fn from_repository(repo_root: &Path) -> Result<Self> {
-        let gitignore_path = repo_root.join(".gitignore");
-        if !gitignore_path.exists() {
-            return Ok(Self { rules: Vec::new() });
-        }
-
-        let raw = fs::read_to_string(&gitignore_path)
-            .with_context(|| format!("failed to read {}", gitignore_path.display()))?;
-
-        let mut rules = Vec::new();
-        for line in raw.lines() {
-            let trimmed = line.trim();
-            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('!') {
-                continue;
+        let mut matcher = Self { rules: Vec::new() };
+        // Breadth-first in name order keeps rule order deterministic; a
+        // directory is only searched if the rules loaded so far keep it
+        let mut pending = VecDeque::from([String::new()]);
+        while let Some(dir) = pending.pop_front() {
+            let dir_path = repo_root.join(&dir);
+            let gitignore_path = dir_path.join(".gitignore");
+            if gitignore_path.is_file() {
+                let raw = fs::read_to_string(&gitignore_path)
+                    .with_context(|| format!("failed to read {}", gitignore_path.display()))?;
+                matcher.rules.extend(
+                    raw.lines()
+                        .filter_map(|line| GitignoreRule::from_pattern(&dir, line)),
+                );
             }
 
-            if let Some(rule) = GitignoreRule::from_pattern(trimmed) {
-                rules.push(rule);
+            let Ok(read_dir) = fs::read_dir(&dir_path) else {
+                continue;
+            };
+            let mut subdirs: Vec<String> = read_dir
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| name != ".git")
+                .collect();
+            subdirs.sort();
+            for name in subdirs {
+                let rel = if dir.is_empty() {
+                    name
+                } else {
+                    format!("{}/{}", dir, name)
+                };
+                if !matcher.is_ignored(&rel, true) {
+                    pending.push_back(rel);
+                }
             }
         }
-
-        Ok(Self { rules })
+        Ok(matcher)
     }
 
     pub(super) fn is_ignored(&self, rel_path: &str, is_dir: bool) -> bool {
+        let mut ignored = false;
         for rule in &self.rules {
             if rule.directory_only && !is_dir {
                 continue;
             }
-            if rule.regex.is_match(rel_path) {
-                return true;
+            let local = if rule.base.is_empty() {
+                rel_path
+            } else {
+                match rel_path
+                    .strip_prefix(rule.base.as_str())
+                    .and_then(|rest| rest.strip_prefix('/'))
+                {
+                    Some(local) => local,
+                    None => continue,
+                }
+            };
+            if rule.regex.is_match(local) {
+                ignored = !rule.negated;
             }
         }
-        false
+        ignored
     }
 }
 
 impl GitignoreRule {
-    pub(super) fn from_pattern(pattern: &str) -> Option<Self> {
+    /// Parse one `.gitignore` line from the file in `base`
+    pub(super) fn from_pattern(base: &str, line: &str) -> Option<Self> {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            return None;
+        }
+        let negated = trimmed.starts_with('!');
+        let pattern = trimmed.trim_start_matches('!');
+        let pattern = pattern.strip_prefix('\\').unwrap_or(pattern);
+
         let directory_only = pattern.ends_with('/');
         let mut core = pattern.trim_end_matches('/').trim_start_matches("./");
 
@@ -363,7 +447,16 @@ impl GitignoreRule {
             return None;
         }
 
-        let anchored = core.starts_with('/');
+        // A leading `**/` matches in all directories; otherwise a slash
+        // anywhere but the end anchors the pattern to the .gitignore's
+        // directory
+        let anchored = match core.strip_prefix("**/") {
+            Some(rest) => {
+                core = rest;
+                false
+            }
+            None => core.contains('/'),
+        };
         core = core.trim_start_matches('/');
 
         let mut regex = String::new();
@@ -386,6 +479,8 @@ impl GitignoreRule {
         Some(Self {
             regex: compiled,
             directory_only,
+            negated,
+            base: base.to_string(),
         })
     }
 }
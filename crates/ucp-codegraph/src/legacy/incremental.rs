@@ -6,14 +6,17 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
 use crate::model::{CodeLanguage, ExtractedInput, ExtractedModifiers, FileAnalysis, ImportBinding};
+use ucm_core::Document;
+
 use crate::{
-    CodeGraphBuildResult, CodeGraphDiagnostic, CodeGraphExtractorConfig,
-    CodeGraphIncrementalBuildInput, CodeGraphIncrementalStats, CODEGRAPH_EXTRACTOR_VERSION,
+    CodeGraphBuildInput, CodeGraphBuildResult, CodeGraphDiagnostic, CodeGraphExtractorConfig,
+    CodeGraphIncrementalBuildInput, CodeGraphIncrementalStats, CodeGraphUpdateInput,
+    CODEGRAPH_EXTRACTOR_VERSION,
 };
 
 use super::build::{
     analyze_loaded_repo_file, assemble_code_graph_from_analyzed_files, load_repo_file,
-    AnalyzedRepoFile,
+    map_parallel, AnalyzedRepoFile,
 };
 use super::*;
 
@@ -96,7 +99,7 @@ pub fn build_code_graph_incremental(
     let state_status = load_compatible_state(
         &input.state_file,
         &normalized_repo_path,
-        &normalized_config,
+        Some(&normalized_config),
         &mut diagnostics,
     )?;
 
@@ -161,22 +164,27 @@ pub fn build_code_graph_incremental(
         let mut surface_change_roots = deleted_paths.clone();
         let mut surface_changed_files = deleted_paths.len();
 
-        for loaded in loaded_files
+        let direct_rebuilds = loaded_files
             .iter()
             .filter(|loaded| direct_rebuild_paths.contains(&loaded.repo_file.relative_path))
-        {
-            let analyzed = analyze_loaded_repo_file(loaded.clone());
+            .cloned()
+            .collect();
+        for analyzed in map_parallel(
+            direct_rebuilds,
+            input.build.config.parallelism,
+            analyze_loaded_repo_file,
+        ) {
             let current_surface = compute_file_surface_signature(analyzed.analysis.as_ref());
             let previous_surface = state
                 .files
-                .get(&loaded.repo_file.relative_path)
+                .get(&analyzed.relative_path)
                 .map(|entry| entry.surface_signature.as_str())
                 .unwrap_or("");
             if current_surface != previous_surface {
-                surface_change_roots.insert(loaded.repo_file.relative_path.clone());
+                surface_change_roots.insert(analyzed.relative_path.clone());
                 surface_changed_files += 1;
             }
-            pre_analyzed.insert(loaded.repo_file.relative_path.clone(), analyzed);
+            pre_analyzed.insert(analyzed.relative_path.clone(), analyzed);
         }
 
         let expanded_invalidations = expand_invalidations(&surface_change_roots, state);
@@ -227,10 +235,11 @@ pub fn build_code_graph_incremental(
         )
     } else {
         let rebuilt_files = loaded_files.len();
-        let analyzed_files = loaded_files
-            .into_iter()
-            .map(analyze_loaded_repo_file)
-            .collect::<Vec<_>>();
+        let analyzed_files = map_parallel(
+            loaded_files,
+            input.build.config.parallelism,
+            analyze_loaded_repo_file,
+        );
         (
             analyzed_files,
             0,
@@ -275,6 +284,154 @@ pub fn build_code_graph_incremental(
         invalidated_files,
         full_rebuild_reason: state_status.full_rebuild_reason,
     });
+    result.with_lazy_document(input.build.config.lazy_loading)
+}
+
+/// Bring a code graph up to date after the listed files changed.
+///
+/// Only the listed files, and files whose imports they affect, are read and
+/// re-analyzed; everything else comes from the state of the previous build.
+/// The document is then reassembled, so it matches a full rebuild of the same
+/// tree. Falls back to [`build_code_graph_incremental`] if the state is
+/// missing or was written for another repository or extractor version.
+pub fn update_code_graph(
+    doc: &mut Document,
+    input: &CodeGraphUpdateInput,
+) -> Result<CodeGraphBuildResult> {
+    let repository_path = doc
+        .metadata
+        .custom
+        .get("repository_path")
+        .and_then(|path| path.as_str())
+        .context("document has no repository_path; is it a code graph?")?;
+    let repo_root = Path::new(repository_path)
+        .canonicalize()
+        .with_context(|| format!("failed to resolve repository path {}", repository_path))?;
+    let repo_name = repo_root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "repository".to_string());
+    let normalized_repo_path = normalize_path(&repo_root);
+
+    let mut diagnostics = Vec::new();
+    let state_status = load_compatible_state(
+        &input.state_file,
+        &normalized_repo_path,
+        None,
+        &mut diagnostics,
+    )?;
+    let Some(state) = state_status.state else {
+        let result = build_code_graph_incremental(&CodeGraphIncrementalBuildInput {
+            build: CodeGraphBuildInput {
+                repository_path: repo_root,
+                commit_hash: input.commit_hash.clone(),
+                config: CodeGraphExtractorConfig::default(),
+            },
+            state_file: input.state_file.clone(),
+        })?;
+        *doc = result.document.clone();
+        return Ok(result);
+    };
+    let config = state.config.clone();
+    let matcher = GitignoreMatcher::from_repository(&repo_root)?;
+
+    let relative = |path: &Path| {
+        let path = path
+            .strip_prefix(&repo_root)
+            .or_else(|_| path.strip_prefix(repository_path))
+            .unwrap_or(path);
+        normalize_path(path)
+    };
+    let mut deleted_paths: BTreeSet<String> = input
+        .deleted
+        .iter()
+        .map(|path| relative(path))
+        .filter(|path| state.files.contains_key(path))
+        .collect();
+    let mut changed_files = Vec::new();
+    for path in input.changed.iter().map(|path| relative(path)) {
+        match repository_file(&repo_root, &path, &config, &matcher) {
+            Some(repo_file) => changed_files.push(repo_file),
+            // Gone or now excluded: drop it like a deletion
+            None if state.files.contains_key(&path) => {
+                deleted_paths.insert(path);
+            }
+            None => {}
+        }
+    }
+
+    let mut analyzed: BTreeMap<String, AnalyzedRepoFile> = BTreeMap::new();
+    let mut added_files = 0usize;
+    let mut surface_change_roots = deleted_paths.clone();
+    for repo_file in &changed_files {
+        let file = analyze_loaded_repo_file(load_repo_file(repo_file, &config)?);
+        let previous = state.files.get(&repo_file.relative_path);
+        if previous.is_none() {
+            added_files += 1;
+        }
+        let surface = compute_file_surface_signature(file.analysis.as_ref());
+        if previous.map(|entry| entry.surface_signature.as_str()) != Some(surface.as_str()) {
+            surface_change_roots.insert(repo_file.relative_path.clone());
+        }
+        analyzed.insert(repo_file.relative_path.clone(), file);
+    }
+    let direct_invalidated_files = analyzed.len() + deleted_paths.len();
+
+    // Dependents of a changed surface are re-analyzed, as in an incremental build
+    let invalidated = expand_invalidations(&surface_change_roots, &state);
+    let mut rebuilt_files = analyzed.len();
+    for path in &invalidated {
+        if analyzed.contains_key(path) || deleted_paths.contains(path) {
+            continue;
+        }
+        if let Some(repo_file) = repository_file(&repo_root, path, &config, &matcher) {
+            let file = analyze_loaded_repo_file(load_repo_file(&repo_file, &config)?);
+            analyzed.insert(path.clone(), file);
+            rebuilt_files += 1;
+        }
+    }
+
+    let mut reused_files = 0usize;
+    for (path, entry) in &state.files {
+        if !analyzed.contains_key(path) && !deleted_paths.contains(path) {
+            analyzed.insert(path.clone(), entry.to_analyzed_repo_file());
+            reused_files += 1;
+        }
+    }
+    let analyzed_files: Vec<AnalyzedRepoFile> = analyzed.into_values().collect();
+
+    let assembled = assemble_code_graph_from_analyzed_files(
+        &repo_root,
+        &repo_name,
+        &input.commit_hash,
+        &config,
+        &analyzed_files,
+        diagnostics,
+    )?;
+    write_state(
+        &input.state_file,
+        &normalized_repo_path,
+        &config,
+        &analyzed_files,
+        &assembled.dependencies_by_file,
+    )?;
+
+    let mut result = assembled.result;
+    result.incremental = Some(CodeGraphIncrementalStats {
+        requested: true,
+        scanned_files: changed_files.len(),
+        state_entries: state.files.len(),
+        direct_invalidated_files,
+        surface_changed_files: surface_change_roots.len(),
+        reused_files,
+        rebuilt_files,
+        added_files,
+        changed_files: changed_files.len() - added_files,
+        deleted_files: deleted_paths.len(),
+        invalidated_files: invalidated.len(),
+        full_rebuild_reason: None,
+    });
+    *doc = result.document.clone();
     Ok(result)
 }
 
@@ -284,10 +441,12 @@ struct StateLoadStatus {
     full_rebuild_reason: Option<String>,
 }
 
+/// Load the state if it was written for this repository and, when given,
+/// this config
 fn load_compatible_state(
     state_file: &Path,
     normalized_repo_path: &str,
-    normalized_config: &CodeGraphExtractorConfig,
+    normalized_config: Option<&CodeGraphExtractorConfig>,
     diagnostics: &mut Vec<CodeGraphDiagnostic>,
 ) -> Result<StateLoadStatus> {
     let contents = match fs::read_to_string(state_file) {
@@ -342,7 +501,7 @@ fn load_compatible_state(
             full_rebuild_reason: Some("repository_changed".to_string()),
         });
     }
-    if state.config != *normalized_config {
+    if normalized_config.is_some_and(|config| state.config != *config) {
         return Ok(StateLoadStatus {
             state: None,
             full_rebuild_reason: Some("config_changed".to_string()),
@@ -430,6 +589,9 @@ fn normalize_incremental_config(config: &CodeGraphExtractorConfig) -> CodeGraphE
     normalized.include_extensions.dedup();
     normalized.exclude_dirs.sort();
     normalized.exclude_dirs.dedup();
+    // Neither changes the graph, so they must not invalidate state
+    normalized.parallelism = None;
+    normalized.lazy_loading = false;
     normalized
 }
 
@@ -39,6 +39,10 @@ pub(super) fn analyze_python_node(
                 target_expr,
                 target_name,
             ));
+        } else if let Some((target_expr, target_name)) = python_value_reference(node, source) {
+            analysis
+                .usages
+                .push(ExtractedUsage::new(source_identity, target_expr, target_name).reference());
         }
     }
 
@@ -209,6 +213,50 @@ pub(super) fn python_call_target(node: Node<'_>, source: &str) -> Option<(String
     }
 }
 
+/// A name or `module.name` used as a value (passed, returned, assigned) rather than called
+pub(super) fn python_value_reference(node: Node<'_>, source: &str) -> Option<(String, String)> {
+    let parent = node.parent()?;
+    let value_position = match parent.kind() {
+        "argument_list"
+        | "list"
+        | "tuple"
+        | "set"
+        | "return_statement"
+        | "binary_operator"
+        | "comparison_operator"
+        | "boolean_operator"
+        | "not_operator"
+        | "parenthesized_expression" => true,
+        "assignment" => parent.child_by_field_name("right") == Some(node),
+        "keyword_argument" | "pair" => parent.child_by_field_name("value") == Some(node),
+        _ => false,
+    };
+    if !value_position {
+        return None;
+    }
+
+    match node.kind() {
+        "identifier" => {
+            let name = node_text(source, node).trim().to_string();
+            (!name.is_empty()).then(|| (name.clone(), name))
+        }
+        "attribute" => {
+            let object = node.child_by_field_name("object")?;
+            let attribute = node.child_by_field_name("attribute")?;
+            if object.kind() != "identifier" {
+                return None;
+            }
+            let object_name = node_text(source, object).trim();
+            let attr_name = node_text(source, attribute).trim().to_string();
+            if object_name.is_empty() || attr_name.is_empty() {
+                return None;
+            }
+            Some((format!("{object_name}.{attr_name}"), attr_name))
+        }
+        _ => None,
+    }
+}
+
 pub(super) fn python_imports_from_import_statement(
     node: Node<'_>,
     source: &str,
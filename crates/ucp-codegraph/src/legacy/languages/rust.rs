@@ -31,6 +31,10 @@ pub(super) fn analyze_rust_node(
                 target_expr,
                 target_name,
             ));
+        } else if let Some((target_expr, target_name)) = rust_value_reference(node, source) {
+            analysis
+                .usages
+                .push(ExtractedUsage::new(source_identity, target_expr, target_name).reference());
         }
     }
 
@@ -264,6 +268,35 @@ pub(super) fn rust_call_target(node: Node<'_>, source: &str) -> Option<(String,
     rust_callable_reference(function_node, source)
 }
 
+/// A path used as a value (passed, returned, assigned) rather than called
+pub(super) fn rust_value_reference(node: Node<'_>, source: &str) -> Option<(String, String)> {
+    if !matches!(node.kind(), "identifier" | "scoped_identifier") {
+        return None;
+    }
+    let parent = node.parent()?;
+    let value_position = match parent.kind() {
+        "arguments"
+        | "array_expression"
+        | "tuple_expression"
+        | "return_expression"
+        | "binary_expression"
+        | "reference_expression"
+        | "unary_expression"
+        | "parenthesized_expression" => true,
+        "let_declaration" | "field_initializer" => {
+            parent.child_by_field_name("value") == Some(node)
+        }
+        "assignment_expression" | "compound_assignment_expr" => {
+            parent.child_by_field_name("right") == Some(node)
+        }
+        _ => false,
+    };
+    if !value_position {
+        return None;
+    }
+    rust_callable_reference(node, source)
+}
+
 pub(super) fn rust_aliases_from_let_declaration(
     node: Node<'_>,
     source: &str,
@@ -38,6 +38,10 @@ pub(super) fn analyze_ts_node(
                 target_expr,
                 target_name,
             ));
+        } else if let Some((target_expr, target_name)) = ts_value_reference(node, source) {
+            analysis
+                .usages
+                .push(ExtractedUsage::new(source_identity, target_expr, target_name).reference());
         }
     }
 
@@ -92,10 +96,8 @@ pub(super) fn analyze_ts_node(
             }
             return;
         }
-        "expression_statement" => {
-            if scope.is_empty() && parent_identity.is_none() {
-                collect_ts_commonjs_exports(node, source, analysis);
-            }
+        "expression_statement" if scope.is_empty() && parent_identity.is_none() => {
+            collect_ts_commonjs_exports(node, source, analysis);
         }
         _ => {}
     }
@@ -350,6 +352,48 @@ pub(super) fn ts_call_target(node: Node<'_>, source: &str) -> Option<(String, St
     }
 }
 
+/// A name or `module.name` used as a value (passed, returned, assigned) rather than called
+pub(super) fn ts_value_reference(node: Node<'_>, source: &str) -> Option<(String, String)> {
+    let parent = node.parent()?;
+    let value_position = match parent.kind() {
+        "arguments"
+        | "array"
+        | "return_statement"
+        | "binary_expression"
+        | "unary_expression"
+        | "parenthesized_expression"
+        | "spread_element"
+        | "template_substitution" => true,
+        "variable_declarator" | "pair" => parent.child_by_field_name("value") == Some(node),
+        "assignment_expression" => parent.child_by_field_name("right") == Some(node),
+        _ => false,
+    };
+    if !value_position {
+        return None;
+    }
+
+    match node.kind() {
+        "identifier" => {
+            let name = node_text(source, node).trim().to_string();
+            (!name.is_empty()).then(|| (name.clone(), name))
+        }
+        "member_expression" => {
+            let object = node.child_by_field_name("object")?;
+            let property = node.child_by_field_name("property")?;
+            if object.kind() != "identifier" {
+                return None;
+            }
+            let object_name = node_text(source, object).trim();
+            let property_name = node_text(source, property).trim().to_string();
+            if object_name.is_empty() || property_name.is_empty() {
+                return None;
+            }
+            Some((format!("{object_name}.{property_name}"), property_name))
+        }
+        _ => None,
+    }
+}
+
 pub(super) fn ts_require_imports_from_variable_statement(
     node: Node<'_>,
     source: &str,
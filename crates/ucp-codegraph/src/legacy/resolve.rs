@@ -291,6 +291,50 @@ pub(super) fn resolve_usage_target_ids(
     unique_ids
 }
 
+/// Whether a call goes through a repository module (`util.helper()`,
+/// `crate::util::helper()`), so failing to resolve it is worth reporting;
+/// calls into builtins and dependencies are not
+#[allow(clippy::too_many_arguments)]
+pub(super) fn is_repository_call(
+    source_file: &str,
+    language: CodeLanguage,
+    usage: &ExtractedUsage,
+    imported_module_targets_by_file: &BTreeMap<String, BTreeMap<String, Vec<String>>>,
+    alias_names_by_scope: &BTreeMap<(String, String), BTreeSet<String>>,
+    alias_records_by_scope: &BTreeMap<(String, String), BTreeMap<String, Vec<ExtractedAlias>>>,
+) -> bool {
+    if usage.kind != UsageKind::Call {
+        return false;
+    }
+
+    if let Some((module_alias, _)) = member_usage_parts(&usage.target_expr) {
+        if !resolve_module_alias_target_files(
+            source_file,
+            Some(&usage.source_identity),
+            &module_alias,
+            imported_module_targets_by_file,
+            alias_names_by_scope,
+            alias_records_by_scope,
+        )
+        .is_empty()
+        {
+            return true;
+        }
+    }
+
+    if language == CodeLanguage::Rust {
+        if let Some((module_path, _)) = usage.target_expr.rsplit_once("::") {
+            let first = module_path.split("::").next().unwrap_or(module_path);
+            return matches!(first, "crate" | "self" | "super")
+                || imported_module_targets_by_file
+                    .get(source_file)
+                    .is_some_and(|aliases| aliases.contains_key(first));
+        }
+    }
+
+    false
+}
+
 pub(super) fn member_usage_parts(text: &str) -> Option<(String, String)> {
     let (left, right) = text.split_once('.')?;
     let left = left.trim();
@@ -133,38 +133,76 @@ pub fn validate_code_graph_profile(doc: &Document) -> CodeGraphValidationResult
 
             let target_class = node_class(target_block).unwrap_or_default();
 
-            match &edge.edge_type {
-                EdgeType::References => {
-                    if source_class != "file" || target_class != "file" {
-                        diagnostics.push(
-                            CodeGraphDiagnostic::error(
-                                "CG1015",
-                                "references edges must connect file -> file",
-                            )
-                            .with_logical_key(
-                                logical_by_id
-                                    .get(source_id)
-                                    .cloned()
-                                    .unwrap_or_else(|| source_id.to_string()),
+            if let (Some(from), Some(to)) = (edge.valid_from, edge.valid_to) {
+                if from > to {
+                    diagnostics.push(
+                        CodeGraphDiagnostic::error(
+                            "CG1020",
+                            format!(
+                                "{} edge valid_from {} is after valid_to {}",
+                                edge.edge_type.as_str(),
+                                from.to_rfc3339(),
+                                to.to_rfc3339()
                             ),
-                        );
-                    }
+                        )
+                        .with_logical_key(
+                            logical_by_id
+                                .get(source_id)
+                                .cloned()
+                                .unwrap_or_else(|| source_id.to_string()),
+                        ),
+                    );
                 }
-                EdgeType::Custom(name) if name == "exports" => {
-                    if source_class != "file" || target_class != "symbol" {
-                        diagnostics.push(
-                            CodeGraphDiagnostic::error(
-                                "CG1016",
-                                "exports edges must connect file -> symbol",
-                            )
-                            .with_logical_key(
-                                logical_by_id
-                                    .get(source_id)
-                                    .cloned()
-                                    .unwrap_or_else(|| source_id.to_string()),
-                            ),
-                        );
-                    }
+            }
+
+            match &edge.edge_type {
+                EdgeType::References if (source_class != "file" || target_class != "file") => {
+                    diagnostics.push(
+                        CodeGraphDiagnostic::error(
+                            "CG1015",
+                            "references edges must connect file -> file",
+                        )
+                        .with_logical_key(
+                            logical_by_id
+                                .get(source_id)
+                                .cloned()
+                                .unwrap_or_else(|| source_id.to_string()),
+                        ),
+                    );
+                }
+                EdgeType::Custom(name)
+                    if name == "exports"
+                        && (source_class != "file" || target_class != "symbol") =>
+                {
+                    diagnostics.push(
+                        CodeGraphDiagnostic::error(
+                            "CG1016",
+                            "exports edges must connect file -> symbol",
+                        )
+                        .with_logical_key(
+                            logical_by_id
+                                .get(source_id)
+                                .cloned()
+                                .unwrap_or_else(|| source_id.to_string()),
+                        ),
+                    );
+                }
+                EdgeType::Custom(name)
+                    if (name == "calls" || name == "uses_symbol")
+                        && (source_class != "symbol" || target_class != "symbol") =>
+                {
+                    diagnostics.push(
+                        CodeGraphDiagnostic::error(
+                            "CG1022",
+                            format!("{} edges must connect symbol -> symbol", name),
+                        )
+                        .with_logical_key(
+                            logical_by_id
+                                .get(source_id)
+                                .cloned()
+                                .unwrap_or_else(|| source_id.to_string()),
+                        ),
+                    );
                 }
                 _ => {}
             }
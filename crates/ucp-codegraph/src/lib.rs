@@ -1,8 +1,13 @@
 mod context;
+mod dead_code;
+mod diff;
+mod impact;
 mod legacy;
 mod model;
 mod programmatic;
 mod projection;
+mod search;
+mod store;
 
 pub use context::{
     approximate_prompt_tokens, export_codegraph_context, export_codegraph_context_with_config,
@@ -18,15 +23,23 @@ pub use context::{
     CodeGraphSessionMutationKind, CodeGraphSessionPersistenceMetadata, CodeGraphTraversalConfig,
     HydratedSourceExcerpt,
 };
+pub use dead_code::find_dead_code;
+pub use diff::{
+    diff_code_graphs, CodeGraphChangeKind, CodeGraphDiff, CodeGraphDiffEdge, CodeGraphDiffNode,
+    CodeGraphDiffSummary, CodeGraphFileRename, CodeGraphNodeChange,
+};
+pub use impact::{dependencies_of, dependents_of, ImpactEntry};
 pub use legacy::{
     build_code_graph, build_code_graph_incremental, canonical_codegraph_json,
-    canonical_fingerprint, validate_code_graph_profile,
+    canonical_fingerprint, check_code_syntax, code_graph_stats, update_code_graph,
+    validate_code_graph_profile,
 };
 pub use model::{
     CodeGraphBuildInput, CodeGraphBuildResult, CodeGraphBuildStatus, CodeGraphDiagnostic,
     CodeGraphExtractorConfig, CodeGraphIncrementalBuildInput, CodeGraphIncrementalStats,
-    CodeGraphSeverity, CodeGraphStats, CodeGraphValidationResult, CODEGRAPH_EXTRACTOR_VERSION,
-    CODEGRAPH_PROFILE_MARKER, CODEGRAPH_PROFILE_VERSION,
+    CodeGraphSeverity, CodeGraphStats, CodeGraphUpdateInput, CodeGraphValidationResult,
+    CodeSyntaxError, SymbolDepth, CODEGRAPH_EXTRACTOR_VERSION, CODEGRAPH_PROFILE_MARKER,
+    CODEGRAPH_PROFILE_VERSION,
 };
 pub use programmatic::{
     CodeGraphExpandMode, CodeGraphExportOmissionExplanation, CodeGraphFindQuery,
@@ -39,4 +52,6 @@ pub use projection::{
     codegraph_prompt_projection, codegraph_prompt_projection_with_config,
     CodeGraphPromptProjectionConfig,
 };
+pub use search::{find_symbols, SymbolHit, SymbolQuery};
+pub use store::CodeGraphStore;
 pub use ucm_core::PortableDocument;
@@ -1,12 +1,12 @@
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
-use ucm_core::Document;
+use ucm_core::{Document, LazyDocument};
 
 pub const CODEGRAPH_PROFILE: &str = "codegraph";
 pub const CODEGRAPH_PROFILE_VERSION: &str = "v1";
 pub const CODEGRAPH_PROFILE_MARKER: &str = "codegraph.v1";
-pub const CODEGRAPH_EXTRACTOR_VERSION: &str = "ucp-codegraph-extractor.v1";
+pub const CODEGRAPH_EXTRACTOR_VERSION: &str = "ucp-codegraph-extractor.v2";
 
 pub(crate) const META_NODE_CLASS: &str = "node_class";
 pub(crate) const META_LOGICAL_KEY: &str = "logical_key";
@@ -15,6 +15,7 @@ pub(crate) const META_LANGUAGE: &str = "language";
 pub(crate) const META_SYMBOL_KIND: &str = "symbol_kind";
 pub(crate) const META_SYMBOL_NAME: &str = "name";
 pub(crate) const META_EXPORTED: &str = "exported";
+pub(crate) const META_DOC: &str = "doc";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -24,6 +25,16 @@ pub enum CodeGraphSeverity {
     Info,
 }
 
+/// A syntax error found by [`crate::check_code_syntax`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeSyntaxError {
+    /// 1-based line within the checked source
+    pub line: usize,
+    /// 1-based column within the line
+    pub column: usize,
+    pub message: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CodeGraphDiagnostic {
     pub severity: CodeGraphSeverity,
@@ -90,9 +101,15 @@ pub struct CodeGraphStats {
     pub directory_nodes: usize,
     pub file_nodes: usize,
     pub symbol_nodes: usize,
+    /// Symbols declared inside another symbol, such as methods; included in
+    /// `symbol_nodes`
+    #[serde(default)]
+    pub nested_symbol_nodes: usize,
     pub total_edges: usize,
     pub reference_edges: usize,
     pub export_edges: usize,
+    #[serde(default)]
+    pub call_edges: usize,
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub languages: BTreeMap<String, usize>,
 }
@@ -114,6 +131,10 @@ pub struct CodeGraphBuildResult {
     pub canonical_fingerprint: String,
     pub status: CodeGraphBuildStatus,
     pub incremental: Option<CodeGraphIncrementalStats>,
+    /// The same graph backed by a memory-mapped file, set when
+    /// [`CodeGraphExtractorConfig::lazy_loading`] is on. Callers that keep the
+    /// graph around can take this and drop the rest of the result.
+    pub lazy_document: Option<LazyDocument>,
 }
 
 impl CodeGraphBuildResult {
@@ -138,6 +159,21 @@ pub struct CodeGraphIncrementalBuildInput {
     pub state_file: PathBuf,
 }
 
+/// Files changed since the build that wrote `state_file`, for
+/// [`crate::update_code_graph`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeGraphUpdateInput {
+    /// Added or modified files, absolute or relative to the repository root
+    #[serde(default)]
+    pub changed: Vec<PathBuf>,
+    /// Removed files, absolute or relative to the repository root
+    #[serde(default)]
+    pub deleted: Vec<PathBuf>,
+    pub commit_hash: String,
+    /// State written by `build_code_graph_incremental` or a previous update
+    pub state_file: PathBuf,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct CodeGraphIncrementalStats {
     #[serde(default)]
@@ -180,6 +216,30 @@ pub struct CodeGraphExtractorConfig {
     pub max_file_bytes: usize,
     #[serde(default = "default_emit_export_edges")]
     pub emit_export_edges: bool,
+    /// Maximum characters kept from a doc comment; `0` drops docs entirely
+    #[serde(default = "default_max_doc_chars")]
+    pub max_doc_chars: usize,
+    /// Worker threads for file analysis; `None` uses every available CPU,
+    /// `0` or `1` analyzes files sequentially
+    #[serde(default)]
+    pub parallelism: Option<usize>,
+    /// Also return the graph as a [`LazyDocument`] whose block content is
+    /// read from a temporary file on demand
+    #[serde(default)]
+    pub lazy_loading: bool,
+    #[serde(default)]
+    pub symbol_depth: SymbolDepth,
+}
+
+/// Which symbols become nodes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolDepth {
+    /// Only symbols declared directly in a file
+    TopLevel,
+    /// Also members nested in other symbols, keyed `symbol:path::Class::method`
+    #[default]
+    Members,
 }
 
 impl Default for CodeGraphExtractorConfig {
@@ -191,6 +251,10 @@ impl Default for CodeGraphExtractorConfig {
             include_hidden: false,
             max_file_bytes: default_max_file_bytes(),
             emit_export_edges: default_emit_export_edges(),
+            max_doc_chars: default_max_doc_chars(),
+            parallelism: None,
+            lazy_loading: false,
+            symbol_depth: SymbolDepth::default(),
         }
     }
 }
@@ -221,6 +285,10 @@ fn default_emit_export_edges() -> bool {
     true
 }
 
+fn default_max_doc_chars() -> usize {
+    200
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub(crate) enum CodeLanguage {
@@ -231,6 +299,17 @@ pub(crate) enum CodeLanguage {
 }
 
 impl CodeLanguage {
+    /// Language for a canonical name as produced by `ucm_core::canonical_language`
+    pub(crate) fn from_canonical(name: &str) -> Option<Self> {
+        match name {
+            "rust" => Some(Self::Rust),
+            "python" => Some(Self::Python),
+            "typescript" => Some(Self::TypeScript),
+            "javascript" => Some(Self::JavaScript),
+            _ => None,
+        }
+    }
+
     pub(crate) fn as_str(self) -> &'static str {
         match self {
             Self::Rust => "rust",
@@ -333,6 +412,19 @@ pub(crate) struct ExtractedUsage {
     pub(crate) source_identity: String,
     pub(crate) target_expr: String,
     pub(crate) target_name: String,
+    #[serde(default)]
+    pub(crate) kind: UsageKind,
+}
+
+/// How a symbol body refers to another symbol
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum UsageKind {
+    /// The symbol is called (`calls` and `uses_symbol` edges)
+    #[default]
+    Call,
+    /// The symbol is used as a value without being called (`uses_symbol` edge)
+    Reference,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -476,8 +568,14 @@ impl ExtractedUsage {
             source_identity: source_identity.into(),
             target_expr: target_expr.into(),
             target_name: target_name.into(),
+            kind: UsageKind::Call,
         }
     }
+
+    pub(crate) fn reference(mut self) -> Self {
+        self.kind = UsageKind::Reference;
+        self
+    }
 }
 
 impl ExtractedAlias {
@@ -0,0 +1,301 @@
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ucm_core::{Block, BlockId, Document};
+
+use crate::model::{
+    META_CODEREF, META_EXPORTED, META_LANGUAGE, META_LOGICAL_KEY, META_NODE_CLASS,
+    META_SYMBOL_KIND, META_SYMBOL_NAME,
+};
+
+/// Minimum share of the query's trigrams a name must contain to match fuzzily
+const FUZZY_THRESHOLD: f64 = 0.5;
+
+/// Filters for [`find_symbols`]; unset fields match every symbol
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolQuery {
+    /// Case-insensitive substring of the symbol name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_contains: Option<String>,
+    /// Symbol kind such as `function` or `struct`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub exported_only: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_prefix: Option<String>,
+    /// Also accept names sharing most of the query's trigrams, so typos and
+    /// partial words still match
+    #[serde(default)]
+    pub fuzzy: bool,
+}
+
+/// A symbol matched by [`find_symbols`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolHit {
+    pub block_id: BlockId,
+    pub name: String,
+    pub kind: String,
+    pub language: String,
+    pub logical_key: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    pub exported: bool,
+    /// File block the symbol is declared in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_block_id: Option<BlockId>,
+    /// 1.0 for an exact name match, down to 0 for a weak fuzzy match
+    pub score: f64,
+}
+
+/// Symbol nodes matching `query`, best match first.
+///
+/// Exact names rank above prefixes, prefixes above substrings and substrings
+/// above fuzzy matches; ties are broken by logical key so output is stable.
+pub fn find_symbols(doc: &Document, query: &SymbolQuery) -> Vec<SymbolHit> {
+    let needle = query
+        .name_contains
+        .as_deref()
+        .map(str::trim)
+        .filter(|needle| !needle.is_empty())
+        .map(str::to_lowercase);
+    let path_prefix = query
+        .path_prefix
+        .as_deref()
+        .map(|prefix| prefix.trim_start_matches("./"));
+
+    let mut hits: Vec<SymbolHit> = doc
+        .blocks
+        .values()
+        .filter(|block| meta_str(block, META_NODE_CLASS) == Some("symbol"))
+        .filter_map(|block| {
+            let name = meta_str(block, META_SYMBOL_NAME)?;
+            let kind = meta_str(block, META_SYMBOL_KIND).unwrap_or_default();
+            let language = meta_str(block, META_LANGUAGE).unwrap_or_default();
+            let exported = block
+                .metadata
+                .custom
+                .get(META_EXPORTED)
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            let coderef = block.metadata.custom.get(META_CODEREF);
+            let path = coderef
+                .and_then(|value| value.get("path"))
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+
+            if query.exported_only && !exported
+                || query
+                    .kind
+                    .as_deref()
+                    .is_some_and(|wanted| !wanted.eq_ignore_ascii_case(kind))
+                || query
+                    .language
+                    .as_deref()
+                    .is_some_and(|wanted| !wanted.eq_ignore_ascii_case(language))
+                || path_prefix.is_some_and(|prefix| !path.starts_with(prefix))
+            {
+                return None;
+            }
+            let score = match &needle {
+                Some(needle) => name_score(&name.to_lowercase(), needle, query.fuzzy)?,
+                None => 1.0,
+            };
+
+            let line = |field: &str| {
+                coderef
+                    .and_then(|value| value.get(field))
+                    .and_then(Value::as_u64)
+                    .map(|line| line as usize)
+            };
+            Some(SymbolHit {
+                block_id: block.id,
+                name: name.to_string(),
+                kind: kind.to_string(),
+                language: language.to_string(),
+                logical_key: meta_str(block, META_LOGICAL_KEY)
+                    .unwrap_or_default()
+                    .to_string(),
+                path: path.to_string(),
+                start_line: line("start_line"),
+                end_line: line("end_line"),
+                exported,
+                file_block_id: owning_file(doc, &block.id),
+                score,
+            })
+        })
+        .collect();
+
+    hits.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.logical_key.cmp(&b.logical_key))
+    });
+    hits
+}
+
+/// Score of `name` against `needle`, both lowercase; `None` if it does not
+/// match
+fn name_score(name: &str, needle: &str, fuzzy: bool) -> Option<f64> {
+    if name == needle {
+        Some(1.0)
+    } else if name.starts_with(needle) {
+        Some(0.9)
+    } else if name.contains(needle) {
+        Some(0.8)
+    } else if fuzzy {
+        let wanted = trigrams(needle);
+        if wanted.is_empty() {
+            return None;
+        }
+        let present = trigrams(name);
+        let shared = wanted.intersection(&present).count() as f64 / wanted.len() as f64;
+        (shared >= FUZZY_THRESHOLD).then_some(0.7 * shared)
+    } else {
+        None
+    }
+}
+
+/// Distinct three-character windows; spaces count as underscores so a
+/// phrase like `validate profile` lines up with `validate_profile`
+fn trigrams(text: &str) -> BTreeSet<String> {
+    let chars: Vec<char> = text
+        .chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .collect();
+    chars
+        .windows(3)
+        .map(|window| window.iter().collect())
+        .collect()
+}
+
+pub(crate) fn owning_file(doc: &Document, id: &BlockId) -> Option<BlockId> {
+    let mut current = *id;
+    while let Some(parent) = doc.parent(&current) {
+        let block = doc.get_block(parent)?;
+        if meta_str(block, META_NODE_CLASS) == Some("file") {
+            return Some(block.id);
+        }
+        current = *parent;
+    }
+    None
+}
+
+fn meta_str<'a>(block: &'a Block, key: &str) -> Option<&'a str> {
+    block.metadata.custom.get(key).and_then(Value::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{build_code_graph, CodeGraphBuildInput, CodeGraphExtractorConfig};
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn sample() -> Document {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(
+            dir.path().join("src/lib.rs"),
+            "pub fn validate_code_graph_profile() {}\nfn validate() {}\npub struct Profile;\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("tools.py"),
+            "def validate_profile():\n    pass\n",
+        )
+        .unwrap();
+        build_code_graph(&CodeGraphBuildInput {
+            repository_path: dir.path().to_path_buf(),
+            commit_hash: "search".to_string(),
+            config: CodeGraphExtractorConfig::default(),
+        })
+        .unwrap()
+        .document
+    }
+
+    fn names(hits: &[SymbolHit]) -> Vec<&str> {
+        hits.iter().map(|hit| hit.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_substring_matches_rank_exact_then_prefix() {
+        let doc = sample();
+        let hits = find_symbols(
+            &doc,
+            &SymbolQuery {
+                name_contains: Some("Validate".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            names(&hits),
+            [
+                "validate",
+                "validate_code_graph_profile",
+                "validate_profile"
+            ]
+        );
+
+        let hit = &hits[1];
+        assert_eq!(hit.kind, "function");
+        assert_eq!(hit.language, "rust");
+        assert_eq!(hit.path, "src/lib.rs");
+        assert_eq!(hit.start_line, Some(1));
+        assert!(hit.exported);
+        let file = doc.get_block(&hit.file_block_id.unwrap()).unwrap();
+        assert_eq!(meta_str(file, META_LOGICAL_KEY), Some("file:src/lib.rs"));
+    }
+
+    #[test]
+    fn test_filters_narrow_results() {
+        let doc = sample();
+        let query = SymbolQuery {
+            name_contains: Some("validate".to_string()),
+            exported_only: true,
+            path_prefix: Some("./src".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&find_symbols(&doc, &query)),
+            ["validate_code_graph_profile"]
+        );
+
+        let query = SymbolQuery {
+            kind: Some("struct".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(names(&find_symbols(&doc, &query)), ["Profile"]);
+        let query = SymbolQuery {
+            language: Some("python".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(names(&find_symbols(&doc, &query)), ["validate_profile"]);
+    }
+
+    #[test]
+    fn test_fuzzy_matches_typos_and_phrases() {
+        let doc = sample();
+        let mut query = SymbolQuery {
+            name_contains: Some("validate graph profile".to_string()),
+            ..Default::default()
+        };
+        assert!(find_symbols(&doc, &query).is_empty());
+
+        query.fuzzy = true;
+        let hits = find_symbols(&doc, &query);
+        assert_eq!(hits[0].name, "validate_code_graph_profile");
+        assert!(hits.iter().all(|hit| hit.score < 0.8));
+
+        query.name_contains = Some("valdiate_profile".to_string());
+        assert_eq!(find_symbols(&doc, &query)[0].name, "validate_profile");
+    }
+}
@@ -0,0 +1,309 @@
+//! Binary storage for codegraph documents.
+//!
+//! A store file holds one MessagePack record per block with its content,
+//! followed by a header with the tree, document metadata and every block's
+//! metadata and edges. [`CodeGraphStore::open`] memory-maps the file and reads
+//! only the header, which is all [`dependents_of`](crate::dependents_of) and
+//! [`find_symbols`](crate::find_symbols) look at; content is decoded per block
+//! by [`CodeGraphStore::get_block`].
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+use ucm_core::{
+    Block, BlockId, BlockMetadata, Content, Document, DocumentId, DocumentMetadata,
+    DocumentVersion, Edge, Version,
+};
+
+const MAGIC: &[u8; 8] = b"UCPCGS01";
+const TRAILER_LEN: usize = 8;
+
+/// A codegraph opened from a store file
+#[derive(Debug)]
+pub struct CodeGraphStore {
+    skeleton: Document,
+    records: HashMap<BlockId, (usize, usize)>,
+    mmap: Mmap,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    id: DocumentId,
+    root: BlockId,
+    structure: Vec<(BlockId, Vec<BlockId>)>,
+    metadata: DocumentMetadata,
+    version: DocumentVersion,
+    blocks: Vec<StoredBlock>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredBlock {
+    id: BlockId,
+    metadata: BlockMetadata,
+    edges: Vec<Edge>,
+    offset: u64,
+    length: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ContentRecord {
+    content: Content,
+    version: Version,
+}
+
+impl CodeGraphStore {
+    /// Write `doc` in the store format
+    pub fn write(path: impl AsRef<Path>, doc: &Document) -> Result<()> {
+        let path = path.as_ref();
+        let file = File::create(path).with_context(|| format!("failed to create {:?}", path))?;
+        let mut out = BufWriter::new(file);
+        out.write_all(MAGIC)?;
+        let mut offset = MAGIC.len() as u64;
+
+        let mut blocks: Vec<&Block> = doc.blocks.values().collect();
+        blocks.sort_by_key(|block| block.id.to_string());
+        let mut stored = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let record = rmp_serde::to_vec_named(&ContentRecord {
+                content: block.content.clone(),
+                version: block.version,
+            })?;
+            out.write_all(&record)?;
+            stored.push(StoredBlock {
+                id: block.id,
+                metadata: block.metadata.clone(),
+                edges: block.edges.clone(),
+                offset,
+                length: record.len(),
+            });
+            offset += record.len() as u64;
+        }
+
+        let mut structure: Vec<_> = doc
+            .structure
+            .iter()
+            .map(|(parent, children)| (*parent, children.clone()))
+            .collect();
+        structure.sort_by_key(|(parent, _)| parent.to_string());
+        let header = rmp_serde::to_vec_named(&Header {
+            id: doc.id.clone(),
+            root: doc.root,
+            structure,
+            metadata: doc.metadata.clone(),
+            version: doc.version.clone(),
+            blocks: stored,
+        })?;
+        out.write_all(&header)?;
+        out.write_all(&(header.len() as u64).to_le_bytes())?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Whether `path` starts like a store file
+    pub fn is_store(path: impl AsRef<Path>) -> bool {
+        let mut magic = [0u8; MAGIC.len()];
+        File::open(path)
+            .and_then(|mut file| file.read_exact(&mut magic))
+            .is_ok()
+            && &magic == MAGIC
+    }
+
+    /// Map a store file, decoding its header but no block content
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).with_context(|| format!("failed to open {:?}", path))?;
+        // SAFETY: the mapping is only read. Like any mmap, it assumes the file
+        // is not truncated or rewritten while mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        if mmap.len() < MAGIC.len() + TRAILER_LEN || &mmap[..MAGIC.len()] != MAGIC {
+            return Err(anyhow!("{:?} is not a codegraph store", path));
+        }
+        let trailer_start = mmap.len() - TRAILER_LEN;
+        let mut trailer = [0u8; TRAILER_LEN];
+        trailer.copy_from_slice(&mmap[trailer_start..]);
+        let header_len = usize::try_from(u64::from_le_bytes(trailer))
+            .ok()
+            .filter(|len| *len <= trailer_start - MAGIC.len())
+            .ok_or_else(|| anyhow!("codegraph store {:?} has a bad header length", path))?;
+        let header_start = trailer_start - header_len;
+        let header: Header = rmp_serde::from_slice(&mmap[header_start..trailer_start])
+            .with_context(|| format!("failed to decode codegraph store {:?}", path))?;
+
+        let mut skeleton = Document::new(header.id);
+        skeleton.blocks.clear();
+        skeleton.root = header.root;
+        skeleton.structure = header.structure.into_iter().collect();
+        skeleton.metadata = header.metadata;
+        skeleton.version = header.version;
+
+        let mut records = HashMap::with_capacity(header.blocks.len());
+        for stored in header.blocks {
+            let start = usize::try_from(stored.offset)
+                .ok()
+                .filter(|start| *start >= MAGIC.len())
+                .filter(|start| {
+                    start
+                        .checked_add(stored.length)
+                        .is_some_and(|end| end <= header_start)
+                })
+                .ok_or_else(|| anyhow!("record for {} is out of bounds", stored.id))?;
+            records.insert(stored.id, (start, stored.length));
+
+            let mut block = Block::with_id(stored.id, Content::text(""));
+            block.metadata = stored.metadata;
+            block.edges = stored.edges;
+            skeleton.blocks.insert(stored.id, block);
+        }
+        if !skeleton.blocks.contains_key(&skeleton.root) {
+            return Err(anyhow!("codegraph store {:?} has no root block", path));
+        }
+        skeleton.rebuild_indices();
+
+        Ok(Self {
+            skeleton,
+            records,
+            mmap,
+        })
+    }
+
+    /// The graph with every block's metadata, edges and position but empty
+    /// content; enough for impact and symbol queries
+    pub fn skeleton(&self) -> &Document {
+        &self.skeleton
+    }
+
+    /// Take the skeleton, dropping the mapping
+    pub fn into_skeleton(self) -> Document {
+        self.skeleton
+    }
+
+    pub fn block_count(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Block `id` with its content decoded from the file
+    pub fn get_block(&self, id: &BlockId) -> Result<Block> {
+        let mut block = self
+            .skeleton
+            .get_block(id)
+            .cloned()
+            .ok_or_else(|| anyhow!("block {} not found", id))?;
+        let record = self.content_record(id)?;
+        block.content = record.content;
+        block.version = record.version;
+        Ok(block)
+    }
+
+    /// Decode every block into a full in-memory document
+    pub fn to_document(&self) -> Result<Document> {
+        let mut doc = self.skeleton.clone();
+        for (id, block) in doc.blocks.iter_mut() {
+            let record = self.content_record(id)?;
+            block.content = record.content;
+            block.version = record.version;
+        }
+        doc.rebuild_indices();
+        Ok(doc)
+    }
+
+    fn content_record(&self, id: &BlockId) -> Result<ContentRecord> {
+        let (start, length) = self
+            .records
+            .get(id)
+            .copied()
+            .ok_or_else(|| anyhow!("block {} not found", id))?;
+        // Bounds were checked in `CodeGraphStore::open`
+        rmp_serde::from_slice(&self.mmap[start..start + length])
+            .with_context(|| format!("failed to decode block {}", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        build_code_graph, canonical_fingerprint, dependents_of, find_symbols, CodeGraphBuildInput,
+        CodeGraphExtractorConfig, SymbolQuery,
+    };
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn sample() -> Document {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(
+            dir.path().join("src/lib.rs"),
+            "/// Entry point\npub mod util;\npub fn run() { util::helper(); }\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("src/util.rs"), "pub fn helper() {}\n").unwrap();
+        build_code_graph(&CodeGraphBuildInput {
+            repository_path: dir.path().to_path_buf(),
+            commit_hash: "store".to_string(),
+            config: CodeGraphExtractorConfig::default(),
+        })
+        .unwrap()
+        .document
+    }
+
+    #[test]
+    fn test_round_trip_preserves_fingerprint() {
+        let doc = sample();
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("graph.ucgs");
+        CodeGraphStore::write(&path, &doc).unwrap();
+        assert!(CodeGraphStore::is_store(&path));
+
+        let store = CodeGraphStore::open(&path).unwrap();
+        assert_eq!(store.block_count(), doc.blocks.len());
+        let restored = store.to_document().unwrap();
+        assert_eq!(
+            canonical_fingerprint(&restored).unwrap(),
+            canonical_fingerprint(&doc).unwrap()
+        );
+        for (id, block) in &doc.blocks {
+            assert_eq!(&store.get_block(id).unwrap(), block);
+        }
+    }
+
+    #[test]
+    fn test_skeleton_answers_queries_without_content() {
+        let doc = sample();
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("graph.ucgs");
+        CodeGraphStore::write(&path, &doc).unwrap();
+        let skeleton = CodeGraphStore::open(&path).unwrap().into_skeleton();
+
+        assert_eq!(
+            dependents_of(&skeleton, "src/util.rs", usize::MAX),
+            dependents_of(&doc, "src/util.rs", usize::MAX)
+        );
+        let query = SymbolQuery {
+            name_contains: Some("help".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(find_symbols(&skeleton, &query), find_symbols(&doc, &query));
+    }
+
+    #[test]
+    fn test_rejects_json_and_truncated_files() {
+        let doc = sample();
+        let dir = tempdir().unwrap();
+        let json = dir.path().join("graph.json");
+        fs::write(&json, doc.to_portable_json().unwrap()).unwrap();
+        assert!(!CodeGraphStore::is_store(&json));
+        assert!(CodeGraphStore::open(&json).is_err());
+
+        let path = dir.path().join("graph.ucgs");
+        CodeGraphStore::write(&path, &doc).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() - 3]).unwrap();
+        assert!(CodeGraphStore::open(&path).is_err());
+    }
+}
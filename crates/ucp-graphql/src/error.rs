@@ -0,0 +1,25 @@
+//! Error types for the GraphQL layer.
+
+use thiserror::Error;
+
+/// GraphQL store error
+#[derive(Debug, Error)]
+pub enum GraphqlError {
+    #[error("Document not found: {0}")]
+    DocumentNotFound(String),
+
+    #[error("Invalid block ID: {0}")]
+    InvalidBlockId(String),
+
+    #[error("Block not found: {0}")]
+    BlockNotFound(String),
+
+    #[error("Operation failed: {0}")]
+    OperationFailed(String),
+
+    #[error(transparent)]
+    Core(#[from] ucm_core::Error),
+}
+
+/// Result type for GraphQL store operations
+pub type Result<T> = std::result::Result<T, GraphqlError>;
@@ -0,0 +1,40 @@
+//! # UCP GraphQL
+//!
+//! GraphQL schema for querying and editing UCP documents.
+//!
+//! The schema is transport-agnostic: serve it with any `async-graphql`
+//! integration (e.g. `async-graphql-axum` at `POST /api/graphql`) or call
+//! [`UcpSchema::execute`] directly. Mutations run through the
+//! [`Engine`](ucm_engine::Engine) in a transaction and leave the stored
+//! document untouched if any operation fails.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use ucm_core::{Block, Content, Document};
+//! use ucp_graphql::{build_schema, DocumentStore};
+//!
+//! let mut doc = Document::create();
+//! let root = doc.root;
+//! doc.add_block(Block::new(Content::text("Hello"), None), &root).unwrap();
+//!
+//! let store = DocumentStore::new();
+//! let id = store.insert(doc);
+//! let schema = build_schema(store);
+//!
+//! let query = format!(r#"{{ document(id: "{}") {{ blocks {{ id contentText }} }} }}"#, id);
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! let response = schema.execute(query).await;
+//! assert!(response.errors.is_empty());
+//! # });
+//! ```
+
+pub mod error;
+mod schema;
+mod store;
+
+pub use error::{GraphqlError, Result};
+pub use schema::{
+    build_schema, BlockObject, DocumentObject, EdgeObject, MutationRoot, QueryRoot, UcpSchema,
+};
+pub use store::DocumentStore;
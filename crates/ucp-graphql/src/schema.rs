@@ -0,0 +1,522 @@
+//! GraphQL object types and resolvers.
+
+use std::sync::Arc;
+
+use async_graphql::{Context, EmptySubscription, Object, Schema, ID};
+use ucm_core::{Block, BlockId, Content, Document, EdgeType};
+use ucm_engine::{EditOperator, Operation};
+
+use crate::error::{GraphqlError, Result};
+use crate::store::DocumentStore;
+
+/// The complete UCP GraphQL schema
+pub type UcpSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+/// Build a schema serving the documents in `store`
+pub fn build_schema(store: DocumentStore) -> UcpSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .data(store)
+        .finish()
+}
+
+fn parse_block_id(id: &str) -> Result<BlockId> {
+    id.parse()
+        .map_err(|_| GraphqlError::InvalidBlockId(id.to_string()))
+}
+
+/// Searchable text of a block's content, if it has any
+fn content_text(content: &Content) -> Option<String> {
+    match content {
+        Content::Text(t) => Some(t.text.clone()),
+        Content::RichText(r) => Some(r.plain_text()),
+        Content::Code(c) => Some(c.source.clone()),
+        Content::CompressedCode { .. } => content.decompress(),
+        Content::Math(m) => Some(m.expression.clone()),
+        Content::Diagram(d) => Some(d.source.clone()),
+        Content::Diff(d) => Some(d.patch.clone()),
+        Content::Json { value, .. } | Content::GeoJson(value) => Some(value.to_string()),
+        Content::Form(form) => Some(
+            std::iter::once(form.title.as_str())
+                .chain(form.fields.iter().map(|f| f.label.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        Content::Table(_)
+        | Content::Spreadsheet(_)
+        | Content::Media(_)
+        | Content::Binary { .. }
+        | Content::Composite { .. } => None,
+    }
+}
+
+/// Reachable block ids, depth-first in child order
+fn document_order(doc: &Document) -> Vec<BlockId> {
+    let mut order = Vec::new();
+    let mut stack = vec![doc.root];
+    while let Some(id) = stack.pop() {
+        order.push(id);
+        stack.extend(doc.children(&id).iter().rev().copied());
+    }
+    order
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Look up a document by id
+    async fn document(&self, ctx: &Context<'_>, id: ID) -> Option<DocumentObject> {
+        let store = ctx.data_unchecked::<DocumentStore>();
+        store.get(&id).map(DocumentObject)
+    }
+
+    /// All stored documents
+    async fn documents(&self, ctx: &Context<'_>) -> Vec<DocumentObject> {
+        let store = ctx.data_unchecked::<DocumentStore>();
+        store
+            .ids()
+            .iter()
+            .filter_map(|id| store.get(id))
+            .map(DocumentObject)
+            .collect()
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    /// Append a text block under a parent
+    #[allow(clippy::too_many_arguments)]
+    async fn append_block(
+        &self,
+        ctx: &Context<'_>,
+        document_id: ID,
+        parent_id: ID,
+        text: String,
+        role: Option<String>,
+        label: Option<String>,
+        #[graphql(default)] tags: Vec<String>,
+        index: Option<usize>,
+    ) -> async_graphql::Result<BlockObject> {
+        let store = ctx.data_unchecked::<DocumentStore>();
+        let results = store.apply(
+            &document_id,
+            vec![Operation::Append {
+                parent_id: parse_block_id(&parent_id)?,
+                content: Content::text(text),
+                label,
+                tags,
+                semantic_role: role,
+                index,
+            }],
+        )?;
+        let id = results
+            .first()
+            .and_then(|r| r.affected_blocks.first().copied())
+            .ok_or_else(|| GraphqlError::OperationFailed("no block was created".into()))?;
+        block_in(store, &document_id, id)
+    }
+
+    /// Set a value on a block (defaults to replacing its text)
+    async fn edit_block(
+        &self,
+        ctx: &Context<'_>,
+        document_id: ID,
+        block_id: ID,
+        value: String,
+        #[graphql(default_with = "\"content.text\".to_string()")] path: String,
+    ) -> async_graphql::Result<BlockObject> {
+        let store = ctx.data_unchecked::<DocumentStore>();
+        let id = parse_block_id(&block_id)?;
+        store.apply(
+            &document_id,
+            vec![Operation::Edit {
+                block_id: id,
+                path,
+                value: serde_json::Value::String(value),
+                operator: EditOperator::Set,
+            }],
+        )?;
+        block_in(store, &document_id, id)
+    }
+
+    /// Delete a block. Returns the ids of all removed blocks.
+    async fn delete_block(
+        &self,
+        ctx: &Context<'_>,
+        document_id: ID,
+        block_id: ID,
+        #[graphql(default)] cascade: bool,
+    ) -> async_graphql::Result<Vec<ID>> {
+        let store = ctx.data_unchecked::<DocumentStore>();
+        let before = store
+            .get(&document_id)
+            .ok_or_else(|| GraphqlError::DocumentNotFound(document_id.to_string()))?;
+        let id = parse_block_id(&block_id)?;
+        store.apply(
+            &document_id,
+            vec![Operation::Delete {
+                block_id: id,
+                cascade,
+                preserve_children: false,
+            }],
+        )?;
+
+        let after = store
+            .get(&document_id)
+            .ok_or_else(|| GraphqlError::DocumentNotFound(document_id.to_string()))?;
+        let mut removed: Vec<String> = before
+            .blocks
+            .keys()
+            .filter(|id| !after.blocks.contains_key(id))
+            .map(|id| id.to_string())
+            .collect();
+        removed.sort();
+        Ok(removed.into_iter().map(ID).collect())
+    }
+}
+
+fn block_in(
+    store: &DocumentStore,
+    document_id: &str,
+    id: BlockId,
+) -> async_graphql::Result<BlockObject> {
+    let doc = store
+        .get(document_id)
+        .ok_or_else(|| GraphqlError::DocumentNotFound(document_id.to_string()))?;
+    BlockObject::new(doc, id).ok_or_else(|| GraphqlError::BlockNotFound(id.to_string()).into())
+}
+
+/// A document snapshot
+pub struct DocumentObject(Arc<Document>);
+
+#[Object(name = "Document")]
+impl DocumentObject {
+    async fn id(&self) -> ID {
+        ID(self.0.id.to_string())
+    }
+
+    async fn title(&self) -> Option<&str> {
+        self.0.metadata.title.as_deref()
+    }
+
+    async fn root(&self) -> BlockObject {
+        BlockObject {
+            doc: self.0.clone(),
+            id: self.0.root,
+        }
+    }
+
+    async fn block_count(&self) -> usize {
+        self.0.block_count()
+    }
+
+    /// All reachable blocks in document order
+    async fn blocks(&self) -> Vec<BlockObject> {
+        document_order(&self.0)
+            .into_iter()
+            .filter_map(|id| BlockObject::new(self.0.clone(), id))
+            .collect()
+    }
+
+    async fn block(&self, id: ID) -> Option<BlockObject> {
+        let id = id.parse().ok()?;
+        BlockObject::new(self.0.clone(), id)
+    }
+
+    /// Blocks whose text, label, or tags contain `query` (case-insensitive)
+    async fn search_blocks(&self, query: String) -> Vec<BlockObject> {
+        let query = query.to_lowercase();
+        document_order(&self.0)
+            .into_iter()
+            .filter(|id| {
+                self.0.get_block(id).is_some_and(|block| {
+                    let matches = |s: &str| s.to_lowercase().contains(&query);
+                    content_text(&block.content).is_some_and(|t| matches(&t))
+                        || block.metadata.label.as_deref().is_some_and(matches)
+                        || block.metadata.tags.iter().any(|t| matches(t))
+                })
+            })
+            .filter_map(|id| BlockObject::new(self.0.clone(), id))
+            .collect()
+    }
+}
+
+/// A block within a document snapshot
+pub struct BlockObject {
+    doc: Arc<Document>,
+    id: BlockId,
+}
+
+impl BlockObject {
+    fn new(doc: Arc<Document>, id: BlockId) -> Option<Self> {
+        doc.get_block(&id)?;
+        Some(Self { doc, id })
+    }
+
+    fn block(&self) -> &Block {
+        self.doc
+            .get_block(&self.id)
+            .expect("BlockObject refers to an existing block")
+    }
+
+    fn related(&self, ids: impl IntoIterator<Item = BlockId>) -> Vec<BlockObject> {
+        ids.into_iter()
+            .filter_map(|id| BlockObject::new(self.doc.clone(), id))
+            .collect()
+    }
+}
+
+#[Object(name = "Block")]
+impl BlockObject {
+    async fn id(&self) -> ID {
+        ID(self.id.to_string())
+    }
+
+    async fn content_type(&self) -> &str {
+        self.block().content_type()
+    }
+
+    async fn content_text(&self) -> Option<String> {
+        content_text(&self.block().content)
+    }
+
+    async fn label(&self) -> Option<&str> {
+        self.block().metadata.label.as_deref()
+    }
+
+    async fn tags(&self) -> &[String] {
+        &self.block().metadata.tags
+    }
+
+    async fn role(&self) -> Option<String> {
+        self.block()
+            .metadata
+            .semantic_role
+            .as_ref()
+            .map(|r| r.to_string())
+    }
+
+    async fn parent(&self) -> Option<BlockObject> {
+        let parent = *self.doc.parent(&self.id)?;
+        BlockObject::new(self.doc.clone(), parent)
+    }
+
+    async fn children(&self) -> Vec<BlockObject> {
+        self.related(self.doc.children(&self.id).iter().copied())
+    }
+
+    /// Ancestors from the parent up to the root
+    async fn ancestors(&self) -> Vec<BlockObject> {
+        let mut ids = Vec::new();
+        let mut current = self.id;
+        while let Some(parent) = self.doc.parent(&current) {
+            ids.push(*parent);
+            current = *parent;
+        }
+        self.related(ids)
+    }
+
+    /// Outgoing semantic edges
+    async fn edges(&self) -> Vec<EdgeObject> {
+        self.doc
+            .edge_index
+            .outgoing_from(&self.id)
+            .iter()
+            .map(|(edge_type, target)| EdgeObject {
+                doc: self.doc.clone(),
+                edge_type: edge_type.clone(),
+                source: self.id,
+                target: *target,
+            })
+            .collect()
+    }
+
+    /// Incoming semantic edges
+    async fn incoming_edges(&self) -> Vec<EdgeObject> {
+        self.doc
+            .edge_index
+            .incoming_to(&self.id)
+            .iter()
+            .map(|(edge_type, source)| EdgeObject {
+                doc: self.doc.clone(),
+                edge_type: edge_type.clone(),
+                source: *source,
+                target: self.id,
+            })
+            .collect()
+    }
+}
+
+/// A typed edge between two blocks
+pub struct EdgeObject {
+    doc: Arc<Document>,
+    edge_type: EdgeType,
+    source: BlockId,
+    target: BlockId,
+}
+
+#[Object(name = "Edge")]
+impl EdgeObject {
+    async fn edge_type(&self) -> String {
+        self.edge_type.as_str()
+    }
+
+    async fn source(&self) -> Option<BlockObject> {
+        BlockObject::new(self.doc.clone(), self.source)
+    }
+
+    async fn target(&self) -> Option<BlockObject> {
+        BlockObject::new(self.doc.clone(), self.target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use ucm_core::DocumentMetadata;
+
+    struct Fixture {
+        schema: UcpSchema,
+        store: DocumentStore,
+        doc_id: String,
+        intro: BlockId,
+        body: BlockId,
+    }
+
+    fn fixture() -> Fixture {
+        let mut doc = Document::create().with_metadata(DocumentMetadata::new().with_title("Guide"));
+        let root = doc.root;
+        let intro = doc
+            .add_block(
+                Block::new(Content::text("Introduction"), Some("heading1")).with_label("intro"),
+                &root,
+            )
+            .unwrap();
+        let body = doc
+            .add_block(
+                Block::new(Content::text("Install the CLI"), Some("paragraph")).with_tag("setup"),
+                &intro,
+            )
+            .unwrap();
+        doc.add_edge(&body, EdgeType::References, intro);
+
+        let store = DocumentStore::new();
+        let doc_id = store.insert(doc);
+        Fixture {
+            schema: build_schema(store.clone()),
+            store,
+            doc_id,
+            intro,
+            body,
+        }
+    }
+
+    async fn run(schema: &UcpSchema, query: &str) -> Value {
+        let response = schema.execute(query).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        response.data.into_json().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_query_document_tree() {
+        let f = fixture();
+        let query = format!(
+            r#"{{ document(id: "{}") {{
+                title blockCount
+                blocks {{ id contentType contentText label children {{ id }} edges {{ edgeType target {{ id }} }} }}
+            }} }}"#,
+            f.doc_id
+        );
+        let data = run(&f.schema, &query).await;
+        let doc = &data["document"];
+        assert_eq!(doc["title"], "Guide");
+        assert_eq!(doc["blockCount"], 3);
+
+        let blocks = doc["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[1]["label"], "intro");
+        assert_eq!(blocks[1]["children"], json!([{ "id": f.body.to_string() }]));
+        assert_eq!(blocks[2]["contentText"], "Install the CLI");
+        assert_eq!(
+            blocks[2]["edges"],
+            json!([{ "edgeType": "references", "target": { "id": f.intro.to_string() } }])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_block_navigation_and_search() {
+        let f = fixture();
+        let query = format!(
+            r#"{{ document(id: "{}") {{
+                block(id: "{}") {{ role tags ancestors {{ id }} }}
+                intro: block(id: "{}") {{ incomingEdges {{ source {{ id }} }} }}
+                searchBlocks(query: "SETUP") {{ id }}
+            }} }}"#,
+            f.doc_id, f.body, f.intro
+        );
+        let data = run(&f.schema, &query).await;
+        let doc = &data["document"];
+        assert_eq!(doc["block"]["role"], "paragraph");
+        assert_eq!(doc["block"]["tags"], json!(["setup"]));
+        assert_eq!(doc["block"]["ancestors"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            doc["intro"]["incomingEdges"],
+            json!([{ "source": { "id": f.body.to_string() } }])
+        );
+        assert_eq!(doc["searchBlocks"], json!([{ "id": f.body.to_string() }]));
+    }
+
+    #[tokio::test]
+    async fn test_mutations_update_store() {
+        let f = fixture();
+        let mutation = format!(
+            r#"mutation {{
+                appendBlock(documentId: "{doc}", parentId: "{intro}", text: "Run it", label: "run") {{ id contentText label }}
+                editBlock(documentId: "{doc}", blockId: "{body}", value: "Install the tool") {{ contentText }}
+            }}"#,
+            doc = f.doc_id,
+            intro = f.intro,
+            body = f.body
+        );
+        let data = run(&f.schema, &mutation).await;
+        assert_eq!(data["appendBlock"]["contentText"], "Run it");
+        assert_eq!(data["appendBlock"]["label"], "run");
+        assert_eq!(data["editBlock"]["contentText"], "Install the tool");
+
+        let doc = f.store.get(&f.doc_id).unwrap();
+        assert_eq!(doc.block_count(), 4);
+        assert_eq!(doc.children(&f.intro).len(), 2);
+
+        let mutation = format!(
+            r#"mutation {{ deleteBlock(documentId: "{}", blockId: "{}", cascade: true) }}"#,
+            f.doc_id, f.intro
+        );
+        let data = run(&f.schema, &mutation).await;
+        assert_eq!(data["deleteBlock"].as_array().unwrap().len(), 3);
+        assert_eq!(f.store.get(&f.doc_id).unwrap().block_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_mutation_leaves_document_unchanged() {
+        let f = fixture();
+        let before = f.store.get(&f.doc_id).unwrap();
+        let mutation = format!(
+            r#"mutation {{ editBlock(documentId: "{}", blockId: "blk_ffffffffffffffffffffffff", value: "x") {{ id }} }}"#,
+            f.doc_id
+        );
+        let response = f.schema.execute(mutation).await;
+        assert_eq!(response.errors.len(), 1);
+
+        let after = f.store.get(&f.doc_id).unwrap();
+        assert!(Arc::ptr_eq(&before, &after));
+
+        let response = f
+            .schema
+            .execute(r#"{ document(id: "missing") { id } }"#)
+            .await;
+        assert_eq!(response.data.into_json().unwrap()["document"], Value::Null);
+    }
+}
@@ -0,0 +1,94 @@
+//! Shared document store backing the schema.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use ucm_core::Document;
+use ucm_engine::{Engine, Operation, OperationResult};
+
+use crate::error::{GraphqlError, Result};
+
+/// Documents served by the schema, keyed by document id
+#[derive(Clone, Default)]
+pub struct DocumentStore {
+    inner: Arc<StoreInner>,
+}
+
+#[derive(Default)]
+struct StoreInner {
+    documents: RwLock<HashMap<String, Arc<Document>>>,
+    engine: Mutex<Engine>,
+}
+
+impl DocumentStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace a document. Returns its id.
+    pub fn insert(&self, doc: Document) -> String {
+        let id = doc.id.to_string();
+        self.inner
+            .documents
+            .write()
+            .unwrap()
+            .insert(id.clone(), Arc::new(doc));
+        id
+    }
+
+    /// Remove a document
+    pub fn remove(&self, id: &str) -> Option<Document> {
+        self.inner
+            .documents
+            .write()
+            .unwrap()
+            .remove(id)
+            .map(|doc| (*doc).clone())
+    }
+
+    /// Snapshot of a document; later mutations do not affect it
+    pub fn get(&self, id: &str) -> Option<Arc<Document>> {
+        self.inner.documents.read().unwrap().get(id).cloned()
+    }
+
+    /// Ids of all stored documents, sorted
+    pub fn ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .inner
+            .documents
+            .read()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// Run operations in an engine transaction.
+    ///
+    /// The operations are applied to a copy of the document, which replaces
+    /// the stored one only if every operation succeeds.
+    pub fn apply(&self, id: &str, ops: Vec<Operation>) -> Result<Vec<OperationResult>> {
+        let mut documents = self.inner.documents.write().unwrap();
+        let current = documents
+            .get(id)
+            .ok_or_else(|| GraphqlError::DocumentNotFound(id.to_string()))?;
+        let mut staged = (**current).clone();
+
+        let mut engine = self.inner.engine.lock().unwrap();
+        let txn = engine.begin_transaction();
+        for op in ops {
+            engine.add_to_transaction(&txn, op)?;
+        }
+        let results = engine.commit_transaction(&txn, &mut staged)?;
+
+        if let Some(failed) = results.iter().find(|r| !r.success) {
+            return Err(GraphqlError::OperationFailed(
+                failed.error.clone().unwrap_or_default(),
+            ));
+        }
+        documents.insert(id.to_string(), Arc::new(staged));
+        Ok(results)
+    }
+}
@@ -0,0 +1,399 @@
+//! Symbol-centric rendering of CodeGraph documents.
+//!
+//! CodeGraph documents (built by `ucp-codegraph`) tag each block with a
+//! `node_class` of `repository`, `directory`, `file` or `symbol` and keep
+//! symbol details as JSON content. The rendering lists files in path order
+//! with their symbols nested below, puts call and inheritance edges on the
+//! symbol's own line and leaves repository and directory blocks out.
+
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ucm_core::{Block, BlockId, Content, Document, EdgeType};
+
+use crate::id_mapper::IdMapper;
+
+const META_NODE_CLASS: &str = "node_class";
+const META_LOGICAL_KEY: &str = "logical_key";
+const META_CODEREF: &str = "coderef";
+const META_LANGUAGE: &str = "language";
+
+/// Lines of a symbol's docs or text kept in the rendering
+const SYMBOL_CONTENT_LINES: usize = 5;
+
+/// Edge relations shown inline on a symbol's line
+const INLINE_RELATIONS: &[&str] = &["calls", "extends", "implements", "inherits"];
+
+/// Budgets for rendering a CodeGraph document into a prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeGraphPromptProjectionConfig {
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+    #[serde(default = "default_max_symbols_total")]
+    pub max_symbols_total: usize,
+    #[serde(default = "default_max_symbols_per_file")]
+    pub max_symbols_per_file: usize,
+    #[serde(default = "default_max_edges_per_symbol")]
+    pub max_edges_per_symbol: usize,
+}
+
+impl Default for CodeGraphPromptProjectionConfig {
+    fn default() -> Self {
+        Self {
+            max_files: default_max_files(),
+            max_symbols_total: default_max_symbols_total(),
+            max_symbols_per_file: default_max_symbols_per_file(),
+            max_edges_per_symbol: default_max_edges_per_symbol(),
+        }
+    }
+}
+
+const fn default_max_files() -> usize {
+    40
+}
+
+const fn default_max_symbols_total() -> usize {
+    160
+}
+
+const fn default_max_symbols_per_file() -> usize {
+    8
+}
+
+const fn default_max_edges_per_symbol() -> usize {
+    4
+}
+
+/// One-line description of the repository a CodeGraph document was built from
+pub(crate) fn describe_repository(doc: &Document) -> String {
+    let name = doc
+        .blocks
+        .values()
+        .find(|block| node_class(block) == Some("repository"))
+        .and_then(|block| content_str(block, "name"))
+        .unwrap_or("repository");
+    let files = count_class(doc, "file");
+    let symbols = count_class(doc, "symbol");
+    format!(
+        "The document is a CodeGraph of {} with {} files and {} symbols.",
+        name, files, symbols
+    )
+}
+
+pub(crate) fn render(
+    doc: &Document,
+    config: &CodeGraphPromptProjectionConfig,
+    mapper: Option<&IdMapper>,
+) -> String {
+    let renderer = Renderer {
+        doc,
+        config,
+        mapper,
+    };
+    renderer.render()
+}
+
+struct Renderer<'a> {
+    doc: &'a Document,
+    config: &'a CodeGraphPromptProjectionConfig,
+    mapper: Option<&'a IdMapper>,
+}
+
+impl Renderer<'_> {
+    fn render(&self) -> String {
+        let doc = self.doc;
+        let files = file_ids(doc);
+        let total_symbols = count_class(doc, "symbol");
+        let total_edges: usize = doc.blocks.values().map(|block| block.edges.len()).sum();
+
+        let mut out = String::new();
+        out.push_str("CodeGraph projection\n");
+        let _ = writeln!(
+            out,
+            "summary: files={} symbols={} edges={}",
+            files.len(),
+            total_symbols,
+            total_edges
+        );
+
+        let mut emitted = 0;
+        for file_id in files.iter().take(self.config.max_files) {
+            let Some(file) = doc.get_block(file_id) else {
+                continue;
+            };
+            let language = file
+                .metadata
+                .custom
+                .get(META_LANGUAGE)
+                .and_then(Value::as_str)
+                .unwrap_or("unknown");
+            let _ = writeln!(
+                out,
+                "\n[{}] file {} [{}]",
+                self.display_id(file_id),
+                location(file).unwrap_or("file"),
+                language
+            );
+
+            let mut budget = self
+                .config
+                .max_symbols_per_file
+                .min(self.config.max_symbols_total - emitted);
+            for symbol_id in top_level_symbols(doc, file_id) {
+                if budget == 0 {
+                    break;
+                }
+                self.render_symbol(&mut out, &symbol_id, 1, &mut budget, &mut emitted);
+            }
+
+            if emitted >= self.config.max_symbols_total {
+                let omitted = total_symbols.saturating_sub(emitted);
+                if omitted > 0 {
+                    let _ = writeln!(out, "… {} more symbols omitted by budget", omitted);
+                }
+                break;
+            }
+        }
+
+        if files.len() > self.config.max_files {
+            let _ = writeln!(
+                out,
+                "\n… {} more files omitted by budget",
+                files.len() - self.config.max_files
+            );
+        }
+
+        out.trim_end().to_string()
+    }
+
+    fn render_symbol(
+        &self,
+        out: &mut String,
+        id: &BlockId,
+        depth: usize,
+        budget: &mut usize,
+        emitted: &mut usize,
+    ) {
+        let Some(block) = self.doc.get_block(id) else {
+            return;
+        };
+        *budget -= 1;
+        *emitted += 1;
+
+        let pad = "  ".repeat(depth);
+        let _ = write!(
+            out,
+            "{}[{}] {}{}",
+            pad,
+            self.display_id(id),
+            signature(block),
+            modifiers(block)
+        );
+        let edges = self.inline_edges(block);
+        if !edges.is_empty() {
+            let _ = write!(out, " | {}", edges.join(", "));
+        }
+        out.push('\n');
+
+        let text = symbol_text(block);
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        for line in lines.by_ref().take(SYMBOL_CONTENT_LINES) {
+            let _ = writeln!(out, "{}    {}", pad, line.trim_end());
+        }
+        if lines.next().is_some() {
+            let _ = writeln!(out, "{}    …", pad);
+        }
+
+        for child in child_symbols(self.doc, id) {
+            if *budget == 0 {
+                break;
+            }
+            self.render_symbol(out, &child, depth + 1, budget, emitted);
+        }
+    }
+
+    fn inline_edges(&self, block: &Block) -> Vec<String> {
+        let mut edges: Vec<String> = block
+            .edges
+            .iter()
+            .filter_map(|edge| {
+                let relation = edge
+                    .metadata
+                    .custom
+                    .get("relation")
+                    .and_then(Value::as_str)
+                    .or(match &edge.edge_type {
+                        EdgeType::Custom(name) => Some(name.as_str()),
+                        _ => None,
+                    })?;
+                if !INLINE_RELATIONS.contains(&relation) {
+                    return None;
+                }
+                let target = self
+                    .doc
+                    .get_block(&edge.target)
+                    .and_then(logical_key)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| self.display_id(&edge.target));
+                Some(format!("{} {}", relation, target))
+            })
+            .collect();
+        edges.sort();
+        edges.dedup();
+        edges.truncate(self.config.max_edges_per_symbol);
+        edges
+    }
+
+    fn display_id(&self, id: &BlockId) -> String {
+        self.mapper
+            .and_then(|mapper| mapper.to_short_id(id))
+            .map(|short| short.to_string())
+            .unwrap_or_else(|| id.to_string())
+    }
+}
+
+fn file_ids(doc: &Document) -> Vec<BlockId> {
+    let mut files: Vec<&Block> = doc
+        .blocks
+        .values()
+        .filter(|block| node_class(block) == Some("file"))
+        .collect();
+    files.sort_by_key(|block| sort_key(block));
+    files.into_iter().map(|block| block.id).collect()
+}
+
+/// Symbols below `parent` that have no symbol between them and `parent`
+fn top_level_symbols(doc: &Document, parent: &BlockId) -> Vec<BlockId> {
+    let mut symbols = Vec::new();
+    let mut stack = doc.children(parent).to_vec();
+    while let Some(id) = stack.pop() {
+        let Some(block) = doc.get_block(&id) else {
+            continue;
+        };
+        if node_class(block) == Some("symbol") {
+            symbols.push(block);
+        } else {
+            stack.extend(doc.children(&id));
+        }
+    }
+    symbols.sort_by_key(|block| sort_key(block));
+    symbols.into_iter().map(|block| block.id).collect()
+}
+
+fn child_symbols(doc: &Document, parent: &BlockId) -> Vec<BlockId> {
+    let mut symbols: Vec<&Block> = doc
+        .children(parent)
+        .iter()
+        .filter_map(|id| doc.get_block(id))
+        .filter(|block| node_class(block) == Some("symbol"))
+        .collect();
+    symbols.sort_by_key(|block| sort_key(block));
+    symbols.into_iter().map(|block| block.id).collect()
+}
+
+fn count_class(doc: &Document, class: &str) -> usize {
+    doc.blocks
+        .values()
+        .filter(|block| node_class(block) == Some(class))
+        .count()
+}
+
+fn sort_key(block: &Block) -> (String, String) {
+    (
+        location(block).unwrap_or_default().to_string(),
+        logical_key(block)
+            .map(str::to_string)
+            .unwrap_or_else(|| block.id.to_string()),
+    )
+}
+
+fn signature(block: &Block) -> String {
+    let kind = content_str(block, "kind").unwrap_or("symbol");
+    let name = content_str(block, "name").unwrap_or("unknown");
+    match kind {
+        "function" | "method" => {
+            let inputs: Vec<String> = content_value(block, "inputs")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .map(|input| {
+                    let name = input.get("name").and_then(Value::as_str).unwrap_or("_");
+                    match input.get("type").and_then(Value::as_str) {
+                        Some(type_name) => format!("{}: {}", name, type_name),
+                        None => name.to_string(),
+                    }
+                })
+                .collect();
+            let mut rendered = format!("{} {}({})", kind, name, inputs.join(", "));
+            if let Some(output) = content_str(block, "output") {
+                let _ = write!(rendered, " -> {}", output);
+            }
+            rendered
+        }
+        _ => match content_str(block, "type") {
+            Some(type_name) => format!("{} {} : {}", kind, name, type_name),
+            None => format!("{} {}", kind, name),
+        },
+    }
+}
+
+fn modifiers(block: &Block) -> String {
+    let Some(modifiers) = content_value(block, "modifiers").and_then(Value::as_object) else {
+        return String::new();
+    };
+    let mut parts: Vec<&str> = ["async", "static", "generator"]
+        .into_iter()
+        .filter(|flag| modifiers.get(*flag).and_then(Value::as_bool) == Some(true))
+        .collect();
+    if let Some(visibility) = modifiers.get("visibility").and_then(Value::as_str) {
+        parts.push(visibility);
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", parts.join(", "))
+    }
+}
+
+/// Docs for a JSON symbol, the content itself for text or code symbols
+fn symbol_text(block: &Block) -> String {
+    if let Some(summary) = &block.metadata.summary {
+        return summary.clone();
+    }
+    match &block.content {
+        Content::Json { .. } => content_str(block, "description")
+            .unwrap_or_default()
+            .to_string(),
+        Content::Text(text) => text.text.clone(),
+        Content::Code(code) => code.source.clone(),
+        _ => String::new(),
+    }
+}
+
+fn node_class(block: &Block) -> Option<&str> {
+    block.metadata.custom.get(META_NODE_CLASS)?.as_str()
+}
+
+fn logical_key(block: &Block) -> Option<&str> {
+    block.metadata.custom.get(META_LOGICAL_KEY)?.as_str()
+}
+
+/// The coderef display, e.g. `src/lib.rs` or `src/lib.rs#L3`
+fn location(block: &Block) -> Option<&str> {
+    content_value(block, "coderef")
+        .or_else(|| block.metadata.custom.get(META_CODEREF))?
+        .get("display")?
+        .as_str()
+}
+
+fn content_value<'a>(block: &'a Block, field: &str) -> Option<&'a Value> {
+    let Content::Json { value, .. } = &block.content else {
+        return None;
+    };
+    value.get(field)
+}
+
+fn content_str<'a>(block: &'a Block, field: &str) -> Option<&'a str> {
+    content_value(block, field)?.as_str()
+}
@@ -5,7 +5,11 @@
 //! the knowledge graph, and curate context windows while preserving UCM invariants.
 
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 use ucm_core::{BlockId, Content, Document};
 
 #[cfg(test)]
@@ -58,6 +62,72 @@ pub struct ContextMetadata {
     pub last_modified: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// Counts tokens for a specific model's tokenizer
+pub trait TokenCounter: Send + Sync {
+    /// Count the tokens in `text`
+    fn count_tokens(&self, text: &str) -> usize;
+
+    /// Name used when displaying the counter
+    fn name(&self) -> &str {
+        "custom"
+    }
+}
+
+/// Default heuristic: roughly 4 characters per token
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicTokenCounter;
+
+impl TokenCounter for HeuristicTokenCounter {
+    fn count_tokens(&self, text: &str) -> usize {
+        (text.len() / 4).max(1)
+    }
+
+    fn name(&self) -> &str {
+        "heuristic"
+    }
+}
+
+/// Shared handle to the token counter used by a context window.
+///
+/// Not serialized: a deserialized window falls back to the heuristic until
+/// a counter is installed again (see [`ContextManager::set_token_counter`]).
+#[derive(Clone)]
+pub struct SharedTokenCounter(Arc<dyn TokenCounter>);
+
+impl SharedTokenCounter {
+    pub fn new(counter: impl TokenCounter + 'static) -> Self {
+        Self(Arc::new(counter))
+    }
+
+    pub fn count_tokens(&self, text: &str) -> usize {
+        self.0.count_tokens(text)
+    }
+
+    pub fn as_counter(&self) -> &dyn TokenCounter {
+        self.0.as_ref()
+    }
+}
+
+impl From<Arc<dyn TokenCounter>> for SharedTokenCounter {
+    fn from(counter: Arc<dyn TokenCounter>) -> Self {
+        Self(counter)
+    }
+}
+
+impl Default for SharedTokenCounter {
+    fn default() -> Self {
+        Self::new(HeuristicTokenCounter)
+    }
+}
+
+impl fmt::Debug for SharedTokenCounter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedTokenCounter")
+            .field(&self.0.name())
+            .finish()
+    }
+}
+
 /// Constraints for the context window
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContextConstraints {
@@ -69,6 +139,12 @@ pub struct ContextConstraints {
     pub excluded_tags: Vec<String>,
     pub preserve_structure: bool,
     pub allow_compression: bool,
+    /// Jaccard similarity above which near-duplicate blocks are dropped
+    /// while pruning (`None` disables deduplication)
+    #[serde(default)]
+    pub dedup_threshold: Option<f32>,
+    #[serde(skip)]
+    pub token_counter: SharedTokenCounter,
 }
 
 impl Default for ContextConstraints {
@@ -82,6 +158,8 @@ impl Default for ContextConstraints {
             excluded_tags: Vec::new(),
             preserve_structure: true,
             allow_compression: true,
+            dedup_threshold: None,
+            token_counter: SharedTokenCounter::default(),
         }
     }
 }
@@ -208,6 +286,40 @@ impl ContextWindow {
     pub fn block_ids(&self) -> Vec<BlockId> {
         self.blocks.keys().copied().collect()
     }
+
+    /// Re-estimate every entry's token count with a different tokenizer.
+    ///
+    /// Compressed entries keep their compression ratio relative to the
+    /// window's current counter. Blocks no longer in `doc` are left as-is.
+    /// This does not prune; use [`ContextManager::set_token_counter`] to
+    /// recount and enforce constraints in one step.
+    pub fn recount(&mut self, doc: &Document, counter: &dyn TokenCounter) {
+        for (block_id, context_block) in self.blocks.iter_mut() {
+            let Some(block) = doc.get_block(block_id) else {
+                continue;
+            };
+            let text = content_text(&block.content);
+            let full = counter.count_tokens(&text).max(1);
+
+            context_block.token_estimate = if context_block.compressed {
+                let previous_full = self.constraints.token_counter.count_tokens(&text).max(1);
+                let ratio = context_block.token_estimate as f64 / previous_full as f64;
+                ((full as f64 * ratio).ceil() as usize).max(1)
+            } else {
+                full
+            };
+        }
+    }
+
+    /// Serialize the window to JSON
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Deserialize a window from JSON (the token counter resets to the heuristic)
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
 }
 
 /// Context Management Infrastructure
@@ -250,11 +362,38 @@ impl ContextManager {
         self
     }
 
+    /// Restore a manager from a previously persisted window
+    pub fn from_window(window: ContextWindow) -> Self {
+        Self {
+            window,
+            expansion_policy: ExpansionPolicy::default(),
+            pruning_policy: PruningPolicy::default(),
+        }
+    }
+
     /// Get a reference to the context window
     pub fn window(&self) -> &ContextWindow {
         &self.window
     }
 
+    /// Switch tokenizer: re-estimate all entries, then prune to the constraints
+    pub fn set_token_counter(
+        &mut self,
+        doc: &Document,
+        counter: SharedTokenCounter,
+    ) -> ContextUpdateResult {
+        self.window.recount(doc, counter.as_counter());
+        self.window.constraints.token_counter = counter;
+        self.window.metadata.last_modified = Some(chrono::Utc::now());
+
+        ContextUpdateResult {
+            blocks_removed: self.prune_if_needed(doc),
+            total_tokens: self.window.total_tokens(),
+            total_blocks: self.window.block_count(),
+            ..Default::default()
+        }
+    }
+
     /// Initialize context with a focus block
     pub fn initialize_focus(
         &mut self,
@@ -317,7 +456,7 @@ impl ContextManager {
         }
 
         // Prune if needed
-        let pruned = self.prune_if_needed();
+        let pruned = self.prune_if_needed(doc);
         result.blocks_removed = pruned;
 
         result.total_tokens = self.window.total_tokens();
@@ -340,7 +479,7 @@ impl ContextManager {
         }
 
         // Prune if needed
-        let pruned = self.prune_if_needed();
+        let pruned = self.prune_if_needed(doc);
         result.blocks_removed = pruned;
 
         result.total_tokens = self.window.total_tokens();
@@ -398,7 +537,7 @@ impl ContextManager {
         }
 
         // Prune if needed
-        let pruned = self.prune_if_needed();
+        let pruned = self.prune_if_needed(doc);
         result.blocks_removed = pruned;
 
         self.window.metadata.last_modified = Some(chrono::Utc::now());
@@ -471,6 +610,28 @@ impl ContextManager {
         result
     }
 
+    /// Remove near-duplicate blocks from the context.
+    ///
+    /// Blocks are compared by Jaccard similarity of their word 3-gram
+    /// shingles; of each pair above `threshold`, the block with the lower
+    /// relevance is removed. The focus block is never removed.
+    pub fn deduplicate(&mut self, doc: &Document, threshold: f32) -> ContextUpdateResult {
+        let removed = self.find_duplicates(doc, threshold);
+        for block_id in &removed {
+            self.window.blocks.remove(block_id);
+        }
+        if !removed.is_empty() {
+            self.window.metadata.last_modified = Some(chrono::Utc::now());
+        }
+
+        ContextUpdateResult {
+            blocks_removed: removed,
+            total_tokens: self.window.total_tokens(),
+            total_blocks: self.window.block_count(),
+            ..Default::default()
+        }
+    }
+
     /// Get statistics about the context
     pub fn get_statistics(&self) -> ContextStatistics {
         let mut blocks_by_reason: HashMap<String, usize> = HashMap::new();
@@ -516,6 +677,10 @@ impl ContextManager {
 
         for (block_id, context_block) in blocks {
             if let Some(block) = doc.get_block(block_id) {
+                // Diagram source is layout markup, not prose the model can use
+                if matches!(block.content, Content::Diagram(_)) {
+                    continue;
+                }
                 let content = if context_block.compressed {
                     if let Some(ref original) = context_block.original_content {
                         format!("[compressed] {}...", &original[..original.len().min(50)])
@@ -678,9 +843,21 @@ impl ContextManager {
         added
     }
 
-    fn prune_if_needed(&mut self) -> Vec<BlockId> {
+    fn over_budget(&self) -> bool {
+        self.window.block_count() > self.window.constraints.max_blocks
+            || self.window.total_tokens() > self.window.constraints.max_tokens
+    }
+
+    fn prune_if_needed(&mut self, doc: &Document) -> Vec<BlockId> {
         let mut removed = Vec::new();
 
+        // Duplicates go first, and only once the window is over budget
+        if let Some(threshold) = self.window.constraints.dedup_threshold {
+            if self.over_budget() {
+                removed = self.deduplicate(doc, threshold).blocks_removed;
+            }
+        }
+
         while self.window.block_count() > self.window.constraints.max_blocks
             || self.window.total_tokens() > self.window.constraints.max_tokens
         {
@@ -715,6 +892,53 @@ impl ContextManager {
             .map(|(id, _)| *id)
     }
 
+    /// Walk blocks from most to least relevant, marking each one that is too
+    /// similar to a block already kept
+    fn find_duplicates(&self, doc: &Document, threshold: f32) -> Vec<BlockId> {
+        let focus = self.window.metadata.focus_area;
+        let mut candidates: Vec<(&ContextBlock, Vec<u64>)> = self
+            .window
+            .blocks
+            .values()
+            .filter_map(|cb| {
+                let block = doc.get_block(&cb.block_id)?;
+                Some((cb, shingles(&content_text(&block.content))))
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            (Some(b.0.block_id) == focus)
+                .cmp(&(Some(a.0.block_id) == focus))
+                .then_with(|| {
+                    b.0.relevance_score
+                        .partial_cmp(&a.0.relevance_score)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .then_with(|| a.0.block_id.as_bytes().cmp(b.0.block_id.as_bytes()))
+        });
+
+        let mut kept: Vec<&[u64]> = Vec::new();
+        let mut removed = Vec::new();
+        for (cb, shingles) in &candidates {
+            if shingles.is_empty() {
+                continue;
+            }
+            // Similarity can be no higher than the ratio of the set sizes,
+            // which rules out most pairs without comparing shingles
+            let len = shingles.len() as f32;
+            let duplicate = kept.iter().any(|other| {
+                let other_len = other.len() as f32;
+                len.min(other_len) / len.max(other_len) > threshold
+                    && jaccard(shingles, other) > threshold
+            });
+            if duplicate {
+                removed.push(cb.block_id);
+            } else {
+                kept.push(shingles);
+            }
+        }
+        removed
+    }
+
     fn find_least_recent(&self) -> Option<BlockId> {
         self.window
             .blocks
@@ -726,24 +950,87 @@ impl ContextManager {
 
     fn estimate_tokens(&self, content: &Content) -> usize {
         let text = self.extract_content_text(content);
-        // Rough estimate: ~4 characters per token
-        (text.len() / 4).max(1)
+        self.window
+            .constraints
+            .token_counter
+            .count_tokens(&text)
+            .max(1)
     }
 
     fn extract_content_text(&self, content: &Content) -> String {
-        match content {
-            Content::Text(t) => t.text.clone(),
-            Content::Code(c) => c.source.clone(),
-            Content::Table(t) => format!("Table: {} rows", t.rows.len()),
-            Content::Math(m) => m.expression.clone(),
-            Content::Media(m) => m.alt_text.clone().unwrap_or_else(|| "Media".to_string()),
-            Content::Json { .. } => "JSON data".to_string(),
-            Content::Binary { .. } => "Binary data".to_string(),
-            Content::Composite { children, .. } => {
-                format!("Composite: {} children", children.len())
+        content_text(content)
+    }
+}
+
+fn content_text(content: &Content) -> String {
+    match content {
+        Content::Text(t) => t.text.clone(),
+        Content::RichText(r) => r.plain_text(),
+        Content::Code(c) => c.source.clone(),
+        Content::CompressedCode { .. } => content.decompress().unwrap_or_default(),
+        Content::Table(t) => format!("Table: {} rows", t.rows.len()),
+        Content::Spreadsheet(s) => format!("Spreadsheet: {} rows", s.rows.len()),
+        Content::Math(m) => m.expression.clone(),
+        Content::Diagram(d) => d.source.clone(),
+        Content::Diff(d) => d.patch.clone(),
+        Content::Media(m) => m.alt_text.clone().unwrap_or_else(|| "Media".to_string()),
+        Content::GeoJson(value) => format!(
+            "GeoJSON {}",
+            value
+                .get("type")
+                .and_then(|t| t.as_str())
+                .unwrap_or("object")
+        ),
+        Content::Form(f) => format!("Form: {} ({} fields)", f.title, f.fields.len()),
+        Content::Json { .. } => "JSON data".to_string(),
+        Content::Binary { .. } => "Binary data".to_string(),
+        Content::Composite { children, .. } => {
+            format!("Composite: {} children", children.len())
+        }
+    }
+}
+
+/// Sorted, deduplicated hashes of the lowercase word 3-grams in `text`.
+/// Text with fewer than three words yields one shingle of all its words.
+fn shingles(text: &str) -> Vec<u64> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let mut hashes: Vec<u64> = words
+        .windows(3.min(words.len()).max(1))
+        .map(|gram| {
+            let mut hasher = DefaultHasher::new();
+            gram.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect();
+    hashes.sort_unstable();
+    hashes.dedup();
+    hashes
+}
+
+/// Jaccard similarity of two sorted shingle sets
+fn jaccard(a: &[u64], b: &[u64]) -> f32 {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if large.is_empty() {
+        return 0.0;
+    }
+    let mut shared = 0;
+    let (mut i, mut j) = (0, 0);
+    while i < small.len() && j < large.len() {
+        match small[i].cmp(&large[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                shared += 1;
+                i += 1;
+                j += 1;
             }
         }
     }
+    shared as f32 / (small.len() + large.len() - shared) as f32
 }
 
 #[cfg(test)]
@@ -876,4 +1163,259 @@ mod tests {
         assert!(!prompt.is_empty());
         assert!(prompt.contains("Chapter 1"));
     }
+
+    #[test]
+    fn test_render_for_prompt_skips_diagrams() {
+        let mut doc = create_test_document();
+        let h1_id = doc.children(&doc.root)[0];
+        let diagram_id = doc
+            .add_block(
+                Block::new(Content::mermaid("graph TD\n  A --> B"), None),
+                &h1_id,
+            )
+            .unwrap();
+        let mut manager = ContextManager::new("test-context");
+
+        manager.initialize_focus(&doc, h1_id, "Test task");
+        manager.add_block(&doc, diagram_id, InclusionReason::DirectReference);
+        assert!(manager.window.blocks.contains_key(&diagram_id));
+
+        let prompt = manager.render_for_prompt(&doc);
+        assert!(prompt.contains("Chapter 1"));
+        assert!(!prompt.contains("A --> B"));
+    }
+
+    /// Counts one token per 8 characters (cheaper than the default heuristic)
+    struct CompactCounter;
+
+    impl TokenCounter for CompactCounter {
+        fn count_tokens(&self, text: &str) -> usize {
+            (text.len() / 8).max(1)
+        }
+    }
+
+    fn paragraph_document(count: usize) -> (Document, Vec<BlockId>) {
+        let mut doc = Document::new(DocumentId::new("paragraphs"));
+        let root = doc.root;
+        let ids = (0..count)
+            .map(|i| {
+                // 40 characters: 10 heuristic tokens, 5 compact tokens
+                let text = format!("Paragraph {:02} padded to forty characters.", i);
+                assert_eq!(text.len(), 40);
+                doc.add_block(Block::new(Content::text(text), Some("paragraph")), &root)
+                    .unwrap()
+            })
+            .collect();
+        (doc, ids)
+    }
+
+    #[test]
+    fn test_recount_changes_eviction() {
+        let constraints = ContextConstraints {
+            max_tokens: 25,
+            ..Default::default()
+        };
+        let (doc, ids) = paragraph_document(3);
+
+        // Default heuristic: three 10-token blocks overflow a 25-token budget
+        let mut heuristic = ContextManager::with_constraints("heuristic", constraints.clone());
+        heuristic.add_block(&doc, ids[0], InclusionReason::DirectReference);
+        heuristic.add_block(&doc, ids[1], InclusionReason::DirectReference);
+        let result = heuristic.add_block(&doc, ids[2], InclusionReason::DirectReference);
+        assert_eq!(result.blocks_removed.len(), 1);
+        assert_eq!(heuristic.window().block_count(), 2);
+
+        // After switching to a cheaper tokenizer all three fit
+        let mut compact = ContextManager::with_constraints("compact", constraints);
+        compact.add_block(&doc, ids[0], InclusionReason::DirectReference);
+        compact.add_block(&doc, ids[1], InclusionReason::DirectReference);
+        assert_eq!(compact.window().total_tokens(), 20);
+
+        let result = compact.set_token_counter(&doc, SharedTokenCounter::new(CompactCounter));
+        assert!(result.blocks_removed.is_empty());
+        assert_eq!(compact.window().total_tokens(), 10);
+
+        let result = compact.add_block(&doc, ids[2], InclusionReason::DirectReference);
+        assert!(result.blocks_removed.is_empty());
+        assert_eq!(compact.window().block_count(), 3);
+        assert_eq!(compact.window().total_tokens(), 15);
+    }
+
+    #[test]
+    fn test_recount_to_more_expensive_counter_prunes() {
+        let constraints = ContextConstraints {
+            max_tokens: 25,
+            token_counter: SharedTokenCounter::new(CompactCounter),
+            ..Default::default()
+        };
+        let (doc, ids) = paragraph_document(3);
+        let mut manager = ContextManager::with_constraints("ctx", constraints);
+        for id in &ids {
+            manager.add_block(&doc, *id, InclusionReason::DirectReference);
+        }
+        assert_eq!(manager.window().total_tokens(), 15);
+
+        let result = manager.set_token_counter(&doc, SharedTokenCounter::default());
+        assert_eq!(result.blocks_removed.len(), 1);
+        assert!(manager.window().total_tokens() <= 25);
+    }
+
+    #[test]
+    fn test_recount_preserves_compression_ratio() {
+        let (doc, ids) = paragraph_document(1);
+        let mut window = ContextWindow::new("ctx", ContextConstraints::default());
+        window.blocks.insert(
+            ids[0],
+            ContextBlock {
+                block_id: ids[0],
+                inclusion_reason: InclusionReason::DirectReference,
+                relevance_score: 1.0,
+                token_estimate: 5, // half of the 10 heuristic tokens
+                access_count: 1,
+                last_accessed: chrono::Utc::now(),
+                compressed: true,
+                original_content: None,
+            },
+        );
+
+        window.recount(&doc, &CompactCounter);
+        assert_eq!(window.get(&ids[0]).unwrap().token_estimate, 3);
+    }
+
+    #[test]
+    fn test_context_window_serialization() {
+        let doc = create_test_document();
+        let h1_id = doc.children(&doc.root)[0];
+        let mut manager = ContextManager::new("persisted");
+        manager.initialize_focus(&doc, h1_id, "Test task");
+        manager.expand_context(&doc, ExpandDirection::Down, 2);
+
+        let json = manager.window().to_json().unwrap();
+        let restored = ContextWindow::from_json(&json).unwrap();
+
+        assert_eq!(restored.id, "persisted");
+        assert_eq!(restored.block_count(), manager.window().block_count());
+        assert_eq!(restored.metadata.focus_area, Some(h1_id));
+        let original = manager.window().get(&h1_id).unwrap();
+        let block = restored.get(&h1_id).unwrap();
+        assert_eq!(block.inclusion_reason, original.inclusion_reason);
+        assert_eq!(block.relevance_score, original.relevance_score);
+
+        let total = manager.window().total_tokens();
+        let resumed = ContextManager::from_window(restored);
+        assert_eq!(resumed.window().total_tokens(), total);
+    }
+
+    fn near_duplicate_document() -> (Document, Vec<BlockId>) {
+        let mut doc = Document::new(DocumentId::new("duplicates"));
+        let root = doc.root;
+        let texts = [
+            "The quick brown fox jumps over the lazy dog near the quiet river bank today.",
+            "The quick brown fox jumps over the lazy dog near the quiet river bank today!",
+            "Completely unrelated paragraph about compiling Rust crates with cargo.",
+        ];
+        let ids = texts
+            .iter()
+            .map(|text| {
+                doc.add_block(Block::new(Content::text(*text), Some("paragraph")), &root)
+                    .unwrap()
+            })
+            .collect();
+        (doc, ids)
+    }
+
+    #[test]
+    fn test_deduplicate_removes_lower_relevance() {
+        let (doc, ids) = near_duplicate_document();
+        let mut manager = ContextManager::new("ctx");
+        manager.add_block(&doc, ids[0], InclusionReason::StructuralContext);
+        manager.add_block(&doc, ids[1], InclusionReason::DirectReference);
+        manager.add_block(&doc, ids[2], InclusionReason::StructuralContext);
+        manager
+            .window
+            .blocks
+            .get_mut(&ids[1])
+            .unwrap()
+            .relevance_score = 0.9;
+
+        let result = manager.deduplicate(&doc, 0.8);
+        assert_eq!(result.blocks_removed, vec![ids[0]]);
+        assert_eq!(result.total_blocks, 2);
+        assert!(manager.window().contains(&ids[1]));
+        assert!(manager.window().contains(&ids[2]));
+
+        // Nothing left above the threshold
+        assert!(manager.deduplicate(&doc, 0.8).blocks_removed.is_empty());
+    }
+
+    #[test]
+    fn test_prune_deduplicates_when_over_budget() {
+        let (doc, ids) = near_duplicate_document();
+        let constraints = ContextConstraints {
+            max_blocks: 2,
+            dedup_threshold: Some(0.8),
+            ..Default::default()
+        };
+        let mut manager = ContextManager::with_constraints("ctx", constraints);
+        manager.add_block(&doc, ids[0], InclusionReason::DirectReference);
+        manager.add_block(&doc, ids[2], InclusionReason::DirectReference);
+        manager
+            .window
+            .blocks
+            .get_mut(&ids[2])
+            .unwrap()
+            .relevance_score = 0.1;
+
+        // Without deduplication the unrelated, less relevant block would go
+        let result = manager.add_block(&doc, ids[1], InclusionReason::DirectReference);
+        assert_eq!(result.blocks_removed.len(), 1);
+        assert!(manager.window().contains(&ids[2]));
+        assert_ne!(result.blocks_removed[0], ids[2]);
+
+        // Within budget, near-duplicates are left alone
+        let mut relaxed = ContextManager::with_constraints(
+            "ctx",
+            ContextConstraints {
+                dedup_threshold: Some(0.8),
+                ..Default::default()
+            },
+        );
+        for id in &ids {
+            relaxed.add_block(&doc, *id, InclusionReason::DirectReference);
+        }
+        assert_eq!(relaxed.window().block_count(), 3);
+    }
+
+    #[test]
+    fn test_deduplicate_large_context() {
+        let mut doc = Document::new(DocumentId::new("large"));
+        let root = doc.root;
+        let mut manager = ContextManager::with_constraints(
+            "ctx",
+            ContextConstraints {
+                max_blocks: 1000,
+                max_tokens: 1_000_000,
+                ..Default::default()
+            },
+        );
+        for i in 0..500 {
+            // Pairs of blocks share all but their last word
+            let text = format!(
+                "Paragraph {} discusses topic number {} in considerable detail with several \
+                 supporting sentences about the subject matter and variant {}",
+                i / 2,
+                i / 2,
+                i % 2
+            );
+            let id = doc
+                .add_block(Block::new(Content::text(text), Some("paragraph")), &root)
+                .unwrap();
+            manager.add_block(&doc, id, InclusionReason::DirectReference);
+        }
+        assert_eq!(manager.window().block_count(), 500);
+
+        let result = manager.deduplicate(&doc, 0.8);
+        assert_eq!(result.blocks_removed.len(), 250);
+        assert_eq!(manager.window().block_count(), 250);
+    }
 }
@@ -20,13 +20,19 @@ use std::collections::HashMap;
 use ucm_core::{BlockId, Content, Document};
 
 /// Get the full string representation of content
-fn content_to_string(content: &Content) -> String {
+pub(crate) fn content_to_string(content: &Content) -> String {
     match content {
         Content::Text(t) => t.text.clone(),
+        Content::RichText(r) => r.plain_text(),
         Content::Code(c) => c.source.clone(),
+        Content::CompressedCode { .. } => content.decompress().unwrap_or_default(),
         Content::Table(t) => format!("Table {}x{}", t.columns.len(), t.rows.len()),
+        Content::Spreadsheet(s) => format!("Spreadsheet {}x{}", s.headers.len(), s.rows.len()),
         Content::Math(m) => m.expression.clone(),
-        Content::Json { value, .. } => value.to_string(),
+        Content::Diagram(d) => d.source.clone(),
+        Content::Diff(d) => d.patch.clone(),
+        Content::Json { value, .. } | Content::GeoJson(value) => value.to_string(),
+        Content::Form(f) => serde_json::to_string(f).unwrap_or_default(),
         Content::Media(m) => format!("Media: {:?}", m.media_type),
         Content::Binary { mime_type, .. } => format!("Binary: {}", mime_type),
         Content::Composite { layout, children } => {
@@ -156,7 +162,7 @@ impl IdMapper {
         // Replace all block IDs with their short versions
         // Process longer IDs first to avoid partial matches
         let mut entries: Vec<_> = self.to_short.iter().collect();
-        entries.sort_by(|a, b| b.0.to_string().len().cmp(&a.0.to_string().len()));
+        entries.sort_by_key(|e| std::cmp::Reverse(e.0.to_string().len()));
 
         for (block_id, short_id) in entries {
             result = result.replace(&block_id.to_string(), &short_id.to_string());
@@ -254,6 +260,11 @@ impl IdMapper {
 
     /// Generate a normalized document representation for LLM prompts
     pub fn document_to_prompt(&self, doc: &Document) -> String {
+        self.section_to_prompt(doc, &doc.root)
+    }
+
+    /// Like [`Self::document_to_prompt`], but only `root` and its descendants
+    pub fn section_to_prompt(&self, doc: &Document, root: &BlockId) -> String {
         let mut lines = Vec::new();
 
         // Header
@@ -262,7 +273,7 @@ impl IdMapper {
         // Collect all block IDs in BFS order
         let mut all_blocks = Vec::new();
         let mut queue = std::collections::VecDeque::new();
-        queue.push_back(doc.root);
+        queue.push_back(*root);
         while let Some(block_id) = queue.pop_front() {
             all_blocks.push(block_id);
             if let Some(children) = doc.structure.get(&block_id) {
@@ -359,7 +370,7 @@ impl Default for IdMapper {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ucm_core::{Block, Content};
+    use ucm_core::{Block, Content, InlineSpan};
 
     #[test]
     fn test_id_mapper() {
@@ -491,4 +502,42 @@ mod tests {
         assert!(prompt.contains("type="));
         assert!(prompt.contains("content=\""));
     }
+
+    #[test]
+    fn test_section_to_prompt_only_renders_subtree() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let intro = doc
+            .add_block(Block::new(Content::text("Intro"), Some("heading1")), &root)
+            .unwrap();
+        doc.add_block(Block::new(Content::text("Inside"), None), &intro)
+            .unwrap();
+        doc.add_block(Block::new(Content::text("Outside"), None), &root)
+            .unwrap();
+
+        let mapper = IdMapper::from_document(&doc);
+        let prompt = mapper.section_to_prompt(&doc, &intro);
+        assert!(prompt.contains("content=\"Inside\""));
+        assert!(!prompt.contains("Outside"));
+        // Short IDs stay those of the whole document
+        let intro_short = mapper.to_short_id(&intro).unwrap();
+        assert!(prompt.contains(&format!("\n{}: ", intro_short)));
+    }
+
+    #[test]
+    fn test_rich_text_prompt_is_plain() {
+        let mut doc = Document::create();
+        let root = doc.root;
+        let rich = Content::rich_text(vec![
+            InlineSpan::plain("Run "),
+            InlineSpan::Code("cargo test".into()),
+            InlineSpan::plain(" "),
+            InlineSpan::Bold(vec![InlineSpan::plain("first")]),
+        ]);
+        doc.add_block(Block::new(rich, Some("paragraph")), &root)
+            .unwrap();
+
+        let prompt = IdMapper::from_document(&doc).document_to_prompt(&doc);
+        assert!(prompt.contains("type=rich_text content=\"Run cargo test first\""));
+    }
 }
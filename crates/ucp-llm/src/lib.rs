@@ -11,6 +11,7 @@
 //! - [`ContextManager`] - Context window management with expansion and pruning
 //! - [`IdMapper`] - Token-efficient ID mapping (shortens block IDs for LLMs)
 //! - [`PromptBuilder`] - Dynamic prompt generation with capability scoping
+//! - [`PromptProjection`] - Document rendering folded around focus blocks
 //!
 //! ## Example
 //!
@@ -24,13 +25,20 @@
 //! println!("Short ID: {} (saves ~18 tokens)", short_id);
 //! ```
 
+pub mod code_graph;
 pub mod context;
 pub mod id_mapper;
+pub mod projection;
 pub mod prompt_builder;
 
+pub use code_graph::CodeGraphPromptProjectionConfig;
 pub use context::{
     CompressionMethod, ContextConstraints, ContextManager, ContextStatistics, ContextUpdateResult,
-    ContextWindow, ExpandDirection, ExpansionPolicy, InclusionReason, PruningPolicy,
+    ContextWindow, ExpandDirection, ExpansionPolicy, HeuristicTokenCounter, InclusionReason,
+    PruningPolicy, SharedTokenCounter, TokenCounter,
 };
 pub use id_mapper::IdMapper;
-pub use prompt_builder::{presets, PromptBuilder, UclCapability};
+pub use projection::PromptProjection;
+pub use prompt_builder::{
+    presets, Example, PromptBuilder, PromptBuilderConfig, PromptBuilderMode, UclCapability,
+};
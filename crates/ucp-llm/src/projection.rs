@@ -0,0 +1,561 @@
+//! Relevance-folded document rendering for LLM prompts
+//!
+//! [`IdMapper::document_to_prompt`] dumps every block, which overflows the
+//! context window on large documents. [`PromptProjection`] renders the
+//! document around a set of focus blocks instead:
+//!
+//! - focus blocks are rendered in full
+//! - their ancestors are rendered as one-line headers
+//! - everything else is folded, e.g. `[... 14 blocks omitted under "Appendix" ...]`
+//!
+//! When a token cap is set, the least relevant focus blocks are demoted to
+//! headers first, and trailing lines are dropped as a last resort.
+
+use std::collections::{HashMap, HashSet};
+
+use ucm_core::{Block, BlockId, Document};
+
+use crate::context::{CompressionMethod, ContextWindow, SharedTokenCounter};
+use crate::id_mapper::{content_to_string, IdMapper};
+
+/// Maximum characters shown for a header or fold title
+const TITLE_CHARS: usize = 80;
+
+/// A rendered line, optionally with a cheaper form used under token pressure
+struct Line {
+    text: String,
+    demoted: Option<String>,
+    relevance: f32,
+}
+
+/// Builder for rendering a document folded around focus blocks
+///
+/// The [`CompressionMethod`] controls how folded subtrees appear:
+/// - `Summarize` (default): blocks with a metadata summary keep their own
+///   line showing the summary; the rest collapse into fold markers
+/// - `Truncate`: every folded run collapses into a single fold marker
+/// - `StructureOnly`: every folded block keeps a header line, descendants
+///   are counted but not shown
+#[derive(Debug, Clone)]
+pub struct PromptProjection {
+    focus: HashMap<BlockId, f32>,
+    max_tokens: Option<usize>,
+    compression: CompressionMethod,
+    id_mapper: Option<IdMapper>,
+    token_counter: SharedTokenCounter,
+}
+
+impl PromptProjection {
+    /// Create a projection with no focus blocks
+    pub fn new() -> Self {
+        Self {
+            focus: HashMap::new(),
+            max_tokens: None,
+            compression: CompressionMethod::Summarize,
+            id_mapper: None,
+            token_counter: SharedTokenCounter::default(),
+        }
+    }
+
+    /// Focus on every block of a context window, keeping its relevance scores
+    /// and token counter
+    pub fn from_context(window: &ContextWindow) -> Self {
+        let mut projection =
+            Self::new().with_token_counter(window.constraints.token_counter.clone());
+        for (id, block) in &window.blocks {
+            projection.focus.insert(*id, block.relevance_score);
+        }
+        projection
+    }
+
+    /// Add focus blocks with full relevance
+    pub fn with_focus(mut self, ids: impl IntoIterator<Item = BlockId>) -> Self {
+        for id in ids {
+            self.focus.insert(id, 1.0);
+        }
+        self
+    }
+
+    /// Add a focus block with an explicit relevance score
+    pub fn with_focus_block(mut self, id: BlockId, relevance: f32) -> Self {
+        self.focus.insert(id, relevance);
+        self
+    }
+
+    /// Set a hard token cap for the rendered output
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Set how folded subtrees are rendered
+    pub fn with_compression(mut self, method: CompressionMethod) -> Self {
+        self.compression = method;
+        self
+    }
+
+    /// Render short IDs from the given mapper instead of full block IDs
+    pub fn with_id_mapper(mut self, mapper: IdMapper) -> Self {
+        self.id_mapper = Some(mapper);
+        self
+    }
+
+    /// Set the tokenizer used to enforce the token cap
+    pub fn with_token_counter(mut self, counter: SharedTokenCounter) -> Self {
+        self.token_counter = counter;
+        self
+    }
+
+    /// Check whether a short ID mapper is installed
+    pub fn has_id_mapper(&self) -> bool {
+        self.id_mapper.is_some()
+    }
+
+    pub(crate) fn id_mapper(&self) -> Option<&IdMapper> {
+        self.id_mapper.as_ref()
+    }
+
+    /// Render the document
+    pub fn render(&self, doc: &Document) -> String {
+        let ancestors = self.ancestors(doc);
+        let mut lines = Vec::new();
+        self.render_block(doc, &doc.root, 0, &ancestors, &mut lines);
+        self.fit(lines)
+    }
+
+    /// Blocks on the path from the root to any focus block
+    fn ancestors(&self, doc: &Document) -> HashSet<BlockId> {
+        let mut ancestors = HashSet::new();
+        for id in self.focus.keys() {
+            if !doc.blocks.contains_key(id) {
+                continue;
+            }
+            let mut current = doc.parent(id);
+            while let Some(parent) = current {
+                if !ancestors.insert(*parent) {
+                    break;
+                }
+                current = doc.parent(parent);
+            }
+        }
+        ancestors
+    }
+
+    fn render_block(
+        &self,
+        doc: &Document,
+        id: &BlockId,
+        depth: usize,
+        ancestors: &HashSet<BlockId>,
+        lines: &mut Vec<Line>,
+    ) {
+        let Some(block) = doc.get_block(id) else {
+            return;
+        };
+
+        let header = self.header(doc, block, depth);
+        match self.focus.get(id) {
+            Some(&relevance) => lines.push(Line {
+                text: self.full(block, depth),
+                demoted: Some(header),
+                relevance,
+            }),
+            None => lines.push(Line {
+                text: header,
+                demoted: None,
+                relevance: 0.0,
+            }),
+        }
+
+        let mut folded = 0;
+        for child in doc.children(id) {
+            if self.focus.contains_key(child) || ancestors.contains(child) {
+                self.flush_folded(doc, block, depth + 1, &mut folded, lines);
+                self.render_block(doc, child, depth + 1, ancestors, lines);
+                continue;
+            }
+
+            let Some(child_block) = doc.get_block(child) else {
+                continue;
+            };
+            let hidden = doc.descendants(child).len();
+            let summary = child_block.metadata.summary.as_deref();
+            let line = match (&self.compression, summary) {
+                (CompressionMethod::Summarize, Some(summary)) => Some(format!(
+                    "{}[{}] {}: {}",
+                    indent(depth + 1),
+                    self.display_id(child),
+                    role(child_block),
+                    summary
+                )),
+                (CompressionMethod::StructureOnly, _) => {
+                    Some(self.header(doc, child_block, depth + 1))
+                }
+                _ => None,
+            };
+
+            match line {
+                Some(mut text) => {
+                    self.flush_folded(doc, block, depth + 1, &mut folded, lines);
+                    if hidden > 0 {
+                        text.push_str(&format!(" [... {} omitted ...]", blocks(hidden)));
+                    }
+                    lines.push(Line {
+                        text,
+                        demoted: None,
+                        relevance: 0.0,
+                    });
+                }
+                None => folded += hidden + 1,
+            }
+        }
+        self.flush_folded(doc, block, depth + 1, &mut folded, lines);
+    }
+
+    fn flush_folded(
+        &self,
+        doc: &Document,
+        parent: &Block,
+        depth: usize,
+        folded: &mut usize,
+        lines: &mut Vec<Line>,
+    ) {
+        if *folded == 0 {
+            return;
+        }
+        lines.push(Line {
+            text: format!(
+                "{}[... {} omitted under \"{}\" ...]",
+                indent(depth),
+                blocks(*folded),
+                title(doc, parent)
+            ),
+            demoted: None,
+            relevance: 0.0,
+        });
+        *folded = 0;
+    }
+
+    fn header(&self, doc: &Document, block: &Block, depth: usize) -> String {
+        format!(
+            "{}[{}] {}: {}",
+            indent(depth),
+            self.display_id(&block.id),
+            role(block),
+            title(doc, block)
+        )
+    }
+
+    fn full(&self, block: &Block, depth: usize) -> String {
+        let continuation = format!("\n{}  ", indent(depth));
+        format!(
+            "{}[{}] {}: {}",
+            indent(depth),
+            self.display_id(&block.id),
+            role(block),
+            content_to_string(&block.content).replace('\n', &continuation)
+        )
+    }
+
+    fn display_id(&self, id: &BlockId) -> String {
+        self.id_mapper
+            .as_ref()
+            .and_then(|mapper| mapper.to_short_id(id))
+            .map(|short| short.to_string())
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// Enforce the token cap: demote focus blocks, then drop trailing lines
+    fn fit(&self, mut lines: Vec<Line>) -> String {
+        let Some(max_tokens) = self.max_tokens else {
+            return join(&lines);
+        };
+        let count = |text: &str| self.token_counter.count_tokens(text);
+
+        let mut costs: Vec<usize> = lines.iter().map(|line| count(&line.text)).collect();
+        let mut total: usize = costs.iter().sum();
+
+        // Least relevant first; later blocks go first on ties
+        let mut demotable: Vec<usize> = (0..lines.len())
+            .filter(|&i| lines[i].demoted.is_some())
+            .collect();
+        demotable.sort_by(|&a, &b| {
+            lines[a]
+                .relevance
+                .partial_cmp(&lines[b].relevance)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.cmp(&a))
+        });
+        let mut demotable = demotable.into_iter();
+
+        // Cheap pass on per-line estimates, then confirm against the joined
+        // output since counters need not be additive
+        for i in demotable.by_ref() {
+            if total <= max_tokens {
+                break;
+            }
+            total = total - costs[i] + demote(&mut lines[i], &mut costs[i], &count);
+        }
+        loop {
+            let output = join(&lines);
+            if count(&output) <= max_tokens {
+                return output;
+            }
+            match demotable.next() {
+                Some(i) => total = total - costs[i] + demote(&mut lines[i], &mut costs[i], &count),
+                None => break,
+            }
+        }
+
+        let marker = format!("[... truncated to fit {} tokens ...]", max_tokens);
+        let marker_cost = count(&marker);
+        while total + marker_cost > max_tokens {
+            match (lines.pop(), costs.pop()) {
+                (Some(_), Some(cost)) => total -= cost,
+                _ => break,
+            }
+        }
+        // Per-line costs are an estimate; confirm against the joined output
+        loop {
+            let mut output = join(&lines);
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            output.push_str(&marker);
+            if count(&output) <= max_tokens {
+                return output;
+            }
+            if lines.pop().is_none() {
+                return String::new();
+            }
+        }
+    }
+}
+
+impl Default for PromptProjection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Swap a line to its demoted form, returning the new cost
+fn demote(line: &mut Line, cost: &mut usize, count: &impl Fn(&str) -> usize) -> usize {
+    if let Some(demoted) = line.demoted.take() {
+        *cost = count(&demoted);
+        line.text = demoted;
+    }
+    *cost
+}
+
+fn join(lines: &[Line]) -> String {
+    lines
+        .iter()
+        .map(|line| line.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn indent(depth: usize) -> String {
+    "  ".repeat(depth)
+}
+
+fn blocks(count: usize) -> String {
+    if count == 1 {
+        "1 block".to_string()
+    } else {
+        format!("{} blocks", count)
+    }
+}
+
+fn role(block: &Block) -> String {
+    if block.is_root() {
+        return "document".to_string();
+    }
+    block
+        .metadata
+        .semantic_role
+        .as_ref()
+        .map(|role| role.to_string())
+        .unwrap_or_else(|| block.content_type().to_string())
+}
+
+/// Label, or the first line of content, truncated for one-line display
+fn title(doc: &Document, block: &Block) -> String {
+    let text = match &block.metadata.label {
+        Some(label) => label.clone(),
+        None => content_to_string(&block.content)
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+    };
+    let text = if text.is_empty() && block.is_root() {
+        doc.metadata
+            .title
+            .clone()
+            .unwrap_or_else(|| "document".to_string())
+    } else {
+        text
+    };
+
+    if text.chars().count() <= TITLE_CHARS {
+        text
+    } else {
+        let truncated: String = text.chars().take(TITLE_CHARS).collect();
+        format!("{}...", truncated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{ContextConstraints, ContextManager, InclusionReason};
+    use ucm_core::{Content, DocumentId};
+
+    struct Fixture {
+        doc: Document,
+        intro: BlockId,
+        intro_para: BlockId,
+        appendix: BlockId,
+    }
+
+    /// Root -> Intro (2 paragraphs), Appendix (3 paragraphs, one nested list)
+    fn fixture() -> Fixture {
+        let mut doc = Document::new(DocumentId::new("projection"));
+        let root = doc.root;
+
+        let intro = doc
+            .add_block(
+                Block::new(Content::text("Introduction"), Some("heading1")),
+                &root,
+            )
+            .unwrap();
+        let intro_para = doc
+            .add_block(
+                Block::new(
+                    Content::text("The focused paragraph.\nSecond line."),
+                    Some("paragraph"),
+                ),
+                &intro,
+            )
+            .unwrap();
+        doc.add_block(
+            Block::new(Content::text("A sibling paragraph."), Some("paragraph")),
+            &intro,
+        )
+        .unwrap();
+
+        let appendix = doc
+            .add_block(
+                Block::new(Content::text("Appendix"), Some("heading1")),
+                &root,
+            )
+            .unwrap();
+        let first = doc
+            .add_block(
+                Block::new(Content::text("Appendix A"), Some("paragraph")),
+                &appendix,
+            )
+            .unwrap();
+        doc.add_block(
+            Block::new(Content::text("Nested item"), Some("list")),
+            &first,
+        )
+        .unwrap();
+        doc.add_block(
+            Block::new(Content::text("Appendix B"), Some("paragraph")),
+            &appendix,
+        )
+        .unwrap();
+
+        Fixture {
+            doc,
+            intro,
+            intro_para,
+            appendix,
+        }
+    }
+
+    #[test]
+    fn test_focus_ancestors_and_folds() {
+        let f = fixture();
+        let output = PromptProjection::new()
+            .with_focus([f.intro_para])
+            .render(&f.doc);
+
+        // Focus block rendered in full, continuation lines indented
+        assert!(output.contains("The focused paragraph.\n      Second line."));
+        // Ancestor rendered as header
+        assert!(output.contains(&format!("[{}] heading1: Introduction", f.intro)));
+        // Unrelated sibling and the whole appendix are folded
+        assert!(output.contains("[... 1 block omitted under \"Introduction\" ...]"));
+        assert!(output.contains("[... 4 blocks omitted under \"document\" ...]"));
+        assert!(!output.contains("Nested item"));
+    }
+
+    #[test]
+    fn test_summaries_follow_compression_method() {
+        let mut f = fixture();
+        f.doc.get_block_mut(&f.appendix).unwrap().metadata.summary =
+            Some("Reference tables".to_string());
+
+        let summarized = PromptProjection::new()
+            .with_focus([f.intro_para])
+            .render(&f.doc);
+        assert!(summarized.contains(&format!(
+            "[{}] heading1: Reference tables [... 3 blocks omitted ...]",
+            f.appendix
+        )));
+
+        let truncated = PromptProjection::new()
+            .with_focus([f.intro_para])
+            .with_compression(CompressionMethod::Truncate)
+            .render(&f.doc);
+        assert!(!truncated.contains("Reference tables"));
+        assert!(truncated.contains("[... 4 blocks omitted under \"document\" ...]"));
+
+        let structure = PromptProjection::new()
+            .with_focus([f.intro_para])
+            .with_compression(CompressionMethod::StructureOnly)
+            .render(&f.doc);
+        assert!(structure.contains("heading1: Appendix [... 3 blocks omitted ...]"));
+    }
+
+    #[test]
+    fn test_token_cap_demotes_least_relevant_first() {
+        let f = fixture();
+        let uncapped = PromptProjection::new()
+            .with_focus_block(f.intro, 0.1)
+            .with_focus_block(f.intro_para, 0.9);
+        let full = uncapped.render(&f.doc);
+
+        let counter = SharedTokenCounter::default();
+        let cap = counter.count_tokens(&full) - 1;
+        let capped = uncapped.clone().with_max_tokens(cap).render(&f.doc);
+        assert!(counter.count_tokens(&capped) <= cap);
+        // The heading is already a one-liner, so the paragraph must be demoted
+        // for the cap to hold, but the truncation marker is not needed
+        assert!(!capped.contains("Second line."));
+        assert!(!capped.contains("truncated"));
+
+        let tiny = uncapped.with_max_tokens(20).render(&f.doc);
+        assert!(counter.count_tokens(&tiny) <= 20);
+        assert!(tiny.ends_with("[... truncated to fit 20 tokens ...]"));
+    }
+
+    #[test]
+    fn test_from_context_and_short_ids() {
+        let f = fixture();
+        let mut manager = ContextManager::with_constraints("ctx", ContextConstraints::default());
+        manager.add_block(&f.doc, f.intro_para, InclusionReason::DirectReference);
+
+        let mapper = IdMapper::from_document(&f.doc);
+        let short = mapper.to_short_id(&f.intro_para).unwrap();
+        let output = PromptProjection::from_context(manager.window())
+            .with_id_mapper(mapper)
+            .render(&f.doc);
+
+        assert!(output.contains(&format!("[{}] paragraph: The focused paragraph.", short)));
+        assert!(!output.contains("blk_"));
+    }
+}
@@ -2,10 +2,19 @@
 //!
 //! Builds prompts based on specified capabilities so LLMs generate valid UCL.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use ucm_core::Document;
+
+use crate::code_graph::{self, CodeGraphPromptProjectionConfig};
+use crate::context::{HeuristicTokenCounter, TokenCounter};
+use crate::id_mapper::IdMapper;
+use crate::projection::PromptProjection;
 
 /// UCL command capabilities that can be enabled for an agent
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum UclCapability {
     /// EDIT command - modify block content
     Edit,
@@ -62,6 +71,133 @@ impl UclCapability {
             Self::Transaction => TRANSACTION_DOC,
         }
     }
+
+    /// Get the JSON `op` name(s) for this capability
+    pub fn json_ops(&self) -> Vec<&'static str> {
+        match self {
+            Self::Edit => vec!["edit"],
+            Self::Append => vec!["append"],
+            Self::Move => vec!["move"],
+            Self::Delete => vec!["delete"],
+            Self::Link => vec!["link", "unlink"],
+            Self::Snapshot => vec!["snapshot"],
+            Self::Transaction => vec!["begin", "commit", "rollback", "atomic"],
+        }
+    }
+
+    /// Get the JSON schema(s) of the command objects for this capability
+    pub fn json_schemas(&self) -> Vec<serde_json::Value> {
+        use serde_json::json;
+
+        let block_id = json!({ "type": ["string", "integer"] });
+        match self {
+            Self::Edit => vec![command_schema(
+                "edit",
+                json!({
+                    "block": block_id,
+                    "path": { "type": "string", "description": "e.g. \"content.text\" or \"metadata.label\"" },
+                    "value": {},
+                    "operator": { "enum": ["set", "append", "remove", "increment", "decrement"], "default": "set" },
+                }),
+                &["block", "path", "value"],
+            )],
+            Self::Append => vec![command_schema(
+                "append",
+                json!({
+                    "parent": block_id,
+                    "content_type": {
+                        "enum": ["text", "code", "table", "math", "json", "media", "binary", "composite"],
+                        "default": "text",
+                    },
+                    "content": { "type": "string" },
+                    "label": { "type": "string" },
+                    "role": { "type": "string" },
+                    "index": { "type": "integer", "minimum": 0 },
+                }),
+                &["parent", "content"],
+            )],
+            Self::Move => {
+                let mut schema = command_schema(
+                    "move",
+                    json!({
+                        "block": block_id,
+                        "to": block_id,
+                        "index": { "type": "integer", "minimum": 0 },
+                        "before": block_id,
+                        "after": block_id,
+                    }),
+                    &["block"],
+                );
+                schema["description"] = json!("Set exactly one of \"to\", \"before\" or \"after\"");
+                vec![schema]
+            }
+            Self::Delete => vec![command_schema(
+                "delete",
+                json!({
+                    "block": block_id,
+                    "cascade": { "type": "boolean", "default": false },
+                    "preserve_children": { "type": "boolean", "default": false },
+                }),
+                &["block"],
+            )],
+            Self::Link => ["link", "unlink"]
+                .into_iter()
+                .map(|op| {
+                    command_schema(
+                        op,
+                        json!({
+                            "source": block_id,
+                            "edge_type": {
+                                "enum": ["references", "elaborates", "summarizes", "contradicts", "supports", "parent_of"],
+                            },
+                            "target": block_id,
+                        }),
+                        &["source", "edge_type", "target"],
+                    )
+                })
+                .collect(),
+            Self::Snapshot => vec![command_schema(
+                "snapshot",
+                json!({
+                    "action": { "enum": ["create", "restore", "list", "delete", "diff"] },
+                    "name": { "type": "string" },
+                    "description": { "type": "string" },
+                    "other": { "type": "string", "description": "second snapshot for diff" },
+                }),
+                &["action"],
+            )],
+            Self::Transaction => {
+                let mut schemas: Vec<_> = ["begin", "commit", "rollback"]
+                    .into_iter()
+                    .map(|op| command_schema(op, json!({ "name": { "type": "string" } }), &[]))
+                    .collect();
+                schemas.push(command_schema(
+                    "atomic",
+                    json!({ "commands": { "type": "array", "items": { "type": "object" } } }),
+                    &["commands"],
+                ));
+                schemas
+            }
+        }
+    }
+}
+
+/// Schema for one command object: a fixed `op` plus the given properties
+fn command_schema(op: &str, properties: serde_json::Value, required: &[&str]) -> serde_json::Value {
+    let mut props = serde_json::Map::new();
+    props.insert("op".to_string(), serde_json::json!({ "const": op }));
+    if let serde_json::Value::Object(extra) = properties {
+        props.extend(extra);
+    }
+    let mut req = vec!["op"];
+    req.extend_from_slice(required);
+
+    serde_json::json!({
+        "type": "object",
+        "properties": props,
+        "required": req,
+        "additionalProperties": false,
+    })
 }
 
 const EDIT_DOC: &str = r#"### EDIT - Modify block content
@@ -121,6 +257,98 @@ ROLLBACK
 ATOMIC { <commands> }
 ```"#;
 
+/// Output format the prompt asks the model to produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptBuilderMode {
+    /// Raw UCL commands
+    #[default]
+    Ucl,
+    /// A JSON array of command objects (see `ucp_api::parse_json_commands`)
+    JsonCommands,
+    /// Raw UCL commands against a CodeGraph document, which is rendered as
+    /// symbols grouped by file instead of a block tree
+    CodeGraph,
+}
+
+/// A worked example shown to the model for a capability
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    /// Natural-language task the model is asked to perform
+    pub task: String,
+    /// Excerpt of the document the task refers to
+    pub document_excerpt: String,
+    /// UCL the model is expected to produce
+    pub expected_ucl: String,
+}
+
+impl Example {
+    /// Create a new example
+    pub fn new(
+        task: impl Into<String>,
+        document_excerpt: impl Into<String>,
+        expected_ucl: impl Into<String>,
+    ) -> Self {
+        Self {
+            task: task.into(),
+            document_excerpt: document_excerpt.into(),
+            expected_ucl: expected_ucl.into(),
+        }
+    }
+
+    fn render(&self) -> String {
+        format!(
+            "Document:\n{}\nTask: {}\nUCL:\n```\n{}\n```",
+            self.document_excerpt, self.task, self.expected_ucl
+        )
+    }
+}
+
+/// Serializable [`PromptBuilder`] settings, e.g. read from a JSON file
+///
+/// Worked examples are not part of the config; add them to the built
+/// builder with [`PromptBuilder::with_examples`].
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PromptBuilderConfig {
+    /// Enabled capabilities; all of them when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<Vec<UclCapability>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_context: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_context: Option<String>,
+    pub rules: Vec<String>,
+    pub short_ids: bool,
+    /// Token budget for the system prompt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+    pub mode: PromptBuilderMode,
+}
+
+impl PromptBuilderConfig {
+    /// Create a builder with these settings
+    pub fn build(&self) -> PromptBuilder {
+        let mut builder = match &self.capabilities {
+            Some(caps) => PromptBuilder::new().with_capabilities(caps.iter().copied()),
+            None => PromptBuilder::with_all_capabilities(),
+        };
+        if let Some(context) = &self.system_context {
+            builder = builder.with_system_context(context.clone());
+        }
+        if let Some(context) = &self.task_context {
+            builder = builder.with_task_context(context.clone());
+        }
+        for rule in &self.rules {
+            builder = builder.with_rule(rule.clone());
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            builder = builder.with_max_tokens(max_tokens);
+        }
+        builder.with_short_ids(self.short_ids).with_mode(self.mode)
+    }
+}
+
 /// Builder for constructing LLM prompts with specific capabilities
 #[derive(Debug, Clone)]
 pub struct PromptBuilder {
@@ -129,6 +357,10 @@ pub struct PromptBuilder {
     task_context: Option<String>,
     rules: Vec<String>,
     use_short_ids: bool,
+    examples: HashMap<UclCapability, Vec<Example>>,
+    max_tokens: Option<usize>,
+    mode: PromptBuilderMode,
+    code_graph: CodeGraphPromptProjectionConfig,
 }
 
 impl PromptBuilder {
@@ -140,6 +372,10 @@ impl PromptBuilder {
             task_context: None,
             rules: Vec::new(),
             use_short_ids: false,
+            examples: HashMap::new(),
+            max_tokens: None,
+            mode: PromptBuilderMode::default(),
+            code_graph: CodeGraphPromptProjectionConfig::default(),
         }
     }
 
@@ -152,6 +388,15 @@ impl PromptBuilder {
         builder
     }
 
+    /// Create a builder in [`PromptBuilderMode::CodeGraph`] with all
+    /// capabilities, whose task context names the repository `doc` was
+    /// built from
+    pub fn for_code_graph(doc: &Document) -> Self {
+        Self::with_all_capabilities()
+            .with_mode(PromptBuilderMode::CodeGraph)
+            .with_task_context(code_graph::describe_repository(doc))
+    }
+
     /// Add a single capability
     pub fn with_capability(mut self, cap: UclCapability) -> Self {
         self.capabilities.insert(cap);
@@ -194,7 +439,38 @@ impl PromptBuilder {
         self
     }
 
+    /// Add worked examples for a capability.
+    ///
+    /// Examples are listed in priority order: when a token budget is set,
+    /// later examples are dropped before earlier ones.
+    pub fn with_examples(mut self, cap: UclCapability, examples: Vec<Example>) -> Self {
+        self.examples.entry(cap).or_default().extend(examples);
+        self
+    }
+
+    /// Set a token budget for the system prompt (examples are trimmed to fit)
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Set the output format the prompt asks for
+    pub fn with_mode(mut self, mode: PromptBuilderMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the file, symbol and edge budgets used in
+    /// [`PromptBuilderMode::CodeGraph`]
+    pub fn with_code_graph_config(mut self, config: CodeGraphPromptProjectionConfig) -> Self {
+        self.code_graph = config;
+        self
+    }
+
     /// Build the system prompt
+    ///
+    /// In [`PromptBuilderMode::JsonCommands`] the UCL reference is replaced by
+    /// a JSON schema and UCL examples are omitted.
     pub fn build_system_prompt(&self) -> String {
         let mut parts = Vec::new();
 
@@ -205,13 +481,22 @@ impl PromptBuilder {
             parts.push(self.default_system_context());
         }
 
-        // Command reference header
-        parts.push("\n## UCL Command Reference\n".to_string());
-
-        // Add documentation for each enabled capability
-        for cap in &self.capabilities {
-            parts.push(cap.documentation().to_string());
-            parts.push(String::new());
+        match self.mode {
+            PromptBuilderMode::Ucl | PromptBuilderMode::CodeGraph => {
+                // Command reference header
+                parts.push("\n## UCL Command Reference\n".to_string());
+
+                // Add documentation for each enabled capability
+                for cap in &self.capabilities {
+                    parts.push(cap.documentation().to_string());
+                    parts.push(String::new());
+                }
+            }
+            PromptBuilderMode::JsonCommands => {
+                parts.push("\n## JSON Command Schema\n".to_string());
+                parts.push(format!("```json\n{}\n```", self.json_schema_string()));
+                parts.push(String::new());
+            }
         }
 
         // Rules section
@@ -229,7 +514,73 @@ impl PromptBuilder {
             parts.push(format!("{}. {}", offset + i + 1, rule));
         }
 
-        parts.join("\n")
+        let prompt = parts.join("\n");
+        if self.mode == PromptBuilderMode::JsonCommands {
+            return prompt;
+        }
+        match self.render_examples(HeuristicTokenCounter.count_tokens(&prompt)) {
+            Some(examples) => format!("{}\n\n{}", prompt, examples),
+            None => prompt,
+        }
+    }
+
+    /// Render the examples section, trimmed to fit the remaining token budget.
+    ///
+    /// Examples are admitted by rank (first example of every capability, then
+    /// the second, ...) until one does not fit, so the lowest-priority
+    /// examples are dropped first.
+    fn render_examples(&self, used_tokens: usize) -> Option<String> {
+        let enabled: Vec<UclCapability> = UclCapability::all()
+            .into_iter()
+            .filter(|cap| self.capabilities.contains(cap))
+            .collect();
+
+        let mut candidates: Vec<(usize, usize, &Example)> = Vec::new();
+        for (cap_index, cap) in enabled.iter().enumerate() {
+            if let Some(examples) = self.examples.get(cap) {
+                for (rank, example) in examples.iter().enumerate() {
+                    candidates.push((rank, cap_index, example));
+                }
+            }
+        }
+        if candidates.is_empty() {
+            return None;
+        }
+        candidates.sort_by_key(|(rank, cap_index, _)| (*rank, *cap_index));
+
+        let header = "## Examples";
+        let mut budget = self.max_tokens.map(|max| {
+            max.saturating_sub(used_tokens + HeuristicTokenCounter.count_tokens(header))
+        });
+
+        let mut selected: Vec<(usize, usize)> = Vec::new();
+        for (rank, cap_index, example) in &candidates {
+            let cost = HeuristicTokenCounter.count_tokens(&example.render());
+            if let Some(remaining) = budget.as_mut() {
+                if cost > *remaining {
+                    break;
+                }
+                *remaining -= cost;
+            }
+            selected.push((*cap_index, *rank));
+        }
+        if selected.is_empty() {
+            return None;
+        }
+        selected.sort();
+
+        let mut parts = vec![header.to_string()];
+        let mut current_cap = None;
+        for (cap_index, rank) in selected {
+            let cap = enabled[cap_index];
+            if current_cap != Some(cap_index) {
+                parts.push(format!("\n### {}", cap.command_names().join("/")));
+                current_cap = Some(cap_index);
+            }
+            parts.push(self.examples[&cap][rank].render());
+        }
+
+        Some(parts.join("\n"))
     }
 
     /// Build a complete prompt with document context
@@ -250,12 +601,82 @@ impl PromptBuilder {
         parts.push(task.to_string());
 
         // Instruction
-        parts.push("\nGenerate the UCL command:".to_string());
+        parts.push(match self.mode {
+            PromptBuilderMode::Ucl | PromptBuilderMode::CodeGraph => {
+                "\nGenerate the UCL command:".to_string()
+            }
+            PromptBuilderMode::JsonCommands => "\nGenerate the JSON commands:".to_string(),
+        });
 
         parts.join("\n")
     }
 
+    /// Build a complete prompt, rendering the document through a projection
+    pub fn build_projected_prompt(
+        &self,
+        doc: &Document,
+        projection: &PromptProjection,
+        task: &str,
+    ) -> String {
+        self.build_prompt(&self.describe_document(doc, projection), task)
+    }
+
+    /// Render the document the way [`Self::build_projected_prompt`] shows it
+    ///
+    /// Short IDs are used when enabled on the builder and the projection has
+    /// no mapper of its own. In [`PromptBuilderMode::CodeGraph`] the document
+    /// is rendered symbol by symbol and only the projection's mapper is used.
+    pub fn describe_document(&self, doc: &Document, projection: &PromptProjection) -> String {
+        let short_ids = self.use_short_ids && !projection.has_id_mapper();
+        if self.mode == PromptBuilderMode::CodeGraph {
+            let mapper = short_ids.then(|| IdMapper::from_document(doc));
+            let mapper = mapper.as_ref().or(projection.id_mapper());
+            return code_graph::render(doc, &self.code_graph, mapper);
+        }
+        if short_ids {
+            projection
+                .clone()
+                .with_id_mapper(IdMapper::from_document(doc))
+                .render(doc)
+        } else {
+            projection.render(doc)
+        }
+    }
+
+    /// JSON schema describing the command objects for the enabled capabilities
+    pub fn json_schema(&self) -> serde_json::Value {
+        let variants: Vec<serde_json::Value> = UclCapability::all()
+            .into_iter()
+            .filter(|cap| self.capabilities.contains(cap))
+            .flat_map(|cap| cap.json_schemas())
+            .collect();
+
+        serde_json::json!({
+            "type": "array",
+            "items": { "oneOf": variants },
+        })
+    }
+
+    fn json_schema_string(&self) -> String {
+        serde_json::to_string_pretty(&self.json_schema()).unwrap_or_default()
+    }
+
     fn default_system_context(&self) -> String {
+        if self.mode == PromptBuilderMode::JsonCommands {
+            let ops: Vec<_> = UclCapability::all()
+                .into_iter()
+                .filter(|cap| self.capabilities.contains(cap))
+                .flat_map(|c| c.json_ops())
+                .collect();
+
+            return format!(
+                "You are a document command generator. \
+                Your task is to generate JSON commands to manipulate documents.\n\n\
+                Available ops: {}",
+                ops.join(", ")
+            );
+        }
+
         let caps: Vec<_> = self
             .capabilities
             .iter()
@@ -271,6 +692,21 @@ impl PromptBuilder {
     }
 
     fn default_rules(&self) -> Vec<&'static str> {
+        if self.mode == PromptBuilderMode::JsonCommands {
+            let mut rules = vec![
+                "Output ONLY a JSON array of command objects, no explanations or markdown",
+                "Every command object must have an \"op\" field and only the fields in the schema",
+                "Use exact block IDs as provided",
+            ];
+            if self.use_short_ids {
+                rules.push("Block IDs are short numeric IDs (1, 2, 3, etc.)");
+            } else {
+                rules.push("Block IDs have format: blk_XXXXXXXXXXXX (12 hex chars)");
+            }
+            rules.push("To extend text, use \"operator\": \"append\" instead of repeating it");
+            return rules;
+        }
+
         let mut rules = vec![
             "Output ONLY the UCL command(s), no explanations or markdown",
             "Use exact block IDs as provided",
@@ -295,6 +731,16 @@ impl PromptBuilder {
         self.capabilities.iter()
     }
 
+    /// Get the output mode
+    pub fn mode(&self) -> PromptBuilderMode {
+        self.mode
+    }
+
+    /// Check if short ID mode is enabled
+    pub fn uses_short_ids(&self) -> bool {
+        self.use_short_ids
+    }
+
     /// Check if a capability is enabled
     pub fn has_capability(&self, cap: UclCapability) -> bool {
         self.capabilities.contains(&cap)
@@ -347,6 +793,98 @@ pub mod presets {
     pub fn token_efficient() -> PromptBuilder {
         PromptBuilder::with_all_capabilities().with_short_ids(true)
     }
+
+    /// All capabilities with the curated few-shot examples
+    pub fn few_shot() -> PromptBuilder {
+        UclCapability::all()
+            .into_iter()
+            .fold(PromptBuilder::with_all_capabilities(), |builder, cap| {
+                builder.with_examples(cap, default_examples(cap))
+            })
+    }
+
+    /// Curated examples for a capability, highest priority first
+    pub fn default_examples(cap: UclCapability) -> Vec<Example> {
+        match cap {
+            UclCapability::Edit => vec![
+                Example::new(
+                    "Change the introduction to say 'Welcome to the guide'",
+                    "[blk_000000000002] paragraph: Introduction text",
+                    r#"EDIT blk_000000000002 SET text = "Welcome to the guide""#,
+                ),
+                Example::new(
+                    "Add ' (draft)' to the end of the title",
+                    "[blk_000000000001] heading1: Release Notes",
+                    r#"EDIT blk_000000000001 SET text += " (draft)""#,
+                ),
+            ],
+            UclCapability::Append => vec![
+                Example::new(
+                    "Add a paragraph about installation under the Setup section",
+                    "[blk_000000000003] heading2: Setup",
+                    r#"APPEND blk_000000000003 text :: "Install the package with pip.""#,
+                ),
+                Example::new(
+                    "Insert a code sample labelled 'example' as the first child of Usage",
+                    "[blk_000000000004] heading2: Usage",
+                    r#"APPEND blk_000000000004 code WITH label = "example" AT 0 :: "print('hi')""#,
+                ),
+            ],
+            UclCapability::Move => vec![
+                Example::new(
+                    "Move the FAQ section under the Appendix",
+                    "[blk_000000000005] heading2: FAQ\n[blk_000000000006] heading1: Appendix",
+                    "MOVE blk_000000000005 TO blk_000000000006",
+                ),
+                Example::new(
+                    "Place the summary before the introduction",
+                    "[blk_000000000002] paragraph: Introduction\n[blk_000000000007] paragraph: Summary",
+                    "MOVE blk_000000000007 BEFORE blk_000000000002",
+                ),
+            ],
+            UclCapability::Delete => vec![
+                Example::new(
+                    "Remove the outdated section and everything under it",
+                    "[blk_000000000008] heading2: Legacy API",
+                    "DELETE blk_000000000008 CASCADE",
+                ),
+                Example::new(
+                    "Delete the wrapper section but keep its contents",
+                    "[blk_000000000009] heading2: Misc",
+                    "DELETE blk_000000000009 PRESERVE_CHILDREN",
+                ),
+            ],
+            UclCapability::Link => vec![
+                Example::new(
+                    "Mark that the conclusion summarizes the results",
+                    "[blk_00000000000a] paragraph: Conclusion\n[blk_00000000000b] paragraph: Results",
+                    "LINK blk_00000000000a summarizes blk_00000000000b",
+                ),
+                Example::new(
+                    "Remove the reference from the intro to the appendix",
+                    "[blk_000000000002] paragraph: Introduction\n[blk_000000000006] heading1: Appendix",
+                    "UNLINK blk_000000000002 references blk_000000000006",
+                ),
+            ],
+            UclCapability::Snapshot => vec![
+                Example::new(
+                    "Save the current state before the rewrite",
+                    "(whole document)",
+                    r#"SNAPSHOT CREATE "before-rewrite" WITH description = "State before rewrite""#,
+                ),
+                Example::new(
+                    "Go back to the 'v1' version",
+                    "(whole document)",
+                    r#"SNAPSHOT RESTORE "v1""#,
+                ),
+            ],
+            UclCapability::Transaction => vec![Example::new(
+                "Rename the title and delete the draft note together",
+                "[blk_000000000001] heading1: Draft\n[blk_00000000000c] paragraph: TODO",
+                "ATOMIC {\n  EDIT blk_000000000001 SET text = \"Final\"\n  DELETE blk_00000000000c\n}",
+            )],
+        }
+    }
 }
 
 #[cfg(test)]
@@ -409,6 +947,185 @@ mod tests {
         assert!(!full.has_capability(UclCapability::Transaction));
     }
 
+    #[test]
+    fn test_prompt_builder_examples() {
+        let builder = PromptBuilder::new()
+            .with_capability(UclCapability::Edit)
+            .with_examples(
+                UclCapability::Edit,
+                vec![Example::new(
+                    "Rename the title",
+                    "[blk_000000000001] heading1: Old",
+                    r#"EDIT blk_000000000001 SET text = "New""#,
+                )],
+            )
+            .with_examples(
+                UclCapability::Move,
+                vec![Example::new("Move it", "(doc)", "MOVE a TO b")],
+            );
+
+        let prompt = builder.build_system_prompt();
+        assert!(prompt.contains("## Examples"));
+        assert!(prompt.contains("Rename the title"));
+        // Examples for disabled capabilities are not rendered
+        assert!(!prompt.contains("MOVE a TO b"));
+    }
+
+    #[test]
+    fn test_prompt_builder_examples_respect_token_budget() {
+        let unbounded = presets::few_shot().build_system_prompt();
+        let base_tokens = HeuristicTokenCounter
+            .count_tokens(&PromptBuilder::with_all_capabilities().build_system_prompt());
+
+        let bounded = presets::few_shot()
+            .with_max_tokens(base_tokens + 120)
+            .build_system_prompt();
+
+        assert!(bounded.len() < unbounded.len());
+        // Highest-priority examples survive, lowest-priority are dropped first
+        assert!(bounded.contains("Welcome to the guide"));
+        assert!(!bounded.contains("(draft)"));
+
+        let none = presets::few_shot()
+            .with_max_tokens(base_tokens)
+            .build_system_prompt();
+        assert!(!none.contains("## Examples"));
+    }
+
+    #[test]
+    fn test_prompt_builder_examples_stop_at_first_that_does_not_fit() {
+        let base = PromptBuilder::new().with_capability(UclCapability::Edit);
+        let base_tokens = HeuristicTokenCounter.count_tokens(&base.build_system_prompt());
+        let prompt = base
+            .with_examples(
+                UclCapability::Edit,
+                vec![
+                    Example::new("Short first", "a", "EDIT a SET x = 1"),
+                    Example::new("Long second", "b".repeat(400), "EDIT b SET x = 2"),
+                    Example::new("Short third", "c", "EDIT c SET x = 3"),
+                ],
+            )
+            .with_max_tokens(base_tokens + 60)
+            .build_system_prompt();
+
+        assert!(prompt.contains("Short first"));
+        assert!(!prompt.contains("Long second"));
+        // A lower-priority example is not admitted after a higher one was dropped
+        assert!(!prompt.contains("Short third"));
+    }
+
+    #[test]
+    fn test_json_commands_mode() {
+        let builder = presets::few_shot()
+            .without_capability(UclCapability::Snapshot)
+            .with_mode(PromptBuilderMode::JsonCommands);
+
+        let prompt = builder.build_system_prompt();
+        assert!(prompt.contains("## JSON Command Schema"));
+        assert!(prompt.contains("\"const\": \"edit\""));
+        assert!(!prompt.contains("\"const\": \"snapshot\""));
+        assert!(!prompt.contains("## UCL Command Reference"));
+        // UCL examples would contradict the requested output format
+        assert!(!prompt.contains("## Examples"));
+
+        let schema = builder.json_schema();
+        assert_eq!(schema["type"], "array");
+        assert!(schema["items"]["oneOf"].as_array().unwrap().len() >= 6);
+
+        let prompt = builder.build_prompt("[1] Title", "Rename the title");
+        assert!(prompt.ends_with("Generate the JSON commands:"));
+    }
+
+    #[test]
+    fn test_code_graph_mode() {
+        use ucm_core::{Block, Content, EdgeType};
+
+        fn node(class: &str, key: &str, content: serde_json::Value) -> Block {
+            let mut block = Block::new(Content::json(content), None);
+            block
+                .metadata
+                .custom
+                .insert("node_class".into(), class.into());
+            block
+                .metadata
+                .custom
+                .insert("logical_key".into(), key.into());
+            block
+        }
+
+        let mut doc = Document::create();
+        let root = doc.root;
+        let repo = doc
+            .add_block(
+                node("repository", "repo", serde_json::json!({"name": "demo"})),
+                &root,
+            )
+            .unwrap();
+        let dir = doc
+            .add_block(node("directory", "dir:src", serde_json::json!({})), &repo)
+            .unwrap();
+        let file = doc
+            .add_block(
+                node(
+                    "file",
+                    "file:src/lib.rs",
+                    serde_json::json!({"coderef": {"display": "src/lib.rs"}}),
+                ),
+                &dir,
+            )
+            .unwrap();
+        let base = doc
+            .add_block(
+                node(
+                    "symbol",
+                    "symbol:Base",
+                    serde_json::json!({"kind": "class", "name": "Base"}),
+                ),
+                &file,
+            )
+            .unwrap();
+        let docs = (1..=7).map(|i| format!("line {}", i)).collect::<Vec<_>>();
+        let child = doc
+            .add_block(
+                node(
+                    "symbol",
+                    "symbol:Child",
+                    serde_json::json!({
+                        "kind": "class",
+                        "name": "Child",
+                        "description": docs.join("\n"),
+                    }),
+                ),
+                &file,
+            )
+            .unwrap();
+        doc.add_edge(&child, EdgeType::Custom("extends".into()), base);
+        doc.add_edge(&child, EdgeType::Custom("imports_symbol".into()), base);
+
+        let builder = PromptBuilder::for_code_graph(&doc);
+        assert_eq!(builder.mode(), PromptBuilderMode::CodeGraph);
+        assert!(builder
+            .build_system_prompt()
+            .contains("## UCL Command Reference"));
+
+        let rendered = builder.describe_document(&doc, &PromptProjection::new());
+        assert!(rendered.contains(&format!("[{}] file src/lib.rs [unknown]", file)));
+        assert!(rendered.contains(&format!("[{}] class Child | extends symbol:Base", child)));
+        assert!(!rendered.contains("imports_symbol"));
+        assert!(!rendered.contains("dir:src"));
+        assert!(rendered.contains("line 5\n      …"));
+        assert!(!rendered.contains("line 6"));
+
+        let prompt = builder.build_projected_prompt(&doc, &PromptProjection::new(), "Rename Base");
+        assert!(prompt.contains("CodeGraph of demo with 1 files and 2 symbols"));
+        assert!(prompt.ends_with("Generate the UCL command:"));
+
+        let short = builder
+            .with_short_ids(true)
+            .describe_document(&doc, &PromptProjection::new());
+        assert!(!short.contains("blk_"));
+    }
+
     #[test]
     fn test_build_complete_prompt() {
         let builder = presets::basic_editing();
@@ -423,4 +1140,24 @@ mod tests {
         assert!(prompt.contains("Task"));
         assert!(prompt.contains(task));
     }
+
+    #[test]
+    fn test_config_from_json_builds_builder() {
+        let config: PromptBuilderConfig = serde_json::from_str(
+            r#"{"capabilities": ["edit", "append"], "rules": ["Be brief"], "mode": "json_commands"}"#,
+        )
+        .unwrap();
+        let builder = config.build();
+        assert!(builder.has_capability(UclCapability::Edit));
+        assert!(builder.has_capability(UclCapability::Append));
+        assert!(!builder.has_capability(UclCapability::Delete));
+        assert_eq!(builder.mode(), PromptBuilderMode::JsonCommands);
+        assert!(builder.build_system_prompt().contains("Be brief"));
+
+        let builder = PromptBuilderConfig::default().build();
+        assert!(UclCapability::all()
+            .into_iter()
+            .all(|cap| builder.has_capability(cap)));
+        assert!(!builder.uses_short_ids());
+    }
 }
@@ -5,12 +5,20 @@
 //! - Event bus for subscribing to engine events
 //! - Audit logging
 //! - Metrics recording
+//! - Live document sync for connected clients
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock};
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
+pub mod sync;
+
+pub use sync::{
+    DocumentSyncHandler, SyncClient, SyncEvent, SyncReply, SyncRequest,
+    DEFAULT_MAX_EVENTS_PER_SECOND, DEFAULT_SYNC_BUFFER,
+};
+
 // =============================================================================
 // EVENT TYPES
 // =============================================================================
@@ -0,0 +1,469 @@
+//! Live document sync.
+//!
+//! [`DocumentSyncHandler`] fans block events out to subscribed clients, one
+//! broadcast channel per document. It is transport-agnostic: a WebSocket
+//! handler forwards incoming text to [`SyncClient::handle_message`] and sends
+//! whatever [`SyncClient::poll`] returns.
+//!
+//! ```json
+//! → {"type": "subscribe", "document_id": "doc_1"}
+//! ← {"type": "block_updated", "document_id": "doc_1", "block_id": "blk_…", ...}
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::TryRecvError};
+
+use crate::{EventBus, UcpEvent};
+
+/// Events buffered per document before slow clients start lagging
+pub const DEFAULT_SYNC_BUFFER: usize = 256;
+
+/// Events delivered per client per second
+pub const DEFAULT_MAX_EVENTS_PER_SECOND: u32 = 100;
+
+/// Block change pushed to clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SyncEvent {
+    BlockCreated {
+        document_id: String,
+        block_id: String,
+        parent_id: String,
+        content_type: String,
+        timestamp: DateTime<Utc>,
+    },
+    /// Content or metadata edit (`path`), or a move (`path` is `"parent"`)
+    BlockUpdated {
+        document_id: String,
+        block_id: String,
+        path: String,
+        timestamp: DateTime<Utc>,
+    },
+    BlockDeleted {
+        document_id: String,
+        block_id: String,
+        cascade: bool,
+        timestamp: DateTime<Utc>,
+    },
+    /// The client fell behind and missed events; it should reload the document
+    Lagged { document_id: String, skipped: u64 },
+}
+
+impl SyncEvent {
+    /// Convert an engine event; non-block events are not synced
+    pub fn from_event(event: &UcpEvent) -> Option<Self> {
+        Some(match event.clone() {
+            UcpEvent::BlockAdded {
+                document_id,
+                block_id,
+                parent_id,
+                content_type,
+                timestamp,
+            } => Self::BlockCreated {
+                document_id,
+                block_id,
+                parent_id,
+                content_type,
+                timestamp,
+            },
+            UcpEvent::BlockEdited {
+                document_id,
+                block_id,
+                path,
+                timestamp,
+            } => Self::BlockUpdated {
+                document_id,
+                block_id,
+                path,
+                timestamp,
+            },
+            UcpEvent::BlockMoved {
+                document_id,
+                block_id,
+                timestamp,
+                ..
+            } => Self::BlockUpdated {
+                document_id,
+                block_id,
+                path: "parent".to_string(),
+                timestamp,
+            },
+            UcpEvent::BlockDeleted {
+                document_id,
+                block_id,
+                cascade,
+                timestamp,
+            } => Self::BlockDeleted {
+                document_id,
+                block_id,
+                cascade,
+                timestamp,
+            },
+            _ => return None,
+        })
+    }
+
+    pub fn document_id(&self) -> &str {
+        match self {
+            Self::BlockCreated { document_id, .. }
+            | Self::BlockUpdated { document_id, .. }
+            | Self::BlockDeleted { document_id, .. }
+            | Self::Lagged { document_id, .. } => document_id,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Message sent by a client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SyncRequest {
+    Subscribe { document_id: String },
+    Unsubscribe { document_id: String },
+}
+
+/// Reply to a [`SyncRequest`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SyncReply {
+    Subscribed { document_id: String },
+    Unsubscribed { document_id: String },
+    Error { message: String },
+}
+
+impl SyncReply {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Routes block events to per-document broadcast channels
+#[derive(Clone)]
+pub struct DocumentSyncHandler {
+    channels: Arc<RwLock<HashMap<String, broadcast::Sender<SyncEvent>>>>,
+    buffer: usize,
+    max_events_per_second: u32,
+}
+
+impl DocumentSyncHandler {
+    pub fn new() -> Self {
+        Self {
+            channels: Arc::new(RwLock::new(HashMap::new())),
+            buffer: DEFAULT_SYNC_BUFFER,
+            max_events_per_second: DEFAULT_MAX_EVENTS_PER_SECOND,
+        }
+    }
+
+    /// Set the per-document buffer (applies to channels created afterwards)
+    pub fn with_buffer(mut self, buffer: usize) -> Self {
+        self.buffer = buffer.max(1);
+        self
+    }
+
+    /// Set the per-client delivery rate
+    pub fn with_rate_limit(mut self, max_events_per_second: u32) -> Self {
+        self.max_events_per_second = max_events_per_second.max(1);
+        self
+    }
+
+    /// Forward every block event published on `bus`
+    pub fn attach(&self, bus: &EventBus) {
+        let handler = self.clone();
+        bus.subscribe(Arc::new(move |event| {
+            handler.publish(event);
+        }));
+    }
+
+    /// Push an event to the document's subscribers. Returns how many received it.
+    pub fn publish(&self, event: &UcpEvent) -> usize {
+        let Some(event) = SyncEvent::from_event(event) else {
+            return 0;
+        };
+        let channels = self.channels.read().unwrap();
+        channels
+            .get(event.document_id())
+            .and_then(|tx| tx.send(event).ok())
+            .unwrap_or(0)
+    }
+
+    /// Number of clients subscribed to a document
+    pub fn subscriber_count(&self, document_id: &str) -> usize {
+        self.channels
+            .read()
+            .unwrap()
+            .get(document_id)
+            .map_or(0, |tx| tx.receiver_count())
+    }
+
+    /// Create a client with no subscriptions
+    pub fn client(&self) -> SyncClient {
+        SyncClient {
+            handler: self.clone(),
+            receivers: HashMap::new(),
+            limiter: RateLimiter::new(self.max_events_per_second),
+        }
+    }
+
+    fn receiver(&self, document_id: &str) -> broadcast::Receiver<SyncEvent> {
+        let mut channels = self.channels.write().unwrap();
+        // Drop channels whose subscribers have all gone away
+        channels.retain(|_, tx| tx.receiver_count() > 0);
+        channels
+            .entry(document_id.to_string())
+            .or_insert_with(|| broadcast::channel(self.buffer).0)
+            .subscribe()
+    }
+}
+
+impl Default for DocumentSyncHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for DocumentSyncHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DocumentSyncHandler")
+            .field("documents", &self.channels.read().unwrap().len())
+            .field("buffer", &self.buffer)
+            .field("max_events_per_second", &self.max_events_per_second)
+            .finish()
+    }
+}
+
+/// One connected client
+pub struct SyncClient {
+    handler: DocumentSyncHandler,
+    receivers: HashMap<String, broadcast::Receiver<SyncEvent>>,
+    limiter: RateLimiter,
+}
+
+impl SyncClient {
+    /// Handle a JSON message from the client and return the JSON reply
+    pub fn handle_message(&mut self, text: &str) -> String {
+        let reply = match serde_json::from_str::<SyncRequest>(text) {
+            Ok(SyncRequest::Subscribe { document_id }) => {
+                self.subscribe(&document_id);
+                SyncReply::Subscribed { document_id }
+            }
+            Ok(SyncRequest::Unsubscribe { document_id }) => {
+                self.unsubscribe(&document_id);
+                SyncReply::Unsubscribed { document_id }
+            }
+            Err(e) => SyncReply::Error {
+                message: format!("Invalid sync message: {}", e),
+            },
+        };
+        reply.to_json()
+    }
+
+    pub fn subscribe(&mut self, document_id: &str) {
+        if !self.receivers.contains_key(document_id) {
+            let rx = self.handler.receiver(document_id);
+            self.receivers.insert(document_id.to_string(), rx);
+        }
+    }
+
+    pub fn unsubscribe(&mut self, document_id: &str) {
+        self.receivers.remove(document_id);
+    }
+
+    /// Subscribed document ids, sorted
+    pub fn subscriptions(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.receivers.keys().map(String::as_str).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Take pending events, up to the rate limit.
+    ///
+    /// Events over the limit stay queued for a later poll; if the queue
+    /// overflows, a [`SyncEvent::Lagged`] event is returned instead.
+    pub fn poll(&mut self) -> Vec<SyncEvent> {
+        self.poll_at(Instant::now())
+    }
+
+    fn poll_at(&mut self, now: Instant) -> Vec<SyncEvent> {
+        let mut events = Vec::new();
+        let mut ids: Vec<String> = self.receivers.keys().cloned().collect();
+        ids.sort();
+
+        for id in ids {
+            let rx = self.receivers.get_mut(&id).expect("subscribed");
+            while self.limiter.has_capacity(now) {
+                match rx.try_recv() {
+                    Ok(event) => {
+                        self.limiter.take();
+                        events.push(event);
+                    }
+                    Err(TryRecvError::Lagged(skipped)) => {
+                        self.limiter.take();
+                        events.push(SyncEvent::Lagged {
+                            document_id: id.clone(),
+                            skipped,
+                        });
+                    }
+                    Err(TryRecvError::Empty) | Err(TryRecvError::Closed) => break,
+                }
+            }
+        }
+        events
+    }
+}
+
+/// Fixed one-second window counter
+struct RateLimiter {
+    max_per_second: u32,
+    window_start: Option<Instant>,
+    used: u32,
+}
+
+impl RateLimiter {
+    fn new(max_per_second: u32) -> Self {
+        Self {
+            max_per_second,
+            window_start: None,
+            used: 0,
+        }
+    }
+
+    fn has_capacity(&mut self, now: Instant) -> bool {
+        let expired = match self.window_start {
+            Some(start) => now.duration_since(start) >= Duration::from_secs(1),
+            None => true,
+        };
+        if expired {
+            self.window_start = Some(now);
+            self.used = 0;
+        }
+        self.used < self.max_per_second
+    }
+
+    fn take(&mut self) {
+        self.used += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edited(document_id: &str, block_id: &str) -> UcpEvent {
+        UcpEvent::BlockEdited {
+            document_id: document_id.into(),
+            block_id: block_id.into(),
+            path: "content.text".into(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_subscribe_and_receive() {
+        let handler = DocumentSyncHandler::new();
+        let mut client = handler.client();
+
+        let reply = client.handle_message(r#"{"type":"subscribe","document_id":"doc_1"}"#);
+        assert_eq!(reply, r#"{"type":"subscribed","document_id":"doc_1"}"#);
+        assert_eq!(handler.subscriber_count("doc_1"), 1);
+
+        assert_eq!(handler.publish(&edited("doc_1", "blk_1")), 1);
+        assert_eq!(handler.publish(&edited("doc_2", "blk_2")), 0);
+
+        let events = client.poll();
+        assert_eq!(events.len(), 1);
+        let json: serde_json::Value = serde_json::from_str(&events[0].to_json()).unwrap();
+        assert_eq!(json["type"], "block_updated");
+        assert_eq!(json["block_id"], "blk_1");
+        assert_eq!(json["path"], "content.text");
+        assert!(client.poll().is_empty());
+
+        client.handle_message(r#"{"type":"unsubscribe","document_id":"doc_1"}"#);
+        assert_eq!(handler.subscriber_count("doc_1"), 0);
+    }
+
+    #[test]
+    fn test_event_mapping() {
+        let moved = UcpEvent::BlockMoved {
+            document_id: "d".into(),
+            block_id: "b".into(),
+            old_parent: None,
+            new_parent: "p".into(),
+            timestamp: Utc::now(),
+        };
+        assert!(matches!(
+            SyncEvent::from_event(&moved),
+            Some(SyncEvent::BlockUpdated { path, .. }) if path == "parent"
+        ));
+        let created = UcpEvent::DocumentCreated {
+            document_id: "d".into(),
+            timestamp: Utc::now(),
+        };
+        assert!(SyncEvent::from_event(&created).is_none());
+    }
+
+    #[test]
+    fn test_invalid_message() {
+        let mut client = DocumentSyncHandler::new().client();
+        let reply: serde_json::Value =
+            serde_json::from_str(&client.handle_message(r#"{"type":"watch"}"#)).unwrap();
+        assert_eq!(reply["type"], "error");
+        assert!(client.subscriptions().is_empty());
+    }
+
+    #[test]
+    fn test_rate_limit_defers_events() {
+        let handler = DocumentSyncHandler::new().with_rate_limit(3);
+        let mut client = handler.client();
+        client.subscribe("doc");
+        for i in 0..5 {
+            handler.publish(&edited("doc", &format!("blk_{}", i)));
+        }
+
+        let start = Instant::now();
+        assert_eq!(client.poll_at(start).len(), 3);
+        assert!(client
+            .poll_at(start + Duration::from_millis(500))
+            .is_empty());
+        assert_eq!(client.poll_at(start + Duration::from_secs(1)).len(), 2);
+    }
+
+    #[test]
+    fn test_slow_client_lags() {
+        let handler = DocumentSyncHandler::new().with_buffer(2);
+        let mut client = handler.client();
+        client.subscribe("doc");
+        for i in 0..5 {
+            handler.publish(&edited("doc", &format!("blk_{}", i)));
+        }
+
+        let events = client.poll();
+        assert_eq!(
+            events[0],
+            SyncEvent::Lagged {
+                document_id: "doc".into(),
+                skipped: 3
+            }
+        );
+        assert_eq!(events.len(), 3);
+    }
+
+    #[test]
+    fn test_attach_to_event_bus() {
+        let bus = EventBus::new();
+        let handler = DocumentSyncHandler::new();
+        handler.attach(&bus);
+        let mut client = handler.client();
+        client.subscribe("doc");
+
+        bus.publish(&edited("doc", "blk_1"));
+        assert_eq!(client.poll().len(), 1);
+    }
+}
@@ -25,6 +25,7 @@ from ucp._core import (
     # Engine and validation
     Engine,
     EngineConfig,
+    Transaction,
     TransactionId,
     ResourceLimits,
     ValidationPipeline,
@@ -55,6 +56,7 @@ from ucp._core import (
     # LLM utilities
     IdMapper,
     PromptBuilder,
+    PromptBuilderMode,
     PromptPresets,
     UclCapability,
     # Snapshot management
@@ -70,6 +72,7 @@ from ucp._core import (
     render,
     parse_html,
     execute_ucl,
+    execute_ucl_with_mapper,
     create,
     # Section functions
     clear_section_with_undo,
@@ -126,6 +129,7 @@ __all__ = [
     # Engine and validation
     "Engine",
     "EngineConfig",
+    "Transaction",
     "TransactionId",
     "ResourceLimits",
     "ValidationPipeline",
@@ -156,6 +160,7 @@ __all__ = [
     # LLM utilities
     "IdMapper",
     "PromptBuilder",
+    "PromptBuilderMode",
     "PromptPresets",
     "UclCapability",
     # Snapshot management
@@ -171,6 +176,7 @@ __all__ = [
     "render",
     "parse_html",
     "execute_ucl",
+    "execute_ucl_with_mapper",
     "create",
     # Section functions
     "clear_section_with_undo",
@@ -0,0 +1,1298 @@
+"""Type stubs for the native ``ucp._core`` extension module."""
+
+from types import TracebackType
+from collections.abc import Awaitable, Callable
+from datetime import datetime
+from typing import Any, ClassVar
+
+class UcpError(Exception):
+    """Base UCP error"""
+
+class BlockNotFoundError(UcpError):
+    """Block not found"""
+
+class InvalidBlockIdError(UcpError):
+    """Invalid block ID format"""
+
+class CycleDetectedError(UcpError):
+    """Cycle detected in document structure"""
+
+class ValidationError(UcpError):
+    """Validation error"""
+
+class ParseError(UcpError):
+    """Parse error"""
+
+class AgentSessionId:
+    """Agent session ID wrapper."""
+    def __repr__(self) -> str: ...
+    def __str__(self) -> str: ...
+    def __hash__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+
+
+class ViewMode:
+    """View mode for block content display."""
+    @staticmethod
+    def full() -> ViewMode:
+        """Create a Full view mode (shows complete content)."""
+    @staticmethod
+    def preview(length: int = 100) -> ViewMode:
+        """Create a Preview view mode (shows first N characters)."""
+    @staticmethod
+    def ids_only() -> ViewMode:
+        """Create an IdsOnly view mode (just block IDs)."""
+    @staticmethod
+    def metadata() -> ViewMode:
+        """Create a Metadata view mode (role, tags, edge counts)."""
+    @staticmethod
+    def adaptive(threshold: float = 0.5) -> ViewMode:
+        """Create an Adaptive view mode (auto-select based on relevance)."""
+    def __repr__(self) -> str: ...
+
+
+class AgentCapabilities:
+    """Agent capabilities configuration."""
+    def __init__(self) -> None:
+        """Create capabilities with all permissions (default)."""
+    @staticmethod
+    def read_only() -> AgentCapabilities:
+        """Create read-only capabilities (traverse only, no context modification)."""
+    @property
+    def can_traverse(self) -> bool: ...
+    @property
+    def can_search(self) -> bool: ...
+    @property
+    def can_modify_context(self) -> bool: ...
+    @property
+    def can_coordinate(self) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class SessionConfig:
+    """Session configuration for creating new agent sessions."""
+    def __init__(self, name: str | None = None, start_block: BlockId | None = None) -> None: ...
+    def with_name(self, name: str) -> SessionConfig:
+        """Set session name."""
+    def with_view_mode(self, mode: ViewMode) -> SessionConfig:
+        """Set initial view mode."""
+    def with_capabilities(self, caps: AgentCapabilities) -> SessionConfig:
+        """Set agent capabilities."""
+    def with_filter_expired_edges(self, filter: bool) -> SessionConfig:
+        """Skip expired edges during semantic expansion."""
+    def __repr__(self) -> str: ...
+
+
+class NavigationResult:
+    """Navigation result from a GOTO or BACK operation."""
+    @property
+    def position(self) -> BlockId: ...
+    @property
+    def refreshed(self) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class ExpansionResult:
+    """Expansion result from an EXPAND operation."""
+    @property
+    def root(self) -> BlockId: ...
+    @property
+    def levels(self) -> list[list[BlockId]]: ...
+    @property
+    def total_blocks(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+
+class BlockView:
+    """Block view result from a VIEW operation."""
+    @property
+    def block_id(self) -> BlockId: ...
+    @property
+    def content(self) -> str | None: ...
+    @property
+    def role(self) -> str | None: ...
+    @property
+    def tags(self) -> list[str]: ...
+    @property
+    def children_count(self) -> int: ...
+    @property
+    def incoming_edges(self) -> int: ...
+    @property
+    def outgoing_edges(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+
+class SearchResult:
+    """Search result from a SEARCH operation."""
+    @property
+    def query(self) -> str: ...
+    @property
+    def matches(self) -> list[tuple[BlockId, float, str | None]]: ...
+    @property
+    def total_searched(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+
+class FindResult:
+    """Find result from a FIND operation."""
+    @property
+    def matches(self) -> list[BlockId]: ...
+    @property
+    def total_searched(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+
+class Connection:
+    """Connection information (block + edge type)."""
+    @property
+    def block(self) -> BlockView: ...
+    @property
+    def edge_type(self) -> str: ...
+    def __repr__(self) -> str: ...
+
+
+class NeighborhoodView:
+    """Neighborhood view result from a VIEW NEIGHBORHOOD operation."""
+    @property
+    def position(self) -> BlockId: ...
+    @property
+    def ancestors(self) -> list[BlockView]: ...
+    @property
+    def children(self) -> list[BlockView]: ...
+    @property
+    def siblings(self) -> list[BlockView]: ...
+    @property
+    def connections(self) -> list[Connection]: ...
+    def __repr__(self) -> str: ...
+
+
+class AgentTraversal:
+    """Main agent traversal interface."""
+    def __init__(self, doc: Document) -> None:
+        """Create a new agent traversal system from a document."""
+    def create_session(self, config: SessionConfig | None = None) -> AgentSessionId:
+        """Create a new agent session."""
+    def close_session(self, session_id: AgentSessionId) -> None:
+        """Close a session."""
+    def navigate_to(self, session_id: AgentSessionId, block_id: BlockId) -> NavigationResult:
+        """Navigate to a specific block (GOTO command).
+
+        Args:
+            session_id: The agent session
+            block_id: The block to navigate to
+
+        Returns:
+            NavigationResult with the new position
+
+        Note:
+            If you get "Block not found" errors after adding blocks to the document,
+            call update_document() to sync the changes to the traversal.
+        """
+    def update_document(self, doc: Document) -> None:
+        """Update the internal document with a new copy.
+
+        Use this when you've added blocks to the original document after
+        creating the AgentTraversal. The traversal creates a snapshot of the
+        document at creation time, so any changes made afterwards won't be
+        visible until you call this method.
+
+        Args:
+            doc: The updated document
+
+        Example:
+            doc = ucp.create("My Document")
+            traversal = ucp.AgentTraversal(doc)
+
+            # Add blocks after creating traversal
+            block_id = doc.add_block(doc.root_id, "New content")
+
+            # Sync changes to traversal
+            traversal.update_document(doc)
+
+            # Now you can navigate to the new block
+            traversal.navigate_to(session, block_id)
+        """
+    def go_back(self, session_id: AgentSessionId, steps: int = 1) -> NavigationResult:
+        """Go back in navigation history (BACK command)."""
+    def expand(self, session_id: AgentSessionId, block_id: BlockId, direction: str = "down", depth: int = 3, view_mode: ViewMode | None = None) -> ExpansionResult:
+        """Expand from a block in a given direction (EXPAND command).
+
+        Args:
+            session_id: The agent session
+            block_id: The block to expand from
+            direction: Direction to expand - "down" (children), "up" (ancestors),
+                       "both" (bidirectional), or "semantic" (via semantic edges)
+            depth: Maximum expansion depth (default: 3)
+            view_mode: How to display block content (default: Full)
+
+        Returns:
+            ExpansionResult with blocks organized by depth level
+
+        Example:
+            # Expand children
+            result = traversal.expand(session, block_id, "down", depth=2)
+
+            # Expand via semantic edges
+            result = traversal.expand(session, block_id, "semantic", depth=1)
+        """
+    def view_block(self, session_id: AgentSessionId, block_id: BlockId, view_mode: ViewMode | None = None) -> BlockView:
+        """View a specific block (VIEW command)."""
+    def view_neighborhood(self, session_id: AgentSessionId) -> NeighborhoodView:
+        """View the neighborhood around the current cursor position.
+
+        Returns information about the current position and its surrounding
+        context: ancestors, children, siblings, and semantic connections.
+
+        Args:
+            session_id: The agent session
+
+        Returns:
+            NeighborhoodView with position and surrounding blocks
+        """
+    def find(self, session_id: AgentSessionId, role: str | None = None, tag: str | None = None, tags: list[str] | None = None, label: str | None = None, pattern: str | None = None) -> FindResult:
+        """Find blocks by pattern (FIND command).
+
+        Args:
+            session_id: The agent session
+            role: Filter by semantic role (e.g., "paragraph", "heading1")
+            tag: Filter by a single tag
+            tags: Filter by multiple tags (alias for tag, uses first tag)
+            label: Filter by block label
+            pattern: Regex pattern to match content
+        """
+    def search(self, session_id: AgentSessionId, query: str, limit: int = 10, min_similarity: float = 0.0) -> SearchResult:
+        """Perform semantic search (SEARCH command).
+
+        Requires a RAG provider to be configured. Returns blocks matching
+        the semantic query with similarity scores.
+
+        Args:
+            session_id: The agent session
+            query: The search query string
+            limit: Maximum number of results (default: 10)
+            min_similarity: Minimum similarity threshold 0.0-1.0 (default: 0.0)
+
+        Returns:
+            SearchResult with matching blocks and similarity scores
+
+        Raises:
+            RuntimeError: If RAG provider is not configured
+        """
+    def find_path(self, session_id: AgentSessionId, from_block: BlockId, to_block: BlockId, max_length: int | None = None) -> list[BlockId]:
+        """Find a path between two blocks (PATH command)."""
+    def context_add(self, session_id: AgentSessionId, block_id: BlockId, reason: str | None = None, relevance: float | None = None) -> None:
+        """Add a block to context (CTX ADD command)."""
+    def context_add_results(self, session_id: AgentSessionId) -> list[BlockId]:
+        """Add all last results to context (CTX ADD RESULTS command)."""
+    def context_remove(self, session_id: AgentSessionId, block_id: BlockId) -> None:
+        """Remove a block from context (CTX REMOVE command)."""
+    def context_clear(self, session_id: AgentSessionId) -> None:
+        """Clear the context window (CTX CLEAR command)."""
+    def context_focus(self, session_id: AgentSessionId, block_id: BlockId | None = None) -> None:
+        """Set focus block (CTX FOCUS command)."""
+    def execute_ucl(self, session_id: AgentSessionId, ucl_input: str) -> list[str]:
+        """Execute UCL commands from a string."""
+    def __repr__(self) -> str: ...
+
+
+class Block:
+    """A block is the fundamental unit of content in UCM."""
+    @property
+    def id(self) -> BlockId:
+        """Get the block ID."""
+    @property
+    def content(self) -> Content:
+        """Get the content."""
+    @property
+    def content_type(self) -> str:
+        """Get the content type tag."""
+    @property
+    def role(self) -> str | None:
+        """Get the semantic role if set."""
+    @property
+    def label(self) -> str | None:
+        """Get the label if set."""
+    @property
+    def tags(self) -> list[str]:
+        """Get the tags."""
+    @property
+    def summary(self) -> str | None:
+        """Get the summary if set."""
+    @property
+    def edges(self) -> list[Edge]:
+        """Get the edges."""
+    def is_root(self) -> bool:
+        """Check if this is the root block."""
+    def has_tag(self, tag: str) -> bool:
+        """Check if the block has a specific tag."""
+    def edges_of_type(self, edge_type: EdgeType) -> list[Edge]:
+        """Get edges of a specific type."""
+    def token_estimate(self) -> int:
+        """Get the estimated token count."""
+    def size_bytes(self) -> int:
+        """Get the content size in bytes."""
+    @property
+    def version(self) -> int:
+        """Get the version counter."""
+    @property
+    def created_at(self) -> str:
+        """Get the creation timestamp as ISO 8601 string."""
+    @property
+    def modified_at(self) -> str:
+        """Get the modification timestamp as ISO 8601 string."""
+    def get_text(self) -> str | None:
+        """Get the text content if this is a text block."""
+    def __repr__(self) -> str: ...
+
+
+class CodeGraph:
+    @staticmethod
+    def build(repo_path: str, commit_hash: str | None = None, include_hidden: bool = False, continue_on_parse_error: bool = True, max_file_bytes: int | None = None, emit_export_edges: bool = True, include_extensions: list[str] | None = None, exclude_dirs: list[str] | None = None) -> CodeGraph: ...
+    @staticmethod
+    def from_document(doc: Document) -> CodeGraph: ...
+    @staticmethod
+    def from_json(payload: str) -> CodeGraph: ...
+    @staticmethod
+    def load(path: str) -> CodeGraph: ...
+    def save(self, path: str) -> None: ...
+    def to_json(self) -> str: ...
+    def to_document(self) -> Document: ...
+    def session(self) -> CodeGraphSession: ...
+    def explain_selector(self, selector: str) -> Any: ...
+    def load_session_json(self, payload: str) -> CodeGraphSession: ...
+    def load_session(self, path: str) -> CodeGraphSession: ...
+    def resolve(self, selector: str) -> BlockId | None: ...
+    def describe(self, selector: str) -> Any: ...
+    def find_nodes(self, node_class: str | None = None, name_regex: str | None = None, path_regex: str | None = None, logical_key_regex: str | None = None, exported: bool | None = None, case_sensitive: bool = False, limit: int | None = None) -> Any: ...
+    def path_between(self, start: str, end: str, max_hops: int = 8) -> Any: ...
+    def __repr__(self) -> str: ...
+
+
+class CodeGraphSession:
+    def fork(self) -> CodeGraphSession: ...
+    def selected_block_ids(self) -> list[BlockId]: ...
+    def session_id(self) -> str: ...
+    def to_json(self) -> str: ...
+    def save(self, path: str) -> None: ...
+    def mutation_log(self) -> Any: ...
+    def event_log(self) -> Any: ...
+    def summary(self) -> Any: ...
+    def seed_overview(self, max_depth: int | None = None) -> Any: ...
+    def focus(self, target: str | None = None) -> Any: ...
+    def select(self, target: str, detail_level: str = "symbol_card") -> Any: ...
+    def expand(self, target: str, mode: str = "dependencies", relation: str | None = None, relations: list[str] | None = None, depth: int = 1, max_add: int | None = None, priority_threshold: int | None = None, max_nodes_visited: int | None = None, max_elapsed_ms: int | None = None, max_emitted_telemetry_events: int | None = None) -> Any: ...
+    def hydrate(self, target: str, padding: int = 2, max_hydrated_bytes: int | None = None, max_elapsed_ms: int | None = None, max_emitted_telemetry_events: int | None = None) -> Any: ...
+    def collapse(self, target: str, include_descendants: bool = False) -> Any: ...
+    def pin(self, target: str, pinned: bool = True) -> Any: ...
+    def prune(self, max_selected: int | None = None) -> Any: ...
+    def export(self, max_tokens: int | None = None, compact: bool = False, include_rendered: bool | None = None, visible_levels: int | None = None, only_node_classes: list[str] | None = None, exclude_node_classes: list[str] | None = None, max_frontier_actions: int | None = None, max_rendered_bytes: int | None = None) -> Any: ...
+    def render_prompt(self, max_tokens: int | None = None, max_rendered_bytes: int | None = None) -> str: ...
+    def apply_recommended(self, top: int = 1, padding: int = 2, depth: int | None = None, max_add: int | None = None, priority_threshold: int | None = None) -> Any: ...
+    def recommendations(self, top: int = 3) -> Any: ...
+    def why_selected(self, target: str) -> Any: ...
+    def explain_selector(self, target: str) -> Any: ...
+    def explain_export_omission(self, target: str, max_tokens: int | None = None, compact: bool = False, include_rendered: bool | None = None, visible_levels: int | None = None, only_node_classes: list[str] | None = None, exclude_node_classes: list[str] | None = None, max_frontier_actions: int | None = None, max_rendered_bytes: int | None = None) -> Any: ...
+    def why_pruned(self, target: str) -> Any: ...
+    def estimate_expand(self, target: str, mode: str = "dependencies", relation: str | None = None, relations: list[str] | None = None, depth: int = 1, max_add: int | None = None, priority_threshold: int | None = None, max_nodes_visited: int | None = None, max_elapsed_ms: int | None = None, max_emitted_telemetry_events: int | None = None) -> Any: ...
+    def estimate_hydrate(self, target: str, padding: int = 2, max_hydrated_bytes: int | None = None, max_elapsed_ms: int | None = None, max_emitted_telemetry_events: int | None = None) -> Any: ...
+    def diff(self, other: CodeGraphSession) -> Any: ...
+    def path_between(self, start: str, end: str, max_hops: int = 8) -> Any: ...
+    def find_nodes(self, node_class: str | None = None, name_regex: str | None = None, path_regex: str | None = None, logical_key_regex: str | None = None, exported: bool | None = None, case_sensitive: bool = False, limit: int | None = None) -> Any: ...
+    def __repr__(self) -> str: ...
+
+
+class Content:
+    """Block content with typed payload."""
+    @staticmethod
+    def text(text: str) -> Content:
+        """Create plain text content."""
+    @staticmethod
+    def markdown(text: str) -> Content:
+        """Create markdown text content."""
+    @staticmethod
+    def code(language: str, source: str) -> Content:
+        """Create code content."""
+    @staticmethod
+    def json(value: Any) -> Content:
+        """Create JSON content."""
+    @staticmethod
+    def geojson(value: Any) -> Content:
+        """Create GeoJSON content; raises ValueError for non-GeoJSON values."""
+    @staticmethod
+    def table(rows: list[list[str]]) -> Content:
+        """Create table content from rows."""
+    @staticmethod
+    def math(expression: str, display_mode: bool = False, format: str = "latex") -> Content:
+        """Create math content (LaTeX by default)."""
+    @staticmethod
+    def media(media_type: str, url: str, alt_text: str | None = None, width: int | None = None, height: int | None = None) -> Content:
+        """Create media content (image, audio, video, document)."""
+    @staticmethod
+    def binary(mime_type: str, data: bytes, encoding: str = "raw") -> Content:
+        """Create binary content."""
+    @staticmethod
+    def composite(layout: str = "vertical", children: list[str] | None = None) -> Content:
+        """Create composite content (container for other blocks)."""
+    @property
+    def type_tag(self) -> str:
+        """Get the content type tag (e.g., "text", "code", "table")."""
+    @property
+    def is_empty(self) -> bool:
+        """Check if the content is empty."""
+    @property
+    def size_bytes(self) -> int:
+        """Get the approximate size in bytes."""
+    def as_text(self) -> str | None:
+        """Get the text content if this is a text block."""
+    def as_code(self) -> tuple[str, str] | None:
+        """Get the code source if this is a code block."""
+    def as_json(self) -> Any:
+        """Get the JSON value if this is a JSON block."""
+    def as_math(self) -> tuple[str, bool, str] | None:
+        """Get the math expression if this is a math block."""
+    def as_media(self) -> tuple[str, str, str | None] | None:
+        """Get the media info if this is a media block."""
+    def as_binary(self) -> tuple[str, bytes] | None:
+        """Get the binary data if this is a binary block."""
+    def as_spreadsheet(self, evaluate: bool = True) -> tuple[list[str], list[list[Any]]] | None:
+        """Get the headers and rows if this is a spreadsheet block, with formulas evaluated unless `evaluate` is False."""
+    def as_form(self) -> list[dict[str, Any]] | None:
+        """Get the fields as dicts if this is a form block."""
+    def as_table(self) -> tuple[list[str], list[list[str]]] | None:
+        """Get the table data if this is a table block."""
+    def to_dict(self) -> Any:
+        """Convert content to a Python dict representation."""
+    def __repr__(self) -> str: ...
+
+
+class Document:
+    """A UCM document is a collection of blocks with hierarchical structure."""
+    def __init__(self, title: str | None = None) -> None:
+        """Create a new empty document (same as `Document.create`)."""
+    @staticmethod
+    def create(title: str | None = None) -> Document:
+        """Create a new empty document."""
+    @property
+    def id(self) -> str:
+        """Get the document ID."""
+    @property
+    def root_id(self) -> BlockId:
+        """Get the root block ID."""
+    @property
+    def title(self) -> str | None:
+        """Get the document title."""
+    @title.setter
+    def title(self, title: str | None) -> None:
+        """Set the document title."""
+    @property
+    def description(self) -> str | None:
+        """Get the document description."""
+    @description.setter
+    def description(self, description: str | None) -> None:
+        """Set the document description."""
+    @property
+    def block_count(self) -> int:
+        """Get the total block count."""
+    def get_block(self, id: BlockId) -> Block | None:
+        """Get a block by ID."""
+    def children(self, parent_id: BlockId) -> list[BlockId]:
+        """Get the children of a block."""
+    def parent(self, child_id: BlockId) -> BlockId | None:
+        """Get the parent of a block."""
+    def ancestors(self, id: BlockId) -> list[BlockId]:
+        """Get all ancestors of a block (from parent to root)."""
+    def descendants(self, id: BlockId) -> list[BlockId]:
+        """Get all descendants of a block."""
+    def is_reachable(self, id: BlockId) -> bool:
+        """Check if a block is reachable from root."""
+    def is_ancestor(self, potential_ancestor: BlockId, block: BlockId) -> bool:
+        """Check if one block is an ancestor of another."""
+    def add_block(self, parent_id: BlockId, content: str, role: str | None = None, label: str | None = None, tags: list[str] | None = None) -> BlockId:
+        """Add a new block to the document."""
+    def add_block_with_content(self, parent_id: BlockId, content: Content, role: str | None = None, label: str | None = None, index: int | None = None) -> BlockId:
+        """Add a new block with specific content type."""
+    def intern_block(self, block: Block, parent_id: BlockId | None = None) -> BlockId:
+        """Add a block unless one with the same content was interned before; the existing block is then also attached under the parent and its ID returned. Adds under the root when no parent is given."""
+    def add_code(self, parent_id: BlockId, language: str, source: str, label: str | None = None) -> BlockId:
+        """Add a code block."""
+    def edit_block(self, id: BlockId, content: str, role: str | None = None) -> None:
+        """Edit a block's content."""
+    def edit_block_content(self, id: BlockId, content: Content, role: str | None = None) -> None:
+        """Edit a block with specific content."""
+    def move_block(self, id: BlockId, new_parent_id: BlockId, index: int | None = None) -> None:
+        """Move a block to a new parent."""
+    def delete_block(self, id: BlockId, cascade: bool = False) -> list[BlockId]:
+        """Delete a block."""
+    def add_tag(self, id: BlockId, tag: str) -> None:
+        """Add a tag to a block."""
+    def remove_tag(self, id: BlockId, tag: str) -> bool:
+        """Remove a tag from a block."""
+    def set_label(self, id: BlockId, label: str | None = None) -> None:
+        """Set a block's label."""
+    def add_edge(self, source_id: BlockId, edge_type: EdgeType, target_id: BlockId) -> None:
+        """Add an edge to a block."""
+    def remove_edge(self, source_id: BlockId, edge_type: EdgeType, target_id: BlockId) -> bool:
+        """Remove an edge from a block."""
+    def find_by_tag(self, tag: str) -> list[BlockId]:
+        """Find blocks by tag."""
+    def find_by_type(self, content_type: str) -> list[BlockId]:
+        """Find blocks by content type."""
+    def find_by_label(self, label: str) -> BlockId | None:
+        """Find a block by label."""
+    def outgoing_edges(self, id: BlockId) -> list[tuple[EdgeType, BlockId]]:
+        """Get outgoing edges from a block."""
+    def incoming_edges(self, id: BlockId) -> list[tuple[EdgeType, BlockId]]:
+        """Get incoming edges to a block."""
+    def find_orphans(self) -> list[BlockId]:
+        """Find orphaned blocks (unreachable from root)."""
+    def prune_unreachable(self) -> list[BlockId]:
+        """Prune unreachable blocks."""
+    def validate(self) -> list[tuple[str, str, str]]:
+        """Validate the document structure."""
+    def validate_schema(self, schema_json: str) -> ValidationResult:
+        """Validate the document against a document schema given as JSON."""
+    def statistics(self) -> dict[str, Any]:
+        """Get block, edge, depth and size statistics as a dict."""
+    def block_ids(self) -> list[BlockId]:
+        """Get all block IDs in the document."""
+    @property
+    def blocks(self) -> list[Block]:
+        """Iterate over all blocks."""
+    def to_json(self) -> str:
+        """Serialize to portable JSON (the format `ucp export json` writes)."""
+    @staticmethod
+    def from_json(json: str) -> Document:
+        """Deserialize from portable JSON."""
+    def to_markdown(self, block_id: BlockId | None = None) -> str:
+        """Render to markdown, or only the subtree under `block_id` with its top
+        heading promoted to H1."""
+    def __getstate__(self) -> bytes:
+        """Pickle state: the document as compact portable JSON bytes."""
+    def __setstate__(self, state: bytes) -> None:
+        """Restore from pickle state written by `__getstate__`."""
+    def __copy__(self) -> Document: ...
+    def __deepcopy__(self, memo: dict[int, Any]) -> Document:
+        """Documents own all their data, so a deep copy is a clone."""
+    def to_proto(self) -> bytes:
+        """Serialize to protobuf bytes."""
+    @staticmethod
+    def from_proto(data: bytes) -> Document:
+        """Deserialize from protobuf bytes."""
+    @property
+    def version(self) -> int:
+        """Get document version."""
+    @property
+    def created_at(self) -> str:
+        """Get created timestamp as ISO 8601 string."""
+    @property
+    def modified_at(self) -> str:
+        """Get modified timestamp as ISO 8601 string."""
+    def siblings(self, id: BlockId) -> list[BlockId]:
+        """Get the siblings of a block (children of same parent, excluding self)."""
+    def depth(self, id: BlockId) -> int:
+        """Get the depth of a block from the root (root has depth 0)."""
+    def find_by_role(self, role: str) -> list[BlockId]:
+        """Find blocks by semantic role."""
+    def path_from_root(self, id: BlockId) -> list[BlockId]:
+        """Get the path from root to a block (list of block IDs)."""
+    def sibling_index(self, id: BlockId) -> int | None:
+        """Get the index of a block among its siblings."""
+    def write_section(self, section_id: BlockId, markdown: str, base_heading_level: int | None = None) -> WriteSectionResult:
+        """Write markdown content into a section by block ID."""
+    def __repr__(self) -> str: ...
+    def __len__(self) -> int: ...
+
+
+class EdgeType:
+    """Edge type enumeration."""
+    DerivedFrom: ClassVar[EdgeType]
+    Supersedes: ClassVar[EdgeType]
+    TransformedFrom: ClassVar[EdgeType]
+    References: ClassVar[EdgeType]
+    CitedBy: ClassVar[EdgeType]
+    LinksTo: ClassVar[EdgeType]
+    Supports: ClassVar[EdgeType]
+    Contradicts: ClassVar[EdgeType]
+    Elaborates: ClassVar[EdgeType]
+    Summarizes: ClassVar[EdgeType]
+    ParentOf: ClassVar[EdgeType]
+    ChildOf: ClassVar[EdgeType]
+    SiblingOf: ClassVar[EdgeType]
+    PreviousSibling: ClassVar[EdgeType]
+    NextSibling: ClassVar[EdgeType]
+    VersionOf: ClassVar[EdgeType]
+    AlternativeOf: ClassVar[EdgeType]
+    TranslationOf: ClassVar[EdgeType]
+    @staticmethod
+    def from_string(s: str) -> EdgeType:
+        """Parse an edge type from string."""
+    def as_string(self) -> str:
+        """Convert to string representation."""
+    def is_symmetric(self) -> bool:
+        """Check if this edge type is symmetric."""
+    def is_structural(self) -> bool:
+        """Check if this is a structural edge type (auto-maintained)."""
+    def __str__(self) -> str: ...
+    def __repr__(self) -> str: ...
+
+
+class Edge:
+    """An edge representing a relationship between blocks."""
+    def __init__(self, edge_type: EdgeType, target: BlockId) -> None:
+        """Create a new edge."""
+    @property
+    def edge_type(self) -> EdgeType:
+        """Get the edge type."""
+    @property
+    def target(self) -> BlockId:
+        """Get the target block ID."""
+    @property
+    def confidence(self) -> float | None:
+        """Get the confidence score (0.0-1.0) if set."""
+    @property
+    def weight(self) -> float | None:
+        """Get the edge weight if set."""
+    @property
+    def valid_from(self) -> datetime | None:
+        """Get the start of the validity window if set."""
+    @property
+    def valid_to(self) -> datetime | None:
+        """Get the end of the validity window if set."""
+    @property
+    def description(self) -> str | None:
+        """Get the description if set."""
+    @property
+    def created_at(self) -> str:
+        """Get the creation timestamp as ISO 8601 string."""
+    def __repr__(self) -> str: ...
+
+
+class EngineConfig:
+    """Engine configuration."""
+    def __init__(self, validate_on_operation: bool = True, max_batch_size: int = 10000, enable_transactions: bool = True, enable_snapshots: bool = True) -> None: ...
+    @property
+    def validate_on_operation(self) -> bool: ...
+    @property
+    def max_batch_size(self) -> int: ...
+    @property
+    def enable_transactions(self) -> bool: ...
+    @property
+    def enable_snapshots(self) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class TransactionId:
+    """Transaction ID wrapper."""
+    def __repr__(self) -> str: ...
+    def __str__(self) -> str: ...
+    def __hash__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+
+
+class Transaction:
+    """Context manager returned by `Engine.transaction()`."""
+    @property
+    def id(self) -> TransactionId | None:
+        """The transaction id, once the transaction has started."""
+    def __enter__(self) -> TransactionId: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None = None,
+        _exc_value: BaseException | None = None,
+        _traceback: TracebackType | None = None,
+    ) -> bool:
+        """Commit on success, roll back on exception. Never suppresses the exception."""
+    def __repr__(self) -> str: ...
+
+
+class Engine:
+    """The main transformation engine with transaction support."""
+    def __init__(self, config: EngineConfig | None = None) -> None:
+        """Create a new engine with default configuration."""
+    def validate(self, doc: Document) -> ValidationResult:
+        """Validate a document."""
+    def explain(self, doc: Document, ucl: str) -> str:
+        """Describe what UCL commands would do to a document, without executing them."""
+    def begin_transaction(self) -> TransactionId:
+        """Begin a new transaction."""
+    def begin_named_transaction(self, name: str) -> TransactionId:
+        """Begin a named transaction."""
+    def commit_transaction(self, txn_id: TransactionId, doc: Document) -> None:
+        """Commit a transaction, applying its queued operations to a document."""
+    def rollback_transaction(self, txn_id: TransactionId) -> None:
+        """Rollback a transaction."""
+    def transaction(self, name: str | None = None, doc: Document | None = None) -> Transaction:
+        """Start a transaction for use in a `with` block."""
+    def transaction_context(
+        self, name: str | None = None, doc: Document | None = None
+    ) -> Transaction:
+        """Alias for `transaction()`."""
+    def create_snapshot(self, name: str, doc: Document, description: str | None = None) -> None:
+        """Create a snapshot."""
+    def restore_snapshot(self, name: str) -> Document:
+        """Restore from a snapshot."""
+    def list_snapshots(self) -> list[str]:
+        """List all snapshots."""
+    def delete_snapshot(self, name: str) -> bool:
+        """Delete a snapshot."""
+    def __repr__(self) -> str: ...
+
+
+class ResourceLimits:
+    """Resource limits for validation."""
+    def __init__(self, max_document_size: int | None = None, max_block_count: int | None = None, max_block_size: int | None = None, max_nesting_depth: int | None = None, max_edges_per_block: int | None = None) -> None: ...
+    @staticmethod
+    def default_limits() -> ResourceLimits:
+        """Create default resource limits."""
+    @property
+    def max_document_size(self) -> int: ...
+    @property
+    def max_block_count(self) -> int: ...
+    @property
+    def max_block_size(self) -> int: ...
+    @property
+    def max_nesting_depth(self) -> int: ...
+    @property
+    def max_edges_per_block(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+
+class ValidationResult:
+    """Validation result."""
+    @property
+    def valid(self) -> bool: ...
+    @property
+    def issues(self) -> list[ValidationIssue]: ...
+    def errors(self) -> list[ValidationIssue]:
+        """Get only error issues."""
+    def warnings(self) -> list[ValidationIssue]:
+        """Get only warning issues."""
+    def __repr__(self) -> str: ...
+    def __bool__(self) -> bool: ...
+
+
+class ValidationIssue:
+    """A single validation issue."""
+    @property
+    def severity(self) -> str: ...
+    @property
+    def code(self) -> str: ...
+    @property
+    def message(self) -> str: ...
+    def __repr__(self) -> str: ...
+
+
+class ValidationPipeline:
+    """Validation pipeline with configurable resource limits."""
+    def __init__(self, limits: ResourceLimits | None = None) -> None:
+        """Create a new validation pipeline with default limits."""
+    def validate(self, doc: Document) -> ValidationResult:
+        """Validate a document."""
+    def __repr__(self) -> str: ...
+
+
+class TraversalDirection:
+    """Traversal direction."""
+    DOWN: ClassVar[str]
+    UP: ClassVar[str]
+    BOTH: ClassVar[str]
+    SIBLINGS: ClassVar[str]
+    BREADTH_FIRST: ClassVar[str]
+    DEPTH_FIRST: ClassVar[str]
+
+
+class TraversalFilter:
+    """Traversal filter for filtering blocks during traversal."""
+    def __init__(self, include_roles: list[str] | None = None, exclude_roles: list[str] | None = None, include_tags: list[str] | None = None, exclude_tags: list[str] | None = None, content_pattern: str | None = None) -> None: ...
+    def __repr__(self) -> str: ...
+
+
+class TraversalConfig:
+    """Traversal configuration."""
+    def __init__(self, max_depth: int = 100, max_nodes: int = 10000, include_orphans: bool = False) -> None: ...
+    @property
+    def max_depth(self) -> int: ...
+    @property
+    def max_nodes(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+
+class TraversalNode:
+    """A node in the traversal result."""
+    @property
+    def id(self) -> str: ...
+    @property
+    def depth(self) -> int: ...
+    @property
+    def parent_id(self) -> str | None: ...
+    @property
+    def content_preview(self) -> str | None: ...
+    @property
+    def semantic_role(self) -> str | None: ...
+    @property
+    def child_count(self) -> int: ...
+    @property
+    def edge_count(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+
+class TraversalResult:
+    """Traversal result containing nodes, edges, and summary."""
+    @property
+    def nodes(self) -> list[TraversalNode]: ...
+    @property
+    def total_nodes(self) -> int: ...
+    @property
+    def max_depth(self) -> int: ...
+    @property
+    def execution_time_ms(self) -> int | None: ...
+    def node_ids(self) -> list[str]:
+        """Get node IDs only."""
+    def __len__(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+
+class TraversalEngine:
+    """Graph traversal engine for UCM documents."""
+    def __init__(self, config: TraversalConfig | None = None) -> None:
+        """Create a new traversal engine."""
+    def navigate(self, doc: Document, direction: str, start_id: BlockId | None = None, depth: int | None = None, filter: TraversalFilter | None = None) -> TraversalResult:
+        """Navigate from a starting point in a specific direction."""
+    def expand(self, doc: Document, node_id: BlockId) -> TraversalResult:
+        """Expand a node to get its immediate children."""
+    def path_to_root(self, doc: Document, node_id: BlockId) -> list[BlockId]:
+        """Get the path from a node to the root."""
+    def find_paths(self, doc: Document, from_id: BlockId, to_id: BlockId, max_paths: int = 10) -> list[list[BlockId]]:
+        """Find all paths between two nodes."""
+    def __repr__(self) -> str: ...
+
+
+class Graph:
+    @staticmethod
+    def from_document(doc: Document) -> Graph: ...
+    @staticmethod
+    def from_json(payload: str) -> Graph: ...
+    @staticmethod
+    def load(path: str) -> Graph: ...
+    @staticmethod
+    def from_sqlite(path: str, graph_key: str) -> Graph: ...
+    def persist_sqlite(self, path: str, graph_key: str) -> Graph: ...
+    def save(self, path: str) -> None: ...
+    def to_json(self) -> str: ...
+    def to_document(self) -> Document: ...
+    def session(self) -> GraphSession: ...
+    def root_id(self) -> BlockId: ...
+    def resolve(self, selector: str) -> BlockId | None: ...
+    def store_stats(self) -> Any: ...
+    def observability(self) -> Any: ...
+    def describe(self, selector: str) -> Any: ...
+    def find_nodes(self, label_regex: str | None = None, content_type: str | None = None, semantic_role_regex: str | None = None, tag_regex: str | None = None, case_sensitive: bool = False, limit: int | None = None) -> Any: ...
+    def path_between(self, start: str, end: str, max_hops: int = 6) -> Any: ...
+
+
+class GraphSession:
+    def fork(self) -> GraphSession: ...
+    def selected_block_ids(self) -> list[BlockId]: ...
+    def summary(self) -> Any: ...
+    def seed_overview(self, max_depth: int | None = None) -> Any: ...
+    def focus(self, selector: str | None = None) -> Any: ...
+    def select(self, selector: str, detail_level: str = "summary") -> Any: ...
+    def expand(self, selector: str, mode: str = "children", depth: int = 1, max_add: int | None = None) -> Any: ...
+    def collapse(self, selector: str, include_descendants: bool = False) -> Any: ...
+    def pin(self, selector: str, pinned: bool = True) -> Any: ...
+    def prune(self, max_selected: int | None = None) -> Any: ...
+    def export(self) -> Any: ...
+    def why_selected(self, selector: str) -> Any: ...
+    def diff(self, other: GraphSession) -> Any: ...
+
+
+class UclCapability:
+    """UCL command capability enumeration."""
+    Edit: ClassVar[UclCapability]
+    Append: ClassVar[UclCapability]
+    Move: ClassVar[UclCapability]
+    Delete: ClassVar[UclCapability]
+    Link: ClassVar[UclCapability]
+    Snapshot: ClassVar[UclCapability]
+    Transaction: ClassVar[UclCapability]
+    @staticmethod
+    def all() -> list[UclCapability]:
+        """Get all available capabilities."""
+    def command_names(self) -> list[str]:
+        """Get the command names for this capability."""
+    def documentation(self) -> str:
+        """Get documentation for this capability."""
+    def __str__(self) -> str: ...
+    def __repr__(self) -> str: ...
+
+
+class PromptBuilderMode:
+    """Output format a prompt asks the model to produce."""
+    Ucl: ClassVar[PromptBuilderMode]
+    JsonCommands: ClassVar[PromptBuilderMode]
+    CodeGraph: ClassVar[PromptBuilderMode]
+
+
+class IdMapper:
+    """Bidirectional mapping between BlockIds and short numeric IDs.
+
+    Useful for token-efficient LLM prompts by replacing long block IDs
+    with short numeric identifiers.
+    """
+    def __init__(self) -> None:
+        """Create a new empty IdMapper."""
+    @staticmethod
+    def from_document(doc: Document) -> IdMapper:
+        """Create a mapper from a document, assigning sequential IDs to all blocks."""
+    def register(self, block_id: BlockId) -> int:
+        """Register a BlockId and get its short ID."""
+    def to_short_id(self, block_id: BlockId) -> int | None:
+        """Get short ID for a BlockId."""
+    def to_block_id(self, short_id: int) -> BlockId | None:
+        """Get BlockId for a short ID."""
+    def shorten_text(self, text: str) -> str:
+        """Convert a string containing block IDs to use short IDs."""
+    def expand_text(self, text: str) -> str:
+        """Convert a string containing short IDs back to block IDs."""
+    def shorten_ucl(self, ucl: str) -> str:
+        """Convert UCL commands from long BlockIds to short numeric IDs."""
+    def expand_ucl(self, ucl: str) -> str:
+        """Convert UCL commands from short numeric IDs back to full BlockIds."""
+    def estimate_token_savings(self, text: str) -> tuple[int, int, int]:
+        """Estimate token savings from using short IDs.
+
+        Returns (original_tokens, shortened_tokens, savings).
+        """
+    def document_to_prompt(self, doc: Document) -> str:
+        """Generate a normalized document representation for LLM prompts."""
+    def mapping_table(self) -> str:
+        """Get the mapping table as a string (useful for debugging)."""
+    def __len__(self) -> int:
+        """Total number of mappings."""
+    def __repr__(self) -> str: ...
+
+
+class PromptBuilder:
+    """Builder for constructing LLM prompts with specific capabilities."""
+    def __init__(self) -> None:
+        """Create a new prompt builder with no capabilities."""
+    @staticmethod
+    def with_all_capabilities() -> PromptBuilder:
+        """Create a builder with all capabilities enabled."""
+    @staticmethod
+    def for_code_graph(doc: Document) -> PromptBuilder:
+        """Create a builder for a CodeGraph document: all capabilities, CodeGraph
+        mode, and a task context naming the repository.
+        """
+    def with_capability(self, cap: UclCapability) -> PromptBuilder:
+        """Add a single capability."""
+    def with_capabilities(self, caps: list[UclCapability]) -> PromptBuilder:
+        """Add multiple capabilities."""
+    def without_capability(self, cap: UclCapability) -> PromptBuilder:
+        """Remove a capability."""
+    def with_system_context(self, context: str) -> PromptBuilder:
+        """Set custom system context (prepended to prompt)."""
+    def with_task_context(self, context: str) -> PromptBuilder:
+        """Set task-specific context."""
+    def with_rule(self, rule: str) -> PromptBuilder:
+        """Add a custom rule."""
+    def with_short_ids(self, enabled: bool) -> PromptBuilder:
+        """Enable short ID mode (for token efficiency)."""
+    def with_mode(self, mode: PromptBuilderMode) -> PromptBuilder:
+        """Set the output format the prompt asks for."""
+    @property
+    def mode(self) -> PromptBuilderMode:
+        """Get the output mode."""
+    def build_system_prompt(self) -> str:
+        """Build the system prompt."""
+    def build_prompt(self, document_description: str, task: str) -> str:
+        """Build a complete prompt with document context."""
+    def project_document(self, doc: Document, focus: list[BlockId], max_tokens: int | None = None) -> str:
+        """Render a document folded around focus blocks.
+
+        Focus blocks are shown in full, their ancestors as headers, and
+        everything else is folded into one-line summaries. In CodeGraph mode
+        the document is rendered as symbols grouped by file instead.
+        """
+    def build_projected_prompt(self, doc: Document, focus: list[BlockId], task: str, max_tokens: int | None = None) -> str:
+        """Build a complete prompt with the document folded around focus blocks."""
+    def has_capability(self, cap: UclCapability) -> bool:
+        """Check if a capability is enabled."""
+    def capabilities(self) -> list[UclCapability]:
+        """Get a list of enabled capabilities."""
+    def __repr__(self) -> str: ...
+
+
+class PromptPresets:
+    """Preset prompt configurations for common use cases."""
+    @staticmethod
+    def basic_editing() -> PromptBuilder:
+        """Basic editing only (EDIT, APPEND, DELETE)."""
+    @staticmethod
+    def structure_manipulation() -> PromptBuilder:
+        """Structure manipulation (MOVE, LINK)."""
+    @staticmethod
+    def full_editing() -> PromptBuilder:
+        """Full document editing (all except transactions)."""
+    @staticmethod
+    def version_control() -> PromptBuilder:
+        """Version control focused."""
+    @staticmethod
+    def token_efficient() -> PromptBuilder:
+        """Token-efficient mode with short IDs."""
+
+
+class UcpEvent:
+    """UCP event wrapper for Python."""
+    @staticmethod
+    def document_created(document_id: str) -> UcpEvent:
+        """Create a document created event."""
+    @staticmethod
+    def block_added(document_id: str, block_id: str, parent_id: str, content_type: str) -> UcpEvent:
+        """Create a block added event."""
+    @staticmethod
+    def block_deleted(document_id: str, block_id: str, cascade: bool) -> UcpEvent:
+        """Create a block deleted event."""
+    @staticmethod
+    def snapshot_created(document_id: str, snapshot_name: str) -> UcpEvent:
+        """Create a snapshot created event."""
+    @property
+    def event_type(self) -> str:
+        """Get the event type."""
+    @property
+    def document_id(self) -> str | None:
+        """Get the document ID if present."""
+    @property
+    def timestamp(self) -> str:
+        """Get the timestamp as ISO 8601 string."""
+    @property
+    def details(self) -> str:
+        """Get event details as JSON string."""
+    def __repr__(self) -> str: ...
+
+
+class EventBus:
+    """Event bus for subscribing to UCP events."""
+    def __init__(self) -> None:
+        """Create a new event bus."""
+    @property
+    def subscriber_count(self) -> int:
+        """Get the number of subscribers."""
+    def on_event(self, callback: Callable[[UcpEvent], object]) -> None:
+        """Call `callback(event)` synchronously for every published event."""
+    def on_event_async(self, coro_fn: Callable[[UcpEvent], Awaitable[object]]) -> None:
+        """Schedule `await coro_fn(event)` on the running asyncio loop for every
+        published event. Publishing returns without waiting for the coroutine."""
+    def publish(self, event: UcpEvent) -> None:
+        """Publish an event to all subscribers."""
+    def __repr__(self) -> str: ...
+
+
+class AuditEntry:
+    """Audit log entry."""
+    def __init__(self, operation: str, document_id: str) -> None:
+        """Create a new audit entry."""
+    def with_user(self, user_id: str) -> AuditEntry:
+        """Set the user ID."""
+    def with_duration(self, duration_ms: int) -> AuditEntry:
+        """Set the duration in milliseconds."""
+    def failed(self) -> AuditEntry:
+        """Mark as failed."""
+    @property
+    def operation(self) -> str:
+        """Get the operation name."""
+    @property
+    def document_id(self) -> str:
+        """Get the document ID."""
+    @property
+    def user_id(self) -> str | None:
+        """Get the user ID if present."""
+    @property
+    def success(self) -> bool:
+        """Check if the operation was successful."""
+    @property
+    def duration_ms(self) -> int:
+        """Get the duration in milliseconds."""
+    @property
+    def timestamp(self) -> str:
+        """Get the timestamp as ISO 8601 string."""
+    def to_dict(self) -> Any:
+        """Convert to dict."""
+    def __repr__(self) -> str: ...
+
+
+class MetricsRecorder:
+    """Simple metrics recorder."""
+    def __init__(self) -> None:
+        """Create a new metrics recorder."""
+    def record_operation(self, success: bool) -> None:
+        """Record an operation."""
+    def record_block_created(self) -> None:
+        """Record a block creation."""
+    def record_block_deleted(self) -> None:
+        """Record a block deletion."""
+    def record_snapshot(self) -> None:
+        """Record a snapshot creation."""
+    @property
+    def operations_total(self) -> int:
+        """Get total operations count."""
+    @property
+    def operations_failed(self) -> int:
+        """Get failed operations count."""
+    @property
+    def blocks_created(self) -> int:
+        """Get blocks created count."""
+    @property
+    def blocks_deleted(self) -> int:
+        """Get blocks deleted count."""
+    @property
+    def snapshots_created(self) -> int:
+        """Get snapshots created count."""
+    def to_dict(self) -> Any:
+        """Convert to dict."""
+    def __repr__(self) -> str: ...
+
+
+class WriteSectionResult:
+    """Result of writing markdown into a section."""
+    @property
+    def success(self) -> bool: ...
+    @property
+    def section_id(self) -> BlockId: ...
+    @property
+    def blocks_removed(self) -> list[BlockId]: ...
+    @property
+    def blocks_added(self) -> list[BlockId]: ...
+    def __repr__(self) -> str: ...
+
+
+class ClearResult:
+    """Result of a section clear operation with undo support."""
+    @property
+    def removed_ids(self) -> list[BlockId]:
+        """Get the IDs of removed blocks."""
+    @property
+    def deleted_content(self) -> DeletedContent:
+        """Get the deleted content for potential restoration."""
+    def __len__(self) -> int:
+        """Get the number of removed blocks."""
+    def __repr__(self) -> str: ...
+
+
+class DeletedContent:
+    """Deleted content that can be restored."""
+    @property
+    def is_empty(self) -> bool:
+        """Check if there is any deleted content."""
+    @property
+    def block_count(self) -> int:
+        """Get the number of deleted blocks."""
+    def block_ids(self) -> list[BlockId]:
+        """Get all block IDs in the deleted content."""
+    @property
+    def parent_id(self) -> BlockId:
+        """Get the parent block ID where this content was attached."""
+    @property
+    def deleted_at(self) -> str:
+        """Get the deletion timestamp as ISO 8601 string."""
+    def to_dict(self) -> Any:
+        """Convert to dict for serialization."""
+    def to_json(self) -> str:
+        """Serialize to JSON string for persistence."""
+    @staticmethod
+    def from_json(json_str: str) -> DeletedContent:
+        """Deserialize from JSON string."""
+    def __len__(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+
+class SnapshotInfo:
+    """Snapshot information."""
+    @property
+    def name(self) -> str: ...
+    @property
+    def description(self) -> str | None: ...
+    @property
+    def created_at(self) -> str: ...
+    @property
+    def version(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+
+class SnapshotManager:
+    """Manages document snapshots for versioning."""
+    def __init__(self, max_snapshots: int | None = None) -> None:
+        """Create a new snapshot manager."""
+    def create(self, name: str, doc: Document, description: str | None = None) -> str:
+        """Create a snapshot of a document."""
+    def restore(self, name: str) -> Document:
+        """Restore a document from a snapshot."""
+    def get(self, name: str) -> SnapshotInfo | None:
+        """Get information about a snapshot."""
+    def list(self) -> list[SnapshotInfo]:
+        """List all snapshots (most recent first)."""
+    def delete(self, name: str) -> bool:
+        """Delete a snapshot."""
+    def exists(self, name: str) -> bool:
+        """Check if a snapshot exists."""
+    def __len__(self) -> int:
+        """Get snapshot count."""
+    def __repr__(self) -> str: ...
+
+
+class BlockId:
+    """A content-addressed block identifier."""
+    def __init__(self, s: str) -> None:
+        """Create a BlockId from a hex string (e.g., "blk_0102030405060708090a0b0c")."""
+    @staticmethod
+    def root() -> BlockId:
+        """Create the root block ID."""
+    def is_root(self) -> bool:
+        """Check if this is the root block ID."""
+    def __str__(self) -> str: ...
+    def to_string_repr(self) -> str:
+        """Get the string representation (for internal use)."""
+    def __repr__(self) -> str: ...
+    def __hash__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+
+
+def parse(markdown: str) -> Document:
+    """Parse markdown into a Document."""
+
+def render(doc: Document) -> str:
+    """Render a Document to markdown."""
+
+def parse_html(html: str) -> Document:
+    """Parse HTML into a Document."""
+
+def clear_section_with_undo(doc: Document, section_id: BlockId) -> ClearResult:
+    """Clear a section's content with undo support."""
+
+def restore_deleted_section(doc: Document, deleted: DeletedContent) -> list[BlockId]:
+    """Restore previously deleted section content."""
+
+def find_section_by_path(doc: Document, path: str) -> BlockId | None:
+    """Find a section by path (e.g., "Introduction > Getting Started")."""
+
+def get_all_sections(doc: Document) -> list[tuple[BlockId, int]]:
+    """Get all sections (heading blocks) in the document."""
+
+def get_section_depth(doc: Document, section_id: BlockId) -> int | None:
+    """Get the depth of a section in the document hierarchy."""
+
+def execute_ucl(doc: Document, ucl: str) -> list[BlockId]:
+    """Execute UCL commands on a document."""
+
+def execute_ucl_with_mapper(doc: Document, ucl: str, mapper: IdMapper) -> tuple[list[BlockId], list[tuple[int, BlockId]]]:
+    """Execute UCL that may use short IDs from an IdMapper prompt.
+
+    Returns the affected BlockIds and the `(short_id, BlockId)` pairs
+    registered in the mapper for newly created blocks.
+    """
+
+def create(title: str | None = None) -> Document:
+    """Create a new empty document."""
+
+def write_section(doc: Document, section_id: BlockId, markdown: str, base_heading_level: int | None = None) -> WriteSectionResult:
+    """Write markdown content into a section, replacing its children."""
@@ -4,7 +4,7 @@ use pyo3::prelude::*;
 use ucp_agent::{AgentCapabilities, AgentSessionId, AgentTraversal, SessionConfig, ViewMode};
 
 use crate::document::PyDocument;
-use crate::types::PyBlockId;
+use crate::types::{hash_of, PyBlockId};
 
 /// Agent session ID wrapper.
 #[pyclass(name = "AgentSessionId")]
@@ -16,12 +16,20 @@ pub struct PyAgentSessionId {
 #[pymethods]
 impl PyAgentSessionId {
     fn __repr__(&self) -> String {
-        format!("AgentSessionId({})", self.inner)
+        format!("AgentSessionId(\"{}\")", self.inner)
     }
 
     fn __str__(&self) -> String {
         self.inner.to_string()
     }
+
+    fn __hash__(&self) -> isize {
+        hash_of(&self.inner)
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
 }
 
 impl From<AgentSessionId> for PyAgentSessionId {
@@ -105,6 +113,7 @@ pub struct PyAgentCapabilities {
 impl PyAgentCapabilities {
     /// Create capabilities with all permissions (default).
     #[new]
+    #[pyo3(text_signature = "()")]
     fn new() -> Self {
         Self {
             inner: AgentCapabilities::default(),
@@ -160,6 +169,7 @@ pub struct PySessionConfig {
 #[pymethods]
 impl PySessionConfig {
     #[new]
+    #[pyo3(text_signature = "(name=None, start_block=None)")]
     #[pyo3(signature = (name=None, start_block=None))]
     fn new(name: Option<String>, start_block: Option<PyBlockId>) -> Self {
         let mut config = SessionConfig::new();
@@ -193,6 +203,13 @@ impl PySessionConfig {
         }
     }
 
+    /// Skip expired edges during semantic expansion.
+    fn with_filter_expired_edges(&self, filter: bool) -> Self {
+        Self {
+            inner: self.inner.clone().with_filter_expired_edges(filter),
+        }
+    }
+
     fn __repr__(&self) -> String {
         format!("SessionConfig(name={:?})", self.inner.name)
     }
@@ -525,6 +542,7 @@ pub struct PyAgentTraversal {
 impl PyAgentTraversal {
     /// Create a new agent traversal system from a document.
     #[new]
+    #[pyo3(text_signature = "(doc)")]
     fn new(doc: &PyDocument) -> PyResult<Self> {
         let runtime = tokio::runtime::Runtime::new()
             .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
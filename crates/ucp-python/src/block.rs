@@ -3,7 +3,7 @@
 use pyo3::prelude::*;
 use ucm_core::Block;
 
-use crate::content::PyContent;
+use crate::content::{preview, PyContent};
 use crate::edge::{PyEdge, PyEdgeType};
 use crate::types::PyBlockId;
 
@@ -140,24 +140,33 @@ impl PyBlock {
     fn get_text(&self) -> Option<String> {
         match &self.0.content {
             ucm_core::Content::Text(t) => Some(t.text.clone()),
+            ucm_core::Content::RichText(r) => Some(r.plain_text()),
             ucm_core::Content::Code(c) => Some(c.source.clone()),
+            ucm_core::Content::CompressedCode { .. } => self.0.content.decompress(),
+            ucm_core::Content::Diagram(d) => Some(d.source.clone()),
+            ucm_core::Content::Diff(d) => Some(d.patch.clone()),
             _ => None,
         }
     }
 
     fn __repr__(&self) -> String {
-        let content_preview = match &self.0.content {
-            ucm_core::Content::Text(t) => {
-                let preview = if t.text.len() > 30 {
-                    format!("{}...", &t.text[..30])
-                } else {
-                    t.text.clone()
-                };
-                format!("text={:?}", preview)
-            }
+        let metadata = &self.0.metadata;
+        let mut fields = vec![format!("id={}", self.0.id)];
+        if let Some(role) = &metadata.semantic_role {
+            fields.push(format!("role={}", role));
+        }
+        if let Some(label) = &metadata.label {
+            fields.push(format!("label={:?}", label));
+        }
+        if !metadata.tags.is_empty() {
+            fields.push(format!("tags={:?}", metadata.tags));
+        }
+        fields.push(match &self.0.content {
+            ucm_core::Content::Text(t) => format!("text={:?}", preview(&t.text, 30)),
             ucm_core::Content::Code(c) => format!("code={}", c.language),
+            ucm_core::Content::CompressedCode { language, .. } => format!("code={}", language),
             _ => format!("type={}", self.0.content_type()),
-        };
-        format!("Block(id={}, {})", self.0.id, content_preview)
+        });
+        format!("Block({})", fields.join(", "))
     }
 }
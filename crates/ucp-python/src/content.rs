@@ -12,6 +12,14 @@ fn new_dict(py: Python<'_>) -> Bound<'_, PyDict> {
     PyDict::new_bound(py)
 }
 
+/// First `max_chars` characters of `text`, with "..." when cut short.
+pub(crate) fn preview(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
 /// Block content with typed payload.
 #[pyclass(name = "Content")]
 #[derive(Clone)]
@@ -62,6 +70,20 @@ impl PyContent {
         Ok(PyContent(Content::json(json_value)))
     }
 
+    /// Create GeoJSON content; raises ValueError for non-GeoJSON values.
+    #[staticmethod]
+    fn geojson(py: Python<'_>, value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let json_str: String = py
+            .import_bound("json")?
+            .call_method1("dumps", (value,))?
+            .extract()?;
+        let json_value: serde_json::Value = serde_json::from_str(&json_str)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid JSON: {}", e)))?;
+        Content::new_geojson(json_value)
+            .map(PyContent)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
     /// Create table content from rows.
     #[staticmethod]
     fn table(rows: Vec<Vec<String>>) -> Self {
@@ -212,6 +234,9 @@ impl PyContent {
     fn as_code(&self) -> Option<(String, String)> {
         match &self.0 {
             Content::Code(c) => Some((c.language.clone(), c.source.clone())),
+            Content::CompressedCode { language, .. } => {
+                Some((language.clone(), self.0.decompress()?))
+            }
             _ => None,
         }
     }
@@ -281,6 +306,43 @@ impl PyContent {
         }
     }
 
+    /// Get the headers and rows if this is a spreadsheet block.
+    ///
+    /// With `evaluate`, formula cells hold their computed values; otherwise
+    /// they are `{"formula": ...}` dicts.
+    #[pyo3(signature = (evaluate=true))]
+    fn as_spreadsheet(
+        &self,
+        py: Python<'_>,
+        evaluate: bool,
+    ) -> PyResult<Option<(Vec<String>, PyObject)>> {
+        let Content::Spreadsheet(sheet) = &self.0 else {
+            return Ok(None);
+        };
+        let rows = if evaluate {
+            sheet.evaluate()
+        } else {
+            sheet.rows.clone()
+        };
+        let json_str = serde_json::to_string(&rows).unwrap_or_default();
+        let json_module = py.import_bound("json")?;
+        let rows = json_module.call_method1("loads", (json_str,))?;
+        Ok(Some((sheet.headers.clone(), rows.into())))
+    }
+
+    /// Get the fields as dicts if this is a form block.
+    ///
+    /// Select field types are `{"select": [...]}` dicts, other types plain
+    /// strings such as `"text"`.
+    fn as_form(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        let Content::Form(form) = &self.0 else {
+            return Ok(None);
+        };
+        let json_str = serde_json::to_string(&form.fields).unwrap_or_default();
+        let json_module = py.import_bound("json")?;
+        Ok(Some(json_module.call_method1("loads", (json_str,))?.into()))
+    }
+
     /// Get the table data if this is a table block.
     fn as_table(&self) -> Option<(Vec<String>, Vec<Vec<String>>)> {
         match &self.0 {
@@ -320,26 +382,52 @@ impl PyContent {
                 dict.set_item("text", &t.text)?;
                 dict.set_item("format", format!("{:?}", t.format).to_lowercase())?;
             }
+            Content::RichText(r) => {
+                dict.set_item("text", r.plain_text())?;
+                let json_str = serde_json::to_string(&r.spans).unwrap_or_default();
+                let json_module = py.import_bound("json")?;
+                dict.set_item("spans", json_module.call_method1("loads", (json_str,))?)?;
+            }
             Content::Code(c) => {
                 dict.set_item("language", &c.language)?;
                 dict.set_item("source", &c.source)?;
             }
+            Content::CompressedCode { language, .. } => {
+                dict.set_item("language", language)?;
+                dict.set_item("source", self.0.decompress().unwrap_or_default())?;
+            }
             Content::Table(t) => {
                 let columns: Vec<&str> = t.columns.iter().map(|c| c.name.as_str()).collect();
                 dict.set_item("columns", columns)?;
                 dict.set_item("row_count", t.rows.len())?;
             }
+            Content::Spreadsheet(s) => {
+                dict.set_item("headers", &s.headers)?;
+                dict.set_item("row_count", s.rows.len())?;
+            }
             Content::Math(m) => {
                 dict.set_item("expression", &m.expression)?;
                 dict.set_item("display_mode", m.display_mode)?;
             }
+            Content::Diagram(d) => {
+                dict.set_item("dialect", d.dialect.fence_tag())?;
+                dict.set_item("source", &d.source)?;
+            }
+            Content::Diff(d) => {
+                dict.set_item("format", d.format.as_str())?;
+                dict.set_item("patch", &d.patch)?;
+            }
             Content::Media(m) => {
                 dict.set_item("media_type", format!("{:?}", m.media_type).to_lowercase())?;
                 if let Some(alt) = &m.alt_text {
                     dict.set_item("alt_text", alt)?;
                 }
             }
-            Content::Json { value, .. } => {
+            Content::Form(f) => {
+                dict.set_item("title", &f.title)?;
+                dict.set_item("fields", self.as_form(py)?)?;
+            }
+            Content::Json { value, .. } | Content::GeoJson(value) => {
                 let json_str = serde_json::to_string(value).unwrap_or_default();
                 let json_module = py.import_bound("json")?;
                 let obj = json_module.call_method1("loads", (json_str,))?;
@@ -362,20 +450,35 @@ impl PyContent {
 
     fn __repr__(&self) -> String {
         match &self.0 {
-            Content::Text(t) => {
-                let preview = if t.text.len() > 50 {
-                    format!("{}...", &t.text[..50])
-                } else {
-                    t.text.clone()
-                };
-                format!("Content.text({:?})", preview)
+            Content::Text(t) => format!("Content.text({:?})", preview(&t.text, 50)),
+            Content::Code(c) => format!(
+                "Content.code({:?}, {:?})",
+                c.language,
+                preview(&c.source, 50)
+            ),
+            Content::Math(m) => format!("Content.math({:?})", preview(&m.expression, 50)),
+            Content::Diagram(d) => format!(
+                "Content.diagram({:?}, {:?})",
+                d.dialect.fence_tag(),
+                preview(&d.source, 50)
+            ),
+            Content::Diff(d) => format!(
+                "Content.diff({:?}, {:?})",
+                d.format.as_str(),
+                preview(&d.patch, 50)
+            ),
+            Content::Json { value, .. } => {
+                format!("Content.json({:?})", preview(&value.to_string(), 50))
             }
-            Content::Code(c) => format!("Content.code({:?}, ...)", c.language),
             Content::Table(t) => format!(
                 "Content.table(columns={}, rows={})",
                 t.columns.len(),
                 t.rows.len()
             ),
+            Content::Media(m) => match &m.source {
+                MediaSource::Url(u) => format!("Content.media({:?})", preview(u, 50)),
+                _ => format!("Content.media(type={:?})", m.media_type),
+            },
             _ => format!("Content(type={:?})", self.type_tag()),
         }
     }
@@ -1,17 +1,21 @@
 //! Document type wrapper for Python.
 
 use pyo3::prelude::*;
-use ucm_core::{Block, Content, Document, Edge, EdgeType};
+use pyo3::types::PyBytes;
+use ucm_core::{Block, Content, Document, DocumentSchema, Edge, EdgeType};
+use ucm_proto::ProtoCodec;
 
 use crate::block::PyBlock;
 use crate::content::PyContent;
 use crate::edge::PyEdgeType;
+use crate::engine::PyValidationResult;
 use crate::errors::IntoPyResult;
+use crate::json::to_python_json;
 use crate::section::{write_section as write_section_fn, PyWriteSectionResult};
 use crate::types::PyBlockId;
 
 /// A UCM document is a collection of blocks with hierarchical structure.
-#[pyclass(name = "Document")]
+#[pyclass(name = "Document", module = "ucp._core")]
 pub struct PyDocument {
     inner: Document,
 }
@@ -32,6 +36,13 @@ impl PyDocument {
 
 #[pymethods]
 impl PyDocument {
+    /// Create a new empty document (same as `Document.create`).
+    #[new]
+    #[pyo3(signature = (title=None), text_signature = "(title=None)")]
+    fn py_new(title: Option<&str>) -> Self {
+        Self::create(title)
+    }
+
     /// Create a new empty document.
     #[staticmethod]
     #[pyo3(signature = (title=None))]
@@ -188,6 +199,23 @@ impl PyDocument {
         Ok(PyBlockId::from(id))
     }
 
+    /// Add a block unless one with the same content was interned before; the existing
+    /// block is then also attached under the parent and its ID returned. Adds under the
+    /// root when no parent is given.
+    #[pyo3(signature = (block, parent_id=None))]
+    fn intern_block(
+        &mut self,
+        block: &PyBlock,
+        parent_id: Option<&PyBlockId>,
+    ) -> PyResult<PyBlockId> {
+        let parent = parent_id.map_or(self.inner.root, |id| *id.inner());
+        let id = self
+            .inner
+            .intern_block(block.inner().clone(), &parent)
+            .into_py_result()?;
+        Ok(PyBlockId::from(id))
+    }
+
     /// Add a code block.
     #[pyo3(signature = (parent_id, language, source, label=None))]
     fn add_code(
@@ -419,6 +447,19 @@ impl PyDocument {
             .collect()
     }
 
+    /// Validate the document against a document schema given as JSON.
+    fn validate_schema(&self, schema_json: &str) -> PyResult<PyValidationResult> {
+        let schema = DocumentSchema::from_json(schema_json).into_py_result()?;
+        Ok(PyValidationResult::from(
+            self.inner.validate_against_schema(&schema),
+        ))
+    }
+
+    /// Get block, edge, depth and size statistics as a dict.
+    fn statistics(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python_json(py, &self.inner.statistics())
+    }
+
     /// Get all block IDs in the document.
     fn block_ids(&self) -> Vec<PyBlockId> {
         self.inner
@@ -434,26 +475,78 @@ impl PyDocument {
         self.inner.blocks.values().map(PyBlock::from).collect()
     }
 
-    /// Serialize to JSON string.
+    /// Serialize to portable JSON (the format `ucp export json` writes).
     fn to_json(&self) -> PyResult<String> {
-        // Create a serializable representation
-        let blocks: Vec<_> = self.inner.blocks.values().collect();
-        serde_json::to_string_pretty(&serde_json::json!({
-            "id": self.inner.id.0,
-            "root": self.inner.root.to_string(),
-            "structure": self.inner.structure.iter()
-                .map(|(k, v)| (k.to_string(), v.iter().map(|id| id.to_string()).collect::<Vec<_>>()))
-                .collect::<std::collections::HashMap<_, _>>(),
-            "blocks": blocks,
-            "metadata": {
-                "title": self.inner.metadata.title,
-                "description": self.inner.metadata.description,
-                "authors": self.inner.metadata.authors,
-                "created_at": self.inner.metadata.created_at.to_rfc3339(),
-                "modified_at": self.inner.metadata.modified_at.to_rfc3339(),
-            }
-        }))
-        .map_err(|e| crate::errors::PyUcpError::new_err(format!("Serialization error: {}", e)))
+        self.inner
+            .to_portable_json_pretty()
+            .map_err(|e| crate::errors::PyUcpError::new_err(e.to_string()))
+    }
+
+    /// Deserialize from portable JSON.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        Document::from_portable_json(json)
+            .map(PyDocument::new)
+            .map_err(|e| crate::errors::PyUcpError::new_err(e.to_string()))
+    }
+
+    /// Render to markdown, or only the subtree under `block_id` with its top
+    /// heading promoted to H1.
+    #[pyo3(signature = (block_id=None))]
+    fn to_markdown(&self, block_id: Option<&PyBlockId>) -> PyResult<String> {
+        match block_id {
+            Some(id) => ucp_translator_markdown::render_markdown_block(
+                &self.inner,
+                id.inner(),
+                Default::default(),
+            ),
+            None => ucp_translator_markdown::render_markdown(&self.inner),
+        }
+        .map_err(|e| crate::errors::PyUcpError::new_err(e.to_string()))
+    }
+
+    /// Pickle state: the document as compact portable JSON bytes.
+    fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let json = self
+            .inner
+            .to_portable_json()
+            .map_err(|e| crate::errors::PyUcpError::new_err(e.to_string()))?;
+        Ok(PyBytes::new_bound(py, json.as_bytes()))
+    }
+
+    /// Restore from pickle state written by `__getstate__`.
+    fn __setstate__(&mut self, state: &[u8]) -> PyResult<()> {
+        let json = std::str::from_utf8(state)
+            .map_err(|e| crate::errors::PyUcpError::new_err(e.to_string()))?;
+        self.inner = Document::from_portable_json(json)
+            .map_err(|e| crate::errors::PyUcpError::new_err(e.to_string()))?;
+        Ok(())
+    }
+
+    fn __copy__(&self) -> Self {
+        PyDocument::new(self.inner.clone())
+    }
+
+    /// Documents own all their data, so a deep copy is a clone.
+    fn __deepcopy__(&self, _memo: &Bound<'_, PyAny>) -> Self {
+        PyDocument::new(self.inner.clone())
+    }
+
+    /// Serialize to protobuf bytes.
+    fn to_proto<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let bytes = self
+            .inner
+            .to_proto()
+            .map_err(|e| crate::errors::PyUcpError::new_err(e.to_string()))?;
+        Ok(PyBytes::new_bound(py, &bytes))
+    }
+
+    /// Deserialize from protobuf bytes.
+    #[staticmethod]
+    fn from_proto(data: &[u8]) -> PyResult<Self> {
+        Document::from_proto(data)
+            .map(PyDocument::new)
+            .map_err(|e| crate::errors::PyUcpError::new_err(e.to_string()))
     }
 
     /// Get document version.
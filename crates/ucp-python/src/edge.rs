@@ -1,5 +1,6 @@
 //! Edge type wrappers for Python.
 
+use chrono::{DateTime, Utc};
 use pyo3::prelude::*;
 use std::str::FromStr;
 use ucm_core::{Edge, EdgeType};
@@ -139,6 +140,7 @@ impl From<&Edge> for PyEdge {
 impl PyEdge {
     /// Create a new edge.
     #[new]
+    #[pyo3(text_signature = "(edge_type, target)")]
     fn new(edge_type: PyEdgeType, target: &PyBlockId) -> Self {
         let et: EdgeType = edge_type.into();
         PyEdge(Edge::new(et, *target.inner()))
@@ -162,6 +164,24 @@ impl PyEdge {
         self.0.metadata.confidence
     }
 
+    /// Get the edge weight if set.
+    #[getter]
+    fn weight(&self) -> Option<f64> {
+        self.0.weight
+    }
+
+    /// Get the start of the validity window if set.
+    #[getter]
+    fn valid_from(&self) -> Option<DateTime<Utc>> {
+        self.0.valid_from
+    }
+
+    /// Get the end of the validity window if set.
+    #[getter]
+    fn valid_to(&self) -> Option<DateTime<Utc>> {
+        self.0.valid_to
+    }
+
     /// Get the description if set.
     #[getter]
     fn description(&self) -> Option<String> {
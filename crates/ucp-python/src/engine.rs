@@ -3,6 +3,7 @@
 //! Exposes the UCM Engine with transaction support, validation, and batch operations.
 
 use pyo3::prelude::*;
+use ucm_core::Document;
 use ucm_engine::engine::{Engine, EngineConfig};
 use ucm_engine::traversal::{
     NavigateDirection, TraversalConfig, TraversalEngine, TraversalFilter, TraversalOutput,
@@ -11,7 +12,7 @@ use ucm_engine::traversal::{
 use ucm_engine::validate::{ResourceLimits, ValidationPipeline, ValidationResult};
 
 use crate::document::PyDocument;
-use crate::types::PyBlockId;
+use crate::types::{hash_of, PyBlockId};
 
 /// Engine configuration.
 #[pyclass(name = "EngineConfig")]
@@ -23,6 +24,9 @@ pub struct PyEngineConfig {
 #[pymethods]
 impl PyEngineConfig {
     #[new]
+    #[pyo3(
+        text_signature = "(validate_on_operation=True, max_batch_size=10000, enable_transactions=True, enable_snapshots=True)"
+    )]
     #[pyo3(signature = (validate_on_operation=true, max_batch_size=10000, enable_transactions=true, enable_snapshots=true))]
     fn new(
         validate_on_operation: bool,
@@ -81,12 +85,20 @@ pub struct PyTransactionId {
 #[pymethods]
 impl PyTransactionId {
     fn __repr__(&self) -> String {
-        format!("TransactionId({})", self.inner)
+        format!("TransactionId(\"{}\")", self.inner)
     }
 
     fn __str__(&self) -> String {
         self.inner.clone()
     }
+
+    fn __hash__(&self) -> isize {
+        hash_of(&self.inner)
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
 }
 
 /// The main transformation engine with transaction support.
@@ -99,6 +111,7 @@ pub struct PyEngine {
 impl PyEngine {
     /// Create a new engine with default configuration.
     #[new]
+    #[pyo3(text_signature = "(config=None)")]
     #[pyo3(signature = (config=None))]
     fn new(config: Option<PyEngineConfig>) -> Self {
         let engine = match config {
@@ -114,6 +127,13 @@ impl PyEngine {
         PyValidationResult::from(result)
     }
 
+    /// Describe what UCL commands would do to a document, without executing them.
+    fn explain(&self, doc: &PyDocument, ucl: &str) -> PyResult<String> {
+        ucp_api::UcpClient::new()
+            .explain_with(&self.inner, doc.inner(), ucl)
+            .map_err(crate::errors::convert_error)
+    }
+
     /// Begin a new transaction.
     fn begin_transaction(&mut self) -> PyTransactionId {
         let id = self.inner.begin_transaction();
@@ -126,6 +146,15 @@ impl PyEngine {
         PyTransactionId { inner: id.0 }
     }
 
+    /// Commit a transaction, applying its queued operations to a document.
+    fn commit_transaction(
+        &mut self,
+        txn_id: &PyTransactionId,
+        doc: &mut PyDocument,
+    ) -> PyResult<()> {
+        self.commit(txn_id, doc.inner_mut())
+    }
+
     /// Rollback a transaction.
     fn rollback_transaction(&mut self, txn_id: &PyTransactionId) -> PyResult<()> {
         let id = ucm_engine::transaction::TransactionId(txn_id.inner.clone());
@@ -134,6 +163,36 @@ impl PyEngine {
             .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
     }
 
+    /// Start a transaction for use in a `with` block.
+    ///
+    /// It commits when the block exits normally and rolls back if it raises.
+    /// If `doc` is given, the document is restored to its state on entry when
+    /// the transaction rolls back.
+    #[pyo3(signature = (name=None, doc=None))]
+    fn transaction(
+        slf: Py<Self>,
+        name: Option<String>,
+        doc: Option<Py<PyDocument>>,
+    ) -> PyTransaction {
+        PyTransaction {
+            engine: slf,
+            name,
+            doc,
+            id: None,
+            saved: None,
+        }
+    }
+
+    /// Alias for `transaction()`.
+    #[pyo3(signature = (name=None, doc=None))]
+    fn transaction_context(
+        slf: Py<Self>,
+        name: Option<String>,
+        doc: Option<Py<PyDocument>>,
+    ) -> PyTransaction {
+        Self::transaction(slf, name, doc)
+    }
+
     /// Create a snapshot.
     #[pyo3(signature = (name, doc, description=None))]
     fn create_snapshot(
@@ -171,6 +230,92 @@ impl PyEngine {
     }
 }
 
+impl PyEngine {
+    fn commit(&mut self, txn_id: &PyTransactionId, doc: &mut Document) -> PyResult<()> {
+        let id = ucm_engine::transaction::TransactionId(txn_id.inner.clone());
+        self.inner
+            .commit_transaction(&id, doc)
+            .map(|_| ())
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+    }
+}
+
+/// Context manager returned by `Engine.transaction()`.
+#[pyclass(name = "Transaction")]
+pub struct PyTransaction {
+    engine: Py<PyEngine>,
+    name: Option<String>,
+    doc: Option<Py<PyDocument>>,
+    id: Option<PyTransactionId>,
+    /// Document state on entry, restored on rollback
+    saved: Option<Document>,
+}
+
+#[pymethods]
+impl PyTransaction {
+    /// The transaction id, once the transaction has started.
+    #[getter]
+    fn id(&self) -> Option<PyTransactionId> {
+        self.id.clone()
+    }
+
+    fn __enter__(&mut self, py: Python<'_>) -> PyResult<PyTransactionId> {
+        if self.id.is_some() {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(
+                "transaction already started",
+            ));
+        }
+        let mut engine = self.engine.bind(py).borrow_mut();
+        let id = match &self.name {
+            Some(name) => engine.begin_named_transaction(name),
+            None => engine.begin_transaction(),
+        };
+        self.saved = self
+            .doc
+            .as_ref()
+            .map(|doc| doc.bind(py).borrow().inner().clone());
+        self.id = Some(id.clone());
+        Ok(id)
+    }
+
+    /// Commit on success, roll back on exception. Never suppresses the exception.
+    #[pyo3(signature = (exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(
+        &mut self,
+        py: Python<'_>,
+        exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<bool> {
+        let Some(id) = self.id.as_ref() else {
+            return Ok(false);
+        };
+        let mut engine = self.engine.bind(py).borrow_mut();
+
+        if exc_type.map_or(true, |t| t.is_none()) {
+            match &self.doc {
+                Some(doc) => engine.commit(id, doc.bind(py).borrow_mut().inner_mut())?,
+                // Without a document nothing can have been queued, so there is nothing to apply
+                None => engine.commit(id, &mut Document::create())?,
+            }
+            self.saved = None;
+        } else {
+            engine.rollback_transaction(id)?;
+            if let (Some(doc), Some(saved)) = (&self.doc, self.saved.take()) {
+                *doc.bind(py).borrow_mut().inner_mut() = saved;
+            }
+        }
+        Ok(false)
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.id {
+            Some(id) => format!("Transaction({})", id.inner),
+            None => "Transaction(<not started>)".to_string(),
+        }
+    }
+}
+
 /// Resource limits for validation.
 #[pyclass(name = "ResourceLimits")]
 #[derive(Clone)]
@@ -181,6 +326,9 @@ pub struct PyResourceLimits {
 #[pymethods]
 impl PyResourceLimits {
     #[new]
+    #[pyo3(
+        text_signature = "(max_document_size=None, max_block_count=None, max_block_size=None, max_nesting_depth=None, max_edges_per_block=None)"
+    )]
     #[pyo3(signature = (
         max_document_size=None,
         max_block_count=None,
@@ -356,6 +504,7 @@ pub struct PyValidationPipeline {
 impl PyValidationPipeline {
     /// Create a new validation pipeline with default limits.
     #[new]
+    #[pyo3(text_signature = "(limits=None)")]
     #[pyo3(signature = (limits=None))]
     fn new(limits: Option<PyResourceLimits>) -> Self {
         let pipeline = match limits {
@@ -423,6 +572,9 @@ pub struct PyTraversalFilter {
 #[pymethods]
 impl PyTraversalFilter {
     #[new]
+    #[pyo3(
+        text_signature = "(include_roles=None, exclude_roles=None, include_tags=None, exclude_tags=None, content_pattern=None)"
+    )]
     #[pyo3(signature = (
         include_roles=None,
         exclude_roles=None,
@@ -477,6 +629,7 @@ pub struct PyTraversalConfig {
 #[pymethods]
 impl PyTraversalConfig {
     #[new]
+    #[pyo3(text_signature = "(max_depth=100, max_nodes=10000, include_orphans=False)")]
     #[pyo3(signature = (max_depth=100, max_nodes=10000, include_orphans=false))]
     fn new(max_depth: usize, max_nodes: usize, include_orphans: bool) -> Self {
         Self {
@@ -486,6 +639,8 @@ impl PyTraversalConfig {
                 default_preview_length: 100,
                 include_orphans,
                 cache_enabled: true,
+                use_edge_weights: false,
+                include_hyperedges: false,
             },
         }
     }
@@ -620,6 +775,7 @@ pub struct PyTraversalEngine {
 impl PyTraversalEngine {
     /// Create a new traversal engine.
     #[new]
+    #[pyo3(text_signature = "(config=None)")]
     #[pyo3(signature = (config=None))]
     fn new(config: Option<PyTraversalConfig>) -> Self {
         let engine = match config {
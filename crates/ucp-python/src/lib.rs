@@ -34,7 +34,7 @@ use content::PyContent;
 use document::PyDocument;
 use edge::{PyEdge, PyEdgeType};
 use engine::{
-    PyEngine, PyEngineConfig, PyResourceLimits, PyTransactionId, PyTraversalConfig,
+    PyEngine, PyEngineConfig, PyResourceLimits, PyTransaction, PyTransactionId, PyTraversalConfig,
     PyTraversalDirection, PyTraversalEngine, PyTraversalFilter, PyTraversalNode, PyTraversalResult,
     PyValidationIssue, PyValidationPipeline, PyValidationResult,
 };
@@ -43,7 +43,7 @@ use errors::{
     PyValidationError,
 };
 use graph::{PyGraph, PyGraphSession};
-use llm::{PyIdMapper, PyPromptBuilder, PyPromptPresets, PyUclCapability};
+use llm::{PyIdMapper, PyPromptBuilder, PyPromptBuilderMode, PyPromptPresets, PyUclCapability};
 use observe::{PyAuditEntry, PyEventBus, PyMetricsRecorder, PyUcpEvent};
 use section::{write_section, PyClearResult, PyDeletedContent, PyWriteSectionResult};
 use snapshot::{PySnapshotInfo, PySnapshotManager};
@@ -132,6 +132,35 @@ fn execute_ucl(doc: &mut PyDocument, ucl: &str) -> PyResult<Vec<PyBlockId>> {
         .collect())
 }
 
+/// Execute UCL that may use short IDs from an IdMapper prompt.
+///
+/// Returns the affected BlockIds and the `(short_id, BlockId)` pairs
+/// registered in the mapper for newly created blocks.
+#[pyfunction]
+#[allow(clippy::type_complexity)]
+fn execute_ucl_with_mapper(
+    doc: &mut PyDocument,
+    ucl: &str,
+    mapper: &mut PyIdMapper,
+) -> PyResult<(Vec<PyBlockId>, Vec<(u32, PyBlockId)>)> {
+    let client = ucp_api::UcpClient::new();
+    let execution = client
+        .execute_ucl_with_mapper(doc.inner_mut(), ucl, mapper.inner_mut())
+        .map_err(errors::convert_error)?;
+
+    let affected = execution
+        .results
+        .iter()
+        .flat_map(|r| r.affected_blocks.iter().map(|id| PyBlockId::from(*id)))
+        .collect();
+    let new_ids = execution
+        .new_ids
+        .into_iter()
+        .map(|(short, id)| (short, PyBlockId::from(id)))
+        .collect();
+    Ok((affected, new_ids))
+}
+
 /// Create a new empty document.
 #[pyfunction]
 #[pyo3(signature = (title=None))]
@@ -195,6 +224,7 @@ fn _core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyPromptBuilder>()?;
     m.add_class::<PyPromptPresets>()?;
     m.add_class::<PyUclCapability>()?;
+    m.add_class::<PyPromptBuilderMode>()?;
 
     // Snapshot management
     m.add_class::<PySnapshotManager>()?;
@@ -214,6 +244,7 @@ fn _core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Engine and validation classes
     m.add_class::<PyEngine>()?;
     m.add_class::<PyEngineConfig>()?;
+    m.add_class::<PyTransaction>()?;
     m.add_class::<PyTransactionId>()?;
     m.add_class::<PyResourceLimits>()?;
     m.add_class::<PyValidationPipeline>()?;
@@ -233,6 +264,7 @@ fn _core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(render_markdown, m)?)?;
     m.add_function(wrap_pyfunction!(parse_html, m)?)?;
     m.add_function(wrap_pyfunction!(execute_ucl, m)?)?;
+    m.add_function(wrap_pyfunction!(execute_ucl_with_mapper, m)?)?;
     m.add_function(wrap_pyfunction!(create, m)?)?;
 
     // Section functions
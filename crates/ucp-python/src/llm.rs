@@ -1,7 +1,7 @@
 //! LLM utilities wrapper for Python.
 
 use pyo3::prelude::*;
-use ucp_llm::{IdMapper, PromptBuilder, UclCapability};
+use ucp_llm::{IdMapper, PromptBuilder, PromptBuilderMode, PromptProjection, UclCapability};
 
 use crate::document::PyDocument;
 use crate::types::PyBlockId;
@@ -92,6 +92,35 @@ impl PyUclCapability {
     }
 }
 
+/// Output format a prompt asks the model to produce.
+#[pyclass(name = "PromptBuilderMode", eq, eq_int)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PyPromptBuilderMode {
+    Ucl = 0,
+    JsonCommands = 1,
+    CodeGraph = 2,
+}
+
+impl From<PyPromptBuilderMode> for PromptBuilderMode {
+    fn from(mode: PyPromptBuilderMode) -> Self {
+        match mode {
+            PyPromptBuilderMode::Ucl => PromptBuilderMode::Ucl,
+            PyPromptBuilderMode::JsonCommands => PromptBuilderMode::JsonCommands,
+            PyPromptBuilderMode::CodeGraph => PromptBuilderMode::CodeGraph,
+        }
+    }
+}
+
+impl From<PromptBuilderMode> for PyPromptBuilderMode {
+    fn from(mode: PromptBuilderMode) -> Self {
+        match mode {
+            PromptBuilderMode::Ucl => PyPromptBuilderMode::Ucl,
+            PromptBuilderMode::JsonCommands => PyPromptBuilderMode::JsonCommands,
+            PromptBuilderMode::CodeGraph => PyPromptBuilderMode::CodeGraph,
+        }
+    }
+}
+
 /// Bidirectional mapping between BlockIds and short numeric IDs.
 ///
 /// Useful for token-efficient LLM prompts by replacing long block IDs
@@ -101,10 +130,17 @@ pub struct PyIdMapper {
     inner: IdMapper,
 }
 
+impl PyIdMapper {
+    pub(crate) fn inner_mut(&mut self) -> &mut IdMapper {
+        &mut self.inner
+    }
+}
+
 #[pymethods]
 impl PyIdMapper {
     /// Create a new empty IdMapper.
     #[new]
+    #[pyo3(text_signature = "()")]
     fn new() -> Self {
         Self {
             inner: IdMapper::new(),
@@ -194,6 +230,7 @@ pub struct PyPromptBuilder {
 impl PyPromptBuilder {
     /// Create a new prompt builder with no capabilities.
     #[new]
+    #[pyo3(text_signature = "()")]
     fn new() -> Self {
         Self {
             inner: PromptBuilder::new(),
@@ -208,6 +245,15 @@ impl PyPromptBuilder {
         }
     }
 
+    /// Create a builder for a CodeGraph document: all capabilities, CodeGraph
+    /// mode, and a task context naming the repository.
+    #[staticmethod]
+    fn for_code_graph(doc: &PyDocument) -> Self {
+        Self {
+            inner: PromptBuilder::for_code_graph(doc.inner()),
+        }
+    }
+
     /// Add a single capability.
     fn with_capability(&self, cap: PyUclCapability) -> Self {
         Self {
@@ -260,6 +306,19 @@ impl PyPromptBuilder {
         }
     }
 
+    /// Set the output format the prompt asks for.
+    fn with_mode(&self, mode: PyPromptBuilderMode) -> Self {
+        Self {
+            inner: self.inner.clone().with_mode(mode.into()),
+        }
+    }
+
+    /// Get the output mode.
+    #[getter]
+    fn mode(&self) -> PyPromptBuilderMode {
+        self.inner.mode().into()
+    }
+
     /// Build the system prompt.
     fn build_system_prompt(&self) -> String {
         self.inner.build_system_prompt()
@@ -270,6 +329,35 @@ impl PyPromptBuilder {
         self.inner.build_prompt(document_description, task)
     }
 
+    /// Render a document folded around focus blocks.
+    ///
+    /// Focus blocks are shown in full, their ancestors as headers, and
+    /// everything else is folded into one-line summaries. In CodeGraph mode
+    /// the document is rendered as symbols grouped by file instead.
+    #[pyo3(signature = (doc, focus, max_tokens=None))]
+    fn project_document(
+        &self,
+        doc: &PyDocument,
+        focus: Vec<PyBlockId>,
+        max_tokens: Option<usize>,
+    ) -> String {
+        self.inner
+            .describe_document(doc.inner(), &projection(&focus, max_tokens))
+    }
+
+    /// Build a complete prompt with the document folded around focus blocks.
+    #[pyo3(signature = (doc, focus, task, max_tokens=None))]
+    fn build_projected_prompt(
+        &self,
+        doc: &PyDocument,
+        focus: Vec<PyBlockId>,
+        task: &str,
+        max_tokens: Option<usize>,
+    ) -> String {
+        self.inner
+            .build_projected_prompt(doc.inner(), &projection(&focus, max_tokens), task)
+    }
+
     /// Check if a capability is enabled.
     fn has_capability(&self, cap: PyUclCapability) -> bool {
         self.inner.has_capability(cap.into())
@@ -289,6 +377,14 @@ impl PyPromptBuilder {
     }
 }
 
+fn projection(focus: &[PyBlockId], max_tokens: Option<usize>) -> PromptProjection {
+    let projection = PromptProjection::new().with_focus(focus.iter().map(|id| *id.inner()));
+    match max_tokens {
+        Some(max) => projection.with_max_tokens(max),
+        None => projection,
+    }
+}
+
 /// Preset prompt configurations for common use cases.
 #[pyclass(name = "PromptPresets")]
 pub struct PyPromptPresets;
@@ -9,6 +9,7 @@ use ucp_observe::{AuditEntry, EventBus, MetricsRecorder, UcpEvent};
 #[pyclass(name = "UcpEvent")]
 #[derive(Clone)]
 pub struct PyUcpEvent {
+    inner: UcpEvent,
     event_type: String,
     document_id: Option<String>,
     timestamp: String,
@@ -99,6 +100,7 @@ impl PyUcpEvent {
 impl PyUcpEvent {
     fn from_event(event: &UcpEvent) -> Self {
         Self {
+            inner: event.clone(),
             event_type: event.event_type().to_string(),
             document_id: event.document_id().map(|s| s.to_string()),
             timestamp: event.timestamp().to_rfc3339(),
@@ -117,6 +119,7 @@ pub struct PyEventBus {
 impl PyEventBus {
     /// Create a new event bus.
     #[new]
+    #[pyo3(text_signature = "()")]
     fn new() -> Self {
         Self {
             inner: Arc::new(EventBus::new()),
@@ -129,6 +132,45 @@ impl PyEventBus {
         self.inner.subscriber_count()
     }
 
+    /// Call `callback(event)` synchronously for every published event.
+    fn on_event(&self, callback: PyObject) {
+        self.inner.subscribe(Arc::new(move |event: &UcpEvent| {
+            Python::with_gil(|py| {
+                if let Err(err) = callback.call1(py, (PyUcpEvent::from_event(event),)) {
+                    err.write_unraisable_bound(py, Some(callback.bind(py)));
+                }
+            })
+        }));
+    }
+
+    /// Schedule `await coro_fn(event)` on the running asyncio loop for every
+    /// published event. Publishing returns without waiting for the coroutine.
+    fn on_event_async(&self, py: Python<'_>, coro_fn: PyObject) -> PyResult<()> {
+        let event_loop: PyObject = py
+            .import_bound("asyncio")?
+            .call_method0("get_running_loop")?
+            .unbind();
+        self.inner.subscribe(Arc::new(move |event: &UcpEvent| {
+            Python::with_gil(|py| {
+                let scheduled = coro_fn
+                    .call1(py, (PyUcpEvent::from_event(event),))
+                    .and_then(|coro| {
+                        let create_task = event_loop.getattr(py, "create_task")?;
+                        event_loop.call_method1(py, "call_soon_threadsafe", (create_task, coro))
+                    });
+                if let Err(err) = scheduled {
+                    err.write_unraisable_bound(py, Some(coro_fn.bind(py)));
+                }
+            })
+        }));
+        Ok(())
+    }
+
+    /// Publish an event to all subscribers.
+    fn publish(&self, event: &PyUcpEvent) {
+        self.inner.publish(&event.inner);
+    }
+
     fn __repr__(&self) -> String {
         format!("EventBus(subscribers={})", self.subscriber_count())
     }
@@ -145,6 +187,7 @@ pub struct PyAuditEntry {
 impl PyAuditEntry {
     /// Create a new audit entry.
     #[new]
+    #[pyo3(text_signature = "(operation, document_id)")]
     fn new(operation: &str, document_id: &str) -> Self {
         Self {
             inner: AuditEntry::new(operation, document_id),
@@ -238,6 +281,7 @@ pub struct PyMetricsRecorder {
 impl PyMetricsRecorder {
     /// Create a new metrics recorder.
     #[new]
+    #[pyo3(text_signature = "()")]
     fn new() -> Self {
         Self {
             inner: MetricsRecorder::new(),
@@ -70,6 +70,7 @@ pub struct PySnapshotManager {
 impl PySnapshotManager {
     /// Create a new snapshot manager.
     #[new]
+    #[pyo3(text_signature = "(max_snapshots=None)")]
     #[pyo3(signature = (max_snapshots=None))]
     fn new(max_snapshots: Option<usize>) -> Self {
         let inner = if let Some(max) = max_snapshots {
@@ -5,6 +5,13 @@ use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use ucm_core::BlockId;
 
+/// Python hash of any hashable id.
+pub(crate) fn hash_of<T: Hash>(value: &T) -> isize {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish() as isize
+}
+
 /// A content-addressed block identifier.
 #[pyclass(name = "BlockId", frozen)]
 #[derive(Clone)]
@@ -26,6 +33,7 @@ impl From<BlockId> for PyBlockId {
 impl PyBlockId {
     /// Create a BlockId from a hex string (e.g., "blk_0102030405060708090a0b0c").
     #[new]
+    #[pyo3(text_signature = "(s)")]
     fn new(s: &str) -> PyResult<Self> {
         s.parse::<BlockId>()
             .map(PyBlockId)
@@ -53,13 +61,11 @@ impl PyBlockId {
     }
 
     fn __repr__(&self) -> String {
-        format!("BlockId('{}')", self.0)
+        format!("BlockId(\"{}\")", self.0)
     }
 
     fn __hash__(&self) -> isize {
-        let mut hasher = DefaultHasher::new();
-        self.0.hash(&mut hasher);
-        hasher.finish() as isize
+        hash_of(&self.0)
     }
 
     fn __eq__(&self, other: &Self) -> bool {
@@ -119,3 +119,21 @@ def test_codegraph_session_observability_persistence_and_estimates(tmp_path):
     restored = graph.load_session(str(session_path))
     assert restored.session_id() == session.session_id()
     assert restored.selected_block_ids() == session.selected_block_ids()
+
+
+def test_prompt_builder_code_graph_mode(tmp_path):
+    import ucp
+
+    _write_repo(tmp_path)
+    doc = ucp.CodeGraph.build(str(tmp_path)).to_document()
+
+    builder = ucp.PromptBuilder.for_code_graph(doc)
+    assert builder.mode == ucp.PromptBuilderMode.CodeGraph
+
+    rendered = builder.project_document(doc, [])
+    assert "] file src/lib.rs [rust]" in rendered
+    assert "function add(a: i32, b: i32) -> i32" in rendered
+    assert "calls symbol:src/util.rs::util" in rendered
+
+    plain = ucp.PromptBuilder().with_mode(ucp.PromptBuilderMode.Ucl)
+    assert plain.mode == ucp.PromptBuilderMode.Ucl
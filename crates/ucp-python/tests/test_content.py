@@ -180,6 +180,18 @@ class TestContentCreation:
         assert "text" in d
         assert d["text"] == "Test"
 
+    def test_repr_previews_content(self):
+        """Test that repr shows the type and a short preview."""
+        import ucp
+
+        assert repr(ucp.Content.text("Hi")) == 'Content.text("Hi")'
+        assert repr(ucp.Content.code("rust", "fn main() {}")) == (
+            'Content.code("rust", "fn main() {}")'
+        )
+
+        long = ucp.Content.text("é" * 80)
+        assert repr(long) == 'Content.text("' + "é" * 50 + '...")'
+
 
 class TestContentInDocument:
     """Test content within documents."""
@@ -1,5 +1,16 @@
 """Tests for Document operations."""
 
+import copy
+import json
+import os
+import pickle
+import subprocess
+import sys
+
+import pytest
+
+import ucp
+
 
 class TestDocumentCreation:
     """Test document creation and basic properties."""
@@ -96,6 +107,22 @@ class TestBlockOperations:
         block = empty_doc.get_block(block_id)
         assert block.content_type == "text"
 
+    def test_intern_block_reuses_identical_content(self, empty_doc):
+        """Test interning a block whose content is already in the document."""
+        import ucp
+
+        source = ucp.Document.create()
+        intro = source.add_block(source.root_id, "Repeated", role="intro")
+        body = source.add_block(source.root_id, "Repeated", role="body")
+
+        section = empty_doc.add_block(empty_doc.root_id, "Section", role="heading1")
+        interned = empty_doc.intern_block(source.get_block(intro), section)
+        assert interned == intro
+        assert empty_doc.intern_block(source.get_block(body)) == interned
+        assert empty_doc.block_count() == 3
+        assert empty_doc.children(section) == [interned]
+        assert interned in empty_doc.children(empty_doc.root_id)
+
     def test_get_block(self, doc_with_blocks):
         """Test getting a block by ID."""
         doc, root, block1, block2, block3 = doc_with_blocks
@@ -317,6 +344,42 @@ class TestTags:
         assert block.label == "new-label"
 
 
+class TestBlockId:
+    """Test BlockId value semantics."""
+
+    def test_equality_and_hash(self, doc_with_blocks):
+        """Test that equal ids compare and hash the same."""
+        doc, root, block1, block2, block3 = doc_with_blocks
+
+        same = ucp.BlockId(str(block1))
+        assert same == block1
+        assert same != block2
+        assert hash(same) == hash(block1)
+        assert len({block1, same, block2}) == 2
+        assert {block1: "first"}[same] == "first"
+        assert block1 != str(block1)
+
+    def test_repr_and_str(self):
+        """Test the repr and string forms."""
+        block_id = ucp.BlockId("blk_0102030405060708090a0b0c")
+
+        assert str(block_id) == "blk_0102030405060708090a0b0c"
+        assert repr(block_id) == 'BlockId("blk_0102030405060708090a0b0c")'
+
+    def test_block_repr_shows_metadata(self, empty_doc):
+        """Test that Block repr includes role, label and tags."""
+        block_id = empty_doc.add_block(
+            empty_doc.root_id, "Hello", role="paragraph", label="greeting"
+        )
+        empty_doc.add_tag(block_id, "intro")
+
+        text = repr(empty_doc.get_block(block_id))
+        assert "role=paragraph" in text
+        assert 'label="greeting"' in text
+        assert "tags=[\"intro\"]" in text
+        assert 'text="Hello"' in text
+
+
 class TestSerialization:
     """Test document serialization."""
 
@@ -329,6 +392,113 @@ class TestSerialization:
         assert "blocks" in json_str
         assert "structure" in json_str
 
+    def test_json_round_trip(self, doc_with_blocks):
+        """Test serializing document to portable JSON and back."""
+        doc, root, block1, block2, block3 = doc_with_blocks
+
+        data = json.loads(doc.to_json())
+        assert data["schema_version"] == 1
+
+        restored = ucp.Document.from_json(doc.to_json())
+        assert restored.id == doc.id
+        assert restored.block_count == doc.block_count
+        assert restored.children(root) == doc.children(root)
+        assert json.loads(restored.to_json()) == data
+
+    def test_from_json_ignores_unknown_fields(self, doc_with_blocks):
+        """Test that fields from newer writers are ignored."""
+        doc, root, block1, block2, block3 = doc_with_blocks
+
+        data = json.loads(doc.to_json())
+        data["added_later"] = {"x": 1}
+        del data["schema_version"]
+
+        restored = ucp.Document.from_json(json.dumps(data))
+        assert restored.block_count == doc.block_count
+
+    def test_from_json_rejects_garbage(self):
+        """Test that invalid JSON raises an error."""
+        with pytest.raises(ucp.UcpError):
+            ucp.Document.from_json("{not json")
+
+    def test_pickle_round_trip(self, doc_with_blocks):
+        """Test pickling and unpickling a document."""
+        doc, root, block1, block2, block3 = doc_with_blocks
+        doc.title = "Pickled"
+
+        restored = pickle.loads(pickle.dumps(doc))
+        assert isinstance(restored, ucp.Document)
+        assert restored.id == doc.id
+        assert restored.title == "Pickled"
+        assert restored.children(root) == doc.children(root)
+        assert json.loads(restored.to_json()) == json.loads(doc.to_json())
+
+    def test_pickle_in_subprocess(self, doc_with_blocks):
+        """Test unpickling a document in another interpreter."""
+        doc, root, block1, block2, block3 = doc_with_blocks
+
+        script = (
+            "import json, pickle, sys\n"
+            "doc = pickle.loads(sys.stdin.buffer.read())\n"
+            "print(json.dumps({'count': doc.block_count, 'json': json.loads(doc.to_json())}))\n"
+        )
+        env = dict(os.environ, PYTHONPATH=os.pathsep.join(p for p in sys.path if p))
+        result = subprocess.run(
+            [sys.executable, "-c", script],
+            input=pickle.dumps(doc),
+            capture_output=True,
+            env=env,
+            check=True,
+        )
+        out = json.loads(result.stdout)
+        assert out["count"] == doc.block_count
+        assert out["json"] == json.loads(doc.to_json())
+
+    def test_setstate_rejects_garbage(self):
+        """Test that corrupt pickle state raises an error."""
+        with pytest.raises(ucp.UcpError):
+            ucp.Document().__setstate__(b"{not json")
+
+    def test_copy_and_deepcopy(self, doc_with_blocks):
+        """Test that copies are independent of the original."""
+        doc, root, block1, block2, block3 = doc_with_blocks
+
+        for clone in (copy.copy(doc), copy.deepcopy(doc)):
+            assert clone.id == doc.id
+            assert clone.block_count == doc.block_count
+            clone.add_block(root, "Only in the copy")
+            assert clone.block_count == doc.block_count + 1
+
+    def test_to_markdown_subtree(self):
+        """Test rendering a whole document or a single section."""
+        doc = ucp.parse("# Guide\n\n## Install\n\nRun it.\n")
+        guide = doc.children(doc.root_id)[0]
+        install = doc.children(guide)[0]
+
+        assert doc.to_markdown() == "# Guide\n\n## Install\n\nRun it.\n"
+        assert doc.to_markdown(install) == "# Install\n\nRun it.\n"
+        assert doc.to_markdown(block_id=doc.children(install)[0]) == "Run it.\n"
+
+        with pytest.raises(ucp.UcpError):
+            doc.to_markdown(ucp.BlockId("blk_000000000000000000000000"))
+
+    def test_proto_round_trip(self, doc_with_blocks):
+        """Test serializing document to protobuf and back."""
+        doc, root, block1, block2, block3 = doc_with_blocks
+
+        data = doc.to_proto()
+        assert isinstance(data, bytes)
+
+        restored = ucp.Document.from_proto(data)
+        assert restored.id == doc.id
+        assert restored.block_count == doc.block_count
+        assert restored.children(root) == doc.children(root)
+
+    def test_from_proto_rejects_garbage(self):
+        """Test that invalid protobuf bytes raise an error."""
+        with pytest.raises(ucp.UcpError):
+            ucp.Document.from_proto(b"\xff\xff\xff")
+
     def test_block_ids(self, doc_with_blocks):
         """Test getting all block IDs."""
         doc, root, block1, block2, block3 = doc_with_blocks
@@ -357,6 +527,30 @@ class TestValidation:
         # May have some issues but shouldn't crash
         assert isinstance(issues, list)
 
+    def test_validate_schema(self, empty_doc):
+        """Test validating against a document schema."""
+        schema = json.dumps({"name": "note", "required_roles": ["intro", "conclusion"]})
+        empty_doc.add_block(empty_doc.root_id, "Introduction", role="intro")
+
+        result = empty_doc.validate_schema(schema)
+        assert not result.valid
+        assert len(result.errors()) == 1
+        assert "conclusion" in result.errors()[0].message
+
+        with pytest.raises(ucp.UcpError):
+            empty_doc.validate_schema("{")
+
+    def test_statistics(self, doc_with_blocks):
+        """Test document statistics."""
+        doc, root, block1, block2, block3 = doc_with_blocks
+
+        stats = doc.statistics()
+        assert stats["total_blocks"] == 4
+        assert stats["block_count_by_type"]["text"] == 4
+        assert stats["max_depth"] == 2
+        assert stats["orphan_blocks"] == 0
+        assert stats["snapshot_count"] == 0
+
     def test_find_orphans(self, doc_with_blocks):
         """Test finding orphaned blocks."""
         doc, root, block1, block2, block3 = doc_with_blocks
@@ -29,6 +29,29 @@ class TestEngine:
         engine = ucp.Engine(config)
         assert engine is not None
 
+    def test_constructor_signatures(self):
+        """Test that constructors expose their signature to help()."""
+        import ucp
+
+        assert ucp.EngineConfig.__text_signature__ == (
+            "(validate_on_operation=True, max_batch_size=10000, "
+            "enable_transactions=True, enable_snapshots=True)"
+        )
+        assert ucp.Engine.__text_signature__ == "(config=None)"
+
+    def test_transaction_ids_are_hashable(self):
+        """Test that transaction ids can be compared and used as keys."""
+        import ucp
+
+        engine = ucp.Engine()
+        first = engine.begin_transaction()
+        second = engine.begin_transaction()
+
+        assert first == first
+        assert first != second
+        assert {first: "a", second: "b"}[first] == "a"
+        assert repr(first) == f'TransactionId("{first}")'
+
     def test_engine_validate(self):
         """Test validating a document."""
         import ucp
@@ -40,6 +63,18 @@ class TestEngine:
         assert result.valid is True
         assert len(result.issues) == 0
 
+    def test_engine_explain(self):
+        """Test describing UCL without executing it."""
+        import ucp
+
+        engine = ucp.Engine()
+        doc = ucp.create("Test")
+        block = doc.add_block(doc.root_id, "Intro", label="Introduction")
+
+        explanation = engine.explain(doc, f"EXPLAIN DELETE {block} CASCADE")
+        assert explanation == f"Would delete {block} 'Introduction' and its 0 descendants"
+        assert doc.get_block(block) is not None
+
     def test_engine_begin_transaction(self):
         """Test beginning a transaction."""
         import ucp
@@ -66,6 +101,50 @@ class TestEngine:
         engine.rollback_transaction(txn_id)
         # Should not raise
 
+    def test_transaction_context_commits(self):
+        """Test that a with block commits and keeps changes."""
+        import ucp
+
+        engine = ucp.Engine()
+        doc = ucp.parse("# Title")
+        before = doc.block_count
+
+        with engine.transaction("edit", doc=doc) as txn_id:
+            assert str(txn_id) == "edit"
+            doc.add_block(doc.root_id, "Kept")
+
+        assert doc.block_count == before + 1
+
+    def test_transaction_context_rolls_back_on_exception(self):
+        """Test that an exception inside the with block undoes block additions."""
+        import pytest
+        import ucp
+
+        engine = ucp.Engine()
+        doc = ucp.parse("# Title\n\nBody")
+        before = doc.block_count
+        markdown = ucp.render(doc)
+
+        with pytest.raises(ValueError, match="boom"):
+            with engine.transaction(doc=doc):
+                doc.add_block(doc.root_id, "First")
+                doc.add_block(doc.root_id, "Second")
+                raise ValueError("boom")
+
+        assert doc.block_count == before
+        assert ucp.render(doc) == markdown
+
+    def test_transaction_context_alias(self):
+        """Test transaction_context() as an alias without a document."""
+        import ucp
+
+        engine = ucp.Engine()
+        txn = engine.transaction_context()
+        assert txn.id is None
+        with txn as txn_id:
+            assert str(txn_id).startswith("txn_")
+        assert str(txn.id) == str(txn_id)
+
     def test_engine_snapshots(self):
         """Test snapshot creation and restoration."""
         import ucp
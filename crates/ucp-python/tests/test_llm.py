@@ -1,5 +1,7 @@
 """Tests for LLM utilities (IdMapper, PromptBuilder)."""
 
+import pytest
+
 
 class TestIdMapper:
     """Test IdMapper for token-efficient LLM prompts."""
@@ -110,6 +112,26 @@ class TestIdMapper:
         # Short ID should be expanded
         assert str(block1) in expanded
 
+    def test_execute_ucl_with_mapper(self, doc_with_blocks):
+        """Test executing short-ID UCL without string replacement."""
+        import ucp
+
+        doc, root, block1, block2, block3 = doc_with_blocks
+
+        mapper = ucp.IdMapper.from_document(doc)
+        short_id = mapper.to_short_id(block1)
+        ucl = f'EDIT {short_id} SET text = "hello"\nAPPEND {short_id} text :: "new"'
+        affected, new_ids = ucp.execute_ucl_with_mapper(doc, ucl, mapper)
+
+        assert block1 in affected
+        assert len(new_ids) == 1
+        new_short, new_block = new_ids[0]
+        assert mapper.to_block_id(new_short) == new_block
+        assert doc.get_block(block1).content.as_text() == "hello"
+
+        with pytest.raises(Exception, match="short id 99"):
+            ucp.execute_ucl_with_mapper(doc, "DELETE 99", mapper)
+
     def test_estimate_token_savings(self, doc_with_blocks):
         """Test estimating token savings."""
         import ucp
@@ -289,6 +311,43 @@ class TestPromptBuilder:
         assert "Task" in prompt
         assert "Edit block 2" in prompt
 
+    def test_project_document(self, doc_with_blocks):
+        """Test folding a document around focus blocks."""
+        import ucp
+
+        doc, root, block1, block2, block3 = doc_with_blocks
+
+        builder = ucp.PromptBuilder().with_capability(ucp.UclCapability.Edit)
+        projection = builder.project_document(doc, [block2])
+
+        assert "Second paragraph" in projection
+        assert "Nested block" not in projection
+        assert "2 blocks omitted" in projection
+
+    def test_project_document_max_tokens(self, doc_with_blocks):
+        """Test that projection respects the token cap."""
+        import ucp
+
+        doc, root, block1, block2, block3 = doc_with_blocks
+
+        builder = ucp.PromptBuilder().with_capability(ucp.UclCapability.Edit)
+        projection = builder.project_document(doc, [block2], max_tokens=12)
+
+        assert len(projection) // 4 <= 12
+
+    def test_build_projected_prompt_short_ids(self, doc_with_blocks):
+        """Test projected prompt uses short IDs when enabled."""
+        import ucp
+
+        doc, root, block1, block2, block3 = doc_with_blocks
+
+        builder = ucp.PromptPresets.token_efficient()
+        prompt = builder.build_projected_prompt(doc, [block3], "Edit the note")
+
+        assert "Nested block" in prompt
+        assert "blk_" not in prompt
+        assert "Edit the note" in prompt
+
 
 class TestPromptPresets:
     """Test preset prompt configurations."""
@@ -420,3 +420,48 @@ class TestObservability:
 
         bus = ucp.EventBus()
         assert bus.subscriber_count == 0
+
+    def test_event_bus_sync_callback(self):
+        """Test that sync callbacks run during publish."""
+        import ucp
+
+        bus = ucp.EventBus()
+        seen = []
+        bus.on_event(lambda event: seen.append(event.event_type))
+        assert bus.subscriber_count == 1
+
+        bus.publish(ucp.UcpEvent.document_created("doc_1"))
+        assert seen == ["document_created"]
+
+    def test_event_bus_async_callbacks_run_after_publish(self):
+        """Test that async callbacks are scheduled, not awaited, by publish."""
+        import asyncio
+
+        import ucp
+
+        async def main():
+            bus = ucp.EventBus()
+            seen = []
+
+            async def record(event):
+                await asyncio.sleep(0)
+                seen.append(event.document_id)
+
+            bus.on_event_async(record)
+            for i in range(3):
+                bus.publish(ucp.UcpEvent.block_added(f"doc_{i}", "blk_1", "root", "text"))
+            # publish returned before any coroutine ran
+            assert seen == []
+
+            for _ in range(5):
+                await asyncio.sleep(0)
+            return seen
+
+        assert asyncio.run(main()) == ["doc_0", "doc_1", "doc_2"]
+
+    def test_event_bus_async_requires_running_loop(self):
+        """Test that async callbacks need a running event loop."""
+        import ucp
+
+        with pytest.raises(RuntimeError):
+            ucp.EventBus().on_event_async(lambda event: None)
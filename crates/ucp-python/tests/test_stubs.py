@@ -0,0 +1,73 @@
+"""Tests that the ucp._core type stubs match the compiled module."""
+
+import ast
+import importlib.util
+import subprocess
+import sys
+from pathlib import Path
+
+import pytest
+
+STUB_PATH = Path(__file__).resolve().parent.parent / "python" / "ucp" / "_core.pyi"
+
+
+def _stub_names():
+    """Map each top-level stub name to the set of members it declares."""
+    tree = ast.parse(STUB_PATH.read_text())
+    names = {}
+    for node in tree.body:
+        if isinstance(node, ast.ClassDef):
+            members = set()
+            for item in node.body:
+                if isinstance(item, (ast.FunctionDef, ast.AsyncFunctionDef)):
+                    members.add(item.name)
+                elif isinstance(item, ast.AnnAssign) and isinstance(item.target, ast.Name):
+                    members.add(item.target.id)
+            names[node.name] = members
+        elif isinstance(node, ast.FunctionDef):
+            names[node.name] = set()
+    return names
+
+
+class TestStubs:
+    """Keep _core.pyi in sync with the extension module."""
+
+    def test_stub_parses(self):
+        """The stub file is valid Python syntax."""
+        ast.parse(STUB_PATH.read_text())
+
+    def test_every_export_is_stubbed(self):
+        """Every public class and function in ucp._core has a stub."""
+        from ucp import _core
+
+        stubbed = _stub_names()
+        public = [n for n in dir(_core) if not n.startswith("_")]
+        missing = [n for n in public if n not in stubbed]
+        assert missing == [], f"missing from _core.pyi: {missing}"
+
+    def test_every_member_is_stubbed(self):
+        """Every public method and attribute of each class has a stub."""
+        from ucp import _core
+
+        stubbed = _stub_names()
+        missing = []
+        for name, members in stubbed.items():
+            obj = getattr(_core, name, None)
+            if not isinstance(obj, type) or issubclass(obj, BaseException):
+                continue
+            for attr in dir(obj):
+                if attr.startswith("_") or attr in members:
+                    continue
+                missing.append(f"{name}.{attr}")
+        assert missing == [], f"missing from _core.pyi: {missing}"
+
+    def test_stub_passes_mypy_strict(self):
+        """The stub type-checks under mypy --strict when mypy is installed."""
+        if importlib.util.find_spec("mypy") is None:
+            pytest.skip("mypy is not installed")
+        result = subprocess.run(
+            [sys.executable, "-m", "mypy", "--strict", str(STUB_PATH)],
+            capture_output=True,
+            text=True,
+        )
+        assert result.returncode == 0, result.stdout + result.stderr
@@ -0,0 +1,148 @@
+//! CRDT document wrapper for WASM.
+
+use ucm_crdt::{CrdtDocument, Op};
+use wasm_bindgen::prelude::*;
+
+use crate::Document;
+
+fn parse_id(id: &str) -> Result<ucm_core::BlockId, JsValue> {
+    id.parse()
+        .map_err(|_| JsValue::from_str(&format!("Invalid block ID: {}", id)))
+}
+
+fn crdt_err(err: ucm_crdt::CrdtError) -> JsValue {
+    JsValue::from_str(&err.to_string())
+}
+
+/// A document that several peers can edit concurrently and merge without conflicts.
+#[wasm_bindgen(js_name = CrdtDocument)]
+pub struct WasmCrdtDocument {
+    inner: CrdtDocument,
+}
+
+#[wasm_bindgen(js_class = CrdtDocument)]
+impl WasmCrdtDocument {
+    /// Start tracking edits to a document as the given actor.
+    #[wasm_bindgen(constructor)]
+    pub fn new(doc: &Document, actor: &str) -> Self {
+        Self {
+            inner: CrdtDocument::new(doc.inner().clone(), actor),
+        }
+    }
+
+    /// Create a replica for another peer, sharing base and history.
+    pub fn fork(&self, actor: &str) -> WasmCrdtDocument {
+        Self {
+            inner: self.inner.fork(actor),
+        }
+    }
+
+    /// This peer's actor id.
+    #[wasm_bindgen(getter)]
+    pub fn actor(&self) -> String {
+        self.inner.actor().to_string()
+    }
+
+    /// Current Lamport clock.
+    #[wasm_bindgen(getter)]
+    pub fn clock(&self) -> f64 {
+        self.inner.clock() as f64
+    }
+
+    /// Number of known operations.
+    #[wasm_bindgen(getter, js_name = opCount)]
+    pub fn op_count(&self) -> usize {
+        self.inner.ops().len()
+    }
+
+    /// Append a text block under a parent. Returns the new block ID.
+    #[wasm_bindgen(js_name = insertBlock)]
+    pub fn insert_block(
+        &mut self,
+        parent_id: &str,
+        content: &str,
+        role: Option<String>,
+        label: Option<String>,
+    ) -> Result<String, JsValue> {
+        let parent = parse_id(parent_id)?;
+        let mut block = ucm_core::Block::new(ucm_core::Content::text(content), role.as_deref());
+        if let Some(l) = label {
+            block.metadata.label = Some(l);
+        }
+        let id = self.inner.insert_block(&parent, block).map_err(crdt_err)?;
+        Ok(id.to_string())
+    }
+
+    /// Replace a block's text (last write wins).
+    #[wasm_bindgen(js_name = setText)]
+    pub fn set_text(&mut self, id: &str, text: &str) -> Result<(), JsValue> {
+        self.inner
+            .set_content(&parse_id(id)?, ucm_core::Content::text(text))
+            .map_err(crdt_err)
+    }
+
+    /// Set or clear a block's label (last write wins).
+    #[wasm_bindgen(js_name = setLabel)]
+    pub fn set_label(&mut self, id: &str, label: Option<String>) -> Result<(), JsValue> {
+        self.inner
+            .set_label(&parse_id(id)?, label)
+            .map_err(crdt_err)
+    }
+
+    /// Set or clear the document title (last write wins).
+    #[wasm_bindgen(js_name = setTitle)]
+    pub fn set_title(&mut self, title: Option<String>) {
+        self.inner.set_title(title);
+    }
+
+    /// Add a tag to a block.
+    #[wasm_bindgen(js_name = addTag)]
+    pub fn add_tag(&mut self, id: &str, tag: &str) -> Result<(), JsValue> {
+        self.inner.add_tag(&parse_id(id)?, tag).map_err(crdt_err)
+    }
+
+    /// Remove a tag from a block. Concurrent adds from other peers survive.
+    #[wasm_bindgen(js_name = removeTag)]
+    pub fn remove_tag(&mut self, id: &str, tag: &str) -> Result<(), JsValue> {
+        self.inner.remove_tag(&parse_id(id)?, tag).map_err(crdt_err)
+    }
+
+    /// Move a block to the end of a new parent's children.
+    #[wasm_bindgen(js_name = moveBlock)]
+    pub fn move_block(&mut self, id: &str, new_parent_id: &str) -> Result<(), JsValue> {
+        self.inner
+            .move_block(&parse_id(id)?, &parse_id(new_parent_id)?)
+            .map_err(crdt_err)
+    }
+
+    /// Delete a block and its descendants.
+    #[wasm_bindgen(js_name = deleteBlock)]
+    pub fn delete_block(&mut self, id: &str) -> Result<(), JsValue> {
+        self.inner.delete_block(&parse_id(id)?).map_err(crdt_err)
+    }
+
+    /// Merge another peer's operations. Returns the number of new operations applied.
+    pub fn merge(&mut self, other: &WasmCrdtDocument) -> Result<usize, JsValue> {
+        self.inner.merge(&other.inner).map_err(crdt_err)
+    }
+
+    /// Get all known operations as plain objects (for sending to other peers).
+    pub fn ops(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(self.inner.ops())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Apply operations received from another peer. Returns the number of new operations applied.
+    #[wasm_bindgen(js_name = applyOps)]
+    pub fn apply_ops(&mut self, ops: JsValue) -> Result<usize, JsValue> {
+        let ops: Vec<Op> =
+            serde_wasm_bindgen::from_value(ops).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(self.inner.apply_ops(ops))
+    }
+
+    /// Snapshot of the merged state.
+    #[wasm_bindgen(js_name = toDocument)]
+    pub fn to_document(&self) -> Document {
+        Document::new(self.inner.to_document())
+    }
+}
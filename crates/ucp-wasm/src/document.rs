@@ -405,111 +405,145 @@ impl Document {
         serde_wasm_bindgen::to_value(&issues).unwrap_or(JsValue::NULL)
     }
 
-    /// Serialize to JSON object.
+    /// Serialize to protobuf bytes.
+    #[wasm_bindgen(js_name = toProto)]
+    pub fn to_proto(&self) -> Result<Vec<u8>, JsValue> {
+        use ucm_proto::ProtoCodec;
+        self.inner
+            .to_proto()
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Deserialize from protobuf bytes.
+    #[wasm_bindgen(js_name = fromProto)]
+    pub fn from_proto(bytes: &[u8]) -> Result<Document, JsValue> {
+        use ucm_proto::ProtoCodec;
+        ucm_core::Document::from_proto(bytes)
+            .map(Document::new)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Deserialize bytes whose encoding is given by `contentType`:
+    /// `application/x-protobuf` (or `application/protobuf`) for protobuf,
+    /// `application/json` for portable JSON. Pass the result to
+    /// `renderMarkdown` to render serialized input.
+    pub fn decode(data: &[u8], content_type: &str) -> Result<Document, JsValue> {
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        match mime.as_str() {
+            "application/x-protobuf" | "application/protobuf" => Self::from_proto(data),
+            "application/json" => {
+                let json =
+                    std::str::from_utf8(data).map_err(|e| JsValue::from_str(&e.to_string()))?;
+                ucm_core::Document::from_portable_json(json)
+                    .map(Document::new)
+                    .into_wasm_result()
+            }
+            other => Err(JsValue::from_str(&format!(
+                "Unsupported content type: {}",
+                other
+            ))),
+        }
+    }
+
+    /// Serialize to MessagePack bytes, with the same layout as portable JSON.
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, JsValue> {
+        self.inner
+            .to_portable()
+            .to_msgpack()
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Deserialize from MessagePack bytes written by `toBytes`.
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Document, JsValue> {
+        ucm_core::PortableDocument::from_msgpack(bytes)
+            .and_then(|portable| portable.to_document())
+            .map(Document::new)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Serialize to a portable JSON object (the format `ucp export json` writes).
     #[wasm_bindgen(js_name = toJson)]
     pub fn to_json(&self) -> Result<JsValue, JsValue> {
-        let obj = js_sys::Object::new();
-
-        // Set id
-        js_sys::Reflect::set(
-            &obj,
-            &JsValue::from_str("id"),
-            &JsValue::from_str(&self.inner.id.0),
-        )?;
-
-        // Set root
-        js_sys::Reflect::set(
-            &obj,
-            &JsValue::from_str("root"),
-            &JsValue::from_str(&self.inner.root.to_string()),
-        )?;
-
-        // Set blocks as object
-        let blocks_obj = js_sys::Object::new();
-        for (id, block) in &self.inner.blocks {
-            let block_obj = js_sys::Object::new();
-            js_sys::Reflect::set(
-                &block_obj,
-                &JsValue::from_str("id"),
-                &JsValue::from_str(&id.to_string()),
-            )?;
-            js_sys::Reflect::set(
-                &block_obj,
-                &JsValue::from_str("contentType"),
-                &JsValue::from_str(block.content_type()),
-            )?;
-
-            match &block.content {
-                ucm_core::Content::Text(t) => {
-                    js_sys::Reflect::set(
-                        &block_obj,
-                        &JsValue::from_str("text"),
-                        &JsValue::from_str(&t.text),
-                    )?;
-                }
-                ucm_core::Content::Code(c) => {
-                    js_sys::Reflect::set(
-                        &block_obj,
-                        &JsValue::from_str("language"),
-                        &JsValue::from_str(&c.language),
-                    )?;
-                    js_sys::Reflect::set(
-                        &block_obj,
-                        &JsValue::from_str("source"),
-                        &JsValue::from_str(&c.source),
-                    )?;
-                }
-                _ => {}
-            }
+        let json = self
+            .inner
+            .to_portable_json()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        js_sys::JSON::parse(&json)
+    }
 
-            js_sys::Reflect::set(&blocks_obj, &JsValue::from_str(&id.to_string()), &block_obj)?;
-        }
-        js_sys::Reflect::set(&obj, &JsValue::from_str("blocks"), &blocks_obj)?;
-
-        // Set structure as object
-        let structure_obj = js_sys::Object::new();
-        for (parent_id, children) in &self.inner.structure {
-            let children_arr = js_sys::Array::new();
-            for child_id in children {
-                children_arr.push(&JsValue::from_str(&child_id.to_string()));
+    /// Deserialize from portable JSON, given as a string or a parsed object.
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: JsValue) -> Result<Document, JsValue> {
+        let json = match json.as_string() {
+            Some(json) => json,
+            None => js_sys::JSON::stringify(&json)?.into(),
+        };
+        ucm_core::Document::from_portable_json(&json)
+            .map(Document::new)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Diff this document against `other`, matching blocks by ID.
+    ///
+    /// Returns `{ added, removed, modified: [{ id, oldContent, newContent }],
+    /// structureChanges: [{ id, fromParent, toParent }], reordered }`, where
+    /// `reordered` lists parents whose children changed order.
+    #[wasm_bindgen(js_name = diff)]
+    pub fn diff(&self, other: &Document) -> Result<JsValue, JsValue> {
+        use ucm_diff::{BlockOp, StructureOp};
+
+        let diff = ucm_diff::diff_documents(&self.inner, &other.inner);
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        let mut modified = Vec::new();
+        for op in &diff.block_ops {
+            match op {
+                BlockOp::Add(block) => added.push(block.id.to_string()),
+                BlockOp::Remove(id) => removed.push(id.to_string()),
+                BlockOp::Modify {
+                    id,
+                    old_content,
+                    new_content,
+                } => modified.push(serde_json::json!({
+                    "id": id.to_string(),
+                    "oldContent": old_content,
+                    "newContent": new_content,
+                })),
             }
-            js_sys::Reflect::set(
-                &structure_obj,
-                &JsValue::from_str(&parent_id.to_string()),
-                &children_arr,
-            )?;
-        }
-        js_sys::Reflect::set(&obj, &JsValue::from_str("structure"), &structure_obj)?;
-
-        // Set metadata
-        let metadata_obj = js_sys::Object::new();
-        if let Some(title) = &self.inner.metadata.title {
-            js_sys::Reflect::set(
-                &metadata_obj,
-                &JsValue::from_str("title"),
-                &JsValue::from_str(title),
-            )?;
         }
-        if let Some(desc) = &self.inner.metadata.description {
-            js_sys::Reflect::set(
-                &metadata_obj,
-                &JsValue::from_str("description"),
-                &JsValue::from_str(desc),
-            )?;
+        let mut structure_changes = Vec::new();
+        let mut reordered = Vec::new();
+        for op in &diff.structure_ops {
+            match op {
+                StructureOp::Move {
+                    id,
+                    from_parent,
+                    to_parent,
+                    ..
+                } => structure_changes.push(serde_json::json!({
+                    "id": id.to_string(),
+                    "fromParent": from_parent.to_string(),
+                    "toParent": to_parent.to_string(),
+                })),
+                StructureOp::Reorder { parent, .. } => reordered.push(parent.to_string()),
+            }
         }
-        js_sys::Reflect::set(
-            &metadata_obj,
-            &JsValue::from_str("createdAt"),
-            &JsValue::from_str(&self.inner.metadata.created_at.to_rfc3339()),
-        )?;
-        js_sys::Reflect::set(
-            &metadata_obj,
-            &JsValue::from_str("modifiedAt"),
-            &JsValue::from_str(&self.inner.metadata.modified_at.to_rfc3339()),
-        )?;
-        js_sys::Reflect::set(&obj, &JsValue::from_str("metadata"), &metadata_obj)?;
 
-        Ok(obj.into())
+        let json = serde_json::json!({
+            "added": added,
+            "removed": removed,
+            "modified": modified,
+            "structureChanges": structure_changes,
+            "reordered": reordered,
+        });
+        js_sys::JSON::parse(&json.to_string())
     }
 
     /// Get document version.
@@ -784,7 +818,10 @@ impl Document {
                     "contentType": block.content_type(),
                     "content": match &block.content {
                         ucm_core::Content::Text(t) => serde_json::json!({"text": t.text}),
+                        ucm_core::Content::RichText(r) => serde_json::json!({"text": r.plain_text()}),
                         ucm_core::Content::Code(c) => serde_json::json!({"language": c.language, "source": c.source}),
+                        ucm_core::Content::Diagram(d) => serde_json::json!({"dialect": d.dialect, "source": d.source}),
+                        ucm_core::Content::Diff(d) => serde_json::json!({"format": d.format, "patch": d.patch}),
                         _ => serde_json::json!({"type": block.content_type()}),
                     },
                     "role": block.metadata.semantic_role.as_ref().map(|r| r.to_string()),
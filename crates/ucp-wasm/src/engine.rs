@@ -2,7 +2,12 @@
 //!
 //! Exposes the UCM Engine with transaction support, validation, and traversal.
 
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use ucm_core::metadata::RoleCategory;
 use ucm_engine::engine::{Engine, EngineConfig};
+use ucm_engine::transaction::TransactionId;
 use ucm_engine::traversal::{
     NavigateDirection, TraversalConfig, TraversalEngine, TraversalFilter, TraversalOutput,
     TraversalResult,
@@ -61,7 +66,42 @@ impl WasmEngineConfig {
 /// The main transformation engine with transaction support.
 #[wasm_bindgen]
 pub struct WasmEngine {
-    inner: Engine,
+    inner: Rc<RefCell<Engine>>,
+}
+
+/// Handle to an open engine transaction.
+///
+/// Freeing the handle (or letting it be garbage collected) rolls back the
+/// transaction if it was neither committed nor rolled back.
+#[wasm_bindgen]
+pub struct WasmTransactionId {
+    id: TransactionId,
+    engine: Weak<RefCell<Engine>>,
+}
+
+#[wasm_bindgen]
+impl WasmTransactionId {
+    /// The transaction ID string.
+    #[wasm_bindgen(getter)]
+    pub fn id(&self) -> String {
+        self.id.0.clone()
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_string_js(&self) -> String {
+        self.id.0.clone()
+    }
+}
+
+impl Drop for WasmTransactionId {
+    fn drop(&mut self) {
+        if let Some(engine) = self.engine.upgrade() {
+            if let Ok(mut engine) = engine.try_borrow_mut() {
+                // Fails for finished transactions, which need no rollback
+                let _ = engine.rollback_transaction(&self.id);
+            }
+        }
+    }
 }
 
 #[wasm_bindgen]
@@ -72,35 +112,85 @@ impl WasmEngine {
             Some(c) => Engine::with_config(c.inner),
             None => Engine::new(),
         };
-        WasmEngine { inner: engine }
+        WasmEngine {
+            inner: Rc::new(RefCell::new(engine)),
+        }
     }
 
     /// Validate a document.
     pub fn validate(&self, doc: &Document) -> WasmValidationResult {
-        let result = self.inner.validate(doc.inner());
+        let result = self.inner.borrow().validate(doc.inner());
         WasmValidationResult::from(result)
     }
 
-    /// Begin a new transaction.
+    /// Begin a new transaction, optionally named.
     #[wasm_bindgen(js_name = beginTransaction)]
-    pub fn begin_transaction(&mut self) -> String {
-        let id = self.inner.begin_transaction();
-        id.0
+    pub fn begin_transaction(&mut self, name: Option<String>) -> WasmTransactionId {
+        let id = match name {
+            Some(name) => self.inner.borrow_mut().begin_named_transaction(name),
+            None => self.inner.borrow_mut().begin_transaction(),
+        };
+        WasmTransactionId {
+            id,
+            engine: Rc::downgrade(&self.inner),
+        }
     }
 
     /// Begin a named transaction.
     #[wasm_bindgen(js_name = beginNamedTransaction)]
-    pub fn begin_named_transaction(&mut self, name: &str) -> String {
-        let id = self.inner.begin_named_transaction(name);
-        id.0
+    pub fn begin_named_transaction(&mut self, name: &str) -> WasmTransactionId {
+        self.begin_transaction(Some(name.to_string()))
+    }
+
+    /// Queue UCL commands in a transaction; nothing runs until commit.
+    #[wasm_bindgen(js_name = addToTransaction)]
+    pub fn add_to_transaction(
+        &mut self,
+        txn: &WasmTransactionId,
+        ucl: &str,
+    ) -> Result<(), JsValue> {
+        let ops = ucp_api::UcpClient::new()
+            .ucl_to_operations(ucl)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let mut engine = self.inner.borrow_mut();
+        for op in ops {
+            engine
+                .add_to_transaction(&txn.id, op)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Commit a transaction, applying its operations to `doc`.
+    ///
+    /// If any operation fails, `doc` is left as it was and an error is thrown.
+    #[wasm_bindgen(js_name = commitTransaction)]
+    pub fn commit_transaction(
+        &mut self,
+        txn: &WasmTransactionId,
+        doc: &mut Document,
+    ) -> Result<(), JsValue> {
+        let mut staged = doc.inner().clone();
+        let results = self
+            .inner
+            .borrow_mut()
+            .commit_transaction(&txn.id, &mut staged)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        if let Some(failed) = results.iter().find(|result| !result.success) {
+            return Err(JsValue::from_str(
+                failed.error.as_deref().unwrap_or("operation failed"),
+            ));
+        }
+        *doc.inner_mut() = staged;
+        Ok(())
     }
 
     /// Rollback a transaction.
     #[wasm_bindgen(js_name = rollbackTransaction)]
-    pub fn rollback_transaction(&mut self, txn_id: &str) -> Result<(), JsValue> {
-        let id = ucm_engine::transaction::TransactionId(txn_id.to_string());
+    pub fn rollback_transaction(&mut self, txn: &WasmTransactionId) -> Result<(), JsValue> {
         self.inner
-            .rollback_transaction(&id)
+            .borrow_mut()
+            .rollback_transaction(&txn.id)
             .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
@@ -113,6 +203,7 @@ impl WasmEngine {
         description: Option<String>,
     ) -> Result<(), JsValue> {
         self.inner
+            .borrow_mut()
             .create_snapshot(name, doc.inner(), description)
             .map_err(|e| JsValue::from_str(&e.to_string()))
     }
@@ -122,6 +213,7 @@ impl WasmEngine {
     pub fn restore_snapshot(&self, name: &str) -> Result<Document, JsValue> {
         let doc = self
             .inner
+            .borrow()
             .restore_snapshot(name)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         Ok(Document::new(doc))
@@ -131,7 +223,7 @@ impl WasmEngine {
     #[wasm_bindgen(js_name = listSnapshots)]
     pub fn list_snapshots(&self) -> js_sys::Array {
         let arr = js_sys::Array::new();
-        for name in self.inner.list_snapshots() {
+        for name in self.inner.borrow().list_snapshots() {
             arr.push(&JsValue::from_str(&name));
         }
         arr
@@ -140,7 +232,7 @@ impl WasmEngine {
     /// Delete a snapshot.
     #[wasm_bindgen(js_name = deleteSnapshot)]
     pub fn delete_snapshot(&mut self, name: &str) -> bool {
-        self.inner.delete_snapshot(name)
+        self.inner.borrow_mut().delete_snapshot(name)
     }
 }
 
@@ -265,13 +357,26 @@ impl WasmValidationResult {
         self.valid
     }
 
+    /// Whether the document has no errors.
+    #[wasm_bindgen(js_name = isValid)]
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
     #[wasm_bindgen(getter)]
-    pub fn issues(&self) -> js_sys::Array {
-        let arr = js_sys::Array::new();
-        for issue in &self.issues {
-            arr.push(&issue.to_json());
-        }
-        arr
+    pub fn issues(&self) -> Vec<WasmValidationIssue> {
+        self.issues.clone()
+    }
+
+    /// Issues with the given severity: `error`, `warning` or `info`.
+    #[wasm_bindgen(js_name = issuesBySeverity)]
+    pub fn issues_by_severity(&self, severity: &str) -> Vec<WasmValidationIssue> {
+        let severity = severity.to_lowercase();
+        self.issues
+            .iter()
+            .filter(|i| i.severity == severity)
+            .cloned()
+            .collect()
     }
 
     /// Get error count.
@@ -298,7 +403,11 @@ impl WasmValidationResult {
             &JsValue::from_str("valid"),
             &JsValue::from_bool(self.valid),
         );
-        let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("issues"), &self.issues());
+        let issues = js_sys::Array::new();
+        for issue in &self.issues {
+            issues.push(&issue.to_json());
+        }
+        let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("issues"), &issues);
         obj.into()
     }
 }
@@ -310,6 +419,7 @@ pub struct WasmValidationIssue {
     severity: String,
     code: String,
     message: String,
+    block_id: Option<String>,
 }
 
 impl From<ucm_core::ValidationIssue> for WasmValidationIssue {
@@ -318,6 +428,7 @@ impl From<ucm_core::ValidationIssue> for WasmValidationIssue {
             severity: format!("{:?}", issue.severity).to_lowercase(),
             code: format!("{:?}", issue.code),
             message: issue.message,
+            block_id: issue.block_id.map(|id| id.to_string()),
         }
     }
 }
@@ -339,6 +450,12 @@ impl WasmValidationIssue {
         self.message.clone()
     }
 
+    /// ID of the block the issue is about, if any.
+    #[wasm_bindgen(getter, js_name = blockId)]
+    pub fn block_id(&self) -> Option<String> {
+        self.block_id.clone()
+    }
+
     /// Convert to JSON object.
     #[wasm_bindgen(js_name = toJson)]
     pub fn to_json(&self) -> JsValue {
@@ -358,6 +475,13 @@ impl WasmValidationIssue {
             &JsValue::from_str("message"),
             &JsValue::from_str(&self.message),
         );
+        if let Some(block_id) = &self.block_id {
+            let _ = js_sys::Reflect::set(
+                &obj,
+                &JsValue::from_str("blockId"),
+                &JsValue::from_str(block_id),
+            );
+        }
         obj.into()
     }
 }
@@ -379,6 +503,26 @@ impl WasmValidationPipeline {
         WasmValidationPipeline { inner: pipeline }
     }
 
+    /// Check the content of blocks with semantic role `role` against a JSON
+    /// Schema, given as an object or a string.
+    #[wasm_bindgen(js_name = addSchemaRule)]
+    pub fn add_schema_rule(&mut self, role: &str, json_schema: JsValue) -> Result<(), JsValue> {
+        let role: RoleCategory = role
+            .parse()
+            .map_err(|_| JsValue::from_str(&format!("Unknown role: {}", role)))?;
+        let json = match json_schema.as_string() {
+            Some(json) => json,
+            None => js_sys::JSON::stringify(&json_schema)?.into(),
+        };
+        let schema: serde_json::Value =
+            serde_json::from_str(&json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        if !schema.is_object() {
+            return Err(JsValue::from_str("JSON Schema must be an object"));
+        }
+        self.inner = self.inner.clone().with_schema_rule(role, schema);
+        Ok(())
+    }
+
     /// Validate a document.
     pub fn validate(&self, doc: &Document) -> WasmValidationResult {
         let result = self.inner.validate_document(doc.inner());
@@ -462,6 +606,8 @@ impl WasmTraversalConfig {
                 default_preview_length: 100,
                 include_orphans: include_orphans.unwrap_or(false),
                 cache_enabled: true,
+                use_edge_weights: false,
+                include_hyperedges: false,
             },
         }
     }
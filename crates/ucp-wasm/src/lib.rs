@@ -4,6 +4,7 @@
 
 use wasm_bindgen::prelude::*;
 
+mod crdt;
 mod document;
 mod engine;
 mod errors;
@@ -13,6 +14,7 @@ mod section;
 mod snapshot;
 mod types;
 
+pub use crdt::*;
 pub use document::*;
 pub use engine::*;
 pub use errors::*;
@@ -36,10 +38,82 @@ pub fn parse_markdown(markdown: &str) -> Result<Document, JsValue> {
     Ok(Document::new(doc))
 }
 
+/// Markdown render options as passed from JavaScript.
+#[derive(serde::Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct MarkdownRenderOptions {
+    include_front_matter: bool,
+    heading_offset: i8,
+    emit_block_ids: bool,
+    code_fence_style: Option<String>,
+    hard_line_breaks: bool,
+    math_style: Option<String>,
+}
+
+impl MarkdownRenderOptions {
+    fn from_js(value: JsValue) -> Result<ucp_translator_markdown::RenderOptions, JsValue> {
+        let options: Self = if value.is_undefined() || value.is_null() {
+            Self::default()
+        } else {
+            serde_wasm_bindgen::from_value(value).map_err(|e| JsValue::from_str(&e.to_string()))?
+        };
+        let code_fence_style = match options.code_fence_style.as_deref() {
+            None | Some("backtick") => ucp_translator_markdown::CodeFenceStyle::Backtick,
+            Some("tilde") => ucp_translator_markdown::CodeFenceStyle::Tilde,
+            Some(other) => {
+                return Err(JsValue::from_str(&format!(
+                    "Invalid codeFenceStyle: {} (expected backtick or tilde)",
+                    other
+                )))
+            }
+        };
+        let math_style = match options.math_style.as_deref() {
+            None | Some("dollar") => ucp_translator_markdown::MathStyle::Dollar,
+            Some("fence") => ucp_translator_markdown::MathStyle::Fence,
+            Some(other) => {
+                return Err(JsValue::from_str(&format!(
+                    "Invalid mathStyle: {} (expected dollar or fence)",
+                    other
+                )))
+            }
+        };
+        Ok(ucp_translator_markdown::RenderOptions {
+            include_front_matter: options.include_front_matter,
+            heading_offset: options.heading_offset,
+            emit_block_ids: options.emit_block_ids,
+            code_fence_style,
+            hard_line_breaks: options.hard_line_breaks,
+            math_style,
+        })
+    }
+}
+
 /// Render a Document to markdown.
+///
+/// `options` may set `includeFrontMatter`, `headingOffset`, `emitBlockIds`,
+/// `codeFenceStyle` (`"backtick"` or `"tilde"`) and `hardLineBreaks`.
 #[wasm_bindgen(js_name = renderMarkdown)]
-pub fn render_markdown(doc: &Document) -> Result<String, JsValue> {
-    ucp_translator_markdown::render_markdown(doc.inner())
+pub fn render_markdown(doc: &Document, options: JsValue) -> Result<String, JsValue> {
+    let options = MarkdownRenderOptions::from_js(options)?;
+    ucp_translator_markdown::render_markdown_with_options(doc.inner(), options)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Render one block and its descendants to markdown.
+///
+/// The subtree's top heading becomes H1 (plus any `headingOffset`); `options`
+/// takes the same fields as `renderMarkdown`.
+#[wasm_bindgen(js_name = renderMarkdownBlock)]
+pub fn render_markdown_block(
+    doc: &Document,
+    block_id: &str,
+    options: JsValue,
+) -> Result<String, JsValue> {
+    let block_id: ucm_core::BlockId = block_id
+        .parse()
+        .map_err(|_| JsValue::from_str(&format!("Invalid block ID: {}", block_id)))?;
+    let options = MarkdownRenderOptions::from_js(options)?;
+    ucp_translator_markdown::render_markdown_block(doc.inner(), &block_id, options)
         .map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
@@ -51,6 +125,19 @@ pub fn parse_html(html: &str) -> Result<Document, JsValue> {
     Ok(Document::new(doc))
 }
 
+/// Render a Document to semantic HTML.
+///
+/// With `emitBlockIds`, elements carry `data-ucp-block` attributes for mapping
+/// them back to blocks.
+#[wasm_bindgen(js_name = renderHtml)]
+pub fn render_html(doc: &Document, emit_block_ids: Option<bool>) -> Result<String, JsValue> {
+    let options = ucp_translator_html::HtmlRenderOptions {
+        emit_block_ids: emit_block_ids.unwrap_or(false),
+    };
+    ucp_translator_html::render_html_with_options(doc.inner(), options)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
 /// Execute UCL commands on a document.
 #[wasm_bindgen(js_name = executeUcl)]
 pub fn execute_ucl(doc: &mut Document, ucl: &str) -> Result<js_sys::Array, JsValue> {
@@ -68,6 +155,41 @@ pub fn execute_ucl(doc: &mut Document, ucl: &str) -> Result<js_sys::Array, JsVal
     Ok(arr)
 }
 
+/// Execute UCL that may use short IDs from an IdMapper prompt.
+///
+/// Returns `{ affected: string[], newIds: [shortId, blockId][] }`; new blocks
+/// are registered in the mapper.
+#[wasm_bindgen(js_name = executeUclWithMapper)]
+pub fn execute_ucl_with_mapper(
+    doc: &mut Document,
+    ucl: &str,
+    mapper: &mut WasmIdMapper,
+) -> Result<JsValue, JsValue> {
+    let client = ucp_api::UcpClient::new();
+    let execution = client
+        .execute_ucl_with_mapper(doc.inner_mut(), ucl, mapper.inner_mut())
+        .map_err(convert_error)?;
+
+    let affected = js_sys::Array::new();
+    for result in &execution.results {
+        for block_id in &result.affected_blocks {
+            affected.push(&JsValue::from_str(&block_id.to_string()));
+        }
+    }
+    let new_ids = js_sys::Array::new();
+    for (short, block_id) in execution.new_ids {
+        let pair = js_sys::Array::new();
+        pair.push(&JsValue::from_f64(short as f64));
+        pair.push(&JsValue::from_str(&block_id.to_string()));
+        new_ids.push(&pair);
+    }
+
+    let obj = js_sys::Object::new();
+    let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("affected"), &affected);
+    let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("newIds"), &new_ids);
+    Ok(obj.into())
+}
+
 /// Create a new empty document.
 #[wasm_bindgen(js_name = createDocument)]
 pub fn create_document(title: Option<String>) -> Document {
@@ -55,6 +55,12 @@ pub struct WasmIdMapper {
     inner: IdMapper,
 }
 
+impl WasmIdMapper {
+    pub(crate) fn inner_mut(&mut self) -> &mut IdMapper {
+        &mut self.inner
+    }
+}
+
 #[wasm_bindgen(js_class = IdMapper)]
 impl WasmIdMapper {
     /// Create a new empty IdMapper.
@@ -17,15 +17,29 @@ pub enum ContentType {
     Json = 5,
     Binary = 6,
     Composite = 7,
+    RichText = 8,
+    Diagram = 9,
+    Spreadsheet = 10,
+    GeoJson = 11,
+    Form = 12,
+    Diff = 13,
 }
 
 impl From<&ucm_core::Content> for ContentType {
     fn from(c: &ucm_core::Content) -> Self {
         match c {
             ucm_core::Content::Text(_) => ContentType::Text,
-            ucm_core::Content::Code(_) => ContentType::Code,
+            ucm_core::Content::RichText(_) => ContentType::RichText,
+            ucm_core::Content::Code(_) | ucm_core::Content::CompressedCode { .. } => {
+                ContentType::Code
+            }
             ucm_core::Content::Table(_) => ContentType::Table,
+            ucm_core::Content::Spreadsheet(_) => ContentType::Spreadsheet,
             ucm_core::Content::Math(_) => ContentType::Math,
+            ucm_core::Content::Diagram(_) => ContentType::Diagram,
+            ucm_core::Content::Diff(_) => ContentType::Diff,
+            ucm_core::Content::GeoJson(_) => ContentType::GeoJson,
+            ucm_core::Content::Form(_) => ContentType::Form,
             ucm_core::Content::Media(_) => ContentType::Media,
             ucm_core::Content::Json { .. } => ContentType::Json,
             ucm_core::Content::Binary { .. } => ContentType::Binary,
@@ -275,6 +275,92 @@ describe('Document', () => {
       expect(json).toBeDefined();
       expect(json.blocks).toBeDefined();
       expect(json.structure).toBeDefined();
+      expect(json.schema_version).toBe(1);
+    });
+
+    test('round-trips through portable JSON', () => {
+      const doc = new ucp.Document('Test');
+      const blockId = doc.addBlock(doc.rootId, 'Hello');
+
+      const json = doc.toJson();
+      expect(json.blocks[blockId].content.text).toBe('Hello');
+
+      const fromObject = ucp.Document.fromJson(json);
+      expect(fromObject.id).toBe(doc.id);
+      expect(fromObject.title).toBe('Test');
+      expect(fromObject.children(fromObject.rootId)).toEqual([blockId]);
+
+      const fromString = ucp.Document.fromJson(JSON.stringify(json));
+      expect(fromString.toJson()).toEqual(json);
+    });
+
+    test('fromJson rejects invalid documents', () => {
+      expect(() => ucp.Document.fromJson('{"id": 1}')).toThrow();
+    });
+
+    test('round-trips through MessagePack bytes', () => {
+      const doc = new ucp.Document('Test');
+      const section = doc.addBlock(doc.rootId, 'Section', 'heading1');
+      doc.addCode(section, 'rust', 'fn main() {}');
+      doc.addTag(section, 'important');
+
+      const bytes = doc.toBytes();
+      expect(bytes).toBeInstanceOf(Uint8Array);
+      expect(bytes.length).toBeLessThan(JSON.stringify(doc.toJson()).length);
+
+      const restored = ucp.Document.fromBytes(bytes);
+      expect(restored.toJson()).toEqual(doc.toJson());
+      expect(() => ucp.Document.fromBytes(new Uint8Array([0xc1]))).toThrow();
+    });
+
+    test('round-trips through protobuf', () => {
+      const doc = new ucp.Document('Test');
+      const blockId = doc.addBlock(doc.rootId, 'Hello');
+
+      const bytes = doc.toProto();
+      expect(bytes).toBeInstanceOf(Uint8Array);
+
+      const restored = ucp.Document.fromProto(bytes);
+      expect(restored.id).toBe(doc.id);
+      expect(restored.children(restored.rootId)).toContain(blockId);
+    });
+
+    test('diffs two document states', () => {
+      const before = new ucp.Document('Test');
+      const section = before.addBlock(before.rootId, 'Section');
+      const edited = before.addBlock(section, 'Old text');
+      const moved = before.addBlock(section, 'Moved');
+      const dropped = before.addBlock(before.rootId, 'Dropped');
+
+      const after = ucp.Document.fromJson(before.toJson());
+      after.editBlock(edited, 'New text');
+      after.moveBlock(moved, after.rootId);
+      after.deleteBlock(dropped);
+      const added = after.addBlock(section, 'Added');
+
+      const diff = before.diff(after);
+      expect(diff.added).toEqual([added]);
+      expect(diff.removed).toEqual([dropped]);
+      expect(diff.modified).toEqual([
+        {
+          id: edited,
+          oldContent: { type: 'text', text: 'Old text', format: 'plain' },
+          newContent: { type: 'text', text: 'New text', format: 'plain' },
+        },
+      ]);
+      expect(diff.structureChanges).toEqual([
+        { id: moved, fromParent: section, toParent: after.rootId },
+      ]);
+      expect(diff.reordered).toContain(section);
+
+      const empty = before.diff(before);
+      expect(empty).toEqual({
+        added: [],
+        removed: [],
+        modified: [],
+        structureChanges: [],
+        reordered: [],
+      });
     });
 
     test('gets all block IDs', () => {
@@ -447,6 +533,43 @@ This is a paragraph.
     expect(md).toBeDefined();
     expect(md.length).toBeGreaterThan(0);
   });
+
+  test('renders markdown with options', () => {
+    const doc = ucp.parseMarkdown('# Title\n\n```js\nx()\n```\n');
+
+    const md = ucp.renderMarkdown(doc, { headingOffset: 1, codeFenceStyle: 'tilde' });
+    expect(md).toBe('## Title\n\n~~~js\nx()\n~~~\n');
+    expect(ucp.renderMarkdown(doc, { emitBlockIds: true })).toContain('<!-- ucp:blk_');
+    expect(() => ucp.renderMarkdown(doc, { codeFenceStyle: 'wavy' })).toThrow();
+
+    const math = ucp.parseMarkdown('$$\nx^2\n$$\n');
+    expect(ucp.renderMarkdown(math)).toBe('$$\nx^2\n$$\n');
+    expect(ucp.renderMarkdown(math, { mathStyle: 'fence' })).toBe('```math\nx^2\n```\n');
+  });
+
+  test('renders a single block subtree', () => {
+    const doc = ucp.parseMarkdown('# Guide\n\n## Install\n\nRun it.\n');
+    const guide = doc.children(doc.rootId)[0];
+    const install = doc.children(guide)[0];
+
+    expect(ucp.renderMarkdownBlock(doc, install)).toBe('# Install\n\nRun it.\n');
+    expect(ucp.renderMarkdownBlock(doc, install, { headingOffset: 1 })).toBe(
+      '## Install\n\nRun it.\n'
+    );
+    expect(() => ucp.renderMarkdownBlock(doc, 'blk_000000000000000000000000')).toThrow();
+  });
+
+  test('renders markdown from serialized bytes', () => {
+    const doc = new ucp.Document('Test');
+    doc.addBlock(doc.rootId, 'Hello, World!');
+
+    const proto = ucp.Document.decode(doc.toProto(), 'application/x-protobuf');
+    expect(ucp.renderMarkdown(proto)).toBe(ucp.renderMarkdown(doc));
+    const json = new TextEncoder().encode(JSON.stringify(doc.toJson()));
+    const fromJson = ucp.Document.decode(json, 'application/json; charset=utf-8');
+    expect(ucp.renderMarkdown(fromJson)).toBe(ucp.renderMarkdown(doc));
+    expect(() => ucp.Document.decode(json, 'text/plain')).toThrow();
+  });
 });
 
 describe('UCL Execution', () => {
@@ -471,6 +594,25 @@ describe('UCL Execution', () => {
 
     expect(doc.blockCount()).toBe(initialCount + 1);
   });
+
+  test('executes UCL with short IDs through a mapper', () => {
+    const doc = new ucp.Document();
+    const blockId = doc.addBlock(doc.rootId, 'Original');
+    const mapper = ucp.IdMapper.fromDocument(doc);
+    const shortId = mapper.toShortId(blockId);
+
+    const { affected, newIds } = ucp.executeUclWithMapper(
+      doc,
+      `EDIT ${shortId} SET text = "Updated"\nAPPEND ${shortId} text :: "Child"`,
+      mapper,
+    );
+
+    expect(affected).toContain(blockId);
+    expect(doc.getBlock(blockId).text).toBe('Updated');
+    expect(newIds.length).toBe(1);
+    expect(mapper.toBlockId(newIds[0][0])).toBe(newIds[0][1]);
+    expect(() => ucp.executeUclWithMapper(doc, 'DELETE 99', mapper)).toThrow();
+  });
 });
 
 describe('IdMapper', () => {
@@ -651,6 +793,21 @@ describe('HTML Parsing', () => {
     const doc = ucp.parseHtml(html);
     expect(doc.blockCount()).toBe(1); // Just root
   });
+
+  test('renders HTML that parses back', () => {
+    const html = '<h1>Title</h1><p>Hello <strong>world</strong></p>';
+    const doc = ucp.parseHtml(html);
+    const rendered = ucp.renderHtml(doc);
+    expect(rendered).toContain('<h1>Title</h1>');
+    expect(rendered).toContain('<p>Hello <strong>world</strong></p>');
+    expect(ucp.parseHtml(rendered).blockCount()).toBe(doc.blockCount());
+  });
+
+  test('renders block ids when requested', () => {
+    const doc = ucp.parseHtml('<p>Hello</p>');
+    expect(ucp.renderHtml(doc)).not.toContain('data-ucp-block');
+    expect(ucp.renderHtml(doc, true)).toContain('data-ucp-block="blk_');
+  });
 });
 
 describe('Section Utilities', () => {
@@ -897,6 +1054,60 @@ describe('SnapshotManager', () => {
   });
 });
 
+describe('CrdtDocument', () => {
+  test('merges concurrent edits from two peers', () => {
+    const doc = new ucp.Document('Shared');
+    const intro = doc.addBlock(doc.rootId, 'Intro');
+
+    const alice = new ucp.CrdtDocument(doc, 'alice');
+    const bob = alice.fork('bob');
+
+    alice.setText(intro, 'Alice intro');
+    bob.setText(intro, 'Bob intro');
+    alice.addTag(intro, 'important');
+    bob.addTag(intro, 'reviewed');
+    const added = bob.insertBlock(doc.rootId, 'From Bob');
+
+    expect(alice.merge(bob)).toBe(3);
+    bob.merge(alice);
+
+    const merged = alice.toDocument();
+    const block = merged.getBlock(intro);
+    // Same counter on both peers: the larger actor id wins
+    expect(block.text).toBe('Bob intro');
+    expect(block.tags).toEqual(expect.arrayContaining(['important', 'reviewed']));
+    expect(merged.getBlock(added)).toBeDefined();
+    expect(bob.toDocument().blockCount()).toBe(merged.blockCount());
+  });
+
+  test('merge is idempotent', () => {
+    const doc = new ucp.Document();
+    const alice = new ucp.CrdtDocument(doc, 'alice');
+    const bob = alice.fork('bob');
+    bob.setTitle('Title');
+
+    expect(alice.merge(bob)).toBe(1);
+    expect(alice.merge(bob)).toBe(0);
+    expect(alice.toDocument().title).toBe('Title');
+  });
+
+  test('exchanges ops as plain objects', () => {
+    const doc = new ucp.Document();
+    const alice = new ucp.CrdtDocument(doc, 'alice');
+    const bob = alice.fork('bob');
+    alice.insertBlock(doc.rootId, 'Hello');
+
+    expect(bob.applyOps(alice.ops())).toBe(1);
+    expect(bob.opCount).toBe(1);
+    expect(bob.clock).toBe(1);
+  });
+
+  test('rejects edits to unknown blocks', () => {
+    const alice = new ucp.CrdtDocument(new ucp.Document(), 'alice');
+    expect(() => alice.setText('blk_000000000000000000000001', 'x')).toThrow();
+  });
+});
+
 describe('WasmEngine', () => {
   test('creates engine', () => {
     const engine = new ucp.WasmEngine();
@@ -925,22 +1136,51 @@ describe('WasmEngine', () => {
 
   test('begins transaction', () => {
     const engine = new ucp.WasmEngine();
-    const txnId = engine.beginTransaction();
-    expect(txnId).toBeDefined();
-    expect(txnId.startsWith('txn_')).toBe(true);
+    const txn = engine.beginTransaction();
+    expect(txn).toBeDefined();
+    expect(txn.id.startsWith('txn_')).toBe(true);
   });
 
   test('begins named transaction', () => {
     const engine = new ucp.WasmEngine();
-    const txnId = engine.beginNamedTransaction('my_txn');
-    expect(txnId).toBe('my_txn');
+    expect(engine.beginTransaction('my_txn').id).toBe('my_txn');
+    expect(engine.beginNamedTransaction('other_txn').id).toBe('other_txn');
   });
 
   test('rollbacks transaction', () => {
     const engine = new ucp.WasmEngine();
-    const txnId = engine.beginTransaction();
-    engine.rollbackTransaction(txnId);
-    // Should not throw
+    const txn = engine.beginTransaction();
+    engine.rollbackTransaction(txn);
+    expect(() => engine.rollbackTransaction(txn)).toThrow();
+  });
+
+  test('commits transaction', () => {
+    const engine = new ucp.WasmEngine();
+    const doc = new ucp.Document();
+    const txn = engine.beginTransaction();
+    engine.addToTransaction(txn, `APPEND ${doc.rootId} text :: "First"`);
+    engine.addToTransaction(txn, `APPEND ${doc.rootId} text :: "Second"`);
+    expect(doc.blockCount()).toBe(1);
+
+    engine.commitTransaction(txn, doc);
+    expect(doc.blockCount()).toBe(3);
+    expect(() => engine.commitTransaction(txn, doc)).toThrow();
+  });
+
+  test('failed commit leaves document unchanged', () => {
+    const engine = new ucp.WasmEngine();
+    const doc = new ucp.Document();
+    const txn = engine.beginTransaction();
+    try {
+      engine.addToTransaction(txn, `APPEND ${doc.rootId} text :: "First"`);
+      engine.addToTransaction(txn, 'APPEND blk_000000000000000000000001 text :: "Second"');
+      engine.commitTransaction(txn, doc);
+    } catch (e) {
+      // The second append has no parent
+    } finally {
+      txn.free();
+    }
+    expect(doc.blockCount()).toBe(1);
   });
 
   test('creates and restores snapshot', () => {
@@ -1033,6 +1273,32 @@ describe('WasmValidationPipeline', () => {
     expect(json.valid).toBe(true);
     expect(Array.isArray(json.issues)).toBe(true);
   });
+
+  test('reports schema violations per block', () => {
+    const pipeline = new ucp.WasmValidationPipeline();
+    pipeline.addSchemaRule('note', {
+      properties: { text: { type: 'string', minLength: 5 } },
+    });
+    const doc = new ucp.Document();
+    const ok = doc.addBlock(doc.rootId, 'Long enough', 'note');
+    const bad = doc.addBlock(doc.rootId, 'Hi', 'note');
+    doc.addBlock(doc.rootId, 'No');
+
+    const result = pipeline.validate(doc);
+    expect(result.isValid()).toBe(false);
+    const errors = result.issuesBySeverity('error');
+    expect(errors.length).toBe(1);
+    expect(errors[0].code).toBe('E200SchemaViolation');
+    expect(errors[0].blockId).toBe(bad);
+    expect(errors[0].message).toContain('$.text');
+    expect(result.issues.some((issue) => issue.blockId === ok)).toBe(false);
+    expect(result.issuesBySeverity('warning')).toEqual([]);
+  });
+
+  test('rejects unknown schema rule roles', () => {
+    const pipeline = new ucp.WasmValidationPipeline();
+    expect(() => pipeline.addSchemaRule('not-a-role', {})).toThrow();
+  });
 });
 
 describe('WasmTraversalEngine', () => {